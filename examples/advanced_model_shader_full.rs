@@ -42,9 +42,9 @@ impl Spot for AdvancedModelShaderFull {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<AdvancedModelShaderFull>(WindowConfig {
         title: "Advanced Model Shader Full".to_string(),
         ..Default::default()
-    });
+    })
 }
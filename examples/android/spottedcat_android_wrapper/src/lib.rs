@@ -725,5 +725,7 @@ pub fn android_main(app: AndroidApp) {
         spottedcat::android::set_floating_window_service("com/example/gameactivityexample/FloatingWindowService");
     }
 
-    spottedcat::run::<AndroidFfiSpot>(WindowConfig::default(), app);
+    if let Err(error) = spottedcat::run::<AndroidFfiSpot>(WindowConfig::default(), app) {
+        eprintln!("{error}");
+    }
 }
@@ -120,7 +120,7 @@ impl Spot for AnimatedGltfApp {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     let path = std::env::args().nth(1).unwrap_or_else(|| {
         eprintln!(
             "usage: cargo run --example animated_gltf --features gltf -- <path-to-model.glb>"
@@ -132,5 +132,5 @@ fn main() {
     spottedcat::run::<AnimatedGltfApp>(WindowConfig {
         title: "SpottedCat Animated glTF".to_string(),
         ..Default::default()
-    });
+    })
 }
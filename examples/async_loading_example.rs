@@ -60,9 +60,9 @@ impl Spot for AsyncLoadingExample {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     run::<AsyncLoadingExample>(WindowConfig {
         title: "Async Asset Loading Example".to_string(),
         ..Default::default()
-    });
+    })
 }
@@ -158,9 +158,9 @@ impl Spot for SubImageNestTest {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     run::<SubImageNestTest>(WindowConfig {
         title: "SubImage Nested Render Target Test".to_string(),
         ..Default::default()
-    });
+    })
 }
@@ -35,9 +35,9 @@ impl Spot for AudioTest {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<AudioTest>(WindowConfig {
         title: "Audio Test".to_string(),
         ..Default::default()
-    });
+    })
 }
@@ -111,9 +111,9 @@ impl Spot for BillboardExample {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<BillboardExample>(WindowConfig {
         title: "Billboard (Option 1) Example".to_string(),
         ..Default::default()
-    });
+    })
 }
@@ -102,6 +102,6 @@ impl Spot for CenteredTextTestSpot {
     }
 }
 
-fn main() {
-    run::<CenteredTextTestSpot>(WindowConfig::default());
+fn main() -> Result<(), spottedcat::SpotError> {
+    run::<CenteredTextTestSpot>(WindowConfig::default())
 }
@@ -239,12 +239,12 @@ impl Spot for FlipTest {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     let config = WindowConfig {
         title: "Flip Test (Final Diagnose)".to_string(),
         width: Pt::from(1000.0),
         height: Pt::from(800.0),
         ..Default::default()
     };
-    spottedcat::run::<FlipTest>(config);
+    spottedcat::run::<FlipTest>(config)
 }
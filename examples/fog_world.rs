@@ -173,9 +173,9 @@ impl Spot for FogWorld {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<FogWorld>(WindowConfig {
         title: "Fog World Example".to_string(),
         ..Default::default()
-    });
+    })
 }
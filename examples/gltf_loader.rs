@@ -35,8 +35,8 @@ impl Spot for GltfApp {
     }
 }
 
-fn main() {
-    spottedcat::run::<GltfApp>(WindowConfig::default());
+fn main() -> Result<(), spottedcat::SpotError> {
+    spottedcat::run::<GltfApp>(WindowConfig::default())
 }
 
 /// Loads mesh and skin data using the `gltf` crate.
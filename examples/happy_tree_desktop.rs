@@ -47,11 +47,11 @@ impl Spot for HappyTreeDesktop {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<HappyTreeDesktop>(WindowConfig {
         title: "Happy Tree Desktop".to_string(),
         width: Pt::from(960.0),
         height: Pt::from(720.0),
         ..Default::default()
-    });
+    })
 }
@@ -126,11 +126,11 @@ fn build_noise_rgba() -> Vec<u8> {
     out
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<ImageShaderTemplateExample>(WindowConfig {
         title: "Image Shader Template".to_string(),
         width: Pt::from(960.0),
         height: Pt::from(640.0),
         ..Default::default()
-    });
+    })
 }
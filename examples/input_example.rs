@@ -82,6 +82,6 @@ impl Spot for InputExample {
     }
 }
 
-fn main() {
-    spottedcat::run::<InputExample>(spottedcat::WindowConfig::default());
+fn main() -> Result<(), spottedcat::SpotError> {
+    spottedcat::run::<InputExample>(spottedcat::WindowConfig::default())
 }
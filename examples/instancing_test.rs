@@ -106,7 +106,7 @@ impl Spot for InstancingTest {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     unsafe {
         std::env::set_var("SPOT_PROFILE_RENDER", "1");
     }
@@ -115,5 +115,5 @@ fn main() {
         width: spottedcat::Pt::from(1280.0),
         height: spottedcat::Pt::from(720.0),
         ..Default::default()
-    });
+    })
 }
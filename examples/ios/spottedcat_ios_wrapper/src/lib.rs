@@ -360,5 +360,7 @@ pub extern "C" fn spottedcat_ios_start() {
         }
     }
 
-    spottedcat::run::<IosFfiSpot>(WindowConfig::default());
+    if let Err(error) = spottedcat::run::<IosFfiSpot>(WindowConfig::default()) {
+        eprintln!("{error}");
+    }
 }
@@ -67,9 +67,9 @@ return vec4<f32>(final_rgb, src.a * model_globals.extra.x);
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<MetalSphere>(WindowConfig {
         title: "Metal Sphere Example".to_string(),
         ..Default::default()
-    });
+    })
 }
@@ -120,9 +120,9 @@ impl Spot for ModelTest {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<ModelTest>(WindowConfig {
         title: "3D Model Test".to_string(),
         ..Default::default()
-    });
+    })
 }
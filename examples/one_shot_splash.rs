@@ -47,11 +47,11 @@ impl Spot for GameplayScene {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     run::<OneShotSplash<GameplayScene>>(WindowConfig {
         title: "Rusty-spotted cat".to_string(),
         width: Pt::from(540.0),
         height: Pt::from(960.0),
         ..Default::default()
-    });
+    })
 }
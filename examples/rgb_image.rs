@@ -67,11 +67,11 @@ impl Spot for RgbImageExample {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<RgbImageExample>(WindowConfig {
         title: "RGB Image Example".to_string(),
         width: Pt::from(960.0),
         height: Pt::from(720.0),
         ..Default::default()
-    });
+    })
 }
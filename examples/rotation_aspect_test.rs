@@ -153,11 +153,11 @@ impl Spot for RotationAspectTest {
     }
 }
 
-fn main() {
+fn main() -> Result<(), spottedcat::SpotError> {
     spottedcat::run::<RotationAspectTest>(WindowConfig {
         title: "Rotation Aspect Test".to_string(),
         width: Pt::from(390.0),
         height: Pt::from(844.0),
         ..Default::default()
-    });
+    })
 }
@@ -114,6 +114,6 @@ impl Spot for SevenLevelNestTestSpot {
     }
 }
 
-fn main() {
-    run::<SevenLevelNestTestSpot>(WindowConfig::default());
+fn main() -> Result<(), spottedcat::SpotError> {
+    run::<SevenLevelNestTestSpot>(WindowConfig::default())
 }
@@ -25,6 +25,6 @@ impl Spot for TouchTest {
     }
 }
 
-fn main() {
-    spottedcat::run::<TouchTest>(spottedcat::WindowConfig::default());
+fn main() -> Result<(), spottedcat::SpotError> {
+    spottedcat::run::<TouchTest>(spottedcat::WindowConfig::default())
 }
@@ -395,5 +395,7 @@ pub fn run_flappy_cat() {
     #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     let config = WindowConfig::default();
 
-    spottedcat::run::<OneShotSplash<FlappyCat>>(config);
+    if let Err(error) = spottedcat::run::<OneShotSplash<FlappyCat>>(config) {
+        eprintln!("{error}");
+    }
 }
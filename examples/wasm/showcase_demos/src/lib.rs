@@ -351,18 +351,24 @@ fn build_noise_rgba() -> Vec<u8> {
 #[wasm_bindgen]
 pub fn run_input_demo() {
     console_error_panic_hook::set_once();
-    spottedcat::run::<InputDemo>(web_config("Spottedcat Input Demo"));
+    if let Err(error) = spottedcat::run::<InputDemo>(web_config("Spottedcat Input Demo")) {
+        eprintln!("{error}");
+    }
 }
 
 #[wasm_bindgen]
 pub fn run_image_demo() {
     console_error_panic_hook::set_once();
-    spottedcat::run::<ImageDemo>(web_config("Spottedcat Image Demo"));
+    if let Err(error) = spottedcat::run::<ImageDemo>(web_config("Spottedcat Image Demo")) {
+        eprintln!("{error}");
+    }
 }
 
 #[wasm_bindgen]
 pub fn run_shader_demo() {
     console_error_panic_hook::set_once();
-    spottedcat::run::<ShaderDemo>(web_config("Spottedcat Shader Demo"));
+    if let Err(error) = spottedcat::run::<ShaderDemo>(web_config("Spottedcat Shader Demo")) {
+        eprintln!("{error}");
+    }
 }
 
@@ -95,5 +95,7 @@ pub fn run_demo() {
     #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     let config = WindowConfig::default();
 
-    spottedcat::run::<WasmDemo>(config);
+    if let Err(error) = spottedcat::run::<WasmDemo>(config) {
+        eprintln!("{error}");
+    }
 }
@@ -0,0 +1,51 @@
+#pragma once
+
+/* C API of spottedcat, built with the `ffi` feature. See src/ffi.rs. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct SpotContext SpotContext;
+
+typedef struct SpotCallbacks {
+    void *user_data;
+    void (*init)(SpotContext *ctx, void *user_data);
+    void (*update)(SpotContext *ctx, double dt, void *user_data);
+    void (*draw)(SpotContext *ctx, uint32_t screen, void *user_data);
+    void (*exit)(SpotContext *ctx, void *user_data);
+} SpotCallbacks;
+
+typedef struct SpotWindowConfig {
+    const char *title;
+    float width;
+    float height;
+    bool resizable;
+    bool fullscreen;
+    uint32_t update_hz;
+} SpotWindowConfig;
+
+#define SPOT_SCREEN 0u
+
+void spot_set_callbacks(const SpotCallbacks *callbacks);
+int32_t spot_run(const SpotWindowConfig *config);
+const char *spot_last_error(void);
+void spot_quit(void);
+
+uint32_t spot_image_create(SpotContext *ctx, uint32_t width, uint32_t height,
+                           const uint8_t *rgba, size_t len);
+void spot_image_destroy(SpotContext *ctx, uint32_t image);
+bool spot_draw_image(SpotContext *ctx, uint32_t target, uint32_t image, float x, float y,
+                     float rotation, float scale_x, float scale_y);
+
+bool spot_mouse_down(const SpotContext *ctx, uint32_t button);
+bool spot_cursor_position(const SpotContext *ctx, float *x, float *y);
+void spot_window_size(const SpotContext *ctx, float *width, float *height);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
@@ -107,18 +107,275 @@ pub fn request_runtime_permission(permission: &str, request_code: i32) -> Result
     Ok(())
 }
 
+/// Returns how many pixels the on-screen keyboard covers at the bottom of the window.
+///
+/// Reads the IME insets of the decor view, which needs Android 11 (API 30).
 #[cfg(target_os = "android")]
-pub(crate) fn logcat_info(message: &str) {
-    logcat(ndk_sys::android_LogPriority::ANDROID_LOG_INFO, message);
+pub(crate) fn ime_inset_bottom() -> Result<i32, String> {
+    Ok(root_window_insets(&["ime"])?.map_or(0, |insets| insets.bottom))
 }
 
+/// Returns the pixels covered by the system bars and display cutouts, or `None` before
+/// Android 11 (API 30) or while the window is detached.
+///
+/// Unlike the content rect this stays correct when the app draws edge to edge, where
+/// bars overlap the window instead of shrinking it.
 #[cfg(target_os = "android")]
-pub(crate) fn logcat_warn(message: &str) {
-    logcat(ndk_sys::android_LogPriority::ANDROID_LOG_WARN, message);
+pub(crate) fn system_insets() -> Result<Option<WindowInsets>, String> {
+    if sdk_int()? < 30 {
+        return Ok(None);
+    }
+    root_window_insets(&["systemBars", "displayCutout"])
+}
+
+/// Insets in physical pixels from each edge of the window.
+#[cfg(target_os = "android")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct WindowInsets {
+    pub(crate) top: i32,
+    pub(crate) bottom: i32,
+    pub(crate) left: i32,
+    pub(crate) right: i32,
+}
+
+/// Reads the decor view's insets for the union of the named `WindowInsets.Type`s.
+#[cfg(target_os = "android")]
+fn root_window_insets(types: &[&str]) -> Result<Option<WindowInsets>, String> {
+    let jvm = JVM.get().ok_or_else(|| "JVM unavailable".to_string())?;
+    let activity_ref = ACTIVITY
+        .get()
+        .ok_or_else(|| "Activity unavailable".to_string())?;
+    let mut env = jvm.attach_current_thread().map_err(|err| err.to_string())?;
+    let window = env
+        .call_method(
+            activity_ref.as_obj(),
+            "getWindow",
+            "()Landroid/view/Window;",
+            &[],
+        )
+        .and_then(|value| value.l())
+        .map_err(|err| err.to_string())?;
+    let decor_view = env
+        .call_method(&window, "getDecorView", "()Landroid/view/View;", &[])
+        .and_then(|value| value.l())
+        .map_err(|err| err.to_string())?;
+    let insets = env
+        .call_method(
+            &decor_view,
+            "getRootWindowInsets",
+            "()Landroid/view/WindowInsets;",
+            &[],
+        )
+        .and_then(|value| value.l())
+        .map_err(|err| err.to_string())?;
+    if insets.is_null() {
+        return Ok(None);
+    }
+    let inset_type =
+        find_class(&mut env, "android/view/WindowInsets$Type").map_err(|err| err.to_string())?;
+    let mut mask = 0;
+    for name in types {
+        mask |= env
+            .call_static_method(&inset_type, name, "()I", &[])
+            .and_then(|value| value.i())
+            .map_err(|err| err.to_string())?;
+    }
+    let type_insets = env
+        .call_method(
+            &insets,
+            "getInsets",
+            "(I)Landroid/graphics/Insets;",
+            &[jni::objects::JValue::Int(mask)],
+        )
+        .and_then(|value| value.l())
+        .map_err(|err| err.to_string())?;
+    let mut field = |name: &str| {
+        env.get_field(&type_insets, name, "I")
+            .and_then(|value| value.i())
+            .map_err(|err| err.to_string())
+    };
+    Ok(Some(WindowInsets {
+        top: field("top")?,
+        bottom: field("bottom")?,
+        left: field("left")?,
+        right: field("right")?,
+    }))
+}
+
+/// Asks the activity to draw edge to edge with the system bars hidden, or to restore
+/// them, through its `setEdgeToEdge(boolean)` method.
+///
+/// Hiding the bars must happen on the UI thread, which native code cannot post to, so
+/// the activity provides the method; `Ok(false)` means it does not.
+#[cfg(target_os = "android")]
+pub(crate) fn set_edge_to_edge(enabled: bool) -> Result<bool, String> {
+    let jvm = JVM.get().ok_or_else(|| "JVM unavailable".to_string())?;
+    let activity_ref = ACTIVITY
+        .get()
+        .ok_or_else(|| "Activity unavailable".to_string())?;
+    let mut env = jvm.attach_current_thread().map_err(|err| err.to_string())?;
+    let class = env
+        .get_object_class(activity_ref.as_obj())
+        .map_err(|err| err.to_string())?;
+    if env.get_method_id(&class, "setEdgeToEdge", "(Z)V").is_err() {
+        let _ = env.exception_clear();
+        return Ok(false);
+    }
+    env.call_method(
+        activity_ref.as_obj(),
+        "setEdgeToEdge",
+        "(Z)V",
+        &[jni::objects::JValue::Bool(enabled.into())],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(true)
+}
+
+/// Finishes the activity, which is what the system does for an unhandled back press.
+#[cfg(target_os = "android")]
+pub(crate) fn finish_activity() -> Result<(), String> {
+    let jvm = JVM.get().ok_or_else(|| "JVM unavailable".to_string())?;
+    let activity_ref = ACTIVITY
+        .get()
+        .ok_or_else(|| "Activity unavailable".to_string())?;
+    let mut env = jvm.attach_current_thread().map_err(|err| err.to_string())?;
+    env.call_method(activity_ref.as_obj(), "finish", "()V", &[])
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Vibrates through the `Vibrator` service, playing the predefined click effect for
+/// `style` on Android 10+ and a one-shot vibration of `duration` otherwise.
+#[cfg(target_os = "android")]
+pub(crate) fn vibrate(
+    duration: std::time::Duration,
+    style: Option<crate::HapticStyle>,
+) -> Result<(), String> {
+    use jni::objects::JValue;
+
+    let sdk = sdk_int()?;
+    let jvm = JVM.get().ok_or_else(|| "JVM unavailable".to_string())?;
+    let activity_ref = ACTIVITY
+        .get()
+        .ok_or_else(|| "Activity unavailable".to_string())?;
+    let mut env = jvm.attach_current_thread().map_err(|err| err.to_string())?;
+    let vibrator = system_service(&mut env, activity_ref, "vibrator")?;
+    if vibrator.is_null() {
+        return Err("Vibrator unavailable".to_string());
+    }
+    let millis = duration.as_millis().clamp(1, i64::MAX as u128) as i64;
+    if sdk < 26 {
+        env.call_method(&vibrator, "vibrate", "(J)V", &[JValue::Long(millis)])
+            .map_err(|err| err.to_string())?;
+        return Ok(());
+    }
+
+    let effect_class =
+        find_class(&mut env, "android/os/VibrationEffect").map_err(|err| err.to_string())?;
+    let effect = match style {
+        Some(style) if sdk >= 29 => {
+            // VibrationEffect.EFFECT_TICK, EFFECT_CLICK and EFFECT_HEAVY_CLICK.
+            let effect_id = match style {
+                crate::HapticStyle::Light => 2,
+                crate::HapticStyle::Medium => 0,
+                crate::HapticStyle::Heavy => 5,
+            };
+            env.call_static_method(
+                &effect_class,
+                "createPredefined",
+                "(I)Landroid/os/VibrationEffect;",
+                &[JValue::Int(effect_id)],
+            )
+        }
+        // VibrationEffect.DEFAULT_AMPLITUDE
+        _ => env.call_static_method(
+            &effect_class,
+            "createOneShot",
+            "(JI)Landroid/os/VibrationEffect;",
+            &[JValue::Long(millis), JValue::Int(-1)],
+        ),
+    }
+    .and_then(|value| value.l())
+    .map_err(|err| err.to_string())?;
+    env.call_method(
+        &vibrator,
+        "vibrate",
+        "(Landroid/os/VibrationEffect;)V",
+        &[JValue::Object(&effect)],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
 }
 
+/// Reads the battery from `BatteryManager` and, on Android 10+, the thermal status from
+/// `PowerManager`.
 #[cfg(target_os = "android")]
-fn logcat(priority: ndk_sys::android_LogPriority, message: &str) {
+pub(crate) fn power_status() -> Result<crate::power::PowerStatus, String> {
+    let sdk = sdk_int()?;
+    let jvm = JVM.get().ok_or_else(|| "JVM unavailable".to_string())?;
+    let activity_ref = ACTIVITY
+        .get()
+        .ok_or_else(|| "Activity unavailable".to_string())?;
+    let mut env = jvm.attach_current_thread().map_err(|err| err.to_string())?;
+    let mut status = crate::power::PowerStatus::default();
+
+    let battery = system_service(&mut env, activity_ref, "batterymanager")?;
+    if !battery.is_null() {
+        // BatteryManager.BATTERY_PROPERTY_CAPACITY, in percent.
+        let capacity = env
+            .call_method(
+                &battery,
+                "getIntProperty",
+                "(I)I",
+                &[jni::objects::JValue::Int(4)],
+            )
+            .and_then(|value| value.i())
+            .map_err(|err| err.to_string())?;
+        status.battery_level = (0..=100)
+            .contains(&capacity)
+            .then(|| capacity as f32 / 100.0);
+        if sdk >= 23 {
+            let charging = env
+                .call_method(&battery, "isCharging", "()Z", &[])
+                .and_then(|value| value.z())
+                .map_err(|err| err.to_string())?;
+            status.charging = Some(charging);
+        }
+    }
+
+    if sdk >= 29 {
+        let power = system_service(&mut env, activity_ref, "power")?;
+        if !power.is_null() {
+            let thermal = env
+                .call_method(&power, "getCurrentThermalStatus", "()I", &[])
+                .and_then(|value| value.i())
+                .map_err(|err| err.to_string())?;
+            status.thermal = crate::power::ThermalState::from_android_status(thermal);
+        }
+    }
+    Ok(status)
+}
+
+/// Looks up a system service of the activity by its `Context.*_SERVICE` name.
+#[cfg(target_os = "android")]
+fn system_service<'a>(
+    env: &mut jni::JNIEnv<'a>,
+    activity: &jni::objects::GlobalRef,
+    name: &str,
+) -> Result<jni::objects::JObject<'a>, String> {
+    let name = env.new_string(name).map_err(|err| err.to_string())?;
+    env.call_method(
+        activity.as_obj(),
+        "getSystemService",
+        "(Ljava/lang/String;)Ljava/lang/Object;",
+        &[jni::objects::JValue::Object(&name)],
+    )
+    .and_then(|value| value.l())
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(target_os = "android")]
+pub(crate) fn logcat(priority: ndk_sys::android_LogPriority, message: &str) {
     let Ok(message) = CString::new(message.replace('\0', " ")) else {
         return;
     };
@@ -170,21 +427,21 @@ pub fn init(app: AndroidApp) {
 
     unsafe {
         let Ok(vm) = jni::JavaVM::from_raw(app.vm_as_ptr() as *mut _) else {
-            eprintln!("[spot][android] failed to create JavaVM from raw pointer");
+            crate::diagnostics::error!("android", "failed to create JavaVM from raw pointer");
             return;
         };
         let activity = jni::objects::JObject::from_raw(app.activity_as_ptr() as *mut _);
         let _ = JVM.set(vm);
         let Some(jvm) = JVM.get() else {
-            eprintln!("[spot][android] JVM was not stored during init");
+            crate::diagnostics::error!("android", "JVM was not stored during init");
             return;
         };
         let Ok(mut env) = jvm.attach_current_thread() else {
-            eprintln!("[spot][android] failed to attach current thread during init");
+            crate::diagnostics::error!("android", "failed to attach current thread during init");
             return;
         };
         let Ok(activity_ref) = env.new_global_ref(activity) else {
-            eprintln!("[spot][android] failed to create global activity ref");
+            crate::diagnostics::error!("android", "failed to create global activity ref");
             return;
         };
         let _ = ACTIVITY.set(activity_ref);
@@ -192,9 +449,11 @@ pub fn init(app: AndroidApp) {
         // If service class was already set, register it now
         if let Some(class_name) = floating_window_service_class() {
             if let Err(err) = register_floating_window_methods(&mut env, class_name) {
-                eprintln!(
-                    "[spot][android] failed to register floating window methods for {}: {:?}",
-                    class_name, err
+                crate::diagnostics::error!(
+                    "android",
+                    "failed to register floating window methods for {}: {:?}",
+                    class_name,
+                    err
                 );
             }
         }
@@ -203,7 +462,7 @@ pub fn init(app: AndroidApp) {
 
 #[cfg(target_os = "android")]
 pub fn set_floating_window_scene<T: crate::Spot + 'static>() {
-    let _ = FLOATING_SCENE_FACTORY.set(Box::new(|ctx| Box::new(T::initialize(ctx))));
+    let _ = FLOATING_SCENE_FACTORY.set(crate::scenes::scene_factory::<T>());
 }
 
 #[cfg(target_os = "android")]
@@ -216,7 +475,7 @@ pub(crate) fn take_floating_surface() -> Option<jni::objects::GlobalRef> {
     match FLOATING_SURFACE.lock() {
         Ok(mut guard) => guard.take(),
         Err(err) => {
-            eprintln!("[spot][android] failed to lock floating surface: {}", err);
+            crate::diagnostics::error!("android", "failed to lock floating surface: {}", err);
             None
         }
     }
@@ -224,14 +483,19 @@ pub(crate) fn take_floating_surface() -> Option<jni::objects::GlobalRef> {
 
 #[cfg(target_os = "android")]
 pub fn on_surface_created(env: &jni::JNIEnv, surface: jni::objects::JObject) {
-    eprintln!("[spot][android] on_surface_created called");
+    crate::diagnostics::info!("android", "on_surface_created called");
     let Ok(global_ref) = env.new_global_ref(surface) else {
-        eprintln!("[spot][android] failed to create global ref for floating surface");
+        crate::diagnostics::error!(
+            "android",
+            "failed to create global ref for floating surface"
+        );
         return;
     };
     match FLOATING_SURFACE.lock() {
         Ok(mut guard) => *guard = Some(global_ref),
-        Err(err) => eprintln!("[spot][android] failed to lock floating surface: {}", err),
+        Err(err) => {
+            crate::diagnostics::error!("android", "failed to lock floating surface: {}", err)
+        }
     }
 }
 
@@ -239,7 +503,9 @@ pub fn on_surface_created(env: &jni::JNIEnv, surface: jni::objects::JObject) {
 pub fn on_surface_destroyed() {
     match FLOATING_SURFACE.lock() {
         Ok(mut guard) => *guard = None,
-        Err(err) => eprintln!("[spot][android] failed to lock floating surface: {}", err),
+        Err(err) => {
+            crate::diagnostics::error!("android", "failed to lock floating surface: {}", err)
+        }
     }
 }
 
@@ -268,15 +534,18 @@ pub fn set_floating_window_service(class_name: &str) {
     if let Some(jvm) = JVM.get() {
         if ACTIVITY.get().is_some() {
             let Ok(mut env) = jvm.attach_current_thread() else {
-                eprintln!(
-                    "[spot][android] failed to attach thread for floating service registration"
+                crate::diagnostics::error!(
+                    "android",
+                    "failed to attach thread for floating service registration"
                 );
                 return;
             };
             if let Err(err) = register_floating_window_methods(&mut env, class_name) {
-                eprintln!(
-                    "[spot][android] failed to register floating window methods for {}: {:?}",
-                    class_name, err
+                crate::diagnostics::error!(
+                    "android",
+                    "failed to register floating window methods for {}: {:?}",
+                    class_name,
+                    err
                 );
             }
         }
@@ -303,20 +572,17 @@ pub fn start_service(class_name: &str) {
     };
 
     let Ok(mut env) = jvm.attach_current_thread() else {
-        eprintln!("[spot][android] failed to attach thread for start_service");
+        crate::diagnostics::error!("android", "failed to attach thread for start_service");
         return;
     };
     let activity = activity_ref.as_obj();
 
     let Ok(intent_class) = find_class(&mut env, "android/content/Intent") else {
-        eprintln!("[spot][android] failed to resolve Intent class");
+        crate::diagnostics::error!("android", "failed to resolve Intent class");
         return;
     };
     let Ok(service_class) = find_class(&mut env, class_name) else {
-        eprintln!(
-            "[spot][android] failed to resolve service class {}",
-            class_name
-        );
+        crate::diagnostics::error!("android", "failed to resolve service class {}", class_name);
         return;
     };
 
@@ -326,8 +592,9 @@ pub fn start_service(class_name: &str) {
         "(Landroid/content/Context;Ljava/lang/Class;)V",
         &[(&activity).into(), (&service_class).into()],
     ) else {
-        eprintln!(
-            "[spot][android] failed to create intent for service {}",
+        crate::diagnostics::error!(
+            "android",
+            "failed to create intent for service {}",
             class_name
         );
         return;
@@ -335,15 +602,15 @@ pub fn start_service(class_name: &str) {
 
     // Context.startService(intent) or Context.startForegroundService(intent)
     let Ok(version_class) = find_class(&mut env, "android/os/Build$VERSION") else {
-        eprintln!("[spot][android] failed to resolve Build.VERSION");
+        crate::diagnostics::error!("android", "failed to resolve Build.VERSION");
         return;
     };
     let Ok(sdk_int) = env.get_static_field(version_class, "SDK_INT", "I") else {
-        eprintln!("[spot][android] failed to read SDK_INT");
+        crate::diagnostics::error!("android", "failed to read SDK_INT");
         return;
     };
     let Ok(sdk_int) = sdk_int.i() else {
-        eprintln!("[spot][android] SDK_INT field had unexpected type");
+        crate::diagnostics::error!("android", "SDK_INT field had unexpected type");
         return;
     };
 
@@ -354,9 +621,11 @@ pub fn start_service(class_name: &str) {
             "(Landroid/content/Intent;)Landroid/content/ComponentName;",
             &[(&intent).into()],
         ) {
-            eprintln!(
-                "[spot][android] startForegroundService failed for {}: {:?}",
-                class_name, err
+            crate::diagnostics::error!(
+                "android",
+                "startForegroundService failed for {}: {:?}",
+                class_name,
+                err
             );
         }
     } else {
@@ -366,9 +635,11 @@ pub fn start_service(class_name: &str) {
             "(Landroid/content/Intent;)Landroid/content/ComponentName;",
             &[(&intent).into()],
         ) {
-            eprintln!(
-                "[spot][android] startService failed for {}: {:?}",
-                class_name, err
+            crate::diagnostics::error!(
+                "android",
+                "startService failed for {}: {:?}",
+                class_name,
+                err
             );
         }
     }
@@ -384,20 +655,17 @@ pub fn stop_service(class_name: &str) {
     };
 
     let Ok(mut env) = jvm.attach_current_thread() else {
-        eprintln!("[spot][android] failed to attach thread for stop_service");
+        crate::diagnostics::error!("android", "failed to attach thread for stop_service");
         return;
     };
     let activity = activity_ref.as_obj();
 
     let Ok(intent_class) = find_class(&mut env, "android/content/Intent") else {
-        eprintln!("[spot][android] failed to resolve Intent class");
+        crate::diagnostics::error!("android", "failed to resolve Intent class");
         return;
     };
     let Ok(service_class) = find_class(&mut env, class_name) else {
-        eprintln!(
-            "[spot][android] failed to resolve service class {}",
-            class_name
-        );
+        crate::diagnostics::error!("android", "failed to resolve service class {}", class_name);
         return;
     };
 
@@ -406,8 +674,9 @@ pub fn stop_service(class_name: &str) {
         "(Landroid/content/Context;Ljava/lang/Class;)V",
         &[(&activity).into(), (&service_class).into()],
     ) else {
-        eprintln!(
-            "[spot][android] failed to create stop-service intent for {}",
+        crate::diagnostics::error!(
+            "android",
+            "failed to create stop-service intent for {}",
             class_name
         );
         return;
@@ -419,9 +688,11 @@ pub fn stop_service(class_name: &str) {
         "(Landroid/content/Intent;)Z",
         &[(&intent).into()],
     ) {
-        eprintln!(
-            "[spot][android] stopService failed for {}: {:?}",
-            class_name, err
+        crate::diagnostics::error!(
+            "android",
+            "stopService failed for {}: {:?}",
+            class_name,
+            err
         );
     }
 }
@@ -0,0 +1,306 @@
+use crate::{Bounds, Context, Image, Pt};
+use std::time::Duration;
+
+/// What an [`Animation`] does after its last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Starts over from the first frame.
+    #[default]
+    Loop,
+    /// Plays backwards to the first frame, then forwards again.
+    PingPong,
+    /// Holds the last frame.
+    Once,
+}
+
+/// A flipbook animation: frames with their own durations, played back with a
+/// [`LoopMode`], a speed and pause control.
+///
+/// Advance it with [`update`](Self::update) once per `update` and draw
+/// [`current_frame`](Self::current_frame), or hand it to a [`Sprite`](crate::Sprite).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    frames: Vec<(Image, Duration)>,
+    mode: LoopMode,
+    speed: f32,
+    playing: bool,
+    elapsed: Duration,
+}
+
+impl Animation {
+    /// Shows every frame for `frame_duration`.
+    pub fn new(frames: Vec<Image>, frame_duration: Duration) -> Self {
+        Self::from_frames(
+            frames
+                .into_iter()
+                .map(|frame| (frame, frame_duration))
+                .collect(),
+        )
+    }
+
+    /// Shows each frame for its own duration.
+    pub fn from_frames(frames: Vec<(Image, Duration)>) -> Self {
+        Self {
+            frames,
+            mode: LoopMode::Loop,
+            speed: 1.0,
+            playing: true,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Cuts `sheet` into `frame_width` by `frame_height` cells, read left to right and
+    /// top to bottom, and shows each for `frame_duration`. Partial cells at the right and
+    /// bottom edges are skipped.
+    pub fn from_sheet(
+        ctx: &mut Context,
+        sheet: Image,
+        frame_width: Pt,
+        frame_height: Pt,
+        frame_duration: Duration,
+    ) -> anyhow::Result<Self> {
+        if frame_width.as_f32() <= 0.0 || frame_height.as_f32() <= 0.0 {
+            anyhow::bail!("sprite sheet frames must have a positive size");
+        }
+        let columns = (sheet.width().as_f32() / frame_width.as_f32()) as u32;
+        let rows = (sheet.height().as_f32() / frame_height.as_f32()) as u32;
+        let mut frames = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for column in 0..columns {
+                let bounds = Bounds::new(
+                    frame_width * column as f32,
+                    frame_height * row as f32,
+                    frame_width,
+                    frame_height,
+                );
+                frames.push(Image::sub_image(ctx, sheet, bounds)?);
+            }
+        }
+        Ok(Self::new(frames, frame_duration))
+    }
+
+    pub fn with_mode(mut self, mode: LoopMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.set_speed(speed);
+        self
+    }
+
+    pub fn mode(&self) -> LoopMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: LoopMode) {
+        self.mode = mode;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Scales playback; `2.0` plays twice as fast. Negative speeds are treated as zero.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+
+    /// Resumes playback from where it was paused.
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Jumps back to the first frame, keeping the play or pause state.
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    pub fn frames(&self) -> &[(Image, Duration)] {
+        &self.frames
+    }
+
+    /// Advances playback by `dt`, scaled by the speed, unless paused.
+    pub fn update(&mut self, dt: Duration) {
+        if !self.playing {
+            return;
+        }
+        self.elapsed += dt.mul_f32(self.speed);
+        // Keep the clock within one cycle so it cannot grow without bound.
+        let cycle = self.cycle_duration();
+        if !cycle.is_zero() && self.elapsed >= cycle {
+            self.elapsed = match self.mode {
+                LoopMode::Once => cycle,
+                LoopMode::Loop | LoopMode::PingPong => {
+                    Duration::from_nanos((self.elapsed.as_nanos() % cycle.as_nanos()) as u64)
+                }
+            };
+        }
+    }
+
+    /// Whether a [`LoopMode::Once`] animation has played through.
+    pub fn is_finished(&self) -> bool {
+        self.mode == LoopMode::Once && self.elapsed >= self.cycle_duration()
+    }
+
+    /// Index into [`frames`](Self::frames) of the frame shown now, or `None` without frames.
+    pub fn current_index(&self) -> Option<usize> {
+        let last = self.frames.len().checked_sub(1)?;
+        if self.is_finished() {
+            return Some(last);
+        }
+        let mut remaining = self.elapsed;
+        for step in 0..self.cycle_len() {
+            let index = self.frame_index(step);
+            let duration = self.frames[index].1;
+            if remaining < duration {
+                return Some(index);
+            }
+            remaining -= duration;
+        }
+        Some(self.frame_index(self.cycle_len() - 1))
+    }
+
+    /// The image to draw now, or `None` without frames.
+    pub fn current_frame(&self) -> Option<Image> {
+        self.current_index().map(|index| self.frames[index].0)
+    }
+
+    /// Number of steps in one cycle; ping-pong does not repeat the end frames.
+    fn cycle_len(&self) -> usize {
+        let len = self.frames.len();
+        match self.mode {
+            LoopMode::PingPong if len > 2 => 2 * len - 2,
+            _ => len,
+        }
+    }
+
+    fn frame_index(&self, step: usize) -> usize {
+        let len = self.frames.len();
+        if step < len { step } else { 2 * len - 2 - step }
+    }
+
+    fn cycle_duration(&self) -> Duration {
+        (0..self.cycle_len())
+            .map(|step| self.frames[self.frame_index(step)].1)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(ctx: &mut Context, count: usize) -> Vec<Image> {
+        (0..count)
+            .map(|_| Image::new(ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn loop_modes_pick_frames_by_their_durations() {
+        let mut ctx = Context::new();
+        let images = frames(&mut ctx, 3);
+        let ms = Duration::from_millis;
+        let timed = vec![
+            (images[0], ms(100)),
+            (images[1], ms(50)),
+            (images[2], ms(50)),
+        ];
+
+        let mut looping = Animation::from_frames(timed.clone());
+        let mut ping_pong = Animation::from_frames(timed.clone()).with_mode(LoopMode::PingPong);
+        let mut once = Animation::from_frames(timed).with_mode(LoopMode::Once);
+        let mut shown = Vec::new();
+        for _ in 0..6 {
+            shown.push([
+                looping.current_index(),
+                ping_pong.current_index(),
+                once.current_index(),
+            ]);
+            for animation in [&mut looping, &mut ping_pong, &mut once] {
+                animation.update(ms(50));
+            }
+        }
+        let shown: Vec<[usize; 3]> = shown
+            .into_iter()
+            .map(|row| row.map(Option::unwrap))
+            .collect();
+        assert_eq!(
+            shown,
+            [
+                [0, 0, 0],
+                [0, 0, 0],
+                [1, 1, 1],
+                [2, 2, 2],
+                [0, 1, 2],
+                [0, 0, 2]
+            ]
+        );
+        assert!(once.is_finished());
+        assert!(!looping.is_finished());
+    }
+
+    #[test]
+    fn pause_and_speed_control_playback() {
+        let mut ctx = Context::new();
+        let images = frames(&mut ctx, 4);
+        let mut animation = Animation::new(images.clone(), Duration::from_millis(100));
+
+        animation.set_speed(2.0);
+        animation.update(Duration::from_millis(100));
+        assert_eq!(animation.current_frame(), Some(images[2]));
+
+        animation.pause();
+        animation.update(Duration::from_millis(100));
+        assert_eq!(animation.current_frame(), Some(images[2]));
+
+        animation.play();
+        animation.set_speed(-1.0);
+        assert_eq!(animation.speed(), 0.0);
+        animation.restart();
+        assert_eq!(animation.current_frame(), Some(images[0]));
+        assert_eq!(
+            Animation::new(Vec::new(), Duration::ZERO).current_frame(),
+            None
+        );
+    }
+
+    #[test]
+    fn sheets_are_cut_row_by_row() {
+        let mut ctx = Context::new();
+        let sheet = Image::new(&mut ctx, Pt(6.0), Pt(5.0), &[255; 6 * 5 * 4]).unwrap();
+        let animation =
+            Animation::from_sheet(&mut ctx, sheet, Pt(3.0), Pt(2.0), Duration::ZERO).unwrap();
+        let bounds = |image: Image| ctx.registry.images[image.index()].as_ref().unwrap().bounds;
+        let sheet_bounds = bounds(sheet);
+        let origins: Vec<(Pt, Pt)> = animation
+            .frames()
+            .iter()
+            .map(|&(frame, _)| {
+                let frame_bounds = bounds(frame);
+                (
+                    frame_bounds.x - sheet_bounds.x,
+                    frame_bounds.y - sheet_bounds.y,
+                )
+            })
+            .collect();
+        assert_eq!(
+            origins,
+            [
+                (Pt(0.0), Pt(0.0)),
+                (Pt(3.0), Pt(0.0)),
+                (Pt(0.0), Pt(2.0)),
+                (Pt(3.0), Pt(2.0))
+            ]
+        );
+    }
+}
@@ -14,6 +14,11 @@ pub fn get_registered_font(ctx: &Context, font_id: u32) -> Option<Vec<u8>> {
 /// Unregisters a font and clears any cached GPU state for it.
 pub fn unregister_font(ctx: &mut Context, font_id: u32) {
     ctx.registry.fonts.remove(&font_id);
+    if let Some(font) = ctx.registry.bitmap_fonts.remove(&font_id) {
+        for image in font.glyphs.values().filter_map(|glyph| glyph.image) {
+            image.destroy(ctx);
+        }
+    }
     if let Some(g) = ctx.runtime.graphics.as_mut() {
         g.font_cache.remove(&(font_id as u64));
         g.dirty_assets = true;
@@ -29,18 +34,17 @@ pub fn rebuild_assets(ctx: &mut Context) {
 }
 
 /// Loads an asset from disk or from the platform-specific bundle.
+///
+/// On Android `path` is read from the APK's `assets/` directory through the
+/// `AndroidApp` asset manager, so the same relative paths work as on desktop; a
+/// leading `./` or `assets/` is stripped. Image, sound, font and script loaders all
+/// go through this function.
 pub fn load_asset(path: &str) -> anyhow::Result<Vec<u8>> {
     #[cfg(target_os = "android")]
     {
         use std::ffi::CString;
         if let Some(app) = crate::android::get_app() {
-            let mut normalized_path = path;
-            if normalized_path.starts_with("./") {
-                normalized_path = &normalized_path[2..];
-            }
-            if normalized_path.starts_with("assets/") {
-                normalized_path = &normalized_path[7..];
-            }
+            let normalized_path = bundle_asset_path(path);
             let asset_path = CString::new(normalized_path)?;
             let mut asset = app
                 .asset_manager()
@@ -53,6 +57,66 @@ pub fn load_asset(path: &str) -> anyhow::Result<Vec<u8>> {
     Ok(std::fs::read(path)?)
 }
 
+/// Maps a desktop-style relative path to its name inside the APK's `assets/` directory.
+#[cfg_attr(not(target_os = "android"), allow(dead_code))]
+fn bundle_asset_path(path: &str) -> &str {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    path.strip_prefix("assets/").unwrap_or(path)
+}
+
+/// Fetches an asset over HTTP relative to the page.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) async fn fetch_asset(path: &str) -> anyhow::Result<Vec<u8>> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let js_error = |e: wasm_bindgen::JsValue| anyhow::anyhow!("failed to fetch {path}: {e:?}");
+    let window = web_sys::window().ok_or_else(|| anyhow::anyhow!("no window"))?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(path))
+        .await
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    if !response.ok() {
+        anyhow::bail!("failed to fetch {path}: HTTP {}", response.status());
+    }
+    let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// Loads an asset without blocking and calls `on_complete` with its bytes on the main
+/// thread, right before the next `update`.
+///
+/// Native platforms read `path` like [`load_asset`] on a background thread; the web
+/// fetches it over HTTP relative to the page, where `std::fs` is unavailable.
+pub fn load_bytes_async<C>(ctx: &mut Context, path: impl Into<String>, on_complete: C)
+where
+    C: FnOnce(&mut Context, anyhow::Result<Vec<u8>>) + 'static,
+{
+    let path = path.into();
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    crate::spawn_task(ctx, move || load_asset(&path), on_complete);
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    crate::spawn_future(ctx, async move { fetch_asset(&path).await }, on_complete);
+}
+
+/// Loads a TTF/OTF font like [`load_bytes_async`] and registers it, calling
+/// `on_complete` with the new font ID.
+pub fn load_font_async<C>(ctx: &mut Context, path: impl Into<String>, on_complete: C)
+where
+    C: FnOnce(&mut Context, anyhow::Result<u32>) + 'static,
+{
+    load_bytes_async(ctx, path, move |ctx, bytes| {
+        let font_id = bytes.and_then(|bytes| {
+            ab_glyph::FontArc::try_from_vec(bytes.clone())?;
+            Ok(ctx.register_font(bytes))
+        });
+        on_complete(ctx, font_id);
+    });
+}
+
 /// Sets whether the current window background should be transparent.
 pub fn set_background_transparent(ctx: &mut Context, transparent: bool) {
     if let Some(g) = ctx.runtime.graphics.as_mut() {
@@ -68,3 +132,49 @@ pub fn is_background_transparent(ctx: &Context) -> bool {
         .map(|g| g.transparent())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn poll_until_done(ctx: &mut Context, done: impl Fn() -> bool) {
+        for _ in 0..1000 {
+            crate::tasks::poll(ctx);
+            if done() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn bytes_and_errors_arrive_through_the_callback() {
+        let mut ctx = Context::new();
+        let loaded = Rc::new(RefCell::new(None));
+        let failed = Rc::new(RefCell::new(None));
+
+        let slot = loaded.clone();
+        load_bytes_async(&mut ctx, "Cargo.toml", move |_ctx, bytes| {
+            *slot.borrow_mut() = Some(bytes.unwrap());
+        });
+        let slot = failed.clone();
+        load_font_async(&mut ctx, "Cargo.toml", move |_ctx, font| {
+            *slot.borrow_mut() = Some(font.is_err());
+        });
+        poll_until_done(&mut ctx, || {
+            loaded.borrow().is_some() && failed.borrow().is_some()
+        });
+
+        assert!(loaded.borrow().as_ref().unwrap().starts_with(b"[package]"));
+        assert_eq!(*failed.borrow(), Some(true), "a TOML file is not a font");
+    }
+
+    #[test]
+    fn bundle_paths_drop_desktop_prefixes() {
+        assert_eq!(bundle_asset_path("./assets/hero.png"), "hero.png");
+        assert_eq!(bundle_asset_path("assets/sfx/jump.ogg"), "sfx/jump.ogg");
+        assert_eq!(bundle_asset_path("fonts/ui.ttf"), "fonts/ui.ttf");
+    }
+}
@@ -0,0 +1,445 @@
+//! Effect processors applied to a bus's mix on the audio thread.
+
+use std::f32::consts::{FRAC_1_SQRT_2, TAU};
+use std::time::Duration;
+
+/// An effect applied to everything routed through an [`AudioBus`](super::AudioBus).
+///
+/// Effects run in chain order before the bus volume is applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioEffect {
+    /// Removes frequencies above `cutoff_hz`, e.g. to muffle sound underwater.
+    LowPass { cutoff_hz: f32, q: f32 },
+    /// Removes frequencies below `cutoff_hz`, e.g. for a thin radio voice.
+    HighPass { cutoff_hz: f32, q: f32 },
+    /// Repeats the signal every `delay`, each repeat scaled by `feedback` (0.0 to 0.95).
+    /// `mix` is the level of the repeats added to the dry signal.
+    Echo {
+        delay: Duration,
+        feedback: f32,
+        mix: f32,
+    },
+    /// Simulates a room. `room_size` and `damping` range from 0.0 to 1.0, and `mix`
+    /// blends from the dry signal (0.0) to the reverberated one (1.0).
+    Reverb {
+        room_size: f32,
+        damping: f32,
+        mix: f32,
+    },
+}
+
+impl AudioEffect {
+    /// A low-pass filter without resonance.
+    pub fn low_pass(cutoff_hz: f32) -> Self {
+        Self::LowPass {
+            cutoff_hz,
+            q: FRAC_1_SQRT_2,
+        }
+    }
+
+    /// A high-pass filter without resonance.
+    pub fn high_pass(cutoff_hz: f32) -> Self {
+        Self::HighPass {
+            cutoff_hz,
+            q: FRAC_1_SQRT_2,
+        }
+    }
+}
+
+/// The effects of one bus together with their running state.
+pub(crate) struct EffectChain {
+    processors: Vec<Processor>,
+}
+
+impl EffectChain {
+    pub(crate) fn new() -> Self {
+        Self {
+            processors: Vec::new(),
+        }
+    }
+
+    /// Replaces the chain. Effects that keep their kind and slot only update their
+    /// parameters, so sweeping a filter cutoff every frame does not click or cut tails.
+    pub(crate) fn configure(&mut self, effects: &[AudioEffect], sample_rate: u32) {
+        self.processors.truncate(effects.len());
+        for (index, effect) in effects.iter().enumerate() {
+            match self.processors.get_mut(index) {
+                Some(processor) if processor.same_kind(effect) => {
+                    processor.update(*effect, sample_rate)
+                }
+                Some(processor) => *processor = Processor::new(*effect, sample_rate),
+                None => self.processors.push(Processor::new(*effect, sample_rate)),
+            }
+        }
+    }
+
+    pub(crate) fn effects(&self) -> Vec<AudioEffect> {
+        self.processors.iter().map(|p| p.effect).collect()
+    }
+
+    pub(crate) fn process(&mut self, mut frame: [f32; 2]) -> [f32; 2] {
+        for processor in &mut self.processors {
+            frame = processor.process(frame);
+        }
+        frame
+    }
+}
+
+struct Processor {
+    effect: AudioEffect,
+    state: ProcessorState,
+}
+
+enum ProcessorState {
+    Biquad(Biquad),
+    Echo(Echo),
+    Reverb(Box<Reverb>),
+}
+
+impl Processor {
+    fn new(effect: AudioEffect, sample_rate: u32) -> Self {
+        let state = match effect {
+            AudioEffect::LowPass { .. } | AudioEffect::HighPass { .. } => {
+                ProcessorState::Biquad(Biquad::new(effect, sample_rate))
+            }
+            AudioEffect::Echo { delay, .. } => {
+                ProcessorState::Echo(Echo::new(delay_frames(delay, sample_rate)))
+            }
+            AudioEffect::Reverb { .. } => {
+                ProcessorState::Reverb(Box::new(Reverb::new(sample_rate)))
+            }
+        };
+        Self { effect, state }
+    }
+
+    fn same_kind(&self, effect: &AudioEffect) -> bool {
+        std::mem::discriminant(&self.effect) == std::mem::discriminant(effect)
+    }
+
+    fn update(&mut self, effect: AudioEffect, sample_rate: u32) {
+        match (&mut self.state, effect) {
+            (ProcessorState::Biquad(biquad), _) => *biquad = biquad.retuned(effect, sample_rate),
+            (ProcessorState::Echo(echo), AudioEffect::Echo { delay, .. }) => {
+                echo.resize(delay_frames(delay, sample_rate))
+            }
+            // The tail cannot be carried over to delay lines of other lengths.
+            (ProcessorState::Reverb(reverb), _) if reverb.sample_rate != sample_rate => {
+                **reverb = Reverb::new(sample_rate)
+            }
+            _ => {}
+        }
+        self.effect = effect;
+    }
+
+    fn process(&mut self, frame: [f32; 2]) -> [f32; 2] {
+        match (&mut self.state, self.effect) {
+            (ProcessorState::Biquad(biquad), _) => {
+                [biquad.process(0, frame[0]), biquad.process(1, frame[1])]
+            }
+            (ProcessorState::Echo(echo), AudioEffect::Echo { feedback, mix, .. }) => {
+                echo.process(frame, feedback.clamp(0.0, 0.95), mix.max(0.0))
+            }
+            (
+                ProcessorState::Reverb(reverb),
+                AudioEffect::Reverb {
+                    room_size,
+                    damping,
+                    mix,
+                },
+            ) => reverb.process(
+                frame,
+                room_size.clamp(0.0, 1.0),
+                damping.clamp(0.0, 1.0),
+                mix.clamp(0.0, 1.0),
+            ),
+            _ => frame,
+        }
+    }
+}
+
+fn delay_frames(delay: Duration, sample_rate: u32) -> usize {
+    ((delay.as_secs_f64() * sample_rate as f64).round() as usize).max(1)
+}
+
+/// A second-order filter using the RBJ cookbook coefficients.
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    /// Transposed direct form II state per channel.
+    state: [[f32; 2]; 2],
+}
+
+impl Biquad {
+    fn new(effect: AudioEffect, sample_rate: u32) -> Self {
+        let (high_pass, cutoff_hz, q) = match effect {
+            AudioEffect::HighPass { cutoff_hz, q } => (true, cutoff_hz, q),
+            AudioEffect::LowPass { cutoff_hz, q } => (false, cutoff_hz, q),
+            _ => (false, f32::MAX, FRAC_1_SQRT_2),
+        };
+        let sample_rate = sample_rate.max(1) as f32;
+        let cutoff_hz = cutoff_hz.clamp(10.0, sample_rate * 0.49);
+        let w0 = TAU * cutoff_hz / sample_rate;
+        let alpha = w0.sin() / (2.0 * q.max(0.01));
+        let cos = w0.cos();
+        let (b0, b1) = if high_pass {
+            ((1.0 + cos) / 2.0, -(1.0 + cos))
+        } else {
+            ((1.0 - cos) / 2.0, 1.0 - cos)
+        };
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            state: [[0.0; 2]; 2],
+        }
+    }
+
+    fn retuned(&self, effect: AudioEffect, sample_rate: u32) -> Self {
+        Self {
+            state: self.state,
+            ..Self::new(effect, sample_rate)
+        }
+    }
+
+    fn process(&mut self, channel: usize, input: f32) -> f32 {
+        let [z1, z2] = &mut self.state[channel];
+        let output = self.b0 * input + *z1;
+        *z1 = self.b1 * input - self.a1 * output + *z2;
+        *z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+}
+
+struct Echo {
+    buffer: Vec<[f32; 2]>,
+    cursor: usize,
+}
+
+impl Echo {
+    fn new(frames: usize) -> Self {
+        Self {
+            buffer: vec![[0.0; 2]; frames],
+            cursor: 0,
+        }
+    }
+
+    fn resize(&mut self, frames: usize) {
+        if frames != self.buffer.len() {
+            self.buffer.resize(frames, [0.0; 2]);
+            self.cursor %= frames;
+        }
+    }
+
+    fn process(&mut self, frame: [f32; 2], feedback: f32, mix: f32) -> [f32; 2] {
+        let delayed = self.buffer[self.cursor];
+        self.buffer[self.cursor] = [
+            frame[0] + delayed[0] * feedback,
+            frame[1] + delayed[1] * feedback,
+        ];
+        self.cursor = (self.cursor + 1) % self.buffer.len();
+        [frame[0] + delayed[0] * mix, frame[1] + delayed[1] * mix]
+    }
+}
+
+/// Comb and all-pass delay lengths from Freeverb, tuned for 44.1 kHz.
+const COMB_TUNING: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASS_TUNING: [usize; 2] = [556, 441];
+/// Extra delay of the right channel, which decorrelates the two sides.
+const STEREO_SPREAD: usize = 23;
+const REVERB_INPUT_GAIN: f32 = 0.03;
+
+/// A reduced Freeverb: parallel damped combs followed by series all-passes.
+struct Reverb {
+    /// Rate the delay lines are tuned for.
+    sample_rate: u32,
+    combs: [[Comb; 4]; 2],
+    allpasses: [[AllPass; 2]; 2],
+}
+
+impl Reverb {
+    fn new(sample_rate: u32) -> Self {
+        let scale = sample_rate.max(1) as f32 / 44_100.0;
+        let length = |tuning: usize, channel: usize| {
+            (((tuning + channel * STEREO_SPREAD) as f32 * scale) as usize).max(1)
+        };
+        Self {
+            sample_rate,
+            combs: std::array::from_fn(|ch| {
+                std::array::from_fn(|i| Comb::new(length(COMB_TUNING[i], ch)))
+            }),
+            allpasses: std::array::from_fn(|ch| {
+                std::array::from_fn(|i| AllPass::new(length(ALLPASS_TUNING[i], ch)))
+            }),
+        }
+    }
+
+    fn process(&mut self, frame: [f32; 2], room_size: f32, damping: f32, mix: f32) -> [f32; 2] {
+        let feedback = 0.7 + room_size * 0.28;
+        let damping = damping * 0.4;
+        let input = (frame[0] + frame[1]) * REVERB_INPUT_GAIN;
+        let mut output = [0.0; 2];
+        for (ch, out) in output.iter_mut().enumerate() {
+            let mut wet: f32 = self.combs[ch]
+                .iter_mut()
+                .map(|comb| comb.process(input, feedback, damping))
+                .sum();
+            for allpass in &mut self.allpasses[ch] {
+                wet = allpass.process(wet);
+            }
+            *out = frame[ch] * (1.0 - mix) + wet * mix;
+        }
+        output
+    }
+}
+
+struct Comb {
+    buffer: Vec<f32>,
+    cursor: usize,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length],
+            cursor: 0,
+            filter_store: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.cursor];
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        self.buffer[self.cursor] = input + self.filter_store * feedback;
+        self.cursor = (self.cursor + 1) % self.buffer.len();
+        output
+    }
+}
+
+struct AllPass {
+    buffer: Vec<f32>,
+    cursor: usize,
+}
+
+impl AllPass {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length],
+            cursor: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.cursor];
+        self.buffer[self.cursor] = input + buffered * 0.5;
+        self.cursor = (self.cursor + 1) % self.buffer.len();
+        buffered - input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(chain: &mut EffectChain, input: impl Iterator<Item = f32>) -> Vec<f32> {
+        input.map(|sample| chain.process([sample; 2])[0]).collect()
+    }
+
+    #[test]
+    fn low_pass_keeps_dc_and_removes_nyquist() {
+        let mut chain = EffectChain::new();
+        chain.configure(&[AudioEffect::low_pass(1_000.0)], 48_000);
+
+        let dc = run(&mut chain, std::iter::repeat_n(1.0, 2_000));
+        assert!((dc.last().unwrap() - 1.0).abs() < 1e-3);
+
+        let nyquist = run(
+            &mut chain,
+            (0..2_000).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 }),
+        );
+        assert!(nyquist.last().unwrap().abs() < 0.01);
+    }
+
+    #[test]
+    fn echo_repeats_an_impulse_after_the_delay() {
+        let mut chain = EffectChain::new();
+        chain.configure(
+            &[AudioEffect::Echo {
+                delay: Duration::from_millis(10),
+                feedback: 0.5,
+                mix: 1.0,
+            }],
+            1_000,
+        );
+        let output = run(&mut chain, (0..30).map(|i| if i == 0 { 1.0 } else { 0.0 }));
+        assert_eq!(output[0], 1.0);
+        assert_eq!(output[10], 1.0);
+        assert_eq!(output[20], 0.5);
+        assert_eq!(output[5], 0.0);
+    }
+
+    #[test]
+    fn reverb_tail_decays() {
+        let mut chain = EffectChain::new();
+        chain.configure(
+            &[AudioEffect::Reverb {
+                room_size: 0.5,
+                damping: 0.5,
+                mix: 1.0,
+            }],
+            44_100,
+        );
+        let output = run(
+            &mut chain,
+            (0..88_200).map(|i| if i == 0 { 1.0 } else { 0.0 }),
+        );
+        let early: f32 = output[..22_050].iter().map(|s| s.abs()).sum();
+        let late: f32 = output[66_150..].iter().map(|s| s.abs()).sum();
+        assert!(early > 0.0);
+        assert!(late < early * 0.01);
+    }
+
+    #[test]
+    fn reverb_delay_lines_follow_the_sample_rate() {
+        let reverb = AudioEffect::Reverb {
+            room_size: 0.5,
+            damping: 0.5,
+            mix: 1.0,
+        };
+        let comb_length = |chain: &EffectChain| match &chain.processors[0].state {
+            ProcessorState::Reverb(reverb) => reverb.combs[0][0].buffer.len(),
+            _ => unreachable!(),
+        };
+        let mut chain = EffectChain::new();
+        chain.configure(&[reverb], 44_100);
+        assert_eq!(comb_length(&chain), COMB_TUNING[0]);
+        chain.configure(&[reverb], 88_200);
+        assert_eq!(comb_length(&chain), COMB_TUNING[0] * 2);
+    }
+
+    #[test]
+    fn reconfiguring_the_same_kind_keeps_state() {
+        let echo = |mix| AudioEffect::Echo {
+            delay: Duration::from_millis(10),
+            feedback: 0.0,
+            mix,
+        };
+        let mut chain = EffectChain::new();
+        chain.configure(&[echo(1.0)], 1_000);
+        chain.process([1.0; 2]);
+
+        chain.configure(&[echo(0.5)], 1_000);
+        let output = run(&mut chain, std::iter::repeat_n(0.0, 10));
+        assert_eq!(output[9], 0.5);
+        assert_eq!(chain.effects(), vec![echo(0.5)]);
+
+        chain.configure(&[], 1_000);
+        assert!(chain.effects().is_empty());
+    }
+}
@@ -0,0 +1,1944 @@
+//! The mixer behind the functions in `sound.rs`, the crate's only audio path.
+//!
+//! [`AudioSystem`] is owned by the [`Context`](crate::Context) and forwards to a
+//! [`MixerHandler`] that renders on the audio thread.
+
+mod effects;
+mod output;
+
+pub use effects::AudioEffect;
+pub use output::AudioDevice;
+pub(crate) use output::output_devices;
+
+use crate::Pt;
+use anyhow::{Context as _, Result};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use symphonia::core::audio::{AudioBufferRef, Signal};
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+pub struct AudioSystem(pub(crate) Arc<AudioSystemInner>);
+
+impl Clone for AudioSystem {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+type AudioRegistrationQueue = Arc<Mutex<Vec<(u32, SoundData)>>>;
+/// Status of sounds loaded with `load_sound_async` that are not yet registered.
+type AsyncLoadStatus = Arc<Mutex<HashMap<u32, SoundStatus>>>;
+
+/// Loading state of a sound, see [`sound_status`](crate::sound_status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundStatus {
+    /// Still being read or decoded in the background.
+    Loading,
+    /// Decoded and playable.
+    Ready,
+    /// Reading or decoding failed; the error was logged.
+    Failed,
+}
+
+pub(crate) struct AudioSystemInner {
+    output: Mutex<output::OutputStream>,
+    handler: Arc<Mutex<MixerHandler>>,
+    next_sound_id: AtomicU32,
+    registration_queue: AudioRegistrationQueue,
+    load_status: AsyncLoadStatus,
+}
+
+// cpal::Stream is safe to send and sync on most platforms.
+// We explicitly implement Send and Sync to allow storing AudioSystem in a global static/Arc.
+unsafe impl Send for AudioSystemInner {}
+unsafe impl Sync for AudioSystemInner {}
+
+impl fmt::Debug for AudioSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioSystem").finish()
+    }
+}
+
+impl fmt::Debug for AudioSystemInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioSystemInner").finish()
+    }
+}
+
+/// A mixing group with its own volume. Every sound plays on one bus, and all buses
+/// feed [`AudioBus::Master`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AudioBus {
+    /// Scales everything, including sounds routed to the other buses.
+    Master,
+    Music,
+    #[default]
+    Sfx,
+    Voice,
+}
+
+impl AudioBus {
+    pub const ALL: [AudioBus; 4] = [
+        AudioBus::Master,
+        AudioBus::Music,
+        AudioBus::Sfx,
+        AudioBus::Voice,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+const BUS_COUNT: usize = AudioBus::ALL.len();
+
+/// A point on the mixer clock, which counts the audio frames sent to the output device.
+///
+/// Unlike frame times it advances with the audio hardware, so sounds scheduled against it
+/// with [`play_sound_at_time`](crate::play_sound_at_time) land on exact samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct AudioTime(Duration);
+
+impl AudioTime {
+    pub const ZERO: AudioTime = AudioTime(Duration::ZERO);
+
+    pub fn from_duration(duration: Duration) -> Self {
+        Self(duration)
+    }
+
+    /// Time since the mixer clock started.
+    pub fn as_duration(self) -> Duration {
+        self.0
+    }
+
+    pub fn as_secs_f64(self) -> f64 {
+        self.0.as_secs_f64()
+    }
+
+    fn from_frames(frames: u64, sample_rate: u32) -> Self {
+        if sample_rate == 0 {
+            return Self::ZERO;
+        }
+        Self(Duration::from_secs_f64(frames as f64 / sample_rate as f64))
+    }
+
+    fn to_frames(self, sample_rate: u32) -> u64 {
+        (self.0.as_secs_f64() * sample_rate as f64).round() as u64
+    }
+}
+
+impl std::ops::Add<Duration> for AudioTime {
+    type Output = AudioTime;
+
+    fn add(self, rhs: Duration) -> AudioTime {
+        AudioTime(self.0 + rhs)
+    }
+}
+
+impl std::ops::AddAssign<Duration> for AudioTime {
+    fn add_assign(&mut self, rhs: Duration) {
+        self.0 += rhs;
+    }
+}
+
+impl std::ops::Sub for AudioTime {
+    type Output = Duration;
+
+    /// Saturates to zero when `rhs` is later.
+    fn sub(self, rhs: AudioTime) -> Duration {
+        self.0.saturating_sub(rhs.0)
+    }
+}
+
+/// Lowers a bus while sounds play on another, e.g. music under dialog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ducking {
+    /// The bus whose sounds trigger the dip.
+    pub trigger: AudioBus,
+    /// Volume multiplier applied while ducked, from 0.0 (silent) to 1.0 (no change).
+    pub level: f32,
+    /// Time to dip down to `level` once a sound starts on the trigger bus.
+    pub attack: Duration,
+    /// Time to come back up after the last sound on the trigger bus ends.
+    pub release: Duration,
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self {
+            trigger: AudioBus::Voice,
+            level: 0.35,
+            attack: Duration::from_millis(80),
+            release: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Voices mixed at once before new sounds steal older ones.
+pub(crate) const DEFAULT_MAX_VOICES: usize = 64;
+
+/// Fade applied to a stolen voice so it does not click.
+const VOICE_STEAL_FADE: Duration = Duration::from_millis(5);
+
+/// The point positional sounds are heard from, and how they fade with distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioListener {
+    pub x: Pt,
+    pub y: Pt,
+    /// Sounds closer than this play at full volume.
+    pub min_distance: Pt,
+    /// Sounds at or beyond this distance are silent. A sound this far to either side
+    /// is also panned fully to that side.
+    pub max_distance: Pt,
+}
+
+impl Default for AudioListener {
+    fn default() -> Self {
+        Self {
+            x: Pt(0.0),
+            y: Pt(0.0),
+            min_distance: Pt(64.0),
+            max_distance: Pt(1024.0),
+        }
+    }
+}
+
+impl AudioListener {
+    /// Returns the left and right gains for a sound at `(x, y)`.
+    fn stereo_gains(&self, x: f32, y: f32) -> [f32; 2] {
+        let dx = x - self.x.0;
+        let dy = y - self.y.0;
+        let distance = (dx * dx + dy * dy).sqrt();
+        let min = self.min_distance.0.max(0.0);
+        let max = self.max_distance.0.max(min);
+        let attenuation = if distance <= min {
+            1.0
+        } else if distance >= max {
+            0.0
+        } else {
+            1.0 - (distance - min) / (max - min)
+        };
+        let pan = if max > 0.0 {
+            (dx / max).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+        // Balance pan: the far side is attenuated while the near side stays at full level,
+        // so a centered positional sound is as loud as a regular one.
+        [
+            attenuation * (1.0 - pan).min(1.0),
+            attenuation * (1.0 + pan).min(1.0),
+        ]
+    }
+}
+
+struct BusState {
+    volume: f32,
+    muted: bool,
+    ramp: Option<FadeState>,
+    effects: effects::EffectChain,
+    ducking: Option<Ducking>,
+    /// Current ducking multiplier, moving towards the ducked level or back to 1.0.
+    duck_gain: f32,
+}
+
+impl BusState {
+    fn new() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+            ramp: None,
+            effects: effects::EffectChain::new(),
+            ducking: None,
+            duck_gain: 1.0,
+        }
+    }
+
+    /// Advances the ducking envelope by one frame and returns its multiplier.
+    ///
+    /// `triggered` holds, per bus, whether any sound is playing on it.
+    fn next_duck_gain(&mut self, triggered: &[bool; BUS_COUNT], sample_rate: u32) -> f32 {
+        let Some(ducking) = self.ducking else {
+            self.duck_gain = 1.0;
+            return 1.0;
+        };
+        let level = ducking.level.clamp(0.0, 1.0);
+        let (target, time) = if triggered[ducking.trigger.index()] {
+            (level, ducking.attack)
+        } else {
+            (1.0, ducking.release)
+        };
+        let frames = duration_to_frames(time, sample_rate).max(1);
+        let step = (1.0 - level).max(0.001) / frames as f32;
+        self.duck_gain = if self.duck_gain > target {
+            (self.duck_gain - step).max(target)
+        } else {
+            (self.duck_gain + step).min(target)
+        };
+        self.duck_gain
+    }
+
+    /// Advances any running fade by one frame and returns the bus gain.
+    fn next_gain(&mut self) -> f32 {
+        let gain = match &mut self.ramp {
+            Some(ramp) => {
+                let gain = ramp.next_gain();
+                if ramp.finished() {
+                    self.volume = ramp.end;
+                    self.ramp = None;
+                } else {
+                    self.volume = gain;
+                }
+                gain
+            }
+            None => self.volume,
+        };
+        if self.muted { 0.0 } else { gain }
+    }
+
+    fn target_volume(&self) -> f32 {
+        self.ramp.as_ref().map_or(self.volume, |ramp| ramp.end)
+    }
+}
+
+/// Renders mono samples at the given output sample rate on the audio thread.
+pub(crate) type AudioSourceFn = Box<dyn FnMut(&mut [f32], u32) + Send>;
+
+/// Most frames mixed in one pass. Longer device buffers are mixed in several passes, so
+/// the scratch buffers sized to it when a source starts never grow on the audio thread.
+const MAX_MIX_FRAMES: usize = 4096;
+
+/// An application callback mixed like a playing sound.
+struct CustomSource {
+    id: u64,
+    bus: AudioBus,
+    render: AudioSourceFn,
+    /// `MAX_MIX_FRAMES` samples of scratch space for `render`.
+    buffer: Vec<f32>,
+}
+
+pub(crate) struct MixerHandler {
+    sample_rate: u32,
+    channels: u16,
+    next_play_id: u64,
+    /// Frames rendered so far at `sample_rate`; the mixer clock.
+    clock: u64,
+    /// Frames the output callback filled with silence because the game thread held the
+    /// mixer; added to `clock` on the next `process` so it keeps pace with the device.
+    missed_frames: Arc<AtomicU64>,
+    sound_registry: HashMap<u32, SoundData>,
+    sounds: Vec<PlayingSound>,
+    sources: Vec<CustomSource>,
+    /// Upper bound on unpaused sounds; the quietest voice is stolen beyond it.
+    max_voices: usize,
+    buses: [BusState; BUS_COUNT],
+    listener: AudioListener,
+    /// Play ids with a completion callback; finishing one wakes the event loop.
+    watched: HashSet<u64>,
+}
+
+impl fmt::Debug for MixerHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MixerHandler").finish()
+    }
+}
+
+impl MixerHandler {
+    fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            next_play_id: 1,
+            clock: 0,
+            missed_frames: Arc::new(AtomicU64::new(0)),
+            sound_registry: HashMap::new(),
+            sounds: Vec::new(),
+            sources: Vec::new(),
+            max_voices: DEFAULT_MAX_VOICES,
+            buses: std::array::from_fn(|_| BusState::new()),
+            listener: AudioListener::default(),
+            watched: HashSet::new(),
+        }
+    }
+
+    /// Adopts the format of a newly opened output stream.
+    fn set_output_format(&mut self, sample_rate: u32, channels: u16) {
+        if self.sample_rate != sample_rate && self.sample_rate != 0 {
+            // Keep the clock and scheduled starts at the same time in the new rate.
+            let rescale =
+                |frames: u64| (frames as f64 * sample_rate as f64 / self.sample_rate as f64) as u64;
+            self.clock = rescale(self.clock);
+            for sound in &mut self.sounds {
+                sound.start_frame = rescale(sound.start_frame);
+            }
+        }
+        if self.sample_rate != sample_rate {
+            for bus in &mut self.buses {
+                let effects = bus.effects.effects();
+                bus.effects.configure(&effects, sample_rate);
+            }
+        }
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+    }
+
+    /// Counter the output callback adds skipped frames to; see `missed_frames`.
+    pub(crate) fn missed_frames(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.missed_frames)
+    }
+
+    fn process(&mut self, output: &mut [f32]) {
+        self.clock += self.missed_frames.swap(0, Ordering::Relaxed);
+        let channels = self.channels.max(1) as usize;
+        for block in output.chunks_mut(MAX_MIX_FRAMES * channels) {
+            self.mix(block, channels);
+        }
+    }
+
+    /// Mixes at most `MAX_MIX_FRAMES` frames into `output`.
+    fn mix(&mut self, output: &mut [f32], channels: usize) {
+        let frames = output.len() / channels;
+        for sound in &mut self.sounds {
+            sound.gains = sound
+                .emitter
+                .map_or([1.0, 1.0], |(x, y)| self.listener.stereo_gains(x, y));
+        }
+        for source in &mut self.sources {
+            let buffer = &mut source.buffer[..frames];
+            buffer.fill(0.0);
+            (source.render)(buffer, self.sample_rate);
+        }
+        let mut triggered = [false; BUS_COUNT];
+        for sound in &self.sounds {
+            if !sound.finished && !sound.paused && sound.start_frame < self.clock + frames as u64 {
+                triggered[sound.bus.index()] = true;
+            }
+        }
+        for frame in 0..frames {
+            let mut bus_mix = [[0.0f32; 2]; BUS_COUNT];
+            for source in &self.sources {
+                let sample = source.buffer[frame];
+                let mix = &mut bus_mix[source.bus.index()];
+                mix[0] += sample;
+                mix[1] += sample;
+            }
+            for sound in &mut self.sounds {
+                if sound.finished || sound.paused || sound.start_frame > self.clock + frame as u64 {
+                    continue;
+                }
+                let sample = sound.next_sample(self.sample_rate);
+                let mix = &mut bus_mix[sound.bus.index()];
+                mix[0] += sample * sound.gains[0];
+                mix[1] += sample * sound.gains[1];
+            }
+            let [mut left, mut right] = bus_mix[AudioBus::Master.index()];
+            for bus in &AudioBus::ALL[1..] {
+                let state = &mut self.buses[bus.index()];
+                let [bus_left, bus_right] = state.effects.process(bus_mix[bus.index()]);
+                let gain = state.next_gain() * state.next_duck_gain(&triggered, self.sample_rate);
+                left += bus_left * gain;
+                right += bus_right * gain;
+            }
+            let master = &mut self.buses[AudioBus::Master.index()];
+            let [left, right] = master.effects.process([left, right]);
+            let master_gain =
+                master.next_gain() * master.next_duck_gain(&triggered, self.sample_rate);
+            let left = (left * master_gain).clamp(-1.0, 1.0);
+            let right = (right * master_gain).clamp(-1.0, 1.0);
+            let base = frame * channels;
+            if channels == 1 {
+                output[base] = (left + right) * 0.5;
+                continue;
+            }
+            output[base] = left;
+            output[base + 1] = right;
+            for ch in 2..channels {
+                output[base + ch] = (left + right) * 0.5;
+            }
+        }
+        self.clock += frames as u64;
+        let watched = &mut self.watched;
+        let mut wake = false;
+        self.sounds.retain(|sound| {
+            if sound.finished && watched.remove(&sound.id) {
+                wake = true;
+            }
+            !sound.finished
+        });
+        if wake {
+            crate::user_events::wake();
+        }
+    }
+
+    fn unregister_sound(&mut self, sound_id: u32) {
+        self.sound_registry.remove(&sound_id);
+    }
+
+    /// Fades out voices so that one more sound fits within the global limit and, for
+    /// `sound_id`, within `max_voices` instances of that sound.
+    fn make_room_for(&mut self, sound_id: Option<u32>, max_voices: Option<usize>) {
+        if let (Some(sound_id), Some(limit)) = (sound_id, max_voices) {
+            self.steal_voices(limit.max(1), |sound| sound.sound_id == Some(sound_id));
+        }
+        self.steal_voices(self.max_voices, |_| true);
+    }
+
+    /// Fades out the quietest, then oldest, matching voices until fewer than `limit`
+    /// remain audible. Paused and already fading-out voices are not counted.
+    fn steal_voices(&mut self, limit: usize, matches: impl Fn(&PlayingSound) -> bool) {
+        let mut candidates: Vec<(f32, u64)> = self
+            .sounds
+            .iter()
+            .filter(|sound| sound.is_active() && matches(sound))
+            .map(|sound| (self.voice_level(sound), sound.id))
+            .collect();
+        if candidates.len() < limit {
+            return;
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        let frames = duration_to_frames(VOICE_STEAL_FADE, self.sample_rate);
+        for (_, id) in candidates.iter().take(candidates.len() + 1 - limit) {
+            if let Some(sound) = self.sounds.iter_mut().find(|sound| sound.id == *id) {
+                sound.fade = Some(FadeState::new(sound.fade_gain, 0.0, frames, true));
+                // Voices that have not started yet would never advance their fade.
+                if frames == 0 || sound.start_frame > self.clock {
+                    sound.finished = true;
+                }
+            }
+        }
+    }
+
+    /// Approximate loudness of a voice, used to pick which one to steal.
+    fn voice_level(&self, sound: &PlayingSound) -> f32 {
+        let bus = &self.buses[sound.bus.index()];
+        let bus_gain = if bus.muted { 0.0 } else { bus.target_volume() };
+        sound.volume * sound.fade_gain * sound.gains[0].max(sound.gains[1]) * bus_gain
+    }
+
+    fn allocate_play_id(&mut self) -> u64 {
+        let play_id = self.next_play_id;
+        self.next_play_id = self.next_play_id.wrapping_add(1).max(1);
+        play_id
+    }
+}
+
+impl AudioSystem {
+    pub fn new() -> Result<Self> {
+        Ok(Self(Arc::new(AudioSystemInner::new()?)))
+    }
+
+    pub(crate) fn play_sine(&self, freq: f32, volume: f32) -> Option<u64> {
+        self.0.play_sine(freq, volume)
+    }
+
+    pub(crate) fn play_registered_sound_with_options(
+        &self,
+        sound_id: u32,
+        options: PlayOptions,
+    ) -> Option<u64> {
+        self.0.play_registered_sound_with_options(sound_id, options)
+    }
+
+    pub(crate) fn register_sound(&self, bytes: Vec<u8>) -> u32 {
+        let sound_id = self.0.next_sound_id.fetch_add(1, Ordering::SeqCst);
+        if let Ok(sound_data) = decode_sound_from_bytes(bytes)
+            && let Ok(mut queue) = self.0.registration_queue.lock()
+        {
+            queue.push((sound_id, sound_data));
+        }
+        sound_id
+    }
+
+    /// Decodes `bytes` and swaps them in for `sound_id`; voices already playing keep the
+    /// old samples.
+    #[cfg(all(
+        feature = "hot-reload",
+        any(target_os = "windows", target_os = "macos", target_os = "linux")
+    ))]
+    pub(crate) fn replace_sound(&self, sound_id: u32, bytes: Vec<u8>) -> Result<()> {
+        let sound_data = decode_sound_from_bytes(bytes)?;
+        if let Ok(mut queue) = self.0.registration_queue.lock() {
+            queue.push((sound_id, sound_data));
+        }
+        Ok(())
+    }
+
+    /// Reserves a sound id and reads and decodes `path` in the background.
+    ///
+    /// Natively this runs on its own thread; the web fetches the file over HTTP and
+    /// decodes it once the response arrives.
+    pub(crate) fn load_sound_async(&self, path: String) -> u32 {
+        let sound_id = self.0.next_sound_id.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut status) = self.0.load_status.lock() {
+            status.insert(sound_id, SoundStatus::Loading);
+        }
+        let status = Arc::clone(&self.0.load_status);
+        let queue = Arc::clone(&self.0.registration_queue);
+
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        std::thread::spawn(move || {
+            let result = crate::load_asset(&path).and_then(decode_sound_from_bytes);
+            finish_async_load(&status, &queue, sound_id, &path, result);
+        });
+
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = crate::assets::fetch_asset(&path)
+                .await
+                .and_then(decode_sound_from_bytes);
+            finish_async_load(&status, &queue, sound_id, &path, result);
+        });
+
+        sound_id
+    }
+
+    pub(crate) fn sound_status(&self, sound_id: u32) -> Option<SoundStatus> {
+        self.0.sound_status(sound_id)
+    }
+
+    pub(crate) fn pause_play_id(&self, play_id: u64) {
+        self.0.pause_play_id(play_id);
+    }
+
+    pub(crate) fn resume_play_id(&self, play_id: u64) {
+        self.0.resume_play_id(play_id);
+    }
+
+    pub(crate) fn stop_play_id(&self, play_id: u64) {
+        self.0.stop_play_id(play_id);
+    }
+
+    pub(crate) fn stop_all_sounds(&self) {
+        self.0.stop_all_sounds();
+    }
+
+    pub(crate) fn audio_time(&self) -> AudioTime {
+        self.0.audio_time()
+    }
+
+    pub(crate) fn set_max_voices(&self, max_voices: usize) {
+        self.0.set_max_voices(max_voices);
+    }
+
+    pub(crate) fn max_voices(&self) -> usize {
+        self.0.max_voices()
+    }
+
+    pub(crate) fn active_voices(&self) -> usize {
+        self.0.active_voices()
+    }
+
+    /// Reopens the output stream if its device disappeared or the system default changed.
+    ///
+    /// Called once per frame by the platform loop.
+    pub(crate) fn maintain(&self) {
+        self.0.maintain();
+    }
+
+    pub(crate) fn set_output_device(&self, id: Option<&str>) -> Result<()> {
+        self.0.set_output_device(id)
+    }
+
+    /// Pauses the whole mixer while the app is in the background. Sounds keep their
+    /// play positions and continue from there on [`AudioSystem::resume`].
+    pub(crate) fn suspend(&self) {
+        self.0.suspend();
+    }
+
+    pub(crate) fn resume(&self) {
+        self.0.resume();
+    }
+
+    pub(crate) fn output_device(&self) -> Option<AudioDevice> {
+        self.0.output_device()
+    }
+
+    pub(crate) fn fade_in_play_id(&self, play_id: u64, duration: Duration) {
+        self.0.fade_in_play_id(play_id, duration);
+    }
+
+    pub(crate) fn fade_out_play_id(&self, play_id: u64, duration: Duration) {
+        self.0.fade_out_play_id(play_id, duration);
+    }
+
+    pub(crate) fn set_volume_play_id(&self, play_id: u64, volume: f32) {
+        self.0.set_volume_play_id(play_id, volume);
+    }
+
+    pub(crate) fn set_speed_play_id(&self, play_id: u64, speed: f32) {
+        self.0.set_speed_play_id(play_id, speed);
+    }
+
+    pub(crate) fn is_playing_play_id(&self, play_id: u64) -> bool {
+        self.0.is_playing_play_id(play_id)
+    }
+
+    /// Returns true while the sound is playing or paused.
+    pub(crate) fn is_active_play_id(&self, play_id: u64) -> bool {
+        self.0.is_active_play_id(play_id)
+    }
+
+    pub(crate) fn watch_play_id(&self, play_id: u64) {
+        self.0.watch_play_id(play_id);
+    }
+
+    pub(crate) fn add_source(&self, bus: AudioBus, render: AudioSourceFn) -> Option<u64> {
+        self.0.add_source(bus, render)
+    }
+
+    pub(crate) fn remove_source(&self, source_id: u64) {
+        self.0.remove_source(source_id);
+    }
+
+    pub(crate) fn seek_play_id(&self, play_id: u64, position: Duration) {
+        self.0.seek_play_id(play_id, position);
+    }
+
+    pub(crate) fn playback_position(&self, play_id: u64) -> Option<Duration> {
+        self.0.playback_position(play_id)
+    }
+
+    pub(crate) fn set_position_play_id(&self, play_id: u64, x: f32, y: f32) {
+        self.0.set_position_play_id(play_id, x, y);
+    }
+
+    pub(crate) fn set_listener(&self, listener: AudioListener) {
+        self.0.set_listener(listener);
+    }
+
+    pub(crate) fn listener(&self) -> AudioListener {
+        self.0.listener()
+    }
+
+    pub(crate) fn set_bus_volume(&self, bus: AudioBus, volume: f32) {
+        self.0.set_bus_volume(bus, volume);
+    }
+
+    pub(crate) fn fade_bus(&self, bus: AudioBus, volume: f32, duration: Duration) {
+        self.0.fade_bus(bus, volume, duration);
+    }
+
+    pub(crate) fn set_bus_muted(&self, bus: AudioBus, muted: bool) {
+        self.0.set_bus_muted(bus, muted);
+    }
+
+    pub(crate) fn bus_volume(&self, bus: AudioBus) -> f32 {
+        self.0.bus_volume(bus)
+    }
+
+    pub(crate) fn set_bus_effects(&self, bus: AudioBus, effects: &[AudioEffect]) {
+        self.0.set_bus_effects(bus, effects);
+    }
+
+    pub(crate) fn bus_effects(&self, bus: AudioBus) -> Vec<AudioEffect> {
+        self.0.bus_effects(bus)
+    }
+
+    pub(crate) fn is_bus_muted(&self, bus: AudioBus) -> bool {
+        self.0.is_bus_muted(bus)
+    }
+
+    pub(crate) fn set_bus_ducking(&self, bus: AudioBus, ducking: Option<Ducking>) {
+        self.0.set_bus_ducking(bus, ducking);
+    }
+
+    pub(crate) fn bus_ducking(&self, bus: AudioBus) -> Option<Ducking> {
+        self.0.bus_ducking(bus)
+    }
+
+    pub(crate) fn unregister_sound(&self, sound_id: u32) {
+        self.0.unregister_sound(sound_id);
+    }
+
+    /// Try to resume the audio stream (useful for WASM autoplay policy).
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) fn try_resume(&self) {
+        self.0.try_resume();
+    }
+}
+
+impl AudioSystemInner {
+    fn new() -> Result<Self> {
+        let handler = Arc::new(Mutex::new(MixerHandler::new(0, 2)));
+        let registration_queue = Arc::new(Mutex::new(Vec::new()));
+        let output = output::OutputStream::open(None, &handler, &registration_queue)?;
+
+        Ok(Self {
+            output: Mutex::new(output),
+            handler,
+            next_sound_id: AtomicU32::new(1),
+            registration_queue,
+            load_status: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    fn maintain(&self) {
+        let Ok(mut output) = self.output.lock() else {
+            return;
+        };
+        if output.needs_rebuild() {
+            output.rebuild(&self.handler, &self.registration_queue);
+        }
+    }
+
+    fn suspend(&self) {
+        if let Ok(mut output) = self.output.lock() {
+            output.suspend();
+        }
+    }
+
+    fn resume(&self) {
+        if let Ok(mut output) = self.output.lock() {
+            output.resume();
+        }
+    }
+
+    fn set_output_device(&self, id: Option<&str>) -> Result<()> {
+        let opened = output::OutputStream::open(
+            id.map(str::to_owned),
+            &self.handler,
+            &self.registration_queue,
+        )?;
+        if let Ok(mut output) = self.output.lock() {
+            let suspended = output.is_suspended();
+            *output = opened;
+            if suspended {
+                output.suspend();
+            }
+        }
+        Ok(())
+    }
+
+    fn output_device(&self) -> Option<AudioDevice> {
+        let device_id = self.output.lock().ok()?.device_id()?.to_owned();
+        output_devices()
+            .into_iter()
+            .find(|device| device.id == device_id)
+    }
+
+    fn play_sine(&self, freq: f32, volume: f32) -> Option<u64> {
+        let sample_rate = {
+            let Ok(h) = self.handler.lock() else {
+                return None;
+            };
+            h.sample_rate
+        };
+        let duration = Duration::from_millis(600);
+        let total_samples = (duration.as_secs_f64() * sample_rate as f64) as usize;
+        let mut samples = Vec::with_capacity(total_samples);
+        for i in 0..total_samples {
+            let t = i as f32 / sample_rate as f32;
+            samples.push((t * freq * std::f32::consts::TAU).sin());
+        }
+        let sound = SoundData {
+            samples: Arc::new(samples),
+            sample_rate,
+            channels: 1,
+        };
+        self.play_sound_with_options(
+            &sound,
+            PlayOptions {
+                volume,
+                fade_in: Duration::from_millis(20),
+                fade_out: Some(Duration::from_millis(80)),
+                ..PlayOptions::default()
+            },
+        )
+    }
+
+    fn play_registered_sound_with_options(
+        &self,
+        sound_id: u32,
+        options: PlayOptions,
+    ) -> Option<u64> {
+        self.promote_pending_registrations();
+
+        let Ok(mut handler) = self.handler.lock() else {
+            return None;
+        };
+        let sound = handler.sound_registry.get(&sound_id)?.clone();
+        Some(Self::add_playing_sound_locked(
+            &mut handler,
+            Some(sound_id),
+            &sound,
+            options,
+        ))
+    }
+
+    fn play_sound_with_options(&self, sound: &SoundData, options: PlayOptions) -> Option<u64> {
+        if sound.samples.is_empty() || sound.sample_rate == 0 {
+            return None;
+        }
+        if let Ok(mut handler) = self.handler.lock() {
+            Some(Self::add_playing_sound_locked(
+                &mut handler,
+                None,
+                sound,
+                options,
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn sound_status(&self, sound_id: u32) -> Option<SoundStatus> {
+        if let Some(status) = self.load_status.lock().ok()?.get(&sound_id) {
+            return Some(*status);
+        }
+        let queued = self
+            .registration_queue
+            .lock()
+            .ok()?
+            .iter()
+            .any(|(id, _)| *id == sound_id);
+        let registered = queued
+            || self
+                .handler
+                .lock()
+                .ok()?
+                .sound_registry
+                .contains_key(&sound_id);
+        registered.then_some(SoundStatus::Ready)
+    }
+
+    fn unregister_sound(&self, sound_id: u32) {
+        if let Ok(mut status) = self.load_status.lock() {
+            status.remove(&sound_id);
+        }
+        if let Ok(mut queue) = self.registration_queue.lock() {
+            remove_pending_registration_locked(&mut queue, sound_id);
+        }
+        if let Ok(mut handler) = self.handler.lock() {
+            handler.unregister_sound(sound_id);
+        }
+    }
+
+    fn promote_pending_registrations(&self) {
+        let Ok(mut queue) = self.registration_queue.lock() else {
+            return;
+        };
+        if queue.is_empty() {
+            return;
+        }
+
+        let Ok(mut handler) = self.handler.lock() else {
+            return;
+        };
+        promote_pending_registrations_locked(&mut handler, &mut queue);
+    }
+
+    fn add_playing_sound_locked(
+        handler: &mut MixerHandler,
+        sound_id: Option<u32>,
+        sound: &SoundData,
+        options: PlayOptions,
+    ) -> u64 {
+        if !options.start_paused {
+            handler.make_room_for(sound_id, options.max_voices);
+        }
+        let play_id = handler.allocate_play_id();
+        let mut playing = PlayingSound::new(play_id, Arc::clone(&sound.samples), sound.sample_rate);
+        playing.sound_id = sound_id;
+        playing.start_frame = options
+            .start_at
+            .map_or(0, |at| at.to_frames(handler.sample_rate));
+        playing.volume = options.volume.max(0.0);
+        playing.speed = valid_speed(options.speed).unwrap_or(1.0);
+        playing.bus = options.bus;
+        playing.emitter = options.position;
+        playing.paused = options.start_paused;
+        if options.fade_in > Duration::ZERO {
+            playing.fade_gain = 0.0;
+            playing.fade = Some(FadeState::new(
+                0.0,
+                1.0,
+                duration_to_frames(options.fade_in, handler.sample_rate),
+                false,
+            ));
+        }
+        if let Some(fade_out) = options.fade_out {
+            let frames = duration_to_frames(fade_out, handler.sample_rate);
+            if frames > 0 {
+                playing.fade_out_on_end = Some(FadeOnEnd {
+                    frames,
+                    started: false,
+                });
+            }
+        }
+        handler.sounds.push(playing);
+        play_id
+    }
+
+    fn pause_play_id(&self, play_id: u64) {
+        self.update_playing(play_id, |sound| sound.paused = true);
+    }
+
+    fn resume_play_id(&self, play_id: u64) {
+        self.update_playing(play_id, |sound| sound.paused = false);
+    }
+
+    fn stop_play_id(&self, play_id: u64) {
+        self.update_playing(play_id, |sound| sound.finished = true);
+    }
+
+    fn audio_time(&self) -> AudioTime {
+        self.handler.lock().map_or(AudioTime::ZERO, |handler| {
+            let missed = handler.missed_frames.load(Ordering::Relaxed);
+            AudioTime::from_frames(handler.clock + missed, handler.sample_rate)
+        })
+    }
+
+    fn set_max_voices(&self, max_voices: usize) {
+        if let Ok(mut handler) = self.handler.lock() {
+            handler.max_voices = max_voices.max(1);
+        }
+    }
+
+    fn max_voices(&self) -> usize {
+        self.handler
+            .lock()
+            .map_or(DEFAULT_MAX_VOICES, |handler| handler.max_voices)
+    }
+
+    fn active_voices(&self) -> usize {
+        self.handler.lock().map_or(0, |handler| {
+            handler
+                .sounds
+                .iter()
+                .filter(|sound| !sound.finished && !sound.paused)
+                .count()
+        })
+    }
+
+    fn stop_all_sounds(&self) {
+        let Ok(mut handler) = self.handler.lock() else {
+            return;
+        };
+        for sound in &mut handler.sounds {
+            sound.finished = true;
+        }
+    }
+
+    fn fade_in_play_id(&self, play_id: u64, duration: Duration) {
+        let frames = duration_to_frames(duration, self.sample_rate());
+        if frames == 0 {
+            return;
+        }
+        self.update_playing(play_id, |sound| {
+            sound.fade_gain = 0.0;
+            sound.fade = Some(FadeState::new(0.0, 1.0, frames, false));
+        });
+    }
+
+    fn fade_out_play_id(&self, play_id: u64, duration: Duration) {
+        let frames = duration_to_frames(duration, self.sample_rate());
+        if frames == 0 {
+            self.stop_play_id(play_id);
+            return;
+        }
+        self.update_playing(play_id, |sound| {
+            let start = sound.fade_gain;
+            sound.fade = Some(FadeState::new(start, 0.0, frames, true));
+        });
+    }
+
+    fn set_volume_play_id(&self, play_id: u64, volume: f32) {
+        self.update_playing(play_id, |sound| sound.volume = volume.max(0.0));
+    }
+
+    fn set_speed_play_id(&self, play_id: u64, speed: f32) {
+        if let Some(speed) = valid_speed(speed) {
+            self.update_playing(play_id, |sound| sound.speed = speed);
+        }
+    }
+
+    fn set_bus_volume(&self, bus: AudioBus, volume: f32) {
+        self.update_bus(bus, |state| {
+            state.volume = volume.max(0.0);
+            state.ramp = None;
+        });
+    }
+
+    fn fade_bus(&self, bus: AudioBus, volume: f32, duration: Duration) {
+        let frames = duration_to_frames(duration, self.sample_rate());
+        self.update_bus(bus, |state| {
+            state.ramp = Some(FadeState::new(state.volume, volume.max(0.0), frames, false));
+        });
+    }
+
+    fn set_bus_muted(&self, bus: AudioBus, muted: bool) {
+        self.update_bus(bus, |state| state.muted = muted);
+    }
+
+    fn bus_volume(&self, bus: AudioBus) -> f32 {
+        let Ok(handler) = self.handler.lock() else {
+            return 1.0;
+        };
+        handler.buses[bus.index()].target_volume()
+    }
+
+    fn is_bus_muted(&self, bus: AudioBus) -> bool {
+        let Ok(handler) = self.handler.lock() else {
+            return false;
+        };
+        handler.buses[bus.index()].muted
+    }
+
+    fn add_source(&self, bus: AudioBus, render: AudioSourceFn) -> Option<u64> {
+        let mut handler = self.handler.lock().ok()?;
+        let id = handler.allocate_play_id();
+        handler.sources.push(CustomSource {
+            id,
+            bus,
+            render,
+            buffer: vec![0.0; MAX_MIX_FRAMES],
+        });
+        Some(id)
+    }
+
+    fn remove_source(&self, source_id: u64) {
+        if let Ok(mut handler) = self.handler.lock() {
+            handler.sources.retain(|source| source.id != source_id);
+        }
+    }
+
+    fn seek_play_id(&self, play_id: u64, position: Duration) {
+        self.update_playing(play_id, |sound| sound.seek(position));
+    }
+
+    fn playback_position(&self, play_id: u64) -> Option<Duration> {
+        let Ok(handler) = self.handler.lock() else {
+            return None;
+        };
+        handler
+            .sounds
+            .iter()
+            .find(|sound| sound.id == play_id && !sound.finished)
+            .map(PlayingSound::elapsed)
+    }
+
+    fn set_position_play_id(&self, play_id: u64, x: f32, y: f32) {
+        self.update_playing(play_id, |sound| sound.emitter = Some((x, y)));
+    }
+
+    fn set_listener(&self, listener: AudioListener) {
+        if let Ok(mut handler) = self.handler.lock() {
+            handler.listener = listener;
+        }
+    }
+
+    fn listener(&self) -> AudioListener {
+        let Ok(handler) = self.handler.lock() else {
+            return AudioListener::default();
+        };
+        handler.listener
+    }
+
+    fn set_bus_effects(&self, bus: AudioBus, effects: &[AudioEffect]) {
+        if let Ok(mut handler) = self.handler.lock() {
+            let sample_rate = handler.sample_rate;
+            handler.buses[bus.index()]
+                .effects
+                .configure(effects, sample_rate);
+        }
+    }
+
+    fn bus_effects(&self, bus: AudioBus) -> Vec<AudioEffect> {
+        let Ok(handler) = self.handler.lock() else {
+            return Vec::new();
+        };
+        handler.buses[bus.index()].effects.effects()
+    }
+
+    fn set_bus_ducking(&self, bus: AudioBus, ducking: Option<Ducking>) {
+        self.update_bus(bus, |state| state.ducking = ducking);
+    }
+
+    fn bus_ducking(&self, bus: AudioBus) -> Option<Ducking> {
+        let handler = self.handler.lock().ok()?;
+        handler.buses[bus.index()].ducking
+    }
+
+    fn update_bus(&self, bus: AudioBus, f: impl FnOnce(&mut BusState)) {
+        if let Ok(mut handler) = self.handler.lock() {
+            f(&mut handler.buses[bus.index()]);
+        }
+    }
+
+    fn is_active_play_id(&self, play_id: u64) -> bool {
+        let Ok(handler) = self.handler.lock() else {
+            return false;
+        };
+        handler
+            .sounds
+            .iter()
+            .any(|sound| sound.id == play_id && !sound.finished)
+    }
+
+    fn watch_play_id(&self, play_id: u64) {
+        if let Ok(mut handler) = self.handler.lock() {
+            handler.watched.insert(play_id);
+        }
+    }
+
+    fn is_playing_play_id(&self, play_id: u64) -> bool {
+        let Ok(handler) = self.handler.lock() else {
+            return false;
+        };
+        handler
+            .sounds
+            .iter()
+            .find(|sound| sound.id == play_id)
+            .map(|sound| !sound.finished && !sound.paused)
+            .unwrap_or(false)
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    fn try_resume(&self) {
+        if let Ok(output) = self.output.lock() {
+            output.play();
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        let Ok(handler) = self.handler.lock() else {
+            return 0;
+        };
+        handler.sample_rate
+    }
+
+    fn update_playing(&self, play_id: u64, f: impl FnOnce(&mut PlayingSound)) {
+        let Ok(mut handler) = self.handler.lock() else {
+            return;
+        };
+        if let Some(sound) = handler.sounds.iter_mut().find(|sound| sound.id == play_id) {
+            f(sound);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PlayOptions {
+    pub volume: f32,
+    pub speed: f32,
+    pub bus: AudioBus,
+    /// World position for positional sounds, relative to the [`AudioListener`].
+    pub position: Option<(f32, f32)>,
+    /// Limit on simultaneous voices of the same registered sound.
+    pub max_voices: Option<usize>,
+    /// Mixer clock time to start at; `None` or a time already past starts right away.
+    pub start_at: Option<AudioTime>,
+    pub fade_in: Duration,
+    pub fade_out: Option<Duration>,
+    pub start_paused: bool,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            speed: 1.0,
+            bus: AudioBus::Sfx,
+            position: None,
+            max_voices: None,
+            start_at: None,
+            fade_in: Duration::ZERO,
+            fade_out: None,
+            start_paused: false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct SoundData {
+    pub samples: Arc<Vec<f32>>,
+    pub sample_rate: u32,
+    #[allow(dead_code)]
+    pub channels: u16,
+}
+
+struct PlayingSound {
+    id: u64,
+    /// Registered sound this voice plays, for per-sound voice limits.
+    sound_id: Option<u32>,
+    /// Mixer clock frame of the first sample; earlier frames are silent.
+    start_frame: u64,
+    samples: Arc<Vec<f32>>,
+    source_rate: u32,
+    position: f64,
+    volume: f32,
+    /// Playback rate multiplier; also shifts pitch since samples are resampled.
+    speed: f32,
+    bus: AudioBus,
+    /// World position of a positional sound.
+    emitter: Option<(f32, f32)>,
+    /// Left/right gains from the emitter position, refreshed once per buffer.
+    gains: [f32; 2],
+    paused: bool,
+    fade_gain: f32,
+    fade: Option<FadeState>,
+    fade_out_on_end: Option<FadeOnEnd>,
+    finished: bool,
+}
+
+impl PlayingSound {
+    fn new(id: u64, samples: Arc<Vec<f32>>, source_rate: u32) -> Self {
+        Self {
+            id,
+            sound_id: None,
+            start_frame: 0,
+            samples,
+            source_rate,
+            position: 0.0,
+            volume: 1.0,
+            speed: 1.0,
+            bus: AudioBus::Sfx,
+            emitter: None,
+            gains: [1.0, 1.0],
+            paused: false,
+            fade_gain: 1.0,
+            fade: None,
+            fade_out_on_end: None,
+            finished: false,
+        }
+    }
+
+    fn next_sample(&mut self, output_rate: u32) -> f32 {
+        if self.finished || output_rate == 0 || self.source_rate == 0 {
+            return 0.0;
+        }
+
+        let step = self.source_rate as f64 / output_rate as f64 * self.speed as f64;
+
+        if let Some(fade_out) = &mut self.fade_out_on_end
+            && !fade_out.started
+        {
+            // Derived from the current position so speed changes move the fade with them.
+            let remaining_frames =
+                ((self.samples.len() as f64 - self.position) / step).ceil() as u64;
+            if remaining_frames <= fade_out.frames {
+                let start_gain = self.fade_gain;
+                self.fade = Some(FadeState::new(start_gain, 0.0, fade_out.frames, true));
+                fade_out.started = true;
+            }
+        }
+
+        let sample = self.sample_at_position();
+
+        if let Some(fade) = &mut self.fade {
+            let gain = fade.next_gain();
+            self.fade_gain = gain;
+            if fade.finished() {
+                let stop = fade.stop_on_end && fade.end <= 0.0001;
+                self.fade = None;
+                if stop {
+                    self.finished = true;
+                }
+            }
+        }
+
+        self.position += step;
+
+        if self.position >= self.samples.len() as f64 {
+            self.finished = true;
+        }
+
+        sample * self.volume * self.fade_gain
+    }
+
+    /// Whether the voice is audible and not already on its way out.
+    fn is_active(&self) -> bool {
+        !self.finished
+            && !self.paused
+            && !self
+                .fade
+                .as_ref()
+                .is_some_and(|fade| fade.stop_on_end && fade.end <= 0.0001)
+    }
+
+    /// Moves the playhead, finishing the sound when seeking past its end.
+    fn seek(&mut self, position: Duration) {
+        self.position = position.as_secs_f64() * self.source_rate as f64;
+        if self.position >= self.samples.len() as f64 {
+            self.finished = true;
+            return;
+        }
+        // Seeking back out of the automatic end fade plays at full volume again.
+        if let Some(fade_out) = &mut self.fade_out_on_end
+            && fade_out.started
+        {
+            fade_out.started = false;
+            self.fade = None;
+            self.fade_gain = 1.0;
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        if self.source_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.position / self.source_rate as f64)
+    }
+
+    fn sample_at_position(&self) -> f32 {
+        let len = self.samples.len();
+        if len == 0 {
+            return 0.0;
+        }
+        let idx = self.position.floor() as usize;
+        if idx >= len {
+            return 0.0;
+        }
+        let next_idx = idx + 1;
+        let frac = (self.position - idx as f64) as f32;
+        let s0 = self.samples[idx];
+        let s1 = if next_idx < len {
+            self.samples[next_idx]
+        } else {
+            0.0
+        };
+        s0 + (s1 - s0) * frac
+    }
+}
+
+struct FadeState {
+    current: f32,
+    step: f32,
+    remaining: u64,
+    end: f32,
+    stop_on_end: bool,
+}
+
+impl FadeState {
+    fn new(start: f32, end: f32, frames: u64, stop_on_end: bool) -> Self {
+        if frames == 0 {
+            return Self {
+                current: end,
+                step: 0.0,
+                remaining: 0,
+                end,
+                stop_on_end,
+            };
+        }
+        let step = (end - start) / frames as f32;
+        Self {
+            current: start,
+            step,
+            remaining: frames,
+            end,
+            stop_on_end,
+        }
+    }
+
+    fn next_gain(&mut self) -> f32 {
+        if self.remaining == 0 {
+            return self.end;
+        }
+        let gain = self.current;
+        self.current += self.step;
+        self.remaining = self.remaining.saturating_sub(1);
+        if self.remaining == 0 {
+            self.current = self.end;
+        }
+        gain
+    }
+
+    fn finished(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+struct FadeOnEnd {
+    frames: u64,
+    started: bool,
+}
+
+/// Returns `speed` if it is usable as a playback rate.
+fn valid_speed(speed: f32) -> Option<f32> {
+    (speed.is_finite() && speed > 0.0).then_some(speed)
+}
+
+fn duration_to_frames(duration: Duration, sample_rate: u32) -> u64 {
+    if duration == Duration::ZERO || sample_rate == 0 {
+        return 0;
+    }
+    (duration.as_secs_f64() * sample_rate as f64).round() as u64
+}
+
+pub(crate) fn decode_sound_from_bytes(bytes: Vec<u8>) -> Result<SoundData> {
+    let src = std::io::Cursor::new(bytes);
+    let mss = MediaSourceStream::new(Box::new(src), Default::default());
+    let hint = Hint::new();
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .context("no supported audio track found")?;
+
+    let dec_opts = DecoderOptions::default();
+    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &dec_opts)?;
+
+    let track_id = track.id;
+    let mut sample_rate = 0;
+    let mut channels = 0;
+    let mut samples = Vec::new();
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(ref err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = decoder.decode(&packet)?;
+
+        if sample_rate == 0 {
+            let spec = decoded.spec();
+            sample_rate = spec.rate;
+            channels = spec.channels.count() as u16;
+        }
+
+        match decoded {
+            AudioBufferRef::F32(buf) => {
+                let channel_count = buf.spec().channels.count();
+                for i in 0..buf.frames() {
+                    let mut sum = 0.0f32;
+                    for ch in 0..channel_count {
+                        sum += buf.chan(ch)[i];
+                    }
+                    samples.push(sum / channel_count as f32);
+                }
+            }
+            AudioBufferRef::U8(buf) => {
+                let channel_count = buf.spec().channels.count();
+                for i in 0..buf.frames() {
+                    let mut sum = 0.0f32;
+                    for ch in 0..channel_count {
+                        sum += (buf.chan(ch)[i] as f32 - 128.0) / 128.0;
+                    }
+                    samples.push(sum / channel_count as f32);
+                }
+            }
+            AudioBufferRef::S16(buf) => {
+                let channel_count = buf.spec().channels.count();
+                for i in 0..buf.frames() {
+                    let mut sum = 0.0f32;
+                    for ch in 0..channel_count {
+                        sum += buf.chan(ch)[i] as f32 / 32768.0;
+                    }
+                    samples.push(sum / channel_count as f32);
+                }
+            }
+            AudioBufferRef::S32(buf) => {
+                let channel_count = buf.spec().channels.count();
+                for i in 0..buf.frames() {
+                    let mut sum = 0.0f32;
+                    for ch in 0..channel_count {
+                        sum += buf.chan(ch)[i] as f32 / 2147483648.0;
+                    }
+                    samples.push(sum / channel_count as f32);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(SoundData {
+        samples: Arc::new(samples),
+        sample_rate,
+        channels,
+    })
+}
+
+fn promote_pending_registrations_locked(
+    handler: &mut MixerHandler,
+    queue: &mut Vec<(u32, SoundData)>,
+) {
+    for (id, sound_data) in std::mem::take(queue) {
+        handler.sound_registry.insert(id, sound_data);
+    }
+}
+
+/// Queues a decoded sound for registration, unless it was unregistered while loading.
+fn finish_async_load(
+    status: &AsyncLoadStatus,
+    queue: &AudioRegistrationQueue,
+    sound_id: u32,
+    path: &str,
+    result: Result<SoundData>,
+) {
+    let Ok(mut status) = status.lock() else {
+        return;
+    };
+    if !status.contains_key(&sound_id) {
+        return;
+    }
+    match result {
+        Ok(sound_data) => {
+            // Queue before clearing the status so the sound never looks unknown.
+            if let Ok(mut queue) = queue.lock() {
+                queue.push((sound_id, sound_data));
+            }
+            status.remove(&sound_id);
+        }
+        Err(e) => {
+            crate::diagnostics::error!("audio", "failed to load sound {path:?}: {e:#}");
+            status.insert(sound_id, SoundStatus::Failed);
+        }
+    }
+    drop(status);
+    crate::user_events::wake();
+}
+
+fn remove_pending_registration_locked(queue: &mut Vec<(u32, SoundData)>, sound_id: u32) {
+    queue.retain(|(id, _)| *id != sound_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_registration_is_available_after_promotion() {
+        let mut handler = MixerHandler::new(48_000, 2);
+        let mut queue = vec![(7, decode_sound_from_bytes(test_wav_bytes()).unwrap())];
+
+        promote_pending_registrations_locked(&mut handler, &mut queue);
+
+        assert!(queue.is_empty());
+        assert!(handler.sound_registry.contains_key(&7));
+    }
+
+    #[test]
+    fn unregister_removes_pending_registration() {
+        let mut queue = vec![
+            (7, decode_sound_from_bytes(test_wav_bytes()).unwrap()),
+            (8, decode_sound_from_bytes(test_wav_bytes()).unwrap()),
+        ];
+
+        remove_pending_registration_locked(&mut queue, 7);
+
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].0, 8);
+    }
+
+    #[test]
+    fn async_load_result_is_queued_or_marked_failed() {
+        let status: AsyncLoadStatus = Arc::new(Mutex::new(HashMap::new()));
+        let queue: AudioRegistrationQueue = Arc::new(Mutex::new(Vec::new()));
+        status.lock().unwrap().insert(1, SoundStatus::Loading);
+        status.lock().unwrap().insert(2, SoundStatus::Loading);
+
+        let decoded = decode_sound_from_bytes(test_wav_bytes());
+        finish_async_load(&status, &queue, 1, "ok.wav", decoded);
+        finish_async_load(&status, &queue, 2, "bad.wav", Err(anyhow::anyhow!("bad")));
+        // Unregistered while loading: the result is dropped.
+        let decoded = decode_sound_from_bytes(test_wav_bytes());
+        finish_async_load(&status, &queue, 3, "gone.wav", decoded);
+
+        let status = status.lock().unwrap();
+        assert_eq!(status.get(&1), None);
+        assert_eq!(status.get(&2), Some(&SoundStatus::Failed));
+        let queued: Vec<u32> = queue.lock().unwrap().iter().map(|(id, _)| *id).collect();
+        assert_eq!(queued, [1]);
+    }
+
+    #[test]
+    fn speed_scales_playback_length() {
+        let samples = Arc::new(vec![0.5; 100]);
+        let frames_until_finished = |speed: f32| {
+            let mut sound = PlayingSound::new(1, Arc::clone(&samples), 100);
+            sound.speed = speed;
+            let mut frames = 0;
+            while !sound.finished {
+                sound.next_sample(100);
+                frames += 1;
+            }
+            frames
+        };
+
+        assert_eq!(frames_until_finished(1.0), 100);
+        assert_eq!(frames_until_finished(2.0), 50);
+        assert_eq!(frames_until_finished(0.5), 200);
+        assert_eq!(valid_speed(0.0), None);
+        assert_eq!(valid_speed(f32::NAN), None);
+    }
+
+    #[test]
+    fn bus_and_master_volumes_scale_the_mix() {
+        let mut handler = MixerHandler::new(100, 1);
+        for bus in [AudioBus::Music, AudioBus::Sfx] {
+            let mut sound =
+                PlayingSound::new(handler.sounds.len() as u64, Arc::new(vec![0.25; 10]), 100);
+            sound.bus = bus;
+            handler.sounds.push(sound);
+        }
+        handler.buses[AudioBus::Music.index()].volume = 0.0;
+        handler.buses[AudioBus::Master.index()].volume = 0.5;
+
+        let mut output = [0.0f32; 1];
+        handler.process(&mut output);
+        assert_eq!(output[0], 0.125);
+
+        handler.buses[AudioBus::Sfx.index()].muted = true;
+        handler.process(&mut output);
+        assert_eq!(output[0], 0.0);
+    }
+
+    #[test]
+    fn custom_sources_are_mixed_into_their_bus() {
+        let mut handler = MixerHandler::new(48_000, 1);
+        handler.buses[AudioBus::Voice.index()].volume = 0.5;
+        handler.sources.push(CustomSource {
+            id: 1,
+            bus: AudioBus::Voice,
+            render: Box::new(|buffer, sample_rate| {
+                assert_eq!(sample_rate, 48_000);
+                buffer.fill(0.5);
+            }),
+            buffer: vec![0.0; MAX_MIX_FRAMES],
+        });
+
+        let mut output = [0.0f32; 4];
+        handler.process(&mut output);
+        assert_eq!(output, [0.25; 4]);
+    }
+
+    #[test]
+    fn voice_limits_steal_quietest_then_oldest() {
+        let mut handler = MixerHandler::new(48_000, 2);
+        handler.max_voices = 3;
+        let sound = decode_sound_from_bytes(test_wav_bytes()).unwrap();
+        let play = |handler: &mut MixerHandler, sound_id, volume, max_voices| {
+            let options = PlayOptions {
+                volume,
+                max_voices,
+                ..PlayOptions::default()
+            };
+            AudioSystemInner::add_playing_sound_locked(handler, sound_id, &sound, options)
+        };
+        let active = |handler: &MixerHandler| -> Vec<u64> {
+            handler
+                .sounds
+                .iter()
+                .filter(|sound| sound.is_active())
+                .map(|sound| sound.id)
+                .collect()
+        };
+
+        let coin_a = play(&mut handler, Some(1), 1.0, Some(2));
+        let coin_b = play(&mut handler, Some(1), 1.0, Some(2));
+        let coin_c = play(&mut handler, Some(1), 1.0, Some(2));
+        assert_eq!(active(&handler), [coin_b, coin_c]);
+        assert!(!active(&handler).contains(&coin_a));
+
+        let quiet = play(&mut handler, Some(2), 0.1, None);
+        let loud = play(&mut handler, Some(3), 1.0, None);
+        assert_eq!(active(&handler), [coin_b, coin_c, loud]);
+        assert!(!active(&handler).contains(&quiet));
+
+        // Stolen voices are removed once their short fade completes.
+        let mut output = vec![0.0f32; 2 * 512];
+        handler.process(&mut output);
+        assert!(
+            handler
+                .sounds
+                .iter()
+                .all(|sound| sound.id != coin_a && sound.id != quiet)
+        );
+    }
+
+    #[test]
+    fn scheduled_sound_starts_on_its_frame() {
+        let mut handler = MixerHandler::new(1_000, 1);
+        let sound = SoundData {
+            samples: Arc::new(vec![0.5; 8]),
+            sample_rate: 1_000,
+            channels: 1,
+        };
+        let mut output = [0.0f32; 4];
+        handler.process(&mut output);
+        assert_eq!(
+            AudioTime::from_frames(handler.clock, handler.sample_rate),
+            AudioTime::from_duration(Duration::from_millis(4))
+        );
+
+        let options = PlayOptions {
+            start_at: Some(AudioTime::from_duration(Duration::from_millis(6))),
+            ..PlayOptions::default()
+        };
+        AudioSystemInner::add_playing_sound_locked(&mut handler, None, &sound, options);
+        handler.process(&mut output);
+        assert_eq!(output, [0.0, 0.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn buffers_the_output_callback_skipped_still_advance_the_clock() {
+        let mut handler = MixerHandler::new(1_000, 2);
+        handler.missed_frames().fetch_add(3, Ordering::Relaxed);
+        let mut output = [0.0f32; 4];
+        handler.process(&mut output);
+        assert_eq!(handler.clock, 5);
+    }
+
+    #[test]
+    fn ducking_dips_and_recovers_with_trigger_bus() {
+        let mut bus = BusState::new();
+        bus.ducking = Some(Ducking {
+            trigger: AudioBus::Voice,
+            level: 0.5,
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(20),
+        });
+        let mut triggered = [false; BUS_COUNT];
+        triggered[AudioBus::Voice.index()] = true;
+
+        // 10ms at 1kHz is 10 frames to reach the ducked level.
+        let gains: Vec<f32> = (0..12)
+            .map(|_| bus.next_duck_gain(&triggered, 1_000))
+            .collect();
+        assert!((gains[0] - 0.95).abs() < 1e-5);
+        assert!((gains[9] - 0.5).abs() < 1e-5);
+        assert_eq!(gains[11], 0.5);
+
+        let released = [false; BUS_COUNT];
+        for _ in 0..10 {
+            bus.next_duck_gain(&released, 1_000);
+        }
+        assert!((bus.duck_gain - 0.75).abs() < 1e-4);
+        for _ in 0..10 {
+            bus.next_duck_gain(&released, 1_000);
+        }
+        assert!((bus.duck_gain - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn bus_fade_reaches_target_volume() {
+        let mut bus = BusState::new();
+        bus.ramp = Some(FadeState::new(1.0, 0.0, 4, false));
+        let gains: Vec<f32> = (0..5).map(|_| bus.next_gain()).collect();
+        assert_eq!(gains, [1.0, 0.75, 0.5, 0.25, 0.0]);
+        assert!(bus.ramp.is_none());
+        assert_eq!(bus.target_volume(), 0.0);
+    }
+
+    #[test]
+    fn seek_moves_playhead_and_reports_elapsed_time() {
+        let mut sound = PlayingSound::new(1, Arc::new(vec![0.5; 1000]), 100);
+        sound.seek(Duration::from_secs(4));
+        assert_eq!(sound.elapsed(), Duration::from_secs(4));
+        sound.next_sample(100);
+        assert_eq!(sound.elapsed(), Duration::from_millis(4010));
+
+        sound.seek(Duration::from_secs(10));
+        assert!(sound.finished);
+    }
+
+    #[test]
+    fn seeking_back_cancels_the_end_fade() {
+        let mut sound = PlayingSound::new(1, Arc::new(vec![0.5; 100]), 100);
+        sound.fade_out_on_end = Some(FadeOnEnd {
+            frames: 10,
+            started: false,
+        });
+        sound.seek(Duration::from_millis(950));
+        sound.next_sample(100);
+        sound.next_sample(100);
+        assert!(sound.fade_gain < 1.0);
+
+        sound.seek(Duration::ZERO);
+        assert_eq!(sound.next_sample(100), 0.5);
+    }
+
+    #[test]
+    fn listener_attenuates_and_pans_by_position() {
+        let listener = AudioListener {
+            x: Pt(100.0),
+            y: Pt(100.0),
+            min_distance: Pt(10.0),
+            max_distance: Pt(110.0),
+        };
+
+        assert_eq!(listener.stereo_gains(100.0, 105.0), [1.0, 1.0]);
+        assert_eq!(listener.stereo_gains(100.0, 300.0), [0.0, 0.0]);
+
+        let [left, right] = listener.stereo_gains(160.0, 100.0);
+        assert!((right - 0.5).abs() < 1e-6);
+        assert!(left < right);
+
+        let [left, right] = listener.stereo_gains(40.0, 100.0);
+        assert!(left > right);
+    }
+
+    #[test]
+    fn stereo_output_keeps_regular_sounds_centered() {
+        let mut handler = MixerHandler::new(100, 2);
+        handler
+            .sounds
+            .push(PlayingSound::new(1, Arc::new(vec![0.5; 10]), 100));
+        let mut sound = PlayingSound::new(2, Arc::new(vec![0.25; 10]), 100);
+        sound.emitter = Some((500.0, 0.0));
+        handler.sounds.push(sound);
+
+        let expected = handler.listener.stereo_gains(500.0, 0.0);
+        let mut output = [0.0f32; 2];
+        handler.process(&mut output);
+        assert_eq!(output[0], 0.5 + 0.25 * expected[0]);
+        assert_eq!(output[1], 0.5 + 0.25 * expected[1]);
+        assert!(output[1] > output[0]);
+    }
+
+    fn test_wav_bytes() -> Vec<u8> {
+        let sample_rate = 8_000u32;
+        let bits_per_sample = 16u16;
+        let channels = 1u16;
+        let samples: [i16; 4] = [0, 8_000, -8_000, 0];
+        let data_len = (samples.len() * std::mem::size_of::<i16>()) as u32;
+        let byte_rate = sample_rate * channels as u32 * bits_per_sample as u32 / 8;
+        let block_align = channels * bits_per_sample / 8;
+        let riff_len = 36 + data_len;
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&riff_len.to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&byte_rate.to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits_per_sample.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+}
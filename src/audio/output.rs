@@ -0,0 +1,274 @@
+//! The cpal output stream, its device, and rebuilding it when the device goes away.
+
+use super::{AudioRegistrationQueue, MixerHandler, promote_pending_registrations_locked};
+use anyhow::{Context as _, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+/// How often to check whether the system default device changed.
+const DEFAULT_DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// An audio output device reported by the platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioDevice {
+    /// Stable identifier to pass to [`set_audio_output_device`](crate::set_audio_output_device).
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Whether this is the system default output.
+    pub is_default: bool,
+}
+
+pub(crate) struct OutputStream {
+    /// Plays for as long as it is alive.
+    stream: cpal::Stream,
+    /// Id of the device the stream plays on.
+    device_id: Option<String>,
+    /// Device chosen by the application; `None` follows the system default.
+    requested: Option<String>,
+    /// Set from the stream's error callback when the device disappeared.
+    lost: Arc<AtomicBool>,
+    last_default_check: Instant,
+    /// Paused while the app is in the background.
+    suspended: bool,
+}
+
+impl OutputStream {
+    /// Opens `requested`, or the system default device when `None`.
+    pub(crate) fn open(
+        requested: Option<String>,
+        handler: &Arc<Mutex<MixerHandler>>,
+        registration_queue: &AudioRegistrationQueue,
+    ) -> Result<Self> {
+        let host = cpal::default_host();
+        let device = match requested.as_deref() {
+            Some(id) => find_output_device(&host, id)
+                .with_context(|| format!("audio output device {id:?} not found"))?,
+            None => host
+                .default_output_device()
+                .context("no output device available")?,
+        };
+        let lost = Arc::new(AtomicBool::new(false));
+        let stream = build_stream(&device, handler, registration_queue, &lost)?;
+        Ok(Self {
+            stream,
+            device_id: device.id().ok().map(|id| id.to_string()),
+            requested,
+            lost,
+            last_default_check: Instant::now(),
+            suspended: false,
+        })
+    }
+
+    pub(crate) fn device_id(&self) -> Option<&str> {
+        self.device_id.as_deref()
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) fn play(&self) {
+        let _ = self.stream.play();
+    }
+
+    pub(crate) fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Stops pulling audio from the mixer so the audio thread idles in the background.
+    pub(crate) fn suspend(&mut self) {
+        self.suspended = true;
+        if let Err(e) = self.stream.pause() {
+            crate::diagnostics::error!("audio", "failed to pause output stream: {e}");
+        }
+    }
+
+    /// Restarts a suspended stream, or schedules a rebuild if the platform invalidated it.
+    pub(crate) fn resume(&mut self) {
+        if !self.suspended {
+            return;
+        }
+        self.suspended = false;
+        if let Err(e) = self.stream.play() {
+            crate::diagnostics::error!("audio", "failed to resume output stream: {e}");
+            self.lost.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns true when the stream should be rebuilt: its device disappeared, a requested
+    /// device came back after a fallback, or the system default changed.
+    pub(crate) fn needs_rebuild(&mut self) -> bool {
+        if self.suspended {
+            return false;
+        }
+        if self.lost.load(Ordering::SeqCst) {
+            return true;
+        }
+        if self.last_default_check.elapsed() < DEFAULT_DEVICE_POLL_INTERVAL {
+            return false;
+        }
+        self.last_default_check = Instant::now();
+        if self.device_id.is_none() {
+            // The last reopen failed; try again.
+            return true;
+        }
+        let host = cpal::default_host();
+        if let Some(requested) = self.requested.as_deref() {
+            return self.device_id.as_deref() != Some(requested)
+                && find_output_device(&host, requested).is_some();
+        }
+        let default_id = host
+            .default_output_device()
+            .and_then(|device| device.id().ok())
+            .map(|id| id.to_string());
+        default_id.is_some() && default_id != self.device_id
+    }
+
+    /// Reopens the stream, falling back to the default device if the requested one is gone.
+    pub(crate) fn rebuild(
+        &mut self,
+        handler: &Arc<Mutex<MixerHandler>>,
+        registration_queue: &AudioRegistrationQueue,
+    ) {
+        let reopened =
+            Self::open(self.requested.clone(), handler, registration_queue).or_else(|e| {
+                if self.requested.is_none() {
+                    return Err(e);
+                }
+                crate::diagnostics::warn!("audio", "{e:#}; falling back to the default device");
+                Self::open(None, handler, registration_queue).map(|mut output| {
+                    output.requested = self.requested.clone();
+                    output
+                })
+            });
+        match reopened {
+            Ok(output) => {
+                crate::diagnostics::info!(
+                    "audio",
+                    "output stream reopened on {}",
+                    output.device_id().unwrap_or("unknown device")
+                );
+                *self = output;
+            }
+            Err(e) => {
+                // Retried on the next device poll.
+                crate::diagnostics::error!("audio", "failed to reopen output stream: {e:#}");
+                self.lost.store(false, Ordering::SeqCst);
+                self.device_id = None;
+                self.last_default_check = Instant::now();
+            }
+        }
+    }
+}
+
+fn build_stream(
+    device: &cpal::Device,
+    handler: &Arc<Mutex<MixerHandler>>,
+    registration_queue: &AudioRegistrationQueue,
+    lost: &Arc<AtomicBool>,
+) -> Result<cpal::Stream> {
+    let config = device.default_output_config()?;
+    if config.sample_format() != cpal::SampleFormat::F32 {
+        return Err(anyhow::anyhow!("Unsupported sample format"));
+    }
+    let channels = config.channels().max(1) as usize;
+    let missed_frames = match handler.lock() {
+        Ok(mut h) => {
+            h.set_output_format(config.sample_rate(), config.channels());
+            h.missed_frames()
+        }
+        Err(_) => Arc::new(AtomicU64::new(0)),
+    };
+
+    let registration_queue = Arc::clone(registration_queue);
+    let handler = Arc::clone(handler);
+    let lost = Arc::clone(lost);
+    let stream = device.build_output_stream(
+        &config.into(),
+        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            let _span = crate::diagnostics::span!("audio", "callback");
+            // Process registrations
+            if let Ok(mut queue) = registration_queue.try_lock()
+                && !queue.is_empty()
+                && let Ok(mut h) = handler.try_lock()
+            {
+                promote_pending_registrations_locked(&mut h, &mut queue);
+            }
+
+            match handler.try_lock() {
+                Ok(mut h) => h.process(data),
+                Err(_) => {
+                    // The game thread holds the mixer: play silence rather than stale
+                    // samples, and keep the mixer clock in step with the device.
+                    data.fill(0.0);
+                    missed_frames.fetch_add((data.len() / channels) as u64, Ordering::Relaxed);
+                }
+            }
+        },
+        move |err| {
+            crate::diagnostics::error!("audio", "stream error: {}", err);
+            if matches!(
+                err,
+                cpal::StreamError::DeviceNotAvailable | cpal::StreamError::StreamInvalidated
+            ) {
+                lost.store(true, Ordering::SeqCst);
+                crate::user_events::wake();
+            }
+        },
+        None,
+    )?;
+
+    // On WASM, stream.play() may fail due to browser autoplay policy.
+    // The AudioContext will be resumed automatically after user interaction.
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        if let Err(e) = stream.play() {
+            web_sys::console::warn_1(
+                &format!("[spot][audio] play deferred (autoplay policy): {e:?}").into(),
+            );
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    stream.play()?;
+
+    Ok(stream)
+}
+
+fn find_output_device(host: &cpal::Host, id: &str) -> Option<cpal::Device> {
+    host.output_devices().ok()?.find(|device| {
+        device
+            .id()
+            .is_ok_and(|device_id| device_id.to_string() == id)
+    })
+}
+
+/// Lists the output devices of the default host.
+pub(crate) fn output_devices() -> Vec<AudioDevice> {
+    let host = cpal::default_host();
+    let default_id = host
+        .default_output_device()
+        .and_then(|device| device.id().ok());
+    let Ok(devices) = host.output_devices() else {
+        return Vec::new();
+    };
+    devices
+        .filter_map(|device| {
+            let id = device.id().ok()?;
+            let name = device
+                .description()
+                .map(|description| description.name().to_string())
+                .unwrap_or_else(|_| id.to_string());
+            Some(AudioDevice {
+                is_default: default_id.as_ref() == Some(&id),
+                id: id.to_string(),
+                name,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,478 @@
+//! Bitmap fonts in the AngelCode BMFont format.
+//!
+//! The text variant of the `.fnt` descriptor is read, as written by BMFont, Hiero,
+//! msdf-bmfont and most other tools. Glyphs are regions of the page images, drawn at
+//! whole multiples of their pixels when the text's font size is a multiple of the font's
+//! own size. Glyphs are tinted by the text color like outline fonts, so pages drawn in
+//! white take any color, while colored pages keep theirs with the default white text.
+
+use crate::context::ResourceRegistry;
+use crate::image::Bounds;
+use crate::text::{CachedGlyph, LineMetrics, Text, TextLayout, line_width};
+use crate::{Context, Image, Pt};
+use anyhow::{Context as _, anyhow, bail};
+use std::collections::HashMap;
+
+/// One glyph of a bitmap font, in the font's pixels.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BitmapGlyph {
+    /// The glyph's region of its page, `None` for glyphs without pixels like spaces.
+    pub(crate) image: Option<Image>,
+    /// Offset of the region from the caret at the top of the line.
+    pub(crate) offset: [f32; 2],
+    pub(crate) size: [f32; 2],
+    pub(crate) advance: f32,
+}
+
+/// A registered bitmap font, laid out by the same text drawing path as outline fonts.
+#[derive(Debug, Clone)]
+pub(crate) struct BitmapFont {
+    /// Size the glyphs were drawn at; a text of this font size draws them 1:1.
+    pub(crate) size: f32,
+    pub(crate) line_height: f32,
+    /// Distance from the top of a line to its baseline.
+    pub(crate) base: f32,
+    pub(crate) glyphs: HashMap<char, BitmapGlyph>,
+    pub(crate) kernings: HashMap<(char, char), f32>,
+}
+
+/// The font's metrics at the size of one text.
+struct ScaledBitmapFont<'a> {
+    font: &'a BitmapFont,
+    scale: f32,
+}
+
+impl LineMetrics for ScaledBitmapFont<'_> {
+    fn advance(&self, ch: char) -> f32 {
+        self.font
+            .glyphs
+            .get(&ch)
+            .map_or(0.0, |glyph| glyph.advance * self.scale)
+    }
+
+    fn kern(&self, left: char, right: char) -> f32 {
+        self.font
+            .kernings
+            .get(&(left, right))
+            .map_or(0.0, |amount| amount * self.scale)
+    }
+}
+
+/// A glyph placed by [`BitmapFont::place`], in the font's pixels.
+struct PlacedGlyph {
+    image: Image,
+    pos: [f32; 2],
+    size: [f32; 2],
+}
+
+impl BitmapFont {
+    fn scaled(&self, text: &Text) -> ScaledBitmapFont<'_> {
+        ScaledBitmapFont {
+            font: self,
+            scale: text.font_size.as_f32().max(1.0) / self.size,
+        }
+    }
+
+    /// Positions the glyphs of `text` with the top of its highest glyph at 0. Returns the
+    /// glyphs and the top and bottom of their ink before that shift.
+    fn place(&self, text: &Text) -> (Vec<PlacedGlyph>, f32, f32) {
+        let metrics = ScaledBitmapFont {
+            font: self,
+            scale: 1.0,
+        };
+        // Wrapping measures in the font's pixels, so the width limit has to be as well.
+        let unscaled = Text {
+            max_width: text
+                .max_width
+                .map(|width| Pt(width.0 / self.scaled(text).scale)),
+            ..text.clone_for_draw()
+        };
+
+        let mut placed = Vec::new();
+        let mut min_top = f32::INFINITY;
+        let mut max_bottom = f32::NEG_INFINITY;
+        for (index, line) in unscaled.wrapped_lines(&metrics).iter().enumerate() {
+            let line_top = index as f32 * self.line_height;
+            let mut caret = 0.0;
+            let mut prev = None;
+            for ch in line.chars() {
+                if let Some(prev) = prev {
+                    caret += metrics.kern(prev, ch);
+                }
+                prev = Some(ch);
+                let Some(glyph) = self.glyphs.get(&ch) else {
+                    continue;
+                };
+                if let Some(image) = glyph.image {
+                    let top = line_top + glyph.offset[1];
+                    min_top = min_top.min(top);
+                    max_bottom = max_bottom.max(top + glyph.size[1]);
+                    placed.push(PlacedGlyph {
+                        image,
+                        pos: [caret + glyph.offset[0], top],
+                        size: glyph.size,
+                    });
+                }
+                caret += glyph.advance;
+            }
+        }
+
+        if placed.is_empty() {
+            return (placed, 0.0, 0.0);
+        }
+        for glyph in &mut placed {
+            glyph.pos[1] -= min_top;
+        }
+        (placed, min_top, max_bottom)
+    }
+
+    /// Lays out `text` for drawing at `image_scale`, like the outline font path.
+    pub(crate) fn layout(
+        &self,
+        text: &Text,
+        registry: &ResourceRegistry,
+        image_scale: [f32; 2],
+    ) -> TextLayout {
+        let scale = self.scaled(text).scale;
+        let (placed, min_top, _) = self.place(text);
+        let glyphs = placed
+            .into_iter()
+            .filter_map(|glyph| {
+                let image_entry = registry.images.get(glyph.image.id() as usize)?.as_ref()?;
+                let texture_entry = registry
+                    .textures
+                    .get(image_entry.texture_id as usize)?
+                    .as_ref()?;
+                Some(CachedGlyph {
+                    instance: crate::image_raw::InstanceData {
+                        pos: [
+                            glyph.pos[0] * scale * image_scale[0],
+                            glyph.pos[1] * scale * image_scale[1],
+                        ],
+                        rotation: 0.0,
+                        size: [
+                            glyph.size[0] * scale * image_scale[0],
+                            glyph.size[1] * scale * image_scale[1],
+                        ],
+                        uv_rect: crate::graphics::image_ops::resolve_image_uv(
+                            image_entry,
+                            texture_entry,
+                        ),
+                        ..Default::default()
+                    },
+                    image_id: glyph.image.id(),
+                })
+            })
+            .collect();
+
+        TextLayout {
+            glyphs,
+            bounds: (0.0, 0.0, (self.base - min_top) * scale),
+            scale: image_scale,
+            revision: text.layout_revision,
+            font_revision: registry.font_revision,
+            complete: true,
+        }
+    }
+
+    /// Returns (width, height, y_offset) of `text` in logical pixels, with the same
+    /// meaning as [`Text::measure_with_y_offset`].
+    pub(crate) fn measure(&self, text: &Text) -> (f32, f32, f32) {
+        let metrics = self.scaled(text);
+        let width = text
+            .wrapped_lines(&metrics)
+            .iter()
+            .map(|line| line_width(line, &metrics))
+            .fold(0.0, f32::max);
+        let (_, min_top, max_bottom) = self.place(text);
+        (
+            width,
+            (max_bottom - min_top) * metrics.scale,
+            (self.base - min_top) * metrics.scale,
+        )
+    }
+}
+
+/// The contents of a `.fnt` file before its pages are loaded.
+#[derive(Debug, Default, PartialEq)]
+struct Descriptor {
+    size: f32,
+    line_height: f32,
+    base: f32,
+    pages: Vec<(u32, String)>,
+    chars: Vec<CharDescriptor>,
+    kernings: Vec<(char, char, f32)>,
+}
+
+#[derive(Debug, PartialEq)]
+struct CharDescriptor {
+    ch: char,
+    page: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    offset: [f32; 2],
+    advance: f32,
+}
+
+/// Splits `tag key=value key="quoted value" ...` into the tag and its attributes.
+fn attributes(line: &str) -> (&str, HashMap<&str, &str>) {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let mut attributes = HashMap::new();
+    loop {
+        rest = rest.trim_start();
+        let Some((key, after)) = rest.split_once('=') else {
+            break;
+        };
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(char::is_whitespace).unwrap_or((after, "")),
+        };
+        attributes.insert(key.trim(), value);
+        rest = after;
+    }
+    (tag, attributes)
+}
+
+fn parse_descriptor(source: &str) -> anyhow::Result<Descriptor> {
+    let mut descriptor = Descriptor::default();
+    let mut saw_common = false;
+    for (index, line) in source.lines().enumerate() {
+        let (tag, attributes) = attributes(line);
+        let number = |key: &str| -> anyhow::Result<i64> {
+            let value = attributes
+                .get(key)
+                .ok_or_else(|| anyhow!("line {}: {tag} is missing {key:?}", index + 1))?;
+            value
+                .parse()
+                .map_err(|_| anyhow!("line {}: invalid {key:?} {value:?}", index + 1))
+        };
+        let character = |key: &str| -> anyhow::Result<Option<char>> {
+            Ok(u32::try_from(number(key)?).ok().and_then(char::from_u32))
+        };
+        match tag {
+            "info" => descriptor.size = number("size")?.unsigned_abs() as f32,
+            "common" => {
+                saw_common = true;
+                descriptor.line_height = number("lineHeight")? as f32;
+                descriptor.base = number("base")? as f32;
+            }
+            "page" => {
+                let file = attributes
+                    .get("file")
+                    .ok_or_else(|| anyhow!("line {}: page is missing \"file\"", index + 1))?;
+                descriptor
+                    .pages
+                    .push((number("id")? as u32, file.to_string()));
+            }
+            "char" => {
+                // Tools write the glyph for missing characters as id -1, which has no
+                // character to map to.
+                let Some(ch) = character("id")? else {
+                    continue;
+                };
+                descriptor.chars.push(CharDescriptor {
+                    ch,
+                    page: number("page")? as u32,
+                    x: number("x")? as u32,
+                    y: number("y")? as u32,
+                    width: number("width")? as u32,
+                    height: number("height")? as u32,
+                    offset: [number("xoffset")? as f32, number("yoffset")? as f32],
+                    advance: number("xadvance")? as f32,
+                });
+            }
+            "kerning" => {
+                if let (Some(first), Some(second)) = (character("first")?, character("second")?) {
+                    descriptor
+                        .kernings
+                        .push((first, second, number("amount")? as f32));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !saw_common {
+        bail!("not a text BMFont descriptor: the \"common\" line is missing");
+    }
+    if descriptor.size == 0.0 {
+        descriptor.size = descriptor.line_height.max(1.0);
+    }
+    Ok(descriptor)
+}
+
+/// Registers a bitmap font from the text of its BMFont `.fnt` descriptor and returns a
+/// font ID for [`Text`], like [`register_font`](crate::register_font).
+///
+/// `load_page` is called with each page's file name as written in the descriptor,
+/// usually relative to it, and returns the page image, e.g. through
+/// [`from_image`](crate::utils::image::from_image) with the `utils` feature.
+///
+/// The font's size from the descriptor is the font size that draws glyphs 1:1; other
+/// sizes scale them. For crisp pixel art, use a multiple of it and whole-pixel positions.
+/// [`unregister_font`](crate::unregister_font) releases the glyphs but not the pages.
+///
+/// ```rust,no_run
+/// # use spottedcat::{Context, Image, Pt, Text};
+/// # fn example(ctx: &mut Context, fnt: &str, page: Image) -> anyhow::Result<()> {
+/// let font_id = spottedcat::register_bitmap_font(ctx, fnt, |_ctx, _file| Ok(page))?;
+/// let text = Text::new("Hello", font_id).with_font_size(Pt::from(16.0));
+/// # Ok(())
+/// # }
+/// ```
+pub fn register_bitmap_font(
+    ctx: &mut Context,
+    descriptor: &str,
+    mut load_page: impl FnMut(&mut Context, &str) -> anyhow::Result<Image>,
+) -> anyhow::Result<u32> {
+    let descriptor = parse_descriptor(descriptor)?;
+    let mut pages = HashMap::new();
+    for (id, file) in &descriptor.pages {
+        let page =
+            load_page(ctx, file).with_context(|| format!("failed to load font page {file:?}"))?;
+        pages.insert(*id, page);
+    }
+
+    let mut glyphs = HashMap::new();
+    for glyph in &descriptor.chars {
+        let image = if glyph.width == 0 || glyph.height == 0 {
+            None
+        } else {
+            let page = *pages
+                .get(&glyph.page)
+                .ok_or_else(|| anyhow!("glyph {:?} is on missing page {}", glyph.ch, glyph.page))?;
+            // Regions are in the page's pixels, sub-images in its logical size.
+            let to_logical = page.width().0 / page.pixel_bounds().width.max(1) as f32;
+            let bounds = Bounds::new(
+                Pt(glyph.x as f32 * to_logical),
+                Pt(glyph.y as f32 * to_logical),
+                Pt(glyph.width as f32 * to_logical),
+                Pt(glyph.height as f32 * to_logical),
+            );
+            Some(
+                Image::sub_image(ctx, page, bounds)
+                    .with_context(|| format!("glyph {:?} lies outside its page", glyph.ch))?,
+            )
+        };
+        glyphs.insert(
+            glyph.ch,
+            BitmapGlyph {
+                image,
+                offset: glyph.offset,
+                size: [glyph.width as f32, glyph.height as f32],
+                advance: glyph.advance,
+            },
+        );
+    }
+
+    let font = BitmapFont {
+        size: descriptor.size,
+        line_height: descriptor.line_height,
+        base: descriptor.base,
+        glyphs,
+        kernings: descriptor
+            .kernings
+            .into_iter()
+            .map(|(first, second, amount)| ((first, second), amount))
+            .collect(),
+    };
+    let id = insert_font(ctx, font);
+    ctx.track_scene_resource(crate::SceneResource::Font(id));
+    Ok(id)
+}
+
+/// Registers a font built in code and returns its font ID.
+pub(crate) fn insert_font(ctx: &mut Context, font: BitmapFont) -> u32 {
+    let id = ctx.registry.next_font_id;
+    ctx.registry.next_font_id += 1;
+    ctx.registry.bitmap_fonts.insert(id, font);
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTOR: &str = r#"info face="Tiny Pixel" size=-8 bold=0 italic=0 padding=0,0,0,0 spacing=1,1
+common lineHeight=10 base=8 scaleW=16 scaleH=8 pages=1 packed=0
+page id=0 file="tiny pixel_0.png"
+chars count=4
+char id=-1   x=0 y=0 width=3 height=3 xoffset=0 yoffset=0 xadvance=4 page=0 chnl=15
+char id=32   x=0 y=0 width=0 height=0 xoffset=0 yoffset=0 xadvance=3 page=0 chnl=15
+char id=65   x=0 y=0 width=5 height=6 xoffset=0 yoffset=2 xadvance=6 page=0 chnl=15
+char id=103  x=6 y=0 width=4 height=8 xoffset=1 yoffset=4 xadvance=5 page=0 chnl=15
+kernings count=1
+kerning first=65 second=103 amount=-1
+"#;
+
+    #[test]
+    fn text_descriptors_are_parsed() {
+        let descriptor = parse_descriptor(DESCRIPTOR).unwrap();
+        assert_eq!(
+            (descriptor.size, descriptor.line_height, descriptor.base),
+            (8.0, 10.0, 8.0)
+        );
+        assert_eq!(descriptor.pages, [(0, "tiny pixel_0.png".to_string())]);
+        assert_eq!(
+            descriptor.chars.iter().map(|c| c.ch).collect::<String>(),
+            " Ag"
+        );
+        assert_eq!(
+            descriptor.chars[2],
+            CharDescriptor {
+                ch: 'g',
+                page: 0,
+                x: 6,
+                y: 0,
+                width: 4,
+                height: 8,
+                offset: [1.0, 4.0],
+                advance: 5.0,
+            }
+        );
+        assert_eq!(descriptor.kernings, [('A', 'g', -1.0)]);
+        assert!(parse_descriptor("not a font").is_err());
+    }
+
+    #[test]
+    fn glyphs_are_laid_out_from_the_descriptor_metrics() {
+        let mut ctx = Context::new();
+        let page = Image::new(&mut ctx, Pt(16.0), Pt(8.0), &[255; 16 * 8 * 4]).unwrap();
+        let mut requested = Vec::new();
+        let font_id = register_bitmap_font(&mut ctx, DESCRIPTOR, |_, file| {
+            requested.push(file.to_string());
+            Ok(page)
+        })
+        .unwrap();
+        assert_eq!(requested, ["tiny pixel_0.png"]);
+
+        // Twice the font's size doubles every metric; kerning pulls the g one pixel in.
+        let text = Text::new("Ag A", font_id).with_font_size(Pt(16.0));
+        assert_eq!(text.measure_with_y_offset(&ctx), (38.0, 20.0, 12.0));
+
+        let font = &ctx.registry.bitmap_fonts[&font_id];
+        let layout = font.layout(&text, &ctx.registry, [1.0, 1.0]);
+        let glyphs: Vec<_> = layout
+            .glyphs
+            .iter()
+            .map(|glyph| (glyph.instance.pos, glyph.instance.size))
+            .collect();
+        assert_eq!(
+            glyphs,
+            [
+                ([0.0, 0.0], [10.0, 12.0]),
+                ([12.0, 4.0], [8.0, 16.0]),
+                ([26.0, 0.0], [10.0, 12.0]),
+            ]
+        );
+
+        let wrapped = text.clone().with_max_width(Pt(24.0));
+        assert_eq!(wrapped.measure(&ctx), (20.0, 32.0));
+
+        crate::unregister_font(&mut ctx, font_id);
+        assert!(ctx.registry.images[layout.glyphs[0].image_id as usize].is_none());
+        assert!(ctx.registry.images[page.id() as usize].is_some());
+    }
+}
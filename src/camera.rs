@@ -0,0 +1,314 @@
+use crate::rand::Rng;
+use crate::{DrawOption, Pt, Rect};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Effect {
+    strength: f32,
+    duration: Duration,
+    remaining: Duration,
+}
+
+impl Effect {
+    /// Current strength, fading linearly to zero over the duration.
+    fn current(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 0.0;
+        }
+        self.strength * self.remaining.as_secs_f32() / self.duration.as_secs_f32()
+    }
+}
+
+/// A 2D view onto the world, with target following, bounds and screen shake.
+///
+/// `position` is the world point shown at the center of a `viewport` sized view; `zoom`
+/// scales the world around it. Convert draws from world to screen with
+/// [`to_screen`](Self::to_screen), and call [`update`](Self::update) once per `update` to
+/// advance following and effects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera2D {
+    pub position: [Pt; 2],
+    pub zoom: f32,
+    /// Size of the view on screen, usually the window size.
+    pub viewport: [Pt; 2],
+    /// Screen-space area around the center the followed target can move in without
+    /// moving the camera.
+    pub deadzone: [Pt; 2],
+    /// How quickly the camera catches up with its target, as a rate per second; `0.0`
+    /// snaps to it.
+    pub smoothing: f32,
+    /// World area the view stays inside; views larger than it are centered on it.
+    pub bounds: Option<Rect>,
+    target: Option<[Pt; 2]>,
+    shake: Option<Effect>,
+    shake_offset: [f32; 2],
+    punch: Option<Effect>,
+    rng: Rng,
+}
+
+impl Camera2D {
+    pub fn new(viewport: [Pt; 2]) -> Self {
+        Self {
+            position: [viewport[0] / 2.0, viewport[1] / 2.0],
+            zoom: 1.0,
+            viewport,
+            deadzone: [Pt(0.0), Pt(0.0)],
+            smoothing: 0.0,
+            bounds: None,
+            target: None,
+            shake: None,
+            shake_offset: [0.0, 0.0],
+            punch: None,
+            rng: Rng::default(),
+        }
+    }
+
+    pub fn with_position(mut self, position: [Pt; 2]) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_deadzone(mut self, deadzone: [Pt; 2]) -> Self {
+        self.deadzone = deadzone;
+        self
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Seeds the random offsets used by [`shake`](Self::shake).
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    /// Keeps `target` in view from the next [`update`](Self::update) on; call it every
+    /// update with the target's new position.
+    pub fn follow(&mut self, target: [Pt; 2]) {
+        self.target = Some(target);
+    }
+
+    pub fn stop_following(&mut self) {
+        self.target = None;
+    }
+
+    /// Shakes the view by up to `amplitude` screen points, fading out over `duration`.
+    /// A stronger shake replaces a weaker running one.
+    pub fn shake(&mut self, amplitude: Pt, duration: Duration) {
+        let amplitude = amplitude.as_f32();
+        if self.shake.is_some_and(|shake| shake.current() > amplitude) {
+            return;
+        }
+        self.shake = Some(Effect {
+            strength: amplitude,
+            duration,
+            remaining: duration,
+        });
+    }
+
+    /// Zooms in by `amount` (e.g. `0.1` for 10%) at once and eases back over `duration`.
+    pub fn zoom_punch(&mut self, amount: f32, duration: Duration) {
+        self.punch = Some(Effect {
+            strength: amount,
+            duration,
+            remaining: duration,
+        });
+    }
+
+    /// Zoom including any running [`zoom_punch`](Self::zoom_punch).
+    pub fn current_zoom(&self) -> f32 {
+        self.zoom * (1.0 + self.punch.map_or(0.0, |punch| punch.current()))
+    }
+
+    /// Moves towards the followed target, keeps the view inside `bounds` and advances
+    /// shake and zoom punch.
+    pub fn update(&mut self, dt: Duration) {
+        for effect in [&mut self.shake, &mut self.punch] {
+            if let Some(running) = effect {
+                running.remaining = running.remaining.saturating_sub(dt);
+                if running.remaining.is_zero() {
+                    *effect = None;
+                }
+            }
+        }
+        self.shake_offset = match self.shake {
+            Some(shake) => {
+                let amplitude = shake.current();
+                [
+                    self.rng.gen_range(-amplitude..=amplitude),
+                    self.rng.gen_range(-amplitude..=amplitude),
+                ]
+            }
+            None => [0.0, 0.0],
+        };
+
+        if let Some(target) = self.target {
+            let zoom = self.current_zoom();
+            let blend = if self.smoothing > 0.0 {
+                1.0 - (-self.smoothing * dt.as_secs_f32()).exp()
+            } else {
+                1.0
+            };
+            let axes = self.position.iter_mut().zip(target).zip(self.deadzone);
+            for ((position, target), deadzone) in axes {
+                let reach = deadzone.as_f32() / zoom / 2.0;
+                let offset = (target - *position).as_f32();
+                let outside = offset - offset.clamp(-reach, reach);
+                *position += Pt(outside * blend);
+            }
+        }
+        self.clamp_to_bounds();
+    }
+
+    /// Moves `position` so the view lies inside `bounds`.
+    pub fn clamp_to_bounds(&mut self) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+        let zoom = self.current_zoom();
+        let ranges = [
+            (bounds.left(), bounds.right(), self.viewport[0]),
+            (bounds.top(), bounds.bottom(), self.viewport[1]),
+        ];
+        for (axis, (min, max, viewport)) in ranges.into_iter().enumerate() {
+            let half = viewport.as_f32() / zoom / 2.0;
+            self.position[axis] = if (max - min).as_f32() <= half * 2.0 {
+                (min + max) / 2.0
+            } else {
+                self.position[axis].clamp(min + Pt(half), max - Pt(half))
+            };
+        }
+    }
+
+    /// The world point at the center of the screen, including shake.
+    pub fn center(&self) -> [Pt; 2] {
+        let zoom = self.current_zoom();
+        [
+            self.position[0] + Pt(self.shake_offset[0] / zoom),
+            self.position[1] + Pt(self.shake_offset[1] / zoom),
+        ]
+    }
+
+    /// The world area on screen.
+    pub fn view_rect(&self) -> Rect {
+        let zoom = self.current_zoom();
+        Rect::from_center(
+            self.center(),
+            self.viewport[0] / zoom,
+            self.viewport[1] / zoom,
+        )
+    }
+
+    pub fn world_to_screen(&self, point: [Pt; 2]) -> [Pt; 2] {
+        let zoom = self.current_zoom();
+        let center = self.center();
+        [
+            (point[0] - center[0]) * zoom + self.viewport[0] / 2.0,
+            (point[1] - center[1]) * zoom + self.viewport[1] / 2.0,
+        ]
+    }
+
+    /// Where a screen point, e.g. the mouse, is in the world.
+    pub fn screen_to_world(&self, point: [Pt; 2]) -> [Pt; 2] {
+        let zoom = self.current_zoom();
+        let center = self.center();
+        [
+            (point[0] - self.viewport[0] / 2.0) / zoom + center[0],
+            (point[1] - self.viewport[1] / 2.0) / zoom + center[1],
+        ]
+    }
+
+    /// Turns options for drawing at a world position into options for drawing on screen.
+    pub fn to_screen(&self, options: DrawOption) -> DrawOption {
+        let zoom = self.current_zoom();
+        let [sx, sy] = options.scale();
+        options
+            .with_position(self.world_to_screen(options.position()))
+            .with_scale([sx * zoom, sy * zoom])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: f32, y: f32) -> [Pt; 2] {
+        [Pt(x), Pt(y)]
+    }
+
+    #[test]
+    fn world_and_screen_points_round_trip_through_zoom() {
+        let camera = Camera2D::new(pt(200.0, 100.0))
+            .with_position(pt(50.0, 50.0))
+            .with_zoom(2.0);
+        assert_eq!(camera.world_to_screen(pt(50.0, 50.0)), pt(100.0, 50.0));
+        assert_eq!(camera.world_to_screen(pt(60.0, 40.0)), pt(120.0, 30.0));
+        assert_eq!(camera.screen_to_world(pt(120.0, 30.0)), pt(60.0, 40.0));
+        assert_eq!(
+            camera.view_rect(),
+            Rect::new(Pt(0.0), Pt(25.0), Pt(100.0), Pt(50.0))
+        );
+
+        let options = camera.to_screen(DrawOption::default().with_position(pt(60.0, 40.0)));
+        assert_eq!(options.position(), pt(120.0, 30.0));
+        assert_eq!(options.scale(), [2.0, 2.0]);
+    }
+
+    #[test]
+    fn following_respects_the_deadzone_and_bounds() {
+        let mut camera = Camera2D::new(pt(100.0, 100.0))
+            .with_position(pt(100.0, 100.0))
+            .with_deadzone(pt(20.0, 20.0))
+            .with_bounds(Rect::new(Pt(0.0), Pt(0.0), Pt(300.0), Pt(150.0)));
+
+        camera.follow(pt(105.0, 95.0));
+        camera.update(Duration::from_millis(16));
+        assert_eq!(camera.position, pt(100.0, 100.0));
+
+        camera.follow(pt(150.0, 100.0));
+        camera.update(Duration::from_millis(16));
+        assert_eq!(camera.position, pt(140.0, 100.0));
+
+        camera.follow(pt(290.0, 290.0));
+        camera.update(Duration::from_millis(16));
+        assert_eq!(camera.position, pt(250.0, 100.0));
+
+        let mut smooth = Camera2D::new(pt(100.0, 100.0)).with_smoothing(10.0);
+        smooth.follow(pt(150.0, 50.0));
+        smooth.update(Duration::from_millis(100));
+        let x = smooth.position[0].as_f32();
+        assert!(x > 50.0 && x < 150.0);
+    }
+
+    #[test]
+    fn shake_and_zoom_punch_fade_out() {
+        let mut camera = Camera2D::new(pt(100.0, 100.0)).with_seed(3);
+        camera.shake(Pt(8.0), Duration::from_millis(200));
+        camera.zoom_punch(0.5, Duration::from_millis(200));
+        assert_eq!(camera.current_zoom(), 1.5);
+
+        camera.update(Duration::from_millis(100));
+        assert_eq!(camera.current_zoom(), 1.25);
+        let center = camera.center();
+        assert_ne!(center, camera.position);
+        for (center, position) in center.into_iter().zip(camera.position) {
+            assert!((center - position).as_f32().abs() <= 4.0 / 1.25);
+        }
+
+        camera.update(Duration::from_millis(100));
+        assert_eq!(camera.current_zoom(), 1.0);
+        assert_eq!(camera.center(), camera.position);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{Bounds, Context, DrawOption, Image, Pt, Texture};
+use std::time::Duration;
+
+/// A self-contained part of a scene, such as a HUD, a minimap or an embedded editor.
+///
+/// Sub-spots are developed like small scenes and mounted into a [`Spot`](crate::Spot)
+/// through a [`Child`], which gives them their own drawing area: `draw` receives a target
+/// whose top-left corner is the area's top-left corner and which clips everything
+/// outside it.
+pub trait SubSpot {
+    fn initialize(ctx: &mut Context) -> Self
+    where
+        Self: Sized;
+
+    /// Draws into `target`, which is the size of the area the parent mounted this in.
+    fn draw(&mut self, ctx: &mut Context, target: Image);
+
+    /// Called by the parent through [`Child::update`].
+    ///
+    /// `area` is where the parent last drew this sub-spot, in the parent's coordinates;
+    /// use [`Child::local_point`] to map pointer positions into it.
+    fn update(&mut self, _ctx: &mut Context, _dt: Duration, _area: Bounds) {}
+}
+
+/// A mounted [`SubSpot`] together with the offscreen target it draws into.
+///
+/// ```rust,no_run
+/// use spottedcat::{Bounds, Child, Context, Image, Pt, Spot, SubSpot};
+/// use std::time::Duration;
+///
+/// struct Minimap;
+///
+/// impl SubSpot for Minimap {
+///     fn initialize(_ctx: &mut Context) -> Self {
+///         Self
+///     }
+///
+///     fn draw(&mut self, _ctx: &mut Context, _target: Image) {}
+/// }
+///
+/// struct Level {
+///     minimap: Child<Minimap>,
+/// }
+///
+/// impl Spot for Level {
+///     fn initialize(ctx: &mut Context) -> Self {
+///         Self { minimap: Child::new(ctx) }
+///     }
+///
+///     fn update(&mut self, ctx: &mut Context, dt: Duration) {
+///         self.minimap.update(ctx, dt);
+///     }
+///
+///     fn draw(&mut self, ctx: &mut Context, screen: Image) {
+///         let area = Bounds::new(Pt::from(16.0), Pt::from(16.0), Pt::from(160.0), Pt::from(120.0));
+///         ctx.draw_child(screen, &mut self.minimap, area);
+///     }
+/// }
+/// ```
+pub struct Child<S: SubSpot> {
+    spot: S,
+    area: Option<Bounds>,
+    target: Option<Texture>,
+}
+
+impl<S: SubSpot> Child<S> {
+    pub fn new(ctx: &mut Context) -> Self {
+        Self::from_spot(S::initialize(ctx))
+    }
+
+    /// Mounts an already constructed sub-spot.
+    pub fn from_spot(spot: S) -> Self {
+        Self {
+            spot,
+            area: None,
+            target: None,
+        }
+    }
+
+    pub fn spot(&self) -> &S {
+        &self.spot
+    }
+
+    pub fn spot_mut(&mut self) -> &mut S {
+        &mut self.spot
+    }
+
+    /// The area this child was last drawn in, in the parent's coordinates.
+    pub fn area(&self) -> Option<Bounds> {
+        self.area
+    }
+
+    /// Updates the sub-spot with the area it was last drawn in. Children that have not
+    /// been drawn yet are not updated.
+    pub fn update(&mut self, ctx: &mut Context, dt: Duration) {
+        if let Some(area) = self.area {
+            self.spot.update(ctx, dt, area);
+        }
+    }
+
+    /// Maps a point in the parent's coordinates, such as the mouse position, into the
+    /// child's area. Returns `None` when the point lies outside it.
+    pub fn local_point(&self, x: Pt, y: Pt) -> Option<(Pt, Pt)> {
+        let area = self.area?;
+        let (local_x, local_y) = (x - area.x, y - area.y);
+        let inside = local_x.as_f32() >= 0.0
+            && local_y.as_f32() >= 0.0
+            && local_x.as_f32() < area.width.as_f32()
+            && local_y.as_f32() < area.height.as_f32();
+        inside.then_some((local_x, local_y))
+    }
+
+    /// Destroys the offscreen target, freeing its GPU memory. Drawing the child again
+    /// creates a new one.
+    pub fn release(&mut self, ctx: &mut Context) {
+        if let Some(target) = self.target.take() {
+            target.destroy(ctx);
+        }
+    }
+}
+
+impl<S: SubSpot> crate::Drawable for &mut Child<S> {
+    type Options = Bounds;
+
+    fn draw_to(self, ctx: &mut Context, target: Image, area: Bounds) {
+        if area.width.as_f32() <= 0.0 || area.height.as_f32() <= 0.0 {
+            return;
+        }
+        self.area = Some(area);
+        let child_target =
+            Texture::sized_render_target(ctx, &mut self.target, area.width, area.height);
+        self.spot.draw(ctx, child_target.view());
+        target.draw(
+            ctx,
+            &child_target.view(),
+            DrawOption::default().with_position([area.x, area.y]),
+        );
+    }
+}
+
+impl Context {
+    /// Draws `child` into `area` of `target`, clipped to the area.
+    ///
+    /// Same as `target.draw(ctx, child, area)`.
+    pub fn draw_child<S: SubSpot>(&mut self, target: Image, child: &mut Child<S>, area: Bounds) {
+        target.draw(self, child, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Panel {
+        draws: u32,
+        last_size: (Pt, Pt),
+    }
+
+    impl SubSpot for Panel {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self {
+                draws: 0,
+                last_size: (Pt(0.0), Pt(0.0)),
+            }
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, target: Image) {
+            self.draws += 1;
+            self.last_size = (target.width(), target.height());
+        }
+    }
+
+    #[test]
+    fn child_draws_into_a_target_sized_to_its_area() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(800, 600, 1.0);
+        let screen = crate::window::make_screen_target(&ctx);
+        let mut child = Child::<Panel>::new(&mut ctx);
+        assert_eq!(child.local_point(Pt(10.0), Pt(10.0)), None);
+
+        let area = Bounds::new(Pt(100.0), Pt(50.0), Pt(200.0), Pt(120.0));
+        ctx.draw_child(screen, &mut child, area);
+        let first_target = child.target;
+        ctx.draw_child(screen, &mut child, area);
+        assert_eq!(child.spot().draws, 2);
+        assert_eq!(child.spot().last_size, (Pt(200.0), Pt(120.0)));
+        assert_eq!(child.target, first_target);
+
+        assert_eq!(
+            child.local_point(Pt(110.0), Pt(60.0)),
+            Some((Pt(10.0), Pt(10.0)))
+        );
+        assert_eq!(child.local_point(Pt(300.0), Pt(60.0)), None);
+
+        let larger = Bounds::new(Pt(0.0), Pt(0.0), Pt(320.0), Pt(240.0));
+        screen.draw(&mut ctx, &mut child, larger);
+        assert_ne!(child.target, first_target);
+        assert_eq!(child.spot().last_size, (Pt(320.0), Pt(240.0)));
+        assert!(
+            !first_target.unwrap().is_render_target(&ctx),
+            "resized away"
+        );
+
+        let resized = child.target.unwrap();
+        child.release(&mut ctx);
+        assert!(child.target.is_none());
+        assert!(!resized.is_render_target(&ctx));
+    }
+}
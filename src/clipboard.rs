@@ -0,0 +1,154 @@
+//! Copying text to and pasting it from the system clipboard.
+
+use crate::Context;
+
+/// Copies `text` to the clipboard.
+///
+/// The web uses the async Clipboard API and falls back to `document.execCommand("copy")`
+/// where it is missing or refused, e.g. outside secure contexts; browsers only allow
+/// either in response to a click or key press. Other platforms keep the text in an
+/// app-local clipboard that [`read_clipboard_text`] returns.
+pub fn set_clipboard_text(ctx: &mut Context, text: impl Into<String>) {
+    let text = text.into();
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    web::write_text(&text);
+    ctx.runtime.clipboard = Some(text);
+}
+
+/// Reads text from the clipboard and calls `on_complete` with it on the main thread,
+/// right before the next `update`.
+///
+/// On the web the browser may ask the user for permission first. Where reading is denied
+/// or unsupported, the last text pasted into the page with the paste shortcut or copied
+/// with [`set_clipboard_text`] is returned instead. `None` if there is no text.
+pub fn read_clipboard_text<C>(ctx: &mut Context, on_complete: C)
+where
+    C: FnOnce(&mut Context, Option<String>) + 'static,
+{
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    crate::spawn_future(ctx, web::read_text(), move |ctx, text| {
+        let text = text.or_else(|| ctx.runtime.clipboard.clone());
+        on_complete(ctx, text);
+    });
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    crate::spawn_task(
+        ctx,
+        || (),
+        move |ctx, ()| {
+            let text = ctx.runtime.clipboard.clone();
+            on_complete(ctx, text);
+        },
+    );
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+mod web {
+    use js_sys::{Function, Object, Promise, Reflect};
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    /// `navigator.clipboard`, which only exists in secure contexts.
+    fn clipboard() -> Option<JsValue> {
+        let navigator = web_sys::window()?.navigator();
+        Reflect::get(&navigator, &"clipboard".into())
+            .ok()
+            .filter(|clipboard| !clipboard.is_undefined())
+    }
+
+    fn call(target: &JsValue, method: &str, args: &[&JsValue]) -> Option<JsValue> {
+        let method = Reflect::get(target, &method.into())
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?;
+        let args: js_sys::Array = args.iter().copied().collect();
+        method.apply(target, &args).ok()
+    }
+
+    async fn await_promise(value: JsValue) -> Result<JsValue, JsValue> {
+        JsFuture::from(value.dyn_into::<Promise>()?).await
+    }
+
+    pub(super) fn write_text(text: &str) {
+        let Some(promise) = clipboard().and_then(|c| call(&c, "writeText", &[&text.into()])) else {
+            copy_with_exec_command(text);
+            return;
+        };
+        let text = text.to_string();
+        wasm_bindgen_futures::spawn_local(async move {
+            if await_promise(promise).await.is_err() {
+                copy_with_exec_command(&text);
+            }
+        });
+    }
+
+    /// Copies through a hidden text area, the pre-Clipboard API way.
+    fn copy_with_exec_command(text: &str) {
+        let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+            return;
+        };
+        let (Ok(area), Some(body)) = (document.create_element("textarea"), document.body()) else {
+            return;
+        };
+        let _ = area.set_attribute("style", "position:fixed;left:0;top:0;opacity:0");
+        let _ = area.set_attribute("readonly", "");
+        area.set_text_content(Some(text));
+        if body.append_child(&area).is_err() {
+            return;
+        }
+        call(&area, "select", &[]);
+        let copied = call(&document, "execCommand", &[&"copy".into()]);
+        area.remove();
+        if !copied.is_some_and(|copied| copied.is_truthy()) {
+            web_sys::console::warn_1(&"[spot][wasm] Copying to the clipboard was refused".into());
+        }
+    }
+
+    /// State of a `navigator.permissions` entry, `None` where the browser cannot tell.
+    async fn permission_state(name: &str) -> Option<String> {
+        let navigator = web_sys::window()?.navigator();
+        let permissions = Reflect::get(&navigator, &"permissions".into()).ok()?;
+        let descriptor = Object::new();
+        Reflect::set(&descriptor, &"name".into(), &name.into()).ok()?;
+        let status = await_promise(call(&permissions, "query", &[&descriptor])?)
+            .await
+            .ok()?;
+        Reflect::get(&status, &"state".into()).ok()?.as_string()
+    }
+
+    pub(super) async fn read_text() -> Option<String> {
+        let clipboard = clipboard()?;
+        if permission_state("clipboard-read").await.as_deref() == Some("denied") {
+            return None;
+        }
+        await_promise(call(&clipboard, "readText", &[])?)
+            .await
+            .ok()?
+            .as_string()
+            .filter(|text| !text.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn copied_text_is_pasted_back() {
+        let mut ctx = Context::new();
+        set_clipboard_text(&mut ctx, "spotted");
+        let pasted = Rc::new(RefCell::new(None));
+        let slot = pasted.clone();
+        read_clipboard_text(&mut ctx, move |_ctx, text| *slot.borrow_mut() = text);
+
+        for _ in 0..1000 {
+            crate::tasks::poll(&mut ctx);
+            if pasted.borrow().is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(pasted.borrow().as_deref(), Some("spotted"));
+    }
+}
@@ -11,6 +11,10 @@ use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// Global resources, kept for the lifetime of the context and across scene switches.
+///
+/// One resource per type, handed out as `Rc` clones or borrowed in place while no clone
+/// is alive.
 #[derive(Default)]
 struct ResourceMap {
     inner: HashMap<TypeId, Rc<dyn Any>>,
@@ -32,14 +36,59 @@ pub(crate) struct ContextRuntime {
     pub(crate) input: InputManager,
     pub(crate) scale_factor: f64,
     pub(crate) window_logical_size: (Pt, Pt),
+    /// Insets of the area not covered by notches and system bars: top, bottom, left, right.
+    pub(crate) safe_area: (Pt, Pt, Pt, Pt),
     pub(crate) graphics: Option<Graphics>,
     pub(crate) audio: Option<AudioSystem>,
     pub(crate) delta_time: std::time::Duration,
     pub(crate) total_elapsed: std::time::Duration,
     pub(crate) draw_alpha: f32,
+    pub(crate) draw_stats: crate::DrawStats,
+    pub(crate) render_stats: crate::RenderStats,
+    pub(crate) frame_stats: crate::FrameStats,
+    pub(crate) baked_layers: crate::layers::BakedLayers,
     pub(crate) pending_window_title: Option<String>,
     pub(crate) pending_cursor_visible: Option<bool>,
     pub(crate) pending_fullscreen: Option<bool>,
+    pub(crate) pending_window_level: Option<crate::WindowLevel>,
+    pub(crate) pending_window_size: Option<(Pt, Pt)>,
+    pub(crate) pending_window_position: Option<(Pt, Pt)>,
+    pub(crate) pending_virtual_keyboard: Option<bool>,
+    pub(crate) pending_keep_screen_on: Option<bool>,
+    /// Text last copied by the app or pasted into the page, for platforms whose system
+    /// clipboard cannot be read.
+    pub(crate) clipboard: Option<String>,
+    pub(crate) power: crate::power::PowerStatus,
+    pub(crate) debug_overlay: crate::debug_overlay::OverlayState,
+    pub(crate) gpu_errors: std::collections::VecDeque<crate::GpuError>,
+    pub(crate) draw_errors: std::collections::VecDeque<crate::DrawError>,
+    #[cfg(feature = "frame-dump")]
+    pub(crate) pending_frame_dump: Option<std::path::PathBuf>,
+    /// Height of the screen covered by the on-screen keyboard while it is shown.
+    pub(crate) virtual_keyboard: Option<Pt>,
+    pub(crate) pending_virtual_keyboard_change: bool,
+    pub(crate) last_frame: Option<crate::FrameCapture>,
+    pub(crate) recording: Option<crate::recording::Recording>,
+    #[cfg(feature = "gif")]
+    pub(crate) replay: Option<crate::replay::ReplayBuffer>,
+    #[cfg(all(
+        feature = "hot-reload",
+        any(target_os = "windows", target_os = "macos", target_os = "linux")
+    ))]
+    pub(crate) hot_reload: Option<crate::hot_reload::HotReload>,
+    pub(crate) pending_resize: bool,
+    pub(crate) pending_scale_factor_change: bool,
+    pub(crate) system_theme: Option<crate::Theme>,
+    pub(crate) pending_theme_change: bool,
+    pub(crate) pending_orientation_change: bool,
+    pub(crate) tasks: crate::tasks::TaskQueue,
+    pub(crate) tweens: crate::tween::Tweens,
+    pub(crate) lockstep: crate::lockstep::Lockstep,
+    pub(crate) redraw_requested: bool,
+    /// Resources to release per scene on the stack, bottom first.
+    pub(crate) scene_scopes: Vec<Vec<crate::SceneResource>>,
+    /// Scopes of removed scenes, released once the frame that removed them is done.
+    pub(crate) retired_scene_scopes: Vec<Vec<crate::SceneResource>>,
 }
 
 impl ContextRuntime {
@@ -51,14 +100,53 @@ impl ContextRuntime {
             input: InputManager::new(),
             scale_factor: 1.0,
             window_logical_size: (Pt(0.0), Pt(0.0)),
+            safe_area: (Pt(0.0), Pt(0.0), Pt(0.0), Pt(0.0)),
             graphics: None,
             audio: None,
             delta_time: std::time::Duration::from_secs(0),
             total_elapsed: std::time::Duration::from_secs(0),
             draw_alpha: 0.0,
+            draw_stats: crate::DrawStats::default(),
+            render_stats: crate::RenderStats::default(),
+            frame_stats: crate::FrameStats::default(),
+            baked_layers: crate::layers::BakedLayers::default(),
             pending_window_title: None,
             pending_cursor_visible: None,
             pending_fullscreen: None,
+            pending_window_level: None,
+            pending_window_size: None,
+            pending_window_position: None,
+            pending_virtual_keyboard: None,
+            pending_keep_screen_on: None,
+            clipboard: None,
+            power: crate::power::PowerStatus::default(),
+            debug_overlay: crate::debug_overlay::OverlayState::default(),
+            gpu_errors: std::collections::VecDeque::new(),
+            draw_errors: std::collections::VecDeque::new(),
+            #[cfg(feature = "frame-dump")]
+            pending_frame_dump: None,
+            virtual_keyboard: None,
+            pending_virtual_keyboard_change: false,
+            last_frame: None,
+            recording: None,
+            #[cfg(feature = "gif")]
+            replay: None,
+            #[cfg(all(
+                feature = "hot-reload",
+                any(target_os = "windows", target_os = "macos", target_os = "linux")
+            ))]
+            hot_reload: None,
+            pending_resize: false,
+            pending_scale_factor_change: false,
+            system_theme: None,
+            pending_theme_change: false,
+            pending_orientation_change: false,
+            tasks: crate::tasks::TaskQueue::default(),
+            tweens: crate::tween::Tweens::default(),
+            lockstep: crate::lockstep::Lockstep::default(),
+            redraw_requested: false,
+            scene_scopes: Vec::new(),
+            retired_scene_scopes: Vec::new(),
         }
     }
 }
@@ -71,13 +159,21 @@ pub(crate) struct ResourceRegistry {
     #[cfg(feature = "model-3d")]
     pub(crate) model_3d: Model3dRegistry,
     pub(crate) fonts: HashMap<u32, Vec<u8>>,
+    pub(crate) bitmap_fonts: HashMap<u32, crate::bitmap_font::BitmapFont>,
     pub(crate) image_shaders: HashMap<u32, ImageShaderDesc>,
     pub(crate) next_texture_id: u32,
     pub(crate) next_image_id: u32,
     pub(crate) next_font_id: u32,
+    /// Bumped whenever a registered font's data is replaced, invalidating text layouts.
+    pub(crate) font_revision: u64,
     pub(crate) next_image_shader_id: u32,
     pub(crate) gpu_generation: u32,
     pub(crate) dirty_assets: bool,
+    pub(crate) texture_budget: Option<u64>,
+    /// The built-in white image, see [`Context::white_image`].
+    white_image: Option<crate::Image>,
+    #[cfg(feature = "i18n")]
+    pub(crate) localization: crate::i18n::Localization,
 }
 
 impl ResourceRegistry {
@@ -89,13 +185,19 @@ impl ResourceRegistry {
             #[cfg(feature = "model-3d")]
             model_3d: Model3dRegistry::default(),
             fonts: HashMap::new(),
+            bitmap_fonts: HashMap::new(),
             image_shaders: HashMap::new(),
             next_texture_id: 1,
             next_image_id: 1,
             next_font_id: 1,
+            font_revision: 0,
             next_image_shader_id: 1,
             gpu_generation: 1,
             dirty_assets: true,
+            texture_budget: None,
+            white_image: None,
+            #[cfg(feature = "i18n")]
+            localization: crate::i18n::Localization::default(),
         }
     }
 }
@@ -160,26 +262,125 @@ impl Context {
         self.runtime.draw_alpha
     }
 
+    /// Returns draw calls, instances, batch breaks, glyphs and texture uploads of the last
+    /// rendered frame, across the screen and all render targets.
+    ///
+    /// Many [`batch_breaks`](crate::FrameStats::batch_breaks) compared to
+    /// [`instances`](crate::FrameStats::instances) usually mean draws alternate between
+    /// textures or shaders; grouping them by texture lets them share draw calls.
+    pub fn frame_stats(&self) -> crate::FrameStats {
+        self.runtime.frame_stats
+    }
+
     pub(crate) fn with_audio<R>(&mut self, f: impl FnOnce(&mut AudioSystem) -> R) -> Option<R> {
         self.runtime.audio.as_mut().map(f)
     }
 
     fn register_defaults(&mut self) {
-        self.register_image(1, 1, Pt::from(1.0), Pt::from(1.0), &[255, 255, 255, 255]); // ID 1
+        // ID 1
+        let white = self.register_image(1, 1, Pt::from(1.0), Pt::from(1.0), &[255, 255, 255, 255]);
+        self.registry.white_image = Some(white);
         self.register_image(1, 1, Pt::from(1.0), Pt::from(1.0), &[0, 0, 0, 255]); // ID 2
         #[cfg(feature = "model-3d")]
         self.register_image(1, 1, Pt::from(1.0), Pt::from(1.0), &[128, 128, 255, 255]); // ID 3 (Normal)
+    }
 
-        let text_shader_src = include_str!("shaders/text_tint.wgsl");
-        self.register_image_shader_desc(ImageShaderDesc::from_wgsl(text_shader_src));
+    /// The built-in 1x1 pt white image, tinted and scaled by widgets and overlays to
+    /// draw filled rectangles and lines.
+    pub(crate) fn white_image(&self) -> crate::Image {
+        self.registry
+            .white_image
+            .expect("registered when the context is created")
     }
 
     pub(crate) fn set_window_logical_size(&mut self, width: Pt, height: Pt) {
         let w = Pt(width.0.max(0.0));
         let h = Pt(height.0.max(0.0));
+        if self.runtime.window_logical_size != (w, h) {
+            self.runtime.pending_resize = true;
+        }
+        let (old_w, old_h) = self.runtime.window_logical_size;
+        if crate::Orientation::of_size(old_w, old_h) != crate::Orientation::of_size(w, h) {
+            self.runtime.pending_orientation_change = true;
+        }
         self.runtime.window_logical_size = (w, h);
     }
 
+    /// Stores the safe area insets; a change is reported like a resize so scenes lay out
+    /// their HUD again.
+    pub(crate) fn set_safe_area(&mut self, top: Pt, bottom: Pt, left: Pt, right: Pt) {
+        let insets = (
+            Pt(top.0.max(0.0)),
+            Pt(bottom.0.max(0.0)),
+            Pt(left.0.max(0.0)),
+            Pt(right.0.max(0.0)),
+        );
+        if self.runtime.safe_area != insets {
+            self.runtime.pending_resize = true;
+        }
+        self.runtime.safe_area = insets;
+    }
+
+    /// Returns the new logical size if it changed since the last call.
+    pub(crate) fn take_resize_event(&mut self) -> Option<(Pt, Pt)> {
+        std::mem::take(&mut self.runtime.pending_resize).then_some(self.runtime.window_logical_size)
+    }
+
+    /// Returns the new scale factor if it changed since the last call.
+    pub(crate) fn take_scale_factor_event(&mut self) -> Option<f64> {
+        std::mem::take(&mut self.runtime.pending_scale_factor_change)
+            .then_some(self.runtime.scale_factor)
+    }
+
+    /// Returns the new system theme if it changed since the last call.
+    pub(crate) fn take_theme_event(&mut self) -> Option<crate::Theme> {
+        if !std::mem::take(&mut self.runtime.pending_theme_change) {
+            return None;
+        }
+        self.runtime.system_theme
+    }
+
+    /// Records the on-screen keyboard reported by the platform.
+    #[allow(dead_code)]
+    pub(crate) fn set_virtual_keyboard(&mut self, height: Option<Pt>) {
+        if self.runtime.virtual_keyboard != height {
+            self.runtime.pending_virtual_keyboard_change = true;
+        }
+        self.runtime.virtual_keyboard = height;
+    }
+
+    /// Returns the on-screen keyboard if it was shown, hidden or resized since the last call.
+    pub(crate) fn take_virtual_keyboard_event(&mut self) -> Option<Option<Pt>> {
+        std::mem::take(&mut self.runtime.pending_virtual_keyboard_change)
+            .then_some(self.runtime.virtual_keyboard)
+    }
+
+    /// Returns the new orientation if it changed since the last call.
+    pub(crate) fn take_orientation_event(&mut self) -> Option<crate::Orientation> {
+        std::mem::take(&mut self.runtime.pending_orientation_change)
+            .then(|| crate::orientation(self))
+    }
+
+    /// Drops pending window events, e.g. for a scene that just saw the current metrics.
+    pub(crate) fn clear_window_events(&mut self) {
+        self.runtime.pending_resize = false;
+        self.runtime.pending_scale_factor_change = false;
+        self.runtime.pending_theme_change = false;
+        self.runtime.pending_orientation_change = false;
+        self.runtime.pending_virtual_keyboard_change = false;
+    }
+
+    pub(crate) fn system_theme(&self) -> Option<crate::Theme> {
+        self.runtime.system_theme
+    }
+
+    pub(crate) fn set_system_theme(&mut self, theme: Option<crate::Theme>) {
+        if self.runtime.system_theme != theme {
+            self.runtime.pending_theme_change = true;
+        }
+        self.runtime.system_theme = theme;
+    }
+
     pub(crate) fn update_window_metrics_physical(
         &mut self,
         width: u32,
@@ -205,6 +406,30 @@ impl Context {
         self.runtime.pending_fullscreen = Some(enabled);
     }
 
+    pub(crate) fn set_window_level(&mut self, level: crate::WindowLevel) {
+        self.runtime.pending_window_level = Some(level);
+    }
+
+    pub(crate) fn set_window_size(&mut self, width: Pt, height: Pt) {
+        self.runtime.pending_window_size = Some((Pt(width.0.max(1.0)), Pt(height.0.max(1.0))));
+    }
+
+    pub(crate) fn set_window_position(&mut self, x: Pt, y: Pt) {
+        self.runtime.pending_window_position = Some((x, y));
+    }
+
+    pub(crate) fn show_virtual_keyboard(&mut self, visible: bool) {
+        self.runtime.pending_virtual_keyboard = Some(visible);
+    }
+
+    pub(crate) fn request_redraw(&mut self) {
+        self.runtime.redraw_requested = true;
+    }
+
+    pub(crate) fn take_redraw_request(&mut self) -> bool {
+        std::mem::take(&mut self.runtime.redraw_requested)
+    }
+
     pub(crate) fn take_window_title_request(&mut self) -> Option<String> {
         self.runtime.pending_window_title.take()
     }
@@ -217,6 +442,26 @@ impl Context {
         self.runtime.pending_fullscreen.take()
     }
 
+    pub(crate) fn take_window_level_request(&mut self) -> Option<crate::WindowLevel> {
+        self.runtime.pending_window_level.take()
+    }
+
+    pub(crate) fn take_window_size_request(&mut self) -> Option<(Pt, Pt)> {
+        self.runtime.pending_window_size.take()
+    }
+
+    pub(crate) fn take_window_position_request(&mut self) -> Option<(Pt, Pt)> {
+        self.runtime.pending_window_position.take()
+    }
+
+    pub(crate) fn take_virtual_keyboard_request(&mut self) -> Option<bool> {
+        self.runtime.pending_virtual_keyboard.take()
+    }
+
+    pub(crate) fn take_keep_screen_on_request(&mut self) -> Option<bool> {
+        self.runtime.pending_keep_screen_on.take()
+    }
+
     /// Returns the logical size of the window in Pt.
     pub(crate) fn window_logical_size(&self) -> (Pt, Pt) {
         self.runtime.window_logical_size
@@ -260,6 +505,56 @@ impl Context {
             .and_then(|v| Rc::downcast::<T>(v).ok())
     }
 
+    /// Stores `value` as the resource of type `T` without wrapping it in an `Rc` first.
+    ///
+    /// Returns the previous resource unless `Rc` clones of it are still alive.
+    pub fn insert_resource_value<T: Any>(&mut self, value: T) -> Option<T> {
+        self.registry
+            .resources
+            .inner
+            .insert(TypeId::of::<T>(), Rc::new(value))
+            .and_then(|old| Rc::downcast::<T>(old).ok())
+            .and_then(|old| Rc::try_unwrap(old).ok())
+    }
+
+    /// Borrows the resource of type `T`, however it was inserted.
+    pub fn resource_value<T: Any>(&self) -> Option<&T> {
+        self.registry
+            .resources
+            .inner
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+    }
+
+    /// Borrows the resource of type `T` for modification, however it was inserted.
+    ///
+    /// Returns `None` while `Rc` clones from [`get_resource`](crate::get_resource) are
+    /// alive, since they would observe the change.
+    pub fn get_resource_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.registry
+            .resources
+            .inner
+            .get_mut(&TypeId::of::<T>())
+            .and_then(Rc::get_mut)
+            .and_then(|value| value.downcast_mut::<T>())
+    }
+
+    /// Removes and returns the resource of type `T`.
+    ///
+    /// A resource with live `Rc` clones cannot be moved out; it stays in place and
+    /// `None` is returned, so use [`take_resource`](Self::take_resource) for those.
+    pub fn remove_resource<T: Any>(&mut self) -> Option<T> {
+        let resources = &mut self.registry.resources.inner;
+        let type_id = TypeId::of::<T>();
+        if Rc::strong_count(resources.get(&type_id)?) > 1 {
+            return None;
+        }
+        resources
+            .remove(&type_id)
+            .and_then(|value| Rc::downcast::<T>(value).ok())
+            .and_then(|value| Rc::try_unwrap(value).ok())
+    }
+
     /// Registers a new texture and returns a handle.
     ///
     /// This also creates a default full-image [`Image`] for the texture.
@@ -306,14 +601,16 @@ impl Context {
 
         self.registry.dirty_assets = true;
 
-        crate::Texture {
+        let texture = crate::Texture {
             id: texture_id,
             default_view_id: image_id,
             width,
             height,
             pixel_width,
             pixel_height,
-        }
+        };
+        self.track_scene_resource(crate::SceneResource::Texture(texture));
+        texture
     }
 
     /// Registers a new image from RGBA data and returns a full-view handle.
@@ -341,6 +638,7 @@ impl Context {
                 pixel_height,
                 rgba,
             ) {
+                self.track_scene_resource(crate::SceneResource::Image(img));
                 return img;
             }
         }
@@ -401,6 +699,7 @@ impl Context {
         self.registry.next_font_id += 1;
         self.registry.fonts.insert(id, font_data);
         self.registry.dirty_assets = true;
+        self.track_scene_resource(crate::SceneResource::Font(id));
         id
     }
 
@@ -453,14 +752,16 @@ impl Context {
         ));
         self.registry.dirty_assets = true;
 
-        crate::Texture {
+        let texture = crate::Texture {
             id: texture_id,
             default_view_id: image_id,
             width,
             height,
             pixel_width,
             pixel_height,
-        }
+        };
+        self.track_scene_resource(crate::SceneResource::Texture(texture));
+        texture
     }
 
     pub(crate) fn insert_resource_dyn(&mut self, type_id: TypeId, value: Rc<dyn Any>) {
@@ -473,6 +774,7 @@ impl Context {
 
     pub(crate) fn begin_frame(&mut self) {
         self.runtime.draw_list.clear();
+        self.release_retired_scene_scopes();
         #[cfg(feature = "model-3d")]
         self.runtime.model_3d.begin_frame();
     }
@@ -482,6 +784,9 @@ impl Context {
         self.runtime.pending_window_title = None;
         self.runtime.pending_cursor_visible = None;
         self.runtime.pending_fullscreen = None;
+        self.runtime.pending_window_level = None;
+        self.runtime.pending_window_size = None;
+        self.runtime.pending_window_position = None;
     }
 
     pub(crate) fn clear_transient_input(&mut self) {
@@ -497,6 +802,9 @@ impl Context {
     }
 
     pub(crate) fn set_scale_factor(&mut self, scale_factor: f64) {
+        if self.runtime.scale_factor != scale_factor {
+            self.runtime.pending_scale_factor_change = true;
+        }
         self.runtime.scale_factor = scale_factor;
     }
 
@@ -632,8 +940,9 @@ impl Context {
 
             if !is_visible {
                 if std::env::var("SPOT_DEBUG_CULL").is_ok() {
-                    eprintln!(
-                        "[spot][cull] image id={} at {:?} (size {:?}) is culled (screen: {:?})",
+                    crate::diagnostics::info!(
+                        "cull",
+                        "image id={} at {:?} (size {:?}) is culled (screen: {:?})",
                         id,
                         pos,
                         [w, h],
@@ -647,8 +956,9 @@ impl Context {
         if std::env::var("SPOT_DEBUG_DRAW").is_ok() {
             match &drawable {
                 DrawCommand::Image(cmd) => {
-                    eprintln!(
-                        "[spot][debug] draw image id={} target={} shader_id={} pos={:?}",
+                    crate::diagnostics::info!(
+                        "draw",
+                        "image id={} target={} shader_id={} pos={:?}",
                         cmd.id,
                         cmd.target_texture_id,
                         cmd.shader_id,
@@ -656,8 +966,9 @@ impl Context {
                     );
                 }
                 DrawCommand::Text(cmd) => {
-                    eprintln!(
-                        "[spot][debug] draw text target={} pos={:?}",
+                    crate::diagnostics::info!(
+                        "draw",
+                        "text target={} pos={:?}",
                         cmd.target_texture_id,
                         cmd.opts.position(),
                     );
@@ -692,6 +1003,62 @@ fn platform_app_data_dir() -> Option<std::path::PathBuf> {
 mod tests {
     use super::*;
     use crate::Pt;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Counts the allocations made by each test thread.
+    struct CountingAllocator;
+
+    fn count_allocation() {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            count_allocation();
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            count_allocation();
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn steady_state_frames_record_draws_without_allocating() {
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt(320.0), Pt(240.0));
+        let screen = crate::window::make_screen_target(&ctx);
+        let sprite = crate::Image::new(&mut ctx, Pt(8.0), Pt(8.0), &[255; 8 * 8 * 4]).unwrap();
+        let label = crate::Text::new("score: 42", 1);
+        let record = |ctx: &mut Context| {
+            ctx.begin_frame();
+            for x in 0..64 {
+                let options = crate::DrawOption::default().with_position([Pt(x as f32), Pt(0.0)]);
+                screen.draw(ctx, &sprite, options);
+            }
+            screen.draw(ctx, &label, crate::DrawOption::default());
+        };
+
+        // The first frame grows the draw list; later ones reuse it.
+        record(&mut ctx);
+        let before = ALLOCATIONS.with(Cell::get);
+        record(&mut ctx);
+        assert_eq!(ALLOCATIONS.with(Cell::get), before);
+        assert_eq!(ctx.runtime.draw_list.len(), 65);
+    }
 
     #[test]
     fn test_auto_atlas_logic() {
@@ -708,4 +1075,29 @@ mod tests {
         // We can't easily mock Graphics here without a lot of setup,
         // but the logic check in register_image is verified by compilation.
     }
+
+    #[test]
+    fn resource_values_are_owned_and_mutable_in_place() {
+        #[derive(Debug, PartialEq)]
+        struct Score(u32);
+
+        let mut ctx = Context::new();
+        assert_eq!(ctx.insert_resource_value(Score(1)), None);
+        ctx.get_resource_mut::<Score>().unwrap().0 += 41;
+        assert_eq!(ctx.resource_value::<Score>(), Some(&Score(42)));
+
+        // Both APIs share one store: `Rc` resources can be borrowed mutably while
+        // no clone is alive.
+        assert_eq!(ctx.get_resource::<Score>().as_deref(), Some(&Score(42)));
+        ctx.insert_resource(Rc::new(Score(7)));
+        ctx.get_resource_mut::<Score>().unwrap().0 += 1;
+        let shared = ctx.get_resource::<Score>().unwrap();
+        assert!(ctx.get_resource_mut::<Score>().is_none());
+        assert_eq!(ctx.remove_resource::<Score>(), None);
+        drop(shared);
+        assert_eq!(ctx.insert_resource_value(Score(3)), Some(Score(8)));
+        assert_eq!(ctx.remove_resource::<Score>(), Some(Score(3)));
+        assert_eq!(ctx.remove_resource::<Score>(), None);
+        assert!(ctx.get_resource::<Score>().is_none());
+    }
 }
@@ -49,6 +49,27 @@ pub fn text_input(ctx: &Context) -> &str {
     ctx.input().text_input()
 }
 
+/// Shows or hides the on-screen keyboard on Android, iOS and mobile browsers.
+///
+/// Showing it also enables text input, so typed characters arrive through
+/// [`text_input`]. Once the platform reports the keyboard, [`virtual_keyboard_height`]
+/// and [`Spot::virtual_keyboard_changed`](crate::Spot::virtual_keyboard_changed) tell how
+/// much of the screen it covers. Ignored on desktop.
+pub fn show_virtual_keyboard(ctx: &mut Context, visible: bool) {
+    if visible {
+        ctx.input_mut().set_text_input_enabled(true);
+    }
+    ctx.show_virtual_keyboard(visible);
+}
+
+/// Returns the logical height covered by the on-screen keyboard at the bottom of the
+/// window, or `None` while it is hidden.
+///
+/// iOS does not report the keyboard's size, so the height is zero there.
+pub fn virtual_keyboard_height(ctx: &Context) -> Option<Pt> {
+    ctx.runtime.virtual_keyboard
+}
+
 /// Compatibility alias for `text_input`.
 pub fn get_input(ctx: &Context) -> &str {
     ctx.input().text_input()
@@ -0,0 +1,614 @@
+//! An on-screen panel of frame timing, renderer and memory statistics.
+//!
+//! ```rust,ignore
+//! fn initialize(ctx: &mut Context) -> Self {
+//!     spottedcat::debug_overlay(ctx, DebugOverlay::FPS | DebugOverlay::DRAW_STATS);
+//!     // ...
+//! }
+//! ```
+//!
+//! The panel is drawn over every scene with a built-in pixel font, so it needs no
+//! assets, and F3 shows or hides it. [`DebugOverlay::BOUNDS`] also outlines what the
+//! frame drew on screen, to track down positioning and clipping bugs.
+
+use std::collections::VecDeque;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use crate::bitmap_font::{BitmapFont, BitmapGlyph};
+use crate::drawable::DrawCommand;
+use crate::image::Bounds;
+use crate::{Camera2D, Context, DrawOption, Image, Key, Pt, Text};
+
+/// Sections of the debug overlay, combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DebugOverlay(u8);
+
+impl DebugOverlay {
+    /// No sections; turns the overlay off.
+    pub const NONE: Self = Self(0);
+    /// Frames per second and a graph of recent frame times.
+    pub const FPS: Self = Self(1);
+    /// Draw calls, quads, batch breaks, culled quads, glyphs and texture uploads.
+    pub const DRAW_STATS: Self = Self(1 << 1);
+    /// Texture memory, shared atlas pages and playing audio voices.
+    pub const MEMORY: Self = Self(1 << 2);
+    /// Outlines of the images, clipped areas and text boxes drawn on screen and of the
+    /// cameras passed to [`debug_camera`].
+    pub const BOUNDS: Self = Self(1 << 3);
+    /// Every section.
+    pub const ALL: Self = Self(0b1111);
+
+    /// Returns whether all sections of `other` are in `self`.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether no section is set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for DebugOverlay {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for DebugOverlay {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Frame times kept for the graph, about two seconds at 60 Hz.
+const HISTORY: usize = 120;
+/// Frame time at the top of the graph, in milliseconds.
+const GRAPH_MAX_MS: f32 = 50.0;
+const GRAPH_HEIGHT: f32 = 40.0;
+const BAR_WIDTH: f32 = 2.0;
+const FONT_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 14.0;
+const MARGIN: f32 = 8.0;
+const PADDING: f32 = 6.0;
+
+const BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.65];
+const TEXT: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const GOOD: [f32; 4] = [0.3, 0.9, 0.4, 1.0];
+const SLOW: [f32; 4] = [1.0, 0.8, 0.2, 1.0];
+const JANK: [f32; 4] = [1.0, 0.3, 0.3, 1.0];
+const TARGET_LINE: [f32; 4] = [1.0, 1.0, 1.0, 0.35];
+const IMAGE_BOUNDS: [f32; 4] = [0.2, 0.9, 1.0, 0.9];
+const CLIP_BOUNDS: [f32; 4] = [1.0, 0.2, 0.9, 1.0];
+const TEXT_BOUNDS: [f32; 4] = [1.0, 0.9, 0.2, 0.9];
+const CAMERA_BOUNDS: [f32; 4] = [0.3, 1.0, 0.3, 1.0];
+const DEADZONE_BOUNDS: [f32; 4] = [0.3, 1.0, 0.3, 0.5];
+
+/// Overlay settings and the frame times sampled while it is enabled.
+#[derive(Debug)]
+pub(crate) struct OverlayState {
+    sections: DebugOverlay,
+    visible: bool,
+    toggle_key: Option<Key>,
+    frame_times: VecDeque<f32>,
+    last_frame: Option<Instant>,
+    /// Viewport and deadzone rectangles passed to [`debug_camera`] this frame.
+    cameras: Vec<[[f32; 2]; 4]>,
+    resources: Option<OverlayResources>,
+}
+
+impl Default for OverlayState {
+    fn default() -> Self {
+        Self {
+            sections: DebugOverlay::NONE,
+            visible: false,
+            toggle_key: Some(Key::F3),
+            frame_times: VecDeque::with_capacity(HISTORY),
+            last_frame: None,
+            cameras: Vec::new(),
+            resources: None,
+        }
+    }
+}
+
+/// The font the overlay draws with, registered the first time it is shown.
+#[derive(Debug, Clone, Copy)]
+struct OverlayResources {
+    font_id: u32,
+}
+
+/// Shows the debug overlay with `sections`, or hides it for [`DebugOverlay::NONE`].
+///
+/// The overlay stays across scene switches and is drawn over every scene and
+/// transition. Statistics are those of the previous frame, as returned by
+/// [`Context::frame_stats`], [`draw_stats`](crate::draw_stats) and
+/// [`texture_memory`](crate::texture_memory).
+pub fn debug_overlay(ctx: &mut Context, sections: DebugOverlay) {
+    let overlay = &mut ctx.runtime.debug_overlay;
+    overlay.sections = sections;
+    overlay.visible = !sections.is_empty();
+    if sections.is_empty() {
+        overlay.frame_times.clear();
+        overlay.last_frame = None;
+    }
+}
+
+/// Sets the key that shows and hides an enabled debug overlay; F3 by default, `None`
+/// leaves it to [`debug_overlay`].
+pub fn set_debug_overlay_key(ctx: &mut Context, key: Option<Key>) {
+    ctx.runtime.debug_overlay.toggle_key = key;
+}
+
+/// Outlines `camera`'s viewport and deadzone this frame when the overlay shows
+/// [`DebugOverlay::BOUNDS`].
+///
+/// Cameras are not tied to the context, so call this from `draw` for each camera that
+/// should be shown.
+pub fn debug_camera(ctx: &mut Context, camera: &Camera2D) {
+    let overlay = &mut ctx.runtime.debug_overlay;
+    if !overlay.sections.contains(DebugOverlay::BOUNDS) {
+        return;
+    }
+    let [width, height] = camera.viewport.map(Pt::as_f32);
+    let [dead_w, dead_h] = camera.deadzone.map(Pt::as_f32);
+    overlay.cameras.push(rect(0.0, 0.0, width, height));
+    if dead_w > 0.0 || dead_h > 0.0 {
+        overlay.cameras.push(rect(
+            (width - dead_w) / 2.0,
+            (height - dead_h) / 2.0,
+            dead_w,
+            dead_h,
+        ));
+    }
+}
+
+/// Flips the overlay's visibility when its key was pressed during this update.
+pub(crate) fn handle_toggle_key(ctx: &mut Context) {
+    let overlay = &ctx.runtime.debug_overlay;
+    if overlay.sections.is_empty() {
+        return;
+    }
+    if let Some(key) = overlay.toggle_key
+        && ctx.input().key_pressed(key)
+    {
+        let overlay = &mut ctx.runtime.debug_overlay;
+        overlay.visible = !overlay.visible;
+    }
+}
+
+/// Samples the frame time and draws the outlines and the panel over `screen`.
+pub(crate) fn draw(ctx: &mut Context, screen: Image) {
+    let overlay = &mut ctx.runtime.debug_overlay;
+    if overlay.sections.is_empty() {
+        return;
+    }
+    let now = Instant::now();
+    if let Some(last) = overlay.last_frame.replace(now) {
+        if overlay.frame_times.len() == HISTORY {
+            overlay.frame_times.pop_front();
+        }
+        overlay
+            .frame_times
+            .push_back(now.duration_since(last).as_secs_f32() * 1000.0);
+    }
+    let cameras = std::mem::take(&mut overlay.cameras);
+    if !overlay.visible {
+        return;
+    }
+    let Some(resources) = resources(ctx) else {
+        return;
+    };
+
+    let sections = ctx.runtime.debug_overlay.sections;
+    if sections.contains(DebugOverlay::BOUNDS) {
+        let mut outlines = outlines(ctx);
+        let camera_colors = [CAMERA_BOUNDS, DEADZONE_BOUNDS].into_iter().cycle();
+        outlines.extend(cameras.into_iter().zip(camera_colors));
+        for (corners, color) in outlines {
+            for (index, from) in corners.iter().enumerate() {
+                line(ctx, screen, *from, corners[(index + 1) % 4], color);
+            }
+        }
+    }
+
+    let lines = lines(ctx);
+    let graph = sections.contains(DebugOverlay::FPS);
+    if lines.is_empty() && !graph {
+        return;
+    }
+    let texts: Vec<Text> = lines
+        .iter()
+        .map(|line| {
+            Text::new(line.as_str(), resources.font_id)
+                .with_font_size(Pt(FONT_SIZE))
+                .with_color(TEXT)
+        })
+        .collect();
+    let text_width = texts
+        .iter()
+        .map(|text| text.measure(ctx).0)
+        .fold(0.0, f32::max);
+    let graph_width = HISTORY as f32 * BAR_WIDTH;
+    let width = text_width.max(if graph { graph_width } else { 0.0 }) + PADDING * 2.0;
+    let height = texts.len() as f32 * LINE_HEIGHT
+        + if graph { GRAPH_HEIGHT + PADDING } else { 0.0 }
+        + PADDING * 2.0;
+    let fill = |ctx: &mut Context, x: f32, y: f32, w: f32, h: f32, color: [f32; 4]| {
+        let options = DrawOption::new([Pt(x), Pt(y)], 0.0, [w, h]).with_tint(color);
+        let white = ctx.white_image();
+        screen.draw(ctx, &white, options);
+    };
+
+    fill(ctx, MARGIN, MARGIN, width, height, BACKGROUND);
+    let left = MARGIN + PADDING;
+    let mut y = MARGIN + PADDING;
+    for text in &texts {
+        let (_, _, y_offset) = text.measure_with_y_offset(ctx);
+        screen.draw(
+            ctx,
+            text,
+            DrawOption::default().with_position([Pt(left), Pt(y + y_offset)]),
+        );
+        y += LINE_HEIGHT;
+    }
+
+    if graph {
+        let bottom = y + GRAPH_HEIGHT;
+        let bar_height = |ms: f32| (ms / GRAPH_MAX_MS).min(1.0) * GRAPH_HEIGHT;
+        let frame_times: Vec<f32> = ctx
+            .runtime
+            .debug_overlay
+            .frame_times
+            .iter()
+            .copied()
+            .collect();
+        for (index, ms) in frame_times.into_iter().enumerate() {
+            let color = match ms {
+                ms if ms <= 17.5 => GOOD,
+                ms if ms <= 34.0 => SLOW,
+                _ => JANK,
+            };
+            let h = bar_height(ms).max(1.0);
+            let x = left + index as f32 * BAR_WIDTH;
+            fill(ctx, x, bottom - h, BAR_WIDTH, h, color);
+        }
+        let target = bottom - bar_height(1000.0 / 60.0);
+        fill(ctx, left, target, graph_width, 1.0, TARGET_LINE);
+    }
+}
+
+/// Corners of everything drawn on screen so far this frame, with its outline color.
+///
+/// Offscreen targets drawn on screen, like [`Child`](crate::Child) and
+/// [`ScrollView`](crate::ScrollView) areas, clip their content and are outlined as such.
+fn outlines(ctx: &Context) -> Vec<([[f32; 2]; 4], [f32; 4])> {
+    let mut outlines = Vec::new();
+    for command in &ctx.runtime.draw_list {
+        match command {
+            DrawCommand::Image(cmd) if cmd.target_texture_id == 0 => {
+                let [w, h] = cmd.size.map(Pt::as_f32);
+                let color = if is_render_target_view(ctx, cmd.id) {
+                    CLIP_BOUNDS
+                } else {
+                    IMAGE_BOUNDS
+                };
+                outlines.push((quad(&cmd.opts, 0.0, w, h), color));
+            }
+            DrawCommand::Text(cmd) if cmd.target_texture_id == 0 => {
+                let (w, h, y_offset) = cmd.text.measure_with_y_offset(ctx);
+                outlines.push((quad(&cmd.opts, -y_offset, w, h), TEXT_BOUNDS));
+            }
+            _ => {}
+        }
+    }
+    outlines
+}
+
+/// Whether `image_id` shows a render target, i.e. content clipped to its size.
+fn is_render_target_view(ctx: &Context, image_id: u32) -> bool {
+    ctx.registry
+        .images
+        .get(image_id as usize)
+        .and_then(Option::as_ref)
+        .and_then(|image| ctx.registry.textures.get(image.texture_id as usize))
+        .and_then(Option::as_ref)
+        .is_some_and(|texture| texture.is_render_target())
+}
+
+/// Corners of a `w` by `h` box `y` below the draw position, scaled and rotated about
+/// the position like the drawn quad.
+fn quad(options: &DrawOption, y: f32, w: f32, h: f32) -> [[f32; 2]; 4] {
+    let [x0, y0] = options.position().map(Pt::as_f32);
+    let [sx, sy] = options.scale();
+    let (sin, cos) = options.rotation().sin_cos();
+    [[0.0, y], [w, y], [w, y + h], [0.0, y + h]].map(|[x, y]| {
+        let (x, y) = (x * sx, y * sy);
+        [x0 + x * cos - y * sin, y0 + x * sin + y * cos]
+    })
+}
+
+fn rect(x: f32, y: f32, w: f32, h: f32) -> [[f32; 2]; 4] {
+    [[x, y], [x + w, y], [x + w, y + h], [x, y + h]]
+}
+
+/// Draws a one point wide line with the built-in white image.
+fn line(ctx: &mut Context, screen: Image, from: [f32; 2], to: [f32; 2], color: [f32; 4]) {
+    let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+    let length = dx.hypot(dy);
+    if length <= 0.0 {
+        return;
+    }
+    let options =
+        DrawOption::new([Pt(from[0]), Pt(from[1])], dy.atan2(dx), [length, 1.0]).with_tint(color);
+    let white = ctx.white_image();
+    screen.draw(ctx, &white, options);
+}
+
+fn resources(ctx: &mut Context) -> Option<OverlayResources> {
+    if let Some(resources) = ctx.runtime.debug_overlay.resources {
+        return Some(resources);
+    }
+    match ctx.outside_scene_scope(register_pixel_font) {
+        Ok(font_id) => {
+            let resources = OverlayResources { font_id };
+            ctx.runtime.debug_overlay.resources = Some(resources);
+            Some(resources)
+        }
+        Err(error) => {
+            crate::diagnostics::warn!("debug", "debug overlay unavailable: {}", error);
+            ctx.runtime.debug_overlay.sections = DebugOverlay::NONE;
+            None
+        }
+    }
+}
+
+/// The panel's text, one entry per line. The pixel font only has capitals.
+fn lines(ctx: &Context) -> Vec<String> {
+    let overlay = &ctx.runtime.debug_overlay;
+    let mut lines = Vec::new();
+    if overlay.sections.contains(DebugOverlay::FPS) {
+        let count = overlay.frame_times.len().max(1) as f32;
+        let average = overlay.frame_times.iter().sum::<f32>() / count;
+        let worst = overlay.frame_times.iter().copied().fold(0.0, f32::max);
+        let fps = if average > 0.0 { 1000.0 / average } else { 0.0 };
+        lines.push(format!("FPS {fps:.0}  {average:.1} MS  MAX {worst:.1} MS"));
+    }
+    if overlay.sections.contains(DebugOverlay::DRAW_STATS) {
+        let frame = ctx.frame_stats();
+        let breaks = frame.batch_breaks;
+        lines.push(format!(
+            "DRAWS {}  QUADS {}  CULLED {}",
+            frame.draw_calls, frame.instances, ctx.runtime.draw_stats.culled
+        ));
+        lines.push(format!(
+            "BREAKS TEX {}  SHADER {}  ALPHA {}  OPTS {}",
+            breaks.texture, breaks.shader, breaks.opacity, breaks.shader_opts
+        ));
+        lines.push(format!(
+            "GLYPHS {}  UPLOADS {}",
+            frame.glyphs, frame.texture_uploads
+        ));
+    }
+    if overlay.sections.contains(DebugOverlay::MEMORY) {
+        let megabytes = crate::texture_memory(ctx) as f64 / (1024.0 * 1024.0);
+        let atlas_pages = ctx
+            .runtime
+            .graphics
+            .as_ref()
+            .and_then(|g| g.shared_atlas.as_ref())
+            .map_or(0, |atlas| atlas.pages.len());
+        lines.push(format!(
+            "TEXTURES {megabytes:.1} MB  ATLAS {atlas_pages} PAGES"
+        ));
+        lines.push(format!(
+            "VOICES {}/{}",
+            crate::active_voices(ctx),
+            crate::max_voices(ctx)
+        ));
+    }
+    lines
+}
+
+/// Width and height of a glyph of the pixel font.
+const GLYPH_SIZE: [u32; 2] = [3, 5];
+/// Glyphs sit in cells with a transparent border so filtering does not bleed.
+const CELL: [u32; 2] = [GLYPH_SIZE[0] + 2, GLYPH_SIZE[1] + 2];
+
+/// A 3x5 pixel font, each row three bits from left to right.
+const PIXEL_GLYPHS: &[(char, [u8; 5])] = &[
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('A', [0b010, 0b101, 0b111, 0b101, 0b101]),
+    ('B', [0b110, 0b101, 0b110, 0b101, 0b110]),
+    ('C', [0b011, 0b100, 0b100, 0b100, 0b011]),
+    ('D', [0b110, 0b101, 0b101, 0b101, 0b110]),
+    ('E', [0b111, 0b100, 0b110, 0b100, 0b111]),
+    ('F', [0b111, 0b100, 0b110, 0b100, 0b100]),
+    ('G', [0b011, 0b100, 0b101, 0b101, 0b011]),
+    ('H', [0b101, 0b101, 0b111, 0b101, 0b101]),
+    ('I', [0b111, 0b010, 0b010, 0b010, 0b111]),
+    ('J', [0b001, 0b001, 0b001, 0b101, 0b010]),
+    ('K', [0b101, 0b101, 0b110, 0b101, 0b101]),
+    ('L', [0b100, 0b100, 0b100, 0b100, 0b111]),
+    ('M', [0b101, 0b111, 0b111, 0b101, 0b101]),
+    ('N', [0b110, 0b101, 0b101, 0b101, 0b101]),
+    ('O', [0b010, 0b101, 0b101, 0b101, 0b010]),
+    ('P', [0b110, 0b101, 0b110, 0b100, 0b100]),
+    ('Q', [0b010, 0b101, 0b101, 0b110, 0b011]),
+    ('R', [0b110, 0b101, 0b110, 0b101, 0b101]),
+    ('S', [0b011, 0b100, 0b010, 0b001, 0b110]),
+    ('T', [0b111, 0b010, 0b010, 0b010, 0b010]),
+    ('U', [0b101, 0b101, 0b101, 0b101, 0b111]),
+    ('V', [0b101, 0b101, 0b101, 0b101, 0b010]),
+    ('W', [0b101, 0b101, 0b111, 0b111, 0b101]),
+    ('X', [0b101, 0b101, 0b010, 0b101, 0b101]),
+    ('Y', [0b101, 0b101, 0b010, 0b010, 0b010]),
+    ('Z', [0b111, 0b001, 0b010, 0b100, 0b111]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+    (':', [0b000, 0b010, 0b000, 0b010, 0b000]),
+    ('/', [0b001, 0b001, 0b010, 0b100, 0b100]),
+    ('%', [0b101, 0b001, 0b010, 0b100, 0b101]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+    ('(', [0b010, 0b100, 0b100, 0b100, 0b010]),
+    (')', [0b010, 0b001, 0b001, 0b001, 0b010]),
+];
+
+/// Builds the pixel font's page and registers it as a bitmap font.
+fn register_pixel_font(ctx: &mut Context) -> anyhow::Result<u32> {
+    let page_width = CELL[0] * PIXEL_GLYPHS.len() as u32;
+    let mut rgba = vec![0u8; (page_width * CELL[1] * 4) as usize];
+    for (index, (_, rows)) in PIXEL_GLYPHS.iter().enumerate() {
+        for (y, row) in rows.iter().enumerate() {
+            for x in 0..GLYPH_SIZE[0] {
+                if row & (0b100 >> x) != 0 {
+                    let px = index as u32 * CELL[0] + 1 + x;
+                    let offset = (((y as u32 + 1) * page_width + px) * 4) as usize;
+                    rgba[offset..offset + 4].fill(255);
+                }
+            }
+        }
+    }
+    let page = Image::new(ctx, Pt(page_width as f32), Pt(CELL[1] as f32), &rgba)?;
+
+    let [width, height] = GLYPH_SIZE.map(|size| size as f32);
+    let mut glyphs = std::collections::HashMap::new();
+    for (index, (ch, _)) in PIXEL_GLYPHS.iter().enumerate() {
+        let bounds = Bounds::new(
+            Pt((index as u32 * CELL[0] + 1) as f32),
+            Pt(1.0),
+            Pt(width),
+            Pt(height),
+        );
+        glyphs.insert(
+            *ch,
+            BitmapGlyph {
+                image: Some(Image::sub_image(ctx, page, bounds)?),
+                offset: [0.0, 0.0],
+                size: [width, height],
+                advance: width + 1.0,
+            },
+        );
+    }
+    glyphs.insert(
+        ' ',
+        BitmapGlyph {
+            image: None,
+            offset: [0.0, 0.0],
+            size: [0.0, 0.0],
+            advance: width + 1.0,
+        },
+    );
+
+    Ok(crate::bitmap_font::insert_font(
+        ctx,
+        BitmapFont {
+            size: height,
+            line_height: height + 2.0,
+            base: height,
+            glyphs,
+            kernings: std::collections::HashMap::new(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_combine_and_fill_the_panel() {
+        let sections = DebugOverlay::FPS | DebugOverlay::MEMORY;
+        assert!(sections.contains(DebugOverlay::FPS));
+        assert!(!sections.contains(DebugOverlay::DRAW_STATS));
+        assert!(DebugOverlay::ALL.contains(sections));
+
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt(800.0), Pt(600.0));
+        let screen = crate::window::make_screen_target(&ctx);
+        draw(&mut ctx, screen);
+        assert!(ctx.runtime.draw_list.is_empty(), "disabled by default");
+
+        debug_overlay(&mut ctx, sections);
+        draw(&mut ctx, screen);
+        assert_eq!(lines(&ctx).len(), 3);
+        assert!(lines(&ctx).iter().all(|line| {
+            line.chars()
+                .all(|ch| ch == ' ' || PIXEL_GLYPHS.iter().any(|(glyph, _)| *glyph == ch))
+        }));
+        assert!(!ctx.runtime.draw_list.is_empty());
+        assert_eq!(ctx.runtime.debug_overlay.frame_times.len(), 1);
+    }
+
+    #[test]
+    fn bounds_outline_screen_draws_and_cameras() {
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt(800.0), Pt(600.0));
+        let screen = crate::window::make_screen_target(&ctx);
+        let image = Image::new(&mut ctx, Pt(10.0), Pt(20.0), &[255; 800]).unwrap();
+        screen.draw(
+            &mut ctx,
+            &image,
+            DrawOption::default()
+                .with_position([Pt(100.0), Pt(50.0)])
+                .with_scale([2.0, 1.0]),
+        );
+        let outlines = outlines(&ctx);
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(
+            outlines[0],
+            (rect(100.0, 50.0, 20.0, 20.0), IMAGE_BOUNDS),
+            "image outlines follow position and scale"
+        );
+
+        let camera = Camera2D::new([Pt(800.0), Pt(600.0)]).with_deadzone([Pt(100.0), Pt(50.0)]);
+        debug_camera(&mut ctx, &camera);
+        assert!(
+            ctx.runtime.debug_overlay.cameras.is_empty(),
+            "ignored while off"
+        );
+
+        debug_overlay(&mut ctx, DebugOverlay::BOUNDS);
+        debug_camera(&mut ctx, &camera);
+        assert_eq!(
+            ctx.runtime.debug_overlay.cameras,
+            vec![
+                rect(0.0, 0.0, 800.0, 600.0),
+                rect(350.0, 275.0, 100.0, 50.0)
+            ]
+        );
+        draw(&mut ctx, screen);
+        assert!(ctx.runtime.debug_overlay.cameras.is_empty());
+        assert_eq!(
+            ctx.runtime.draw_list.len(),
+            1 + 3 * 4,
+            "no panel for bounds only"
+        );
+    }
+
+    #[test]
+    fn the_toggle_key_hides_an_enabled_overlay() {
+        use winit::event::ElementState;
+        use winit::keyboard::{KeyCode, PhysicalKey};
+
+        let mut ctx = Context::new();
+        handle_toggle_key(&mut ctx);
+        debug_overlay(&mut ctx, DebugOverlay::FPS);
+        ctx.input_mut()
+            .handle_keyboard_input(ElementState::Pressed, PhysicalKey::Code(KeyCode::F3));
+        handle_toggle_key(&mut ctx);
+        assert!(!ctx.runtime.debug_overlay.visible);
+    }
+}
@@ -0,0 +1,143 @@
+//! Engine diagnostics.
+//!
+//! With the `tracing` feature, messages are `tracing` events and the frame stages are
+//! spans, all under `spottedcat::<area>` targets, so any subscriber can collect them.
+//! Without it, errors, warnings and info messages are written where each platform shows
+//! them: stderr on desktop, logcat on Android and the console on the web. Debug
+//! messages and spans are then dropped.
+
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    Error,
+    Warn,
+    Info,
+}
+
+/// Writes a message of `area` to the platform's log, for builds without `tracing`.
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn emit(level: Level, area: &str, message: std::fmt::Arguments<'_>) {
+    #[cfg(target_os = "android")]
+    {
+        let priority = match level {
+            Level::Error => ndk_sys::android_LogPriority::ANDROID_LOG_ERROR,
+            Level::Warn => ndk_sys::android_LogPriority::ANDROID_LOG_WARN,
+            Level::Info => ndk_sys::android_LogPriority::ANDROID_LOG_INFO,
+        };
+        crate::android::logcat(priority, &format!("[{area}] {message}"));
+    }
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        let line = wasm_bindgen::JsValue::from(format!("[spot][{area}] {message}"));
+        match level {
+            Level::Error => web_sys::console::error_1(&line),
+            Level::Warn => web_sys::console::warn_1(&line),
+            Level::Info => web_sys::console::log_1(&line),
+        }
+    }
+    #[cfg(not(any(
+        target_os = "android",
+        all(target_arch = "wasm32", target_os = "unknown")
+    )))]
+    {
+        let _ = level;
+        eprintln!("[spot][{area}] {message}");
+    }
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! error {
+    ($area:literal, $($arg:tt)+) => {
+        ::tracing::error!(target: concat!("spottedcat::", $area), $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! error {
+    ($area:literal, $($arg:tt)+) => {
+        $crate::diagnostics::emit(
+            $crate::diagnostics::Level::Error,
+            $area,
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! warning {
+    ($area:literal, $($arg:tt)+) => {
+        ::tracing::warn!(target: concat!("spottedcat::", $area), $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! warning {
+    ($area:literal, $($arg:tt)+) => {
+        $crate::diagnostics::emit(
+            $crate::diagnostics::Level::Warn,
+            $area,
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! info {
+    ($area:literal, $($arg:tt)+) => {
+        ::tracing::info!(target: concat!("spottedcat::", $area), $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! info {
+    ($area:literal, $($arg:tt)+) => {
+        $crate::diagnostics::emit(
+            $crate::diagnostics::Level::Info,
+            $area,
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[cfg(feature = "tracing")]
+#[allow(unused_macros)]
+macro_rules! debug {
+    ($area:literal, $($arg:tt)+) => {
+        ::tracing::debug!(target: concat!("spottedcat::", $area), $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+#[allow(unused_macros)]
+macro_rules! debug {
+    ($area:literal, $($arg:tt)+) => {
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    };
+}
+
+/// Enters a span named `$name` until the returned guard is dropped.
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($area:literal, $name:literal) => {
+        ::tracing::debug_span!(target: concat!("spottedcat::", $area), $name).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($area:literal, $name:literal) => {
+        $crate::diagnostics::NoSpan
+    };
+}
+
+/// Stands in for a span guard in builds without `tracing`.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+// Only Android code logs at debug level so far.
+#[allow(unused_imports)]
+pub(crate) use debug;
+// A macro named `warn` cannot be imported by name next to the `#[warn]` attribute.
+pub(crate) use {error, info, span, warning as warn};
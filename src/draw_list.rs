@@ -0,0 +1,180 @@
+use crate::drawable::{DrawCommand, ImageCommand, TextCommand};
+use crate::{Context, DrawOption, Image, ImageShaderBindings, ShaderOpts, Text};
+
+/// Draw commands recorded without a [`Context`], so they can be built on worker threads.
+///
+/// A `DrawList` is `Send`: split a large world into chunks, record each chunk's visible
+/// images and text into its own list in parallel, then merge them with
+/// [`append`](Self::append) and hand the result to
+/// [`Context::append_draw_list`] during `draw`. Commands keep the order they were
+/// recorded in, and appended lists follow the ones before them.
+///
+/// ```rust,no_run
+/// # use spottedcat::{Context, DrawList, DrawOption, Image};
+/// # fn draw(ctx: &mut Context, screen: Image, chunks: &[Vec<(Image, DrawOption)>]) {
+/// let lists: Vec<DrawList> = std::thread::scope(|scope| {
+///     let workers: Vec<_> = chunks
+///         .iter()
+///         .map(|chunk| {
+///             scope.spawn(move || {
+///                 let mut list = DrawList::new();
+///                 for (tile, options) in chunk {
+///                     list.draw(screen, tile, *options);
+///                 }
+///                 list
+///             })
+///         })
+///         .collect();
+///     workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+/// });
+/// for list in lists {
+///     ctx.append_draw_list(list);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DrawList {
+    commands: Vec<(Image, DrawCommand)>,
+}
+
+impl DrawList {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty list with room for `capacity` commands.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            commands: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Records drawing `image` into `target`, like `target.draw(ctx, image, options)`.
+    pub fn draw(&mut self, target: Image, image: &Image, options: DrawOption) {
+        self.draw_with_shader(target, image, 0, options, ShaderOpts::default());
+    }
+
+    /// Records drawing `image` into `target` with a custom image shader, like
+    /// [`Image::draw_with_shader`].
+    pub fn draw_with_shader(
+        &mut self,
+        target: Image,
+        image: &Image,
+        shader_id: u32,
+        options: DrawOption,
+        shader_opts: ShaderOpts,
+    ) {
+        let command = ImageCommand {
+            id: image.id,
+            target_texture_id: 0,
+            opts: options,
+            shader_id,
+            shader_opts,
+            shader_bindings: ImageShaderBindings::default(),
+            size: [image.width, image.height],
+        };
+        self.commands.push((target, DrawCommand::Image(command)));
+    }
+
+    /// Records drawing `text` into `target`, like `target.draw(ctx, text, options)`.
+    pub fn draw_text(&mut self, target: Image, text: &Text, options: DrawOption) {
+        let command = TextCommand {
+            target_texture_id: 0,
+            text: text.clone_for_draw(),
+            opts: options,
+        };
+        self.commands.push((target, DrawCommand::Text(command)));
+    }
+
+    /// Moves all commands of `other` to the end of this list, leaving `other` empty.
+    pub fn append(&mut self, other: &mut DrawList) {
+        self.commands.append(&mut other.commands);
+    }
+
+    /// Returns the number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Removes all recorded commands, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl Context {
+    /// Submits the commands of `list` as if they were drawn now, after everything drawn
+    /// so far this frame.
+    ///
+    /// Targets are checked here rather than while recording, so this panics like
+    /// `target.draw` does if a command targets an image that is not a render target.
+    pub fn append_draw_list(&mut self, list: DrawList) {
+        self.runtime.draw_list.reserve(list.commands.len());
+        for (target, mut command) in list.commands {
+            let target_texture_id = self.resolve_target_texture_id(target);
+            match &mut command {
+                DrawCommand::Image(image) => image.target_texture_id = target_texture_id,
+                DrawCommand::Text(text) => text.target_texture_id = target_texture_id,
+            }
+            self.push(command);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pt, Texture};
+
+    #[test]
+    fn lists_built_on_other_threads_keep_their_order_and_targets() {
+        fn assert_send<T: Send>() {}
+        assert_send::<DrawList>();
+
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt(320.0), Pt(240.0));
+        let screen = crate::window::make_screen_target(&ctx);
+        let target = Texture::new_render_target(&mut ctx, Pt(64.0), Pt(64.0)).view();
+        let tile = Image::new(&mut ctx, Pt(8.0), Pt(8.0), &[255; 8 * 8 * 4]).unwrap();
+        let at = |x: f32| DrawOption::default().with_position([Pt(x), Pt(0.0)]);
+
+        let (mut first, mut second) = std::thread::scope(|scope| {
+            let first = scope.spawn(|| {
+                let mut list = DrawList::new();
+                list.draw(screen, &tile, at(1.0));
+                list.draw(target, &tile, at(2.0));
+                list
+            });
+            let second = scope.spawn(|| {
+                let mut list = DrawList::with_capacity(1);
+                list.draw(screen, &tile, at(3.0));
+                list
+            });
+            (first.join().unwrap(), second.join().unwrap())
+        });
+        first.append(&mut second);
+        assert!(second.is_empty());
+        assert_eq!(first.len(), 3);
+        ctx.append_draw_list(first);
+
+        let placed: Vec<_> = ctx
+            .runtime
+            .draw_list
+            .iter()
+            .map(|command| match command {
+                DrawCommand::Image(image) => (image.opts.position()[0], image.target_texture_id),
+                DrawCommand::Text(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(
+            placed,
+            [(Pt(1.0), 0), (Pt(2.0), target.texture_id), (Pt(3.0), 0)]
+        );
+    }
+}
@@ -31,17 +31,21 @@ pub(crate) struct ImageCommand {
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct TextCommand {
     pub target_texture_id: u32,
-    pub text: Box<Text>,
+    pub text: Text,
     pub opts: DrawOption,
 }
 
+// Commands are stored inline so the draw list, which is reused across frames, records a
+// frame without allocating.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum DrawCommand {
-    Image(Box<ImageCommand>),
-    Text(Box<TextCommand>),
+    Image(ImageCommand),
+    Text(TextCommand),
 }
 
 /// Controls how an image is sampled when the drawn quad is larger than one tile.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ImageRepeat {
     /// Scale the image to fill the drawn quad.
@@ -64,6 +68,11 @@ pub enum ImageRepeat {
 /// Unified options for drawing images and text.
 ///
 /// Controls the position, rotation, and scale of drawn items.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DrawOption {
     /// Position in logical units relative to the target's top-left corner.
@@ -73,6 +82,7 @@ pub struct DrawOption {
     /// Scale factors (x, y). Applied after size.
     scale: [f32; 2],
     opacity: f32,
+    tint: [f32; 4],
     repeat: ImageRepeat,
     tile_size: Option<[Pt; 2]>,
 }
@@ -84,6 +94,7 @@ impl Default for DrawOption {
             scale: [1.0, 1.0],
             rotation: 0.0,
             opacity: 1.0,
+            tint: [1.0, 1.0, 1.0, 1.0],
             repeat: ImageRepeat::Stretch,
             tile_size: None,
         }
@@ -98,6 +109,7 @@ impl DrawOption {
             rotation,
             scale,
             opacity: 1.0,
+            tint: [1.0, 1.0, 1.0, 1.0],
             repeat: ImageRepeat::Stretch,
             tile_size: None,
         }
@@ -147,6 +159,19 @@ impl DrawOption {
         self
     }
 
+    pub fn tint(&self) -> [f32; 4] {
+        self.tint
+    }
+
+    /// Multiplies the image's colors by `tint`; white leaves them unchanged.
+    ///
+    /// Applied per draw by the default image shader, so draws with different tints still
+    /// batch together. Custom image shaders ignore it.
+    pub fn with_tint(mut self, tint: [f32; 4]) -> Self {
+        self.tint = tint;
+        self
+    }
+
     pub fn repeat(&self) -> ImageRepeat {
         self.repeat
     }
@@ -170,3 +195,54 @@ impl DrawOption {
         self
     }
 }
+
+/// Instance counts of the last rendered frame, across the screen and all render targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawStats {
+    /// Image and glyph quads submitted to the GPU.
+    pub drawn: u32,
+    /// Quads skipped because they lay entirely outside their target.
+    pub culled: u32,
+}
+
+/// Renderer work of the last rendered frame, returned by [`Context::frame_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FrameStats {
+    /// Instanced draw calls issued for images and text.
+    pub draw_calls: u32,
+    /// Quad instances submitted across those draw calls.
+    pub instances: u32,
+    /// Why consecutive quads could not share a draw call.
+    pub batch_breaks: BatchBreaks,
+    /// Glyph quads submitted for text.
+    pub glyphs: u32,
+    /// Full texture uploads and sub-region writes sent to the GPU.
+    pub texture_uploads: u32,
+}
+
+/// Batch breaks of a frame by cause. Each break is counted once, under the first of
+/// these fields that changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchBreaks {
+    /// The source texture or atlas page, or a shader's extra input textures, changed.
+    pub texture: u32,
+    /// A different image shader was used.
+    pub shader: u32,
+    /// The draw or shader opacity changed.
+    pub opacity: u32,
+    /// The shader uniforms changed.
+    pub shader_opts: u32,
+}
+
+impl FrameStats {
+    pub(crate) fn add(&mut self, other: FrameStats) {
+        self.draw_calls += other.draw_calls;
+        self.instances += other.instances;
+        self.batch_breaks.texture += other.batch_breaks.texture;
+        self.batch_breaks.shader += other.batch_breaks.shader;
+        self.batch_breaks.opacity += other.batch_breaks.opacity;
+        self.batch_breaks.shader_opts += other.batch_breaks.shader_opts;
+        self.glyphs += other.glyphs;
+        self.texture_uploads += other.texture_uploads;
+    }
+}
@@ -0,0 +1,308 @@
+//! Easing functions.
+//!
+//! Each function maps linear progress `t` in `0.0..=1.0` to eased progress, with
+//! `f(0.0) == 0.0` and `f(1.0) == 1.0`. Back and elastic curves overshoot in between.
+//! Use them directly to shape any interpolation, or pick one as an [`Easing`] for
+//! [`tween`](crate::tween).
+
+use std::f32::consts::PI;
+
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+pub fn in_quad(t: f32) -> f32 {
+    t * t
+}
+
+pub fn out_quad(t: f32) -> f32 {
+    1.0 - (1.0 - t) * (1.0 - t)
+}
+
+pub fn in_out_quad(t: f32) -> f32 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+    }
+}
+
+pub fn in_cubic(t: f32) -> f32 {
+    t * t * t
+}
+
+pub fn out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+pub fn in_out_cubic(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+pub fn in_quart(t: f32) -> f32 {
+    t.powi(4)
+}
+
+pub fn out_quart(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(4)
+}
+
+pub fn in_out_quart(t: f32) -> f32 {
+    if t < 0.5 {
+        8.0 * t.powi(4)
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(4) / 2.0
+    }
+}
+
+pub fn in_sine(t: f32) -> f32 {
+    1.0 - (t * PI / 2.0).cos()
+}
+
+pub fn out_sine(t: f32) -> f32 {
+    (t * PI / 2.0).sin()
+}
+
+pub fn in_out_sine(t: f32) -> f32 {
+    -((PI * t).cos() - 1.0) / 2.0
+}
+
+pub fn in_expo(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else {
+        2f32.powf(10.0 * t - 10.0)
+    }
+}
+
+pub fn out_expo(t: f32) -> f32 {
+    if t >= 1.0 {
+        1.0
+    } else {
+        1.0 - 2f32.powf(-10.0 * t)
+    }
+}
+
+pub fn in_out_expo(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else if t < 0.5 {
+        2f32.powf(20.0 * t - 10.0) / 2.0
+    } else {
+        (2.0 - 2f32.powf(-20.0 * t + 10.0)) / 2.0
+    }
+}
+
+const BACK: f32 = 1.70158;
+
+/// Pulls back below zero before heading to the end.
+pub fn in_back(t: f32) -> f32 {
+    (BACK + 1.0) * t * t * t - BACK * t * t
+}
+
+/// Overshoots the end slightly before settling.
+pub fn out_back(t: f32) -> f32 {
+    1.0 + (BACK + 1.0) * (t - 1.0).powi(3) + BACK * (t - 1.0).powi(2)
+}
+
+pub fn in_out_back(t: f32) -> f32 {
+    const C2: f32 = BACK * 1.525;
+    if t < 0.5 {
+        (2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2) / 2.0
+    } else {
+        ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
+    }
+}
+
+/// Winds up with growing oscillations, like a stretched spring.
+pub fn in_elastic(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t.clamp(0.0, 1.0);
+    }
+    -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+}
+
+/// Overshoots and wobbles around the end, like a released spring.
+pub fn out_elastic(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t.clamp(0.0, 1.0);
+    }
+    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+}
+
+pub fn in_out_elastic(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t.clamp(0.0, 1.0);
+    }
+    let wave = ((20.0 * t - 11.125) * (2.0 * PI / 4.5)).sin();
+    if t < 0.5 {
+        -(2f32.powf(20.0 * t - 10.0) * wave) / 2.0
+    } else {
+        2f32.powf(-20.0 * t + 10.0) * wave / 2.0 + 1.0
+    }
+}
+
+pub fn in_bounce(t: f32) -> f32 {
+    1.0 - out_bounce(1.0 - t)
+}
+
+/// Bounces to rest at the end, like a dropped ball.
+pub fn out_bounce(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+pub fn in_out_bounce(t: f32) -> f32 {
+    if t < 0.5 {
+        (1.0 - out_bounce(1.0 - 2.0 * t)) / 2.0
+    } else {
+        (1.0 + out_bounce(2.0 * t - 1.0)) / 2.0
+    }
+}
+
+/// An easing function from this module, chosen by name, e.g. for a [`tween`](crate::tween).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InQuart,
+    OutQuart,
+    InOutQuart,
+    InSine,
+    OutSine,
+    InOutSine,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    InBack,
+    OutBack,
+    InOutBack,
+    InElastic,
+    OutElastic,
+    InOutElastic,
+    InBounce,
+    OutBounce,
+    InOutBounce,
+}
+
+impl Easing {
+    /// The easing function this variant names.
+    pub fn function(self) -> fn(f32) -> f32 {
+        match self {
+            Easing::Linear => linear,
+            Easing::InQuad => in_quad,
+            Easing::OutQuad => out_quad,
+            Easing::InOutQuad => in_out_quad,
+            Easing::InCubic => in_cubic,
+            Easing::OutCubic => out_cubic,
+            Easing::InOutCubic => in_out_cubic,
+            Easing::InQuart => in_quart,
+            Easing::OutQuart => out_quart,
+            Easing::InOutQuart => in_out_quart,
+            Easing::InSine => in_sine,
+            Easing::OutSine => out_sine,
+            Easing::InOutSine => in_out_sine,
+            Easing::InExpo => in_expo,
+            Easing::OutExpo => out_expo,
+            Easing::InOutExpo => in_out_expo,
+            Easing::InBack => in_back,
+            Easing::OutBack => out_back,
+            Easing::InOutBack => in_out_back,
+            Easing::InElastic => in_elastic,
+            Easing::OutElastic => out_elastic,
+            Easing::InOutElastic => in_out_elastic,
+            Easing::InBounce => in_bounce,
+            Easing::OutBounce => out_bounce,
+            Easing::InOutBounce => in_out_bounce,
+        }
+    }
+
+    /// Eased progress for linear progress `t`, which is clamped to `0.0..=1.0`.
+    pub fn apply(self, t: f32) -> f32 {
+        self.function()(t.clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 25] = [
+        Easing::Linear,
+        Easing::InQuad,
+        Easing::OutQuad,
+        Easing::InOutQuad,
+        Easing::InCubic,
+        Easing::OutCubic,
+        Easing::InOutCubic,
+        Easing::InQuart,
+        Easing::OutQuart,
+        Easing::InOutQuart,
+        Easing::InSine,
+        Easing::OutSine,
+        Easing::InOutSine,
+        Easing::InExpo,
+        Easing::OutExpo,
+        Easing::InOutExpo,
+        Easing::InBack,
+        Easing::OutBack,
+        Easing::InOutBack,
+        Easing::InElastic,
+        Easing::OutElastic,
+        Easing::InOutElastic,
+        Easing::InBounce,
+        Easing::OutBounce,
+        Easing::InOutBounce,
+    ];
+
+    #[test]
+    fn every_easing_starts_at_zero_and_ends_at_one() {
+        for easing in ALL {
+            assert!(easing.apply(0.0).abs() < 1e-3, "{easing:?} at 0");
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-3, "{easing:?} at 1");
+            assert!(easing.apply(-1.0).abs() < 1e-3, "{easing:?} clamps");
+        }
+    }
+
+    #[test]
+    fn in_out_curves_are_symmetric_around_the_midpoint() {
+        for easing in [
+            in_out_quad,
+            in_out_cubic,
+            in_out_quart,
+            in_out_sine,
+            in_out_expo,
+            in_out_back,
+            in_out_bounce,
+        ] {
+            assert!((easing(0.5) - 0.5).abs() < 1e-3);
+            assert!((easing(0.2) + easing(0.8) - 1.0).abs() < 1e-3);
+        }
+        assert!(out_back(0.8) > 1.0);
+        assert!(out_elastic(0.2) > 1.0);
+    }
+}
@@ -0,0 +1,427 @@
+//! C ABI for embedding spottedcat as the rendering layer of a non-Rust application.
+//!
+//! The host registers its callbacks with `spot_set_callbacks` and hands control to
+//! `spot_run`, which opens the window and calls back into C on every update and draw.
+//! Inside a callback the `SpotContext` pointer is valid for the other `spot_*`
+//! functions. Images are referred to by their `u32` ID; `0` is the screen and is never
+//! returned for a created image. The matching declarations are in
+//! `include/spottedcat.h`.
+//!
+//! Everything must be called on the thread that called `spot_run`.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::{CString, c_char, c_void};
+use std::time::Duration;
+
+use crate::{Context, DrawOption, Image, MouseButton, Pt, Spot};
+
+/// The engine context as seen from C; only ever used behind a pointer.
+#[repr(C)]
+pub struct SpotContext {
+    _private: [u8; 0],
+}
+
+fn as_spot_context(ctx: &mut Context) -> *mut SpotContext {
+    (ctx as *mut Context).cast()
+}
+
+/// # Safety
+/// `ctx` must be null or a pointer handed to a callback that is still running.
+unsafe fn context_mut<'a>(ctx: *mut SpotContext) -> Option<&'a mut Context> {
+    unsafe { ctx.cast::<Context>().as_mut() }
+}
+
+/// # Safety
+/// See [`context_mut`].
+unsafe fn context_ref<'a>(ctx: *const SpotContext) -> Option<&'a Context> {
+    unsafe { ctx.cast::<Context>().as_ref() }
+}
+
+/// Functions the engine calls back into; any of them may be null.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SpotCallbacks {
+    /// Passed back unchanged as the last argument of every callback.
+    pub user_data: *mut c_void,
+    pub init: Option<extern "C" fn(ctx: *mut SpotContext, user_data: *mut c_void)>,
+    /// Called on the fixed timestep with the step in seconds.
+    pub update: Option<extern "C" fn(ctx: *mut SpotContext, dt: f64, user_data: *mut c_void)>,
+    /// Called once per frame with the ID of the screen image to draw into.
+    pub draw: Option<extern "C" fn(ctx: *mut SpotContext, screen: u32, user_data: *mut c_void)>,
+    pub exit: Option<extern "C" fn(ctx: *mut SpotContext, user_data: *mut c_void)>,
+}
+
+/// Window settings for `spot_run`; zero fields fall back to the defaults.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SpotWindowConfig {
+    /// NUL-terminated UTF-8 title, or null for the default.
+    pub title: *const c_char,
+    pub width: f32,
+    pub height: f32,
+    pub resizable: bool,
+    pub fullscreen: bool,
+    pub update_hz: u32,
+}
+
+thread_local! {
+    static CALLBACKS: Cell<Option<SpotCallbacks>> = const { Cell::new(None) };
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Images created through the C API, by ID.
+#[derive(Debug, Default)]
+struct FfiImages(HashMap<u32, Image>);
+
+fn set_last_error(message: impl std::fmt::Display) {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|error| *error.borrow_mut() = None);
+}
+
+fn lookup_image(ctx: &mut Context, id: u32) -> Option<Image> {
+    if id == 0 {
+        return Some(crate::window::make_screen_target(ctx));
+    }
+    ctx.get_resource_mut::<FfiImages>()?.0.get(&id).copied()
+}
+
+/// The scene driven by the registered C callbacks.
+struct FfiSpot {
+    callbacks: SpotCallbacks,
+}
+
+impl Spot for FfiSpot {
+    fn initialize(ctx: &mut Context) -> Self {
+        let callbacks = CALLBACKS.with(Cell::get).unwrap_or(SpotCallbacks {
+            user_data: std::ptr::null_mut(),
+            init: None,
+            update: None,
+            draw: None,
+            exit: None,
+        });
+        if let Some(init) = callbacks.init {
+            init(as_spot_context(ctx), callbacks.user_data);
+        }
+        Self { callbacks }
+    }
+
+    fn update(&mut self, ctx: &mut Context, dt: Duration) {
+        if let Some(update) = self.callbacks.update {
+            update(
+                as_spot_context(ctx),
+                dt.as_secs_f64(),
+                self.callbacks.user_data,
+            );
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _screen: Image) {
+        if let Some(draw) = self.callbacks.draw {
+            draw(as_spot_context(ctx), 0, self.callbacks.user_data);
+        }
+    }
+
+    fn on_exit(&mut self, ctx: &mut Context) {
+        if let Some(exit) = self.callbacks.exit {
+            exit(as_spot_context(ctx), self.callbacks.user_data);
+        }
+    }
+}
+
+/// Registers the callbacks used by the next `spot_run`.
+///
+/// # Safety
+/// `callbacks` must be null or point to a valid `SpotCallbacks`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_set_callbacks(callbacks: *const SpotCallbacks) {
+    let callbacks = unsafe { callbacks.as_ref() }.copied();
+    CALLBACKS.with(|cell| cell.set(callbacks));
+}
+
+/// Opens the window and runs until it closes. Returns `0` on success and `-1` on
+/// failure, with the reason available from `spot_last_error`.
+///
+/// # Safety
+/// `config` must be null or point to a valid `SpotWindowConfig` whose `title` is null or
+/// a NUL-terminated string.
+#[cfg(not(any(target_os = "android", target_arch = "wasm32")))]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_run(config: *const SpotWindowConfig) -> i32 {
+    clear_last_error();
+    let mut window = crate::WindowConfig::default();
+    if let Some(config) = unsafe { config.as_ref() } {
+        if !config.title.is_null() {
+            window.title = unsafe { std::ffi::CStr::from_ptr(config.title) }
+                .to_string_lossy()
+                .into_owned();
+        }
+        if config.width > 0.0 {
+            window.width = Pt(config.width);
+        }
+        if config.height > 0.0 {
+            window.height = Pt(config.height);
+        }
+        if config.update_hz > 0 {
+            window.update_hz = config.update_hz;
+        }
+        window.resizable = config.resizable;
+        window.fullscreen = config.fullscreen;
+    }
+    match crate::run::<FfiSpot>(window) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(e);
+            -1
+        }
+    }
+}
+
+/// The message of the last failed call on this thread, or null. Calls that can fail
+/// (`spot_run` and `spot_image_create`) clear it when they start, so it always belongs to
+/// the latest of them. The string stays valid until the next such call.
+#[unsafe(no_mangle)]
+pub extern "C" fn spot_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Asks the running application to exit after the current frame.
+#[unsafe(no_mangle)]
+pub extern "C" fn spot_quit() {
+    crate::quit();
+}
+
+/// Creates an image from `width * height` RGBA8 pixels and returns its ID, or `0` on
+/// failure.
+///
+/// # Safety
+/// `ctx` must be the pointer passed to a callback and `rgba` must point to `len`
+/// readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_image_create(
+    ctx: *mut SpotContext,
+    width: u32,
+    height: u32,
+    rgba: *const u8,
+    len: usize,
+) -> u32 {
+    clear_last_error();
+    let Some(ctx) = (unsafe { context_mut(ctx) }) else {
+        set_last_error("null context");
+        return 0;
+    };
+    let Some(expected) = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(4))
+    else {
+        set_last_error(format!("a {width}x{height} image is too large"));
+        return 0;
+    };
+    if rgba.is_null() || width == 0 || height == 0 || len != expected {
+        set_last_error(format!(
+            "expected {expected} bytes of RGBA for a {width}x{height} image, got {len}"
+        ));
+        return 0;
+    }
+    let rgba = unsafe { std::slice::from_raw_parts(rgba, len) };
+    match Image::new(ctx, Pt(width as f32), Pt(height as f32), rgba) {
+        Ok(image) => {
+            if ctx.get_resource_mut::<FfiImages>().is_none() {
+                ctx.insert_resource_value(FfiImages::default());
+            }
+            if let Some(images) = ctx.get_resource_mut::<FfiImages>() {
+                images.0.insert(image.id(), image);
+            }
+            image.id()
+        }
+        Err(e) => {
+            set_last_error(e);
+            0
+        }
+    }
+}
+
+/// Destroys an image created with `spot_image_create`.
+///
+/// # Safety
+/// `ctx` must be the pointer passed to a callback.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_image_destroy(ctx: *mut SpotContext, image: u32) {
+    let Some(ctx) = (unsafe { context_mut(ctx) }) else {
+        return;
+    };
+    let removed = ctx
+        .get_resource_mut::<FfiImages>()
+        .and_then(|images| images.0.remove(&image));
+    if let Some(image) = removed {
+        image.destroy(ctx);
+    }
+}
+
+/// Draws `image` into `target` (`0` for the screen) with its top-left corner at `x`,
+/// `y`, rotated by `rotation` radians and scaled by `scale_x`, `scale_y`.
+///
+/// Returns false if either ID is unknown.
+///
+/// # Safety
+/// `ctx` must be the pointer passed to a callback.
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_draw_image(
+    ctx: *mut SpotContext,
+    target: u32,
+    image: u32,
+    x: f32,
+    y: f32,
+    rotation: f32,
+    scale_x: f32,
+    scale_y: f32,
+) -> bool {
+    let Some(ctx) = (unsafe { context_mut(ctx) }) else {
+        return false;
+    };
+    let (Some(target), Some(image)) = (lookup_image(ctx, target), lookup_image(ctx, image)) else {
+        return false;
+    };
+    let options = DrawOption::new([Pt(x), Pt(y)], rotation, [scale_x, scale_y]);
+    target.draw(ctx, &image, options);
+    true
+}
+
+/// Returns whether a mouse button is held: `0` left, `1` right, `2` middle, `3` back,
+/// `4` forward.
+///
+/// # Safety
+/// `ctx` must be the pointer passed to a callback.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_mouse_down(ctx: *const SpotContext, button: u32) -> bool {
+    let Some(ctx) = (unsafe { context_ref(ctx) }) else {
+        return false;
+    };
+    let button = match button {
+        0 => MouseButton::Left,
+        1 => MouseButton::Right,
+        2 => MouseButton::Middle,
+        3 => MouseButton::Back,
+        4 => MouseButton::Forward,
+        other => MouseButton::Other(other as u16),
+    };
+    crate::mouse_down(ctx, button)
+}
+
+/// Writes the cursor position in logical points to `x` and `y`; returns false, leaving
+/// them untouched, while the cursor is outside the window.
+///
+/// # Safety
+/// `ctx` must be the pointer passed to a callback and `x`, `y` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_cursor_position(
+    ctx: *const SpotContext,
+    x: *mut f32,
+    y: *mut f32,
+) -> bool {
+    let Some(ctx) = (unsafe { context_ref(ctx) }) else {
+        return false;
+    };
+    let Some((cx, cy)) = ctx.input().cursor_position() else {
+        return false;
+    };
+    unsafe {
+        if let Some(x) = x.as_mut() {
+            *x = cx.as_f32();
+        }
+        if let Some(y) = y.as_mut() {
+            *y = cy.as_f32();
+        }
+    }
+    true
+}
+
+/// Returns the logical window size through `width` and `height`.
+///
+/// # Safety
+/// `ctx` must be the pointer passed to a callback and `width`, `height` must be
+/// writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spot_window_size(
+    ctx: *const SpotContext,
+    width: *mut f32,
+    height: *mut f32,
+) {
+    let Some(ctx) = (unsafe { context_ref(ctx) }) else {
+        return;
+    };
+    let (w, h) = ctx.window_logical_size();
+    unsafe {
+        if let Some(width) = width.as_mut() {
+            *width = w.as_f32();
+        }
+        if let Some(height) = height.as_mut() {
+            *height = h.as_f32();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn images_are_created_drawn_and_destroyed_by_id() {
+        let mut ctx = Context::new();
+        let ctx_ptr = as_spot_context(&mut ctx);
+        let pixels = [255u8; 2 * 2 * 4];
+
+        let id = unsafe { spot_image_create(ctx_ptr, 2, 2, pixels.as_ptr(), pixels.len()) };
+        assert_ne!(id, 0);
+        assert!(unsafe { spot_draw_image(ctx_ptr, 0, id, 1.0, 2.0, 0.0, 1.0, 1.0) });
+        assert_eq!(ctx.runtime.draw_list.len(), 1);
+
+        unsafe { spot_image_destroy(ctx_ptr, id) };
+        assert!(!unsafe { spot_draw_image(ctx_ptr, 0, id, 0.0, 0.0, 0.0, 1.0, 1.0) });
+    }
+
+    #[test]
+    fn wrong_pixel_length_reports_an_error() {
+        let mut ctx = Context::new();
+        let pixels = [0u8; 3];
+        let id = unsafe {
+            spot_image_create(
+                as_spot_context(&mut ctx),
+                2,
+                2,
+                pixels.as_ptr(),
+                pixels.len(),
+            )
+        };
+        assert_eq!(id, 0);
+        let message = unsafe { CStr::from_ptr(spot_last_error()) };
+        assert!(message.to_string_lossy().contains("expected 16 bytes"));
+    }
+
+    #[test]
+    fn successful_calls_clear_the_last_error() {
+        let mut ctx = Context::new();
+        let ctx_ptr = as_spot_context(&mut ctx);
+        let pixels = [255u8; 4];
+        assert_eq!(
+            unsafe { spot_image_create(ctx_ptr, 1, 1, pixels.as_ptr(), 3) },
+            0
+        );
+        assert!(!spot_last_error().is_null());
+
+        let id = unsafe { spot_image_create(ctx_ptr, 1, 1, pixels.as_ptr(), pixels.len()) };
+        assert_ne!(id, 0);
+        assert!(spot_last_error().is_null());
+    }
+}
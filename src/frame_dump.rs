@@ -0,0 +1,185 @@
+//! Writing one frame's draw commands to JSON, to attach to bug reports or diff between
+//! versions of a game or of the engine.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::drawable::DrawCommand;
+use crate::{Context, DrawOption};
+
+#[derive(Serialize)]
+struct FrameDump {
+    window_size: [f32; 2],
+    scale_factor: f64,
+    commands: Vec<CommandDump>,
+}
+
+/// Content drawn into a target is clipped to the target's size; `[0, 0]` for missing
+/// targets.
+#[derive(Serialize)]
+struct Clip {
+    target_texture_id: u32,
+    size: [f32; 2],
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CommandDump {
+    Image {
+        image_id: u32,
+        texture_id: Option<u32>,
+        render_target: bool,
+        size: [f32; 2],
+        clip: Clip,
+        options: DrawOption,
+        shader_id: u32,
+        shader_opacity: f32,
+        shader_params: Vec<[f32; 4]>,
+        history: bool,
+        screen: bool,
+    },
+    Text {
+        text: String,
+        font_id: u32,
+        font_size: f32,
+        color: [f32; 4],
+        stroke_width: f32,
+        stroke_color: [f32; 4],
+        max_width: Option<f32>,
+        clip: Clip,
+        options: DrawOption,
+    },
+}
+
+impl Context {
+    /// Writes the draw commands of the current frame to `path` as JSON once its scenes
+    /// have drawn.
+    ///
+    /// The dump lists, in drawing order, every command the scenes and transitions
+    /// queued after culling: image and texture ids, [`DrawOption`]s, shader ids and
+    /// parameters, the target each command is clipped to and the text with its font
+    /// settings. The debug overlay is left out. Failures are logged.
+    pub fn dump_frame(&mut self, path: impl Into<PathBuf>) {
+        self.runtime.pending_frame_dump = Some(path.into());
+    }
+}
+
+/// Writes the requested dump once the frame's scenes have drawn.
+pub(crate) fn write_pending(ctx: &mut Context) {
+    let Some(path) = ctx.runtime.pending_frame_dump.take() else {
+        return;
+    };
+    let result = to_json(ctx)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| Ok(std::fs::write(&path, json)?));
+    match result {
+        Ok(()) => crate::diagnostics::info!("debug", "Dumped frame to {}", path.display()),
+        Err(error) => crate::diagnostics::warn!(
+            "debug",
+            "failed to dump frame to {}: {}",
+            path.display(),
+            error
+        ),
+    }
+}
+
+fn to_json(ctx: &Context) -> serde_json::Result<String> {
+    let (width, height) = ctx.runtime.window_logical_size;
+    let dump = FrameDump {
+        window_size: [width.as_f32(), height.as_f32()],
+        scale_factor: ctx.runtime.scale_factor,
+        commands: ctx
+            .runtime
+            .draw_list
+            .iter()
+            .map(|command| command_dump(ctx, command))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&dump)
+}
+
+fn command_dump(ctx: &Context, command: &DrawCommand) -> CommandDump {
+    let clip = |target_texture_id: u32| Clip {
+        target_texture_id,
+        size: ctx
+            .target_logical_size(target_texture_id)
+            .map_or([0.0, 0.0], |(w, h)| [w.as_f32(), h.as_f32()]),
+    };
+    match command {
+        DrawCommand::Image(cmd) => {
+            let texture_id = ctx
+                .registry
+                .images
+                .get(cmd.id as usize)
+                .and_then(Option::as_ref)
+                .map(|image| image.texture_id);
+            let render_target = texture_id
+                .and_then(|id| ctx.registry.textures.get(id as usize))
+                .and_then(Option::as_ref)
+                .is_some_and(|texture| texture.is_render_target());
+            let mut shader_opts = cmd.shader_opts;
+            CommandDump::Image {
+                image_id: cmd.id,
+                texture_id,
+                render_target,
+                size: cmd.size.map(|size| size.as_f32()),
+                clip: clip(cmd.target_texture_id),
+                options: cmd.opts,
+                shader_id: cmd.shader_id,
+                shader_opacity: shader_opts.opacity,
+                shader_params: shader_opts.as_vec4_mut(),
+                history: cmd.shader_bindings.history,
+                screen: cmd.shader_bindings.screen,
+            }
+        }
+        DrawCommand::Text(cmd) => CommandDump::Text {
+            text: cmd.text.content.to_string(),
+            font_id: cmd.text.font_id,
+            font_size: cmd.text.font_size.as_f32(),
+            color: cmd.text.color,
+            stroke_width: cmd.text.stroke_width.as_f32(),
+            stroke_color: cmd.text.stroke_color,
+            max_width: cmd.text.max_width.map(|width| width.as_f32()),
+            clip: clip(cmd.target_texture_id),
+            options: cmd.opts,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Image, Pt, Text};
+
+    #[test]
+    fn dump_lists_images_and_text_in_draw_order() {
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt(320.0), Pt(240.0));
+        let screen = crate::window::make_screen_target(&ctx);
+        let image = Image::new(&mut ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap();
+        screen.draw(
+            &mut ctx,
+            &image,
+            DrawOption::default().with_position([Pt(10.0), Pt(20.0)]),
+        );
+        screen.draw(&mut ctx, &Text::new("hi", 7), DrawOption::default());
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&ctx).unwrap()).unwrap();
+        let commands = json["commands"].as_array().unwrap();
+        assert_eq!(json["window_size"], serde_json::json!([320.0, 240.0]));
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0]["kind"], "image");
+        assert_eq!(commands[0]["image_id"], image.id);
+        assert_eq!(
+            commands[0]["options"]["position"],
+            serde_json::json!([10.0, 20.0])
+        );
+        assert_eq!(
+            commands[0]["clip"]["size"],
+            serde_json::json!([320.0, 240.0])
+        );
+        assert_eq!(commands[1]["kind"], "text");
+        assert_eq!(commands[1]["text"], "hi");
+    }
+}
@@ -1,7 +1,8 @@
 use crate::input::InputManager;
 
 /// Stable identifier assigned to a connected gamepad.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GamepadId(pub u32);
 
 /// Basic information about a gamepad known to the input system.
@@ -13,7 +14,8 @@ pub struct GamepadInfo {
 }
 
 /// Logical gamepad buttons exposed by spottedcat.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum GamepadButton {
     South,
     East,
@@ -38,7 +40,8 @@ pub enum GamepadButton {
 }
 
 /// Logical gamepad axes exposed by spottedcat.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum GamepadAxis {
     LeftX,
     LeftY,
@@ -110,7 +113,7 @@ mod gilrs_backend {
             let gilrs = match Gilrs::new() {
                 Ok(gilrs) => Some(gilrs),
                 Err(err) => {
-                    eprintln!("[spot][gamepad] gilrs initialization failed: {err}");
+                    crate::diagnostics::error!("gamepad", "gilrs initialization failed: {err}");
                     None
                 }
             };
@@ -0,0 +1,391 @@
+//! Shapes for hit testing and simple collisions, in the same logical coordinates as
+//! drawing.
+//!
+//! Edges count as inside, so shapes that only touch are reported as intersecting.
+
+use crate::{Bounds, Pt};
+
+/// An axis-aligned rectangle given by its top-left corner and size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub x: Pt,
+    pub y: Pt,
+    pub width: Pt,
+    pub height: Pt,
+}
+
+impl Rect {
+    pub fn new(x: Pt, y: Pt, width: Pt, height: Pt) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The rectangle centered on `center`.
+    pub fn from_center(center: [Pt; 2], width: Pt, height: Pt) -> Self {
+        Self::new(
+            center[0] - width / 2.0,
+            center[1] - height / 2.0,
+            width,
+            height,
+        )
+    }
+
+    pub fn left(&self) -> Pt {
+        self.x
+    }
+
+    pub fn right(&self) -> Pt {
+        self.x + self.width
+    }
+
+    pub fn top(&self) -> Pt {
+        self.y
+    }
+
+    pub fn bottom(&self) -> Pt {
+        self.y + self.height
+    }
+
+    pub fn center(&self) -> [Pt; 2] {
+        [self.x + self.width / 2.0, self.y + self.height / 2.0]
+    }
+
+    pub fn contains_point(&self, point: [Pt; 2]) -> bool {
+        point[0] >= self.left()
+            && point[0] <= self.right()
+            && point[1] >= self.top()
+            && point[1] <= self.bottom()
+    }
+
+    /// Whether `other` lies completely inside this rectangle.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.left() >= self.left()
+            && other.right() <= self.right()
+            && other.top() >= self.top()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.left() <= other.right()
+            && other.left() <= self.right()
+            && self.top() <= other.bottom()
+            && other.top() <= self.bottom()
+    }
+
+    /// The overlapping area of both rectangles.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        Some(Rect::new(left, top, right - left, bottom - top))
+    }
+
+    /// Whether `point` is inside this rectangle after rotating it by `rotation` radians
+    /// about its top-left corner.
+    ///
+    /// This matches how [`DrawOption`](crate::DrawOption) rotates images, so a click can
+    /// be tested against a drawn image with a rectangle at the draw position, sized to the
+    /// image times its scale.
+    pub fn contains_point_rotated(&self, point: [Pt; 2], rotation: f32) -> bool {
+        let (sin, cos) = rotation.sin_cos();
+        let dx = (point[0] - self.x).as_f32();
+        let dy = (point[1] - self.y).as_f32();
+        // Undo the rotation to get the point in the rectangle's own axes.
+        let u = cos * dx - sin * dy;
+        let v = sin * dx + cos * dy;
+        within(u, self.width.as_f32()) && within(v, self.height.as_f32())
+    }
+
+    /// Moves this rectangle by `motion` and finds when it first touches `other`.
+    ///
+    /// Returns `None` when the rectangles do not touch during the move or already
+    /// overlap at its start; use [`Rect::intersects`] for the latter.
+    pub fn sweep(&self, motion: [Pt; 2], other: &Rect) -> Option<SweepHit> {
+        let (entry_x, exit_x) = sweep_axis(
+            motion[0].as_f32(),
+            [self.left().as_f32(), self.right().as_f32()],
+            [other.left().as_f32(), other.right().as_f32()],
+        )?;
+        let (entry_y, exit_y) = sweep_axis(
+            motion[1].as_f32(),
+            [self.top().as_f32(), self.bottom().as_f32()],
+            [other.top().as_f32(), other.bottom().as_f32()],
+        )?;
+        let entry = entry_x.max(entry_y);
+        let exit = exit_x.min(exit_y);
+        if entry > exit || !(0.0..=1.0).contains(&entry) {
+            return None;
+        }
+        // The axis entered last is the side that was hit.
+        let normal = if entry_x > entry_y {
+            [-motion[0].as_f32().signum(), 0.0]
+        } else {
+            [0.0, -motion[1].as_f32().signum()]
+        };
+        Some(SweepHit {
+            time: entry,
+            normal,
+        })
+    }
+}
+
+impl From<Bounds> for Rect {
+    fn from(bounds: Bounds) -> Self {
+        Rect::new(bounds.x, bounds.y, bounds.width, bounds.height)
+    }
+}
+
+impl From<Rect> for Bounds {
+    fn from(rect: Rect) -> Self {
+        Bounds::new(rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+/// Where a [`Rect::sweep`] first touches the other rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    /// Fraction of the motion travelled before touching, in `0.0..=1.0`.
+    pub time: f32,
+    /// Unit normal of the side that was hit, pointing back towards the moving rectangle.
+    pub normal: [f32; 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Circle {
+    pub center: [Pt; 2],
+    pub radius: Pt,
+}
+
+impl Circle {
+    pub fn new(center: [Pt; 2], radius: Pt) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains_point(&self, point: [Pt; 2]) -> bool {
+        distance_squared(self.center, point) <= self.radius.as_f32().powi(2)
+    }
+
+    pub fn intersects(&self, other: &Circle) -> bool {
+        let reach = (self.radius + other.radius).as_f32();
+        distance_squared(self.center, other.center) <= reach * reach
+    }
+
+    pub fn intersects_rect(&self, rect: &Rect) -> bool {
+        let closest = [
+            self.center[0].clamp(rect.left(), rect.right()),
+            self.center[1].clamp(rect.top(), rect.bottom()),
+        ];
+        self.contains_point(closest)
+    }
+
+    /// The smallest rectangle containing the circle.
+    pub fn bounding_rect(&self) -> Rect {
+        let diameter = self.radius * 2.0;
+        Rect::from_center(self.center, diameter, diameter)
+    }
+}
+
+/// A line segment between two points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Line {
+    pub start: [Pt; 2],
+    pub end: [Pt; 2],
+}
+
+impl Line {
+    pub fn new(start: [Pt; 2], end: [Pt; 2]) -> Self {
+        Self { start, end }
+    }
+
+    pub fn length(&self) -> Pt {
+        Pt(distance_squared(self.start, self.end).sqrt())
+    }
+
+    /// The point on the segment nearest to `point`.
+    pub fn closest_point(&self, point: [Pt; 2]) -> [Pt; 2] {
+        let [dx, dy] = delta(self.start, self.end);
+        let [px, py] = delta(self.start, point);
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared == 0.0 {
+            0.0
+        } else {
+            ((px * dx + py * dy) / length_squared).clamp(0.0, 1.0)
+        };
+        [self.start[0] + Pt(dx * t), self.start[1] + Pt(dy * t)]
+    }
+
+    /// Where the two segments cross; parallel segments never cross, even when they
+    /// overlap.
+    pub fn intersection(&self, other: &Line) -> Option<[Pt; 2]> {
+        let [rx, ry] = delta(self.start, self.end);
+        let [sx, sy] = delta(other.start, other.end);
+        let denominator = rx * sy - ry * sx;
+        if denominator == 0.0 {
+            return None;
+        }
+        let [qx, qy] = delta(self.start, other.start);
+        let t = (qx * sy - qy * sx) / denominator;
+        let u = (qx * ry - qy * rx) / denominator;
+        if !(0.0..=1.0).contains(&t) || !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        Some([self.start[0] + Pt(rx * t), self.start[1] + Pt(ry * t)])
+    }
+
+    pub fn intersects(&self, other: &Line) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersects_circle(&self, circle: &Circle) -> bool {
+        circle.contains_point(self.closest_point(circle.center))
+    }
+
+    pub fn intersects_rect(&self, rect: &Rect) -> bool {
+        // Clip the segment against each pair of edges and check that something is left.
+        let [dx, dy] = delta(self.start, self.end);
+        let mut range = [0.0f32, 1.0];
+        let axes = [
+            (
+                self.start[0].as_f32(),
+                dx,
+                rect.left().as_f32(),
+                rect.right().as_f32(),
+            ),
+            (
+                self.start[1].as_f32(),
+                dy,
+                rect.top().as_f32(),
+                rect.bottom().as_f32(),
+            ),
+        ];
+        for (start, direction, min, max) in axes {
+            if direction == 0.0 {
+                if start < min || start > max {
+                    return false;
+                }
+                continue;
+            }
+            let a = (min - start) / direction;
+            let b = (max - start) / direction;
+            range[0] = range[0].max(a.min(b));
+            range[1] = range[1].min(a.max(b));
+            if range[0] > range[1] {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn within(value: f32, extent: f32) -> bool {
+    value >= extent.min(0.0) && value <= extent.max(0.0)
+}
+
+fn delta(from: [Pt; 2], to: [Pt; 2]) -> [f32; 2] {
+    [(to[0] - from[0]).as_f32(), (to[1] - from[1]).as_f32()]
+}
+
+fn distance_squared(a: [Pt; 2], b: [Pt; 2]) -> f32 {
+    let [dx, dy] = delta(a, b);
+    dx * dx + dy * dy
+}
+
+/// Times along `motion` at which a moving span enters and leaves a fixed span.
+fn sweep_axis(motion: f32, moving: [f32; 2], fixed: [f32; 2]) -> Option<(f32, f32)> {
+    if motion == 0.0 {
+        // Without motion the spans must already overlap on this axis.
+        return (moving[0] <= fixed[1] && fixed[0] <= moving[1])
+            .then_some((f32::NEG_INFINITY, f32::INFINITY));
+    }
+    let (entry, exit) = if motion > 0.0 {
+        (fixed[0] - moving[1], fixed[1] - moving[0])
+    } else {
+        (fixed[1] - moving[0], fixed[0] - moving[1])
+    };
+    Some((entry / motion, exit / motion))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect::new(Pt(x), Pt(y), Pt(width), Pt(height))
+    }
+
+    fn pt(x: f32, y: f32) -> [Pt; 2] {
+        [Pt(x), Pt(y)]
+    }
+
+    #[test]
+    fn rects_and_circles_overlap() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        assert!(a.contains_point(pt(10.0, 5.0)));
+        assert!(!a.contains_point(pt(10.5, 5.0)));
+        assert!(a.contains_rect(&rect(2.0, 2.0, 5.0, 5.0)));
+        assert_eq!(
+            a.intersection(&rect(5.0, 8.0, 10.0, 10.0)),
+            Some(rect(5.0, 8.0, 5.0, 2.0))
+        );
+        assert_eq!(a.intersection(&rect(11.0, 0.0, 1.0, 1.0)), None);
+
+        let circle = Circle::new(pt(13.0, 14.0), Pt(5.0));
+        assert!(circle.intersects_rect(&a));
+        assert!(!Circle::new(pt(14.0, 14.0), Pt(5.0)).intersects_rect(&a));
+        assert!(circle.intersects(&Circle::new(pt(13.0, 24.0), Pt(5.0))));
+        assert_eq!(circle.bounding_rect(), rect(8.0, 9.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn rotated_rects_rotate_about_their_top_left_corner() {
+        let a = rect(10.0, 10.0, 20.0, 4.0);
+        assert!(a.contains_point_rotated(pt(25.0, 12.0), 0.0));
+        // A quarter turn swings the long side from pointing right to pointing up.
+        let quarter = std::f32::consts::FRAC_PI_2;
+        assert!(!a.contains_point_rotated(pt(25.0, 12.0), quarter));
+        assert!(a.contains_point_rotated(pt(12.0, -5.0), quarter));
+        // Flipped rectangles extend the other way from the corner.
+        assert!(rect(10.0, 10.0, -20.0, 4.0).contains_point_rotated(pt(0.0, 12.0), 0.0));
+    }
+
+    #[test]
+    fn lines_cross_lines_rects_and_circles() {
+        let diagonal = Line::new(pt(0.0, 0.0), pt(10.0, 10.0));
+        let across = Line::new(pt(0.0, 10.0), pt(10.0, 0.0));
+        assert_eq!(diagonal.intersection(&across), Some(pt(5.0, 5.0)));
+        assert!(!diagonal.intersects(&Line::new(pt(1.0, 0.0), pt(11.0, 10.0))));
+        assert_eq!(Line::new(pt(0.0, 0.0), pt(3.0, 4.0)).length(), Pt(5.0));
+        assert_eq!(diagonal.closest_point(pt(0.0, 10.0)), pt(5.0, 5.0));
+
+        assert!(diagonal.intersects_rect(&rect(8.0, 0.0, 4.0, 9.0)));
+        assert!(!diagonal.intersects_rect(&rect(8.0, 0.0, 4.0, 7.0)));
+        assert!(diagonal.intersects_circle(&Circle::new(pt(0.0, 10.0), Pt(7.1))));
+        assert!(!diagonal.intersects_circle(&Circle::new(pt(0.0, 10.0), Pt(7.0))));
+    }
+
+    #[test]
+    fn sweeps_find_the_first_contact() {
+        let player = rect(0.0, 0.0, 10.0, 10.0);
+        let wall = rect(20.0, -5.0, 5.0, 30.0);
+
+        let hit = player.sweep(pt(20.0, 5.0), &wall).unwrap();
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.normal, [-1.0, 0.0]);
+
+        let floor = rect(-50.0, 30.0, 100.0, 5.0);
+        let hit = player.sweep(pt(0.0, 40.0), &floor).unwrap();
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.normal, [0.0, -1.0]);
+
+        assert_eq!(player.sweep(pt(5.0, 0.0), &wall), None);
+        assert_eq!(player.sweep(pt(20.0, 60.0), &wall), None);
+    }
+}
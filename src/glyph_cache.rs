@@ -1,5 +1,5 @@
 use crate::Image;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Key for caching individual glyphs in the atlas
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -20,12 +20,15 @@ pub(crate) struct GlyphEntry {
 /// Manages glyph rendering and caching to texture atlas
 pub(crate) struct GlyphCache {
     cache: HashMap<GlyphCacheKey, GlyphEntry>,
+    /// Glyphs without an outline, such as spaces, which only advance the caret.
+    blank: HashSet<GlyphCacheKey>,
 }
 
 impl GlyphCache {
     pub(crate) fn new() -> Self {
         Self {
             cache: HashMap::new(),
+            blank: HashSet::new(),
         }
     }
 
@@ -37,8 +40,35 @@ impl GlyphCache {
         self.cache.insert(key, entry);
     }
 
+    pub(crate) fn is_blank(&self, key: &GlyphCacheKey) -> bool {
+        self.blank.contains(key)
+    }
+
+    pub(crate) fn insert_blank(&mut self, key: GlyphCacheKey) {
+        self.blank.insert(key);
+    }
+
+    /// Forgets every glyph of `font_id` and returns their atlas images for release.
+    #[cfg(all(
+        feature = "hot-reload",
+        any(target_os = "windows", target_os = "macos", target_os = "linux")
+    ))]
+    pub(crate) fn remove_font(&mut self, font_id: u32) -> Vec<Image> {
+        self.blank.retain(|key| key.font_id != font_id);
+        let mut images = Vec::new();
+        self.cache.retain(|key, entry| {
+            let keep = key.font_id != font_id;
+            if !keep {
+                images.push(entry.image);
+            }
+            keep
+        });
+        images
+    }
+
     pub(crate) fn clear(&mut self) {
         self.cache.clear();
+        self.blank.clear();
     }
 }
 
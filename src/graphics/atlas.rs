@@ -66,6 +66,37 @@ mod tests {
         assert_eq!(entry.pending_uploads[0].width, 3);
         assert_eq!(entry.pending_uploads[0].height, 3);
     }
+
+    #[test]
+    fn pages_without_live_images_are_reused_from_the_start() {
+        let mut ctx = Context::new();
+        let mut atlas = DynamicAtlas::new(256);
+        let rgba = [255, 0, 0, 255];
+        let add = |atlas: &mut DynamicAtlas, ctx: &mut Context| {
+            atlas
+                .add_region(
+                    &mut ctx.registry,
+                    1.0,
+                    Pt::from(1.0),
+                    Pt::from(1.0),
+                    1,
+                    1,
+                    &rgba,
+                )
+                .expect("atlas insert should succeed")
+        };
+
+        let first = add(&mut atlas, &mut ctx);
+        let second = add(&mut atlas, &mut ctx);
+        assert_ne!(first.pixel_bounds(), second.pixel_bounds());
+        first.destroy(&mut ctx);
+        second.destroy(&mut ctx);
+
+        atlas.reclaim_empty_pages(&ctx.registry);
+        let reused = add(&mut atlas, &mut ctx);
+        assert_eq!(reused.pixel_bounds(), first.pixel_bounds());
+        assert_eq!(atlas.pages.len(), 1);
+    }
 }
 
 impl Node {
@@ -441,6 +472,27 @@ impl DynamicAtlas {
         })
     }
 
+    /// Makes pages whose images have all been destroyed available for new regions again.
+    pub(crate) fn reclaim_empty_pages(&mut self, registry: &crate::context::ResourceRegistry) {
+        for page in &mut self.pages {
+            let Some(texture) = registry
+                .textures
+                .get(page.texture_id as usize)
+                .and_then(|v| v.as_ref())
+            else {
+                continue;
+            };
+            let in_use = registry.images.iter().enumerate().any(|(id, image)| {
+                image.as_ref().is_some_and(|image| {
+                    image.texture_id == page.texture_id && id as u32 != texture.default_view_id
+                })
+            });
+            if !in_use {
+                page.packer = Packer::new(page.pixel_width as i32, page.pixel_height as i32);
+            }
+        }
+    }
+
     pub(crate) fn sync_raw_data(&self, registry: &mut crate::context::ResourceRegistry) {
         for page in &self.pages {
             if let Some(entry) = registry
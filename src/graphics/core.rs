@@ -31,6 +31,9 @@ pub(crate) struct ResolvedDraw {
     pub shader_opts: ShaderOpts,
 }
 
+/// Texture format used for the offscreen screen texture in headless mode.
+pub(crate) const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
 #[cfg(feature = "model-3d")]
 type GraphicsModel3dState = Option<Graphics3D>;
 #[cfg(not(feature = "model-3d"))]
@@ -48,20 +51,24 @@ pub(crate) struct Graphics {
     pub(crate) batch: Vec<InstanceData>,
     pub(crate) font_cache: HashMap<u64, FontArc>,
     pub(crate) glyph_cache: GlyphCache,
+    /// Rasterizes new glyphs off the main thread; `None` rasterizes them inline.
+    pub(crate) glyph_rasterizer: Option<super::glyph_raster::GlyphRasterizer>,
+    pub(crate) pipeline_cache: Option<super::pipeline_cache::PersistentPipelineCache>,
     pub(crate) resolved_draws: Vec<ResolvedDraw>,
-    pub(crate) text_shader_id: u32,
+    /// Images sampled this frame, reused by `mark_drawn_textures`.
+    pub(crate) drawn_image_ids: Vec<u32>,
     pub(crate) dirty_assets: bool,
     pub(crate) pipelines_dirty: bool,
     pub(crate) gpu_generation: u32,
     #[cfg_attr(not(feature = "model-3d"), allow(dead_code))]
     pub(crate) model_3d: GraphicsModel3dState,
     pub(crate) transparent: bool,
-    pub(crate) font_atlas: Option<super::atlas::DynamicAtlas>,
     pub(crate) shared_atlas: Option<super::atlas::DynamicAtlas>,
     pub(crate) shader_screen_snapshots: HashMap<u32, GpuTexture>,
     pub(crate) shader_history_snapshots: HashMap<u32, GpuTexture>,
     pub(crate) final_screen_texture: Option<GpuTexture>,
     pub(crate) gpu_profiler: Option<crate::graphics::profile::GpuTimestampProfiler>,
+    pub(crate) gpu_errors: super::errors::GpuErrorSink,
 }
 
 impl std::fmt::Debug for Graphics {
@@ -113,71 +120,171 @@ impl Graphics {
                 force_fallback_adapter: false,
             })
             .await?;
+        let (device, queue, enable_gpu_profiling) = Self::request_device(&adapter).await?;
 
-        let info = adapter.get_info();
-        eprintln!(
-            "[spot][init] Selected adapter: {:?} ({:?})",
-            info.name, info.backend
+        let caps = surface.get_capabilities(&adapter);
+        let mut config = surface
+            .get_default_config(&adapter, width, height)
+            .unwrap_or_else(|| wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                format: pick_surface_format(&caps),
+                width: width.max(1),
+                height: height.max(1),
+                present_mode: caps.present_modes[0],
+                alpha_mode: caps.alpha_modes[0],
+                view_formats: vec![],
+                desired_maximum_frame_latency: 1,
+            });
+
+        config.alpha_mode = pick_alpha_mode(&caps, transparent);
+
+        config.present_mode = crate::graphics::profile::pick_present_mode(&caps);
+        config.usage = crate::platform::surface_usage(&caps);
+
+        if crate::graphics::profile::render_profiling_enabled() {
+            crate::diagnostics::info!(
+                "profile",
+                "surface={}x{} format={:?} present_mode={:?}",
+                config.width,
+                config.height,
+                config.format,
+                config.present_mode
+            );
+        }
+
+        surface.configure(&device, &config);
+
+        let graphics = Self::from_device(
+            adapter,
+            device,
+            queue,
+            config,
+            transparent,
+            enable_gpu_profiling,
         );
+        // Headless runs keep rasterizing inline so every frame renders the same.
+        Ok(Self {
+            glyph_rasterizer: if cfg!(target_arch = "wasm32") {
+                None
+            } else {
+                super::glyph_raster::GlyphRasterizer::spawn()
+            },
+            ..graphics
+        })
+    }
+
+    /// Creates graphics without a window surface.
+    ///
+    /// Frames are rendered into the offscreen screen texture only, using
+    /// [`HEADLESS_FORMAT`], and can be read back with [`Graphics::draw_headless`].
+    #[cfg_attr(
+        any(target_os = "android", target_os = "ios", target_arch = "wasm32"),
+        allow(dead_code)
+    )]
+    pub async fn new_headless(
+        instance: &wgpu::Instance,
+        width: u32,
+        height: u32,
+        transparent: bool,
+    ) -> anyhow::Result<Self> {
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await?;
+        let (device, queue, enable_gpu_profiling) = Self::request_device(&adapter).await?;
+
+        // Never passed to a surface; it only carries the size and format the
+        // rest of the renderer reads from `config`.
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: HEADLESS_FORMAT,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 1,
+        };
 
-        let adapter_limits = adapter.limits();
-        let max_texture_dimension_2d = adapter_limits.max_texture_dimension_2d;
+        Ok(Self::from_device(
+            adapter,
+            device,
+            queue,
+            config,
+            transparent,
+            enable_gpu_profiling,
+        ))
+    }
+
+    async fn request_device(
+        adapter: &wgpu::Adapter,
+    ) -> anyhow::Result<(wgpu::Device, wgpu::Queue, bool)> {
+        let info = adapter.get_info();
+        crate::diagnostics::info!(
+            "init",
+            "Selected adapter: {:?} ({:?})",
+            info.name,
+            info.backend
+        );
 
         let timestamp_query_supported =
             adapter.features().contains(wgpu::Features::TIMESTAMP_QUERY);
         let enable_gpu_profiling =
             crate::graphics::profile::gpu_profiling_requested() && timestamp_query_supported;
         if crate::graphics::profile::gpu_profiling_requested() && !timestamp_query_supported {
-            eprintln!(
-                "[spot][profile] adapter does not support GPU timestamp queries; CPU profiling remains enabled"
+            crate::diagnostics::warn!(
+                "profile",
+                "adapter does not support GPU timestamp queries; CPU profiling remains enabled"
             );
         }
-        let required_features = if enable_gpu_profiling {
+        let mut required_features = if enable_gpu_profiling {
             wgpu::Features::TIMESTAMP_QUERY
         } else {
             wgpu::Features::empty()
         };
+        if crate::graphics::pipeline_cache::requested(adapter) {
+            required_features |= wgpu::Features::PIPELINE_CACHE;
+        }
+
+        // WebGL2 and other downlevel adapters report limits below the WebGPU defaults;
+        // request the WebGL2 baseline raised to whatever the adapter supports.
+        let required_limits = if adapter.get_downlevel_capabilities().is_webgpu_compliant() {
+            adapter.limits()
+        } else {
+            wgpu::Limits::downlevel_webgl2_defaults()
+                .using_resolution(adapter.limits())
+                .using_alignment(adapter.limits())
+        };
 
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
                 label: None,
                 required_features,
-                required_limits: adapter_limits,
+                required_limits,
                 experimental_features: wgpu::ExperimentalFeatures::default(),
                 memory_hints: wgpu::MemoryHints::default(),
                 trace: wgpu::Trace::Off,
             })
             .await?;
 
-        let adapter_clone = adapter.clone();
-
-        let caps = surface.get_capabilities(&adapter);
-        let mut config = surface
-            .get_default_config(&adapter, width, height)
-            .unwrap_or_else(|| wgpu::SurfaceConfiguration {
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                format: pick_surface_format(&caps),
-                width: width.max(1),
-                height: height.max(1),
-                present_mode: caps.present_modes[0],
-                alpha_mode: caps.alpha_modes[0],
-                view_formats: vec![],
-                desired_maximum_frame_latency: 1,
-            });
-
-        config.alpha_mode = pick_alpha_mode(&caps, transparent);
-
-        config.present_mode = crate::graphics::profile::pick_present_mode(&caps);
-        config.usage = crate::platform::surface_usage(&caps);
-
-        if crate::graphics::profile::render_profiling_enabled() {
-            eprintln!(
-                "[spot][profile] surface={}x{} format={:?} present_mode={:?}",
-                config.width, config.height, config.format, config.present_mode
-            );
-        }
+        Ok((device, queue, enable_gpu_profiling))
+    }
 
-        surface.configure(&device, &config);
+    fn from_device(
+        adapter: wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        config: wgpu::SurfaceConfiguration,
+        transparent: bool,
+        enable_gpu_profiling: bool,
+    ) -> Self {
+        let max_texture_dimension_2d = device.limits().max_texture_dimension_2d;
+        let gpu_errors = super::errors::GpuErrorSink::install(&device);
+        let pipeline_cache =
+            super::pipeline_cache::PersistentPipelineCache::load(&adapter, &device);
 
         let image_renderer = ImageRenderer::new(&device, config.format, 200000);
 
@@ -230,16 +337,19 @@ impl Graphics {
                 })],
             }),
             multiview_mask: None,
-            cache: None,
+            cache: pipeline_cache
+                .as_ref()
+                .map(super::pipeline_cache::PersistentPipelineCache::use_for_pipeline),
         });
 
         let gpu_profiler = enable_gpu_profiling
             .then(|| crate::graphics::profile::GpuTimestampProfiler::new(&device, &queue));
 
-        let graphics = Self {
+        // Default resources will be registered via the Context in App initialization
+        Self {
             device,
             queue,
-            adapter: adapter_clone,
+            adapter,
             config,
             image_renderer,
             default_pipeline,
@@ -247,23 +357,25 @@ impl Graphics {
             batch: Vec::with_capacity(10000),
             font_cache: HashMap::new(),
             glyph_cache: GlyphCache::new(),
+            glyph_rasterizer: None,
+            pipeline_cache,
             resolved_draws: Vec::with_capacity(10000),
-            text_shader_id: 0,
+            drawn_image_ids: Vec::new(),
             dirty_assets: true,
             pipelines_dirty: false,
             gpu_generation: 0, // This will be set by the platform/app
-            model_3d: GraphicsModel3dState::default(),
+            #[cfg(feature = "model-3d")]
+            model_3d: None,
+            #[cfg(not(feature = "model-3d"))]
+            model_3d: GraphicsModel3dState,
             transparent,
-            font_atlas: Some(super::atlas::DynamicAtlas::new(max_texture_dimension_2d)),
             shared_atlas: Some(super::atlas::DynamicAtlas::new(max_texture_dimension_2d)),
             shader_screen_snapshots: HashMap::new(),
             shader_history_snapshots: HashMap::new(),
             final_screen_texture: None,
             gpu_profiler,
-        };
-
-        // Default resources will be registered via the Context in App initialization
-        Ok(graphics)
+            gpu_errors,
+        }
     }
 
     fn sync_new_runtime_assets(&mut self, ctx: &mut crate::Context) -> anyhow::Result<()> {
@@ -274,16 +386,20 @@ impl Graphics {
         }
         self.sync_new_runtime_3d_assets(ctx)?;
 
+        let mut invalid_fonts = Vec::new();
         for (&id, data) in &ctx.registry.fonts {
             if let std::collections::hash_map::Entry::Vacant(entry) =
                 self.font_cache.entry(id as u64)
             {
-                let font = FontArc::try_from_vec(data.clone()).unwrap_or_else(|e| {
-                    panic!("[spot][graphics] Failed to sync font with ID {}: {}", id, e)
-                });
-                entry.insert(font);
+                match FontArc::try_from_vec(data.clone()) {
+                    Ok(font) => {
+                        entry.insert(font);
+                    }
+                    Err(e) => invalid_fonts.push((id, e.to_string())),
+                }
             }
         }
+        report_invalid_fonts(ctx, invalid_fonts);
 
         self.process_registrations(ctx)?;
         ctx.registry.dirty_assets = false;
@@ -295,9 +411,12 @@ impl Graphics {
         ctx: &mut crate::Context,
         drawables: &[DrawCommand],
     ) -> anyhow::Result<()> {
+        let _span = crate::diagnostics::span!("render", "resolve");
         for drawable in drawables {
-            if let DrawCommand::Text(cmd) = drawable {
-                self.ensure_text_layout(ctx, &cmd.text, cmd.opts.scale())?;
+            if let DrawCommand::Text(cmd) = drawable
+                && let Err(error) = self.ensure_text_layout(ctx, &cmd.text, cmd.opts.scale())
+            {
+                super::errors::report_draw(ctx, error);
             }
         }
 
@@ -322,16 +441,15 @@ impl Graphics {
             return Ok(());
         }
 
-        eprintln!(
-            "[spot][graphics] GPU generation mismatch ({} vs {}). Restoring assets...",
-            self.gpu_generation, ctx.registry.gpu_generation
+        crate::diagnostics::warn!(
+            "graphics",
+            "GPU generation mismatch ({} vs {}). Restoring assets...",
+            self.gpu_generation,
+            ctx.registry.gpu_generation
         );
 
         self.font_cache.clear();
         self.glyph_cache.clear();
-        if let Some(atlas) = self.font_atlas.as_mut() {
-            atlas.pages.clear();
-        }
         if let Some(atlas) = self.shared_atlas.as_mut() {
             atlas.pages.clear();
         }
@@ -341,19 +459,18 @@ impl Graphics {
             self.restore_image_shader(id, source);
         }
 
+        let mut invalid_fonts = Vec::new();
         for (&id, data) in &ctx.registry.fonts {
-            let font = ab_glyph::FontArc::try_from_vec(data.clone()).unwrap_or_else(|e| {
-                panic!(
-                    "[spot][graphics] Failed to restore font with ID {}: {}",
-                    id, e
-                )
-            });
-            self.font_cache.insert(id as u64, font);
+            match ab_glyph::FontArc::try_from_vec(data.clone()) {
+                Ok(font) => {
+                    self.font_cache.insert(id as u64, font);
+                }
+                Err(e) => invalid_fonts.push((id, e.to_string())),
+            }
         }
+        report_invalid_fonts(ctx, invalid_fonts);
 
         self.gpu_generation = ctx.registry.gpu_generation;
-        self.text_shader_id = 1;
-
         self.dirty_assets = true;
         self.rebuild_textures(ctx)?;
         self.restore_3d_assets(ctx);
@@ -361,8 +478,9 @@ impl Graphics {
         // Recreate registered assets before the first frame on the new device.
         self.process_registrations(ctx)?;
 
-        eprintln!(
-            "[spot][graphics] Asset restoration complete. generation={}",
+        crate::diagnostics::info!(
+            "graphics",
+            "Asset restoration complete. generation={}",
             self.gpu_generation
         );
 
@@ -371,16 +489,18 @@ impl Graphics {
 
     pub fn resize(&mut self, surface: &wgpu::Surface<'_>, width: u32, height: u32) {
         if width == 0 || height == 0 {
-            eprintln!(
-                "[spot][graphics] Warning: Attempted resize with zero dimension: {}x{}",
-                width, height
+            crate::diagnostics::warn!(
+                "graphics",
+                "Attempted resize with zero dimension: {}x{}",
+                width,
+                height
             );
             return;
         }
 
         let caps = surface.get_capabilities(&self.adapter);
         if caps.formats.is_empty() {
-            eprintln!("[spot][graphics] Surface has no supported formats on resize!");
+            crate::diagnostics::error!("graphics", "Surface has no supported formats on resize!");
             return;
         }
 
@@ -394,9 +514,11 @@ impl Graphics {
         // Try to keep the same format if possible to avoid pipeline incompatibility
         if !caps.formats.contains(&old_format) {
             let new_fmt = pick_surface_format(&caps);
-            eprintln!(
-                "[spot][graphics] Warning: Original surface format {:?} not supported by new surface. Switching to {:?}. Pipelines may become invalid!",
-                old_format, new_fmt
+            crate::diagnostics::warn!(
+                "graphics",
+                "Original surface format {:?} not supported by new surface. Switching to {:?}. Pipelines may become invalid!",
+                old_format,
+                new_fmt
             );
             self.config.format = new_fmt;
             self.pipelines_dirty = true;
@@ -412,6 +534,24 @@ impl Graphics {
         self.resize_3d_surface_resources(width, height, old_width, old_height);
     }
 
+    /// Resizes the offscreen screen texture of graphics created with [`Graphics::new_headless`].
+    #[cfg_attr(
+        any(target_os = "android", target_os = "ios", target_arch = "wasm32"),
+        allow(dead_code)
+    )]
+    pub(crate) fn resize_headless(&mut self, width: u32, height: u32) {
+        let old_width = self.config.width;
+        let old_height = self.config.height;
+        self.config.width = width.max(1);
+        self.config.height = height.max(1);
+        self.resize_3d_surface_resources(
+            self.config.width,
+            self.config.height,
+            old_width,
+            old_height,
+        );
+    }
+
     pub fn set_transparent(&mut self, transparent: bool) {
         self.transparent = transparent;
     }
@@ -432,6 +572,13 @@ impl Graphics {
         self.transparent
     }
 
+    /// Returns the persistent pipeline cache to create a pipeline with, if enabled.
+    pub(crate) fn pipeline_cache(&self) -> Option<&wgpu::PipelineCache> {
+        self.pipeline_cache
+            .as_ref()
+            .map(super::pipeline_cache::PersistentPipelineCache::use_for_pipeline)
+    }
+
     pub(crate) fn finish_profiling(&mut self) {
         if let Some(profiler) = self.gpu_profiler.as_mut() {
             profiler.finish(&self.device);
@@ -486,3 +633,13 @@ fn pick_alpha_mode(
 
     caps.alpha_modes[0]
 }
+
+/// Reports fonts that failed to parse; their text draws are skipped.
+fn report_invalid_fonts(ctx: &mut crate::Context, fonts: Vec<(u32, String)>) {
+    for (font_id, message) in fonts {
+        super::errors::report_draw(
+            ctx,
+            super::errors::DrawError::InvalidFont { font_id, message },
+        );
+    }
+}
@@ -219,8 +219,9 @@ impl Graphics {
         backend: wgpu::Backend,
     ) -> wgpu::RenderPipeline {
         let shader_source = if backend == wgpu::Backend::Gl {
-            eprintln!(
-                "[spot][3d] Using fog background fallback shader on GL backend because depth textureLoad is unsupported."
+            crate::diagnostics::warn!(
+                "3d",
+                "Using fog background fallback shader on GL backend because depth textureLoad is unsupported."
             );
             include_str!("../shaders/fog_background_fallback.wgsl")
         } else {
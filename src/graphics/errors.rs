@@ -0,0 +1,250 @@
+//! GPU and draw errors reported to the application instead of panicking.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::Context;
+
+/// Errors kept until [`Context::take_gpu_errors`] or [`Context::take_errors`] is called;
+/// older ones are dropped.
+const MAX_GPU_ERRORS: usize = 64;
+
+/// What went wrong on the GPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GpuErrorKind {
+    /// wgpu rejected a call, e.g. a shader or resource that breaks the API's rules.
+    /// The offending resource is invalid and its draws are dropped.
+    Validation,
+    /// The GPU or the surface ran out of memory.
+    OutOfMemory,
+    /// The driver failed in a way the API does not describe.
+    Internal,
+    /// The device was lost, e.g. after a driver reset; nothing renders from then on.
+    DeviceLost,
+    /// The surface was lost and is being recreated.
+    SurfaceLost,
+    /// The surface no longer matches the window and is being reconfigured.
+    SurfaceOutdated,
+    /// The next frame could not be acquired in time; the frame was skipped.
+    SurfaceTimeout,
+    /// Acquiring the next frame failed for another reason; the frame was skipped.
+    SurfaceOther,
+}
+
+/// A GPU error, as returned by [`Context::take_gpu_errors`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuError {
+    pub kind: GpuErrorKind,
+    pub message: String,
+}
+
+impl std::fmt::Display for GpuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+impl From<&wgpu::SurfaceError> for GpuError {
+    fn from(error: &wgpu::SurfaceError) -> Self {
+        let kind = match error {
+            wgpu::SurfaceError::Lost => GpuErrorKind::SurfaceLost,
+            wgpu::SurfaceError::Outdated => GpuErrorKind::SurfaceOutdated,
+            wgpu::SurfaceError::Timeout => GpuErrorKind::SurfaceTimeout,
+            wgpu::SurfaceError::OutOfMemory => GpuErrorKind::OutOfMemory,
+            wgpu::SurfaceError::Other => GpuErrorKind::SurfaceOther,
+        };
+        Self {
+            kind,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// A draw that was skipped because an asset it needs is missing or broken, as returned
+/// by [`Context::take_errors`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DrawError {
+    /// A text draw uses a font id that is not registered.
+    MissingFont { font_id: u32 },
+    /// Registered font data could not be parsed.
+    InvalidFont { font_id: u32, message: String },
+    /// An image draw uses a shader id without a compiled pipeline.
+    MissingShader { shader_id: u32 },
+    /// An image draw, or one of its shader inputs, uses a texture that is not on the GPU.
+    MissingTexture { image_id: u32, texture_id: u32 },
+}
+
+impl std::fmt::Display for DrawError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawError::MissingFont { font_id } => write!(f, "font {font_id} is not registered"),
+            DrawError::InvalidFont { font_id, message } => {
+                write!(f, "font {font_id} could not be parsed: {message}")
+            }
+            DrawError::MissingShader { shader_id } => {
+                write!(f, "image shader {shader_id} has no pipeline")
+            }
+            DrawError::MissingTexture {
+                image_id,
+                texture_id,
+            } => write!(
+                f,
+                "image {image_id} uses texture {texture_id}, which is not on the GPU"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DrawError {}
+
+/// Errors wgpu reports from its callbacks, which may run on other threads.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GpuErrorSink {
+    errors: Arc<Mutex<Vec<GpuError>>>,
+}
+
+impl GpuErrorSink {
+    /// Routes `device`'s uncaptured errors and device loss into the sink, so wgpu
+    /// neither panics on them nor only prints them.
+    pub(crate) fn install(device: &wgpu::Device) -> Self {
+        let sink = Self::default();
+        let errors = sink.errors.clone();
+        device.on_uncaptured_error(Arc::new(move |error: wgpu::Error| {
+            let (kind, message) = match error {
+                wgpu::Error::OutOfMemory { source } => {
+                    (GpuErrorKind::OutOfMemory, source.to_string())
+                }
+                wgpu::Error::Validation { description, .. } => {
+                    (GpuErrorKind::Validation, description)
+                }
+                wgpu::Error::Internal { description, .. } => (GpuErrorKind::Internal, description),
+            };
+            crate::diagnostics::error!("graphics", "{:?} error: {}", kind, message);
+            push(&errors, GpuError { kind, message });
+        }));
+        let errors = sink.errors.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            if reason == wgpu::DeviceLostReason::Destroyed {
+                return;
+            }
+            crate::diagnostics::error!("graphics", "device lost: {}", message);
+            let kind = GpuErrorKind::DeviceLost;
+            push(&errors, GpuError { kind, message });
+        });
+        sink
+    }
+
+    /// Moves the collected errors and `surface_error`, if any, into the context.
+    pub(crate) fn drain_into(&self, ctx: &mut Context, surface_error: Option<&wgpu::SurfaceError>) {
+        let drained = self
+            .errors
+            .lock()
+            .map(|mut errors| std::mem::take(&mut *errors))
+            .unwrap_or_default();
+        for error in drained.into_iter().chain(surface_error.map(GpuError::from)) {
+            report(ctx, error);
+        }
+    }
+}
+
+fn push(errors: &Mutex<Vec<GpuError>>, error: GpuError) {
+    if let Ok(mut errors) = errors.lock() {
+        if errors.len() == MAX_GPU_ERRORS {
+            errors.remove(0);
+        }
+        errors.push(error);
+    }
+}
+
+fn push_back<T>(errors: &mut VecDeque<T>, error: T) {
+    if errors.len() == MAX_GPU_ERRORS {
+        errors.pop_front();
+    }
+    errors.push_back(error);
+}
+
+/// Queues `error` for [`Context::take_gpu_errors`].
+pub(crate) fn report(ctx: &mut Context, error: GpuError) {
+    push_back(&mut ctx.runtime.gpu_errors, error);
+}
+
+/// Logs `error` and queues it for [`Context::take_errors`].
+pub(crate) fn report_draw(ctx: &mut Context, error: DrawError) {
+    crate::diagnostics::warn!("render", "skipped draw: {}", error);
+    push_back(&mut ctx.runtime.draw_errors, error);
+}
+
+impl Context {
+    /// Returns the GPU errors reported since the last call, oldest first.
+    ///
+    /// wgpu validation, out-of-memory and internal errors, a lost device and failures to
+    /// acquire the next frame are logged and collected here instead of panicking, so a
+    /// game can show a message, lower its settings or save and quit. Lost and outdated
+    /// surfaces are recreated before the next frame. Only the latest 64 errors are kept.
+    pub fn take_gpu_errors(&mut self) -> Vec<GpuError> {
+        self.runtime.gpu_errors.drain(..).collect()
+    }
+
+    /// Returns the draws skipped since the last call, oldest first.
+    ///
+    /// A text draw with a missing or unparsable font, or an image draw whose shader or
+    /// texture is missing, is dropped from the frame and reported here instead of
+    /// panicking, so one bad asset does not end the game. Each skipped draw is reported
+    /// every frame it is drawn; only the latest 64 errors are kept.
+    pub fn take_errors(&mut self) -> Vec<DrawError> {
+        self.runtime.draw_errors.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface_errors_queue_and_old_errors_are_dropped() {
+        let mut ctx = Context::new();
+        let sink = GpuErrorSink::default();
+        push(
+            &sink.errors,
+            GpuError {
+                kind: GpuErrorKind::Validation,
+                message: "bad pipeline".to_string(),
+            },
+        );
+        sink.drain_into(&mut ctx, Some(&wgpu::SurfaceError::Outdated));
+        let errors = ctx.take_gpu_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind, GpuErrorKind::Validation);
+        assert_eq!(errors[1].kind, GpuErrorKind::SurfaceOutdated);
+        assert!(ctx.take_gpu_errors().is_empty());
+
+        for index in 0..MAX_GPU_ERRORS + 1 {
+            report(
+                &mut ctx,
+                GpuError {
+                    kind: GpuErrorKind::Internal,
+                    message: index.to_string(),
+                },
+            );
+        }
+        let errors = ctx.take_gpu_errors();
+        assert_eq!(errors.len(), MAX_GPU_ERRORS);
+        assert_eq!(errors[0].message, "1");
+    }
+
+    #[test]
+    fn draws_with_a_missing_font_are_reported_not_fatal() {
+        let mut ctx = Context::new();
+        report_draw(&mut ctx, DrawError::MissingFont { font_id: 9 });
+        assert_eq!(
+            ctx.take_errors(),
+            vec![DrawError::MissingFont { font_id: 9 }]
+        );
+        assert!(ctx.take_errors().is_empty());
+        assert!(ctx.take_gpu_errors().is_empty());
+    }
+}
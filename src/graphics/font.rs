@@ -1,6 +1,6 @@
 //! Font registration and glyph rendering.
 
-use crate::glyph_cache::GlyphEntry;
+use crate::glyph_cache::{GlyphCacheKey, GlyphEntry};
 use ab_glyph::FontArc;
 
 use super::core::Graphics;
@@ -8,87 +8,92 @@ use crate::Pt;
 
 // Context owns font data; Graphics caches parsed fonts.
 
+/// Coverage bitmap of one glyph, ready to be added to the atlas.
+pub(crate) struct GlyphBitmap {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+    pub offset: [f32; 2],
+    pub advance: f32,
+}
+
+/// Outlines and rasterizes `glyph_id` at `px_size`. Returns `None` for glyphs without
+/// an outline, such as spaces.
+///
+/// Only touches the font, so it is safe to call from any thread.
+pub(crate) fn rasterize_glyph(font: &FontArc, px_size: f32, glyph_id: u32) -> Option<GlyphBitmap> {
+    use ab_glyph::{Font as _, Glyph, PxScale, ScaleFont as _};
+
+    let scale = PxScale::from(px_size.max(1.0));
+    let scaled = font.as_scaled(scale);
+
+    let glyph = Glyph {
+        id: ab_glyph::GlyphId(glyph_id as u16),
+        scale,
+        position: ab_glyph::point(0.0, 0.0),
+    };
+
+    let advance = scaled.h_advance(glyph.id);
+    let outlined = scaled.outline_glyph(glyph)?;
+
+    let bounds = outlined.px_bounds();
+    let width = (bounds.max.x - bounds.min.x).ceil().max(1.0) as u32;
+    let height = (bounds.max.y - bounds.min.y).ceil().max(1.0) as u32;
+
+    let mut rgba = vec![0u8; (width * height * 4) as usize];
+
+    outlined.draw(|x, y, v| {
+        if x < width && y < height {
+            let idx = ((y * width + x) * 4) as usize;
+            let alpha = (v * 255.0).round().clamp(0.0, 255.0) as u8;
+            rgba[idx] = 255;
+            rgba[idx + 1] = 255;
+            rgba[idx + 2] = 255;
+            rgba[idx + 3] = alpha;
+        }
+    });
+
+    Some(GlyphBitmap {
+        width,
+        height,
+        rgba,
+        offset: [bounds.min.x, bounds.min.y],
+        advance,
+    })
+}
+
 impl Graphics {
-    /// Render a single glyph to the atlas and cache it
-    pub(super) fn render_single_glyph(
+    /// Adds a rasterized glyph to the shared atlas and caches it under `key`.
+    pub(super) fn insert_glyph(
         &mut self,
         ctx: &mut crate::Context,
-        font_id: u32,
-        font_size: f32,
-        glyph_id: u32,
+        key: GlyphCacheKey,
+        bitmap: &GlyphBitmap,
+        scale_factor: f64,
     ) -> anyhow::Result<GlyphEntry> {
-        use ab_glyph::{Font as _, FontArc, Glyph, PxScale, ScaleFont as _};
-
-        let font_data = ctx
-            .registry
-            .fonts
-            .get(&font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font ID {} not found", font_id))?;
-
-        let font = if let Some(cached_font) = self.get_cached_font(font_id as u64) {
-            cached_font
-        } else {
-            let font = FontArc::try_from_vec(font_data.clone())
-                .map_err(|e| anyhow::anyhow!("Failed to parse font: {:?}", e))?;
-            self.cache_font(font_id as u64, font.clone());
-            font
-        };
-
-        let px_size = font_size.max(1.0);
-        let scale = PxScale::from(px_size);
-        let scaled = font.as_scaled(scale);
-
-        let glyph = Glyph {
-            id: ab_glyph::GlyphId(glyph_id as u16),
-            scale,
-            position: ab_glyph::point(0.0, 0.0),
-        };
-
-        let h_advance = scaled.h_advance(glyph.id);
-
-        let outlined = scaled
-            .outline_glyph(glyph)
-            .ok_or_else(|| anyhow::anyhow!("Cannot outline glyph"))?;
-
-        let bounds = outlined.px_bounds();
-        let glyph_width = (bounds.max.x - bounds.min.x).ceil().max(1.0) as u32;
-        let glyph_height = (bounds.max.y - bounds.min.y).ceil().max(1.0) as u32;
-
-        let mut rgba_data = vec![0u8; (glyph_width * glyph_height * 4) as usize];
-
-        outlined.draw(|x, y, v| {
-            if x < glyph_width && y < glyph_height {
-                let idx = ((y * glyph_width + x) * 4) as usize;
-                let alpha = (v * 255.0).round().clamp(0.0, 255.0) as u8;
-                rgba_data[idx] = 255;
-                rgba_data[idx + 1] = 255;
-                rgba_data[idx + 2] = 255;
-                rgba_data[idx + 3] = alpha;
-            }
-        });
-
-        let scale_factor = ctx.scale_factor();
-        let logical_w = Pt::from_physical_px(glyph_width as f64, scale_factor);
-        let logical_h = Pt::from_physical_px(glyph_height as f64, scale_factor);
+        let logical_w = Pt::from_physical_px(bitmap.width as f64, scale_factor);
+        let logical_h = Pt::from_physical_px(bitmap.height as f64, scale_factor);
         let image = self
-            .font_atlas
+            .shared_atlas
             .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("Font atlas not initialized"))?
+            .ok_or_else(|| anyhow::anyhow!("Shared atlas not initialized"))?
             .add_region(
                 &mut ctx.registry,
                 scale_factor,
                 logical_w,
                 logical_h,
-                glyph_width,
-                glyph_height,
-                &rgba_data,
+                bitmap.width,
+                bitmap.height,
+                &bitmap.rgba,
             )?;
 
-        Ok(GlyphEntry {
+        let entry = GlyphEntry {
             image,
-            offset: [bounds.min.x, bounds.min.y],
-            advance: h_advance,
-        })
+            offset: bitmap.offset,
+            advance: bitmap.advance,
+        };
+        self.glyph_cache.insert(key, entry.clone());
+        Ok(entry)
     }
 
     pub(super) fn get_cached_font(&self, font_hash: u64) -> Option<FontArc> {
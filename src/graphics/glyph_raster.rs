@@ -0,0 +1,189 @@
+//! Glyph rasterization on worker threads.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, mpsc};
+
+use ab_glyph::FontArc;
+
+use super::core::Graphics;
+use super::font::{GlyphBitmap, rasterize_glyph};
+use crate::glyph_cache::GlyphCacheKey;
+
+const MAX_WORKERS: usize = 4;
+
+struct GlyphJob {
+    key: GlyphCacheKey,
+    font: FontArc,
+    px_size: f32,
+    scale_factor: f64,
+}
+
+struct RasterizedGlyph {
+    key: GlyphCacheKey,
+    scale_factor: f64,
+    bitmap: Option<GlyphBitmap>,
+}
+
+/// Rasterizes uncached glyphs off the main thread.
+///
+/// Text drawn with a glyph that is still being rasterized leaves a gap where the glyph
+/// goes and is laid out again once [`Graphics::receive_rasterized_glyphs`] has added it
+/// to the atlas, usually on the next frame.
+pub(crate) struct GlyphRasterizer {
+    jobs: mpsc::Sender<GlyphJob>,
+    results: mpsc::Receiver<RasterizedGlyph>,
+    pending: HashSet<GlyphCacheKey>,
+}
+
+impl GlyphRasterizer {
+    /// Starts the worker threads. Returns `None` if none could be spawned, in which
+    /// case glyphs are rasterized synchronously.
+    pub(crate) fn spawn() -> Option<Self> {
+        let workers = std::thread::available_parallelism()
+            .map(|cores| cores.get().saturating_sub(1))
+            .unwrap_or(1)
+            .clamp(1, MAX_WORKERS);
+        let (jobs, job_receiver) = mpsc::channel::<GlyphJob>();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let mut spawned = 0;
+        for index in 0..workers {
+            let job_receiver = job_receiver.clone();
+            let result_sender = result_sender.clone();
+            let worker = std::thread::Builder::new()
+                .name(format!("spot-glyphs-{index}"))
+                .spawn(move || {
+                    loop {
+                        let job = match job_receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => return,
+                        };
+                        let Ok(job) = job else {
+                            return;
+                        };
+                        let bitmap = rasterize_glyph(&job.font, job.px_size, job.key.glyph_id);
+                        let result = RasterizedGlyph {
+                            key: job.key,
+                            scale_factor: job.scale_factor,
+                            bitmap,
+                        };
+                        if result_sender.send(result).is_err() {
+                            return;
+                        }
+                    }
+                });
+            if worker.is_ok() {
+                spawned += 1;
+            }
+        }
+
+        (spawned > 0).then(|| Self {
+            jobs,
+            results,
+            pending: HashSet::new(),
+        })
+    }
+
+    /// Queues `key` for rasterization unless it is already queued.
+    pub(crate) fn request(
+        &mut self,
+        key: GlyphCacheKey,
+        font: &FontArc,
+        px_size: f32,
+        scale_factor: f64,
+    ) {
+        if !self.pending.insert(key) {
+            return;
+        }
+        let job = GlyphJob {
+            key,
+            font: font.clone(),
+            px_size,
+            scale_factor,
+        };
+        if self.jobs.send(job).is_err() {
+            self.pending.remove(&key);
+        }
+    }
+
+    /// Returns true while queued glyphs have not been received yet.
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+impl Graphics {
+    /// Adds the glyphs finished by the workers since the last call to the atlas.
+    ///
+    /// Requests another frame while glyphs are still being rasterized, so text waiting
+    /// on them is completed even when redrawing on demand.
+    pub(crate) fn receive_rasterized_glyphs(&mut self, ctx: &mut crate::Context) {
+        let Some(rasterizer) = self.glyph_rasterizer.as_mut() else {
+            return;
+        };
+        let mut finished = Vec::new();
+        while let Ok(glyph) = rasterizer.results.try_recv() {
+            rasterizer.pending.remove(&glyph.key);
+            finished.push(glyph);
+        }
+        if rasterizer.has_pending() {
+            ctx.request_redraw();
+        }
+
+        for glyph in finished {
+            match glyph.bitmap {
+                Some(bitmap) => {
+                    if let Err(error) =
+                        self.insert_glyph(ctx, glyph.key, &bitmap, glyph.scale_factor)
+                    {
+                        crate::diagnostics::error!(
+                            "graphics",
+                            "failed to add glyph to atlas: {error:?}"
+                        );
+                        self.glyph_cache.insert_blank(glyph.key);
+                    }
+                }
+                None => self.glyph_cache.insert_blank(glyph.key),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_glyphs_are_rasterized_once() {
+        let font = FontArc::try_from_slice(include_bytes!("../../assets/DejaVuSans.ttf"))
+            .expect("bundled font parses");
+        let mut rasterizer = GlyphRasterizer::spawn().expect("worker threads");
+        let key = |glyph_id| GlyphCacheKey {
+            font_id: 1,
+            font_size_bits: 24f32.to_bits(),
+            glyph_id,
+        };
+        let outlined = u32::from(ab_glyph::Font::glyph_id(&font, 'A').0);
+        let space = u32::from(ab_glyph::Font::glyph_id(&font, ' ').0);
+
+        rasterizer.request(key(outlined), &font, 24.0, 1.0);
+        rasterizer.request(key(outlined), &font, 24.0, 1.0);
+        rasterizer.request(key(space), &font, 24.0, 1.0);
+        assert!(rasterizer.has_pending());
+
+        let mut received = Vec::new();
+        while received.len() < 2 {
+            let glyph = rasterizer
+                .results
+                .recv_timeout(std::time::Duration::from_secs(5))
+                .expect("glyph rasterized");
+            received.push((glyph.key.glyph_id, glyph.bitmap.is_some()));
+        }
+        received.sort();
+        let mut expected = vec![(outlined, true), (space, false)];
+        expected.sort();
+        assert_eq!(received, expected);
+        assert!(rasterizer.results.try_recv().is_err());
+    }
+}
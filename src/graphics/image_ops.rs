@@ -5,9 +5,13 @@ use super::core::Graphics;
 
 impl Graphics {
     pub(crate) fn process_registrations(&mut self, ctx: &mut crate::Context) -> anyhow::Result<()> {
+        let _span = crate::diagnostics::span!("render", "upload");
         let has_pending = ctx.registry.textures.iter().any(|opt| {
             opt.as_ref()
-                .map(|e| !e.is_ready(self.gpu_generation) || !e.pending_uploads.is_empty())
+                .map(|e| {
+                    !e.evicted
+                        && (!e.is_ready(self.gpu_generation) || !e.pending_uploads.is_empty())
+                })
                 .unwrap_or(false)
         });
         if !self.dirty_assets && !has_pending {
@@ -16,7 +20,7 @@ impl Graphics {
 
         if ctx.registry.textures.iter().any(|opt| {
             opt.as_ref()
-                .map(|e| e.dynamic_atlas && !e.is_ready(self.gpu_generation))
+                .map(|e| e.dynamic_atlas && !e.evicted && !e.is_ready(self.gpu_generation))
                 .unwrap_or(false)
         }) {
             self.sync_dynamic_atlas_raw_data(ctx);
@@ -26,6 +30,9 @@ impl Graphics {
             let Some(entry) = ctx.registry.textures[i].as_mut() else {
                 continue;
             };
+            if entry.evicted {
+                continue;
+            }
             let needs_full_upload = !entry.is_ready(self.gpu_generation);
             if !needs_full_upload && entry.pending_uploads.is_empty() {
                 continue;
@@ -58,6 +65,7 @@ impl Graphics {
                 };
 
                 if let Some(raw_data) = entry.raw_data.as_ref() {
+                    ctx.runtime.frame_stats.texture_uploads += 1;
                     upload_rgba_texture_region(
                         &self.queue,
                         &texture,
@@ -82,6 +90,7 @@ impl Graphics {
                 entry.pending_uploads.clear();
             } else if let Some(texture) = entry.runtime.gpu_texture.as_ref() {
                 let pending_uploads = std::mem::take(&mut entry.pending_uploads);
+                ctx.runtime.frame_stats.texture_uploads += pending_uploads.len() as u32;
                 for upload in pending_uploads {
                     upload_texture_region(&self.queue, texture, upload);
                 }
@@ -113,9 +122,6 @@ impl Graphics {
 
 impl Graphics {
     fn sync_dynamic_atlas_raw_data(&self, ctx: &mut crate::Context) {
-        if let Some(atlas) = self.font_atlas.as_ref() {
-            atlas.sync_raw_data(&mut ctx.registry);
-        }
         if let Some(atlas) = self.shared_atlas.as_ref() {
             atlas.sync_raw_data(&mut ctx.registry);
         }
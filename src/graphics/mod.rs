@@ -7,11 +7,14 @@ pub(crate) mod atlas;
 pub(crate) mod core;
 #[cfg(feature = "model-3d")]
 pub(crate) mod core_3d;
+pub(crate) mod errors;
 pub(crate) mod font;
+pub(crate) mod glyph_raster;
 pub(crate) mod image_ops;
 pub(crate) mod image_pipeline;
 #[cfg(feature = "model-3d")]
 pub(crate) mod model_raw;
+pub(crate) mod pipeline_cache;
 pub(crate) mod profile;
 pub(crate) mod render;
 #[cfg(feature = "model-3d")]
@@ -21,3 +24,4 @@ pub(crate) mod shader;
 pub(crate) mod shader_3d;
 pub(crate) mod text_layout;
 pub(crate) mod texture;
+pub(crate) mod texture_budget;
@@ -0,0 +1,198 @@
+//! Compiled pipelines persisted across runs, see
+//! [`WindowConfig::pipeline_cache`](crate::WindowConfig::pipeline_cache).
+
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+static APP_NAME: Mutex<Option<String>> = Mutex::new(None);
+
+/// Sets the application whose cache directory stores pipelines of devices created from
+/// now on. `None` disables the cache.
+pub(crate) fn set_app_name(app_name: Option<String>) {
+    if let Ok(mut current) = APP_NAME.lock() {
+        *current = app_name.filter(|name| !name.trim().is_empty());
+    }
+}
+
+fn app_name() -> Option<String> {
+    APP_NAME.lock().ok()?.clone()
+}
+
+/// Returns true if the device should be created with pipeline caching.
+pub(crate) fn requested(adapter: &wgpu::Adapter) -> bool {
+    adapter.features().contains(wgpu::Features::PIPELINE_CACHE) && app_name().is_some()
+}
+
+/// Source of the bytes saved to the cache file; lets tests stand in for a device.
+pub(crate) trait CacheData {
+    fn get_data(&self) -> Option<Vec<u8>>;
+}
+
+impl CacheData for wgpu::PipelineCache {
+    fn get_data(&self) -> Option<Vec<u8>> {
+        wgpu::PipelineCache::get_data(self)
+    }
+}
+
+/// A [`wgpu::PipelineCache`] loaded from and saved to the app's cache directory.
+pub(crate) struct PersistentPipelineCache<C: CacheData = wgpu::PipelineCache> {
+    cache: C,
+    path: PathBuf,
+    dirty: Cell<bool>,
+    /// Set while a background thread writes the cache file.
+    saving: Arc<AtomicBool>,
+}
+
+impl PersistentPipelineCache {
+    /// Loads the cache for this adapter. Returns `None` if caching was not requested, the
+    /// device lacks [`wgpu::Features::PIPELINE_CACHE`] (everything but Vulkan) or there is
+    /// no cache directory.
+    pub(crate) fn load(adapter: &wgpu::Adapter, device: &wgpu::Device) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::PIPELINE_CACHE) {
+            return None;
+        }
+        let key = wgpu::util::pipeline_cache_key(&adapter.get_info())?;
+        let path = crate::storage::cache_dir(&app_name()?)?.join(key);
+        let data = std::fs::read(&path).ok();
+        // SAFETY: the file is only written by `save_if_dirty` from `PipelineCache::get_data`
+        // under a key naming this adapter and driver; stale or foreign data is discarded
+        // by wgpu because `fallback` is set.
+        let cache = unsafe {
+            device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                label: Some("spot_pipeline_cache"),
+                data: data.as_deref(),
+                fallback: true,
+            })
+        };
+        Some(Self {
+            cache,
+            path,
+            dirty: Cell::new(false),
+            saving: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Returns the cache to pass to a pipeline descriptor, marking it for saving.
+    pub(crate) fn use_for_pipeline(&self) -> &wgpu::PipelineCache {
+        self.dirty.set(true);
+        &self.cache
+    }
+}
+
+impl<C: CacheData> PersistentPipelineCache<C> {
+    /// Writes the cache to disk on a background thread if pipelines were created with it
+    /// since the last save. While an earlier write is still running the save is retried
+    /// on a later frame, so an older cache never replaces a newer one.
+    pub(crate) fn save_if_dirty(&self) {
+        if !self.dirty.get() || self.saving.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        self.dirty.set(false);
+        let Some(data) = self.cache.get_data() else {
+            self.saving.store(false, Ordering::Release);
+            return;
+        };
+        let path = self.path.clone();
+        let saving = Arc::clone(&self.saving);
+        let spawned = std::thread::Builder::new()
+            .name("spot-pipeline-cache".into())
+            .spawn(move || {
+                write(&path, &data);
+                saving.store(false, Ordering::Release);
+            });
+        if let Err(error) = spawned {
+            crate::diagnostics::error!(
+                "graphics",
+                "failed to spawn pipeline cache writer: {error}"
+            );
+            self.dirty.set(true);
+            self.saving.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl<C: CacheData> Drop for PersistentPipelineCache<C> {
+    /// Saves pipelines created after the last background write, which would otherwise be
+    /// lost when the app exits.
+    fn drop(&mut self) {
+        if !self.dirty.get() {
+            return;
+        }
+        // Let a running write finish first so it cannot replace the newer data.
+        while self.saving.load(Ordering::Acquire) {
+            std::thread::yield_now();
+        }
+        if let Some(data) = self.cache.get_data() {
+            write(&self.path, &data);
+        }
+    }
+}
+
+fn write(path: &Path, data: &[u8]) {
+    if let Err(error) = crate::storage::write_atomically(path, data) {
+        crate::diagnostics::error!(
+            "graphics",
+            "failed to save pipeline cache to {}: {error}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl CacheData for Vec<u8> {
+        fn get_data(&self) -> Option<Vec<u8>> {
+            Some(self.clone())
+        }
+    }
+
+    /// A cache with unsaved `data`, as if a pipeline had just been created with it.
+    fn dirty_cache(name: &str, data: &[u8]) -> PersistentPipelineCache<Vec<u8>> {
+        let path =
+            std::env::temp_dir().join(format!("spot_pipeline_cache_{}_{name}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        PersistentPipelineCache {
+            cache: data.to_vec(),
+            path,
+            dirty: Cell::new(true),
+            saving: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[test]
+    fn saves_wait_for_the_running_write() {
+        let cache = dirty_cache("running", b"new");
+        cache.saving.store(true, Ordering::Release);
+        cache.save_if_dirty();
+        assert!(cache.dirty.get());
+        assert!(!cache.path.exists());
+
+        cache.saving.store(false, Ordering::Release);
+        cache.save_if_dirty();
+        assert!(!cache.dirty.get());
+        while cache.saving.load(Ordering::Acquire) {
+            std::thread::yield_now();
+        }
+        assert_eq!(std::fs::read(&cache.path).unwrap(), b"new");
+        std::fs::remove_file(&cache.path).unwrap();
+    }
+
+    #[test]
+    fn unsaved_pipelines_are_written_on_drop() {
+        let cache = dirty_cache("drop", b"pending");
+        let path = cache.path.clone();
+        drop(cache);
+        assert_eq!(std::fs::read(&path).unwrap(), b"pending");
+        std::fs::remove_file(&path).unwrap();
+
+        let saved = dirty_cache("saved", b"unchanged");
+        saved.dirty.set(false);
+        let path = saved.path.clone();
+        drop(saved);
+        assert!(!path.exists());
+    }
+}
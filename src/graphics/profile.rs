@@ -15,6 +15,7 @@ static PROFILE_STATE: OnceLock<Mutex<RenderProfiler>> = OnceLock::new();
 static PROFILE_FRAME_ID: AtomicU64 = AtomicU64::new(0);
 static PENDING_SCENE_TIMES: OnceLock<Mutex<SceneTimes>> = OnceLock::new();
 static GPU_READBACK_WARNED: AtomicBool = AtomicBool::new(false);
+static GPU_TIMING_REQUESTED: AtomicBool = AtomicBool::new(false);
 
 const FRAME_CSV_HEADER: &str = "frame,sample,frame_interval_ms,engine_ms,wait_ms,work_ms,prepare_ms,targets_ms,shadow_ms,main3d_ms,overlay_ms,present_ms,update_ms,draw_ms,gpu_ms,rss_mb";
 const SUMMARY_CSV_HEADER: &str = "scenario,samples,gpu_samples,mean_frame_interval_ms,p50_frame_interval_ms,p95_frame_interval_ms,p99_frame_interval_ms,max_frame_interval_ms,mean_engine_ms,p50_engine_ms,p95_engine_ms,p99_engine_ms,max_engine_ms,mean_work_ms,p95_work_ms,p99_work_ms,mean_update_ms,p95_update_ms,mean_draw_ms,p95_draw_ms,mean_gpu_ms,p50_gpu_ms,p95_gpu_ms,p99_gpu_ms,max_gpu_ms,mean_rss_mb,max_rss_mb";
@@ -51,17 +52,56 @@ impl ProfileConfig {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-pub(crate) struct FrameProfileInput {
-    pub frame_id: u64,
+/// CPU and GPU timings of the last rendered frame, returned by
+/// [`render_stats`](crate::render_stats).
+///
+/// CPU stage times are measured every frame. GPU times are only available when
+/// [`WindowConfig::gpu_timing`](crate::WindowConfig::gpu_timing) is set (or
+/// `SPOT_PROFILE_RENDER` is) and the adapter supports timestamp queries; they are read
+/// back asynchronously, so they describe a frame a few frames older than
+/// [`frame`](Self::frame).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderStats {
+    /// Number of the rendered frame, counting from 1.
+    pub frame: u64,
+    /// CPU time of the whole frame in the renderer, in milliseconds.
     pub engine_ms: f64,
+    /// Time spent waiting for the next surface texture.
     pub wait_ms: f64,
+    /// Asset uploads and per-frame resource preparation.
     pub prepare_ms: f64,
+    /// Encoding the passes of render targets drawn this frame.
     pub targets_ms: f64,
+    /// Encoding the screen's 3D shadow pass.
     pub shadow_ms: f64,
+    /// Encoding the screen's 3D pass.
     pub main_3d_ms: f64,
+    /// Encoding the screen's 2D pass.
     pub overlay_ms: f64,
+    /// Encoding the final composite onto the surface or readback buffer.
     pub present_ms: f64,
+    /// GPU timings of the most recent frame whose timestamps have been read back.
+    pub gpu: Option<GpuFrameStats>,
+}
+
+/// GPU timestamp results of one frame, see [`RenderStats::gpu`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuFrameStats {
+    /// The [`RenderStats::frame`] these timings were measured for.
+    pub frame: u64,
+    /// Time from the start of the first pass to the end of the last, in milliseconds.
+    pub total_ms: f64,
+    /// Duration of each timed pass, in submission order.
+    pub passes: Vec<GpuPassTime>,
+}
+
+/// GPU duration of a single render pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuPassTime {
+    /// Which pass this is, such as `"overlay"` or `"target_2d"`.
+    pub label: &'static str,
+    /// Time between the pass's begin and end timestamps, in milliseconds.
+    pub ms: f64,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -132,8 +172,9 @@ impl MemorySampler {
 impl RenderProfiler {
     fn new() -> Self {
         let config = ProfileConfig::from_env();
-        eprintln!(
-            "[spot][profile] enabled scenario={} warmup_frames={} sample_frames={} report_every={} gpu_requested={}",
+        crate::diagnostics::info!(
+            "profile",
+            "enabled scenario={} warmup_frames={} sample_frames={} report_every={} gpu_requested={}",
             config.scenario,
             config.warmup_frames,
             config.sample_frames,
@@ -153,7 +194,7 @@ impl RenderProfiler {
         }
     }
 
-    fn record(&mut self, input: FrameProfileInput, scene: SceneTimes) -> bool {
+    fn record(&mut self, input: &RenderStats, scene: SceneTimes) -> bool {
         let now = Instant::now();
         let frame_interval_ms = self
             .last_frame_at
@@ -164,14 +205,17 @@ impl RenderProfiler {
 
         if self.rendered_frames <= self.config.warmup_frames || self.sample_complete {
             if self.rendered_frames == self.config.warmup_frames {
-                eprintln!("[spot][profile] warmup complete; sampling starts on the next frame");
+                crate::diagnostics::info!(
+                    "profile",
+                    "warmup complete; sampling starts on the next frame"
+                );
             }
             return false;
         }
 
         self.sampled_frames += 1;
         let sample = FrameSample {
-            frame: input.frame_id,
+            frame: input.frame,
             sample: self.sampled_frames,
             frame_interval_ms,
             engine_ms: input.engine_ms,
@@ -193,8 +237,9 @@ impl RenderProfiler {
             self.samples.pop_front();
             if !self.warned_capacity {
                 self.warned_capacity = true;
-                eprintln!(
-                    "[spot][profile] sample ring reached {}; oldest samples will be discarded",
+                crate::diagnostics::warn!(
+                    "profile",
+                    "sample ring reached {}; oldest samples will be discarded",
                     self.config.max_samples
                 );
             }
@@ -237,8 +282,9 @@ impl RenderProfiler {
         let gpu = values(&self.samples, |s| s.gpu_ms);
         let rss = values(&self.samples, |s| s.rss_mb);
         let label = if final_report { "final" } else { "report" };
-        eprintln!(
-            "[spot][profile][{}] samples={} gpu_samples={} frame_mean={:.2}ms frame_p50={:.2}ms frame_p95={:.2}ms frame_p99={:.2}ms frame_max={:.2}ms engine_mean={:.2}ms engine_p95={:.2}ms work_mean={:.2}ms work_p95={:.2}ms update_mean={:.2}ms draw_mean={:.2}ms gpu_mean={} gpu_p95={} rss_max={}",
+        crate::diagnostics::info!(
+            "profile",
+            "[{}] samples={} gpu_samples={} frame_mean={:.2}ms frame_p50={:.2}ms frame_p95={:.2}ms frame_p99={:.2}ms frame_max={:.2}ms engine_mean={:.2}ms engine_p95={:.2}ms work_mean={:.2}ms work_p95={:.2}ms update_mean={:.2}ms draw_mean={:.2}ms gpu_mean={} gpu_p95={} rss_max={}",
             label,
             self.samples.len(),
             gpu.len(),
@@ -264,11 +310,11 @@ impl RenderProfiler {
     fn write_outputs(&self) -> std::io::Result<()> {
         if let Some(path) = self.config.frame_csv.as_deref() {
             write_frame_csv(path, &self.samples)?;
-            eprintln!("[spot][profile] wrote frame samples to {}", path.display());
+            crate::diagnostics::info!("profile", "wrote frame samples to {}", path.display());
         }
         if let Some(path) = self.config.summary_csv.as_deref() {
             write_summary_csv(path, &self.config.scenario, &self.samples)?;
-            eprintln!("[spot][profile] wrote summary to {}", path.display());
+            crate::diagnostics::info!("profile", "wrote summary to {}", path.display());
         }
         Ok(())
     }
@@ -288,7 +334,13 @@ pub(crate) fn render_profiling_enabled() -> bool {
 }
 
 pub(crate) fn gpu_profiling_requested() -> bool {
-    render_profiling_enabled() && env_bool("SPOT_PROFILE_GPU", true)
+    GPU_TIMING_REQUESTED.load(Ordering::Relaxed)
+        || (render_profiling_enabled() && env_bool("SPOT_PROFILE_GPU", true))
+}
+
+/// Asks for timestamp queries on devices created from now on.
+pub(crate) fn request_gpu_timing(enabled: bool) {
+    GPU_TIMING_REQUESTED.store(enabled, Ordering::Relaxed);
 }
 
 pub(crate) fn next_render_frame_id() -> u64 {
@@ -317,7 +369,7 @@ pub(crate) fn record_scene_draw(elapsed_ms: f64) {
     times.draw_ms += elapsed_ms;
 }
 
-pub(crate) fn record_render_frame(input: FrameProfileInput) {
+pub(crate) fn record_render_frame(input: &RenderStats) {
     if !render_profiling_enabled() {
         return;
     }
@@ -350,7 +402,7 @@ pub(crate) fn finalize_render_profiling() {
     {
         profiler.print_report(true);
         if let Err(error) = profiler.write_outputs() {
-            eprintln!("[spot][profile] failed to write output: {error}");
+            crate::diagnostics::error!("profile", "failed to write output: {error}");
         }
     }
 }
@@ -359,8 +411,9 @@ pub(crate) struct GpuTimestampProfiler {
     slots: Vec<GpuTimestampSlot>,
     next_slot: usize,
     period_ns: f64,
-    sender: mpsc::Sender<(u64, f64)>,
-    receiver: mpsc::Receiver<(u64, f64)>,
+    sender: mpsc::Sender<GpuFrameStats>,
+    receiver: mpsc::Receiver<GpuFrameStats>,
+    latest: Option<GpuFrameStats>,
 }
 
 struct GpuTimestampSlot {
@@ -375,10 +428,10 @@ pub(crate) struct GpuFrameQuery {
     resolve_buffer: wgpu::Buffer,
     read_buffer: wgpu::Buffer,
     busy: Arc<AtomicBool>,
-    sender: mpsc::Sender<(u64, f64)>,
+    sender: mpsc::Sender<GpuFrameStats>,
     frame_id: u64,
     period_ns: f64,
-    used_queries: u32,
+    labels: Vec<&'static str>,
 }
 
 impl GpuTimestampProfiler {
@@ -408,8 +461,9 @@ impl GpuTimestampProfiler {
             });
         }
         let (sender, receiver) = mpsc::channel();
-        eprintln!(
-            "[spot][profile] GPU timestamp profiling enabled (period {:.3}ns)",
+        crate::diagnostics::info!(
+            "profile",
+            "GPU timestamp profiling enabled (period {:.3}ns)",
             queue.get_timestamp_period()
         );
         Self {
@@ -418,6 +472,7 @@ impl GpuTimestampProfiler {
             period_ns: queue.get_timestamp_period() as f64,
             sender,
             receiver,
+            latest: None,
         }
     }
 
@@ -445,7 +500,7 @@ impl GpuTimestampProfiler {
                     sender: self.sender.clone(),
                     frame_id,
                     period_ns: self.period_ns,
-                    used_queries: 0,
+                    labels: Vec::new(),
                 });
             }
         }
@@ -453,11 +508,17 @@ impl GpuTimestampProfiler {
     }
 
     pub(crate) fn drain_results(&mut self) {
-        while let Ok((frame_id, gpu_ms)) = self.receiver.try_recv() {
-            record_gpu_frame(frame_id, gpu_ms);
+        while let Ok(stats) = self.receiver.try_recv() {
+            record_gpu_frame(stats.frame, stats.total_ms);
+            self.latest = Some(stats);
         }
     }
 
+    /// Returns the timings of the newest frame read back so far.
+    pub(crate) fn latest(&self) -> Option<&GpuFrameStats> {
+        self.latest.as_ref()
+    }
+
     pub(crate) fn finish(&mut self, device: &wgpu::Device) {
         for _ in 0..=self.slots.len() {
             let _ = device.poll(wgpu::PollType::Wait {
@@ -477,12 +538,16 @@ impl GpuTimestampProfiler {
 }
 
 impl GpuFrameQuery {
-    pub(crate) fn timestamp_writes(&mut self) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
-        if self.used_queries + 2 > MAX_GPU_TIMESTAMP_QUERIES {
+    /// Allocates the begin/end timestamps of a pass reported as `label`.
+    pub(crate) fn timestamp_writes(
+        &mut self,
+        label: &'static str,
+    ) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        let beginning = self.used_queries();
+        if beginning + 2 > MAX_GPU_TIMESTAMP_QUERIES {
             return None;
         }
-        let beginning = self.used_queries;
-        self.used_queries += 2;
+        self.labels.push(label);
         Some(wgpu::RenderPassTimestampWrites {
             query_set: &self.query_set,
             beginning_of_pass_write_index: Some(beginning),
@@ -490,24 +555,25 @@ impl GpuFrameQuery {
         })
     }
 
+    fn used_queries(&self) -> u32 {
+        self.labels.len() as u32 * 2
+    }
+
     pub(crate) fn resolve_and_map(self, encoder: &mut wgpu::CommandEncoder) {
-        if self.used_queries == 0 {
+        let used_queries = self.used_queries();
+        if used_queries == 0 {
             self.busy.store(false, Ordering::Release);
             return;
         }
-        let byte_size = self.used_queries as u64 * 8;
-        encoder.resolve_query_set(
-            &self.query_set,
-            0..self.used_queries,
-            &self.resolve_buffer,
-            0,
-        );
+        let byte_size = used_queries as u64 * 8;
+        encoder.resolve_query_set(&self.query_set, 0..used_queries, &self.resolve_buffer, 0);
         encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.read_buffer, 0, byte_size);
         let callback_buffer = self.read_buffer.clone();
         let busy = self.busy;
         let sender = self.sender;
         let frame_id = self.frame_id;
         let period_ns = self.period_ns;
+        let labels = self.labels;
         encoder.map_buffer_on_submit(
             &self.read_buffer,
             wgpu::MapMode::Read,
@@ -515,18 +581,18 @@ impl GpuFrameQuery {
             move |result| {
                 if result.is_ok() {
                     let mapped = callback_buffer.get_mapped_range(0..byte_size);
-                    if let Some(frame_ticks) = gpu_timestamp_span_ticks(&mapped) {
-                        let gpu_ms = frame_ticks as f64 * period_ns / 1_000_000.0;
-                        let _ = sender.send((frame_id, gpu_ms));
+                    if let Some(stats) = gpu_frame_stats(frame_id, &labels, &mapped, period_ns) {
+                        let _ = sender.send(stats);
                     } else if !GPU_READBACK_WARNED.swap(true, Ordering::Relaxed) {
-                        eprintln!(
-                            "[spot][profile] GPU timestamp query returned no valid pass pairs"
+                        crate::diagnostics::error!(
+                            "profile",
+                            "GPU timestamp query returned no valid pass pairs"
                         );
                     }
                     drop(mapped);
                     callback_buffer.unmap();
                 } else if !GPU_READBACK_WARNED.swap(true, Ordering::Relaxed) {
-                    eprintln!("[spot][profile] GPU timestamp readback mapping failed");
+                    crate::diagnostics::error!("profile", "GPU timestamp readback mapping failed");
                 }
                 busy.store(false, Ordering::Release);
             },
@@ -554,6 +620,33 @@ fn gpu_timestamp_span_ticks(bytes: &[u8]) -> Option<u64> {
     }
 }
 
+fn gpu_frame_stats(
+    frame: u64,
+    labels: &[&'static str],
+    bytes: &[u8],
+    period_ns: f64,
+) -> Option<GpuFrameStats> {
+    let ticks_to_ms = |ticks: u64| ticks as f64 * period_ns / 1_000_000.0;
+    let total_ticks = gpu_timestamp_span_ticks(bytes)?;
+    let passes = labels
+        .iter()
+        .zip(bytes.chunks_exact(16))
+        .filter_map(|(label, pair)| {
+            let start = u64::from_le_bytes(pair[0..8].try_into().unwrap());
+            let end = u64::from_le_bytes(pair[8..16].try_into().unwrap());
+            (start > 0 && end >= start).then(|| GpuPassTime {
+                label,
+                ms: ticks_to_ms(end - start),
+            })
+        })
+        .collect();
+    Some(GpuFrameStats {
+        frame,
+        total_ms: ticks_to_ms(total_ticks),
+        passes,
+    })
+}
+
 fn env_bool(name: &str, default: bool) -> bool {
     std::env::var(name)
         .ok()
@@ -819,4 +912,19 @@ mod tests {
         assert_eq!(gpu_timestamp_span_ticks(&bytes), Some(160));
         assert_eq!(gpu_timestamp_span_ticks(&[]), None);
     }
+
+    #[test]
+    fn gpu_frame_stats_labels_valid_passes() {
+        let mut bytes = Vec::new();
+        for timestamp in [100u64, 200, 0, 0, 250, 400] {
+            bytes.extend_from_slice(&timestamp.to_le_bytes());
+        }
+        let stats = gpu_frame_stats(7, &["shadow", "main_3d", "overlay"], &bytes, 1000.0).unwrap();
+        assert_eq!(stats.frame, 7);
+        assert!((stats.total_ms - 0.3).abs() < 1e-9);
+        let passes: Vec<_> = stats.passes.iter().map(|pass| pass.label).collect();
+        assert_eq!(passes, ["shadow", "overlay"]);
+        assert!((stats.passes[1].ms - 0.15).abs() < 1e-9);
+        assert_eq!(gpu_frame_stats(7, &[], &[], 1000.0), None);
+    }
 }
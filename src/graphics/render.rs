@@ -1,6 +1,7 @@
 //! Batch rendering and draw operations.
 
 use crate::Context;
+use crate::FrameCapture;
 use crate::ImageRepeat;
 use crate::ShaderOpts;
 use crate::drawable::DrawCommand;
@@ -12,6 +13,7 @@ use std::time::Instant;
 use web_time::Instant;
 
 use super::core::{Graphics, ResolvedDraw, ResolvedImageShaderInput};
+use super::errors::{DrawError, report_draw};
 use super::image_ops::resolve_image_uv;
 use super::image_pipeline::ImagePipeline;
 use crate::image_raw::ImageRenderer;
@@ -27,6 +29,42 @@ pub(crate) struct RenderConfig<'a> {
     pub history_snapshots: &'a HashMap<u32, crate::graphics::texture::GpuTexture>,
 }
 
+/// Where a rendered frame ends up once the screen texture is complete.
+#[cfg_attr(
+    any(target_os = "android", target_os = "ios", target_arch = "wasm32"),
+    allow(dead_code)
+)]
+#[derive(Clone, Copy)]
+enum FrameTarget<'a, 's> {
+    Surface(&'a wgpu::Surface<'s>),
+    Readback(&'a wgpu::Buffer),
+    /// Presented to the surface and also copied to the buffer.
+    Recorded(&'a wgpu::Surface<'s>, &'a wgpu::Buffer),
+}
+
+fn padded_bytes_per_row(width: u32) -> u32 {
+    (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+}
+
+#[cfg_attr(
+    any(target_os = "android", target_os = "ios", target_arch = "wasm32"),
+    allow(dead_code)
+)]
+fn unpad_rows(padded: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let row_bytes = (width * 4) as usize;
+    let padded_row_bytes = padded_bytes_per_row(width) as usize;
+    let mut rgba = Vec::with_capacity(row_bytes * height as usize);
+    for row in padded.chunks(padded_row_bytes).take(height as usize) {
+        rgba.extend_from_slice(&row[..row_bytes]);
+    }
+    rgba
+}
+
+fn report_internal(ctx: &mut Context, message: String) {
+    let kind = crate::GpuErrorKind::Internal;
+    crate::graphics::errors::report(ctx, crate::GpuError { kind, message });
+}
+
 fn expect_image_pipeline<'a>(
     image_pipelines: &'a HashMap<u32, ImagePipeline>,
     default_pipeline: &'a wgpu::RenderPipeline,
@@ -165,17 +203,39 @@ fn resolve_repeat_params(opts: crate::DrawOption, source_size: [crate::Pt; 2]) -
     ]
 }
 
+/// Whether a quad drawn with `opts` at `size` lies entirely outside a `viewport` sized
+/// target. The quad rotates about its top-left corner, like in the image shader.
+fn outside_viewport(opts: &crate::DrawOption, size: [f32; 2], viewport: [f32; 2]) -> bool {
+    let [x, y] = opts.position();
+    let (s, c) = opts.rotation().sin_cos();
+    let (mut min, mut max) = ([f32::MAX; 2], [f32::MIN; 2]);
+    for (ox, oy) in [
+        (0.0, 0.0),
+        (size[0], 0.0),
+        (0.0, size[1]),
+        (size[0], size[1]),
+    ] {
+        let corner = [x.as_f32() + c * ox + s * oy, y.as_f32() + c * oy - s * ox];
+        for axis in 0..2 {
+            min[axis] = min[axis].min(corner[axis]);
+            max[axis] = max[axis].max(corner[axis]);
+        }
+    }
+    max[0] < 0.0 || max[1] < 0.0 || min[0] > viewport[0] || min[1] > viewport[1]
+}
+
 impl Graphics {
+    /// Resolves the draws into `target_texture_id` for batching, skipping default-shader
+    /// quads outside the `viewport`, its logical size.
     pub(crate) fn resolve_drawables(
         &mut self,
         ctx: &mut Context,
         drawables: &[DrawCommand],
         target_texture_id: u32,
-        logical_w: u32,
-        logical_h: u32,
+        viewport: [f32; 2],
     ) {
         self.resolved_draws.clear();
-        let viewport_rect = [0.0, 0.0, logical_w as f32, logical_h as f32];
+        let viewport_rect = [0.0, 0.0, viewport[0], viewport[1]];
 
         for drawable in drawables {
             match drawable {
@@ -195,6 +255,33 @@ impl Graphics {
                         if !entry.visible || !texture_entry.is_ready(self.gpu_generation) {
                             continue;
                         }
+                        if cmd.shader_id != 0 && !self.image_pipelines.contains_key(&cmd.shader_id)
+                        {
+                            let shader_id = cmd.shader_id;
+                            report_draw(ctx, DrawError::MissingShader { shader_id });
+                            continue;
+                        }
+                        if texture_entry.runtime.bind_group.is_none() {
+                            let texture_id = entry.texture_id;
+                            let image_id = cmd.id;
+                            report_draw(
+                                ctx,
+                                DrawError::MissingTexture {
+                                    image_id,
+                                    texture_id,
+                                },
+                            );
+                            continue;
+                        }
+                        // Custom shaders may move vertices, so only default draws are culled.
+                        let size = [
+                            entry.bounds.width.as_f32() * cmd.opts.scale()[0],
+                            entry.bounds.height.as_f32() * cmd.opts.scale()[1],
+                        ];
+                        if cmd.shader_id == 0 && outside_viewport(&cmd.opts, size, viewport) {
+                            ctx.runtime.draw_stats.culled += 1;
+                            continue;
+                        }
 
                         let mut extra_inputs =
                             [ResolvedImageShaderInput::Texture(entry.texture_id); 4];
@@ -255,6 +342,35 @@ impl Graphics {
                             }
                         }
 
+                        // Extra images may still be uploading; drawing without them would
+                        // leave the shader's bind group incomplete.
+                        let missing_input = extra_inputs.iter().find_map(|input| match input {
+                            ResolvedImageShaderInput::Texture(texture_id)
+                                if !ctx
+                                    .registry
+                                    .textures
+                                    .get(*texture_id as usize)
+                                    .and_then(Option::as_ref)
+                                    .is_some_and(|texture| {
+                                        texture.is_ready(self.gpu_generation)
+                                    }) =>
+                            {
+                                Some(*texture_id)
+                            }
+                            _ => None,
+                        });
+                        if let Some(texture_id) = missing_input {
+                            let image_id = cmd.id;
+                            report_draw(
+                                ctx,
+                                DrawError::MissingTexture {
+                                    image_id,
+                                    texture_id,
+                                },
+                            );
+                            continue;
+                        }
+
                         self.resolved_draws.push(ResolvedDraw {
                             texture_id: entry.texture_id,
                             extra_inputs,
@@ -273,11 +389,12 @@ impl Graphics {
                     if let Err(e) =
                         self.layout_and_queue_text(ctx, &cmd.text, &cmd.opts, viewport_rect)
                     {
-                        eprintln!("[spot] Text layout error: {:?}", e);
+                        crate::diagnostics::error!("text", "layout error: {:?}", e);
                     }
                 }
             }
         }
+        ctx.runtime.draw_stats.drawn += self.resolved_draws.len() as u32;
     }
 
     pub(crate) fn render_batches_internal<'a>(
@@ -288,7 +405,11 @@ impl Graphics {
         rpass: &mut wgpu::RenderPass<'a>,
         config: RenderConfig<'a>,
         ctx: &'a Context,
-    ) {
+    ) -> crate::FrameStats {
+        let mut stats = crate::FrameStats {
+            instances: resolved_draws.len() as u32,
+            ..Default::default()
+        };
         let mut current_opacity = 1.0f32;
 
         // Upload initial engine globals
@@ -322,14 +443,30 @@ impl Graphics {
 
             let effective_user_globals = shader_opts;
 
-            let state_changed = current_texture_id != Some(resolved.texture_id)
-                || current_extra_inputs != resolved.extra_inputs
-                || current_shader_id != shader_id
+            let texture_changed = current_texture_id != Some(resolved.texture_id)
+                || current_extra_inputs != resolved.extra_inputs;
+            let shader_changed = current_shader_id != shader_id;
+            let opacity_changed = current_opacity != draw_opacity
+                || current_user_globals.opacity != effective_user_globals.opacity;
+            let state_changed = texture_changed
+                || shader_changed
                 || current_user_globals != effective_user_globals
                 || current_opacity != draw_opacity;
 
             if state_changed && !batch.is_empty() {
-                if let Ok(range) = image_renderer.upload_instances(queue, batch.as_slice()) {
+                let breaks = &mut stats.batch_breaks;
+                if texture_changed {
+                    breaks.texture += 1;
+                } else if shader_changed {
+                    breaks.shader += 1;
+                } else if opacity_changed {
+                    breaks.opacity += 1;
+                } else {
+                    breaks.shader_opts += 1;
+                }
+                if let Ok(range) =
+                    image_renderer.upload_instances(config.device, queue, batch.as_slice())
+                {
                     let (pipeline, uses_extra_textures) = expect_image_pipeline(
                         config.image_pipelines,
                         config.default_pipeline,
@@ -371,6 +508,7 @@ impl Graphics {
                     } else {
                         None
                     };
+                    stats.draw_calls += 1;
                     image_renderer.draw_batch(
                         rpass,
                         pipeline,
@@ -423,12 +561,14 @@ impl Graphics {
                     opts,
                     [resolved.bounds.width, resolved.bounds.height],
                 ),
+                color: opts.tint(),
                 ..Default::default()
             });
         }
 
         if !batch.is_empty()
-            && let Ok(range) = image_renderer.upload_instances(queue, batch.as_slice())
+            && let Ok(range) =
+                image_renderer.upload_instances(config.device, queue, batch.as_slice())
         {
             let (pipeline, uses_extra_textures) = expect_image_pipeline(
                 config.image_pipelines,
@@ -471,6 +611,7 @@ impl Graphics {
             } else {
                 None
             };
+            stats.draw_calls += 1;
             image_renderer.draw_batch(
                 rpass,
                 pipeline,
@@ -481,6 +622,7 @@ impl Graphics {
                 current_engine_globals_offset,
             );
         }
+        stats
     }
 
     pub fn draw_context(
@@ -488,36 +630,143 @@ impl Graphics {
         surface: &wgpu::Surface<'_>,
         ctx: &mut Context,
     ) -> Result<(), wgpu::SurfaceError> {
-        let profile_enabled = crate::graphics::profile::render_profiling_enabled();
-        let engine_started_at = profile_enabled.then(Instant::now);
-        let profile_frame_id = profile_enabled
-            .then(crate::graphics::profile::next_render_frame_id)
-            .unwrap_or(0);
-        let prepare_started_at = profile_enabled.then(Instant::now);
+        let frames_due = ctx
+            .runtime
+            .recording
+            .as_ref()
+            .map_or(0, |recording| recording.frames_due());
+        #[cfg(feature = "gif")]
+        let replay_due = ctx
+            .runtime
+            .replay
+            .as_ref()
+            .is_some_and(|replay| replay.wants_frame());
+        #[cfg(not(feature = "gif"))]
+        let replay_due = false;
+        if frames_due == 0 && !replay_due {
+            let result = self.draw_frame(FrameTarget::Surface(surface), ctx);
+            self.gpu_errors.drain_into(ctx, result.as_ref().err());
+            return result;
+        }
+
+        let readback = self.create_readback_buffer();
+        let result = self.draw_frame(FrameTarget::Recorded(surface, &readback), ctx);
+        self.gpu_errors.drain_into(ctx, result.as_ref().err());
+        result?;
+        match self.read_back(&readback) {
+            Ok(capture) => {
+                #[cfg(feature = "gif")]
+                if replay_due && let Some(replay) = ctx.runtime.replay.as_mut() {
+                    replay.push(&capture);
+                }
+                if frames_due > 0
+                    && let Some(recording) = ctx.runtime.recording.as_mut()
+                {
+                    recording.push(capture, frames_due);
+                }
+            }
+            Err(error) => {
+                crate::diagnostics::error!("graphics", "failed to record frame: {error:?}")
+            }
+        }
+        if ctx
+            .runtime
+            .recording
+            .as_ref()
+            .is_some_and(|recording| recording.is_complete())
+            && let Err(error) = crate::stop_recording(ctx)
+        {
+            crate::diagnostics::error!("graphics", "failed to write recording: {error}");
+        }
+        Ok(())
+    }
+
+    /// Renders a frame without presenting it and reads the screen texture back.
+    #[cfg_attr(
+        any(target_os = "android", target_os = "ios", target_arch = "wasm32"),
+        allow(dead_code)
+    )]
+    pub(crate) fn draw_headless(&mut self, ctx: &mut Context) -> anyhow::Result<FrameCapture> {
+        let readback = self.create_readback_buffer();
+        let result = self.draw_frame(FrameTarget::Readback(&readback), ctx);
+        self.gpu_errors.drain_into(ctx, result.as_ref().err());
+        result.map_err(|e| anyhow::anyhow!("headless frame failed: {e:?}"))?;
+        self.read_back(&readback)
+    }
+
+    fn create_readback_buffer(&self) -> wgpu::Buffer {
+        self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame_readback_buffer"),
+            size: padded_bytes_per_row(self.config.width) as u64 * self.config.height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Waits for a frame copied to `readback` and returns its pixels as RGBA.
+    fn read_back(&self, readback: &wgpu::Buffer) -> anyhow::Result<FrameCapture> {
+        let width = self.config.width;
+        let height = self.config.height;
+        let slice = readback.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::PollType::wait_indefinitely())?;
+        rx.recv()??;
+        let mut rgba = unpad_rows(&slice.get_mapped_range(), width, height);
+        readback.unmap();
+        if matches!(
+            self.config.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            for pixel in rgba.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+        Ok(FrameCapture {
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    fn draw_frame(
+        &mut self,
+        target: FrameTarget<'_, '_>,
+        ctx: &mut Context,
+    ) -> Result<(), wgpu::SurfaceError> {
+        let _span = crate::diagnostics::span!("render", "frame");
+        let engine_started_at = Instant::now();
+        ctx.runtime.frame_stats = crate::FrameStats::default();
+        let profile_frame_id = crate::graphics::profile::next_render_frame_id();
+        let prepare_started_at = Instant::now();
         #[cfg(feature = "model-3d")]
         if !ctx.runtime.model_3d.draw_list.is_empty() {
             self.ensure_model_3d();
         }
         self.sync_assets(ctx).map_err(|e| {
-            eprintln!("[spot][graphics] sync_assets failed: {:?}", e);
+            crate::diagnostics::error!("graphics", "sync_assets failed: {:?}", e);
+            report_internal(ctx, format!("sync_assets failed: {e:?}"));
             wgpu::SurfaceError::Lost
         })?;
+        self.receive_rasterized_glyphs(ctx);
+        self.mark_drawn_textures(ctx, profile_frame_id);
         let _ = self.process_registrations(ctx);
-        let draws = std::mem::take(&mut ctx.runtime.draw_list);
+        let mut draws = std::mem::take(&mut ctx.runtime.draw_list);
+        ctx.runtime.draw_stats = crate::DrawStats::default();
         self.prepare_frame_resources(ctx, &draws).map_err(|e| {
-            eprintln!("[spot][graphics] prepare_frame_resources failed: {:?}", e);
+            crate::diagnostics::error!("graphics", "prepare_frame_resources failed: {:?}", e);
+            report_internal(ctx, format!("prepare_frame_resources failed: {e:?}"));
             wgpu::SurfaceError::Lost
         })?;
-        let prepare_ms = prepare_started_at
-            .map(|started| started.elapsed().as_secs_f64() * 1000.0)
-            .unwrap_or(0.0);
+        let prepare_ms = prepare_started_at.elapsed().as_secs_f64() * 1000.0;
 
         #[allow(unused_mut, unused_assignments)]
         let mut shadow_ms = 0.0;
         #[allow(unused_mut, unused_assignments)]
         let mut main_3d_ms = 0.0;
         let overlay_ms;
-        let present_ms;
 
         #[cfg(feature = "model-3d")]
         if let Some(model_3d) = self.model_3d_mut() {
@@ -536,21 +785,30 @@ impl Graphics {
             });
 
         let targets_started_at = Instant::now();
-        self.render_all_targets(ctx, &draws, &mut encoder, gpu_frame_query.as_mut());
+        {
+            let _span = crate::diagnostics::span!("render", "targets");
+            self.render_all_targets(ctx, &draws, &mut encoder, gpu_frame_query.as_mut());
+        }
         let targets_ms = targets_started_at.elapsed().as_secs_f64() * 1000.0;
 
         let wait_started_at = Instant::now();
-        let frame = match surface.get_current_texture() {
-            Ok(f) => f,
-            Err(e) => {
-                eprintln!("[spot][graphics] get_current_texture failed: {:?}", e);
-                return Err(e);
+        let frame = match target {
+            FrameTarget::Surface(surface) | FrameTarget::Recorded(surface, _) => {
+                match surface.get_current_texture() {
+                    Ok(f) => Some(f),
+                    Err(e) => {
+                        crate::diagnostics::error!(
+                            "graphics",
+                            "get_current_texture failed: {:?}",
+                            e
+                        );
+                        return Err(e);
+                    }
+                }
             }
+            FrameTarget::Readback(_) => None,
         };
         let wait_ms = wait_started_at.elapsed().as_secs_f64() * 1000.0;
-        let surface_view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
         let width = self.config.width;
         let height = self.config.height;
         let final_screen_texture = self.ensure_final_screen_texture(width, height);
@@ -570,6 +828,7 @@ impl Graphics {
                 .unwrap_or(false)
         {
             let shadow_started_at = Instant::now();
+            let _span = crate::diagnostics::span!("render", "shadow");
             self.render_shadow_pass(
                 &mut encoder,
                 ctx,
@@ -578,16 +837,23 @@ impl Graphics {
                 0,
                 gpu_frame_query
                     .as_mut()
-                    .and_then(|query| query.timestamp_writes()),
+                    .and_then(|query| query.timestamp_writes("shadow")),
             );
             shadow_ms = shadow_started_at.elapsed().as_secs_f64() * 1000.0;
         }
 
-        self.resolve_drawables(ctx, &draws, 0, width, height);
+        let scale_factor = ctx.scale_factor() as f32;
+        self.resolve_drawables(
+            ctx,
+            &draws,
+            0,
+            [width as f32 / scale_factor, height as f32 / scale_factor],
+        );
 
         #[cfg(feature = "model-3d")]
         {
             let main_3d_started_at = Instant::now();
+            let _span = crate::diagnostics::span!("render", "main_3d");
             let depth_stencil_attachment =
                 self.model_3d()
                     .map(|model_3d| wgpu::RenderPassDepthStencilAttachment {
@@ -627,7 +893,7 @@ impl Graphics {
                 depth_stencil_attachment,
                 timestamp_writes: gpu_frame_query
                     .as_mut()
-                    .and_then(|query| query.timestamp_writes()),
+                    .and_then(|query| query.timestamp_writes("main_3d")),
                 occlusion_query_set: None,
                 multiview_mask: None,
             });
@@ -651,6 +917,7 @@ impl Graphics {
 
         {
             let overlay_started_at = Instant::now();
+            let _span = crate::diagnostics::span!("render", "overlay");
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("main_overlay_render_pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -682,7 +949,7 @@ impl Graphics {
                 depth_stencil_attachment: None,
                 timestamp_writes: gpu_frame_query
                     .as_mut()
-                    .and_then(|query| query.timestamp_writes()),
+                    .and_then(|query| query.timestamp_writes("overlay")),
                 occlusion_query_set: None,
                 multiview_mask: None,
             });
@@ -702,7 +969,7 @@ impl Graphics {
             let lh = height as f32 / screen_scale_factor;
             let screen_size_data = [2.0 / lw, 2.0 / lh, 1.0 / lw, 1.0 / lh];
 
-            Self::render_batches_internal(
+            let batch_stats = Self::render_batches_internal(
                 &mut self.image_renderer,
                 &self.queue,
                 &mut self.batch,
@@ -719,6 +986,8 @@ impl Graphics {
                 },
                 ctx,
             );
+            drop(rpass);
+            ctx.runtime.frame_stats.add(batch_stats);
             overlay_ms = overlay_started_at.elapsed().as_secs_f64() * 1000.0;
         }
 
@@ -733,8 +1002,30 @@ impl Graphics {
             self.config.format,
         );
 
-        {
-            let present_started_at = Instant::now();
+        let present_started_at = Instant::now();
+        if let FrameTarget::Readback(buffer) | FrameTarget::Recorded(_, buffer) = target {
+            encoder.copy_texture_to_buffer(
+                final_screen_texture.0.texture.as_image_copy(),
+                wgpu::TexelCopyBufferInfo {
+                    buffer,
+                    layout: wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_bytes_per_row(width)),
+                        rows_per_image: Some(height),
+                    },
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        if let Some(frame) = frame.as_ref() {
+            let _span = crate::diagnostics::span!("render", "present");
+            let surface_view = frame
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("present_render_pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -749,7 +1040,7 @@ impl Graphics {
                 depth_stencil_attachment: None,
                 timestamp_writes: gpu_frame_query
                     .as_mut()
-                    .and_then(|query| query.timestamp_writes()),
+                    .and_then(|query| query.timestamp_writes("present")),
                 occlusion_query_set: None,
                 multiview_mask: None,
             });
@@ -776,6 +1067,7 @@ impl Graphics {
             let range = self
                 .image_renderer
                 .upload_instances(
+                    &self.device,
                     &self.queue,
                     &[InstanceData {
                         pos: [0.0, 0.0],
@@ -798,8 +1090,8 @@ impl Graphics {
                 user_offset,
                 engine_offset,
             );
-            present_ms = present_started_at.elapsed().as_secs_f64() * 1000.0;
         }
+        let present_ms = present_started_at.elapsed().as_secs_f64() * 1000.0;
 
         self.image_renderer.flush_pending_uploads(&self.queue);
         #[cfg(feature = "model-3d")]
@@ -810,22 +1102,34 @@ impl Graphics {
             query.resolve_and_map(&mut encoder);
         }
         self.queue.submit(std::iter::once(encoder.finish()));
-        frame.present();
-        if let Some(engine_started_at) = engine_started_at {
-            crate::graphics::profile::record_render_frame(
-                crate::graphics::profile::FrameProfileInput {
-                    frame_id: profile_frame_id,
-                    engine_ms: engine_started_at.elapsed().as_secs_f64() * 1000.0,
-                    wait_ms,
-                    prepare_ms,
-                    targets_ms,
-                    shadow_ms,
-                    main_3d_ms,
-                    overlay_ms,
-                    present_ms,
-                },
-            );
+        if let Some(frame) = frame {
+            frame.present();
+        }
+        // Hand the list back so the next frame records into the same allocation.
+        draws.clear();
+        if ctx.runtime.draw_list.is_empty() {
+            ctx.runtime.draw_list = draws;
+        }
+        if let Some(pipeline_cache) = self.pipeline_cache.as_ref() {
+            pipeline_cache.save_if_dirty();
         }
+        self.enforce_texture_budget(ctx, profile_frame_id);
+        ctx.runtime.render_stats = crate::RenderStats {
+            frame: profile_frame_id,
+            engine_ms: engine_started_at.elapsed().as_secs_f64() * 1000.0,
+            wait_ms,
+            prepare_ms,
+            targets_ms,
+            shadow_ms,
+            main_3d_ms,
+            overlay_ms,
+            present_ms,
+            gpu: self
+                .gpu_profiler
+                .as_ref()
+                .and_then(|profiler| profiler.latest().cloned()),
+        };
+        crate::graphics::profile::record_render_frame(&ctx.runtime.render_stats);
 
         Ok(())
     }
@@ -1205,12 +1509,17 @@ impl Graphics {
                     target_texture_id,
                     gpu_frame_query
                         .as_deref_mut()
-                        .and_then(|query| query.timestamp_writes()),
+                        .and_then(|query| query.timestamp_writes("target_shadow")),
                 );
             }
         }
 
-        self.resolve_drawables(ctx, drawables, target_texture_id, width, height);
+        self.resolve_drawables(
+            ctx,
+            drawables,
+            target_texture_id,
+            [bounds.width.as_f32(), bounds.height.as_f32()],
+        );
         let mut target_resolved = std::mem::take(&mut self.resolved_draws);
 
         {
@@ -1225,9 +1534,12 @@ impl Graphics {
                 let texture_obj = runtime.gpu_texture.as_ref().unwrap();
 
                 if runtime.generation != self.gpu_generation {
-                    eprintln!(
-                        "[spot][render] WARNING: Rendering to texture {} with generation mismatch ({} vs {})",
-                        target_texture_id, runtime.generation, self.gpu_generation
+                    crate::diagnostics::warn!(
+                        "render",
+                        "Rendering to texture {} with generation mismatch ({} vs {})",
+                        target_texture_id,
+                        runtime.generation,
+                        self.gpu_generation
                     );
                 }
 
@@ -1287,7 +1599,7 @@ impl Graphics {
                         }),
                         timestamp_writes: gpu_frame_query
                             .as_deref_mut()
-                            .and_then(|query| query.timestamp_writes()),
+                            .and_then(|query| query.timestamp_writes("target_3d")),
                         occlusion_query_set: None,
                         multiview_mask: None,
                     });
@@ -1328,7 +1640,7 @@ impl Graphics {
                     depth_stencil_attachment: None,
                     timestamp_writes: gpu_frame_query
                         .as_deref_mut()
-                        .and_then(|query| query.timestamp_writes()),
+                        .and_then(|query| query.timestamp_writes("target_2d")),
                     occlusion_query_set: None,
                     multiview_mask: None,
                 });
@@ -1337,7 +1649,7 @@ impl Graphics {
                 let lh = bounds.height.as_f32();
                 let screen_size_data = [2.0 / lw, 2.0 / lh, 1.0 / lw, 1.0 / lh];
 
-                Self::render_batches_internal(
+                let batch_stats = Self::render_batches_internal(
                     &mut self.image_renderer,
                     &self.queue,
                     &mut self.batch,
@@ -1354,6 +1666,8 @@ impl Graphics {
                     },
                     ctx,
                 );
+                drop(rpass);
+                ctx.runtime.frame_stats.add(batch_stats);
             }
 
             Self::update_shader_snapshot(
@@ -1369,3 +1683,40 @@ impl Graphics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{outside_viewport, padded_bytes_per_row, unpad_rows};
+    use crate::{DrawOption, Pt};
+
+    #[test]
+    fn readback_rows_are_aligned_and_unpadded() {
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+
+        let mut padded = vec![0u8; 256 * 2];
+        padded[..12].copy_from_slice(&[1; 12]);
+        padded[256..268].copy_from_slice(&[2; 12]);
+        let rgba = unpad_rows(&padded, 3, 2);
+        assert_eq!(rgba.len(), 24);
+        assert!(rgba[..12].iter().all(|&b| b == 1));
+        assert!(rgba[12..].iter().all(|&b| b == 2));
+    }
+
+    #[test]
+    fn quads_are_culled_only_when_fully_off_screen() {
+        let at = |x: f32, y: f32| DrawOption::default().with_position([Pt(x), Pt(y)]);
+        let viewport = [100.0, 50.0];
+        assert!(!outside_viewport(&at(10.0, 10.0), [20.0, 20.0], viewport));
+        assert!(!outside_viewport(&at(-15.0, 40.0), [20.0, 20.0], viewport));
+        assert!(outside_viewport(&at(-25.0, 10.0), [20.0, 20.0], viewport));
+        assert!(outside_viewport(&at(10.0, 51.0), [20.0, 20.0], viewport));
+        // Flipped quads extend to the left of their position.
+        assert!(!outside_viewport(&at(105.0, 10.0), [-20.0, 20.0], viewport));
+
+        // A quad rotated half a turn about its corner ends up above and left of it.
+        assert!(!outside_viewport(&at(-1.0, 20.0), [5.0, 5.0], viewport));
+        let rotated = at(-1.0, 20.0).with_rotation(std::f32::consts::PI);
+        assert!(outside_viewport(&rotated, [5.0, 5.0], viewport));
+    }
+}
@@ -320,7 +320,11 @@ impl Graphics {
                     let instance_range = match model_renderer.upload_instances(queue, &transforms) {
                         Ok(range) => range,
                         Err(e) => {
-                            eprintln!("[spot][render] Failed to upload shadow instances: {}", e);
+                            crate::diagnostics::error!(
+                                "render",
+                                "Failed to upload shadow instances: {}",
+                                e
+                            );
                             index = next_index;
                             continue;
                         }
@@ -400,7 +404,11 @@ impl Graphics {
                         match model_renderer.upload_shared_instances(queue, transforms) {
                             Ok(range) => range,
                             Err(e) => {
-                                eprintln!("[spot][render] Failed to upload instances: {}", e);
+                                crate::diagnostics::error!(
+                                    "render",
+                                    "Failed to upload instances: {}",
+                                    e
+                                );
                                 index += 1;
                                 continue;
                             }
@@ -979,7 +987,11 @@ impl Graphics {
                         match model_renderer.upload_shared_instances(queue, transforms) {
                             Ok(range) => range,
                             Err(e) => {
-                                eprintln!("[spot][render] Failed to upload instances: {}", e);
+                                crate::diagnostics::error!(
+                                    "render",
+                                    "Failed to upload instances: {}",
+                                    e
+                                );
                                 continue;
                             }
                         };
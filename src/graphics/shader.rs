@@ -105,7 +105,7 @@ impl Graphics {
                     })],
                 }),
                 multiview_mask: None,
-                cache: None,
+                cache: self.pipeline_cache(),
             });
 
         ImagePipeline {
@@ -191,4 +191,24 @@ impl Graphics {
         let pipeline = self.create_image_pipeline_from_desc("custom_image_shader", desc);
         self.image_pipelines.insert(shader_id, pipeline);
     }
+
+    /// Compiles `desc` in place of shader `shader_id`, keeping the current pipeline when
+    /// the new source fails validation.
+    #[cfg(all(
+        feature = "hot-reload",
+        any(target_os = "windows", target_os = "macos", target_os = "linux")
+    ))]
+    pub(crate) fn recompile_image_shader(
+        &mut self,
+        shader_id: u32,
+        desc: &ImageShaderDesc,
+    ) -> anyhow::Result<()> {
+        let scope = self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipeline = self.create_image_pipeline_from_desc("custom_image_shader", desc);
+        if let Some(error) = crate::platform::block_on(scope.pop()) {
+            anyhow::bail!("{error}");
+        }
+        self.image_pipelines.insert(shader_id, pipeline);
+        Ok(())
+    }
 }
@@ -70,7 +70,7 @@ impl Graphics {
                     })],
                 }),
                 multiview_mask: None,
-                cache: None,
+                cache: self.pipeline_cache(),
             });
 
         let transparent_model_pipeline =
@@ -109,7 +109,7 @@ impl Graphics {
                         })],
                     }),
                     multiview_mask: None,
-                    cache: None,
+                    cache: self.pipeline_cache(),
                 });
 
         let instanced_model_shader =
@@ -185,7 +185,7 @@ impl Graphics {
                         })],
                     }),
                     multiview_mask: None,
-                    cache: None,
+                    cache: self.pipeline_cache(),
                 });
 
         let transparent_instanced_model_pipeline =
@@ -252,7 +252,7 @@ impl Graphics {
                         })],
                     }),
                     multiview_mask: None,
-                    cache: None,
+                    cache: self.pipeline_cache(),
                 });
 
         (
@@ -322,7 +322,7 @@ impl Graphics {
                 })],
             }),
             multiview_mask: None,
-            cache: None,
+            cache: self.pipeline_cache(),
         });
 
         let transparent_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -359,7 +359,7 @@ impl Graphics {
                 })],
             }),
             multiview_mask: None,
-            cache: None,
+            cache: self.pipeline_cache(),
         });
 
         let instanced_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -424,7 +424,7 @@ impl Graphics {
                 })],
             }),
             multiview_mask: None,
-            cache: None,
+            cache: self.pipeline_cache(),
         });
 
         let transparent_instanced_pipeline =
@@ -490,7 +490,7 @@ impl Graphics {
                     })],
                 }),
                 multiview_mask: None,
-                cache: None,
+                cache: self.pipeline_cache(),
             });
 
         self.ensure_model_3d()
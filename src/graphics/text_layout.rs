@@ -6,6 +6,7 @@ use crate::pt::Pt;
 
 use super::core::Graphics;
 use super::core::ResolvedDraw;
+use super::errors::DrawError;
 use super::image_ops::resolve_image_uv;
 
 impl Graphics {
@@ -14,7 +15,7 @@ impl Graphics {
         ctx: &mut crate::Context,
         text: &crate::Text,
         image_scale: [f32; 2],
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), DrawError> {
         use crate::text::{CachedGlyph, TextLayout};
         use ab_glyph::{Font as _, FontArc, PxScale, ScaleFont as _};
 
@@ -23,27 +24,34 @@ impl Graphics {
             if let Some(layout) = cache_lock.as_ref()
                 && layout.scale == image_scale
                 && layout.revision == text.layout_revision
+                && layout.font_revision == ctx.registry.font_revision
+                && layout.complete
             {
                 return Ok(());
             }
         }
 
+        if let Some(font) = ctx.registry.bitmap_fonts.get(&text.font_id) {
+            let layout = font.layout(text, &ctx.registry, image_scale);
+            *text.layout_cache.as_ref().lock().unwrap() = Some(layout);
+            return Ok(());
+        }
+
         let font_id = text.font_id;
         let font_data = ctx
             .registry
             .fonts
             .get(&font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font ID {} not found", font_id))?;
+            .ok_or(DrawError::MissingFont { font_id })?;
 
         let font = if let Some(cached_font) = self.get_cached_font(font_id as u64) {
             cached_font
         } else {
-            let font = FontArc::try_from_vec(font_data.clone()).unwrap_or_else(|e| {
-                panic!(
-                    "[spot][graphics] Failed to parse font with ID {}: {}",
-                    font_id, e
-                )
-            });
+            let font =
+                FontArc::try_from_vec(font_data.clone()).map_err(|e| DrawError::InvalidFont {
+                    font_id,
+                    message: e.to_string(),
+                })?;
             self.cache_font(font_id as u64, font.clone());
             font
         };
@@ -59,7 +67,7 @@ impl Graphics {
                 .map(std::borrow::Cow::Owned)
                 .collect()
         } else {
-            vec![std::borrow::Cow::Borrowed(text.content.as_str())]
+            vec![std::borrow::Cow::Borrowed(&*text.content)]
         };
 
         let mut caret_pos = [Pt(0.0), Pt(0.0)];
@@ -72,6 +80,7 @@ impl Graphics {
         let sy = image_scale[1];
 
         let mut cached_glyphs = Vec::new();
+        let mut complete = true;
 
         for line in lines {
             let mut prev: Option<ab_glyph::GlyphId> = None;
@@ -93,13 +102,23 @@ impl Graphics {
                 };
 
                 let entry = if let Some(e) = self.glyph_cache.get(&cache_key) {
-                    e.clone()
-                } else if let Ok(e) =
-                    self.render_single_glyph(ctx, font_id, px_size, glyph_id.0.into())
+                    Some(e.clone())
+                } else if self.glyph_cache.is_blank(&cache_key) {
+                    None
+                } else if let Some(rasterizer) = self.glyph_rasterizer.as_mut() {
+                    rasterizer.request(cache_key, &font, px_size, scale_factor);
+                    complete = false;
+                    None
+                } else if let Some(bitmap) =
+                    super::font::rasterize_glyph(&font, px_size, cache_key.glyph_id)
                 {
-                    self.glyph_cache.insert(cache_key, e.clone());
-                    e
+                    self.insert_glyph(ctx, cache_key, &bitmap, scale_factor)
+                        .ok()
                 } else {
+                    self.glyph_cache.insert_blank(cache_key);
+                    None
+                };
+                let Some(entry) = entry else {
                     caret_pos[0] +=
                         Pt::from_physical_px(scaled.h_advance(glyph_id) as f64, scale_factor);
                     continue;
@@ -161,6 +180,8 @@ impl Graphics {
             bounds: (0.0, 0.0, y_offset),
             scale: image_scale,
             revision: text.layout_revision,
+            font_revision: ctx.registry.font_revision,
+            complete,
         };
 
         let mut cache_lock = text.layout_cache.as_ref().lock().unwrap();
@@ -177,9 +198,10 @@ impl Graphics {
         viewport_rect: [f32; 4],
     ) -> anyhow::Result<()> {
         let start_pos = opts.position();
-        let mut shader_opts = ShaderOpts::default();
-        shader_opts.set_vec4(0, text.color);
-        self.ensure_text_layout(ctx, text, opts.scale())?;
+        // Failed layouts were reported while preparing the frame.
+        if self.ensure_text_layout(ctx, text, opts.scale()).is_err() {
+            return Ok(());
+        }
         if ctx.registry.dirty_assets {
             self.process_registrations(ctx)?;
         }
@@ -193,16 +215,23 @@ impl Graphics {
             let final_x = start_pos[0].as_f32() + glyph.instance.pos[0];
             let final_y = start_pos[1].as_f32() + glyph.instance.pos[1];
 
-            if final_x + glyph.instance.size[0] >= viewport_rect[0]
-                && final_x <= viewport_rect[2]
-                && final_y + glyph.instance.size[1] >= viewport_rect[1]
-                && final_y <= viewport_rect[3]
-                && let Some(Some(img_entry)) = ctx.registry.images.get(glyph.image_id as usize)
+            if final_x + glyph.instance.size[0] < viewport_rect[0]
+                || final_x > viewport_rect[2]
+                || final_y + glyph.instance.size[1] < viewport_rect[1]
+                || final_y > viewport_rect[3]
+            {
+                ctx.runtime.draw_stats.culled += 1;
+                continue;
+            }
+            if let Some(Some(img_entry)) = ctx.registry.images.get(glyph.image_id as usize)
                 && let Some(Some(texture_entry)) =
                     ctx.registry.textures.get(img_entry.texture_id as usize)
                 && texture_entry.is_ready(self.gpu_generation)
             {
-                let mut glyph_opts = *opts;
+                // Glyphs share the shared atlas and the default image pipeline, colored by
+                // their tint, so text batches together with the images around it.
+                ctx.runtime.frame_stats.glyphs += 1;
+                let mut glyph_opts = opts.with_tint(text.color);
                 glyph_opts.set_position(Pt::from(final_x), Pt::from(final_y));
 
                 self.resolved_draws.push(ResolvedDraw {
@@ -213,8 +242,8 @@ impl Graphics {
                     bounds: img_entry.bounds,
                     uv_rect: resolve_image_uv(img_entry, texture_entry),
                     opts: glyph_opts,
-                    shader_id: self.text_shader_id,
-                    shader_opts,
+                    shader_id: 0,
+                    shader_opts: ShaderOpts::default(),
                 });
             }
         }
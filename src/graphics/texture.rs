@@ -37,6 +37,25 @@ impl Texture {
         ctx.register_render_target_texture(width, height)
     }
 
+    /// Returns the render target in `slot` if it is `width` x `height`, otherwise
+    /// destroys it and stores a new target of that size.
+    pub(crate) fn sized_render_target(
+        ctx: &mut crate::Context,
+        slot: &mut Option<Texture>,
+        width: Pt,
+        height: Pt,
+    ) -> Self {
+        match *slot {
+            Some(target) if target.width == width && target.height == height => target,
+            old => {
+                if let Some(old) = old {
+                    old.destroy(ctx);
+                }
+                *slot.insert(Self::new_render_target(ctx, width, height))
+            }
+        }
+    }
+
     /// Returns the default full-image view for this texture.
     pub fn view(self) -> crate::Image {
         crate::Image {
@@ -94,6 +113,29 @@ impl Texture {
         self.id
     }
 
+    /// Destroys the texture together with every image viewing it, freeing its GPU memory.
+    pub fn destroy(self, ctx: &mut crate::Context) -> bool {
+        let registry = &mut ctx.registry;
+        let Some(entry) = registry
+            .textures
+            .get_mut(self.id as usize)
+            .and_then(|v| v.take())
+        else {
+            return false;
+        };
+        if entry.dynamic_atlas {
+            // Atlas pages are shared; only the atlas may drop them.
+            registry.textures[self.id as usize] = Some(entry);
+            return false;
+        }
+        for image in registry.images.iter_mut() {
+            if image.as_ref().is_some_and(|e| e.texture_id == self.id) {
+                *image = None;
+            }
+        }
+        true
+    }
+
     pub fn is_render_target(self, ctx: &crate::Context) -> bool {
         ctx.registry
             .textures
@@ -402,6 +444,10 @@ pub(crate) struct TextureEntry {
     pub(crate) dynamic_atlas: bool,
     pub(crate) raw_data: Option<Arc<[u8]>>,
     pub(crate) pending_uploads: Vec<TextureUploadRegion>,
+    /// Set while the GPU copy was dropped to stay within the texture budget; the texture
+    /// is uploaded again from `raw_data` the next time it is drawn.
+    pub(crate) evicted: bool,
+    pub(crate) last_drawn_frame: u64,
     pub(crate) runtime: TextureRuntimeData,
 }
 
@@ -459,6 +505,8 @@ impl TextureEntry {
             dynamic_atlas: false,
             raw_data: Some(raw_data),
             pending_uploads: Vec::new(),
+            evicted: false,
+            last_drawn_frame: 0,
             runtime: TextureRuntimeData {
                 gpu_texture: None,
                 bind_group: None,
@@ -485,6 +533,8 @@ impl TextureEntry {
             dynamic_atlas: true,
             raw_data: Some(raw_data),
             pending_uploads: Vec::new(),
+            evicted: false,
+            last_drawn_frame: 0,
             runtime: TextureRuntimeData {
                 gpu_texture: None,
                 bind_group: None,
@@ -510,6 +560,8 @@ impl TextureEntry {
             dynamic_atlas: false,
             raw_data: None,
             pending_uploads: Vec::new(),
+            evicted: false,
+            last_drawn_frame: 0,
             runtime: TextureRuntimeData {
                 gpu_texture: None,
                 bind_group: None,
@@ -522,6 +574,16 @@ impl TextureEntry {
         self.runtime.gpu_texture.is_some() && self.runtime.generation == current_gen
     }
 
+    /// Approximate video memory of the GPU copy, including mipmaps.
+    pub(crate) fn gpu_bytes(&self) -> u64 {
+        let base = u64::from(self.pixel_width) * u64::from(self.pixel_height) * 4;
+        if self.render_target || self.dynamic_atlas {
+            base
+        } else {
+            base + base / 3
+        }
+    }
+
     pub(crate) fn is_render_target(&self) -> bool {
         self.render_target
     }
@@ -0,0 +1,215 @@
+//! Eviction of least-recently-drawn image textures, see
+//! [`set_texture_budget`](crate::set_texture_budget).
+
+use super::core::Graphics;
+use crate::drawable::DrawCommand;
+use crate::image_shader::ImageShaderInput;
+
+/// A texture that may be evicted: its id, GPU size and the frame it was last drawn in.
+#[derive(Debug, Clone, Copy)]
+struct EvictionCandidate {
+    texture_id: u32,
+    bytes: u64,
+    last_drawn_frame: u64,
+}
+
+/// Picks the least recently drawn candidates until at least `excess` bytes are freed.
+fn least_recently_drawn(mut candidates: Vec<EvictionCandidate>, excess: u64) -> Vec<u32> {
+    candidates.sort_by_key(|candidate| candidate.last_drawn_frame);
+    let mut freed = 0;
+    candidates
+        .into_iter()
+        .take_while(|candidate| {
+            let needed = freed < excess;
+            freed += candidate.bytes;
+            needed
+        })
+        .map(|candidate| candidate.texture_id)
+        .collect()
+}
+
+/// Returns the bytes held by uploaded images and atlas pages. Render targets are not
+/// counted because they cannot be evicted.
+pub(crate) fn resident_texture_bytes(registry: &crate::context::ResourceRegistry) -> u64 {
+    registry
+        .textures
+        .iter()
+        .flatten()
+        .filter(|entry| !entry.is_render_target() && entry.is_ready(registry.gpu_generation))
+        .map(|entry| entry.gpu_bytes())
+        .sum()
+}
+
+impl Graphics {
+    /// Stamps the textures sampled by this frame's draws with `frame`, bringing evicted
+    /// ones back so the next `process_registrations` uploads them before rendering.
+    /// Does nothing without a budget, since nothing is evicted then.
+    pub(crate) fn mark_drawn_textures(&mut self, ctx: &mut crate::Context, frame: u64) {
+        if ctx.registry.texture_budget.is_none() {
+            return;
+        }
+        let mut image_ids = std::mem::take(&mut self.drawn_image_ids);
+        let mut all_atlas_pages = false;
+        for command in &ctx.runtime.draw_list {
+            match command {
+                DrawCommand::Image(cmd) => {
+                    image_ids.push(cmd.id);
+                    let bindings = &cmd.shader_bindings;
+                    for input in bindings
+                        .extra_inputs
+                        .iter()
+                        .chain(bindings.named_inputs.values())
+                    {
+                        if let ImageShaderInput::Image(image) = input {
+                            image_ids.push(image.id);
+                        }
+                    }
+                }
+                DrawCommand::Text(cmd) => {
+                    let text = &cmd.text;
+                    let layout = text.layout_cache.as_ref().lock().unwrap();
+                    match layout.as_ref() {
+                        Some(layout)
+                            if layout.complete
+                                && layout.revision == text.layout_revision
+                                && layout.scale == cmd.opts.scale() =>
+                        {
+                            image_ids.extend(layout.glyphs.iter().map(|glyph| glyph.image_id));
+                        }
+                        // New glyphs may land on any page while laying out.
+                        _ => all_atlas_pages = true,
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "model-3d")]
+        if !ctx.runtime.model_3d.draw_list.is_empty() {
+            if let Some(model_3d) = self.model_3d() {
+                image_ids.extend([
+                    model_3d.white_image_id,
+                    model_3d.black_image_id,
+                    model_3d.normal_image_id,
+                ]);
+            }
+            for command in &ctx.runtime.model_3d.draw_list {
+                let model = match command {
+                    crate::drawable::DrawCommand3D::Model(_, model, ..)
+                    | crate::drawable::DrawCommand3D::ModelInstanced(_, model, ..) => model,
+                };
+                for part in model.parts.iter() {
+                    let material = &part.material;
+                    image_ids.extend(
+                        [
+                            material.albedo,
+                            material.pbr,
+                            material.normal,
+                            material.occlusion,
+                            material.emissive,
+                        ]
+                        .into_iter()
+                        .flatten(),
+                    );
+                }
+            }
+        }
+
+        let registry = &mut ctx.registry;
+        for image_id in image_ids.drain(..) {
+            let Some(Some(image)) = registry.images.get(image_id as usize) else {
+                continue;
+            };
+            if let Some(Some(entry)) = registry.textures.get_mut(image.texture_id as usize) {
+                entry.last_drawn_frame = frame;
+                entry.evicted = false;
+            }
+        }
+        self.drawn_image_ids = image_ids;
+        if all_atlas_pages {
+            for entry in registry.textures.iter_mut().flatten() {
+                if entry.dynamic_atlas {
+                    entry.last_drawn_frame = frame;
+                    entry.evicted = false;
+                }
+            }
+        }
+    }
+
+    /// Drops the GPU copies of the textures drawn least recently until the resident ones
+    /// fit the budget. Textures drawn in `frame` are kept even if that overshoots it.
+    pub(crate) fn enforce_texture_budget(&mut self, ctx: &mut crate::Context, frame: u64) {
+        let Some(budget) = ctx.registry.texture_budget else {
+            return;
+        };
+        let resident = resident_texture_bytes(&ctx.registry);
+        if resident <= budget {
+            return;
+        }
+
+        let candidates = ctx
+            .registry
+            .textures
+            .iter()
+            .enumerate()
+            .filter_map(|(texture_id, entry)| {
+                let entry = entry.as_ref()?;
+                let evictable = !entry.is_render_target()
+                    && (entry.raw_data.is_some() || entry.dynamic_atlas)
+                    && entry.is_ready(self.gpu_generation)
+                    && entry.last_drawn_frame != frame;
+                evictable.then(|| EvictionCandidate {
+                    texture_id: texture_id as u32,
+                    bytes: entry.gpu_bytes(),
+                    last_drawn_frame: entry.last_drawn_frame,
+                })
+            })
+            .collect();
+        let evicted = least_recently_drawn(candidates, resident - budget);
+        if evicted.is_empty() {
+            return;
+        }
+
+        for texture_id in evicted {
+            if let Some(Some(entry)) = ctx.registry.textures.get_mut(texture_id as usize) {
+                entry.runtime.gpu_texture = None;
+                entry.runtime.bind_group = None;
+                entry.pending_uploads.clear();
+                entry.evicted = true;
+            }
+        }
+        // Cached bind groups would keep the dropped textures alive.
+        self.image_renderer.clear_extra_texture_bind_group_cache();
+        #[cfg(feature = "model-3d")]
+        if let Some(model_3d) = self.model_3d_mut() {
+            model_3d.model_renderer.clear_texture_bind_group_cache();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_textures_are_evicted_until_the_excess_is_freed() {
+        let candidate = |texture_id, bytes, last_drawn_frame| EvictionCandidate {
+            texture_id,
+            bytes,
+            last_drawn_frame,
+        };
+        let candidates = vec![
+            candidate(1, 400, 30),
+            candidate(2, 100, 10),
+            candidate(3, 300, 20),
+            candidate(4, 200, 40),
+        ];
+
+        assert_eq!(
+            least_recently_drawn(candidates.clone(), 0),
+            Vec::<u32>::new()
+        );
+        assert_eq!(least_recently_drawn(candidates.clone(), 100), [2]);
+        assert_eq!(least_recently_drawn(candidates.clone(), 101), [2, 3]);
+        assert_eq!(least_recently_drawn(candidates, 10_000), [2, 3, 1, 4]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Tactile feedback on phones and tablets.
+
+use std::time::Duration;
+
+/// Strength of an [`impact`], mirroring iOS's `UIImpactFeedbackStyle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HapticStyle {
+    /// A faint tick, e.g. for scrolling through a picker.
+    Light,
+    /// A tap, e.g. for pressing a button.
+    #[default]
+    Medium,
+    /// A thud, e.g. for a collision or a lost life.
+    Heavy,
+}
+
+impl HapticStyle {
+    /// One-shot vibration used where the device has no predefined effect.
+    #[cfg_attr(not(target_os = "android"), allow(dead_code))]
+    pub(crate) fn fallback_duration(self) -> Duration {
+        match self {
+            HapticStyle::Light => Duration::from_millis(10),
+            HapticStyle::Medium => Duration::from_millis(20),
+            HapticStyle::Heavy => Duration::from_millis(40),
+        }
+    }
+}
+
+/// Vibrates the device for `duration`.
+///
+/// Uses the `Vibrator` service on Android, which needs the `android.permission.VIBRATE`
+/// permission in the manifest. iOS cannot vibrate for a set time and plays a heavy
+/// [`impact`] instead. Does nothing on desktop and the web.
+pub fn vibrate(duration: Duration) {
+    #[cfg(target_os = "android")]
+    if let Err(error) = crate::android::vibrate(duration, None) {
+        crate::diagnostics::warn!("android", "vibrate failed: {}", error);
+    }
+    #[cfg(target_os = "ios")]
+    {
+        let _ = duration;
+        crate::ios::impact_occurred(HapticStyle::Heavy);
+    }
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let _ = duration;
+}
+
+/// Plays a short tap of the given strength, for button presses and hits.
+///
+/// Uses `UIImpactFeedbackGenerator` on iOS and the predefined click effects of the
+/// `Vibrator` service on Android 10 and later, falling back to a short vibration on
+/// older versions. Does nothing on desktop and the web.
+pub fn impact(style: HapticStyle) {
+    #[cfg(target_os = "android")]
+    if let Err(error) = crate::android::vibrate(style.fallback_duration(), Some(style)) {
+        crate::diagnostics::warn!("android", "haptic impact failed: {}", error);
+    }
+    #[cfg(target_os = "ios")]
+    crate::ios::impact_occurred(style);
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let _ = style;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stronger_impacts_fall_back_to_longer_vibrations() {
+        assert!(HapticStyle::Light.fallback_duration() < HapticStyle::Medium.fallback_duration());
+        assert!(HapticStyle::Medium.fallback_duration() < HapticStyle::Heavy.fallback_duration());
+        vibrate(Duration::from_millis(5));
+        impact(HapticStyle::default());
+    }
+}
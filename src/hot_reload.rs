@@ -0,0 +1,289 @@
+//! Reloading asset files in place when they change on disk.
+//!
+//! Assets loaded through the `load_*_watched` functions remember their path. The
+//! directory holding each one is watched, and changed files are reloaded on the main
+//! thread before the next `update`, behind the handles the game already holds.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, mpsc};
+
+use notify::Watcher as _;
+
+use crate::{Context, Image, ImageShaderDesc};
+
+/// A handle whose contents follow a file on disk.
+#[derive(Debug, Clone)]
+enum WatchedAsset {
+    Image(Image),
+    Font(u32),
+    Sound(u32),
+    ImageShader { id: u32, desc: ImageShaderDesc },
+}
+
+/// The file watcher and the handles to refresh when a watched file changes.
+pub(crate) struct HotReload {
+    watcher: notify::RecommendedWatcher,
+    changes: mpsc::Receiver<PathBuf>,
+    watched_dirs: HashSet<PathBuf>,
+    assets: HashMap<PathBuf, Vec<WatchedAsset>>,
+}
+
+impl std::fmt::Debug for HotReload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HotReload")
+            .field("watched_dirs", &self.watched_dirs.len())
+            .field("assets", &self.assets.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HotReload {
+    fn new() -> notify::Result<Self> {
+        let (tx, changes) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if !matches!(
+                event.kind,
+                notify::EventKind::Create(_) | notify::EventKind::Modify(_)
+            ) {
+                return;
+            }
+            for path in event.paths {
+                let _ = tx.send(path);
+            }
+            crate::user_events::wake();
+        })?;
+        Ok(Self {
+            watcher,
+            changes,
+            watched_dirs: HashSet::new(),
+            assets: HashMap::new(),
+        })
+    }
+
+    /// Watches the directory holding `path`; editors often save by replacing the file,
+    /// which a watch on the file itself would lose.
+    fn watch(&mut self, path: PathBuf, asset: WatchedAsset) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent()
+            && !self.watched_dirs.contains(dir)
+        {
+            self.watcher
+                .watch(dir, notify::RecursiveMode::NonRecursive)?;
+            self.watched_dirs.insert(dir.to_path_buf());
+        }
+        self.assets.entry(path).or_default().push(asset);
+        Ok(())
+    }
+}
+
+fn watch(ctx: &mut Context, path: &str, asset: WatchedAsset) -> anyhow::Result<()> {
+    let path = std::fs::canonicalize(path)?;
+    let hot_reload = match ctx.runtime.hot_reload.as_mut() {
+        Some(hot_reload) => hot_reload,
+        None => ctx.runtime.hot_reload.insert(HotReload::new()?),
+    };
+    hot_reload.watch(path, asset)
+}
+
+/// Loads an image file and re-uploads its pixels whenever the file changes.
+///
+/// The returned handle stays valid across reloads. A changed file must keep the
+/// image's pixel size; other changes are logged and skipped until the next save.
+pub fn load_image_watched(ctx: &mut Context, path: &str) -> anyhow::Result<Image> {
+    let decoded = crate::utils::image::decode(&crate::load_asset(path)?, path)?;
+    let image = crate::utils::image::from_image(ctx, &decoded)?;
+    watch(ctx, path, WatchedAsset::Image(image))?;
+    Ok(image)
+}
+
+/// Loads a TTF/OTF font file and re-registers it under the same font ID whenever the
+/// file changes. Text drawn with it is laid out again with the new glyphs.
+pub fn load_font_watched(ctx: &mut Context, path: &str) -> anyhow::Result<u32> {
+    let bytes = crate::load_asset(path)?;
+    ab_glyph::FontArc::try_from_vec(bytes.clone())?;
+    let font_id = ctx.register_font(bytes);
+    watch(ctx, path, WatchedAsset::Font(font_id))?;
+    Ok(font_id)
+}
+
+/// Loads a sound file and decodes it again under the same sound ID whenever the file
+/// changes. Voices already playing finish with the old samples.
+pub fn load_sound_watched(ctx: &mut Context, path: &str) -> anyhow::Result<u32> {
+    let bytes = crate::load_asset(path)?;
+    let sound_id =
+        crate::register_sound(ctx, bytes).ok_or_else(|| anyhow::anyhow!("audio is unavailable"))?;
+    watch(ctx, path, WatchedAsset::Sound(sound_id))?;
+    Ok(sound_id)
+}
+
+/// Registers an image shader whose WGSL source is read from `path`, recompiling it
+/// under the same shader ID whenever the file changes.
+///
+/// `desc` supplies everything but the source, which is replaced by the file's contents.
+/// A source that fails to compile is logged and the previous pipeline keeps drawing.
+pub fn load_image_shader_watched(
+    ctx: &mut Context,
+    path: &str,
+    mut desc: ImageShaderDesc,
+) -> anyhow::Result<u32> {
+    desc.source = String::from_utf8(crate::load_asset(path)?)?;
+    let id = ctx.register_image_shader_desc(desc.clone());
+    watch(ctx, path, WatchedAsset::ImageShader { id, desc })?;
+    Ok(id)
+}
+
+/// Reloads every watched asset whose file changed since the last call.
+pub(crate) fn poll(ctx: &mut Context) {
+    let Some(hot_reload) = ctx.runtime.hot_reload.as_ref() else {
+        return;
+    };
+    let changed: HashSet<PathBuf> = hot_reload.changes.try_iter().collect();
+    for path in changed {
+        reload(ctx, &path);
+    }
+}
+
+fn reload(ctx: &mut Context, path: &Path) {
+    let Some(assets) = ctx
+        .runtime
+        .hot_reload
+        .as_ref()
+        .and_then(|hot_reload| hot_reload.assets.get(path))
+        .cloned()
+    else {
+        return;
+    };
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            crate::diagnostics::warn!("assets", "Failed to read {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    for asset in &assets {
+        let result = match asset {
+            WatchedAsset::Image(image) => reload_image(ctx, *image, path, &bytes),
+            WatchedAsset::Font(font_id) => reload_font(ctx, *font_id, bytes.clone()),
+            WatchedAsset::Sound(sound_id) => ctx
+                .with_audio(|a| a.replace_sound(*sound_id, bytes.clone()))
+                .unwrap_or(Ok(())),
+            WatchedAsset::ImageShader { id, desc } => reload_image_shader(ctx, *id, desc, &bytes),
+        };
+        match result {
+            Ok(()) => crate::diagnostics::info!("assets", "Reloaded {}", path.display()),
+            Err(e) => {
+                crate::diagnostics::warn!("assets", "Failed to reload {}: {}", path.display(), e)
+            }
+        }
+    }
+    ctx.request_redraw();
+}
+
+fn reload_image(ctx: &mut Context, image: Image, path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    let decoded = crate::utils::image::decode(bytes, &path.to_string_lossy())?.to_rgba8();
+    let entry = ctx
+        .registry
+        .textures
+        .get_mut(image.texture_id() as usize)
+        .and_then(Option::as_mut)
+        .ok_or_else(|| anyhow::anyhow!("the image was destroyed"))?;
+    if (entry.pixel_width, entry.pixel_height) != decoded.dimensions() {
+        anyhow::bail!(
+            "size changed from {}x{} to {}x{}; restart to pick it up",
+            entry.pixel_width,
+            entry.pixel_height,
+            decoded.width(),
+            decoded.height()
+        );
+    }
+
+    entry.raw_data = Some(Arc::from(decoded.as_raw().as_slice()));
+    entry.pending_uploads.clear();
+    entry.runtime.generation = 0;
+    ctx.registry.dirty_assets = true;
+    if let Some(g) = ctx.runtime.graphics.as_mut() {
+        g.image_renderer.clear_extra_texture_bind_group_cache();
+    }
+    Ok(())
+}
+
+fn reload_font(ctx: &mut Context, font_id: u32, bytes: Vec<u8>) -> anyhow::Result<()> {
+    ab_glyph::FontArc::try_from_vec(bytes.clone())?;
+    ctx.registry.fonts.insert(font_id, bytes);
+    ctx.registry.font_revision += 1;
+    ctx.registry.dirty_assets = true;
+
+    let stale_glyphs = match ctx.runtime.graphics.as_mut() {
+        Some(g) => {
+            g.font_cache.remove(&(font_id as u64));
+            g.glyph_cache.remove_font(font_id)
+        }
+        None => Vec::new(),
+    };
+    for glyph in stale_glyphs {
+        glyph.destroy(ctx);
+    }
+    Ok(())
+}
+
+fn reload_image_shader(
+    ctx: &mut Context,
+    id: u32,
+    desc: &ImageShaderDesc,
+    bytes: &[u8],
+) -> anyhow::Result<()> {
+    let mut desc = desc.clone();
+    desc.source = std::str::from_utf8(bytes)?.to_string();
+    if let Some(g) = ctx.runtime.graphics.as_mut() {
+        g.recompile_image_shader(id, &desc)?;
+    }
+    ctx.registry.image_shaders.insert(id, desc);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_png(path: &Path, width: u32, height: u32, color: [u8; 4]) {
+        image::RgbaImage::from_pixel(width, height, image::Rgba(color))
+            .save(path)
+            .expect("test image should be written");
+    }
+
+    #[test]
+    fn changed_image_is_swapped_in_behind_the_same_handle() {
+        let dir = std::env::temp_dir().join(format!("spot_hot_reload_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sprite.png");
+        write_png(&path, 2, 2, [255, 0, 0, 255]);
+
+        let mut ctx = Context::new();
+        let image = load_image_watched(&mut ctx, path.to_str().unwrap()).unwrap();
+        let canonical = std::fs::canonicalize(&path).unwrap();
+
+        write_png(&path, 2, 2, [0, 0, 255, 255]);
+        reload(&mut ctx, &canonical);
+        let entry = ctx.registry.textures[image.texture_id() as usize]
+            .as_ref()
+            .unwrap();
+        assert_eq!(&entry.raw_data.as_deref().unwrap()[..4], &[0, 0, 255, 255]);
+
+        write_png(&path, 4, 4, [0, 255, 0, 255]);
+        reload(&mut ctx, &canonical);
+        let entry = ctx.registry.textures[image.texture_id() as usize]
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            &entry.raw_data.as_deref().unwrap()[..4],
+            &[0, 0, 255, 255],
+            "a resized image should be skipped"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
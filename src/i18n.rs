@@ -0,0 +1,471 @@
+//! String tables per locale and the fonts to draw them with.
+//!
+//! Tables are loaded from Fluent (`.ftl`) or JSON sources with [`add_strings_ftl`],
+//! [`add_strings_json`] or [`load_strings`], then looked up with [`tr!`](crate::tr).
+//! Lookups try the current locale, then the fallback locale, then return the key
+//! itself, so a missing string shows up on screen instead of failing.
+//!
+//! Only the plain parts of Fluent are understood: messages, attributes (looked up as
+//! `message.attribute`), terms, comments, multiline values, and `{ $variable }`,
+//! `{ -term }`, `{ message }` and `{ "literal" }` placeables. JSON tables map keys to
+//! strings, with nested objects joined by dots, and use the same placeable syntax.
+//!
+//! [`LocalizedText`] redraws itself with the new string and font when the locale
+//! changes at runtime.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use crate::{Context, DrawOption, Text};
+
+/// Placeables referencing other messages are followed this deep before giving up.
+const MAX_REFERENCE_DEPTH: usize = 8;
+
+/// Locale tables, fonts and the active selection, kept on the context.
+#[derive(Debug, Default)]
+pub(crate) struct Localization {
+    locale: String,
+    fallback_locale: Option<String>,
+    tables: HashMap<String, HashMap<String, String>>,
+    fonts: HashMap<String, u32>,
+    /// Bumped by every change that can alter a lookup or the locale font.
+    revision: u64,
+}
+
+impl Localization {
+    fn changed(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    fn message(&self, key: &str) -> Option<&str> {
+        let current = self.tables.get(&self.locale);
+        let fallback = self
+            .fallback_locale
+            .as_ref()
+            .and_then(|locale| self.tables.get(locale));
+        current
+            .and_then(|table| table.get(key))
+            .or_else(|| fallback.and_then(|table| table.get(key)))
+            .map(String::as_str)
+    }
+
+    fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut out = String::new();
+        match self.message(key) {
+            Some(pattern) => self.format_pattern(pattern, args, 0, &mut out),
+            None => out.push_str(key),
+        }
+        out
+    }
+
+    fn format_pattern(
+        &self,
+        pattern: &str,
+        args: &[(&str, &dyn Display)],
+        depth: usize,
+        out: &mut String,
+    ) {
+        let mut rest = pattern;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                rest = &rest[open..];
+                break;
+            };
+            let placeable = &rest[open..open + close + 1];
+            let expression = placeable[1..placeable.len() - 1].trim();
+            if !self.format_expression(expression, args, depth, out) {
+                out.push_str(placeable);
+            }
+            rest = &rest[open + close + 1..];
+        }
+        out.push_str(rest);
+    }
+
+    /// Writes the value of one placeable, or returns false to keep it verbatim.
+    fn format_expression(
+        &self,
+        expression: &str,
+        args: &[(&str, &dyn Display)],
+        depth: usize,
+        out: &mut String,
+    ) -> bool {
+        use std::fmt::Write as _;
+
+        if let Some(name) = expression.strip_prefix('$') {
+            let Some((_, value)) = args.iter().find(|(arg, _)| *arg == name) else {
+                return false;
+            };
+            let _ = write!(out, "{value}");
+            return true;
+        }
+        if let Some(literal) = expression
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+        {
+            out.push_str(literal);
+            return true;
+        }
+        if depth < MAX_REFERENCE_DEPTH
+            && let Some(pattern) = self.message(expression)
+        {
+            self.format_pattern(pattern, args, depth + 1, out);
+            return true;
+        }
+        false
+    }
+}
+
+/// Adds the messages of a Fluent source to `locale`, replacing existing keys.
+pub fn add_strings_ftl(ctx: &mut Context, locale: &str, source: &str) -> anyhow::Result<()> {
+    let messages = parse_ftl(source)?;
+    add_strings(ctx, locale, messages);
+    Ok(())
+}
+
+/// Adds the strings of a JSON object to `locale`, replacing existing keys.
+///
+/// Nested objects are flattened into dotted keys, so `{"menu": {"start": "Start"}}`
+/// defines `menu.start`.
+pub fn add_strings_json(ctx: &mut Context, locale: &str, source: &str) -> anyhow::Result<()> {
+    let value: serde_json::Value = serde_json::from_str(source)?;
+    let mut messages = HashMap::new();
+    flatten_json("", &value, &mut messages)?;
+    add_strings(ctx, locale, messages);
+    Ok(())
+}
+
+/// Reads a string table with [`load_asset`](crate::load_asset) and adds it to `locale`.
+///
+/// Files ending in `.json` are read as JSON, everything else as Fluent.
+pub fn load_strings(ctx: &mut Context, locale: &str, path: &str) -> anyhow::Result<()> {
+    let source = String::from_utf8(crate::load_asset(path)?)?;
+    if path.ends_with(".json") {
+        add_strings_json(ctx, locale, &source)
+    } else {
+        add_strings_ftl(ctx, locale, &source)
+    }
+}
+
+fn add_strings(ctx: &mut Context, locale: &str, messages: HashMap<String, String>) {
+    let localization = &mut ctx.registry.localization;
+    localization
+        .tables
+        .entry(locale.to_string())
+        .or_default()
+        .extend(messages);
+    localization.changed();
+}
+
+/// Switches the locale used by [`tr!`](crate::tr) and [`LocalizedText`].
+pub fn set_locale(ctx: &mut Context, locale: &str) {
+    let localization = &mut ctx.registry.localization;
+    if localization.locale != locale {
+        localization.locale = locale.to_string();
+        localization.changed();
+    }
+}
+
+/// Returns the current locale, empty until [`set_locale`] is called.
+pub fn locale(ctx: &Context) -> &str {
+    &ctx.registry.localization.locale
+}
+
+/// Sets the locale whose strings are used when the current locale lacks a key.
+pub fn set_fallback_locale(ctx: &mut Context, locale: Option<&str>) {
+    let localization = &mut ctx.registry.localization;
+    localization.fallback_locale = locale.map(str::to_string);
+    localization.changed();
+}
+
+/// Returns the locales that have strings, in no particular order.
+pub fn locales(ctx: &Context) -> Vec<&str> {
+    ctx.registry
+        .localization
+        .tables
+        .keys()
+        .map(String::as_str)
+        .collect()
+}
+
+/// Draws text in `locale` with `font_id`, e.g. a CJK font for `"ja"`.
+///
+/// Locales without a font of their own use the font their [`LocalizedText`] was
+/// created with.
+pub fn set_locale_font(ctx: &mut Context, locale: &str, font_id: u32) {
+    let localization = &mut ctx.registry.localization;
+    localization.fonts.insert(locale.to_string(), font_id);
+    localization.changed();
+}
+
+/// Returns the font registered for the current locale, if any.
+pub fn locale_font(ctx: &Context) -> Option<u32> {
+    let localization = &ctx.registry.localization;
+    localization.fonts.get(&localization.locale).copied()
+}
+
+/// Returns the string for `key` in the current locale; see [`tr!`](crate::tr).
+pub fn translate(ctx: &Context, key: &str) -> String {
+    ctx.registry.localization.format(key, &[])
+}
+
+/// Returns the string for `key` with `{ $name }` placeables filled from `args`.
+pub fn translate_with(ctx: &Context, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    ctx.registry.localization.format(key, args)
+}
+
+/// Looks up a localized string in the current locale.
+///
+/// ```rust,ignore
+/// let title = spottedcat::tr!(ctx, "menu.start");
+/// let score = spottedcat::tr!(ctx, "hud.score", points = 120);
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($ctx:expr, $key:expr $(,)?) => {
+        $crate::i18n::translate($ctx, $key)
+    };
+    ($ctx:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate_with(
+            $ctx,
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+/// A [`Text`] showing a localized string, refreshed when the locale or its strings
+/// change.
+///
+/// Style it through [`text_mut`](Self::text_mut); its content and font are managed
+/// here.
+///
+/// ```rust,ignore
+/// let mut start = LocalizedText::new("menu.start", font_id);
+/// start.text_mut().set_font_size(Pt::from(32.0));
+/// screen.draw(ctx, &mut start, DrawOption::default());
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalizedText {
+    key: String,
+    font_id: u32,
+    text: Text,
+    revision: Option<u64>,
+}
+
+impl LocalizedText {
+    /// Creates text for `key`, drawn with `font_id` unless the locale has its own font.
+    pub fn new(key: impl Into<String>, font_id: u32) -> Self {
+        Self {
+            key: key.into(),
+            font_id,
+            text: Text::new("", font_id),
+            revision: None,
+        }
+    }
+
+    /// Returns the key looked up in the string tables.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Shows the string for another key.
+    pub fn set_key(&mut self, key: impl Into<String>) {
+        self.key = key.into();
+        self.revision = None;
+    }
+
+    /// Returns the text as of the last refresh.
+    pub fn text(&self) -> &Text {
+        &self.text
+    }
+
+    /// Returns the text for styling.
+    pub fn text_mut(&mut self) -> &mut Text {
+        &mut self.text
+    }
+
+    /// Updates the content and font if the locale changed since the last refresh.
+    pub fn refresh(&mut self, ctx: &Context) -> &Text {
+        let revision = ctx.registry.localization.revision;
+        if self.revision != Some(revision) {
+            self.revision = Some(revision);
+            self.text.set_content(translate(ctx, &self.key));
+            self.text.set_font(locale_font(ctx).unwrap_or(self.font_id));
+        }
+        &self.text
+    }
+}
+
+impl crate::Drawable for &mut LocalizedText {
+    type Options = DrawOption;
+
+    fn draw_to(self, ctx: &mut Context, target: crate::Image, options: Self::Options) {
+        self.refresh(ctx).draw_to(ctx, target, options);
+    }
+}
+
+impl fmt::Display for LocalizedText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.text, f)
+    }
+}
+
+fn flatten_json(
+    prefix: &str,
+    value: &serde_json::Value,
+    out: &mut HashMap<String, String>,
+) -> anyhow::Result<()> {
+    match value {
+        serde_json::Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_json(&key, value, out)?;
+            }
+        }
+        _ => anyhow::bail!("string table entry {prefix:?} must be a string or an object"),
+    }
+    Ok(())
+}
+
+/// Parses the messages, attributes and terms of a Fluent resource.
+fn parse_ftl(source: &str) -> anyhow::Result<HashMap<String, String>> {
+    let mut messages = HashMap::new();
+    // The entry continuation lines are appended to, as (key, value).
+    let mut current: Option<(String, String)> = None;
+    let mut message_id = String::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let indented = line.starts_with([' ', '\t']);
+        let trimmed = line.trim();
+
+        if indented && let Some(attribute) = trimmed.strip_prefix('.') {
+            if message_id.is_empty() {
+                anyhow::bail!("line {}: attribute outside a message", index + 1);
+            }
+            let (name, value) = split_entry(attribute, index)?;
+            flush(&mut messages, current.take());
+            current = Some((format!("{message_id}.{name}"), value.to_string()));
+        } else if indented && !trimmed.is_empty() {
+            let Some((_, value)) = current.as_mut() else {
+                anyhow::bail!("line {}: indented text outside a message", index + 1);
+            };
+            if !value.is_empty() {
+                value.push('\n');
+            }
+            value.push_str(trimmed);
+        } else if trimmed.is_empty() {
+            // Blank lines may separate the lines of a multiline value.
+        } else if trimmed.starts_with('#') {
+            flush(&mut messages, current.take());
+            message_id.clear();
+        } else {
+            let (id, value) = split_entry(trimmed, index)?;
+            flush(&mut messages, current.take());
+            message_id = id.to_string();
+            current = Some((message_id.clone(), value.to_string()));
+        }
+    }
+    flush(&mut messages, current);
+    Ok(messages)
+}
+
+fn split_entry(line: &str, index: usize) -> anyhow::Result<(&str, &str)> {
+    let (id, value) = line
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("line {}: expected `id = value`", index + 1))?;
+    let id = id.trim();
+    let valid = id
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if id.is_empty() || !valid {
+        anyhow::bail!("line {}: invalid identifier {id:?}", index + 1);
+    }
+    Ok((id, value.trim()))
+}
+
+/// Stores a finished entry; messages with only attributes have no value of their own.
+fn flush(messages: &mut HashMap<String, String>, entry: Option<(String, String)>) {
+    if let Some((key, value)) = entry
+        && !value.is_empty()
+    {
+        messages.insert(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EN: &str = r#"
+# Main menu
+-brand = Spotted Cat
+menu = Menu
+    .start = Start { -brand }
+    .quit = Quit
+
+hud-score = Score: { $points }
+intro =
+    Welcome to
+    { -brand }!
+"#;
+
+    #[test]
+    fn fluent_messages_attributes_and_placeables_resolve() {
+        let mut ctx = Context::new();
+        add_strings_ftl(&mut ctx, "en", EN).unwrap();
+        set_locale(&mut ctx, "en");
+
+        assert_eq!(tr!(&ctx, "menu"), "Menu");
+        assert_eq!(tr!(&ctx, "menu.start"), "Start Spotted Cat");
+        assert_eq!(tr!(&ctx, "hud-score", points = 120), "Score: 120");
+        assert_eq!(tr!(&ctx, "hud-score"), "Score: { $points }");
+        assert_eq!(tr!(&ctx, "intro"), "Welcome to\nSpotted Cat!");
+    }
+
+    #[test]
+    fn lookups_fall_back_to_the_fallback_locale_then_the_key() {
+        let mut ctx = Context::new();
+        add_strings_ftl(&mut ctx, "en", EN).unwrap();
+        add_strings_json(&mut ctx, "de", r#"{"menu": {"start": "Los"}}"#).unwrap();
+        set_locale(&mut ctx, "de");
+        set_fallback_locale(&mut ctx, Some("en"));
+
+        assert_eq!(tr!(&ctx, "menu.start"), "Los");
+        assert_eq!(tr!(&ctx, "menu.quit"), "Quit");
+        assert_eq!(tr!(&ctx, "missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn localized_text_follows_locale_and_font_changes() {
+        let mut ctx = Context::new();
+        add_strings_json(&mut ctx, "en", r#"{"menu": {"start": "Start"}}"#).unwrap();
+        add_strings_json(&mut ctx, "ja", r#"{"menu": {"start": "スタート"}}"#).unwrap();
+        set_locale_font(&mut ctx, "ja", 7);
+        set_locale(&mut ctx, "en");
+
+        let mut label = LocalizedText::new("menu.start", 1);
+        assert_eq!(label.refresh(&ctx).to_string(), "Start");
+        assert_eq!(label.text().font_id(), 1);
+
+        set_locale(&mut ctx, "ja");
+        assert_eq!(label.refresh(&ctx).to_string(), "スタート");
+        assert_eq!(label.text().font_id(), 7);
+    }
+
+    #[test]
+    fn malformed_sources_are_rejected() {
+        let mut ctx = Context::new();
+        assert!(add_strings_ftl(&mut ctx, "en", "no equals sign").is_err());
+        assert!(add_strings_ftl(&mut ctx, "en", "    .orphan = x").is_err());
+        assert!(add_strings_json(&mut ctx, "en", r#"{"count": 3}"#).is_err());
+    }
+}
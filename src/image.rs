@@ -197,7 +197,7 @@ impl Image {
         }
 
         let id = ctx.register_sub_image(image, bounds)?;
-        Ok(Self {
+        let sub_image = Self {
             id,
             texture_id: image.texture_id,
             x: image.x + bounds.x,
@@ -208,7 +208,9 @@ impl Image {
                 .as_ref()
                 .unwrap()
                 .pixel_bounds,
-        })
+        };
+        ctx.track_scene_resource(crate::SceneResource::Image(sub_image));
+        Ok(sub_image)
     }
 
     /// Draws a drawable into this image (as a target) with the specified options.
@@ -257,7 +259,7 @@ impl Image {
     ) {
         let source = source.into();
         let target_texture_id = ctx.resolve_target_texture_id(self);
-        ctx.push(crate::drawable::DrawCommand::Image(Box::new(
+        ctx.push(crate::drawable::DrawCommand::Image(
             crate::drawable::ImageCommand {
                 id: source.id,
                 target_texture_id,
@@ -267,7 +269,7 @@ impl Image {
                 shader_bindings,
                 size: [source.width, source.height],
             },
-        )));
+        ));
     }
 
     /// Returns the source-texture bounds of this image.
@@ -314,7 +316,7 @@ impl crate::Drawable for &Image {
 
     fn draw_to(self, ctx: &mut crate::Context, target: crate::Image, options: Self::Options) {
         let target_texture_id = ctx.resolve_target_texture_id(target);
-        ctx.push(crate::drawable::DrawCommand::Image(Box::new(
+        ctx.push(crate::drawable::DrawCommand::Image(
             crate::drawable::ImageCommand {
                 id: self.id,
                 target_texture_id,
@@ -324,6 +326,6 @@ impl crate::Drawable for &Image {
                 shader_bindings: ImageShaderBindings::default(),
                 size: [self.width, self.height],
             },
-        )));
+        ));
     }
 }
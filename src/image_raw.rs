@@ -35,7 +35,7 @@ impl Default for ImageTransform {
 }
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
 pub struct InstanceData {
     pub pos: [f32; 2],
     pub rotation: f32,
@@ -44,6 +44,22 @@ pub struct InstanceData {
     pub _pad2: [f32; 2],
     pub uv_rect: [f32; 4],
     pub repeat: [f32; 4],
+    pub color: [f32; 4],
+}
+
+impl Default for InstanceData {
+    fn default() -> Self {
+        Self {
+            pos: [0.0; 2],
+            rotation: 0.0,
+            _pad1: 0.0,
+            size: [0.0; 2],
+            _pad2: [0.0; 2],
+            uv_rect: [0.0; 4],
+            repeat: [0.0; 4],
+            color: [1.0; 4],
+        }
+    }
 }
 
 #[repr(C)]
@@ -59,7 +75,7 @@ pub struct EngineGlobals {
 }
 
 impl InstanceData {
-    const ATTRS: [wgpu::VertexAttribute; 5] = [
+    const ATTRS: [wgpu::VertexAttribute; 6] = [
         wgpu::VertexAttribute {
             offset: 0,
             shader_location: 0,
@@ -85,6 +101,11 @@ impl InstanceData {
             shader_location: 4,
             format: wgpu::VertexFormat::Float32x4,
         },
+        wgpu::VertexAttribute {
+            offset: 64,
+            shader_location: 5,
+            format: wgpu::VertexFormat::Float32x4,
+        },
     ];
 
     pub(crate) fn layout() -> wgpu::VertexBufferLayout<'static> {
@@ -532,7 +553,8 @@ impl ImageRenderer {
 
     pub fn upload_instances(
         &mut self,
-        _queue: &wgpu::Queue,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
         instances: &[InstanceData],
     ) -> anyhow::Result<std::ops::Range<u32>> {
         let count = instances.len() as u32;
@@ -540,7 +562,7 @@ impl ImageRenderer {
             return Ok(0..0);
         }
         if self.next_instance.saturating_add(count) > self.max_instances {
-            return Err(anyhow::anyhow!("max image instances exceeded"));
+            self.grow_instance_buffer(device, queue, count)?;
         }
 
         let start = self.next_instance;
@@ -554,6 +576,36 @@ impl ImageRenderer {
         Ok(start..(start + count))
     }
 
+    /// Switches to an instance buffer big enough for this frame's instances plus `count`.
+    ///
+    /// Instances appended so far are flushed into the old buffer first: batches already
+    /// encoded keep drawing from it, and the rest of the frame fills the new one from the
+    /// start. The larger buffer is kept for later frames.
+    fn grow_instance_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        count: u32,
+    ) -> anyhow::Result<()> {
+        let max_capacity = (device.limits().max_buffer_size / self.instance_stride as u64)
+            .min(u32::MAX as u64) as u32;
+        let capacity = grown_instance_capacity(self.next_instance, count, max_capacity)
+            .ok_or_else(|| anyhow::anyhow!("max image instances exceeded"))?;
+
+        self.flush_pending_uploads(queue);
+        self.instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("image_instance_buffer"),
+            size: self.instance_stride as wgpu::BufferAddress * capacity as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.max_instances = capacity;
+        self.next_instance = 0;
+        self.pending_instances.clear();
+        self.flushed_instances = 0;
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn draw_batch<'rp>(
         &self,
@@ -593,3 +645,28 @@ impl ImageRenderer {
         pass.draw(0..4, 0..instance_count);
     }
 }
+
+/// Capacity of a replacement instance buffer: the next power of two that fits the
+/// `used` instances of this frame plus `count` more, clamped to `max_capacity`. `None`
+/// if even a maximal buffer cannot hold `count`.
+fn grown_instance_capacity(used: u32, count: u32, max_capacity: u32) -> Option<u32> {
+    let required = (used as u64 + count as u64).next_power_of_two();
+    let capacity = required.min(max_capacity as u64) as u32;
+    (capacity >= count).then_some(capacity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_capacity_grows_to_the_next_power_of_two_within_limits() {
+        assert_eq!(grown_instance_capacity(200_000, 1, u32::MAX), Some(262_144));
+        assert_eq!(grown_instance_capacity(0, 300_000, u32::MAX), Some(524_288));
+        assert_eq!(
+            grown_instance_capacity(200_000, 1_000, 250_000),
+            Some(250_000)
+        );
+        assert_eq!(grown_instance_capacity(0, 300_000, 250_000), None);
+    }
+}
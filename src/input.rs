@@ -9,6 +9,7 @@ use crate::Key;
 use crate::MouseButton as SpotMouseButton;
 use crate::Pt;
 use crate::gamepad::{GamepadAxis, GamepadButton, GamepadId, GamepadInfo};
+use crate::lockstep::{GamepadFrame, InputFrame};
 use crate::touch::{TouchInfo, TouchPhase};
 
 #[derive(Debug, Clone)]
@@ -365,6 +366,102 @@ impl InputManager {
         }
     }
 
+    /// Samples the held state for one tick; anything pressed since the last tick counts
+    /// as held even if it was already released.
+    pub(crate) fn capture_frame(&self) -> InputFrame {
+        let mut mouse_other_down: Vec<u16> = self
+            .mouse_other_down
+            .union(&self.mouse_other_pressed)
+            .copied()
+            .collect();
+        mouse_other_down.sort_unstable();
+        let gamepads = self
+            .gamepads
+            .iter()
+            .filter(|state| state.info.connected)
+            .map(|state| {
+                let mut buttons_down: Vec<GamepadButton> = state
+                    .buttons_down
+                    .union(&state.buttons_pressed)
+                    .copied()
+                    .collect();
+                buttons_down.sort_unstable();
+                let mut axes: Vec<(GamepadAxis, f32)> = state
+                    .axes
+                    .iter()
+                    .map(|(axis, value)| (*axis, *value))
+                    .collect();
+                axes.sort_unstable_by_key(|(axis, _)| *axis);
+                GamepadFrame {
+                    id: state.info.id,
+                    buttons_down,
+                    axes,
+                }
+            })
+            .collect();
+        InputFrame {
+            keys_down: (0..Key::WORDS)
+                .map(|w| self.keys_down[w] | self.keys_pressed[w])
+                .collect(),
+            mouse_down: self.mouse_down | self.mouse_pressed,
+            mouse_other_down,
+            cursor_position: self.cursor_position,
+            scroll_delta: self.scroll_delta,
+            text_input: self.text_input.clone(),
+            gamepads,
+        }
+    }
+
+    /// Replaces the live state with `frame`, deriving presses and releases from
+    /// `previous`.
+    pub(crate) fn apply_frame(&mut self, previous: &InputFrame, frame: &InputFrame) {
+        for w in 0..Key::WORDS {
+            let before = previous.keys_down.get(w).copied().unwrap_or(0);
+            let down = frame.keys_down.get(w).copied().unwrap_or(0);
+            self.keys_down[w] = down;
+            self.keys_pressed[w] = down & !before;
+            self.keys_released[w] = before & !down;
+        }
+
+        self.mouse_down = frame.mouse_down;
+        self.mouse_pressed = frame.mouse_down & !previous.mouse_down;
+        self.mouse_released = previous.mouse_down & !frame.mouse_down;
+        let before: HashSet<u16> = previous.mouse_other_down.iter().copied().collect();
+        self.mouse_other_down = frame.mouse_other_down.iter().copied().collect();
+        self.mouse_other_pressed = self.mouse_other_down.difference(&before).copied().collect();
+        self.mouse_other_released = before.difference(&self.mouse_other_down).copied().collect();
+
+        self.cursor_position = frame.cursor_position;
+        self.scroll_delta = frame.scroll_delta;
+        self.text_input.clone_from(&frame.text_input);
+
+        for state in &mut self.gamepads {
+            state.buttons_down.clear();
+            state.buttons_pressed.clear();
+            state.buttons_released.clear();
+            state.axes.clear();
+        }
+        for gamepad in &previous.gamepads {
+            if let Some(state) = self.gamepad_state_mut(gamepad.id) {
+                state.buttons_released = gamepad.buttons_down.iter().copied().collect();
+            }
+        }
+        for gamepad in &frame.gamepads {
+            let state = self.ensure_gamepad_state(gamepad.id, "Gamepad".to_string());
+            state.info.connected = true;
+            state.buttons_down = gamepad.buttons_down.iter().copied().collect();
+            state.buttons_pressed = state
+                .buttons_down
+                .difference(&state.buttons_released)
+                .copied()
+                .collect();
+            state
+                .buttons_released
+                .retain(|button| !state.buttons_down.contains(button));
+            state.axes = gamepad.axes.iter().copied().collect();
+        }
+    }
+
     #[allow(dead_code)]
     pub(crate) fn handle_focus(&mut self, focused: bool) {
         self.focused = focused;
@@ -373,6 +470,15 @@ impl InputManager {
         }
     }
 
+    /// Presses and releases `key` within the current frame, for platforms that only
+    /// report that a key was typed.
+    #[allow(dead_code)]
+    pub(crate) fn handle_key_tap(&mut self, key: Key) {
+        let (w, mask) = key_word_bit(key);
+        self.keys_pressed[w] |= mask;
+        self.keys_released[w] |= mask;
+    }
+
     #[allow(dead_code)]
     pub(crate) fn handle_received_character(&mut self, ch: char) {
         if !self.text_input_enabled {
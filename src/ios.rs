@@ -19,6 +19,71 @@ fn motion_authorization_status() -> isize {
     unsafe { msg_send![objc2::class!(CMPedometer), authorizationStatus] }
 }
 
+/// Stops the idle timer from dimming and locking the screen while `disabled`.
+#[cfg(target_os = "ios")]
+pub(crate) fn set_idle_timer_disabled(disabled: bool) {
+    unsafe {
+        let app: *mut objc2::runtime::AnyObject =
+            msg_send![objc2::class!(UIApplication), sharedApplication];
+        if !app.is_null() {
+            let _: () = msg_send![app, setIdleTimerDisabled: disabled];
+        }
+    }
+}
+
+/// Reads the battery from `UIDevice`, enabling its monitoring on first use, and the
+/// thermal state from `NSProcessInfo`.
+#[cfg(target_os = "ios")]
+pub(crate) fn power_status() -> crate::power::PowerStatus {
+    use objc2::runtime::AnyObject;
+
+    let mut status = crate::power::PowerStatus::default();
+    unsafe {
+        let device: *mut AnyObject = msg_send![objc2::class!(UIDevice), currentDevice];
+        if !device.is_null() {
+            let _: () = msg_send![device, setBatteryMonitoringEnabled: true];
+            // UIDeviceBatteryStateUnknown, Unplugged, Charging and Full.
+            let state: isize = msg_send![device, batteryState];
+            if state != 0 {
+                let level: f32 = msg_send![device, batteryLevel];
+                status.battery_level = (level >= 0.0).then_some(level);
+                status.charging = Some(state >= 2);
+            }
+        }
+        let process: *mut AnyObject = msg_send![objc2::class!(NSProcessInfo), processInfo];
+        if !process.is_null() {
+            let thermal: isize = msg_send![process, thermalState];
+            status.thermal = crate::power::ThermalState::from_ios_state(thermal);
+        }
+    }
+    status
+}
+
+/// Plays a `UIImpactFeedbackGenerator` tap; UIKit only allows this on the main thread.
+#[cfg(target_os = "ios")]
+pub(crate) fn impact_occurred(style: crate::HapticStyle) {
+    use objc2::rc::Allocated;
+    use objc2::runtime::AnyObject;
+
+    if MainThreadMarker::new().is_none() {
+        return;
+    }
+    // UIImpactFeedbackStyleLight, Medium and Heavy.
+    let style: isize = match style {
+        crate::HapticStyle::Light => 0,
+        crate::HapticStyle::Medium => 1,
+        crate::HapticStyle::Heavy => 2,
+    };
+    unsafe {
+        let generator: Allocated<AnyObject> =
+            msg_send![objc2::class!(UIImpactFeedbackGenerator), alloc];
+        let generator: Option<Retained<AnyObject>> = msg_send![generator, initWithStyle: style];
+        if let Some(generator) = generator {
+            let _: () = msg_send![&generator, impactOccurred];
+        }
+    }
+}
+
 #[cfg(target_os = "ios")]
 pub fn has_runtime_permission(permission: &str) -> Result<bool, String> {
     match permission {
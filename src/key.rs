@@ -2,6 +2,7 @@
 ///
 /// This enum covers standard US-layout keys and is used by the [`InputManager`]
 /// to track key states.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum Key {
@@ -126,6 +127,114 @@ impl Key {
         self as usize
     }
 
+    /// Looks up a key by its variant name, e.g. `"Space"` or `"ArrowLeft"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "A" => Key::A,
+            "B" => Key::B,
+            "C" => Key::C,
+            "D" => Key::D,
+            "E" => Key::E,
+            "F" => Key::F,
+            "G" => Key::G,
+            "H" => Key::H,
+            "I" => Key::I,
+            "J" => Key::J,
+            "K" => Key::K,
+            "L" => Key::L,
+            "M" => Key::M,
+            "N" => Key::N,
+            "O" => Key::O,
+            "P" => Key::P,
+            "Q" => Key::Q,
+            "R" => Key::R,
+            "S" => Key::S,
+            "T" => Key::T,
+            "U" => Key::U,
+            "V" => Key::V,
+            "W" => Key::W,
+            "X" => Key::X,
+            "Y" => Key::Y,
+            "Z" => Key::Z,
+            "Num0" => Key::Num0,
+            "Num1" => Key::Num1,
+            "Num2" => Key::Num2,
+            "Num3" => Key::Num3,
+            "Num4" => Key::Num4,
+            "Num5" => Key::Num5,
+            "Num6" => Key::Num6,
+            "Num7" => Key::Num7,
+            "Num8" => Key::Num8,
+            "Num9" => Key::Num9,
+            "Escape" => Key::Escape,
+            "Enter" => Key::Enter,
+            "Space" => Key::Space,
+            "Tab" => Key::Tab,
+            "Backspace" => Key::Backspace,
+            "Left" => Key::Left,
+            "Right" => Key::Right,
+            "Up" => Key::Up,
+            "Down" => Key::Down,
+            "LShift" => Key::LShift,
+            "RShift" => Key::RShift,
+            "LCtrl" => Key::LCtrl,
+            "RCtrl" => Key::RCtrl,
+            "LAlt" => Key::LAlt,
+            "RAlt" => Key::RAlt,
+            "CapsLock" => Key::CapsLock,
+            "Insert" => Key::Insert,
+            "Delete" => Key::Delete,
+            "Home" => Key::Home,
+            "End" => Key::End,
+            "PageUp" => Key::PageUp,
+            "PageDown" => Key::PageDown,
+            "PrintScreen" => Key::PrintScreen,
+            "ScrollLock" => Key::ScrollLock,
+            "Pause" => Key::Pause,
+            "F1" => Key::F1,
+            "F2" => Key::F2,
+            "F3" => Key::F3,
+            "F4" => Key::F4,
+            "F5" => Key::F5,
+            "F6" => Key::F6,
+            "F7" => Key::F7,
+            "F8" => Key::F8,
+            "F9" => Key::F9,
+            "F10" => Key::F10,
+            "F11" => Key::F11,
+            "F12" => Key::F12,
+            "Minus" => Key::Minus,
+            "Equal" => Key::Equal,
+            "BracketLeft" => Key::BracketLeft,
+            "BracketRight" => Key::BracketRight,
+            "Backslash" => Key::Backslash,
+            "Semicolon" => Key::Semicolon,
+            "Quote" => Key::Quote,
+            "Backquote" => Key::Backquote,
+            "Comma" => Key::Comma,
+            "Period" => Key::Period,
+            "Slash" => Key::Slash,
+            "NumLock" => Key::NumLock,
+            "Numpad0" => Key::Numpad0,
+            "Numpad1" => Key::Numpad1,
+            "Numpad2" => Key::Numpad2,
+            "Numpad3" => Key::Numpad3,
+            "Numpad4" => Key::Numpad4,
+            "Numpad5" => Key::Numpad5,
+            "Numpad6" => Key::Numpad6,
+            "Numpad7" => Key::Numpad7,
+            "Numpad8" => Key::Numpad8,
+            "Numpad9" => Key::Numpad9,
+            "NumpadAdd" => Key::NumpadAdd,
+            "NumpadSubtract" => Key::NumpadSubtract,
+            "NumpadMultiply" => Key::NumpadMultiply,
+            "NumpadDivide" => Key::NumpadDivide,
+            "NumpadEnter" => Key::NumpadEnter,
+            "NumpadDecimal" => Key::NumpadDecimal,
+            _ => return None,
+        })
+    }
+
     #[cfg(not(target_os = "android"))]
     pub(crate) fn from_winit_key_code(code: winit::keyboard::KeyCode) -> Option<Self> {
         use winit::keyboard::KeyCode;
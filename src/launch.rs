@@ -4,7 +4,106 @@ use crate::{Pt, Spot};
 use android_activity::AndroidApp;
 use std::time::Duration;
 
+/// Stacking order of the window relative to other applications' windows.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowLevel {
+    /// Stays below all other windows.
+    AlwaysOnBottom,
+    /// Regular stacking order.
+    #[default]
+    Normal,
+    /// Floats above all other windows, e.g. for overlays and debug tools.
+    AlwaysOnTop,
+}
+
+/// Light or dark appearance reported by the operating system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// Whether the window is taller than it is wide or wider than it is tall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+impl Orientation {
+    pub(crate) fn of_size(width: Pt, height: Pt) -> Self {
+        if width.0 > height.0 {
+            Self::Landscape
+        } else {
+            Self::Portrait
+        }
+    }
+}
+
+/// When the application renders new frames.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedrawMode {
+    /// Updates and redraws every display frame.
+    #[default]
+    Continuous,
+    /// Sleeps until window input, an [`EventSender`](crate::EventSender) message, a finished
+    /// task, or [`request_redraw`](crate::request_redraw), then runs one update and draw.
+    ///
+    /// Suited to GUI-style tools that should not render while idle. Running tweens and
+    /// pending timers, a gliding [`ScrollView`](crate::ScrollView) and a focused
+    /// [text box](crate::ui::Ui::text_box) request frames until they settle; other
+    /// animations must call `request_redraw` each frame. Gamepad input does not wake the
+    /// application. Desktop and web only; other platforms redraw continuously.
+    Reactive,
+}
+
+/// Graphics API the renderer prefers when choosing an adapter.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphicsBackend {
+    /// Picks the best API of the platform. On the web this is WebGPU, falling back to
+    /// WebGL2 on browsers without a usable WebGPU adapter; Android uses OpenGL ES.
+    #[default]
+    Auto,
+    /// Vulkan, on Windows, Linux and Android.
+    Vulkan,
+    /// Metal, on macOS and iOS.
+    Metal,
+    /// Direct3D 12, on Windows.
+    Dx12,
+    /// OpenGL ES, or WebGL2 on the web. Limits such as the maximum texture size are
+    /// lower than on the other APIs.
+    Gl,
+    /// WebGPU, on the web only.
+    WebGpu,
+}
+
+impl GraphicsBackend {
+    pub(crate) fn backends(self) -> wgpu::Backends {
+        match self {
+            Self::Auto if cfg!(all(target_arch = "wasm32", target_os = "unknown")) => {
+                wgpu::Backends::BROWSER_WEBGPU | wgpu::Backends::GL
+            }
+            Self::Auto if cfg!(target_os = "android") => wgpu::Backends::GL,
+            Self::Auto => wgpu::Backends::all(),
+            Self::Vulkan => wgpu::Backends::VULKAN,
+            Self::Metal => wgpu::Backends::METAL,
+            Self::Dx12 => wgpu::Backends::DX12,
+            Self::Gl => wgpu::Backends::GL,
+            Self::WebGpu => wgpu::Backends::BROWSER_WEBGPU,
+        }
+    }
+}
+
 /// Configuration for the application window.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 #[derive(Debug, Clone)]
 pub struct WindowConfig {
     /// The window title.
@@ -16,6 +115,9 @@ pub struct WindowConfig {
     /// Whether the window is resizable by the user.
     pub resizable: bool,
     /// Whether to start in fullscreen mode.
+    ///
+    /// On Android this draws edge to edge with the system bars hidden, see
+    /// [`set_fullscreen`](crate::set_fullscreen).
     pub fullscreen: bool,
     #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
     /// Optional canvas element ID for WebAssembly.
@@ -28,6 +130,39 @@ pub struct WindowConfig {
     /// `update_hz: 60` produces a fixed `Duration` of roughly 16.67 ms for `update`,
     /// while `update_hz: 120` produces roughly 8.33 ms.
     pub update_hz: u32,
+    /// Forces a fixed scale factor instead of following the display's DPI.
+    ///
+    /// Logical sizes, input coordinates and the surface size are all derived from this
+    /// value, so `Some(1.0)` makes one `Pt` equal one physical pixel. `None` follows the
+    /// platform and reports changes through [`Spot::scale_factor_changed`].
+    pub scale_factor: Option<f64>,
+    /// Initial stacking order of the window. Only supported on desktop platforms.
+    pub window_level: WindowLevel,
+    /// Whether to redraw continuously or only on demand.
+    pub redraw: RedrawMode,
+    /// Remembers the window's size, position and fullscreen state across runs.
+    ///
+    /// When set to an application name, the geometry is saved to
+    /// [`storage::data_dir`](crate::storage::data_dir) on exit and restored on the next
+    /// start, taking precedence over `width`, `height` and `fullscreen`. Only supported
+    /// on desktop platforms.
+    pub remember_geometry: Option<String>,
+    /// Measures the GPU time of every render pass with timestamp queries, reported through
+    /// [`render_stats`](crate::render_stats). Ignored when the adapter lacks support.
+    pub gpu_timing: bool,
+    /// Saves compiled render pipelines under [`storage::cache_dir`](crate::storage::cache_dir)
+    /// of this application name and reuses them on the next start.
+    ///
+    /// Cuts the shader compile stall on startup for apps with many custom shaders. Only
+    /// Vulkan drivers support pipeline caches, so this does nothing on other backends.
+    pub pipeline_cache: Option<String>,
+    /// Calls [`Spot::update_tick`](crate::Spot::update_tick) with the fixed update's index
+    /// instead of [`Spot::update`](crate::Spot::update), and samples input once per tick
+    /// into a replayable [`InputFrame`](crate::InputFrame).
+    pub deterministic: bool,
+    /// Graphics API to render with. Initialization fails if the chosen API is not
+    /// available.
+    pub backend: GraphicsBackend,
 }
 
 impl Default for WindowConfig {
@@ -42,22 +177,166 @@ impl Default for WindowConfig {
             canvas_id: None,
             transparent: false,
             update_hz: 60,
+            scale_factor: None,
+            window_level: WindowLevel::Normal,
+            redraw: RedrawMode::Continuous,
+            remember_geometry: None,
+            gpu_timing: false,
+            pipeline_cache: None,
+            deterministic: false,
+            backend: GraphicsBackend::Auto,
         }
     }
 }
 
 impl WindowConfig {
     pub(crate) fn fixed_update_step(&self) -> Duration {
-        assert!(
-            self.update_hz > 0,
-            "WindowConfig::update_hz must be greater than zero"
-        );
-        let step = Duration::from_secs_f64(1.0 / f64::from(self.update_hz));
-        assert!(
-            !step.is_zero(),
-            "WindowConfig::update_hz is too high to represent as a Duration"
-        );
-        step
+        fixed_update_step("WindowConfig", self.update_hz)
+    }
+
+    /// Returns the forced scale factor, ignoring values that are not positive and finite.
+    pub(crate) fn forced_scale_factor(&self) -> Option<f64> {
+        self.scale_factor
+            .filter(|scale_factor| scale_factor.is_finite() && *scale_factor > 0.0)
+    }
+
+    /// Returns the scale factor to use given the one reported by the platform.
+    pub(crate) fn resolve_scale_factor(&self, platform_scale_factor: f64) -> f64 {
+        self.forced_scale_factor().unwrap_or(platform_scale_factor)
+    }
+}
+
+/// Configuration for running without a window, see [`run_headless`].
+#[derive(Debug, Clone)]
+pub struct HeadlessConfig {
+    /// Logical width of the offscreen screen.
+    pub width: Pt,
+    /// Logical height of the offscreen screen.
+    pub height: Pt,
+    /// Scale factor used to convert the logical size into pixels.
+    pub scale_factor: f64,
+    /// Whether the screen is cleared to transparent instead of opaque black.
+    pub transparent: bool,
+    /// Fixed-frequency game logic updates per second.
+    ///
+    /// Headless runs do not follow the wall clock: every frame runs exactly one
+    /// `update` with the fixed step, so results are reproducible.
+    pub update_hz: u32,
+    /// Stops after this many frames. `None` runs until [`quit`](crate::quit) is called.
+    pub frames: Option<u64>,
+    /// Measures GPU pass times, see [`WindowConfig::gpu_timing`].
+    pub gpu_timing: bool,
+    /// Runs the scene in deterministic mode, see [`WindowConfig::deterministic`].
+    pub deterministic: bool,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            width: Pt(800.0),
+            height: Pt(600.0),
+            scale_factor: 1.0,
+            transparent: false,
+            update_hz: 60,
+            frames: None,
+            gpu_timing: false,
+            deterministic: false,
+        }
+    }
+}
+
+impl HeadlessConfig {
+    pub(crate) fn fixed_update_step(&self) -> Duration {
+        fixed_update_step("HeadlessConfig", self.update_hz)
+    }
+
+    pub(crate) fn physical_size(&self) -> (u32, u32) {
+        (
+            (self.width * self.scale_factor as f32)
+                .to_u32_clamped()
+                .max(1),
+            (self.height * self.scale_factor as f32)
+                .to_u32_clamped()
+                .max(1),
+        )
+    }
+}
+
+/// Pixels of a rendered frame, read back from the GPU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameCapture {
+    /// Width in physical pixels.
+    pub width: u32,
+    /// Height in physical pixels.
+    pub height: u32,
+    /// Tightly packed RGBA8 rows, top to bottom.
+    pub rgba: Vec<u8>,
+}
+
+impl FrameCapture {
+    /// Returns the RGBA value of the pixel at `(x, y)`, if it is inside the frame.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let offset = ((y * self.width + x) * 4) as usize;
+        self.rgba
+            .get(offset..offset + 4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+    }
+}
+
+fn fixed_update_step(owner: &str, update_hz: u32) -> Duration {
+    assert!(
+        update_hz > 0,
+        "{owner}::update_hz must be greater than zero"
+    );
+    let step = Duration::from_secs_f64(1.0 / f64::from(update_hz));
+    assert!(
+        !step.is_zero(),
+        "{owner}::update_hz is too high to represent as a Duration"
+    );
+    step
+}
+
+/// Why [`run`], [`run_headless`] or `run_software` stopped the application instead of
+/// letting it quit normally.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SpotError {
+    /// The platform event loop could not be created or stopped with an error.
+    EventLoop(anyhow::Error),
+    /// The window could not be created.
+    Window(anyhow::Error),
+    /// No usable graphics adapter or device was found.
+    Graphics(anyhow::Error),
+    /// A scene returned an error from [`Spot::try_initialize`].
+    Scene(anyhow::Error),
+    /// A headless frame could not be rendered or read back.
+    Frame(anyhow::Error),
+}
+
+impl std::fmt::Display for SpotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EventLoop(error) => write!(f, "event loop error: {error:#}"),
+            Self::Window(error) => write!(f, "failed to create window: {error:#}"),
+            Self::Graphics(error) => write!(f, "failed to initialize graphics: {error:#}"),
+            Self::Scene(error) => write!(f, "failed to initialize scene: {error:#}"),
+            Self::Frame(error) => write!(f, "failed to render frame: {error:#}"),
+        }
+    }
+}
+
+impl std::error::Error for SpotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::EventLoop(error)
+            | Self::Window(error)
+            | Self::Graphics(error)
+            | Self::Scene(error)
+            | Self::Frame(error) => Some(error.as_ref()),
+        }
     }
 }
 
@@ -90,19 +369,125 @@ mod tests {
         }
         .fixed_update_step();
     }
+
+    #[test]
+    fn scale_factor_override_replaces_platform_value() {
+        let config = WindowConfig::default();
+        assert_eq!(config.resolve_scale_factor(2.0), 2.0);
+
+        let config = WindowConfig {
+            scale_factor: Some(1.0),
+            ..Default::default()
+        };
+        assert_eq!(config.resolve_scale_factor(2.0), 1.0);
+
+        let config = WindowConfig {
+            scale_factor: Some(0.0),
+            ..Default::default()
+        };
+        assert_eq!(config.resolve_scale_factor(2.0), 2.0);
+    }
+
+    #[test]
+    fn explicit_backend_selects_only_that_api() {
+        assert_eq!(GraphicsBackend::Gl.backends(), wgpu::Backends::GL);
+        assert_eq!(GraphicsBackend::Vulkan.backends(), wgpu::Backends::VULKAN);
+        assert!(
+            GraphicsBackend::Auto
+                .backends()
+                .contains(wgpu::Backends::GL)
+        );
+    }
+
+    #[test]
+    fn headless_physical_size_applies_scale_factor() {
+        let config = HeadlessConfig {
+            width: Pt(320.0),
+            height: Pt(180.0),
+            scale_factor: 2.0,
+            ..Default::default()
+        };
+        assert_eq!(config.physical_size(), (640, 360));
+    }
+
+    #[test]
+    fn frame_capture_pixel_reads_rgba() {
+        let capture = FrameCapture {
+            width: 2,
+            height: 1,
+            rgba: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        assert_eq!(capture.pixel(1, 0), Some([5, 6, 7, 8]));
+        assert_eq!(capture.pixel(2, 0), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn settings_load_from_partial_json() {
+        let config: WindowConfig =
+            serde_json::from_str(r#"{ "title": "demo", "width": 1280.0, "redraw": "Reactive" }"#)
+                .unwrap();
+        assert_eq!(config.title, "demo");
+        assert_eq!(config.width, Pt(1280.0));
+        assert_eq!(config.height, WindowConfig::default().height);
+        assert_eq!(config.redraw, RedrawMode::Reactive);
+
+        let keybinds = [crate::Key::Space, crate::Key::W];
+        let json = serde_json::to_string(&keybinds).unwrap();
+        assert_eq!(json, r#"["Space","W"]"#);
+        assert_eq!(
+            serde_json::from_str::<[crate::Key; 2]>(&json).unwrap(),
+            keybinds
+        );
+
+        let options: crate::SoundOptions =
+            serde_json::from_str(r#"{ "volume": 0.5, "bus": "Music" }"#).unwrap();
+        assert_eq!(options.bus, crate::AudioBus::Music);
+        assert_eq!(options.speed, 1.0);
+    }
 }
 
 /// Starts the application with the specified scene type `T` and configuration.
 ///
 /// This function is the main entry point for most platforms. On desktop and web,
-/// it initializes the event loop and starts the renderer.
+/// it initializes the event loop and starts the renderer. Returns once the application
+/// quits, with an error if it could not start or a scene failed to initialize.
 #[cfg(not(target_os = "android"))]
-pub fn run<T: Spot + 'static>(window: WindowConfig) {
-    <window::WinitWgpuBackend as window::WindowBackend>::run::<T>(window);
+pub fn run<T: Spot + 'static>(window: WindowConfig) -> Result<(), SpotError> {
+    crate::graphics::profile::request_gpu_timing(window.gpu_timing);
+    crate::graphics::pipeline_cache::set_app_name(window.pipeline_cache.clone());
+    <window::WinitWgpuBackend as window::WindowBackend>::run::<T>(window)
 }
 
 /// Starts the application on Android with the specified scene type `T`.
 #[cfg(target_os = "android")]
-pub fn run<T: Spot + 'static>(window: WindowConfig, app: AndroidApp) {
-    <window::WinitWgpuBackend as window::WindowBackend>::run::<T>(window, app);
+pub fn run<T: Spot + 'static>(window: WindowConfig, app: AndroidApp) -> Result<(), SpotError> {
+    crate::graphics::profile::request_gpu_timing(window.gpu_timing);
+    crate::graphics::pipeline_cache::set_app_name(window.pipeline_cache.clone());
+    <window::WinitWgpuBackend as window::WindowBackend>::run::<T>(window, app)
+}
+
+/// Runs the scene `T` without a window, rendering every frame into an offscreen texture.
+///
+/// Each rendered frame is read back and available through [`last_frame`](crate::last_frame)
+/// on the next update. This is intended for automated tests, CI golden-image checks and
+/// server-side rendering. Returns [`SpotError::Graphics`] if no GPU adapter is available.
+#[cfg(not(any(target_os = "android", target_os = "ios", target_arch = "wasm32")))]
+pub fn run_headless<T: Spot + 'static>(config: HeadlessConfig) -> Result<(), SpotError> {
+    crate::graphics::profile::request_gpu_timing(config.gpu_timing);
+    window::headless::run::<T>(config, false)
+}
+
+/// Runs the scene `T` like [`run_headless`], but rasterizes every frame on the CPU, so
+/// tests that draw run on machines without any GPU.
+///
+/// Images and text are drawn with nearest sampling and without custom shaders, repeat
+/// modes or 3D models, so frames are close to but not identical with GPU frames.
+/// [`HeadlessConfig::gpu_timing`] is ignored.
+#[cfg(all(
+    feature = "software-render",
+    not(any(target_os = "android", target_os = "ios", target_arch = "wasm32"))
+))]
+pub fn run_software<T: Spot + 'static>(config: HeadlessConfig) -> Result<(), SpotError> {
+    window::headless::run::<T>(config, true)
 }
@@ -0,0 +1,127 @@
+use crate::{Context, Image, Pt, Texture};
+use std::collections::HashMap;
+
+/// A layer drawn once into its own window-sized render target.
+#[derive(Debug, Clone, Copy)]
+struct BakedLayer {
+    texture: Texture,
+    scale_factor: f64,
+    gpu_generation: u32,
+    dirty: bool,
+}
+
+/// Layers baked with [`Context::bake_layer`], by id.
+#[derive(Debug, Default)]
+pub(crate) struct BakedLayers {
+    layers: HashMap<String, BakedLayer>,
+}
+
+impl Context {
+    /// Returns a window-sized image holding what `build` drew into it, for static
+    /// backgrounds and HUD chrome that rarely change.
+    ///
+    /// `build` runs the first time `id` is baked and again only after
+    /// [`invalidate_layer`](Self::invalidate_layer), a window resize or scale factor change,
+    /// or a lost GPU device; other frames just return the cached image, so its draws are
+    /// neither resolved nor uploaded again. Draw the returned image like any other:
+    ///
+    /// ```rust,no_run
+    /// # use spottedcat::{Context, DrawOption, Image};
+    /// # fn draw(ctx: &mut Context, screen: Image, tiles: &[(Image, DrawOption)]) {
+    /// let background = ctx.bake_layer("background", |ctx, layer| {
+    ///     for (tile, options) in tiles {
+    ///         layer.draw(ctx, tile, *options);
+    ///     }
+    /// });
+    /// screen.draw(ctx, &background, DrawOption::default());
+    /// # }
+    /// ```
+    pub fn bake_layer(&mut self, id: &str, build: impl FnOnce(&mut Context, Image)) -> Image {
+        let (width, height) = self.window_logical_size();
+        let (width, height) = (width.max(Pt(1.0)), height.max(Pt(1.0)));
+        let scale_factor = self.scale_factor();
+        let gpu_generation = self.registry.gpu_generation;
+
+        let cached = self.runtime.baked_layers.layers.get(id).copied();
+        if let Some(layer) = cached {
+            let current = !layer.dirty
+                && layer.texture.width() == width
+                && layer.texture.height() == height
+                && layer.scale_factor == scale_factor
+                && layer.gpu_generation == gpu_generation;
+            if current {
+                return layer.texture.view();
+            }
+            layer.texture.destroy(self);
+        }
+
+        // Layers are cached by the engine, not owned by the scene baking them.
+        let texture =
+            self.outside_scene_scope(|ctx| Texture::new_render_target(ctx, width, height));
+        build(self, texture.view());
+        self.runtime.baked_layers.layers.insert(
+            id.to_owned(),
+            BakedLayer {
+                texture,
+                scale_factor,
+                gpu_generation,
+                dirty: false,
+            },
+        );
+        texture.view()
+    }
+
+    /// Makes the next [`bake_layer`](Self::bake_layer) call for `id` draw it again.
+    pub fn invalidate_layer(&mut self, id: &str) {
+        if let Some(layer) = self.runtime.baked_layers.layers.get_mut(id) {
+            layer.dirty = true;
+        }
+    }
+
+    /// Frees the render target of layer `id`. Returns false if it was not baked.
+    pub fn remove_layer(&mut self, id: &str) -> bool {
+        match self.runtime.baked_layers.layers.remove(id) {
+            Some(layer) => layer.texture.destroy(self),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DrawOption;
+
+    #[test]
+    fn layers_are_drawn_again_only_when_invalidated_or_resized() {
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt(320.0), Pt(240.0));
+        let tile = Image::new(&mut ctx, Pt(8.0), Pt(8.0), &[255; 8 * 8 * 4]).unwrap();
+        let mut builds = 0;
+        let mut bake = |ctx: &mut Context| {
+            ctx.bake_layer("background", |ctx, layer| {
+                builds += 1;
+                layer.draw(ctx, &tile, DrawOption::default());
+            })
+        };
+
+        let first = bake(&mut ctx);
+        assert_eq!((first.width(), first.height()), (Pt(320.0), Pt(240.0)));
+        assert_eq!(ctx.runtime.draw_list.len(), 1);
+        ctx.runtime.draw_list.clear();
+        assert_eq!(bake(&mut ctx), first);
+        assert!(ctx.runtime.draw_list.is_empty());
+
+        ctx.invalidate_layer("background");
+        let rebaked = bake(&mut ctx);
+        ctx.set_window_logical_size(Pt(640.0), Pt(480.0));
+        let resized = bake(&mut ctx);
+        assert_eq!(resized.width(), Pt(640.0));
+        assert_eq!(builds, 3);
+
+        assert!(ctx.remove_layer("background"));
+        assert!(!ctx.remove_layer("background"));
+        assert!(ctx.registry.textures[rebaked.texture_id as usize].is_none());
+        assert!(ctx.registry.textures[resized.texture_id as usize].is_none());
+    }
+}
@@ -41,28 +41,61 @@
 //!     }
 //! }
 //!
-//! fn main() {
+//! fn main() -> Result<(), spottedcat::SpotError> {
 //!     spottedcat::run::<MyApp>(WindowConfig {
 //!         title: "SpottedCat Example".to_string(),
 //!         ..Default::default()
-//!     });
+//!     })
 //! }
 //! ```
+//!
+//! ## Audio
+//!
+//! All audio goes through one mixer, driven by free functions that take the [`Context`]:
+//!
+//! - [`register_sound`] or [`load_sound_async`] decode a sound once and return a sound ID.
+//! - [`play_sound`] and its variants start a voice of it, for one-shots and music tracks
+//!   alike, and return a play ID for commands such as [`pause_sound`], [`fade_out_sound`]
+//!   or [`seek_sound`].
+//! - Every voice plays on an [`AudioBus`] with its own volume, effects and ducking.
+//! - [`register_audio_source`] mixes in samples generated by the application.
 
 #[cfg(target_os = "android")]
 pub mod android;
+mod animation;
 mod assets;
 mod audio;
+mod bitmap_font;
+mod camera;
+mod child;
+mod clipboard;
 mod context;
 mod context_3d;
 mod controls;
+mod debug_overlay;
+mod diagnostics;
+mod draw_list;
 mod drawable;
 mod drawable_3d;
+pub mod ease;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 #[cfg(feature = "effects")]
 mod fog;
+#[cfg(feature = "frame-dump")]
+mod frame_dump;
 mod gamepad;
+mod geometry;
 mod glyph_cache;
 mod graphics;
+mod haptics;
+#[cfg(all(
+    feature = "hot-reload",
+    any(target_os = "windows", target_os = "macos", target_os = "linux")
+))]
+mod hot_reload;
+#[cfg(feature = "i18n")]
+pub mod i18n;
 pub mod image;
 mod image_raw;
 mod image_shader;
@@ -71,51 +104,120 @@ mod input;
 pub mod ios;
 mod key;
 mod launch;
+mod layers;
+mod loading;
+mod lockstep;
 pub mod math;
 #[cfg(feature = "model-3d")]
 pub mod model;
 mod mouse;
+mod parallax;
+mod particles;
+#[cfg(feature = "rapier2d")]
+pub mod physics;
 mod platform;
 mod platform_events;
+mod power;
 mod pt;
+pub mod rand;
+mod recording;
+#[cfg(feature = "gif")]
+mod replay;
+mod scene_scope;
 mod scenes;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+mod scroll_view;
 mod shader_opts;
 mod shader_templates;
+#[cfg(feature = "software-render")]
+mod software_render;
 mod sound;
 mod splash;
+mod sprite;
+pub mod storage;
+mod tasks;
 pub mod text;
+mod tilemap;
 
 mod touch;
-#[cfg(any(feature = "utils", feature = "model-3d", feature = "gltf"))]
+mod transform;
+mod tween;
+pub mod ui;
+mod user_events;
+#[cfg(any(
+    test,
+    feature = "aseprite",
+    feature = "utils",
+    feature = "model-3d",
+    feature = "gltf",
+    feature = "tiled",
+    all(target_arch = "wasm32", target_os = "unknown")
+))]
 pub mod utils;
 mod window;
 
 #[cfg(target_os = "android")]
 pub use android_activity::AndroidApp;
+pub use animation::{Animation, LoopMode};
 pub use assets::*;
+pub use bitmap_font::register_bitmap_font;
+pub use camera::Camera2D;
 pub use context::Context;
 pub use controls::*;
-pub use drawable::{DrawOption, Drawable, ImageRepeat};
+pub use debug_overlay::{DebugOverlay, debug_camera, debug_overlay, set_debug_overlay_key};
+pub use draw_list::DrawList;
+pub use drawable::{BatchBreaks, DrawOption, DrawStats, Drawable, FrameStats, ImageRepeat};
 #[cfg(feature = "model-3d")]
 pub use drawable_3d::DrawOption3D;
+pub use ease::Easing;
 #[cfg(feature = "effects")]
 pub use fog::{FogBackgroundSettings, FogSamplingSettings, FogSettings};
 pub use gamepad::{GamepadAxis, GamepadButton, GamepadId, GamepadInfo};
+pub use geometry::{Circle, Line, Rect, SweepHit};
 
+pub use graphics::errors::{DrawError, GpuError, GpuErrorKind};
+pub use graphics::profile::{GpuFrameStats, GpuPassTime, RenderStats};
 pub use graphics::texture::Texture;
+#[cfg(all(
+    feature = "hot-reload",
+    any(target_os = "windows", target_os = "macos", target_os = "linux")
+))]
+pub use hot_reload::{
+    load_font_watched, load_image_shader_watched, load_image_watched, load_sound_watched,
+};
 pub use image::{Bounds, Image};
 pub use image_shader::{
     ImageShaderBindings, ImageShaderBlendMode, ImageShaderDesc, ImageShaderInput,
 };
 pub use input::InputManager;
 pub use key::Key;
-pub use launch::{WindowConfig, run};
+#[cfg(not(any(target_os = "android", target_os = "ios", target_arch = "wasm32")))]
+pub use launch::run_headless;
+#[cfg(all(
+    feature = "software-render",
+    not(any(target_os = "android", target_os = "ios", target_arch = "wasm32"))
+))]
+pub use launch::run_software;
+pub use launch::{
+    FrameCapture, GraphicsBackend, HeadlessConfig, Orientation, RedrawMode, SpotError, Theme,
+    WindowConfig, WindowLevel, run,
+};
 #[cfg(feature = "model-3d")]
 pub use model::Model;
 pub use mouse::MouseButton;
+pub use parallax::ParallaxLayer;
+pub use particles::ParticleEmitter;
 pub use platform_events::PlatformEvent;
 pub use pt::Pt;
-pub use scenes::{Spot, quit, switch_scene, switch_scene_with};
+pub use recording::{RecordingConfig, is_recording, start_recording, stop_recording};
+#[cfg(feature = "gif")]
+pub use replay::{ReplayConfig, save_gif, start_replay_buffer, stop_replay_buffer};
+pub use scenes::{
+    Spot, Transition, pop_scene, push_scene, push_scene_with, quit, switch_scene,
+    switch_scene_with, switch_scene_with_transition,
+};
+pub use scroll_view::ScrollView;
 pub use shader_opts::ShaderOpts;
 pub use shader_templates::{
     ImageShaderTemplate, ModelShaderTemplate, image_shader_template, model_shader_template,
@@ -134,12 +236,34 @@ mod export_smoke_tests {
             super::register_image_shader_template;
     }
 }
+pub use child::{Child, SubSpot};
+pub use clipboard::{read_clipboard_text, set_clipboard_text};
+pub use haptics::{HapticStyle, impact, vibrate};
+pub use loading::{Loading, Progress, loading_progress};
+pub use lockstep::{
+    InputFrame, input_frame, is_deterministic, queue_input_frames, queued_input_frames, rng,
+    seed_rng, tick,
+};
+pub use power::{ThermalState, battery_level, is_charging, thermal_state};
+pub use scene_scope::{SceneResource, keep_after_scene, release_with_scene};
 pub use sound::*;
 pub use splash::OneShotSplash;
+pub use sprite::{Sprite, draw_sprites};
+pub use tasks::{spawn_future, spawn_task};
 pub use text::Text;
+pub use tilemap::{MapLayer, MapObject, ObjectLayer, TileLayer, TileMap, TileSet};
 pub use touch::{TouchInfo, TouchPhase};
+pub use transform::{NodeId, Transform, TransformTree};
+pub use tween::{
+    Lerp, TimerId, Tweened, after, cancel_timer, every, is_tweening, stop_tween, tween,
+};
+pub use user_events::{EventSender, UserEvent, event_sender};
 #[cfg(feature = "utils")]
 pub use utils::image::{AsyncImageLoader, LoadingImage, load_image_async};
+/// The windowing library behind [`Spot::on_event`], re-exported so event types match
+/// the version the engine uses.
+#[cfg(not(target_os = "android"))]
+pub use winit;
 
 // --- Functional API ---
 
@@ -235,9 +359,29 @@ pub fn set_ambient_light(ctx: &mut Context, color: [f32; 4]) {
     ctx.set_ambient_light(color);
 }
 
-/// Sets the window's logical size.
+/// Requests resizing the window to the given logical size.
+///
+/// The new size is reported by [`window_size`] and [`Spot::resized`] once the platform
+/// applies it. Ignored on mobile platforms.
 pub fn set_window_size(ctx: &mut Context, width: Pt, height: Pt) {
-    ctx.set_window_logical_size(width, height);
+    ctx.set_window_size(width, height);
+}
+
+/// Requests moving the window's top-left corner to the given logical screen position.
+///
+/// Ignored on web and mobile platforms.
+pub fn set_window_position(ctx: &mut Context, x: Pt, y: Pt) {
+    ctx.set_window_position(x, y);
+}
+
+/// Keeps the display from dimming and locking while `enabled`, for games played without
+/// touching the screen such as tilt controls or idle watch modes.
+///
+/// Sets `FLAG_KEEP_SCREEN_ON` on Android, `idleTimerDisabled` on iOS and holds a screen
+/// wake lock on the web, where it is re-acquired when the page becomes visible again.
+/// Ignored on desktop.
+pub fn set_keep_screen_on(ctx: &mut Context, enabled: bool) {
+    ctx.runtime.pending_keep_screen_on = Some(enabled);
 }
 
 /// Returns the window's logical size as a tuple of `(width, height)`.
@@ -245,7 +389,36 @@ pub fn window_size(ctx: &Context) -> (Pt, Pt) {
     ctx.window_logical_size()
 }
 
+/// Returns whether the window is in portrait or landscape orientation.
+///
+/// Follows the window's aspect ratio, so it also changes when a desktop window or
+/// browser canvas is resized. Changes are delivered through [`Spot::orientation_changed`].
+pub fn orientation(ctx: &Context) -> Orientation {
+    let (width, height) = ctx.window_logical_size();
+    Orientation::of_size(width, height)
+}
+
+/// Returns the logical insets `(top, bottom, left, right)` of the area not covered by
+/// notches, rounded corners, home indicators and system bars.
+///
+/// Keep HUDs and touch controls inside these insets. They come from the safe area on
+/// iOS, the content rect on Android and the CSS `env(safe-area-inset-*)` values on the
+/// web, and are zero elsewhere. Changes are reported through [`Spot::resized`].
+pub fn safe_area(ctx: &Context) -> (Pt, Pt, Pt, Pt) {
+    ctx.runtime.safe_area
+}
+
+/// Returns the most recent frame rendered by [`run_headless`] or `run_software`, if any.
+pub fn last_frame(ctx: &Context) -> Option<&FrameCapture> {
+    ctx.runtime.last_frame.as_ref()
+}
+
 /// Inserts or replaces a resource of type T in the context.
+///
+/// Resources live in the context rather than in a scene, so they persist across
+/// [`switch_scene`], [`push_scene`] and [`pop_scene`] until taken or removed. There is
+/// one resource per type, shared by the `Rc` functions and [`insert_resource_value`],
+/// [`resource_value`], [`get_resource_mut`] and [`remove_resource`].
 pub fn insert_resource<T: std::any::Any>(ctx: &mut Context, value: std::rc::Rc<T>) {
     ctx.insert_resource(value)
 }
@@ -260,6 +433,46 @@ pub fn take_resource<T: std::any::Any>(ctx: &mut Context) -> Option<std::rc::Rc<
     ctx.take_resource::<T>()
 }
 
+/// Stores a value as the resource of type T, returning the previous one unless `Rc`
+/// clones of it are still alive.
+///
+/// Unlike [`insert_resource`], no `Rc` is needed: read it with [`resource_value`] and
+/// change it in place with [`get_resource_mut`].
+pub fn insert_resource_value<T: std::any::Any>(ctx: &mut Context, value: T) -> Option<T> {
+    ctx.insert_resource_value(value)
+}
+
+/// Borrows the resource of type T, if it exists.
+pub fn resource_value<T: std::any::Any>(ctx: &Context) -> Option<&T> {
+    ctx.resource_value::<T>()
+}
+
+/// Borrows the resource of type T for modification, if it exists.
+///
+/// Returns `None` while `Rc` clones from [`get_resource`] are alive. The borrow ends before the context can be used again, so copy out what is needed
+/// or scope the access:
+///
+/// ```rust,no_run
+/// # fn update(ctx: &mut spottedcat::Context) {
+/// struct Score(u32);
+///
+/// if let Some(score) = spottedcat::get_resource_mut::<Score>(ctx) {
+///     score.0 += 10;
+/// }
+/// # }
+/// ```
+pub fn get_resource_mut<T: std::any::Any>(ctx: &mut Context) -> Option<&mut T> {
+    ctx.get_resource_mut::<T>()
+}
+
+/// Removes and returns the resource of type T, if it exists.
+///
+/// Resources with live `Rc` clones stay in place and `None` is returned; use
+/// [`take_resource`] for those.
+pub fn remove_resource<T: std::any::Any>(ctx: &mut Context) -> Option<T> {
+    ctx.remove_resource::<T>()
+}
+
 /// Returns the window's scale factor (DPI).
 pub fn scale_factor(ctx: &Context) -> f64 {
     ctx.scale_factor()
@@ -356,10 +569,44 @@ pub fn set_cursor_visible(ctx: &mut Context, visible: bool) {
 }
 
 /// Requests fullscreen toggle.
+///
+/// On Android fullscreen renders edge to edge, including into display cutouts, with the
+/// system bars hidden until swiped in; [`safe_area`] reports what they and cutouts cover.
+/// Hiding the navigation bar needs a `setEdgeToEdge(boolean)` method on the activity
+/// that does so on the UI thread, as in the GameActivity example; without it only the
+/// status bar is hidden.
 pub fn set_fullscreen(ctx: &mut Context, enabled: bool) {
     ctx.set_fullscreen(enabled);
 }
 
+/// Requests a window stacking order update. Ignored on mobile and web.
+pub fn set_window_level(ctx: &mut Context, level: WindowLevel) {
+    ctx.set_window_level(level);
+}
+
+/// Requests keeping the window above all other windows, or back to normal stacking.
+pub fn set_always_on_top(ctx: &mut Context, enabled: bool) {
+    ctx.set_window_level(if enabled {
+        WindowLevel::AlwaysOnTop
+    } else {
+        WindowLevel::Normal
+    });
+}
+
+/// Returns the operating system's light/dark appearance, if the platform reports one.
+///
+/// Changes are delivered through [`Spot::theme_changed`].
+pub fn system_theme(ctx: &Context) -> Option<Theme> {
+    ctx.system_theme()
+}
+
+/// Requests another update and draw when running with [`RedrawMode::Reactive`].
+///
+/// Has no effect in continuous mode, where every frame is drawn anyway.
+pub fn request_redraw(ctx: &mut Context) {
+    ctx.request_redraw();
+}
+
 /// Scene switch helper that keeps the ctx-first API shape.
 pub fn switch_scene_ctx<T: Spot + 'static>(_ctx: &mut Context) {
     switch_scene::<T>();
@@ -370,6 +617,24 @@ pub fn switch_scene_with_ctx<T: Spot + 'static, P: std::any::Any>(_ctx: &mut Con
     switch_scene_with::<T, P>(payload);
 }
 
+/// Scene switch with transition helper that keeps the ctx-first API shape.
+pub fn switch_scene_with_transition_ctx<T: Spot + 'static>(
+    _ctx: &mut Context,
+    transition: Transition,
+) {
+    switch_scene_with_transition::<T>(transition);
+}
+
+/// Scene push helper that keeps the ctx-first API shape.
+pub fn push_scene_ctx<T: Spot + 'static>(_ctx: &mut Context) {
+    push_scene::<T>();
+}
+
+/// Scene pop helper that keeps the ctx-first API shape.
+pub fn pop_scene_ctx(_ctx: &mut Context) {
+    pop_scene();
+}
+
 /// Quit helper that keeps the ctx-first API shape.
 pub fn quit_ctx(_ctx: &mut Context) {
     quit();
@@ -394,6 +659,41 @@ pub fn total_elapsed(ctx: &Context) -> std::time::Duration {
     ctx.total_elapsed()
 }
 
+/// Returns how many quads the last rendered frame drew and culled off-screen.
+pub fn draw_stats(ctx: &Context) -> DrawStats {
+    ctx.runtime.draw_stats
+}
+
+/// Returns the CPU stage times and, when enabled, GPU pass times of the last rendered frame.
+///
+/// Unlike the `SPOT_PROFILE_RENDER` log this is always available, so games can draw their
+/// own in-game profiler from it.
+pub fn render_stats(ctx: &Context) -> &RenderStats {
+    &ctx.runtime.render_stats
+}
+
+/// Caps the video memory used by images, in bytes; `None` (the default) means no limit.
+///
+/// After each frame, images and shared atlas pages that were not drawn in it are evicted
+/// from the GPU, least recently drawn first, until the rest fit. Their pixels stay in
+/// memory, so an evicted image is uploaded again the next time it is drawn. Render
+/// targets are never evicted and do not count towards the budget. Removing the budget
+/// uploads every evicted image again.
+pub fn set_texture_budget(ctx: &mut Context, bytes: Option<u64>) {
+    ctx.registry.texture_budget = bytes;
+    if bytes.is_none() {
+        // Draws no longer bring evicted textures back one by one.
+        for entry in ctx.registry.textures.iter_mut().flatten() {
+            entry.evicted = false;
+        }
+    }
+}
+
+/// Returns the approximate video memory currently used by images and atlas pages, in bytes.
+pub fn texture_memory(ctx: &Context) -> u64 {
+    graphics::texture_budget::resident_texture_bytes(&ctx.registry)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -408,7 +708,7 @@ mod tests {
         let img_size = [Pt::from(100.0), Pt::from(100.0)];
 
         let opts = DrawOption::default().with_position([Pt::from(100.0), Pt::from(100.0)]);
-        ctx.push(DrawCommand::Image(Box::new(ImageCommand {
+        ctx.push(DrawCommand::Image(ImageCommand {
             id: img_id,
             target_texture_id: 0,
             opts,
@@ -416,7 +716,7 @@ mod tests {
             shader_opts: ShaderOpts::default(),
             shader_bindings: ImageShaderBindings::default(),
             size: img_size,
-        })));
+        }));
         assert_eq!(
             ctx.runtime.draw_list.len(),
             1,
@@ -427,7 +727,7 @@ mod tests {
         let opts = DrawOption::default()
             .with_position([Pt::from(100.0), Pt::from(100.0)])
             .with_scale([-1.0, 1.0]);
-        ctx.push(DrawCommand::Image(Box::new(ImageCommand {
+        ctx.push(DrawCommand::Image(ImageCommand {
             id: img_id,
             target_texture_id: 0,
             opts,
@@ -435,7 +735,7 @@ mod tests {
             shader_opts: ShaderOpts::default(),
             shader_bindings: ImageShaderBindings::default(),
             size: img_size,
-        })));
+        }));
         assert_eq!(
             ctx.runtime.draw_list.len(),
             1,
@@ -446,7 +746,7 @@ mod tests {
         let opts = DrawOption::default()
             .with_position([Pt::from(-0.1), Pt::from(100.0)])
             .with_scale([-1.0, 1.0]);
-        ctx.push(DrawCommand::Image(Box::new(ImageCommand {
+        ctx.push(DrawCommand::Image(ImageCommand {
             id: img_id,
             target_texture_id: 0,
             opts,
@@ -454,7 +754,7 @@ mod tests {
             shader_opts: ShaderOpts::default(),
             shader_bindings: ImageShaderBindings::default(),
             size: img_size,
-        })));
+        }));
         assert_eq!(
             ctx.runtime.draw_list.len(),
             0,
@@ -465,7 +765,7 @@ mod tests {
         let opts = DrawOption::default()
             .with_position([Pt::from(100.0), Pt::from(100.0)])
             .with_scale([1.0, -1.0]);
-        ctx.push(DrawCommand::Image(Box::new(ImageCommand {
+        ctx.push(DrawCommand::Image(ImageCommand {
             id: img_id,
             target_texture_id: 0,
             opts,
@@ -473,7 +773,7 @@ mod tests {
             shader_opts: ShaderOpts::default(),
             shader_bindings: ImageShaderBindings::default(),
             size: img_size,
-        })));
+        }));
         assert_eq!(
             ctx.runtime.draw_list.len(),
             1,
@@ -484,7 +784,7 @@ mod tests {
         let opts = DrawOption::default()
             .with_position([Pt::from(100.0), Pt::from(100.0)])
             .with_scale([-1.0, -1.0]);
-        ctx.push(DrawCommand::Image(Box::new(ImageCommand {
+        ctx.push(DrawCommand::Image(ImageCommand {
             id: img_id,
             target_texture_id: 0,
             opts,
@@ -492,7 +792,7 @@ mod tests {
             shader_opts: ShaderOpts::default(),
             shader_bindings: ImageShaderBindings::default(),
             size: img_size,
-        })));
+        }));
         assert_eq!(
             ctx.runtime.draw_list.len(),
             1,
@@ -523,4 +823,66 @@ mod tests {
         assert_eq!(texture_entry.pixel_width, 100);
         assert_eq!(texture_entry.pixel_height, 200);
     }
+
+    #[test]
+    fn always_on_top_requests_window_level() {
+        let mut ctx = Context::new();
+        set_always_on_top(&mut ctx, true);
+        assert_eq!(
+            ctx.take_window_level_request(),
+            Some(WindowLevel::AlwaysOnTop)
+        );
+
+        set_always_on_top(&mut ctx, false);
+        assert_eq!(ctx.take_window_level_request(), Some(WindowLevel::Normal));
+        assert_eq!(ctx.take_window_level_request(), None);
+    }
+
+    #[test]
+    fn window_geometry_setters_queue_requests() {
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt::from(800.0), Pt::from(600.0));
+
+        set_window_size(&mut ctx, Pt::from(1024.0), Pt::from(768.0));
+        set_window_position(&mut ctx, Pt::from(10.0), Pt::from(20.0));
+        assert_eq!(window_size(&ctx), (Pt::from(800.0), Pt::from(600.0)));
+        assert_eq!(
+            ctx.take_window_size_request(),
+            Some((Pt::from(1024.0), Pt::from(768.0)))
+        );
+        assert_eq!(
+            ctx.take_window_position_request(),
+            Some((Pt::from(10.0), Pt::from(20.0)))
+        );
+    }
+
+    #[test]
+    fn showing_virtual_keyboard_enables_text_input() {
+        let mut ctx = Context::new();
+        show_virtual_keyboard(&mut ctx, true);
+        assert!(text_input_enabled(&ctx));
+        assert_eq!(ctx.take_virtual_keyboard_request(), Some(true));
+        assert_eq!(virtual_keyboard_height(&ctx), None);
+
+        ctx.set_virtual_keyboard(Some(Pt(300.0)));
+        assert_eq!(ctx.take_virtual_keyboard_event(), Some(Some(Pt(300.0))));
+        ctx.set_virtual_keyboard(Some(Pt(300.0)));
+        assert_eq!(ctx.take_virtual_keyboard_event(), None);
+        assert_eq!(virtual_keyboard_height(&ctx), Some(Pt(300.0)));
+    }
+
+    #[test]
+    fn safe_area_change_is_reported_as_resize() {
+        let mut ctx = Context::new();
+        ctx.set_window_logical_size(Pt::from(390.0), Pt::from(844.0));
+        let _ = ctx.take_resize_event();
+        assert_eq!(safe_area(&ctx), (Pt(0.0), Pt(0.0), Pt(0.0), Pt(0.0)));
+
+        ctx.set_safe_area(Pt(47.0), Pt(34.0), Pt(0.0), Pt(-1.0));
+        assert_eq!(safe_area(&ctx), (Pt(47.0), Pt(34.0), Pt(0.0), Pt(0.0)));
+        assert!(ctx.take_resize_event().is_some());
+
+        ctx.set_safe_area(Pt(47.0), Pt(34.0), Pt(0.0), Pt(0.0));
+        assert!(ctx.take_resize_event().is_none());
+    }
 }
@@ -0,0 +1,273 @@
+use crate::{Context, Image, Pt, Spot};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// How far [`Spot::preload`] has come, e.g. the number of assets loaded so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Progress {
+    pub done: u32,
+    pub total: u32,
+}
+
+impl Progress {
+    /// Nothing left to load.
+    pub const DONE: Progress = Progress { done: 0, total: 0 };
+
+    pub fn new(done: u32, total: u32) -> Self {
+        Self { done, total }
+    }
+
+    pub fn is_complete(self) -> bool {
+        self.done >= self.total
+    }
+
+    /// Completed fraction between 0.0 and 1.0, for progress bars.
+    pub fn fraction(self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.done as f32 / self.total as f32).min(1.0)
+    }
+}
+
+/// Returns the progress of the scene being loaded by a [`Loading`] wrapper.
+///
+/// Meant for loading screens; `None` when nothing is loading.
+pub fn loading_progress(ctx: &Context) -> Option<Progress> {
+    ctx.get_resource::<Progress>().map(|progress| *progress)
+}
+
+/// Shows `TScreen` while [`TNext::preload`](Spot::preload) reports progress, then
+/// initializes `TNext` in its place.
+///
+/// Works anywhere a scene type is expected, so the first scene can load without freezing
+/// the window and later scenes can be switched to the same way:
+///
+/// ```rust,no_run
+/// use spottedcat::{Context, Image, Loading, Progress, Spot, WindowConfig, run};
+///
+/// struct LoadingScreen;
+///
+/// impl Spot for LoadingScreen {
+///     fn initialize(_ctx: &mut Context) -> Self {
+///         Self
+///     }
+///
+///     fn draw(&mut self, ctx: &mut Context, _screen: Image) {
+///         let _fraction = spottedcat::loading_progress(ctx).map(Progress::fraction);
+///     }
+/// }
+///
+/// struct Game {
+///     music: u32,
+/// }
+///
+/// impl Spot for Game {
+///     fn preload(ctx: &mut Context) -> Progress {
+///         if spottedcat::get_resource::<u32>(ctx).is_none() {
+///             let id = spottedcat::load_sound_async(ctx, "music.ogg").unwrap_or(0);
+///             spottedcat::insert_resource(ctx, std::rc::Rc::new(id));
+///         }
+///         let id = *spottedcat::get_resource::<u32>(ctx).unwrap();
+///         match spottedcat::sound_status(ctx, id) {
+///             Some(spottedcat::SoundStatus::Loading) => Progress::new(0, 1),
+///             _ => Progress::DONE,
+///         }
+///     }
+///
+///     fn initialize(ctx: &mut Context) -> Self {
+///         let music = spottedcat::take_resource::<u32>(ctx).map_or(0, |id| *id);
+///         Self { music }
+///     }
+///
+///     fn draw(&mut self, _ctx: &mut Context, _screen: Image) {}
+/// }
+///
+/// fn main() -> Result<(), spottedcat::SpotError> {
+///     run::<Loading<Game, LoadingScreen>>(WindowConfig::default())
+/// }
+/// ```
+pub struct Loading<TNext: Spot + 'static, TScreen: Spot + 'static> {
+    inner: LoadingInner<TNext, TScreen>,
+}
+
+enum LoadingInner<TNext, TScreen> {
+    Loading(TScreen),
+    Ready(TNext),
+}
+
+impl<TNext: Spot + 'static, TScreen: Spot + 'static> Loading<TNext, TScreen> {
+    /// Polls `TNext::preload`, returning true once it has completed.
+    fn poll_preload(ctx: &mut Context) -> bool {
+        let progress = TNext::preload(ctx);
+        if progress.is_complete() {
+            ctx.take_resource::<Progress>();
+            return true;
+        }
+        ctx.insert_resource(Rc::new(progress));
+        false
+    }
+}
+
+impl<TNext: Spot + 'static, TScreen: Spot + 'static> Spot for Loading<TNext, TScreen> {
+    fn initialize(ctx: &mut Context) -> Self {
+        crate::scenes::initialize_or_panic(ctx)
+    }
+
+    fn try_initialize(ctx: &mut Context) -> anyhow::Result<Self> {
+        let inner = if Self::poll_preload(ctx) {
+            LoadingInner::Ready(TNext::try_initialize(ctx)?)
+        } else {
+            LoadingInner::Loading(TScreen::try_initialize(ctx)?)
+        };
+        Ok(Self { inner })
+    }
+
+    fn update(&mut self, ctx: &mut Context, dt: Duration) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => {
+                screen.update(ctx, dt);
+                if Self::poll_preload(ctx) {
+                    screen.remove(ctx);
+                    match TNext::try_initialize(ctx) {
+                        Ok(next) => self.inner = LoadingInner::Ready(next),
+                        Err(error) => crate::scenes::fail_scene(error),
+                    }
+                }
+            }
+            LoadingInner::Ready(next) => next.update(ctx, dt),
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, screen: Image) {
+        match &mut self.inner {
+            LoadingInner::Loading(loading) => loading.draw(ctx, screen),
+            LoadingInner::Ready(next) => next.draw(ctx, screen),
+        }
+    }
+
+    fn resized(&mut self, ctx: &mut Context, w: Pt, h: Pt) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.resized(ctx, w, h),
+            LoadingInner::Ready(next) => next.resized(ctx, w, h),
+        }
+    }
+
+    fn scale_factor_changed(&mut self, ctx: &mut Context, scale_factor: f64) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.scale_factor_changed(ctx, scale_factor),
+            LoadingInner::Ready(next) => next.scale_factor_changed(ctx, scale_factor),
+        }
+    }
+
+    fn theme_changed(&mut self, ctx: &mut Context, theme: crate::Theme) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.theme_changed(ctx, theme),
+            LoadingInner::Ready(next) => next.theme_changed(ctx, theme),
+        }
+    }
+
+    fn user_event(&mut self, ctx: &mut Context, event: crate::UserEvent) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.user_event(ctx, event),
+            LoadingInner::Ready(next) => next.user_event(ctx, event),
+        }
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn on_event(&mut self, ctx: &mut Context, event: &winit::event::WindowEvent) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.on_event(ctx, event),
+            LoadingInner::Ready(next) => next.on_event(ctx, event),
+        }
+    }
+
+    fn draws_scenes_below(&self) -> bool {
+        match &self.inner {
+            LoadingInner::Loading(screen) => screen.draws_scenes_below(),
+            LoadingInner::Ready(next) => next.draws_scenes_below(),
+        }
+    }
+
+    fn resumed(&mut self, ctx: &mut Context) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.resumed(ctx),
+            LoadingInner::Ready(next) => next.resumed(ctx),
+        }
+    }
+
+    fn suspended(&mut self, ctx: &mut Context) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.suspended(ctx),
+            LoadingInner::Ready(next) => next.suspended(ctx),
+        }
+    }
+
+    fn on_exit(&mut self, ctx: &mut Context) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => screen.on_exit(ctx),
+            LoadingInner::Ready(next) => next.on_exit(ctx),
+        }
+    }
+
+    fn remove(&mut self, ctx: &mut Context) {
+        match &mut self.inner {
+            LoadingInner::Loading(screen) => {
+                ctx.take_resource::<Progress>();
+                screen.remove(ctx);
+            }
+            LoadingInner::Ready(next) => next.remove(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Counts preload calls; loading completes on the third.
+    struct Polls(Cell<u32>);
+
+    struct Screen;
+    struct Level;
+
+    impl Spot for Screen {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _screen: Image) {}
+    }
+
+    impl Spot for Level {
+        fn preload(ctx: &mut Context) -> Progress {
+            let polls = ctx.get_resource::<Polls>().expect("poll counter");
+            polls.0.set(polls.0.get() + 1);
+            Progress::new(polls.0.get() - 1, 2)
+        }
+
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _screen: Image) {}
+    }
+
+    #[test]
+    fn next_scene_initializes_once_preload_completes() {
+        let mut ctx = Context::new();
+        ctx.insert_resource(Rc::new(Polls(Cell::new(0))));
+
+        let mut scene = Loading::<Level, Screen>::initialize(&mut ctx);
+        assert!(matches!(scene.inner, LoadingInner::Loading(_)));
+        assert_eq!(loading_progress(&ctx), Some(Progress::new(0, 2)));
+
+        scene.update(&mut ctx, Duration::from_millis(16));
+        assert_eq!(loading_progress(&ctx).map(Progress::fraction), Some(0.5));
+
+        scene.update(&mut ctx, Duration::from_millis(16));
+        assert!(matches!(scene.inner, LoadingInner::Ready(_)));
+        assert_eq!(loading_progress(&ctx), None);
+    }
+}
@@ -0,0 +1,200 @@
+//! Deterministic simulation: tick indices, the engine RNG and per-tick input.
+//!
+//! With [`WindowConfig::deterministic`](crate::WindowConfig::deterministic) set, the
+//! engine calls [`Spot::update_tick`] with the index of the fixed update instead of
+//! [`Spot::update`] with a `Duration`. Input is sampled once per tick into an
+//! [`InputFrame`], which can be saved or sent over the network and fed back with
+//! [`queue_input_frames`] to replay the same ticks. Together with [`seed_rng`] this is
+//! what rollback netcode and byte-identical replays build on:
+//!
+//! ```rust,ignore
+//! fn update_tick(&mut self, ctx: &mut Context, tick: u64) {
+//!     self.recording.push(spottedcat::input_frame(ctx).clone());
+//!     let roll = spottedcat::rng(ctx).gen_range(1..=6);
+//!     // ...
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::gamepad::{GamepadAxis, GamepadButton, GamepadId};
+use crate::rand::Rng;
+use crate::{Context, Pt, Spot};
+
+/// The input of a single tick: everything held down plus the cursor, scroll and text.
+///
+/// Presses and releases are derived from the previous frame when one is applied, so a
+/// key tapped and released within one tick still counts as held for that tick. Touches
+/// and sensors are not part of a frame.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InputFrame {
+    pub(crate) keys_down: Vec<u64>,
+    pub(crate) mouse_down: u8,
+    pub(crate) mouse_other_down: Vec<u16>,
+    pub(crate) cursor_position: Option<(Pt, Pt)>,
+    pub(crate) scroll_delta: (f32, f32),
+    pub(crate) text_input: String,
+    pub(crate) gamepads: Vec<GamepadFrame>,
+}
+
+/// Held buttons and axis values of one connected gamepad, sorted so equal input
+/// serializes to equal bytes.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GamepadFrame {
+    pub(crate) id: GamepadId,
+    pub(crate) buttons_down: Vec<GamepadButton>,
+    pub(crate) axes: Vec<(GamepadAxis, f32)>,
+}
+
+/// Tick counter, engine RNG and input frames kept on the context.
+#[derive(Debug, Default)]
+pub(crate) struct Lockstep {
+    pub(crate) enabled: bool,
+    tick: u64,
+    rng: Rng,
+    frame: InputFrame,
+    queued: VecDeque<InputFrame>,
+}
+
+/// Index of the current fixed update, starting at `0`.
+///
+/// Counts in both modes; in deterministic mode it is also passed to
+/// [`Spot::update_tick`].
+pub fn tick(ctx: &Context) -> u64 {
+    ctx.runtime.lockstep.tick
+}
+
+/// Returns whether the engine runs [`Spot::update_tick`] instead of [`Spot::update`].
+pub fn is_deterministic(ctx: &Context) -> bool {
+    ctx.runtime.lockstep.enabled
+}
+
+/// The engine's random number generator.
+///
+/// Starts from seed `0`. Clone it to snapshot the state for a rollback and assign the
+/// clone back to restore it.
+pub fn rng(ctx: &mut Context) -> &mut Rng {
+    &mut ctx.runtime.lockstep.rng
+}
+
+/// Restarts the engine RNG from `seed`.
+pub fn seed_rng(ctx: &mut Context, seed: u64) {
+    ctx.runtime.lockstep.rng = Rng::new(seed);
+}
+
+/// The input frame applied for the current tick in deterministic mode.
+pub fn input_frame(ctx: &Context) -> &InputFrame {
+    &ctx.runtime.lockstep.frame
+}
+
+/// Queues recorded frames to use instead of live input, one per tick in order.
+///
+/// Only consumed in deterministic mode; live input resumes once the queue runs out.
+pub fn queue_input_frames(ctx: &mut Context, frames: impl IntoIterator<Item = InputFrame>) {
+    ctx.runtime.lockstep.queued.extend(frames);
+}
+
+/// Returns how many queued input frames have not been used yet.
+pub fn queued_input_frames(ctx: &Context) -> usize {
+    ctx.runtime.lockstep.queued.len()
+}
+
+/// Samples the input for the coming tick, or takes the next queued frame.
+pub(crate) fn begin_tick(ctx: &mut Context) {
+    if !ctx.runtime.lockstep.enabled {
+        return;
+    }
+    let lockstep = &mut ctx.runtime.lockstep;
+    let frame = match lockstep.queued.pop_front() {
+        Some(frame) => frame,
+        None => ctx.runtime.input.capture_frame(),
+    };
+    ctx.runtime.input.apply_frame(&lockstep.frame, &frame);
+    lockstep.frame = frame;
+}
+
+/// Runs the scene's update for this tick in the configured mode.
+pub(crate) fn update(ctx: &mut Context, spot: &mut dyn Spot, dt: Duration) {
+    if ctx.runtime.lockstep.enabled {
+        let tick = ctx.runtime.lockstep.tick;
+        spot.update_tick(ctx, tick);
+    } else {
+        spot.update(ctx, dt);
+    }
+}
+
+pub(crate) fn end_tick(ctx: &mut Context) {
+    ctx.runtime.lockstep.tick += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    fn holding(key: Key) -> InputFrame {
+        let mut keys_down = vec![0; Key::WORDS];
+        keys_down[key.as_index() / 64] |= 1 << (key.as_index() % 64);
+        InputFrame {
+            keys_down,
+            ..InputFrame::default()
+        }
+    }
+
+    #[test]
+    fn queued_frames_replace_live_input_with_derived_edges() {
+        let mut ctx = Context::new();
+        ctx.runtime.lockstep.enabled = true;
+        queue_input_frames(
+            &mut ctx,
+            [holding(Key::A), holding(Key::A), InputFrame::default()],
+        );
+
+        begin_tick(&mut ctx);
+        assert!(ctx.input().key_pressed(Key::A));
+        assert!(ctx.input().key_down(Key::A));
+        assert_eq!(input_frame(&ctx), &holding(Key::A));
+        ctx.input_mut().end_frame();
+        end_tick(&mut ctx);
+
+        begin_tick(&mut ctx);
+        assert!(!ctx.input().key_pressed(Key::A));
+        assert!(ctx.input().key_down(Key::A));
+        ctx.input_mut().end_frame();
+        end_tick(&mut ctx);
+
+        begin_tick(&mut ctx);
+        assert!(ctx.input().key_released(Key::A));
+        assert!(!ctx.input().key_down(Key::A));
+        ctx.input_mut().end_frame();
+        end_tick(&mut ctx);
+
+        assert_eq!(tick(&ctx), 3);
+        assert_eq!(queued_input_frames(&ctx), 0);
+    }
+
+    #[test]
+    fn seeded_rng_repeats_its_sequence() {
+        let mut ctx = Context::new();
+        seed_rng(&mut ctx, 7);
+        let first: Vec<u64> = (0..4).map(|_| rng(&mut ctx).next_u64()).collect();
+        seed_rng(&mut ctx, 7);
+        let snapshot = rng(&mut ctx).clone();
+        let second: Vec<u64> = (0..4).map(|_| rng(&mut ctx).next_u64()).collect();
+        assert_eq!(first, second);
+
+        *rng(&mut ctx) = snapshot;
+        assert_eq!(rng(&mut ctx).next_u64(), first[0]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn frames_round_trip_through_serde() {
+        let frame = holding(Key::Space);
+        let json = serde_json::to_string(&frame).unwrap();
+        assert_eq!(serde_json::from_str::<InputFrame>(&json).unwrap(), frame);
+    }
+}
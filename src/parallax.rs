@@ -0,0 +1,136 @@
+use crate::{Camera2D, Context, DrawOption, Image, ImageRepeat, Pt};
+
+/// A background or foreground image that scrolls at its own speed relative to a
+/// [`Camera2D`], e.g. distant mountains in a side-scroller.
+///
+/// With `factor` `[0.0, 0.0]` the layer is fixed to the screen, with `[1.0, 1.0]` it moves
+/// with the world, and values in between make it look further away. Repeating axes are
+/// tiled seamlessly across the whole view, so the image never runs out while scrolling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParallaxLayer {
+    pub image: Image,
+    /// How much of the camera movement the layer follows, per axis.
+    pub factor: [f32; 2],
+    /// Where the layer's first tile starts in the world, at camera position zero.
+    pub offset: [Pt; 2],
+    /// Scale of the image before camera zoom.
+    pub scale: f32,
+    pub repeat_x: bool,
+    pub repeat_y: bool,
+    pub opacity: f32,
+}
+
+impl ParallaxLayer {
+    /// A layer that follows the camera by `factor` and repeats horizontally.
+    pub fn new(image: Image, factor: [f32; 2]) -> Self {
+        Self {
+            image,
+            factor,
+            offset: [Pt(0.0), Pt(0.0)],
+            scale: 1.0,
+            repeat_x: true,
+            repeat_y: false,
+            opacity: 1.0,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: [Pt; 2]) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_repeat(mut self, repeat_x: bool, repeat_y: bool) -> Self {
+        self.repeat_x = repeat_x;
+        self.repeat_y = repeat_y;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Draws the layer into `target` as seen through `camera`.
+    pub fn draw(&self, ctx: &mut Context, target: Image, camera: &Camera2D) {
+        if let Some(options) = self.draw_option(camera) {
+            target.draw(ctx, &self.image, options);
+        }
+    }
+
+    fn draw_option(&self, camera: &Camera2D) -> Option<DrawOption> {
+        let zoom = camera.current_zoom();
+        let view = camera.view_rect();
+        let size = [self.image.width().as_f32(), self.image.height().as_f32()];
+        let tile = [size[0] * self.scale * zoom, size[1] * self.scale * zoom];
+        if tile[0] <= 0.0 || tile[1] <= 0.0 {
+            return None;
+        }
+
+        let view_origin = [view.x.as_f32(), view.y.as_f32()];
+        let viewport = [camera.viewport[0].as_f32(), camera.viewport[1].as_f32()];
+        let repeats = [self.repeat_x, self.repeat_y];
+        let mut position = [0.0; 2];
+        let mut extent = [0.0; 2];
+        for axis in 0..2 {
+            let start = (self.offset[axis].as_f32() - view_origin[axis] * self.factor[axis]) * zoom;
+            if repeats[axis] {
+                // Start one partial tile before the screen edge and cover the whole view.
+                position[axis] = start.rem_euclid(tile[axis]) - tile[axis];
+                extent[axis] = viewport[axis] + tile[axis];
+            } else {
+                position[axis] = start;
+                extent[axis] = tile[axis];
+            }
+        }
+
+        let repeat = match (self.repeat_x, self.repeat_y) {
+            (true, true) => ImageRepeat::Repeat,
+            (true, false) => ImageRepeat::RepeatX,
+            (false, true) => ImageRepeat::RepeatY,
+            (false, false) => ImageRepeat::Stretch,
+        };
+        Some(
+            DrawOption::new(
+                [Pt(position[0]), Pt(position[1])],
+                0.0,
+                [extent[0] / size[0], extent[1] / size[1]],
+            )
+            .with_repeat(repeat)
+            .with_tile_size([Pt(tile[0]), Pt(tile[1])])
+            .with_opacity(self.opacity),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_scroll_by_their_factor_and_wrap_repeating_axes() {
+        let mut ctx = Context::new();
+        let image = Image::new(&mut ctx, Pt(50.0), Pt(20.0), &[255; 50 * 20 * 4]).unwrap();
+        let camera = Camera2D::new([Pt(200.0), Pt(100.0)]).with_position([Pt(230.0), Pt(80.0)]);
+        let layer = ParallaxLayer::new(image, [0.5, 0.0]).with_offset([Pt(0.0), Pt(60.0)]);
+
+        // The view starts at x = 130, so the layer has scrolled 65 points: 15 into a tile.
+        let options = layer.draw_option(&camera).unwrap();
+        assert_eq!(options.position(), [Pt(-15.0), Pt(60.0)]);
+        assert_eq!(options.scale(), [5.0, 1.0]);
+        assert_eq!(options.repeat(), ImageRepeat::RepeatX);
+        assert_eq!(options.tile_size(), Some([Pt(50.0), Pt(20.0)]));
+
+        let fixed = ParallaxLayer::new(image, [0.0, 0.0])
+            .with_repeat(false, false)
+            .with_scale(2.0);
+        let options = fixed.draw_option(&camera.clone().with_zoom(2.0)).unwrap();
+        assert_eq!(options.position(), [Pt(0.0), Pt(0.0)]);
+        assert_eq!(options.scale(), [4.0, 4.0]);
+        assert_eq!(options.repeat(), ImageRepeat::Stretch);
+    }
+}
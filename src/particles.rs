@@ -0,0 +1,295 @@
+use crate::rand::Rng;
+use crate::{Context, DrawOption, Image, Lerp, Pt};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Particle {
+    position: [f32; 2],
+    velocity: [f32; 2],
+    age: f32,
+    lifetime: f32,
+}
+
+/// Spawns, moves and draws many copies of one image, e.g. sparks, smoke or dust.
+///
+/// Each particle starts at the emitter position with a random lifetime, speed and
+/// direction, falls with `gravity`, and shrinks or grows and changes color from the
+/// `*_start` to the `*_end` values over its life. Particles are drawn centered on their
+/// position with per-draw tints, so an emitter's particles render as a single batch.
+///
+/// Pairs such as `lifetime` and `speed` are `[min, max]` ranges sampled per particle.
+/// Speeds and gravity are in points per second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleEmitter {
+    pub image: Image,
+    /// Where new particles appear; moving the emitter does not move live particles.
+    pub position: [Pt; 2],
+    /// Particles spawned per second while `emitting`.
+    pub rate: f32,
+    pub emitting: bool,
+    pub lifetime: [Duration; 2],
+    pub speed: [f32; 2],
+    /// Angle of the launch direction in radians; `0.0` points right and positive angles
+    /// turn towards the bottom of the screen.
+    pub direction: f32,
+    /// Largest deviation from `direction` in radians; `PI` launches in every direction.
+    pub spread: f32,
+    pub gravity: [f32; 2],
+    pub scale_start: f32,
+    pub scale_end: f32,
+    pub color_start: [f32; 4],
+    pub color_end: [f32; 4],
+    /// Spawning stops while this many particles are alive.
+    pub max_particles: usize,
+    particles: Vec<Particle>,
+    pending: f32,
+    rng: Rng,
+}
+
+impl ParticleEmitter {
+    pub fn new(image: Image) -> Self {
+        Self {
+            image,
+            position: [Pt(0.0), Pt(0.0)],
+            rate: 50.0,
+            emitting: true,
+            lifetime: [Duration::from_secs(1); 2],
+            speed: [50.0, 100.0],
+            direction: -std::f32::consts::FRAC_PI_2,
+            spread: std::f32::consts::PI,
+            gravity: [0.0, 0.0],
+            scale_start: 1.0,
+            scale_end: 1.0,
+            color_start: [1.0, 1.0, 1.0, 1.0],
+            color_end: [1.0, 1.0, 1.0, 1.0],
+            max_particles: 10_000,
+            particles: Vec::new(),
+            pending: 0.0,
+            rng: Rng::default(),
+        }
+    }
+
+    pub fn with_position(mut self, position: [Pt; 2]) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn with_lifetime(mut self, min: Duration, max: Duration) -> Self {
+        self.lifetime = [min, max];
+        self
+    }
+
+    pub fn with_speed(mut self, min: f32, max: f32) -> Self {
+        self.speed = [min, max];
+        self
+    }
+
+    pub fn with_direction(mut self, direction: f32, spread: f32) -> Self {
+        self.direction = direction;
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: [f32; 2]) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_scale(mut self, start: f32, end: f32) -> Self {
+        self.scale_start = start;
+        self.scale_end = end;
+        self
+    }
+
+    pub fn with_color(mut self, start: [f32; 4], end: [f32; 4]) -> Self {
+        self.color_start = start;
+        self.color_end = end;
+        self
+    }
+
+    pub fn with_max_particles(mut self, max_particles: usize) -> Self {
+        self.max_particles = max_particles;
+        self
+    }
+
+    /// Seeds the random numbers used for new particles, for reproducible effects.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    /// Number of live particles.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Removes every live particle.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.pending = 0.0;
+    }
+
+    /// Spawns `count` particles at once, e.g. for an explosion, up to `max_particles`.
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            self.spawn();
+        }
+    }
+
+    /// Ages and moves live particles, removes expired ones and spawns new ones; call once
+    /// per `update`.
+    pub fn update(&mut self, dt: Duration) {
+        let dt = dt.as_secs_f32();
+        let gravity = self.gravity;
+        self.particles.retain_mut(|particle| {
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                return false;
+            }
+            particle.velocity[0] += gravity[0] * dt;
+            particle.velocity[1] += gravity[1] * dt;
+            particle.position[0] += particle.velocity[0] * dt;
+            particle.position[1] += particle.velocity[1] * dt;
+            true
+        });
+
+        if !self.emitting {
+            self.pending = 0.0;
+            return;
+        }
+        self.pending += self.rate.max(0.0) * dt;
+        while self.pending >= 1.0 {
+            self.pending -= 1.0;
+            self.spawn();
+        }
+    }
+
+    /// Draws every live particle into `target`.
+    pub fn draw(&self, ctx: &mut Context, target: Image) {
+        let width = self.image.width().as_f32();
+        let height = self.image.height().as_f32();
+        for particle in &self.particles {
+            let t = particle.age / particle.lifetime;
+            let scale = self.scale_start.lerp(self.scale_end, t);
+            let position = [
+                Pt(particle.position[0] - width * scale / 2.0),
+                Pt(particle.position[1] - height * scale / 2.0),
+            ];
+            let options = DrawOption::new(position, 0.0, [scale, scale])
+                .with_tint(self.color_start.lerp(self.color_end, t));
+            target.draw(ctx, &self.image, options);
+        }
+    }
+
+    fn spawn(&mut self) {
+        if self.particles.len() >= self.max_particles {
+            return;
+        }
+        let lifetime = self.lifetime[0]
+            .as_secs_f32()
+            .lerp(self.lifetime[1].as_secs_f32(), self.rng.next_f32());
+        if lifetime <= 0.0 {
+            return;
+        }
+        let speed = self.speed[0].lerp(self.speed[1], self.rng.next_f32());
+        let angle = self.direction + self.spread * (self.rng.next_f32() * 2.0 - 1.0);
+        // Positive angles turn towards +y, which points down on screen.
+        let (sin, cos) = angle.sin_cos();
+        self.particles.push(Particle {
+            position: [self.position[0].as_f32(), self.position[1].as_f32()],
+            velocity: [cos * speed, sin * speed],
+            age: 0.0,
+            lifetime,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawable::DrawCommand;
+
+    fn emitter(ctx: &mut Context) -> ParticleEmitter {
+        let image = Image::new(ctx, Pt(4.0), Pt(4.0), &[255; 64]).unwrap();
+        ParticleEmitter::new(image).with_position([Pt(100.0), Pt(100.0)])
+    }
+
+    #[test]
+    fn particles_spawn_at_the_rate_and_expire_after_their_lifetime() {
+        let mut ctx = Context::new();
+        let mut particles = emitter(&mut ctx)
+            .with_rate(10.0)
+            .with_lifetime(Duration::from_millis(500), Duration::from_millis(500))
+            .with_max_particles(4);
+
+        particles.update(Duration::from_millis(250));
+        assert_eq!(particles.len(), 2);
+        particles.update(Duration::from_millis(250));
+        assert_eq!(particles.len(), 4);
+
+        particles.emitting = false;
+        particles.update(Duration::from_millis(500));
+        assert!(particles.is_empty());
+
+        particles.burst(10);
+        assert_eq!(particles.len(), 4);
+    }
+
+    #[test]
+    fn particles_move_under_gravity() {
+        let mut ctx = Context::new();
+        let mut particles = emitter(&mut ctx)
+            .with_speed(10.0, 10.0)
+            .with_direction(0.0, 0.0)
+            .with_gravity([0.0, 20.0]);
+        particles.emitting = false;
+        particles.burst(1);
+
+        particles.update(Duration::from_millis(500));
+        let particle = particles.particles[0];
+        assert_eq!(particle.velocity, [10.0, 10.0]);
+        assert_eq!(particle.position, [105.0, 105.0]);
+    }
+
+    #[test]
+    fn particles_are_drawn_centered_with_interpolated_scale_and_color() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(200, 200, 1.0);
+        let screen = crate::window::make_screen_target(&ctx);
+        let mut particles = emitter(&mut ctx)
+            .with_speed(0.0, 0.0)
+            .with_lifetime(Duration::from_secs(1), Duration::from_secs(1))
+            .with_scale(1.0, 3.0)
+            .with_color([1.0, 1.0, 1.0, 1.0], [1.0, 0.0, 0.0, 0.0]);
+        particles.emitting = false;
+        particles.burst(3);
+        particles.update(Duration::from_millis(500));
+
+        ctx.begin_frame();
+        particles.draw(&mut ctx, screen);
+        let drawn: Vec<DrawOption> = ctx
+            .runtime
+            .draw_list
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Image(command) => Some(command.opts),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(drawn.len(), 3);
+        for options in drawn {
+            assert_eq!(options.position(), [Pt(96.0), Pt(96.0)]);
+            assert_eq!(options.scale(), [2.0, 2.0]);
+            assert_eq!(options.tint(), [1.0, 0.5, 0.5, 0.5]);
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! 2D rigid-body physics backed by [rapier2d](https://rapier.rs).
+//!
+//! Insert a [`PhysicsWorld`] as an owned resource and the engine steps it on the fixed
+//! timestep, right before each [`Spot::update`](crate::Spot::update):
+//!
+//! ```rust,ignore
+//! use spottedcat::physics::{PhysicsWorld, rapier2d::prelude::*};
+//!
+//! let mut world = PhysicsWorld::new(32.0);
+//! let ball = world.insert_body(RigidBodyBuilder::dynamic().translation(vector![4.0, 0.0]));
+//! world.insert_collider(ColliderBuilder::ball(0.5), ball);
+//! spottedcat::insert_resource_value(ctx, world);
+//! ```
+//!
+//! Rapier works in meters; the world converts to and from [`Pt`] with its pixels per
+//! meter, with `y` pointing down like the screen.
+
+use std::time::Duration;
+
+pub use rapier2d;
+use rapier2d::prelude::*;
+
+use crate::{Context, DrawOption, Image, Line, Pt, Sprite};
+
+/// Segments used to outline a ball in [`PhysicsWorld::collider_outlines`].
+const BALL_SEGMENTS: usize = 16;
+
+/// A rapier physics world together with everything needed to step it.
+///
+/// The body and collider sets are public so the full rapier API is available; the
+/// helpers here cover the common cases.
+pub struct PhysicsWorld {
+    /// Gravity in meters per second squared; positive `y` pulls down.
+    pub gravity: Vector<Real>,
+    pub integration_parameters: IntegrationParameters,
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub impulse_joints: ImpulseJointSet,
+    pub multibody_joints: MultibodyJointSet,
+    pub islands: IslandManager,
+    pub query_pipeline: QueryPipeline,
+    pipeline: PhysicsPipeline,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+    pixels_per_meter: f32,
+}
+
+impl std::fmt::Debug for PhysicsWorld {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PhysicsWorld")
+            .field("gravity", &self.gravity)
+            .field("bodies", &self.bodies.len())
+            .field("colliders", &self.colliders.len())
+            .field("pixels_per_meter", &self.pixels_per_meter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PhysicsWorld {
+    /// Creates an empty world with earth gravity where one meter spans
+    /// `pixels_per_meter` logical points.
+    pub fn new(pixels_per_meter: f32) -> Self {
+        Self {
+            gravity: vector![0.0, 9.81],
+            integration_parameters: IntegrationParameters::default(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            islands: IslandManager::new(),
+            query_pipeline: QueryPipeline::new(),
+            pipeline: PhysicsPipeline::new(),
+            broad_phase: DefaultBroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            ccd_solver: CCDSolver::new(),
+            pixels_per_meter: pixels_per_meter.max(f32::EPSILON),
+        }
+    }
+
+    /// Sets gravity in meters per second squared.
+    pub fn with_gravity(mut self, gravity: [f32; 2]) -> Self {
+        self.gravity = vector![gravity[0], gravity[1]];
+        self
+    }
+
+    pub fn pixels_per_meter(&self) -> f32 {
+        self.pixels_per_meter
+    }
+
+    /// Adds a rigid body and returns its handle.
+    pub fn insert_body(&mut self, body: impl Into<RigidBody>) -> RigidBodyHandle {
+        self.bodies.insert(body)
+    }
+
+    /// Attaches a collider to `body` and returns its handle.
+    pub fn insert_collider(
+        &mut self,
+        collider: impl Into<Collider>,
+        body: RigidBodyHandle,
+    ) -> ColliderHandle {
+        self.colliders
+            .insert_with_parent(collider, body, &mut self.bodies)
+    }
+
+    /// Removes a body together with its colliders and joints.
+    pub fn remove_body(&mut self, body: RigidBodyHandle) -> Option<RigidBody> {
+        self.bodies.remove(
+            body,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        )
+    }
+
+    /// Advances the simulation by `dt`.
+    pub fn step(&mut self, dt: Duration) {
+        self.integration_parameters.dt = dt.as_secs_f32();
+        self.pipeline.step(
+            &self.gravity,
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            Some(&mut self.query_pipeline),
+            &(),
+            &(),
+        );
+    }
+
+    /// Converts a rapier position in meters to logical points.
+    pub fn to_points(&self, meters: Vector<Real>) -> [Pt; 2] {
+        [
+            Pt(meters.x * self.pixels_per_meter),
+            Pt(meters.y * self.pixels_per_meter),
+        ]
+    }
+
+    /// Converts logical points to a rapier position in meters.
+    pub fn to_meters(&self, points: [Pt; 2]) -> Vector<Real> {
+        vector![
+            points[0].as_f32() / self.pixels_per_meter,
+            points[1].as_f32() / self.pixels_per_meter
+        ]
+    }
+
+    /// Returns the body's position in logical points and its rotation in radians.
+    pub fn body_transform(&self, body: RigidBodyHandle) -> Option<([Pt; 2], f32)> {
+        let body = self.bodies.get(body)?;
+        Some((self.to_points(*body.translation()), body.rotation().angle()))
+    }
+
+    /// Moves `sprite` to the body's position and rotation; returns false if the body
+    /// no longer exists.
+    ///
+    /// Give the sprite an anchor of `[0.5, 0.5]` so the image is centered on the body.
+    pub fn sync_sprite(&self, body: RigidBodyHandle, sprite: &mut Sprite) -> bool {
+        let Some((position, rotation)) = self.body_transform(body) else {
+            return false;
+        };
+        sprite.position = position;
+        sprite.rotation = rotation;
+        true
+    }
+
+    /// Returns draw options centering an image of `size` on the body.
+    pub fn draw_option(&self, body: RigidBodyHandle, size: [Pt; 2]) -> Option<DrawOption> {
+        let (position, rotation) = self.body_transform(body)?;
+        let (sin, cos) = rotation.sin_cos();
+        let [half_w, half_h] = [size[0].as_f32() / 2.0, size[1].as_f32() / 2.0];
+        // Draw options rotate around the top-left corner, so place that corner
+        // where the rotated center offset puts it.
+        let corner = [
+            position[0] - Pt(half_w * cos - half_h * sin),
+            position[1] - Pt(half_w * sin + half_h * cos),
+        ];
+        Some(DrawOption::new(corner, rotation, [1.0, 1.0]))
+    }
+
+    /// Outlines every collider in logical points: balls as polygons, cuboids as their
+    /// rotated rectangle and other shapes as their bounding box.
+    pub fn collider_outlines(&self) -> Vec<Line> {
+        let mut lines = Vec::new();
+        for (_, collider) in self.colliders.iter() {
+            let position = collider.position();
+            let shape = collider.shape();
+            let corners: Vec<Point<Real>> = if let Some(ball) = shape.as_ball() {
+                (0..BALL_SEGMENTS)
+                    .map(|i| {
+                        let angle = i as f32 / BALL_SEGMENTS as f32 * std::f32::consts::TAU;
+                        position * point![ball.radius * angle.cos(), ball.radius * angle.sin()]
+                    })
+                    .collect()
+            } else if let Some(cuboid) = shape.as_cuboid() {
+                let [hx, hy] = [cuboid.half_extents.x, cuboid.half_extents.y];
+                [[-hx, -hy], [hx, -hy], [hx, hy], [-hx, hy]]
+                    .into_iter()
+                    .map(|[x, y]| position * point![x, y])
+                    .collect()
+            } else {
+                let aabb = collider.compute_aabb();
+                let (min, max) = (aabb.mins, aabb.maxs);
+                vec![min, point![max.x, min.y], max, point![min.x, max.y]]
+            };
+            for (i, start) in corners.iter().enumerate() {
+                let end = corners[(i + 1) % corners.len()];
+                lines.push(Line::new(
+                    self.to_points(start.coords),
+                    self.to_points(end.coords),
+                ));
+            }
+        }
+        lines
+    }
+
+    /// Draws the outline of every collider into `target`, for debugging.
+    pub fn debug_draw(&mut self, ctx: &mut Context, target: Image, color: [f32; 4]) {
+        let white = ctx.white_image();
+        const THICKNESS: f32 = 1.0;
+        for line in self.collider_outlines() {
+            let [dx, dy] = [
+                (line.end[0] - line.start[0]).as_f32(),
+                (line.end[1] - line.start[1]).as_f32(),
+            ];
+            let length = line.length().as_f32();
+            if length <= 0.0 {
+                continue;
+            }
+            // Shift half the thickness sideways so the quad is centered on the segment.
+            let [nx, ny] = [-dy / length, dx / length];
+            let position = [
+                line.start[0] - Pt(nx * THICKNESS / 2.0),
+                line.start[1] - Pt(ny * THICKNESS / 2.0),
+            ];
+            let options =
+                DrawOption::new(position, dy.atan2(dx), [length, THICKNESS]).with_tint(color);
+            target.draw(ctx, &white, options);
+        }
+    }
+}
+
+/// Steps the world stored as an owned resource, if any.
+pub(crate) fn tick(ctx: &mut Context, dt: Duration) {
+    if let Some(world) = ctx.get_resource_mut::<PhysicsWorld>() {
+        world.step(dt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bodies_fall_and_sync_to_sprites() {
+        let mut world = PhysicsWorld::new(32.0);
+        let body = world.insert_body(RigidBodyBuilder::dynamic().translation(vector![1.0, 0.0]));
+        world.insert_collider(ColliderBuilder::ball(0.5), body);
+
+        for _ in 0..60 {
+            world.step(Duration::from_secs_f32(1.0 / 60.0));
+        }
+        let (position, _) = world.body_transform(body).unwrap();
+        assert_eq!(position[0], Pt(32.0));
+        assert!(position[1] > Pt(100.0), "body should fall under gravity");
+
+        let mut ctx = Context::new();
+        let image = Image::new(&mut ctx, Pt(16.0), Pt(16.0), &[255; 16 * 16 * 4]).unwrap();
+        let mut sprite = Sprite::new(image);
+        assert!(world.sync_sprite(body, &mut sprite));
+        assert_eq!(sprite.position, position);
+
+        world.remove_body(body);
+        assert!(!world.sync_sprite(body, &mut sprite));
+        assert!(world.collider_outlines().is_empty());
+    }
+
+    #[test]
+    fn cuboid_outlines_are_four_edges_in_points() {
+        let mut world = PhysicsWorld::new(10.0);
+        let body = world.insert_body(RigidBodyBuilder::fixed().translation(vector![2.0, 3.0]));
+        world.insert_collider(ColliderBuilder::cuboid(1.0, 0.5), body);
+
+        let lines = world.collider_outlines();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].start, [Pt(10.0), Pt(25.0)]);
+        assert_eq!(lines[0].end, [Pt(30.0), Pt(25.0)]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::GraphicsBackend;
 use crate::graphics::core::Graphics;
 
 #[cfg(all(not(target_arch = "wasm32"), target_os = "android"))]
@@ -31,7 +32,7 @@ pub(crate) fn finalize_graphics(init_state: &mut GraphicsInitState) -> Option<Gr
         return None;
     };
     let graphics = slot.take()?;
-    eprintln!("[spot][platform] Finalizing graphics...");
+    crate::diagnostics::info!("platform", "Finalizing graphics...");
     Some(graphics)
 }
 
@@ -43,18 +44,22 @@ pub(crate) fn begin_graphics_init(
     width: u32,
     height: u32,
     transparent: bool,
-) {
+) -> anyhow::Result<()> {
     match init_state {
         GraphicsInitState::NotStarted => {}
-        GraphicsInitState::Ready(_) | GraphicsInitState::Failed => return,
+        GraphicsInitState::Ready(_) | GraphicsInitState::Failed => return Ok(()),
     }
 
     let graphics_r = block_on(Graphics::new(instance, surface, width, height, transparent));
     match graphics_r {
-        Ok(graphics) => *init_state = GraphicsInitState::Ready(Box::new(Some(graphics))),
+        Ok(graphics) => {
+            *init_state = GraphicsInitState::Ready(Box::new(Some(graphics)));
+            Ok(())
+        }
         Err(e) => {
-            eprintln!("[spot][init] Graphics::new failed: {:?}", e);
+            crate::diagnostics::error!("init", "Graphics::new failed: {:?}", e);
             *init_state = GraphicsInitState::Failed;
+            Err(e)
         }
     }
 }
@@ -105,26 +110,26 @@ pub(crate) fn block_on<F: Future>(mut future: F) -> F::Output {
     }
 }
 
-pub(crate) fn create_wgpu_instance() -> wgpu::Instance {
-    #[cfg(all(not(target_arch = "wasm32"), target_os = "android"))]
-    {
-        wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::GL,
-            ..Default::default()
-        })
-    }
-    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
-    {
-        wgpu::Instance::default()
-    }
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn create_wgpu_instance(backend: GraphicsBackend) -> wgpu::Instance {
+    wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: backend.backends(),
+        ..Default::default()
+    })
+}
 
-    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-    {
-        wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        })
-    }
+/// Creates the instance on the web, leaving out WebGPU when the browser exposes no
+/// adapter for it.
+///
+/// This has to be decided before the canvas gets a context: once it has a `webgpu`
+/// context it can no longer be used for WebGL2.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) async fn create_wgpu_instance(backend: GraphicsBackend) -> wgpu::Instance {
+    wgpu::util::new_instance_with_webgpu_detection(&wgpu::InstanceDescriptor {
+        backends: backend.backends(),
+        ..Default::default()
+    })
+    .await
 }
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
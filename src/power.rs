@@ -0,0 +1,110 @@
+//! Battery and thermal status, so games can lower their frame rate or effects before
+//! the device throttles them.
+
+use crate::Context;
+use std::time::Duration;
+
+/// How often platforms re-read the power status.
+#[cfg_attr(not(any(target_os = "android", target_os = "ios")), allow(dead_code))]
+pub(crate) const POWER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How hot the device runs, from cool to about to shut down.
+///
+/// Ordered, so `state >= ThermalState::Serious` checks for heavy throttling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ThermalState {
+    /// Normal operation.
+    Nominal,
+    /// Slightly warm; the system may start light throttling.
+    Fair,
+    /// Performance is being reduced; cut frame rate or effects now.
+    Serious,
+    /// The device is about to shut down work to cool off.
+    Critical,
+}
+
+impl ThermalState {
+    /// Maps `PowerManager.getCurrentThermalStatus()`.
+    #[cfg_attr(not(any(target_os = "android", test)), allow(dead_code))]
+    pub(crate) fn from_android_status(status: i32) -> Option<Self> {
+        match status {
+            0 => Some(ThermalState::Nominal),
+            1 => Some(ThermalState::Fair),
+            2 | 3 => Some(ThermalState::Serious),
+            4..=6 => Some(ThermalState::Critical),
+            _ => None,
+        }
+    }
+
+    /// Maps `NSProcessInfo.thermalState`.
+    #[cfg_attr(not(any(target_os = "ios", test)), allow(dead_code))]
+    pub(crate) fn from_ios_state(state: isize) -> Option<Self> {
+        match state {
+            0 => Some(ThermalState::Nominal),
+            1 => Some(ThermalState::Fair),
+            2 => Some(ThermalState::Serious),
+            3 => Some(ThermalState::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// The last power readings of the platform, `None` where it cannot tell.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct PowerStatus {
+    pub(crate) battery_level: Option<f32>,
+    pub(crate) charging: Option<bool>,
+    pub(crate) thermal: Option<ThermalState>,
+}
+
+/// Returns the battery charge from `0.0` to `1.0`.
+///
+/// Read from `BatteryManager` on Android, `UIDevice` on iOS and the Battery Status API
+/// in Chromium-based browsers; `None` on desktop and where the device has no battery.
+/// Refreshed every few seconds.
+pub fn battery_level(ctx: &Context) -> Option<f32> {
+    ctx.runtime.power.battery_level
+}
+
+/// Returns whether the device is plugged in and charging or full.
+///
+/// Available where [`battery_level`] is.
+pub fn is_charging(ctx: &Context) -> Option<bool> {
+    ctx.runtime.power.charging
+}
+
+/// Returns how hot the device runs.
+///
+/// Read from `PowerManager` on Android 10 and later and from `NSProcessInfo` on iOS;
+/// `None` elsewhere. Refreshed every few seconds.
+pub fn thermal_state(ctx: &Context) -> Option<ThermalState> {
+    ctx.runtime.power.thermal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_thermal_levels_map_to_shared_states() {
+        assert_eq!(
+            ThermalState::from_android_status(0),
+            Some(ThermalState::Nominal)
+        );
+        assert_eq!(
+            ThermalState::from_android_status(3),
+            Some(ThermalState::Serious)
+        );
+        assert_eq!(
+            ThermalState::from_android_status(6),
+            Some(ThermalState::Critical)
+        );
+        assert_eq!(ThermalState::from_ios_state(1), Some(ThermalState::Fair));
+        assert_eq!(ThermalState::from_ios_state(9), None);
+        assert!(ThermalState::Critical > ThermalState::Serious);
+
+        let ctx = Context::new();
+        assert_eq!(battery_level(&ctx), None);
+        assert_eq!(thermal_state(&ctx), None);
+    }
+}
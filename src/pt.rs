@@ -7,6 +7,11 @@ use std::hash::Hasher;
 /// `Pt` is the primary unit used throughout the library to define layout and drawing
 /// coordinates. It abstractly represents a "logical pixel" that remains consistent regardless
 /// of the physical screen resolution or DPI scale.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Pt(pub(crate) f32);
 
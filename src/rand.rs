@@ -0,0 +1,193 @@
+//! Seedable random numbers for gameplay.
+//!
+//! [`Rng`] produces the same sequence for the same seed on every platform, so replays
+//! and lockstep multiplayer can reproduce a run from its seed. It is fast and small, not
+//! cryptographically secure.
+
+use std::ops::{Range, RangeInclusive};
+
+/// A seedable pseudo-random number generator (xoshiro256**).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Default for Rng {
+    /// A generator with seed `0`.
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Rng {
+    /// A generator whose sequence is fully determined by `seed`.
+    pub fn new(seed: u64) -> Self {
+        // Spread the seed over the whole state with SplitMix64, which never yields an
+        // all-zero state.
+        let mut seed = seed;
+        let mut state = [0; 4];
+        for word in &mut state {
+            seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            *word = z ^ (z >> 31);
+        }
+        Self { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+        result
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// A number in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u32 << 24) as f32
+    }
+
+    /// A number in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A number in `range`, e.g. `rng.gen_range(1..=6)` for a die roll.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn gen_range<T, R: SampleRange<T>>(&mut self, range: R) -> T {
+        range.sample(self)
+    }
+
+    /// True with probability `p`, which is clamped to `0.0..=1.0`.
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    /// A random element of `items`, or `None` if it is empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.gen_range(0..items.len()))
+    }
+
+    /// Puts `items` in a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.gen_range(0..=i));
+        }
+    }
+}
+
+/// Ranges [`Rng::gen_range`] can sample from.
+pub trait SampleRange<T> {
+    fn sample(self, rng: &mut Rng) -> T;
+}
+
+macro_rules! impl_int_range {
+    ($($ty:ty),*) => {$(
+        impl SampleRange<$ty> for Range<$ty> {
+            fn sample(self, rng: &mut Rng) -> $ty {
+                assert!(self.start < self.end, "cannot sample empty range");
+                sample_int(rng, self.start as i128, self.end as i128 - 1) as $ty
+            }
+        }
+
+        impl SampleRange<$ty> for RangeInclusive<$ty> {
+            fn sample(self, rng: &mut Rng) -> $ty {
+                assert!(self.start() <= self.end(), "cannot sample empty range");
+                sample_int(rng, *self.start() as i128, *self.end() as i128) as $ty
+            }
+        }
+    )*};
+}
+
+impl_int_range!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A uniform integer in `low..=high`, without the bias of taking a remainder.
+fn sample_int(rng: &mut Rng, low: i128, high: i128) -> i128 {
+    let span = (high - low + 1) as u128;
+    low + ((rng.next_u64() as u128 * span) >> 64) as i128
+}
+
+macro_rules! impl_float_range {
+    ($($ty:ty => $next:ident),*) => {$(
+        impl SampleRange<$ty> for Range<$ty> {
+            fn sample(self, rng: &mut Rng) -> $ty {
+                assert!(self.start < self.end, "cannot sample empty range");
+                let value = self.start + (self.end - self.start) * rng.$next();
+                // Rounding can land on the excluded end.
+                if value < self.end { value } else { self.start }
+            }
+        }
+
+        impl SampleRange<$ty> for RangeInclusive<$ty> {
+            fn sample(self, rng: &mut Rng) -> $ty {
+                assert!(self.start() <= self.end(), "cannot sample empty range");
+                let (start, end) = self.into_inner();
+                (start + (end - start) * rng.$next()).min(end)
+            }
+        }
+    )*};
+}
+
+impl_float_range!(f32 => next_f32, f64 => next_f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_the_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let first: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        assert_eq!(first, (0..8).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first, (0..8).map(|_| c.next_u64()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ranges_cover_their_values_and_stay_inside() {
+        let mut rng = Rng::new(7);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let roll = rng.gen_range(1..=6);
+            seen[roll as usize - 1] = true;
+            assert!((-2..3).contains(&rng.gen_range(-2..3)));
+            assert!((0.5..1.5).contains(&rng.gen_range(0.5f32..1.5)));
+        }
+        assert!(seen.iter().all(|&seen| seen));
+        assert_eq!(rng.gen_range(u64::MAX..=u64::MAX), u64::MAX);
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+
+    #[test]
+    fn pick_and_shuffle_keep_the_items() {
+        let mut rng = Rng::new(1);
+        let empty: [u8; 0] = [];
+        assert_eq!(rng.pick(&empty), None);
+        assert!([1, 2, 3].contains(rng.pick(&[1, 2, 3]).unwrap()));
+
+        let mut items: Vec<u32> = (0..32).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..32).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..32).collect::<Vec<_>>());
+    }
+}
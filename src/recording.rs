@@ -0,0 +1,239 @@
+//! Recording presented frames to an image sequence.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use crate::{Context, FrameCapture};
+
+/// Where and how [`start_recording`] captures frames.
+#[derive(Debug, Clone)]
+pub struct RecordingConfig {
+    /// Directory the frames are written to, created if missing.
+    pub directory: PathBuf,
+    /// Frames per second of the resulting sequence.
+    pub fps: u32,
+    /// Stops the recording by itself after this much time. `None` records until
+    /// [`stop_recording`].
+    pub duration: Option<Duration>,
+}
+
+impl RecordingConfig {
+    /// Records to `directory` at 30 frames per second until stopped.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            fps: 30,
+            duration: None,
+        }
+    }
+
+    /// Sets the frames per second of the sequence.
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Stops the recording after `duration`.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+}
+
+struct FrameJob {
+    first_index: u64,
+    count: u64,
+    capture: FrameCapture,
+}
+
+/// An active recording: samples presented frames on a fixed clock and hands them to a
+/// writer thread, so disk speed does not hold up rendering.
+#[derive(Debug)]
+pub(crate) struct Recording {
+    started_at: Instant,
+    fps: u32,
+    max_frames: Option<u64>,
+    next_index: u64,
+    jobs: Option<mpsc::Sender<FrameJob>>,
+    writer: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl Recording {
+    fn start(config: &RecordingConfig) -> io::Result<Self> {
+        if config.fps == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "RecordingConfig::fps must be greater than zero",
+            ));
+        }
+        std::fs::create_dir_all(&config.directory)?;
+        let directory = config.directory.clone();
+        let (jobs, job_receiver) = mpsc::channel::<FrameJob>();
+        let writer = std::thread::Builder::new()
+            .name("spot-recording".to_string())
+            .spawn(move || {
+                for job in job_receiver {
+                    let first = frame_path(&directory, job.first_index);
+                    write_ppm(&first, &job.capture)?;
+                    for index in job.first_index + 1..job.first_index + job.count {
+                        std::fs::copy(&first, frame_path(&directory, index))?;
+                    }
+                }
+                Ok(())
+            })?;
+        Ok(Self {
+            started_at: Instant::now(),
+            fps: config.fps,
+            max_frames: config
+                .duration
+                .map(|duration| (duration.as_secs_f64() * f64::from(config.fps)).ceil() as u64),
+            next_index: 0,
+            jobs: Some(jobs),
+            writer: Some(writer),
+        })
+    }
+
+    /// Returns how many frames of the sequence are due by now and not written yet.
+    pub(crate) fn frames_due(&self) -> u64 {
+        frames_due(
+            self.started_at.elapsed(),
+            self.fps,
+            self.next_index,
+            self.max_frames,
+        )
+    }
+
+    /// Queues `capture` as the next `count` frames of the sequence.
+    pub(crate) fn push(&mut self, capture: FrameCapture, count: u64) {
+        let job = FrameJob {
+            first_index: self.next_index,
+            count,
+            capture,
+        };
+        self.next_index += count;
+        if let Some(jobs) = self.jobs.as_ref()
+            && jobs.send(job).is_err()
+        {
+            // The writer stopped on an error, which `finish` reports.
+            self.jobs = None;
+        }
+    }
+
+    /// Returns true once the configured duration has been recorded.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.max_frames
+            .is_some_and(|max_frames| self.next_index >= max_frames)
+    }
+
+    /// Waits for the queued frames to be written and returns how many were recorded.
+    fn finish(mut self) -> io::Result<u64> {
+        self.jobs = None;
+        if let Some(writer) = self.writer.take() {
+            writer
+                .join()
+                .map_err(|_| io::Error::other("recording writer panicked"))??;
+        }
+        Ok(self.next_index)
+    }
+}
+
+/// Frames are sampled at `index / fps` seconds; when rendering falls behind, the latest
+/// frame fills every sample point it missed, so the sequence keeps real-time length.
+fn frames_due(elapsed: Duration, fps: u32, next_index: u64, max_frames: Option<u64>) -> u64 {
+    let sampled = (elapsed.as_secs_f64() * f64::from(fps)).floor() as u64 + 1;
+    let sampled = max_frames.map_or(sampled, |max_frames| sampled.min(max_frames));
+    sampled.saturating_sub(next_index)
+}
+
+fn frame_path(directory: &Path, index: u64) -> PathBuf {
+    directory.join(format!("frame_{index:06}.ppm"))
+}
+
+/// Writes the RGB channels of `capture` as a binary PPM image.
+fn write_ppm(path: &Path, capture: &FrameCapture) -> io::Result<()> {
+    let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+    write!(file, "P6\n{} {}\n255\n", capture.width, capture.height)?;
+    for pixel in capture.rgba.chunks_exact(4) {
+        file.write_all(&pixel[..3])?;
+    }
+    file.flush()
+}
+
+/// Starts writing the presented frames to `config.directory` as `frame_000000.ppm`,
+/// `frame_000001.ppm`, ... at `config.fps`, replacing a recording already in progress.
+///
+/// Turn the sequence into a video with any encoder, for example
+/// `ffmpeg -framerate 30 -i frame_%06d.ppm -pix_fmt yuv420p trailer.mp4` or
+/// `ffmpeg -framerate 30 -i frame_%06d.ppm trailer.webm`.
+///
+/// Capturing reads each sampled frame back from the GPU, which costs a few milliseconds
+/// per frame, so record at the rate the video needs rather than the display rate. Frames
+/// are sampled on the wall clock: a frame that took longer than one sample interval is
+/// repeated, keeping the video in step with what was on screen. Not supported on the web.
+pub fn start_recording(ctx: &mut Context, config: RecordingConfig) -> io::Result<()> {
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "recording is not supported on the web",
+        ));
+    }
+    let recording = Recording::start(&config)?;
+    if let Some(previous) = ctx.runtime.recording.replace(recording) {
+        previous.finish()?;
+    }
+    Ok(())
+}
+
+/// Stops the recording and waits for its frames to be written. Returns the number of
+/// frames in the sequence, or 0 if nothing was being recorded.
+pub fn stop_recording(ctx: &mut Context) -> io::Result<u64> {
+    ctx.runtime
+        .recording
+        .take()
+        .map_or(Ok(0), |recording| recording.finish())
+}
+
+/// Returns true while frames are being recorded.
+pub fn is_recording(ctx: &Context) -> bool {
+    ctx.runtime.recording.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missed_sample_points_are_filled_and_duration_caps_the_sequence() {
+        let at = Duration::from_millis;
+        assert_eq!(frames_due(at(0), 30, 0, None), 1);
+        assert_eq!(frames_due(at(10), 30, 1, None), 0);
+        assert_eq!(frames_due(at(100), 30, 1, None), 3);
+        assert_eq!(frames_due(at(5_000), 30, 58, Some(60)), 2);
+        assert_eq!(frames_due(at(5_000), 30, 60, Some(60)), 0);
+    }
+
+    #[test]
+    fn frames_are_written_as_numbered_ppm_files() {
+        let directory = std::env::temp_dir().join(format!("spot_recording_{}", std::process::id()));
+        let mut recording = Recording::start(&RecordingConfig::new(&directory)).unwrap();
+        let capture = FrameCapture {
+            width: 2,
+            height: 1,
+            rgba: vec![1, 2, 3, 255, 4, 5, 6, 255],
+        };
+        recording.push(capture, 2);
+        assert_eq!(recording.finish().unwrap(), 2);
+
+        let expected = b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06";
+        assert_eq!(std::fs::read(frame_path(&directory, 0)).unwrap(), expected);
+        assert_eq!(std::fs::read(frame_path(&directory, 1)).unwrap(), expected);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+//! A rolling buffer of recent frames that can be saved as an animated GIF.
+
+use std::collections::VecDeque;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use crate::{Context, FrameCapture};
+
+/// How much of the recent past [`start_replay_buffer`] keeps, and at what quality.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayConfig {
+    /// Length of the kept history.
+    pub length: Duration,
+    /// Frames per second kept in the history.
+    pub fps: u32,
+    /// Frames wider than this are scaled down to it, keeping their aspect ratio.
+    pub max_width: u32,
+}
+
+impl Default for ReplayConfig {
+    /// Keeps the last 10 seconds at 15 frames per second, at most 320 pixels wide.
+    fn default() -> Self {
+        Self {
+            length: Duration::from_secs(10),
+            fps: 15,
+            max_width: 320,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReplayFrame {
+    captured_at: Instant,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/// The frames captured in the last [`ReplayConfig::length`], oldest first.
+#[derive(Debug)]
+pub(crate) struct ReplayBuffer {
+    config: ReplayConfig,
+    frames: VecDeque<ReplayFrame>,
+}
+
+impl ReplayBuffer {
+    fn new(config: ReplayConfig) -> Self {
+        Self {
+            config: ReplayConfig {
+                fps: config.fps.max(1),
+                max_width: config.max_width.max(1),
+                ..config
+            },
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Returns true if a frame presented now belongs in the buffer.
+    pub(crate) fn wants_frame(&self) -> bool {
+        let interval = Duration::from_secs_f64(1.0 / f64::from(self.config.fps));
+        self.frames
+            .back()
+            .is_none_or(|frame| frame.captured_at.elapsed() >= interval)
+    }
+
+    /// Adds a scaled-down copy of `capture` and drops frames that are too old.
+    pub(crate) fn push(&mut self, capture: &FrameCapture) {
+        self.push_at(capture, Instant::now());
+    }
+
+    fn push_at(&mut self, capture: &FrameCapture, now: Instant) {
+        let (width, height, rgba) = downscale(capture, self.config.max_width);
+        // Every frame of a GIF shares its size, so a resize starts the history over.
+        if self
+            .frames
+            .back()
+            .is_some_and(|frame| (frame.width, frame.height) != (width, height))
+        {
+            self.frames.clear();
+        }
+        self.frames.push_back(ReplayFrame {
+            captured_at: now,
+            width,
+            height,
+            rgba,
+        });
+        while self
+            .frames
+            .front()
+            .is_some_and(|frame| now.duration_since(frame.captured_at) > self.config.length)
+        {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Returns the frames of the last `length`, each with how long it stays on screen.
+    fn last(&self, length: Duration) -> Vec<(ReplayFrame, Duration)> {
+        let Some(newest) = self.frames.back() else {
+            return Vec::new();
+        };
+        let frame_interval = Duration::from_secs_f64(1.0 / f64::from(self.config.fps));
+        let kept: Vec<_> = self
+            .frames
+            .iter()
+            .filter(|frame| newest.captured_at.duration_since(frame.captured_at) <= length)
+            .collect();
+        kept.iter()
+            .enumerate()
+            .map(|(index, frame)| {
+                let shown_for = kept.get(index + 1).map_or(frame_interval, |next| {
+                    next.captured_at.duration_since(frame.captured_at)
+                });
+                ((*frame).clone(), shown_for)
+            })
+            .collect()
+    }
+}
+
+/// Scales `capture` down to at most `max_width` pixels wide by nearest-neighbour sampling.
+fn downscale(capture: &FrameCapture, max_width: u32) -> (u32, u32, Vec<u8>) {
+    if capture.width <= max_width {
+        return (capture.width, capture.height, capture.rgba.clone());
+    }
+    let width = max_width;
+    let height =
+        (u64::from(capture.height) * u64::from(width) / u64::from(capture.width)).max(1) as u32;
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        let source_y = u64::from(y) * u64::from(capture.height) / u64::from(height);
+        for x in 0..width {
+            let source_x = u64::from(x) * u64::from(capture.width) / u64::from(width);
+            let offset = ((source_y * u64::from(capture.width) + source_x) * 4) as usize;
+            rgba.extend_from_slice(&capture.rgba[offset..offset + 4]);
+        }
+    }
+    (width, height, rgba)
+}
+
+fn encode_gif(
+    path: &std::path::Path,
+    frames: Vec<(ReplayFrame, Duration)>,
+) -> Result<(), gif::EncodingError> {
+    let Some((first, _)) = frames.first() else {
+        return Err(io::Error::other("the replay buffer is empty").into());
+    };
+    let width = u16::try_from(first.width).map_err(io::Error::other)?;
+    let height = u16::try_from(first.height).map_err(io::Error::other)?;
+    let file = io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = gif::Encoder::new(file, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for (mut frame, shown_for) in frames {
+        let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut frame.rgba, 10);
+        gif_frame.delay = (shown_for.as_millis() / 10).clamp(1, u128::from(u16::MAX)) as u16;
+        encoder.write_frame(&gif_frame)?;
+    }
+    Ok(())
+}
+
+/// Starts keeping the most recent presented frames so [`save_gif`] can export them,
+/// replacing the history kept so far.
+///
+/// Sampled frames are read back from the GPU, which costs a few milliseconds each;
+/// the default 15 frames per second keeps that small. Memory use is about
+/// `length × fps × max_width² × 2.25` bytes for a 16:9 window, some 35 MB by default.
+///
+/// Does nothing on the web, where reading a frame back would block the page, so
+/// [`save_gif`] fails there.
+pub fn start_replay_buffer(ctx: &mut Context, config: ReplayConfig) {
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        crate::diagnostics::warn!("replay", "the replay buffer is not supported on the web");
+        return;
+    }
+    ctx.runtime.replay = Some(ReplayBuffer::new(config));
+}
+
+/// Stops keeping recent frames and frees them.
+pub fn stop_replay_buffer(ctx: &mut Context) {
+    ctx.runtime.replay = None;
+}
+
+/// Encodes the last `length` of the replay buffer as a looping GIF at `path`.
+///
+/// Encoding takes a while, so it runs off the main thread like
+/// [`spawn_task`](crate::spawn_task) and reports the outcome to `on_saved`. Fails if
+/// [`start_replay_buffer`] was not called or no frame has been presented since.
+///
+/// ```rust,no_run
+/// # use spottedcat::{Context, Key};
+/// # use std::time::Duration;
+/// # fn update(ctx: &mut Context) {
+/// if spottedcat::key_pressed(ctx, Key::F9) {
+///     spottedcat::save_gif(ctx, "moment.gif", Duration::from_secs(5), |_ctx, result| {
+///         if let Err(error) = result {
+///             eprintln!("could not save the clip: {error}");
+///         }
+///     });
+/// }
+/// # }
+/// ```
+pub fn save_gif<C>(ctx: &mut Context, path: impl Into<PathBuf>, length: Duration, on_saved: C)
+where
+    C: FnOnce(&mut Context, io::Result<()>) + 'static,
+{
+    let path = path.into();
+    let frames = ctx
+        .runtime
+        .replay
+        .as_ref()
+        .map(|replay| replay.last(length))
+        .unwrap_or_default();
+    crate::spawn_task(
+        ctx,
+        move || {
+            encode_gif(&path, frames).map_err(|error| match error {
+                gif::EncodingError::Io(error) => error,
+                error => io::Error::other(error),
+            })
+        },
+        on_saved,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(width: u32, height: u32, shade: u8) -> FrameCapture {
+        FrameCapture {
+            width,
+            height,
+            rgba: (0..width * height)
+                .flat_map(|index| [shade, index as u8, 0, 255])
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn wide_frames_are_scaled_down_keeping_their_aspect_ratio() {
+        let (width, height, rgba) = downscale(&capture(8, 4, 0), 4);
+        assert_eq!((width, height), (4, 2));
+        let columns: Vec<u8> = rgba.chunks_exact(4).map(|pixel| pixel[1]).collect();
+        assert_eq!(columns, [0, 2, 4, 6, 16, 18, 20, 22]);
+        assert_eq!(downscale(&capture(3, 2, 0), 4).2, capture(3, 2, 0).rgba);
+    }
+
+    #[test]
+    fn old_frames_fall_out_and_saved_frames_keep_their_timing() {
+        let mut replay = ReplayBuffer::new(ReplayConfig {
+            length: Duration::from_millis(250),
+            fps: 10,
+            max_width: 16,
+        });
+        let start = Instant::now();
+        for (shade, ms) in [(1, 0), (2, 100), (3, 200), (4, 300), (5, 350)] {
+            replay.push_at(&capture(2, 2, shade), start + Duration::from_millis(ms));
+        }
+        assert_eq!(replay.frames.len(), 4);
+
+        let saved: Vec<_> = replay
+            .last(Duration::from_millis(100))
+            .into_iter()
+            .map(|(frame, shown_for)| (frame.rgba[0], shown_for.as_millis()))
+            .collect();
+        assert_eq!(saved, [(4, 50), (5, 100)]);
+
+        replay.push_at(&capture(4, 2, 6), start + Duration::from_millis(400));
+        assert_eq!(replay.frames.len(), 1, "a resize restarts the history");
+    }
+
+    #[test]
+    fn saved_clips_are_looping_gifs() {
+        let mut replay = ReplayBuffer::new(ReplayConfig::default());
+        let start = Instant::now();
+        replay.push_at(&capture(4, 4, 10), start);
+        replay.push_at(&capture(4, 4, 200), start + Duration::from_millis(70));
+        let path = std::env::temp_dir().join(format!("spot_replay_{}.gif", std::process::id()));
+        encode_gif(&path, replay.last(Duration::from_secs(1))).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(&bytes[..]).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 4));
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays, [7, 6]);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{Context, Image, Texture};
+
+/// An asset released together with the scene that owns it.
+///
+/// Assets created while a scene is active belong to it: fonts from
+/// [`register_font`](crate::register_font) and
+/// [`register_bitmap_font`](crate::register_bitmap_font), sounds from
+/// [`register_sound`](crate::register_sound) and
+/// [`load_sound_async`](crate::load_sound_async), and every image and texture. Those
+/// created in `initialize` belong to the scene being initialized. When the scene is
+/// removed by a scene switch, [`pop_scene`](crate::pop_scene) or the application exiting
+/// they are released, so switching scenes over and over does not accumulate fonts,
+/// sounds or atlas space. Assets created outside of any scene are kept.
+///
+/// Assets an async load finishes, e.g. with [`load_font_async`](crate::load_font_async),
+/// belong to the scene active when it finishes. Use [`keep_after_scene`] for assets that
+/// must outlive their scene and [`release_with_scene`] to move one to the active scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneResource {
+    /// A font ID from [`register_font`](crate::register_font) or
+    /// [`register_bitmap_font`](crate::register_bitmap_font).
+    Font(u32),
+    /// A sound ID from [`register_sound`](crate::register_sound) or
+    /// [`load_sound_async`](crate::load_sound_async).
+    Sound(u32),
+    Image(Image),
+    /// A texture and every image viewing it.
+    Texture(Texture),
+}
+
+impl From<Image> for SceneResource {
+    fn from(image: Image) -> Self {
+        Self::Image(image)
+    }
+}
+
+impl From<Texture> for SceneResource {
+    fn from(texture: Texture) -> Self {
+        Self::Texture(texture)
+    }
+}
+
+/// Ties `resource` to the active scene, releasing it when that scene is removed by a
+/// scene switch, [`pop_scene`](crate::pop_scene) or the application exiting.
+///
+/// Fonts, sounds, images and textures a scene creates are tied to it already; this is
+/// for assets created outside of any scene or by another scene, and undoes
+/// [`keep_after_scene`]. Outside of any scene it does nothing.
+pub fn release_with_scene(ctx: &mut Context, resource: impl Into<SceneResource>) {
+    let resource = ctx.owning_resource(resource.into());
+    keep_after_scene(ctx, resource);
+    ctx.track_scene_resource(resource);
+}
+
+/// Keeps `resource` after the scene that created it is removed, until it is released
+/// by hand.
+///
+/// Needed for assets that outlive their scene: a menu preloading the next level, or
+/// handles kept in a resource with [`insert_resource`](crate::insert_resource). Images
+/// cut from a kept texture or image with [`Image::sub_image`] are scene assets of their
+/// own and must be kept too.
+pub fn keep_after_scene(ctx: &mut Context, resource: impl Into<SceneResource>) {
+    let resource = ctx.owning_resource(resource.into());
+    for scope in &mut ctx.runtime.scene_scopes {
+        scope.retain(|tracked| !tracked.is_same_asset(resource));
+    }
+}
+
+impl SceneResource {
+    fn is_same_asset(self, other: SceneResource) -> bool {
+        match (self, other) {
+            (Self::Font(a), Self::Font(b)) | (Self::Sound(a), Self::Sound(b)) => a == b,
+            (Self::Image(a), Self::Image(b)) => a.id() == b.id(),
+            (Self::Texture(a), Self::Texture(b)) => a.id() == b.id(),
+            _ => false,
+        }
+    }
+}
+
+impl Context {
+    /// Opens the scope of a scene that is about to be initialized.
+    pub(crate) fn push_scene_scope(&mut self) {
+        self.runtime.scene_scopes.push(Vec::new());
+    }
+
+    /// Ties a newly created asset to the scene whose scope is innermost.
+    pub(crate) fn track_scene_resource(&mut self, resource: SceneResource) {
+        let Some(scope) = self.runtime.scene_scopes.last() else {
+            return;
+        };
+        // Drop assets destroyed by hand before the scope grows, so scenes that create
+        // and destroy assets all the time do not accumulate their handles.
+        if scope.len() == scope.capacity() {
+            let mut scope = self.runtime.scene_scopes.pop().unwrap_or_default();
+            scope.retain(|tracked| self.is_live(*tracked));
+            self.runtime.scene_scopes.push(scope);
+        }
+        if let Some(scope) = self.runtime.scene_scopes.last_mut() {
+            scope.push(resource);
+        }
+    }
+
+    /// Runs `f` without tying the assets it creates to the active scene, for assets the
+    /// engine creates lazily and keeps across scenes.
+    pub(crate) fn outside_scene_scope<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let scopes = std::mem::take(&mut self.runtime.scene_scopes);
+        let result = f(self);
+        self.runtime.scene_scopes = scopes;
+        result
+    }
+
+    /// Images that show a whole texture of their own, such as large images from
+    /// [`Image::new`], are tracked as that texture so releasing them frees it.
+    fn owning_resource(&self, resource: SceneResource) -> SceneResource {
+        let SceneResource::Image(image) = resource else {
+            return resource;
+        };
+        match self
+            .registry
+            .textures
+            .get(image.texture_id() as usize)
+            .and_then(Option::as_ref)
+        {
+            Some(entry) if entry.default_view_id == image.id() && !entry.dynamic_atlas => {
+                SceneResource::Texture(Texture {
+                    id: image.texture_id(),
+                    default_view_id: entry.default_view_id,
+                    width: entry.width,
+                    height: entry.height,
+                    pixel_width: entry.pixel_width,
+                    pixel_height: entry.pixel_height,
+                })
+            }
+            _ => resource,
+        }
+    }
+
+    fn is_live(&self, resource: SceneResource) -> bool {
+        match resource {
+            SceneResource::Font(font_id) => {
+                self.registry.fonts.contains_key(&font_id)
+                    || self.registry.bitmap_fonts.contains_key(&font_id)
+            }
+            // The audio thread owns sounds; keep them until the scope is released.
+            SceneResource::Sound(_) => true,
+            SceneResource::Image(image) => self
+                .registry
+                .images
+                .get(image.index())
+                .is_some_and(Option::is_some),
+            SceneResource::Texture(texture) => self
+                .registry
+                .textures
+                .get(texture.id() as usize)
+                .is_some_and(Option::is_some),
+        }
+    }
+
+    /// Releases everything tied to the most recently opened scene scope.
+    pub(crate) fn release_scene_scope(&mut self) {
+        if let Some(scope) = self.runtime.scene_scopes.pop() {
+            self.release_scene_resources(scope);
+        }
+    }
+
+    /// Closes the most recently opened scene scope, releasing its assets at the start of
+    /// the next frame. The frame being built may still draw them, e.g. into the capture
+    /// of a scene transition.
+    pub(crate) fn retire_scene_scope(&mut self) {
+        if let Some(scope) = self.runtime.scene_scopes.pop() {
+            self.runtime.retired_scene_scopes.push(scope);
+        }
+    }
+
+    /// Releases the scopes closed by [`retire_scene_scope`](Self::retire_scene_scope).
+    pub(crate) fn release_retired_scene_scopes(&mut self) {
+        for scope in std::mem::take(&mut self.runtime.retired_scene_scopes) {
+            self.release_scene_resources(scope);
+        }
+    }
+
+    fn release_scene_resources(&mut self, scope: Vec<SceneResource>) {
+        if scope.is_empty() {
+            return;
+        }
+        // Newest first, so images cut from a texture go before the texture itself.
+        for resource in scope.into_iter().rev() {
+            match resource {
+                SceneResource::Font(font_id) => crate::unregister_font(self, font_id),
+                SceneResource::Sound(sound_id) => crate::unregister_sound(self, sound_id),
+                SceneResource::Image(image) => {
+                    image.destroy(self);
+                }
+                SceneResource::Texture(texture) => {
+                    texture.destroy(self);
+                }
+            }
+        }
+        if let Some(atlas) = self
+            .runtime
+            .graphics
+            .as_mut()
+            .and_then(|graphics| graphics.shared_atlas.as_mut())
+        {
+            atlas.reclaim_empty_pages(&self.registry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pt;
+
+    #[test]
+    fn released_scope_frees_what_the_scene_created() {
+        let mut ctx = Context::new();
+        let outside = Image::new(&mut ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap();
+        release_with_scene(&mut ctx, outside);
+
+        ctx.push_scene_scope();
+        let font = crate::register_font(&mut ctx, vec![0; 4]);
+        let image = Image::new(&mut ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap();
+        let texture = Texture::new(&mut ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap();
+        let sub_image = Image::sub_image(
+            &mut ctx,
+            texture.view(),
+            crate::Bounds::new(Pt(0.0), Pt(0.0), Pt(1.0), Pt(1.0)),
+        )
+        .unwrap();
+        let kept = Image::new(&mut ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap();
+        keep_after_scene(&mut ctx, kept);
+        release_with_scene(&mut ctx, outside);
+
+        ctx.release_scene_scope();
+        assert!(crate::get_registered_font(&ctx, font).is_none());
+        assert!(ctx.registry.images[image.index()].is_none());
+        assert!(ctx.registry.images[sub_image.index()].is_none());
+        assert!(ctx.registry.textures[texture.id() as usize].is_none());
+        assert!(ctx.registry.images[outside.index()].is_none());
+        assert!(ctx.registry.images[kept.index()].is_some());
+    }
+
+    #[test]
+    fn engine_assets_and_destroyed_assets_leave_the_scope() {
+        let mut ctx = Context::new();
+        ctx.push_scene_scope();
+        let engine =
+            ctx.outside_scene_scope(|ctx| Image::new(ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap());
+        for _ in 0..100 {
+            let texture = Texture::new(&mut ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap();
+            texture.destroy(&mut ctx);
+        }
+        assert!(ctx.runtime.scene_scopes[0].len() < 100);
+
+        ctx.release_scene_scope();
+        assert!(ctx.registry.images[engine.index()].is_some());
+    }
+}
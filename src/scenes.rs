@@ -4,7 +4,13 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::time::Duration;
 
-pub(crate) type SceneFactory = Box<dyn Fn(&mut Context) -> Box<dyn Spot> + Send + Sync>;
+pub(crate) type SceneFactory =
+    Box<dyn Fn(&mut Context) -> anyhow::Result<Box<dyn Spot>> + Send + Sync>;
+
+/// Builds scenes of type `T` through [`Spot::try_initialize`].
+pub(crate) fn scene_factory<T: Spot + 'static>() -> SceneFactory {
+    Box::new(|ctx| T::try_initialize(ctx).map(|spot| Box::new(spot) as Box<dyn Spot>))
+}
 
 pub(crate) struct ScenePayload {
     pub(crate) type_id: TypeId,
@@ -18,24 +24,65 @@ pub(crate) struct SceneSwitchRequest {
     pub(crate) payload: Option<ScenePayload>,
 }
 
+/// An animated hand-over between two scenes, used with [`switch_scene_with_transition`].
+///
+/// The last frame of the outgoing scene is captured and animated over the incoming one,
+/// which runs normally from the first frame of the transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    /// Fades the outgoing scene to black during the first half, then the incoming scene
+    /// in from black.
+    Fade(Duration),
+    /// Slides the outgoing scene off to the left, uncovering the incoming scene.
+    SlideLeft(Duration),
+    /// Blends the outgoing scene into the incoming one.
+    CrossDissolve(Duration),
+}
+
+impl Transition {
+    pub fn duration(self) -> Duration {
+        match self {
+            Transition::Fade(duration)
+            | Transition::SlideLeft(duration)
+            | Transition::CrossDissolve(duration) => duration,
+        }
+    }
+}
+
+/// A pending change to the scene stack, applied after the current frame.
+pub(crate) enum SceneRequest {
+    /// Replaces every scene on the stack.
+    Switch {
+        request: SceneSwitchRequest,
+        transition: Option<Transition>,
+    },
+    /// Pauses the top scene and starts a new one above it.
+    Push(SceneSwitchRequest),
+    /// Removes the top scene and resumes the one below.
+    Pop,
+}
+
 thread_local! {
-    static SCENE_SWITCH_REQUEST: RefCell<Option<SceneSwitchRequest>> = const { RefCell::new(None) };
+    static SCENE_SWITCH_REQUEST: RefCell<Option<SceneRequest>> = const { RefCell::new(None) };
     static QUIT_REQUEST: RefCell<bool> = const { RefCell::new(false) };
+    static SCENE_ERROR: RefCell<Option<anyhow::Error>> = const { RefCell::new(None) };
 }
 
-fn request_scene_switch<F>(factory: F, payload: Option<ScenePayload>)
-where
-    F: Fn(&mut Context) -> Box<dyn Spot> + Send + Sync + 'static,
-{
-    SCENE_SWITCH_REQUEST.with(|request| {
-        *request.borrow_mut() = Some(SceneSwitchRequest {
-            factory: Box::new(factory),
-            payload,
-        });
-    });
+fn request_scene_change(change: SceneRequest) {
+    SCENE_SWITCH_REQUEST.with(|request| *request.borrow_mut() = Some(change));
+}
+
+fn new_scene<T: Spot + 'static, P: Any>(payload: Option<P>) -> SceneSwitchRequest {
+    SceneSwitchRequest {
+        factory: scene_factory::<T>(),
+        payload: payload.map(|payload| ScenePayload {
+            type_id: TypeId::of::<P>(),
+            value: Rc::new(payload),
+        }),
+    }
 }
 
-pub(crate) fn take_scene_switch_request() -> Option<SceneSwitchRequest> {
+pub(crate) fn take_scene_switch_request() -> Option<SceneRequest> {
     SCENE_SWITCH_REQUEST.with(|request| request.borrow_mut().take())
 }
 
@@ -48,11 +95,37 @@ pub(crate) fn take_quit_request() -> bool {
     QUIT_REQUEST.with(|request| request.replace(false))
 }
 
+/// Records a scene that failed to initialize and quits the application, which then
+/// returns the first such error from [`run`](crate::run).
+pub(crate) fn fail_scene(error: anyhow::Error) {
+    crate::diagnostics::error!("scene", "failed to initialize scene: {error:#}");
+    SCENE_ERROR.with(|slot| {
+        slot.borrow_mut().get_or_insert(error);
+    });
+    quit();
+}
+
+/// `initialize` for scenes that override [`Spot::try_initialize`].
+pub(crate) fn initialize_or_panic<T: Spot>(ctx: &mut Context) -> T {
+    match T::try_initialize(ctx) {
+        Ok(spot) => spot,
+        Err(error) => panic!("failed to initialize scene: {error:#}"),
+    }
+}
+
+pub(crate) fn take_scene_error() -> Option<anyhow::Error> {
+    SCENE_ERROR.with(|slot| slot.borrow_mut().take())
+}
+
 /// Switches to a new scene of type `T`.
 ///
-/// The current scene will be removed and the new scene will be initialized.
+/// The current scene, and any scenes paused below it by [`push_scene`], will be removed
+/// and the new scene will be initialized.
 pub fn switch_scene<T: Spot + 'static>() {
-    request_scene_switch(|ctx| Box::new(T::initialize(ctx)), None);
+    request_scene_change(SceneRequest::Switch {
+        request: new_scene::<T, ()>(None),
+        transition: None,
+    });
 }
 
 /// Switches to a new scene of type `T` and passes a payload.
@@ -60,13 +133,39 @@ pub fn switch_scene<T: Spot + 'static>() {
 /// The payload can be retrieved in the new scene's `initialize` method
 /// using `ctx.take_resource::<P>()`.
 pub fn switch_scene_with<T: Spot + 'static, P: Any>(payload: P) {
-    request_scene_switch(
-        |ctx| Box::new(T::initialize(ctx)),
-        Some(ScenePayload {
-            type_id: TypeId::of::<P>(),
-            value: Rc::new(payload),
-        }),
-    );
+    request_scene_change(SceneRequest::Switch {
+        request: new_scene::<T, P>(Some(payload)),
+        transition: None,
+    });
+}
+
+/// Switches to a new scene of type `T`, animating from the current scene with `transition`.
+pub fn switch_scene_with_transition<T: Spot + 'static>(transition: Transition) {
+    request_scene_change(SceneRequest::Switch {
+        request: new_scene::<T, ()>(None),
+        transition: Some(transition),
+    });
+}
+
+/// Starts a scene of type `T` on top of the current one, e.g. a pause menu or a dialog.
+///
+/// The current scene stays alive but paused: it is not updated and receives no user
+/// events until [`pop_scene`] removes the scene above it. It is still drawn underneath
+/// unless the new scene's [`Spot::draws_scenes_below`] returns false.
+pub fn push_scene<T: Spot + 'static>() {
+    request_scene_change(SceneRequest::Push(new_scene::<T, ()>(None)));
+}
+
+/// Like [`push_scene`], passing a payload the same way as [`switch_scene_with`].
+pub fn push_scene_with<T: Spot + 'static, P: Any>(payload: P) {
+    request_scene_change(SceneRequest::Push(new_scene::<T, P>(Some(payload))));
+}
+
+/// Removes the top scene and resumes the scene paused below it by [`push_scene`].
+///
+/// Ignored when there is no scene below.
+pub fn pop_scene() {
+    request_scene_change(SceneRequest::Pop);
 }
 
 /// The core trait for defining application logic and rendering.
@@ -94,6 +193,34 @@ pub trait Spot {
     where
         Self: Sized;
 
+    /// Initializes the scene, reporting setup that cannot complete, such as a missing
+    /// asset, as an error.
+    ///
+    /// The engine creates every scene through this method; the default wraps
+    /// [`initialize`](Spot::initialize). An error stops the application, and
+    /// [`run`](crate::run) returns it as [`SpotError::Scene`](crate::SpotError::Scene).
+    /// Scenes that override it can implement `initialize` by unwrapping it.
+    fn try_initialize(ctx: &mut Context) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::initialize(ctx))
+    }
+
+    /// Prepares the scene before `initialize` when it is started through a
+    /// [`Loading`](crate::Loading) wrapper, which shows a loading scene meanwhile.
+    ///
+    /// Called before every update until it returns a complete [`Progress`](crate::Progress).
+    /// Start background work here, e.g. with [`load_sound_async`](crate::load_sound_async)
+    /// or [`spawn_future`](crate::spawn_future), and keep its state in resources so
+    /// `initialize` can pick up the results. Scenes started without the wrapper skip it.
+    fn preload(_ctx: &mut Context) -> crate::Progress
+    where
+        Self: Sized,
+    {
+        crate::Progress::DONE
+    }
+
     /// Called every frame to draw the scene.
     ///
     /// The `screen` image represents the primary backbuffer/window. All draw commands
@@ -106,12 +233,87 @@ pub trait Spot {
     /// * `dt` - The time elapsed since the last frame.
     fn update(&mut self, _ctx: &mut Context, _dt: Duration) {}
 
+    /// Called instead of [`update`](Self::update) when the window runs with
+    /// [`deterministic`](crate::WindowConfig::deterministic) set.
+    ///
+    /// `tick` counts fixed updates from `0`; derive time from it and the configured
+    /// `update_hz` so every run of the same inputs produces the same state. See
+    /// [`input_frame`](crate::input_frame) and [`rng`](crate::rng).
+    fn update_tick(&mut self, _ctx: &mut Context, _tick: u64) {}
+
+    /// Called before drawing when the window's logical size has changed.
+    ///
+    /// `w` and `h` match [`window_size`](crate::window_size). Scenes see the current size
+    /// in `initialize`, so this is not called for the size a scene starts with.
+    fn resized(&mut self, _ctx: &mut Context, _w: crate::Pt, _h: crate::Pt) {}
+
+    /// Called before drawing when the window's scale factor (DPI) has changed.
+    ///
+    /// Not called when [`WindowConfig::scale_factor`](crate::WindowConfig::scale_factor)
+    /// forces a fixed value. A size change caused by the new scale factor is reported
+    /// through [`Spot::resized`] right after.
+    fn scale_factor_changed(&mut self, _ctx: &mut Context, _scale_factor: f64) {}
+
+    /// Called before drawing when the operating system switches between light and dark
+    /// appearance. The current value is available through [`system_theme`](crate::system_theme).
+    fn theme_changed(&mut self, _ctx: &mut Context, _theme: crate::Theme) {}
+
+    /// Called before drawing when the window switches between portrait and landscape,
+    /// e.g. when a phone is rotated. Runs before [`resized`](Spot::resized) for the same
+    /// change.
+    fn orientation_changed(&mut self, _ctx: &mut Context, _orientation: crate::Orientation) {}
+
+    /// Called before drawing when the on-screen keyboard is shown, hidden or resized, with
+    /// the height it covers at the bottom of the window or `None` once hidden.
+    ///
+    /// Move text fields above it so the player can see what they type.
+    fn virtual_keyboard_changed(&mut self, _ctx: &mut Context, _height: Option<crate::Pt>) {}
+
+    /// Called on the main thread for each value sent through an
+    /// [`EventSender`](crate::EventSender), before the next `update`.
+    fn user_event(&mut self, _ctx: &mut Context, _event: crate::UserEvent) {}
+
+    /// Whether scenes paused below this one by [`push_scene`] are drawn before it.
+    ///
+    /// Defaults to true so overlays such as pause menus show the game behind them. Return
+    /// false from scenes that cover the whole screen to skip drawing what is hidden.
+    fn draws_scenes_below(&self) -> bool {
+        true
+    }
+
+    /// Called with every raw winit window event before the engine handles it.
+    ///
+    /// An escape hatch for winit features the input API does not cover yet; prefer the
+    /// input functions where they exist. Only the active scene receives events.
+    #[cfg(not(target_os = "android"))]
+    fn on_event(&mut self, _ctx: &mut Context, _event: &winit::event::WindowEvent) {}
+
+    /// Called on Android when the back button is pressed or the back gesture completes.
+    ///
+    /// Return `true` to consume it, e.g. to close a menu or pop a scene. Returning `false`
+    /// lets the system handle it, which finishes the activity. Not called on other
+    /// platforms.
+    fn back_requested(&mut self, _ctx: &mut Context) -> bool {
+        false
+    }
+
     /// Called when the application is resumed (e.g., from background).
     fn resumed(&mut self, _ctx: &mut Context) {}
 
     /// Called when the application is suspended (e.g., to background).
     fn suspended(&mut self, _ctx: &mut Context) {}
 
+    /// Called once when the application is about to exit, whether through [`quit`], the
+    /// window being closed or the platform destroying the app, while the context is
+    /// still fully available.
+    ///
+    /// Every scene on the stack receives it, the active one first, before they are
+    /// removed. Flush saves to disk here.
+    fn on_exit(&mut self, _ctx: &mut Context) {}
+
     /// Called when the scene is being removed or the application is quitting.
+    ///
+    /// Runs on scene switches, on [`pop_scene`], and after [`on_exit`](Spot::on_exit)
+    /// when the application exits.
     fn remove(&mut self, _ctx: &mut Context) {}
 }
@@ -0,0 +1,380 @@
+//! Gameplay scripts in [rhai](https://rhai.rs), reloaded when their file changes.
+//!
+//! The host binds images, sounds and scenes by name and forwards its callbacks; the
+//! script defines whichever of `init()`, `update(dt)` and `draw()` it needs. Script
+//! state lives on `this`, which survives reloads while the functions are replaced:
+//!
+//! ```rhai
+//! fn init() { this.x = 100.0; }
+//! fn update(dt) {
+//!     if key_down("ArrowRight") { this.x += 120.0 * dt; }
+//!     if key_pressed("Escape") { switch_scene("menu"); }
+//! }
+//! fn draw() { draw("player", this.x, 200.0); }
+//! ```
+//!
+//! Scripts can call `draw(image, x, y)` and `draw(image, x, y, rotation, scale)`,
+//! `key_down(name)`, `key_pressed(name)`, `key_released(name)` with [`Key`] variant
+//! names, `mouse_down(button)` with `0` for left, `1` right and `2` middle,
+//! `cursor_x()`, `cursor_y()`, `window_width()`, `window_height()`,
+//! `play_sound(name)`, `switch_scene(name)` and `print(value)`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+pub use rhai;
+use rhai::{AST, CallFnOptions, Dynamic, Engine, FuncArgs, Scope};
+
+use crate::{Context, DrawOption, Image, InputManager, Key, MouseButton, Pt, Spot};
+
+/// Side effects requested by the script, applied to the context after each call.
+enum Command {
+    Draw { image: Image, options: DrawOption },
+    PlaySound(u32),
+    SwitchScene(fn()),
+}
+
+/// What the script's registered functions can see and request during a call.
+#[derive(Default)]
+struct Host {
+    input: InputManager,
+    window_size: (Pt, Pt),
+    images: HashMap<String, Image>,
+    sounds: HashMap<String, u32>,
+    scenes: HashMap<String, fn()>,
+    commands: Vec<Command>,
+}
+
+/// A compiled rhai script together with its engine, bindings and state.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    state: Dynamic,
+    host: Rc<RefCell<Host>>,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    initialized: bool,
+}
+
+impl std::fmt::Debug for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Script")
+            .field("path", &self.path)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Script {
+    /// Compiles a script from source.
+    pub fn from_source(source: &str) -> anyhow::Result<Self> {
+        let host = Rc::new(RefCell::new(Host::default()));
+        let engine = new_engine(&host);
+        let ast = engine
+            .compile(source)
+            .map_err(|e| anyhow::anyhow!("script failed to compile: {e}"))?;
+        Ok(Self {
+            engine,
+            ast,
+            state: Dynamic::from_map(rhai::Map::new()),
+            host,
+            path: None,
+            modified: None,
+            initialized: false,
+        })
+    }
+
+    /// Loads and compiles a script file, recompiling it whenever the file changes.
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let source = String::from_utf8(crate::load_asset(path)?)?;
+        let mut script = Self::from_source(&source)?;
+        script.modified = modified_time(path);
+        script.path = Some(PathBuf::from(path));
+        Ok(script)
+    }
+
+    /// Makes `image` drawable from the script as `name`.
+    pub fn bind_image(&mut self, name: impl Into<String>, image: Image) -> &mut Self {
+        self.host.borrow_mut().images.insert(name.into(), image);
+        self
+    }
+
+    /// Makes a registered sound playable from the script as `name`.
+    pub fn bind_sound(&mut self, name: impl Into<String>, sound_id: u32) -> &mut Self {
+        self.host.borrow_mut().sounds.insert(name.into(), sound_id);
+        self
+    }
+
+    /// Lets the script switch to the scene `T` with `switch_scene(name)`.
+    pub fn bind_scene<T: Spot + 'static>(&mut self, name: impl Into<String>) -> &mut Self {
+        self.host
+            .borrow_mut()
+            .scenes
+            .insert(name.into(), crate::switch_scene::<T>);
+        self
+    }
+
+    /// The script's `this` object.
+    pub fn state(&self) -> &Dynamic {
+        &self.state
+    }
+
+    /// Replaces the script's functions with `source`, keeping its state.
+    ///
+    /// On a compile error the previous functions stay in place.
+    pub fn set_source(&mut self, source: &str) -> anyhow::Result<()> {
+        self.ast = self
+            .engine
+            .compile(source)
+            .map_err(|e| anyhow::anyhow!("script failed to compile: {e}"))?;
+        Ok(())
+    }
+
+    /// Recompiles the script if its file changed since it was last read. Returns true
+    /// when the new source was swapped in.
+    pub fn reload_if_changed(&mut self) -> bool {
+        let Some(path) = self.path.as_ref().and_then(|path| path.to_str()) else {
+            return false;
+        };
+        let modified = modified_time(path);
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+
+        let result = crate::load_asset(path)
+            .and_then(|bytes| Ok(String::from_utf8(bytes)?))
+            .and_then(|source| self.set_source(&source));
+        match result {
+            Ok(()) => {
+                crate::diagnostics::info!("script", "Reloaded {}", path);
+                true
+            }
+            Err(e) => {
+                crate::diagnostics::warn!("script", "Failed to reload {}: {}", path, e);
+                false
+            }
+        }
+    }
+
+    /// Reloads a changed file, then runs the script's `init()` once and its
+    /// `update(dt)` on every call. Call this from [`Spot::update`].
+    pub fn update(&mut self, ctx: &mut Context, dt: Duration) -> anyhow::Result<()> {
+        self.reload_if_changed();
+        if !self.initialized {
+            self.initialized = true;
+            self.call(ctx, None, "init", ())?;
+        }
+        self.call(ctx, None, "update", (dt.as_secs_f64(),))?;
+        Ok(())
+    }
+
+    /// Runs the script's `draw()`, drawing into `target`. Call this from [`Spot::draw`].
+    pub fn draw(&mut self, ctx: &mut Context, target: Image) -> anyhow::Result<()> {
+        self.call(ctx, Some(target), "draw", ())?;
+        Ok(())
+    }
+
+    /// Calls the script function `name` if it is defined and returns its result, or
+    /// `None` if it is not. Draws go to `target`, or the screen when `None`.
+    pub fn call(
+        &mut self,
+        ctx: &mut Context,
+        target: Option<Image>,
+        name: &str,
+        args: impl FuncArgs,
+    ) -> anyhow::Result<Option<Dynamic>> {
+        let mut arg_values: Vec<Dynamic> = Vec::new();
+        args.parse(&mut arg_values);
+        let defined = self
+            .ast
+            .iter_functions()
+            .any(|f| f.name == name && f.params.len() == arg_values.len());
+        if !defined {
+            return Ok(None);
+        }
+
+        {
+            let mut host = self.host.borrow_mut();
+            host.input = ctx.input().clone();
+            host.window_size = ctx.window_logical_size();
+        }
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &self.ast,
+            name,
+            arg_values,
+        );
+
+        let commands = std::mem::take(&mut self.host.borrow_mut().commands);
+        let target = target.unwrap_or_else(|| crate::window::make_screen_target(ctx));
+        for command in commands {
+            match command {
+                Command::Draw { image, options } => target.draw(ctx, &image, options),
+                Command::PlaySound(sound_id) => {
+                    crate::play_sound_simple(ctx, sound_id);
+                }
+                Command::SwitchScene(switch) => switch(),
+            }
+        }
+        result
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("script function `{name}` failed: {e}"))
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn new_engine(host: &Rc<RefCell<Host>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.on_print(|text| crate::diagnostics::info!("script", "{}", text));
+    engine.on_debug(|text, _, _| crate::diagnostics::debug!("script", "{}", text));
+
+    let h = host.clone();
+    engine.register_fn("draw", move |name: &str, x: f64, y: f64| {
+        queue_draw(
+            &h,
+            name,
+            DrawOption::new([Pt(x as f32), Pt(y as f32)], 0.0, [1.0, 1.0]),
+        );
+    });
+    let h = host.clone();
+    engine.register_fn(
+        "draw",
+        move |name: &str, x: f64, y: f64, rotation: f64, scale: f64| {
+            let options = DrawOption::new(
+                [Pt(x as f32), Pt(y as f32)],
+                rotation as f32,
+                [scale as f32, scale as f32],
+            );
+            queue_draw(&h, name, options);
+        },
+    );
+
+    let h = host.clone();
+    engine.register_fn("key_down", move |name: &str| {
+        Key::from_name(name).is_some_and(|key| h.borrow().input.key_down(key))
+    });
+    let h = host.clone();
+    engine.register_fn("key_pressed", move |name: &str| {
+        Key::from_name(name).is_some_and(|key| h.borrow().input.key_pressed(key))
+    });
+    let h = host.clone();
+    engine.register_fn("key_released", move |name: &str| {
+        Key::from_name(name).is_some_and(|key| h.borrow().input.key_released(key))
+    });
+    let h = host.clone();
+    engine.register_fn("mouse_down", move |button: i64| {
+        let button = match button {
+            0 => MouseButton::Left,
+            1 => MouseButton::Right,
+            2 => MouseButton::Middle,
+            other => MouseButton::Other(other.clamp(0, u16::MAX as i64) as u16),
+        };
+        h.borrow().input.mouse_down(button)
+    });
+    let h = host.clone();
+    engine.register_fn("cursor_x", move || {
+        h.borrow()
+            .input
+            .cursor_position()
+            .map_or(0.0, |(x, _)| x.as_f32() as f64)
+    });
+    let h = host.clone();
+    engine.register_fn("cursor_y", move || {
+        h.borrow()
+            .input
+            .cursor_position()
+            .map_or(0.0, |(_, y)| y.as_f32() as f64)
+    });
+    let h = host.clone();
+    engine.register_fn("window_width", move || {
+        h.borrow().window_size.0.as_f32() as f64
+    });
+    let h = host.clone();
+    engine.register_fn("window_height", move || {
+        h.borrow().window_size.1.as_f32() as f64
+    });
+
+    let h = host.clone();
+    engine.register_fn("play_sound", move |name: &str| {
+        let mut host = h.borrow_mut();
+        match host.sounds.get(name).copied() {
+            Some(sound_id) => host.commands.push(Command::PlaySound(sound_id)),
+            None => crate::diagnostics::warn!("script", "No sound bound as `{}`", name),
+        }
+    });
+    let h = host.clone();
+    engine.register_fn("switch_scene", move |name: &str| {
+        let mut host = h.borrow_mut();
+        match host.scenes.get(name).copied() {
+            Some(switch) => host.commands.push(Command::SwitchScene(switch)),
+            None => crate::diagnostics::warn!("script", "No scene bound as `{}`", name),
+        }
+    });
+    engine
+}
+
+fn queue_draw(host: &Rc<RefCell<Host>>, name: &str, options: DrawOption) {
+    let mut host = host.borrow_mut();
+    match host.images.get(name).copied() {
+        Some(image) => host.commands.push(Command::Draw { image, options }),
+        None => crate::diagnostics::warn!("script", "No image bound as `{}`", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+        fn init() { this.x = 10.0; this.frames = 0; }
+        fn update(dt) { this.x += 100.0 * dt; this.frames += 1; }
+        fn draw() { draw("player", this.x, 5.0); draw("missing", 0.0, 0.0); }
+    "#;
+
+    #[test]
+    fn script_keeps_state_and_queues_draws() {
+        let mut ctx = Context::new();
+        let image = Image::new(&mut ctx, Pt(2.0), Pt(2.0), &[255; 16]).unwrap();
+        let mut script = Script::from_source(SOURCE).unwrap();
+        script.bind_image("player", image);
+
+        script.update(&mut ctx, Duration::from_millis(500)).unwrap();
+        script.update(&mut ctx, Duration::from_millis(500)).unwrap();
+        let state = script.state().clone().cast::<rhai::Map>();
+        assert_eq!(state["x"].as_float().unwrap(), 110.0);
+        assert_eq!(state["frames"].as_int().unwrap(), 2);
+
+        let screen = crate::window::make_screen_target(&ctx);
+        script.draw(&mut ctx, screen).unwrap();
+        assert_eq!(ctx.runtime.draw_list.len(), 1, "unbound images are skipped");
+    }
+
+    #[test]
+    fn new_source_keeps_state_and_bad_source_keeps_functions() {
+        let mut ctx = Context::new();
+        let mut script = Script::from_source(SOURCE).unwrap();
+        script.update(&mut ctx, Duration::from_secs(1)).unwrap();
+
+        script
+            .set_source("fn update(dt) { this.x -= 1.0; }")
+            .unwrap();
+        script.update(&mut ctx, Duration::from_secs(1)).unwrap();
+        assert!(script.set_source("fn update(dt) {").is_err());
+        script.update(&mut ctx, Duration::from_secs(1)).unwrap();
+
+        let state = script.state().clone().cast::<rhai::Map>();
+        assert_eq!(state["x"].as_float().unwrap(), 108.0);
+        assert_eq!(state["frames"].as_int().unwrap(), 1);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{Bounds, Context, DrawOption, Image, MouseButton, Pt, Texture};
+use std::time::Duration;
+
+/// A clipped area that scrolls over larger content, e.g. a level list, an inventory or a
+/// chat log.
+///
+/// The view scrolls with the mouse wheel over it and by dragging with the mouse or a
+/// finger, keeps gliding after a fling and stops at the content edges. Scrollbars show
+/// on axes where the content is larger than the view.
+///
+/// Like a [`Child`](crate::Child), the content is drawn into an offscreen target the size
+/// of the view, so anything outside it is clipped. Create it once with the content size,
+/// e.g. `ScrollView::new([pt(200.0), pt(2000.0)])` for the list below, then call
+/// [`update`](Self::update) every update and [`draw`](Self::draw) with a closure that
+/// draws the content shifted by the offset it is given:
+///
+/// ```rust,no_run
+/// # use spottedcat::{Bounds, Context, DrawOption, Image, ScrollView, pt};
+/// # fn draw(ctx: &mut Context, screen: Image, list: &mut ScrollView, icon: Image) {
+/// let area = Bounds::new(pt(20.0), pt(20.0), pt(200.0), pt(300.0));
+/// list.draw(ctx, screen, area, |ctx, target, [x, y]| {
+///     for row in 0..50 {
+///         let position = [pt(8.0) - x, pt(row as f32 * 40.0) - y];
+///         target.draw(ctx, &icon, DrawOption::default().with_position(position));
+///     }
+/// });
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ScrollView {
+    pub content_size: [Pt; 2],
+    /// Distance scrolled per unit the wheel reports.
+    pub wheel_step: Pt,
+    /// How quickly a fling slows down, as a rate per second.
+    pub deceleration: f32,
+    pub scrollbar_width: Pt,
+    pub scrollbar_color: [f32; 4],
+    offset: [f32; 2],
+    velocity: [f32; 2],
+    /// Last pointer position while dragging.
+    drag: Option<[f32; 2]>,
+    touching: bool,
+    area: Option<Bounds>,
+    target: Option<Texture>,
+}
+
+impl ScrollView {
+    /// Creates a view over content of `content_size`, scrolled to its top-left corner.
+    pub fn new(content_size: [Pt; 2]) -> Self {
+        Self {
+            content_size,
+            wheel_step: Pt(40.0),
+            deceleration: 4.0,
+            scrollbar_width: Pt(4.0),
+            scrollbar_color: [1.0, 1.0, 1.0, 0.5],
+            offset: [0.0, 0.0],
+            velocity: [0.0, 0.0],
+            drag: None,
+            touching: false,
+            area: None,
+            target: None,
+        }
+    }
+
+    pub fn with_wheel_step(mut self, wheel_step: Pt) -> Self {
+        self.wheel_step = wheel_step;
+        self
+    }
+
+    pub fn with_deceleration(mut self, deceleration: f32) -> Self {
+        self.deceleration = deceleration;
+        self
+    }
+
+    pub fn with_scrollbar(mut self, width: Pt, color: [f32; 4]) -> Self {
+        self.scrollbar_width = width;
+        self.scrollbar_color = color;
+        self
+    }
+
+    /// How far the content is scrolled, from its top-left corner.
+    pub fn offset(&self) -> [Pt; 2] {
+        [Pt(self.offset[0]), Pt(self.offset[1])]
+    }
+
+    /// Scrolls to `offset` at once, stopping any fling; it is kept within the content.
+    pub fn set_offset(&mut self, offset: [Pt; 2]) {
+        self.offset = [offset[0].as_f32(), offset[1].as_f32()];
+        self.velocity = [0.0, 0.0];
+        self.clamp();
+    }
+
+    /// The largest offset, where the content's bottom-right corner is in view.
+    pub fn max_offset(&self) -> [Pt; 2] {
+        let max = self.max_offset_f32();
+        [Pt(max[0]), Pt(max[1])]
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// The area this view was last drawn in, in the parent's coordinates.
+    pub fn area(&self) -> Option<Bounds> {
+        self.area
+    }
+
+    /// Maps a point in the parent's coordinates, such as the mouse position, to content
+    /// coordinates. Returns `None` when the point lies outside the view.
+    pub fn content_point(&self, x: Pt, y: Pt) -> Option<[Pt; 2]> {
+        let area = self.area?;
+        if !contains(area, [x.as_f32(), y.as_f32()]) {
+            return None;
+        }
+        Some([
+            x - area.x + Pt(self.offset[0]),
+            y - area.y + Pt(self.offset[1]),
+        ])
+    }
+
+    /// Applies wheel and drag input and advances a fling. Views that have not been drawn
+    /// yet ignore input.
+    pub fn update(&mut self, ctx: &Context, dt: Duration) {
+        let Some(area) = self.area else {
+            return;
+        };
+        let input = ctx.input();
+        let dt = dt.as_secs_f32();
+        // The first touch acts as the pointer, like the mouse.
+        let touch = input.touches().first().map(|touch| touch.position);
+        let was_touching = std::mem::replace(&mut self.touching, touch.is_some());
+        let pointer = touch
+            .or_else(|| input.cursor_position())
+            .map(|(x, y)| [x.as_f32(), y.as_f32()]);
+        let pressed = input.mouse_pressed(MouseButton::Left) || (self.touching && !was_touching);
+        let held = input.mouse_down(MouseButton::Left) || self.touching;
+
+        if pressed && pointer.is_some_and(|pointer| contains(area, pointer)) {
+            self.drag = pointer;
+            self.velocity = [0.0, 0.0];
+        }
+        match (self.drag, pointer) {
+            (Some(last), Some(pointer)) if held => {
+                for axis in 0..2 {
+                    let moved = pointer[axis] - last[axis];
+                    self.offset[axis] -= moved;
+                    if dt > 0.0 {
+                        self.velocity[axis] = -moved / dt;
+                    }
+                }
+                self.drag = Some(pointer);
+            }
+            _ => {
+                self.drag = None;
+                let decay = (-self.deceleration * dt).exp();
+                for axis in 0..2 {
+                    self.offset[axis] += self.velocity[axis] * dt;
+                    self.velocity[axis] *= decay;
+                    if self.velocity[axis].abs() < 1.0 {
+                        self.velocity[axis] = 0.0;
+                    }
+                }
+            }
+        }
+
+        let (wheel_x, wheel_y) = input.scroll_delta();
+        if (wheel_x != 0.0 || wheel_y != 0.0)
+            && pointer.is_some_and(|pointer| contains(area, pointer))
+        {
+            let step = self.wheel_step.as_f32();
+            self.offset[0] -= wheel_x * step;
+            self.offset[1] -= wheel_y * step;
+            self.velocity = [0.0, 0.0];
+        }
+        self.clamp();
+    }
+
+    /// Draws the view into `area` of `target`: `content` draws into the clipped view,
+    /// shifted by the offset it receives, and scrollbars are drawn on top. While a fling
+    /// glides, another frame is requested.
+    pub fn draw(
+        &mut self,
+        ctx: &mut Context,
+        target: Image,
+        area: Bounds,
+        content: impl FnOnce(&mut Context, Image, [Pt; 2]),
+    ) {
+        if area.width.as_f32() <= 0.0 || area.height.as_f32() <= 0.0 {
+            return;
+        }
+        self.area = Some(area);
+        self.clamp();
+        // Keep a fling gliding in reactive redraw mode.
+        if self.velocity != [0.0, 0.0] {
+            ctx.request_redraw();
+        }
+        let view =
+            Texture::sized_render_target(ctx, &mut self.target, area.width, area.height).view();
+        content(ctx, view, self.offset());
+        for thumb in self.scrollbar_thumbs(area).into_iter().flatten() {
+            let options = DrawOption::new(
+                [thumb.x, thumb.y],
+                0.0,
+                [thumb.width.as_f32(), thumb.height.as_f32()],
+            )
+            .with_tint(self.scrollbar_color);
+            let white = ctx.white_image();
+            view.draw(ctx, &white, options);
+        }
+        target.draw(
+            ctx,
+            &view,
+            DrawOption::default().with_position([area.x, area.y]),
+        );
+    }
+
+    fn max_offset_f32(&self) -> [f32; 2] {
+        let Some(area) = self.area else {
+            return [0.0, 0.0];
+        };
+        [
+            (self.content_size[0] - area.width).as_f32().max(0.0),
+            (self.content_size[1] - area.height).as_f32().max(0.0),
+        ]
+    }
+
+    fn clamp(&mut self) {
+        let max = self.max_offset_f32();
+        let axes = self.offset.iter_mut().zip(&mut self.velocity).zip(max);
+        for ((offset, velocity), max) in axes {
+            let clamped = offset.clamp(0.0, max);
+            if clamped != *offset {
+                *offset = clamped;
+                *velocity = 0.0;
+            }
+        }
+    }
+
+    /// Horizontal and vertical scrollbar thumbs, in view coordinates.
+    fn scrollbar_thumbs(&self, area: Bounds) -> [Option<Bounds>; 2] {
+        let view = [area.width.as_f32(), area.height.as_f32()];
+        let content = [self.content_size[0].as_f32(), self.content_size[1].as_f32()];
+        let width = self.scrollbar_width.as_f32();
+        let mut thumbs = [None, None];
+        for axis in 0..2 {
+            if content[axis] <= view[axis] {
+                continue;
+            }
+            let length = (view[axis] * view[axis] / content[axis]).max(width * 2.0);
+            let travel = view[axis] - length;
+            let max = content[axis] - view[axis];
+            let start = travel * self.offset[axis] / max;
+            let across = view[1 - axis] - width;
+            thumbs[axis] = Some(if axis == 0 {
+                Bounds::new(Pt(start), Pt(across), Pt(length), Pt(width))
+            } else {
+                Bounds::new(Pt(across), Pt(start), Pt(width), Pt(length))
+            });
+        }
+        thumbs
+    }
+
+    /// Destroys the offscreen target, freeing its GPU memory. Drawing the view again
+    /// creates a new one.
+    pub fn release(&mut self, ctx: &mut Context) {
+        if let Some(target) = self.target.take() {
+            target.destroy(ctx);
+        }
+    }
+}
+
+fn contains(area: Bounds, point: [f32; 2]) -> bool {
+    let x = point[0] - area.x.as_f32();
+    let y = point[1] - area.y.as_f32();
+    x >= 0.0 && y >= 0.0 && x < area.width.as_f32() && y < area.height.as_f32()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::event::{ElementState, MouseButton as WinitButton, MouseScrollDelta};
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    fn view() -> ScrollView {
+        let mut view = ScrollView::new([Pt(100.0), Pt(1000.0)]);
+        view.area = Some(Bounds::new(Pt(10.0), Pt(10.0), Pt(100.0), Pt(200.0)));
+        view
+    }
+
+    fn step(ctx: &mut Context, view: &mut ScrollView) {
+        view.update(ctx, FRAME);
+        ctx.input_mut().end_frame();
+    }
+
+    #[test]
+    fn the_wheel_scrolls_within_the_content() {
+        let mut ctx = Context::new();
+        let mut view = view();
+        ctx.input_mut().handle_cursor_moved(Pt(50.0), Pt(50.0));
+
+        ctx.input_mut()
+            .handle_mouse_wheel(MouseScrollDelta::LineDelta(0.0, -2.0));
+        step(&mut ctx, &mut view);
+        assert_eq!(view.offset(), [Pt(0.0), Pt(80.0)]);
+        assert_eq!(
+            view.content_point(Pt(20.0), Pt(20.0)),
+            Some([Pt(10.0), Pt(90.0)])
+        );
+
+        ctx.input_mut()
+            .handle_mouse_wheel(MouseScrollDelta::LineDelta(0.0, -100.0));
+        step(&mut ctx, &mut view);
+        assert_eq!(view.offset(), view.max_offset());
+        assert_eq!(view.max_offset(), [Pt(0.0), Pt(800.0)]);
+
+        // The wheel is ignored outside the view.
+        ctx.input_mut().handle_cursor_moved(Pt(500.0), Pt(50.0));
+        ctx.input_mut()
+            .handle_mouse_wheel(MouseScrollDelta::LineDelta(0.0, 5.0));
+        step(&mut ctx, &mut view);
+        assert_eq!(view.offset(), [Pt(0.0), Pt(800.0)]);
+    }
+
+    #[test]
+    fn flings_keep_gliding_and_slow_down() {
+        let mut ctx = Context::new();
+        let mut view = view();
+        ctx.input_mut().handle_cursor_moved(Pt(50.0), Pt(150.0));
+        ctx.input_mut()
+            .handle_mouse_input(ElementState::Pressed, WinitButton::Left);
+        step(&mut ctx, &mut view);
+        assert!(view.is_dragging());
+
+        ctx.input_mut().handle_cursor_moved(Pt(50.0), Pt(130.0));
+        step(&mut ctx, &mut view);
+        assert_eq!(view.offset()[1], Pt(20.0));
+
+        ctx.input_mut()
+            .handle_mouse_input(ElementState::Released, WinitButton::Left);
+        step(&mut ctx, &mut view);
+        assert!(!view.is_dragging());
+        let after_release = view.offset()[1];
+        assert_eq!(after_release, Pt(40.0));
+
+        step(&mut ctx, &mut view);
+        let glided = (view.offset()[1] - after_release).as_f32();
+        assert!(glided > 0.0 && glided < 20.0);
+    }
+
+    #[test]
+    fn scrollbars_show_for_overflowing_axes() {
+        let mut ctx = Context::new();
+        let mut view = view();
+        let area = view.area.unwrap();
+        assert_eq!(
+            view.scrollbar_thumbs(area),
+            [
+                None,
+                Some(Bounds::new(Pt(96.0), Pt(0.0), Pt(4.0), Pt(40.0)))
+            ]
+        );
+        view.set_offset([Pt(0.0), Pt(400.0)]);
+        assert_eq!(
+            view.scrollbar_thumbs(area)[1],
+            Some(Bounds::new(Pt(96.0), Pt(80.0), Pt(4.0), Pt(40.0)))
+        );
+    }
+}
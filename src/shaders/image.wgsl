@@ -29,6 +29,7 @@ struct VsIn {
     @location(2) size: vec2<f32>,
     @location(3) uv_rect: vec4<f32>,
     @location(4) repeat: vec4<f32>,
+    @location(5) color: vec4<f32>,
 };
 
 struct VsOut {
@@ -38,6 +39,7 @@ struct VsOut {
     @location(2) uv_scale: vec2<f32>,
     @location(3) uv_origin: vec2<f32>,
     @location(4) repeat: vec4<f32>,
+    @location(5) color: vec4<f32>,
 };
 
 
@@ -96,6 +98,7 @@ fn vs_main(in: VsIn) -> VsOut {
     out.uv_scale = in.uv_rect.zw;
     out.uv_origin = in.uv_rect.xy;
     out.repeat = in.repeat;
+    out.color = in.color;
 
     return out;
 }
@@ -115,6 +118,6 @@ fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
     let src_uv = in.uv_origin + src_local_uv * in.uv_scale;
     let c = textureSample(tex, samp, src_uv);
     let opacity = _sp_internal.opacity * _sp_internal.shader_opacity;
-    var color = vec4<f32>(c.rgb, c.a * opacity * repeat_mask);
+    var color = vec4<f32>(c.rgb * in.color.rgb, c.a * in.color.a * opacity * repeat_mask);
     return color;
 }
@@ -0,0 +1,433 @@
+//! A CPU rasterizer for image and text draws, used by [`run_software`](crate::run_software)
+//! so tests that draw can run on machines without a GPU.
+//!
+//! Quads are sampled with nearest filtering and blended on the stored sRGB values, so
+//! edges and semi-transparent pixels can differ slightly from the GPU. Custom image
+//! shaders, repeat modes and 3D models are not drawn; images with a custom shader are
+//! drawn as plain images.
+
+use std::collections::{HashMap, HashSet};
+
+use ab_glyph::{Font as _, FontArc, PxScale, ScaleFont as _};
+
+use crate::drawable::{DrawCommand, ImageCommand, TextCommand};
+use crate::graphics::errors::{DrawError, report_draw};
+use crate::{Context, DrawOption, FrameCapture, Pt};
+
+/// An RGBA8 pixel buffer, top row first.
+struct Canvas {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, clear: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            rgba: clear.repeat((width * height) as usize),
+        }
+    }
+
+    fn blend(&mut self, x: u32, y: u32, color: [f32; 4]) {
+        let offset = ((y * self.width + x) * 4) as usize;
+        let pixel = &mut self.rgba[offset..offset + 4];
+        let alpha = color[3].clamp(0.0, 1.0);
+        for channel in 0..3 {
+            let dst = pixel[channel] as f32 / 255.0;
+            let src = color[channel].clamp(0.0, 1.0);
+            pixel[channel] = ((src * alpha + dst * (1.0 - alpha)) * 255.0).round() as u8;
+        }
+        let dst = pixel[3] as f32 / 255.0;
+        pixel[3] = ((alpha + dst * (1.0 - alpha)) * 255.0).round() as u8;
+    }
+}
+
+/// The `[x, y, width, height]` pixel rectangle of an RGBA8 image `stride` pixels wide
+/// that a quad samples.
+struct Source<'a> {
+    rgba: &'a [u8],
+    stride: u32,
+    rect: [u32; 4],
+}
+
+impl Source<'_> {
+    fn texel(&self, u: f32, v: f32) -> [f32; 4] {
+        let [x, y, width, height] = self.rect;
+        let sx = x + ((u * width as f32) as u32).min(width.saturating_sub(1));
+        let sy = y + ((v * height as f32) as u32).min(height.saturating_sub(1));
+        let offset = ((sy * self.stride + sx) * 4) as usize;
+        match self.rgba.get(offset..offset + 4) {
+            Some(p) => [p[0], p[1], p[2], p[3]].map(|c| c as f32 / 255.0),
+            None => [0.0; 4],
+        }
+    }
+}
+
+/// Where and how a quad lands, in logical units like [`DrawOption`].
+struct Quad {
+    position: [f32; 2],
+    size: [f32; 2],
+    rotation: f32,
+    tint: [f32; 4],
+    opacity: f32,
+}
+
+impl Quad {
+    fn new(options: &DrawOption, position: [f32; 2], size: [f32; 2], tint: [f32; 4]) -> Self {
+        Self {
+            position,
+            size,
+            rotation: options.rotation(),
+            tint,
+            opacity: options.opacity(),
+        }
+    }
+}
+
+/// Renders the queued draws of the frame on the CPU and returns the screen.
+pub(crate) fn render(ctx: &mut Context, transparent: bool) -> FrameCapture {
+    let draws = std::mem::take(&mut ctx.runtime.draw_list);
+    let scale_factor = ctx.scale_factor() as f32;
+    let (width, height) = ctx.window_logical_size();
+    let clear = if transparent { [0; 4] } else { [0, 0, 0, 255] };
+    let mut screen = Canvas::new(
+        (width * scale_factor).to_u32_clamped().max(1),
+        (height * scale_factor).to_u32_clamped().max(1),
+        clear,
+    );
+
+    let mut renderer = Renderer {
+        ctx: &*ctx,
+        draws: &draws,
+        scale_factor,
+        targets: HashMap::new(),
+        rendering: HashSet::new(),
+        errors: Vec::new(),
+    };
+    renderer.render_into(0, &mut screen);
+    let errors = std::mem::take(&mut renderer.errors);
+    for error in errors {
+        report_draw(ctx, error);
+    }
+
+    FrameCapture {
+        width: screen.width,
+        height: screen.height,
+        rgba: screen.rgba,
+    }
+}
+
+struct Renderer<'a> {
+    ctx: &'a Context,
+    draws: &'a [DrawCommand],
+    scale_factor: f32,
+    /// Render targets drawn so far this frame, by texture id.
+    targets: HashMap<u32, Canvas>,
+    /// Targets being drawn, so a target that draws itself is skipped.
+    rendering: HashSet<u32>,
+    errors: Vec<DrawError>,
+}
+
+impl Renderer<'_> {
+    fn render_into(&mut self, target_texture_id: u32, canvas: &mut Canvas) {
+        for command in self.draws {
+            match command {
+                DrawCommand::Image(cmd) if cmd.target_texture_id == target_texture_id => {
+                    self.draw_image(cmd, canvas);
+                }
+                DrawCommand::Text(cmd) if cmd.target_texture_id == target_texture_id => {
+                    self.draw_text(cmd, canvas);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Draws the render target `texture_id` the first time it is sampled.
+    fn ensure_target(&mut self, texture_id: u32) {
+        if self.targets.contains_key(&texture_id) || !self.rendering.insert(texture_id) {
+            return;
+        }
+        let Some(texture) = self
+            .ctx
+            .registry
+            .textures
+            .get(texture_id as usize)
+            .and_then(Option::as_ref)
+        else {
+            return;
+        };
+        let mut canvas = Canvas::new(texture.pixel_width, texture.pixel_height, [0; 4]);
+        self.render_into(texture_id, &mut canvas);
+        self.rendering.remove(&texture_id);
+        self.targets.insert(texture_id, canvas);
+    }
+
+    fn draw_image(&mut self, cmd: &ImageCommand, canvas: &mut Canvas) {
+        let ctx = self.ctx;
+        let Some(entry) = ctx
+            .registry
+            .images
+            .get(cmd.id as usize)
+            .and_then(Option::as_ref)
+        else {
+            return;
+        };
+        let Some(texture) = ctx
+            .registry
+            .textures
+            .get(entry.texture_id as usize)
+            .and_then(Option::as_ref)
+        else {
+            self.errors.push(DrawError::MissingTexture {
+                image_id: cmd.id,
+                texture_id: entry.texture_id,
+            });
+            return;
+        };
+        if !entry.visible {
+            return;
+        }
+        if texture.is_render_target() {
+            self.ensure_target(entry.texture_id);
+        }
+        let rgba: &[u8] = match (&texture.raw_data, self.targets.get(&entry.texture_id)) {
+            (_, Some(target)) => &target.rgba,
+            (Some(raw), None) => &raw[..],
+            (None, None) => return,
+        };
+        let bounds = entry.pixel_bounds;
+        let source = Source {
+            rgba,
+            stride: texture.pixel_width,
+            rect: [bounds.x, bounds.y, bounds.width, bounds.height],
+        };
+        let [sx, sy] = cmd.opts.scale();
+        let size = [
+            entry.bounds.width.as_f32() * sx,
+            entry.bounds.height.as_f32() * sy,
+        ];
+        let position = cmd.opts.position().map(Pt::as_f32);
+        let quad = Quad::new(&cmd.opts, position, size, cmd.opts.tint());
+        draw_quad(canvas, &source, &quad, self.scale_factor);
+    }
+
+    fn draw_text(&mut self, cmd: &TextCommand, canvas: &mut Canvas) {
+        let ctx = self.ctx;
+        let text = &cmd.text;
+        let origin = cmd.opts.position().map(Pt::as_f32);
+        let image_scale = cmd.opts.scale();
+
+        if let Some(font) = ctx.registry.bitmap_fonts.get(&text.font_id) {
+            let layout = font.layout(text, &ctx.registry, image_scale);
+            for glyph in layout.glyphs {
+                let Some(entry) = ctx
+                    .registry
+                    .images
+                    .get(glyph.image_id as usize)
+                    .and_then(Option::as_ref)
+                else {
+                    continue;
+                };
+                let Some(texture) = ctx
+                    .registry
+                    .textures
+                    .get(entry.texture_id as usize)
+                    .and_then(Option::as_ref)
+                else {
+                    continue;
+                };
+                let Some(rgba) = texture.raw_data.as_ref() else {
+                    continue;
+                };
+                let bounds = entry.pixel_bounds;
+                let source = Source {
+                    rgba: &rgba[..],
+                    stride: texture.pixel_width,
+                    rect: [bounds.x, bounds.y, bounds.width, bounds.height],
+                };
+                let position = [
+                    origin[0] + glyph.instance.pos[0],
+                    origin[1] + glyph.instance.pos[1],
+                ];
+                let quad = Quad::new(&cmd.opts, position, glyph.instance.size, text.color);
+                draw_quad(canvas, &source, &quad, self.scale_factor);
+            }
+            return;
+        }
+
+        let font_id = text.font_id;
+        let Some(data) = ctx.registry.fonts.get(&font_id) else {
+            self.errors.push(DrawError::MissingFont { font_id });
+            return;
+        };
+        let font = match FontArc::try_from_vec(data.clone()) {
+            Ok(font) => font,
+            Err(e) => {
+                let message = e.to_string();
+                self.errors
+                    .push(DrawError::InvalidFont { font_id, message });
+                return;
+            }
+        };
+
+        // Same layout as the GPU text path, in physical pixels.
+        let scale_factor = ctx.scale_factor() as f32;
+        let px_size = (text.font_size.as_f32() * scale_factor).max(1.0);
+        let scaled = font.as_scaled(PxScale::from(px_size));
+        let lines = if text.max_width.is_some() {
+            text.get_wrapped_lines(&scaled)
+        } else {
+            vec![text.content.to_string()]
+        };
+        let line_height = scaled.ascent() - scaled.descent() + scaled.line_gap();
+        let mut glyphs = Vec::new();
+        let mut min_y = scaled.ascent();
+        for (index, line) in lines.iter().enumerate() {
+            let mut caret = 0.0;
+            let mut previous = None;
+            for ch in line.chars() {
+                let glyph_id = scaled.glyph_id(ch);
+                if let Some(previous) = previous {
+                    caret += scaled.kern(previous, glyph_id);
+                }
+                previous = Some(glyph_id);
+                if let Some(bitmap) =
+                    crate::graphics::font::rasterize_glyph(&font, px_size, glyph_id.0.into())
+                {
+                    min_y = min_y.min(bitmap.offset[1]);
+                    let position = [
+                        caret + bitmap.offset[0],
+                        index as f32 * line_height + bitmap.offset[1],
+                    ];
+                    caret += bitmap.advance;
+                    glyphs.push((position, bitmap));
+                } else {
+                    caret += scaled.h_advance(glyph_id);
+                }
+            }
+        }
+
+        for (position, bitmap) in &glyphs {
+            let source = Source {
+                rgba: &bitmap.rgba,
+                stride: bitmap.width,
+                rect: [0, 0, bitmap.width, bitmap.height],
+            };
+            let position = [
+                origin[0] + position[0] / scale_factor * image_scale[0],
+                origin[1] + (position[1] - min_y) / scale_factor * image_scale[1],
+            ];
+            let size = [
+                bitmap.width as f32 / scale_factor * image_scale[0],
+                bitmap.height as f32 / scale_factor * image_scale[1],
+            ];
+            let quad = Quad::new(&cmd.opts, position, size, text.color);
+            draw_quad(canvas, &source, &quad, self.scale_factor);
+        }
+    }
+}
+
+/// Fills the pixels whose centers fall inside `quad`, rotated about its position like
+/// the GPU's image quads.
+fn draw_quad(canvas: &mut Canvas, source: &Source<'_>, quad: &Quad, scale_factor: f32) {
+    let [x0, y0] = quad.position.map(|v| v * scale_factor);
+    let [w, h] = quad.size.map(|v| v * scale_factor);
+    if w == 0.0 || h == 0.0 || canvas.width == 0 || canvas.height == 0 {
+        return;
+    }
+    let (sin, cos) = quad.rotation.sin_cos();
+    let corners = [[0.0, 0.0], [w, 0.0], [w, h], [0.0, h]]
+        .map(|[x, y]| [x0 + x * cos - y * sin, y0 + x * sin + y * cos]);
+    let min = |axis: usize| {
+        corners
+            .iter()
+            .map(|c| c[axis])
+            .fold(f32::INFINITY, f32::min)
+    };
+    let max = |axis: usize| {
+        corners
+            .iter()
+            .map(|c| c[axis])
+            .fold(f32::NEG_INFINITY, f32::max)
+    };
+    let left = min(0).floor().max(0.0) as u32;
+    let top = min(1).floor().max(0.0) as u32;
+    let right = (max(0).ceil().max(0.0) as u32).min(canvas.width);
+    let bottom = (max(1).ceil().max(0.0) as u32).min(canvas.height);
+
+    for y in top..bottom {
+        for x in left..right {
+            let dx = x as f32 + 0.5 - x0;
+            let dy = y as f32 + 0.5 - y0;
+            let u = (dx * cos + dy * sin) / w;
+            let v = (dy * cos - dx * sin) / h;
+            if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+                continue;
+            }
+            let texel = source.texel(u, v);
+            let tint = quad.tint;
+            canvas.blend(
+                x,
+                y,
+                [
+                    texel[0] * tint[0],
+                    texel[1] * tint[1],
+                    texel[2] * tint[2],
+                    texel[3] * tint[3] * quad.opacity,
+                ],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Image, Text};
+
+    #[test]
+    fn images_land_where_they_are_drawn_and_blend_over_the_clear_color() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(8, 8, 2.0);
+        let screen = crate::window::make_screen_target(&ctx);
+        let red = Image::new(&mut ctx, Pt(1.0), Pt(1.0), &[255, 0, 0, 255]).unwrap();
+        screen.draw(
+            &mut ctx,
+            &red,
+            DrawOption::default()
+                .with_position([Pt(1.0), Pt(1.0)])
+                .with_scale([2.0, 1.0])
+                .with_opacity(0.5),
+        );
+
+        let frame = render(&mut ctx, false);
+        assert_eq!((frame.width, frame.height), (8, 8));
+        assert_eq!(frame.pixel(0, 0), Some([0, 0, 0, 255]));
+        assert_eq!(frame.pixel(2, 2), Some([128, 0, 0, 255]));
+        assert_eq!(frame.pixel(5, 3), Some([128, 0, 0, 255]));
+        assert_eq!(
+            frame.pixel(6, 2),
+            Some([0, 0, 0, 255]),
+            "2 x 1 points at 2x"
+        );
+        assert!(ctx.runtime.draw_list.is_empty());
+    }
+
+    #[test]
+    fn text_with_a_missing_font_is_skipped_and_reported() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(4, 4, 1.0);
+        let screen = crate::window::make_screen_target(&ctx);
+        screen.draw(&mut ctx, &Text::new("hi", 42), DrawOption::default());
+
+        let frame = render(&mut ctx, true);
+        assert!(frame.rgba.iter().all(|&c| c == 0));
+        assert_eq!(
+            ctx.take_errors(),
+            vec![DrawError::MissingFont { font_id: 42 }]
+        );
+    }
+}
@@ -1,12 +1,32 @@
+//! The public audio API: free functions over the mixer in [`crate::audio`].
+
 use crate::Context;
+use crate::Pt;
 use crate::audio;
+pub use crate::audio::{
+    AudioBus, AudioDevice, AudioEffect, AudioListener, AudioTime, Ducking, SoundStatus,
+};
 use std::time::Duration;
 
 /// Configuration options for playing a sound.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 #[derive(Debug, Clone)]
 pub struct SoundOptions {
     /// Volume multiplier (0.0 to 1.0).
     pub volume: f32,
+    /// Playback rate multiplier. Values above 1.0 play faster and higher pitched,
+    /// values below 1.0 slower and lower. Non-positive values fall back to 1.0.
+    pub speed: f32,
+    /// The bus whose volume, mute and fades apply to this sound.
+    pub bus: AudioBus,
+    /// Maximum number of instances of this sound playing at once. Playing one more
+    /// fades out the quietest, then oldest, instance. `None` leaves only the global
+    /// limit set with [`set_max_voices`].
+    pub max_voices: Option<usize>,
     /// Duration to fade in the sound from zero volume.
     pub fade_in: Duration,
     /// Optional duration to fade out the sound when stopped.
@@ -19,6 +39,9 @@ impl Default for SoundOptions {
     fn default() -> Self {
         Self {
             volume: 1.0,
+            speed: 1.0,
+            bus: AudioBus::Sfx,
+            max_voices: None,
             fade_in: Duration::ZERO,
             fade_out: None,
             start_paused: false,
@@ -30,21 +53,103 @@ impl Default for SoundOptions {
 ///
 /// Returns a unique sound ID if successful.
 pub fn register_sound(ctx: &mut Context, bytes: Vec<u8>) -> Option<u32> {
-    ctx.with_audio(|a| a.register_sound(bytes))
+    let sound_id = ctx.with_audio(|a| a.register_sound(bytes))?;
+    ctx.track_scene_resource(crate::SceneResource::Sound(sound_id));
+    Some(sound_id)
+}
+
+/// Loads and decodes a sound file in the background so startup is not blocked.
+///
+/// Returns the sound ID right away; playing it does nothing until [`sound_status`]
+/// reports [`SoundStatus::Ready`]. Native platforms read `path` like
+/// [`load_asset`](crate::load_asset) on a background thread, the web fetches it over HTTP.
+pub fn load_sound_async(ctx: &mut Context, path: impl Into<String>) -> Option<u32> {
+    let path = path.into();
+    let sound_id = ctx.with_audio(|a| a.load_sound_async(path))?;
+    ctx.track_scene_resource(crate::SceneResource::Sound(sound_id));
+    Some(sound_id)
+}
+
+/// Returns whether a sound is still loading, ready to play, or failed to load.
+///
+/// Returns `None` for unknown or unregistered sound IDs.
+pub fn sound_status(ctx: &Context, sound_id: u32) -> Option<SoundStatus> {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .and_then(|a| a.sound_status(sound_id))
 }
 
 /// Plays a registered sound with the specified options.
 ///
 /// Returns a unique play ID that can be used to control the playing sound.
 pub fn play_sound(ctx: &mut Context, sound_id: u32, options: SoundOptions) -> Option<u64> {
+    let opts = play_options(options);
+    ctx.with_audio(|a| a.play_registered_sound_with_options(sound_id, opts))
+        .flatten()
+}
+
+/// Plays a registered sound at a world position.
+///
+/// The sound is attenuated by its distance to the [`AudioListener`] and panned by its
+/// horizontal offset, updating as either moves. Move it with [`set_sound_position`].
+pub fn play_sound_at(
+    ctx: &mut Context,
+    sound_id: u32,
+    x: Pt,
+    y: Pt,
+    options: SoundOptions,
+) -> Option<u64> {
+    let opts = audio::PlayOptions {
+        position: Some((x.0, y.0)),
+        ..play_options(options)
+    };
+    ctx.with_audio(|a| a.play_registered_sound_with_options(sound_id, opts))
+        .flatten()
+}
+
+/// Plays a registered sound starting exactly at `at` on the mixer clock.
+///
+/// Schedule hits ahead of time from [`audio_time`], e.g. one beat ahead for a metronome;
+/// times that have already passed start right away. Until it starts, the sound counts as
+/// playing and can be stopped or paused through its play ID.
+pub fn play_sound_at_time(
+    ctx: &mut Context,
+    sound_id: u32,
+    at: AudioTime,
+    options: SoundOptions,
+) -> Option<u64> {
     let opts = audio::PlayOptions {
+        start_at: Some(at),
+        ..play_options(options)
+    };
+    ctx.with_audio(|a| a.play_registered_sound_with_options(sound_id, opts))
+        .flatten()
+}
+
+/// Returns the current mixer clock: the time of audio sent to the output device so far.
+///
+/// It advances one output buffer at a time, so scheduling with [`play_sound_at_time`]
+/// needs at least a buffer of lead time to be sample-accurate.
+pub fn audio_time(ctx: &Context) -> AudioTime {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .map_or(AudioTime::ZERO, |a| a.audio_time())
+}
+
+fn play_options(options: SoundOptions) -> audio::PlayOptions {
+    audio::PlayOptions {
         volume: options.volume,
+        speed: options.speed,
+        bus: options.bus,
+        position: None,
+        max_voices: options.max_voices,
+        start_at: None,
         fade_in: options.fade_in,
         fade_out: options.fade_out,
         start_paused: options.start_paused,
-    };
-    ctx.with_audio(|a| a.play_registered_sound_with_options(sound_id, opts))
-        .flatten()
+    }
 }
 
 /// A convenience function to play a registered sound with default options.
@@ -90,6 +195,115 @@ pub fn set_sound_volume(ctx: &mut Context, play_id: u64, volume: f32) {
     ctx.with_audio(|a| a.set_volume_play_id(play_id, volume));
 }
 
+/// Changes the playback rate (and pitch) of a playing sound. Non-positive values are ignored.
+pub fn set_sound_speed(ctx: &mut Context, play_id: u64, speed: f32) {
+    ctx.with_audio(|a| a.set_speed_play_id(play_id, speed));
+}
+
+/// Moves the playhead of a playing or paused sound to `position` from its start.
+///
+/// Seeking past the end stops the sound.
+pub fn seek_sound(ctx: &mut Context, play_id: u64, position: Duration) {
+    ctx.with_audio(|a| a.seek_play_id(play_id, position));
+}
+
+/// Returns how far into the sound the playhead is, or `None` once it has stopped.
+///
+/// The position advances by whole mixer buffers, so expect steps of a few milliseconds.
+pub fn playback_position(ctx: &Context, play_id: u64) -> Option<Duration> {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .and_then(|a| a.playback_position(play_id))
+}
+
+/// Moves a sound to a world position, making it positional if it was not.
+pub fn set_sound_position(ctx: &mut Context, play_id: u64, x: Pt, y: Pt) {
+    ctx.with_audio(|a| a.set_position_play_id(play_id, x.0, y.0));
+}
+
+/// Sets where positional sounds are heard from, typically the player or camera center.
+pub fn set_audio_listener(ctx: &mut Context, listener: AudioListener) {
+    ctx.with_audio(|a| a.set_listener(listener));
+}
+
+/// Returns the current audio listener.
+pub fn audio_listener(ctx: &Context) -> AudioListener {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .map(|a| a.listener())
+        .unwrap_or_default()
+}
+
+/// Sets the volume of a bus, cancelling any fade in progress.
+///
+/// [`AudioBus::Master`] scales every sound, so it suits a global volume slider.
+pub fn set_bus_volume(ctx: &mut Context, bus: AudioBus, volume: f32) {
+    ctx.with_audio(|a| a.set_bus_volume(bus, volume));
+}
+
+/// Smoothly changes the volume of a bus to `volume` over `duration`.
+pub fn fade_bus(ctx: &mut Context, bus: AudioBus, volume: f32, duration: Duration) {
+    ctx.with_audio(|a| a.fade_bus(bus, volume, duration));
+}
+
+/// Mutes or unmutes a bus without losing its volume.
+pub fn set_bus_muted(ctx: &mut Context, bus: AudioBus, muted: bool) {
+    ctx.with_audio(|a| a.set_bus_muted(bus, muted));
+}
+
+/// Returns the volume of a bus, or the target volume while it is fading.
+///
+/// Returns 1.0 when audio is unavailable.
+pub fn bus_volume(ctx: &Context, bus: AudioBus) -> f32 {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .map(|a| a.bus_volume(bus))
+        .unwrap_or(1.0)
+}
+
+/// Replaces the effect chain of a bus, e.g. a low-pass on [`AudioBus::Sfx`] while the
+/// player is underwater. Pass an empty slice to remove all effects.
+///
+/// Effects that stay in the same slot with the same kind keep their state, so their
+/// parameters can be changed every frame without clicks.
+pub fn set_bus_effects(ctx: &mut Context, bus: AudioBus, effects: &[AudioEffect]) {
+    ctx.with_audio(|a| a.set_bus_effects(bus, effects));
+}
+
+/// Returns the effect chain of a bus.
+pub fn bus_effects(ctx: &Context, bus: AudioBus) -> Vec<AudioEffect> {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .map(|a| a.bus_effects(bus))
+        .unwrap_or_default()
+}
+
+/// Returns true if the bus is muted.
+pub fn is_bus_muted(ctx: &Context, bus: AudioBus) -> bool {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .map(|a| a.is_bus_muted(bus))
+        .unwrap_or(false)
+}
+
+/// Makes `bus` dip while sounds play on another bus, or stops ducking it with `None`.
+///
+/// For dialog-heavy games, duck [`AudioBus::Music`] with [`Ducking::default`], which
+/// triggers on [`AudioBus::Voice`]. Ducking stacks with the bus volume and fades.
+pub fn set_bus_ducking(ctx: &mut Context, bus: AudioBus, ducking: Option<Ducking>) {
+    ctx.with_audio(|a| a.set_bus_ducking(bus, ducking));
+}
+
+/// Returns the ducking settings of a bus.
+pub fn bus_ducking(ctx: &Context, bus: AudioBus) -> Option<Ducking> {
+    ctx.runtime.audio.as_ref().and_then(|a| a.bus_ducking(bus))
+}
+
 /// Returns true if the sound associated with the play ID is still active.
 pub fn is_sound_playing(ctx: &Context, play_id: u64) -> bool {
     ctx.runtime
@@ -99,11 +313,100 @@ pub fn is_sound_playing(ctx: &Context, play_id: u64) -> bool {
         .unwrap_or(false)
 }
 
+/// Calls `callback` on the main thread, before the next `update`, once the sound has
+/// played to the end or was stopped.
+///
+/// Paused sounds have not finished. If audio is unavailable or the play ID is unknown,
+/// the callback runs on the next frame.
+pub fn on_sound_finished(
+    ctx: &mut Context,
+    play_id: u64,
+    callback: impl FnOnce(&mut Context) + 'static,
+) {
+    ctx.with_audio(|a| a.watch_play_id(play_id));
+    let mut callback = Some(callback);
+    crate::tasks::poll_until(ctx, move |ctx| {
+        let active = ctx
+            .runtime
+            .audio
+            .as_ref()
+            .is_some_and(|a| a.is_active_play_id(play_id));
+        if active {
+            return false;
+        }
+        if let Some(callback) = callback.take() {
+            callback(ctx);
+        }
+        true
+    });
+}
+
 /// Unregisters a sound and frees its resources.
 pub fn unregister_sound(ctx: &mut Context, sound_id: u32) {
     ctx.with_audio(|a| a.unregister_sound(sound_id));
 }
 
+/// Sets how many sounds may play at once; beyond it, the quietest, then oldest, voice is
+/// faded out to make room. Paused sounds do not count. Defaults to 64.
+pub fn set_max_voices(ctx: &mut Context, max_voices: usize) {
+    ctx.with_audio(|a| a.set_max_voices(max_voices));
+}
+
+/// Returns the global voice limit set with [`set_max_voices`].
+pub fn max_voices(ctx: &Context) -> usize {
+    ctx.runtime
+        .audio
+        .as_ref()
+        .map_or(audio::DEFAULT_MAX_VOICES, |a| a.max_voices())
+}
+
+/// Returns how many sounds are playing right now, not counting paused ones.
+pub fn active_voices(ctx: &Context) -> usize {
+    ctx.runtime.audio.as_ref().map_or(0, |a| a.active_voices())
+}
+
+/// Adds a callback that generates audio, mixed on `bus` alongside regular sounds.
+///
+/// `source` runs on the audio thread and must fill the whole buffer with mono samples at
+/// the given output sample rate, one sample per output frame. Keep it fast and avoid
+/// blocking, since it delays every other sound. Suited to procedural synths, tracker
+/// playback or streamed voice.
+///
+/// Returns an ID for [`unregister_audio_source`].
+pub fn register_audio_source(
+    ctx: &mut Context,
+    bus: AudioBus,
+    source: impl FnMut(&mut [f32], u32) + Send + 'static,
+) -> Option<u64> {
+    ctx.with_audio(|a| a.add_source(bus, Box::new(source)))
+        .flatten()
+}
+
+/// Removes a callback added with [`register_audio_source`].
+pub fn unregister_audio_source(ctx: &mut Context, source_id: u64) {
+    ctx.with_audio(|a| a.remove_source(source_id));
+}
+
+/// Lists the audio output devices available on this system.
+pub fn audio_output_devices(_ctx: &Context) -> Vec<AudioDevice> {
+    audio::output_devices()
+}
+
+/// Returns the device audio is currently playing on.
+pub fn audio_output_device(ctx: &Context) -> Option<AudioDevice> {
+    ctx.runtime.audio.as_ref().and_then(|a| a.output_device())
+}
+
+/// Switches audio output to the device with the given [`AudioDevice::id`], or back to
+/// following the system default with `None`.
+///
+/// Playing sounds continue on the new device. If a chosen device is later unplugged,
+/// output falls back to the default device and returns once it is available again.
+pub fn set_audio_output_device(ctx: &mut Context, id: Option<&str>) -> anyhow::Result<()> {
+    ctx.with_audio(|a| a.set_output_device(id))
+        .unwrap_or_else(|| Err(anyhow::anyhow!("audio is not available")))
+}
+
 /// A debug function to play a simple sine wave at the specified frequency.
 pub fn play_sine(ctx: &mut Context, freq: f32, volume: f32) -> Option<u64> {
     ctx.with_audio(|a| a.play_sine(freq, volume)).flatten()
@@ -126,4 +429,24 @@ mod tests {
 
         assert_eq!(play_sound_simple(&mut ctx, 42), None);
     }
+
+    #[test]
+    fn selecting_an_output_device_fails_when_audio_is_unavailable() {
+        let mut ctx = Context::new();
+
+        assert!(set_audio_output_device(&mut ctx, None).is_err());
+        assert_eq!(audio_output_device(&ctx), None);
+    }
+
+    #[test]
+    fn finished_callback_runs_on_next_poll_without_audio() {
+        let mut ctx = Context::new();
+        let finished = std::rc::Rc::new(std::cell::Cell::new(false));
+        let seen = finished.clone();
+        on_sound_finished(&mut ctx, 7, move |_ctx| seen.set(true));
+
+        assert!(!finished.get());
+        crate::tasks::poll(&mut ctx);
+        assert!(finished.get());
+    }
 }
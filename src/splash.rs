@@ -31,8 +31,8 @@ const LOGO_BOTTOM_INSET: usize = 10;
 ///     fn draw(&mut self, _ctx: &mut spottedcat::Context, _screen: spottedcat::Image) {}
 /// }
 ///
-/// fn main() {
-///     run::<OneShotSplash<Game>>(WindowConfig::default());
+/// fn main() -> Result<(), spottedcat::SpotError> {
+///     run::<OneShotSplash<Game>>(WindowConfig::default())
 /// }
 /// ```
 pub struct OneShotSplash<TNext: Spot + 'static> {
@@ -60,15 +60,19 @@ struct BrandedSplash<TNext: Spot + 'static> {
 
 impl<TNext: Spot + 'static> Spot for OneShotSplash<TNext> {
     fn initialize(ctx: &mut Context) -> Self {
+        crate::scenes::initialize_or_panic(ctx)
+    }
+
+    fn try_initialize(ctx: &mut Context) -> anyhow::Result<Self> {
         let already_shown = crate::get_resource::<OneShotSplashSeen<TNext>>(ctx).is_some();
         let inner = if already_shown {
-            OneShotSplashInner::Next(TNext::initialize(ctx))
+            OneShotSplashInner::Next(TNext::try_initialize(ctx)?)
         } else {
             crate::insert_resource(ctx, Rc::new(OneShotSplashSeen::<TNext>(PhantomData)));
             OneShotSplashInner::Splash(BrandedSplash::new())
         };
 
-        Self { inner }
+        Ok(Self { inner })
     }
 
     fn update(&mut self, ctx: &mut Context, dt: Duration) {
@@ -85,6 +89,44 @@ impl<TNext: Spot + 'static> Spot for OneShotSplash<TNext> {
         }
     }
 
+    fn resized(&mut self, ctx: &mut Context, w: Pt, h: Pt) {
+        if let OneShotSplashInner::Next(next) = &mut self.inner {
+            next.resized(ctx, w, h);
+        }
+    }
+
+    fn scale_factor_changed(&mut self, ctx: &mut Context, scale_factor: f64) {
+        if let OneShotSplashInner::Next(next) = &mut self.inner {
+            next.scale_factor_changed(ctx, scale_factor);
+        }
+    }
+
+    fn theme_changed(&mut self, ctx: &mut Context, theme: crate::Theme) {
+        if let OneShotSplashInner::Next(next) = &mut self.inner {
+            next.theme_changed(ctx, theme);
+        }
+    }
+
+    fn user_event(&mut self, ctx: &mut Context, event: crate::UserEvent) {
+        if let OneShotSplashInner::Next(next) = &mut self.inner {
+            next.user_event(ctx, event);
+        }
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn on_event(&mut self, ctx: &mut Context, event: &winit::event::WindowEvent) {
+        if let OneShotSplashInner::Next(next) = &mut self.inner {
+            next.on_event(ctx, event);
+        }
+    }
+
+    fn draws_scenes_below(&self) -> bool {
+        match &self.inner {
+            OneShotSplashInner::Splash(_) => true,
+            OneShotSplashInner::Next(next) => next.draws_scenes_below(),
+        }
+    }
+
     fn resumed(&mut self, ctx: &mut Context) {
         if let OneShotSplashInner::Next(next) = &mut self.inner {
             next.resumed(ctx);
@@ -97,6 +139,12 @@ impl<TNext: Spot + 'static> Spot for OneShotSplash<TNext> {
         }
     }
 
+    fn on_exit(&mut self, ctx: &mut Context) {
+        if let OneShotSplashInner::Next(next) = &mut self.inner {
+            next.on_exit(ctx);
+        }
+    }
+
     fn remove(&mut self, ctx: &mut Context) {
         if let OneShotSplashInner::Next(next) = &mut self.inner {
             next.remove(ctx);
@@ -0,0 +1,239 @@
+use crate::{Animation, Context, DrawOption, Image, Pt, Transform};
+use std::time::Duration;
+
+/// An image together with everything needed to draw it: placement, layer, flip, tint and
+/// an optional animation.
+///
+/// Game objects keep a sprite instead of rebuilding [`DrawOption`]s every frame, and
+/// [`draw_sprites`] draws many of them in layer order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    pub image: Image,
+    /// Where the anchor is drawn, relative to the target's top-left corner.
+    pub position: [Pt; 2],
+    /// Point of the image placed at `position` and rotated around, from `[0.0, 0.0]`
+    /// (top-left) to `[1.0, 1.0]` (bottom-right).
+    pub anchor: [f32; 2],
+    /// Rotation in radians.
+    pub rotation: f32,
+    pub scale: [f32; 2],
+    /// Sprites on higher layers are drawn on top.
+    pub layer: i32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Multiplied with the image's colors; white leaves them unchanged.
+    pub color: [f32; 4],
+    /// Replaces `image` with its current frame while present.
+    pub animation: Option<Animation>,
+}
+
+impl Sprite {
+    pub fn new(image: Image) -> Self {
+        Self {
+            image,
+            position: [Pt(0.0), Pt(0.0)],
+            anchor: [0.0, 0.0],
+            rotation: 0.0,
+            scale: [1.0, 1.0],
+            layer: 0,
+            flip_x: false,
+            flip_y: false,
+            color: [1.0, 1.0, 1.0, 1.0],
+            animation: None,
+        }
+    }
+
+    pub fn with_position(mut self, position: [Pt; 2]) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_anchor(mut self, anchor: [f32; 2]) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: [f32; 2]) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: i32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Plays `animation` from its first frame.
+    pub fn with_animation(mut self, animation: Animation) -> Self {
+        self.play(animation);
+        self
+    }
+
+    /// Switches to `animation`, starting from its first frame.
+    pub fn play(&mut self, mut animation: Animation) {
+        animation.restart();
+        self.animation = Some(animation);
+    }
+
+    /// Advances the animation; call once per `update`.
+    pub fn update(&mut self, dt: Duration) {
+        if let Some(animation) = &mut self.animation {
+            animation.update(dt);
+        }
+    }
+
+    /// Whether a [`LoopMode::Once`](crate::LoopMode::Once) animation has played through. Sprites without an animation
+    /// are always finished.
+    pub fn is_finished(&self) -> bool {
+        self.animation.as_ref().is_none_or(Animation::is_finished)
+    }
+
+    /// The image drawn this frame: the current animation frame, or `image`.
+    pub fn current_image(&self) -> Image {
+        self.animation
+            .as_ref()
+            .and_then(Animation::current_frame)
+            .unwrap_or(self.image)
+    }
+
+    /// Draw options placing the current image like this sprite describes.
+    pub fn draw_option(&self) -> DrawOption {
+        let image = self.current_image();
+        let (width, height) = (image.width().as_f32(), image.height().as_f32());
+        // Flipping mirrors the quad around its origin, so the origin moves to the far edge.
+        let origin_x = if self.flip_x {
+            (1.0 - self.anchor[0]) * width
+        } else {
+            -self.anchor[0] * width
+        };
+        let origin_y = if self.flip_y {
+            (1.0 - self.anchor[1]) * height
+        } else {
+            -self.anchor[1] * height
+        };
+        let placement = Transform::new(self.position, self.rotation, self.scale);
+        let origin = placement.transform_point([Pt(origin_x), Pt(origin_y)]);
+        let flip = |flipped: bool, scale: f32| if flipped { -scale } else { scale };
+        DrawOption::new(
+            origin,
+            self.rotation,
+            [
+                flip(self.flip_x, self.scale[0]),
+                flip(self.flip_y, self.scale[1]),
+            ],
+        )
+    }
+
+    /// Draws this sprite into `target`.
+    pub fn draw(&self, ctx: &mut Context, target: Image) {
+        target.draw(
+            ctx,
+            &self.current_image(),
+            self.draw_option().with_tint(self.color),
+        );
+    }
+}
+
+/// Draws `sprites` into `target` from the lowest layer to the highest, keeping the given
+/// order within a layer.
+pub fn draw_sprites<'a>(
+    ctx: &mut Context,
+    target: Image,
+    sprites: impl IntoIterator<Item = &'a Sprite>,
+) {
+    let mut sprites: Vec<&Sprite> = sprites.into_iter().collect();
+    sprites.sort_by_key(|sprite| sprite.layer);
+    for sprite in sprites {
+        sprite.draw(ctx, target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawable::DrawCommand;
+
+    fn image(ctx: &mut Context, width: f32, height: f32) -> Image {
+        let pixels = vec![255; (width * height * 4.0) as usize];
+        Image::new(ctx, Pt(width), Pt(height), &pixels).unwrap()
+    }
+
+    #[test]
+    fn anchor_and_flip_keep_the_sprite_in_place() {
+        let mut ctx = Context::new();
+        let sprite = Sprite::new(image(&mut ctx, 20.0, 10.0))
+            .with_position([Pt(100.0), Pt(50.0)])
+            .with_anchor([0.5, 1.0])
+            .with_scale([2.0, 2.0]);
+
+        let options = sprite.draw_option();
+        assert_eq!(options.position(), [Pt(80.0), Pt(30.0)]);
+        assert_eq!(options.scale(), [2.0, 2.0]);
+
+        let options = sprite.with_flip(true, false).draw_option();
+        assert_eq!(options.position(), [Pt(120.0), Pt(30.0)]);
+        assert_eq!(options.scale(), [-2.0, 2.0]);
+    }
+
+    #[test]
+    fn animation_advances_and_holds_the_last_frame() {
+        let mut ctx = Context::new();
+        let frames: Vec<Image> = (0..3).map(|_| image(&mut ctx, 2.0, 2.0)).collect();
+        let animation = Animation::new(frames.clone(), Duration::from_millis(100))
+            .with_mode(crate::LoopMode::Once);
+        let mut sprite = Sprite::new(frames[0]).with_animation(animation);
+
+        sprite.update(Duration::from_millis(150));
+        assert_eq!(sprite.current_image(), frames[1]);
+        assert!(!sprite.is_finished());
+        sprite.update(Duration::from_millis(500));
+        assert_eq!(sprite.current_image(), frames[2]);
+        assert!(sprite.is_finished());
+    }
+
+    #[test]
+    fn sprites_are_drawn_by_layer_with_their_tint() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(200, 200, 1.0);
+        let screen = crate::window::make_screen_target(&ctx);
+        let back = Sprite::new(image(&mut ctx, 2.0, 2.0)).with_layer(-1);
+        let front = Sprite::new(image(&mut ctx, 2.0, 2.0))
+            .with_layer(1)
+            .with_color([1.0, 0.0, 0.0, 1.0]);
+
+        ctx.begin_frame();
+        draw_sprites(&mut ctx, screen, [&front, &back]);
+        let drawn: Vec<(u32, [f32; 4])> = ctx
+            .runtime
+            .draw_list
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Image(command) => Some((command.id, command.opts.tint())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            drawn,
+            [
+                (back.image.id(), [1.0, 1.0, 1.0, 1.0]),
+                (front.image.id(), [1.0, 0.0, 0.0, 1.0])
+            ]
+        );
+    }
+}
@@ -0,0 +1,572 @@
+//! Sanctioned locations for save files, settings and caches.
+//!
+//! [`data_dir`] and [`cache_dir`] resolve the conventional per-user directories on each
+//! platform. The web has no file system, so [`save`], [`load`] and [`remove`] offer a
+//! small key-value API that writes files natively and uses `localStorage` in the browser.
+//! [`save_state`] and [`load_state`] build save slots on top of it for types implementing
+//! [`SaveState`]. [`save_async`] and [`load_async`] do the same work off the main thread
+//! and report back through a callback, so a large save never stalls a frame.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+
+use crate::Context;
+
+/// Returns the directory for persistent application data, such as save files and settings.
+///
+/// - Windows: `%APPDATA%\<app_name>`
+/// - macOS: `~/Library/Application Support/<app_name>`
+/// - Linux: `$XDG_DATA_HOME/<app_name>` or `~/.local/share/<app_name>`
+/// - Android: the app's internal files directory
+/// - iOS: the sandbox's `Library/Application Support`
+///
+/// Android and iOS directories are already private to the app, so `app_name` is not
+/// appended there. Returns `None` on the web and when the location cannot be determined.
+/// The directory is not created.
+pub fn data_dir(app_name: &str) -> Option<PathBuf> {
+    platform_data_dir(app_name)
+}
+
+/// Returns the directory for data that can be deleted and recreated, such as downloads
+/// or baked assets.
+///
+/// - Windows: `%LOCALAPPDATA%\<app_name>\cache`
+/// - macOS: `~/Library/Caches/<app_name>`
+/// - Linux: `$XDG_CACHE_HOME/<app_name>` or `~/.cache/<app_name>`
+/// - Android: the app's cache directory
+/// - iOS: the sandbox's `Library/Caches`
+///
+/// Returns `None` on the web and when the location cannot be determined. The directory
+/// is not created.
+pub fn cache_dir(app_name: &str) -> Option<PathBuf> {
+    platform_cache_dir(app_name)
+}
+
+/// Stores `bytes` under `key` in the application's data storage.
+///
+/// Natively this writes `<data_dir>/<key>`, creating the directory if needed. On the web
+/// it writes to `localStorage`. Keys must be plain file names without path separators.
+pub fn save(app_name: &str, key: &str, bytes: &[u8]) -> io::Result<()> {
+    validate_key(key)?;
+    platform_save(app_name, key, bytes)
+}
+
+/// Loads the bytes stored under `key`, or `None` if nothing was saved.
+pub fn load(app_name: &str, key: &str) -> io::Result<Option<Vec<u8>>> {
+    validate_key(key)?;
+    platform_load(app_name, key)
+}
+
+/// Removes the value stored under `key`. Removing a missing key is not an error.
+pub fn remove(app_name: &str, key: &str) -> io::Result<()> {
+    validate_key(key)?;
+    platform_remove(app_name, key)
+}
+
+/// Stores `bytes` under `key` like [`save`] without blocking the frame, then calls
+/// `on_complete` with the result on the main thread before the next `update`.
+///
+/// Saves to the same key are written one at a time in the order they were requested,
+/// so the last call's bytes are the ones stored.
+pub fn save_async(
+    ctx: &mut Context,
+    app_name: &str,
+    key: &str,
+    bytes: Vec<u8>,
+    on_complete: impl FnOnce(&mut Context, io::Result<()>) + 'static,
+) {
+    let (app_name, key) = (app_name.to_string(), key.to_string());
+    let slot = format!("{app_name}/{key}");
+    let ticket = SAVE_QUEUE.take_ticket(&slot);
+    crate::spawn_task(
+        ctx,
+        move || SAVE_QUEUE.in_turn(&slot, ticket, || save(&app_name, &key, &bytes)),
+        on_complete,
+    );
+}
+
+static SAVE_QUEUE: SaveQueue = SaveQueue {
+    slots: Mutex::new(None),
+    turn: Condvar::new(),
+};
+
+/// Orders [`save_async`] writes per key: each takes a ticket when requested and writes
+/// once every earlier ticket for the key has written.
+struct SaveQueue {
+    /// `(next ticket, ticket allowed to write)` for keys with writes in flight.
+    slots: Mutex<Option<HashMap<String, (u64, u64)>>>,
+    turn: Condvar,
+}
+
+impl SaveQueue {
+    fn take_ticket(&self, slot: &str) -> u64 {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        let (next, _) = slots
+            .get_or_insert_with(HashMap::new)
+            .entry(slot.to_string())
+            .or_default();
+        *next += 1;
+        *next - 1
+    }
+
+    fn in_turn<T>(&self, slot: &str, ticket: u64, write: impl FnOnce() -> T) -> T {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        while slots
+            .as_ref()
+            .and_then(|slots| slots.get(slot))
+            .is_some_and(|&(_, serving)| serving != ticket)
+        {
+            slots = self.turn.wait(slots).unwrap_or_else(|e| e.into_inner());
+        }
+        drop(slots);
+
+        let result = write();
+
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(slots) = slots.as_mut() {
+            let done = slots.get_mut(slot).is_some_and(|(next, serving)| {
+                *serving += 1;
+                serving == next
+            });
+            if done {
+                slots.remove(slot);
+            }
+        }
+        self.turn.notify_all();
+        result
+    }
+}
+
+/// Loads the bytes stored under `key` like [`load`] without blocking the frame, then
+/// calls `on_complete` with the result on the main thread before the next `update`.
+pub fn load_async(
+    ctx: &mut Context,
+    app_name: &str,
+    key: &str,
+    on_complete: impl FnOnce(&mut Context, io::Result<Option<Vec<u8>>>) + 'static,
+) {
+    let (app_name, key) = (app_name.to_string(), key.to_string());
+    crate::spawn_task(ctx, move || load(&app_name, &key), on_complete);
+}
+
+/// Game or editor state that can be written to a save slot and read back.
+///
+/// The engine has no opinion on the format; implementations typically encode with
+/// serde and a format crate of their choice:
+///
+/// ```rust,ignore
+/// impl SaveState for Progress {
+///     fn save_state(&self) -> Vec<u8> {
+///         serde_json::to_vec(self).unwrap()
+///     }
+///
+///     fn restore_state(bytes: &[u8]) -> anyhow::Result<Self> {
+///         Ok(serde_json::from_slice(bytes)?)
+///     }
+/// }
+/// ```
+pub trait SaveState: Sized {
+    fn save_state(&self) -> Vec<u8>;
+    fn restore_state(bytes: &[u8]) -> anyhow::Result<Self>;
+}
+
+impl SaveState for Vec<u8> {
+    fn save_state(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn restore_state(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl SaveState for String {
+    fn save_state(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn restore_state(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+}
+
+/// Writes `state` to the save slot `key`; see [`save`].
+pub fn save_state(app_name: &str, key: &str, state: &impl SaveState) -> io::Result<()> {
+    save(app_name, key, &state.save_state())
+}
+
+/// Reads the state in save slot `key`, or `None` if the slot is empty.
+///
+/// Data the state cannot be restored from is reported as [`io::ErrorKind::InvalidData`].
+pub fn load_state<T: SaveState>(app_name: &str, key: &str) -> io::Result<Option<T>> {
+    load(app_name, key)?
+        .map(|bytes| restore(key, &bytes))
+        .transpose()
+}
+
+fn restore<T: SaveState>(key: &str, bytes: &[u8]) -> io::Result<T> {
+    T::restore_state(bytes).map_err(|error| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("cannot restore save slot {key:?}: {error:#}"),
+        )
+    })
+}
+
+fn validate_key(key: &str) -> io::Result<()> {
+    if key.is_empty() || key == "." || key == ".." || key.contains(['/', '\\']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid storage key {key:?}"),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn storage_path(app_name: &str, key: &str) -> io::Result<PathBuf> {
+    data_dir(app_name).map(|dir| dir.join(key)).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no data directory available on this platform",
+        )
+    })
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn platform_save(app_name: &str, key: &str, bytes: &[u8]) -> io::Result<()> {
+    write_atomically(&storage_path(app_name, key)?, bytes)
+}
+
+/// Writes `bytes` to `path` through a sibling file renamed into place, creating the
+/// directory if needed, so a crash never leaves a truncated file behind.
+///
+/// Every write uses its own temporary file, so concurrent writes to the same or
+/// similarly named paths never rename each other's half-written data into place.
+pub(crate) fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    static NEXT_TEMPORARY: AtomicUsize = AtomicUsize::new(0);
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a file path", path.display()),
+        )
+    })?;
+    let temporary = path.with_file_name(format!(
+        "{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        NEXT_TEMPORARY.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = std::fs::write(&temporary, bytes).and_then(|()| std::fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    result
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn platform_load(app_name: &str, key: &str) -> io::Result<Option<Vec<u8>>> {
+    match std::fs::read(storage_path(app_name, key)?) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn platform_remove(app_name: &str, key: &str) -> io::Result<()> {
+    match std::fs::remove_file(storage_path(app_name, key)?) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn local_storage() -> io::Result<web_sys::Storage> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "localStorage unavailable"))
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn platform_save(app_name: &str, key: &str, bytes: &[u8]) -> io::Result<()> {
+    local_storage()?
+        .set_item(
+            &format!("{app_name}/{key}"),
+            &crate::utils::base64::encode(bytes),
+        )
+        .map_err(|_| io::Error::other("localStorage write failed (quota exceeded?)"))
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn platform_load(app_name: &str, key: &str) -> io::Result<Option<Vec<u8>>> {
+    let value = local_storage()?
+        .get_item(&format!("{app_name}/{key}"))
+        .map_err(|_| io::Error::other("localStorage read failed"))?;
+    value
+        .map(|value| {
+            crate::utils::base64::decode(&value)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "corrupt stored value"))
+        })
+        .transpose()
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn platform_remove(app_name: &str, key: &str) -> io::Result<()> {
+    local_storage()?
+        .remove_item(&format!("{app_name}/{key}"))
+        .map_err(|_| io::Error::other("localStorage remove failed"))
+}
+
+#[cfg(target_os = "windows")]
+fn platform_data_dir(app_name: &str) -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join(app_name))
+}
+
+#[cfg(target_os = "windows")]
+fn platform_cache_dir(app_name: &str) -> Option<PathBuf> {
+    std::env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join(app_name).join("cache"))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_data_dir(app_name: &str) -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join(app_name)
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn platform_cache_dir(app_name: &str) -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library")
+            .join("Caches")
+            .join(app_name)
+    })
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "ios",
+    target_arch = "wasm32"
+)))]
+fn platform_data_dir(app_name: &str) -> Option<PathBuf> {
+    xdg_dir(
+        std::env::var_os("XDG_DATA_HOME"),
+        std::env::var_os("HOME"),
+        ".local/share",
+        app_name,
+    )
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "android",
+    target_os = "ios",
+    target_arch = "wasm32"
+)))]
+fn platform_cache_dir(app_name: &str) -> Option<PathBuf> {
+    xdg_dir(
+        std::env::var_os("XDG_CACHE_HOME"),
+        std::env::var_os("HOME"),
+        ".cache",
+        app_name,
+    )
+}
+
+/// Resolves an XDG base directory; relative overrides are invalid per the spec and ignored.
+#[cfg(any(
+    test,
+    not(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "android",
+        target_os = "ios",
+        target_arch = "wasm32"
+    ))
+))]
+fn xdg_dir(
+    xdg_override: Option<std::ffi::OsString>,
+    home: Option<std::ffi::OsString>,
+    home_relative: &str,
+    app_name: &str,
+) -> Option<PathBuf> {
+    let base = xdg_override
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| PathBuf::from(home).join(home_relative)))?;
+    Some(base.join(app_name))
+}
+
+#[cfg(target_os = "android")]
+fn platform_data_dir(_app_name: &str) -> Option<PathBuf> {
+    crate::android::get_app().and_then(|app| app.internal_data_path())
+}
+
+#[cfg(target_os = "android")]
+fn platform_cache_dir(_app_name: &str) -> Option<PathBuf> {
+    // Context.getCacheDir() is the `cache` sibling of the internal `files` directory.
+    let files = platform_data_dir("")?;
+    files.parent().map(|dir| dir.join("cache"))
+}
+
+#[cfg(target_os = "ios")]
+fn platform_data_dir(_app_name: &str) -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+    })
+}
+
+#[cfg(target_os = "ios")]
+fn platform_cache_dir(_app_name: &str) -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Caches"))
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn platform_data_dir(_app_name: &str) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn platform_cache_dir(_app_name: &str) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xdg_override_must_be_absolute() {
+        assert_eq!(
+            xdg_dir(
+                Some("/data".into()),
+                Some("/home/cat".into()),
+                ".local/share",
+                "game"
+            ),
+            Some(PathBuf::from("/data/game"))
+        );
+        assert_eq!(
+            xdg_dir(
+                Some("relative".into()),
+                Some("/home/cat".into()),
+                ".local/share",
+                "game"
+            ),
+            Some(PathBuf::from("/home/cat/.local/share/game"))
+        );
+        assert_eq!(xdg_dir(None, None, ".cache", "game"), None);
+    }
+
+    #[test]
+    fn atomic_writes_use_their_own_temporary_files() {
+        let dir = std::env::temp_dir().join(format!("spot_storage_{}", std::process::id()));
+        let (json, sav) = (dir.join("slot.json"), dir.join("slot.sav"));
+        let writers: Vec<_> = (0..8u8)
+            .map(|i| {
+                let path = if i % 2 == 0 {
+                    json.clone()
+                } else {
+                    sav.clone()
+                };
+                std::thread::spawn(move || write_atomically(&path, &[i; 4096]))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        for path in [&json, &sav] {
+            let bytes = std::fs::read(path).unwrap();
+            assert!(bytes.iter().all(|&b| b == bytes[0]), "torn write");
+            assert_eq!(bytes.len(), 4096);
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn queued_saves_to_a_key_write_in_request_order() {
+        let queue = SaveQueue {
+            slots: Mutex::new(None),
+            turn: Condvar::new(),
+        };
+        let queue = std::sync::Arc::new(queue);
+        let tickets: Vec<_> = (0..4).map(|_| queue.take_ticket("game/slot")).collect();
+        assert_eq!(queue.take_ticket("game/other"), 0);
+        let order = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let writers: Vec<_> = tickets
+            .into_iter()
+            .rev()
+            .map(|ticket| {
+                let (queue, order) = (queue.clone(), order.clone());
+                std::thread::spawn(move || {
+                    queue.in_turn("game/slot", ticket, || order.lock().unwrap().push(ticket))
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2, 3]);
+        let slots = queue.slots.lock().unwrap();
+        assert!(!slots.as_ref().unwrap().contains_key("game/slot"));
+    }
+
+    #[test]
+    fn keys_with_path_components_are_rejected() {
+        assert!(validate_key("slot1.sav").is_ok());
+        for key in ["", ".", "..", "../escape", "dir/file", "dir\\file"] {
+            assert_eq!(
+                validate_key(key).unwrap_err().kind(),
+                io::ErrorKind::InvalidInput
+            );
+        }
+    }
+
+    #[test]
+    fn unrestorable_state_is_invalid_data() {
+        let name = "Spotted cat".to_string();
+        assert_eq!(
+            restore::<String>("slot1", &name.save_state()).unwrap(),
+            name
+        );
+        let error = restore::<String>("slot1", &[0xff, 0xfe]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("slot1"));
+    }
+
+    #[test]
+    fn async_load_reports_errors_through_the_callback() {
+        let mut ctx = Context::new();
+        let result = std::rc::Rc::new(std::cell::Cell::new(None));
+        let seen = result.clone();
+        load_async(&mut ctx, "game", "../escape", move |_ctx, loaded| {
+            seen.set(Some(loaded.map_err(|e| e.kind())));
+        });
+
+        for _ in 0..1000 {
+            crate::tasks::poll(&mut ctx);
+            if ctx.runtime.tasks.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(result.take(), Some(Err(io::ErrorKind::InvalidInput)));
+    }
+}
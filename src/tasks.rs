@@ -0,0 +1,218 @@
+//! Background work whose results are handed back to the main thread.
+
+use crate::Context;
+use std::future::Future;
+
+/// A spawned task, polled on the main thread until it reports completion.
+type PendingTask = Box<dyn FnMut(&mut Context) -> bool>;
+
+/// Tasks waiting for their completion callback to run.
+#[derive(Default)]
+pub(crate) struct TaskQueue {
+    pending: Vec<PendingTask>,
+}
+
+impl TaskQueue {
+    fn push(&mut self, task: PendingTask) {
+        self.pending.push(task);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl std::fmt::Debug for TaskQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskQueue")
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+/// Runs `work` off the main thread and calls `on_complete` with its result on the main
+/// thread, right before the next `update`.
+///
+/// On the web there are no threads, so `work` runs on the main thread at the start of
+/// the next frame instead; keep it short there or use [`spawn_future`].
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub fn spawn_task<T, W, C>(ctx: &mut Context, work: W, on_complete: C)
+where
+    T: Send + 'static,
+    W: FnOnce() -> T + Send + 'static,
+    C: FnOnce(&mut Context, T) + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    std::thread::spawn(move || {
+        let _ = tx.send(work());
+        crate::user_events::wake();
+    });
+    ctx.runtime.tasks.push(receiver_task(rx, on_complete));
+}
+
+/// Runs `work` off the main thread and calls `on_complete` with its result on the main
+/// thread, right before the next `update`.
+///
+/// On the web there are no threads, so `work` runs on the main thread at the start of
+/// the next frame instead; keep it short there or use [`spawn_future`].
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub fn spawn_task<T, W, C>(ctx: &mut Context, work: W, on_complete: C)
+where
+    T: 'static,
+    W: FnOnce() -> T + 'static,
+    C: FnOnce(&mut Context, T) + 'static,
+{
+    let mut task = Some((work, on_complete));
+    ctx.runtime.tasks.push(Box::new(move |ctx| {
+        if let Some((work, on_complete)) = task.take() {
+            on_complete(ctx, work());
+        }
+        true
+    }));
+}
+
+/// Drives `future` to completion in the background and calls `on_complete` with its
+/// output on the main thread, right before the next `update`.
+///
+/// Native platforms run the future on its own thread; the web runs it on the browser's
+/// event loop.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub fn spawn_future<T, F, C>(ctx: &mut Context, future: F, on_complete: C)
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+    C: FnOnce(&mut Context, T) + 'static,
+{
+    spawn_task(ctx, move || park_on(future), on_complete);
+}
+
+/// Drives `future` to completion in the background and calls `on_complete` with its
+/// output on the main thread, right before the next `update`.
+///
+/// Native platforms run the future on its own thread; the web runs it on the browser's
+/// event loop.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub fn spawn_future<T, F, C>(ctx: &mut Context, future: F, on_complete: C)
+where
+    T: 'static,
+    F: Future<Output = T> + 'static,
+    C: FnOnce(&mut Context, T) + 'static,
+{
+    let slot = std::rc::Rc::new(std::cell::RefCell::new(None));
+    let output = slot.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        *output.borrow_mut() = Some(future.await);
+    });
+    let mut on_complete = Some(on_complete);
+    ctx.runtime.tasks.push(Box::new(move |ctx| {
+        let Some(value) = slot.borrow_mut().take() else {
+            return false;
+        };
+        if let Some(on_complete) = on_complete.take() {
+            on_complete(ctx, value);
+        }
+        true
+    }));
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn receiver_task<T: 'static>(
+    rx: std::sync::mpsc::Receiver<T>,
+    on_complete: impl FnOnce(&mut Context, T) + 'static,
+) -> PendingTask {
+    let mut on_complete = Some(on_complete);
+    Box::new(move |ctx| match rx.try_recv() {
+        Ok(value) => {
+            if let Some(on_complete) = on_complete.take() {
+                on_complete(ctx, value);
+            }
+            true
+        }
+        Err(std::sync::mpsc::TryRecvError::Empty) => false,
+        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+            crate::diagnostics::error!("task", "background task panicked before completing");
+            true
+        }
+    })
+}
+
+/// Blocks the current thread on `future`, parking between polls.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn park_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl std::task::Wake for ThreadWaker {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = std::task::Waker::from(std::sync::Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = std::task::Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            std::task::Poll::Ready(output) => return output,
+            std::task::Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// Runs `task` on the main thread before every update until it returns `true`.
+pub(crate) fn poll_until(ctx: &mut Context, task: impl FnMut(&mut Context) -> bool + 'static) {
+    ctx.runtime.tasks.push(Box::new(task));
+}
+
+/// Runs completion callbacks of finished tasks. Tasks spawned by callbacks are kept
+/// for the next poll.
+pub(crate) fn poll(ctx: &mut Context) {
+    if ctx.runtime.tasks.is_empty() {
+        return;
+    }
+    let mut tasks = std::mem::take(&mut ctx.runtime.tasks.pending);
+    tasks.retain_mut(|task| !task(ctx));
+    tasks.append(&mut ctx.runtime.tasks.pending);
+    ctx.runtime.tasks.pending = tasks;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn completion_runs_on_poll_after_work_finishes() {
+        let mut ctx = Context::new();
+        let result = Rc::new(Cell::new(0));
+        let seen = result.clone();
+        spawn_task(&mut ctx, || 21 * 2, move |_ctx, value| seen.set(value));
+
+        for _ in 0..1000 {
+            poll(&mut ctx);
+            if result.get() != 0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(result.get(), 42);
+        assert!(ctx.runtime.tasks.is_empty());
+    }
+
+    #[test]
+    fn future_output_is_delivered() {
+        let mut ctx = Context::new();
+        let result = Rc::new(Cell::new(0));
+        let seen = result.clone();
+        spawn_future(&mut ctx, async { 7 }, move |_ctx, value| seen.set(value));
+
+        for _ in 0..1000 {
+            poll(&mut ctx);
+            if result.get() != 0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(result.get(), 7);
+    }
+}
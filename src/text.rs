@@ -8,7 +8,8 @@ use std::fmt;
 /// Supports text wrapping with maximum width constraints.
 #[derive(Debug)]
 pub struct Text {
-    pub(crate) content: String,
+    /// Shared with the copies queued by each draw, so drawing does not copy the string.
+    pub(crate) content: std::sync::Arc<str>,
     pub(crate) font_size: crate::Pt,
     pub(crate) color: [f32; 4],
     pub(crate) font_id: u32,
@@ -53,6 +54,10 @@ pub(crate) struct TextLayout {
     pub(crate) bounds: (f32, f32, f32), // width, height, y_offset
     pub(crate) scale: [f32; 2],
     pub(crate) revision: u64,
+    /// The registry's font revision the glyphs were shaped with.
+    pub(crate) font_revision: u64,
+    /// False while some glyphs are still being rasterized and left out of `glyphs`.
+    pub(crate) complete: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -84,7 +89,7 @@ impl Text {
     /// ```
     pub fn new(content: impl Into<String>, font_id: u32) -> Self {
         Self {
-            content: content.into(),
+            content: content.into().into(),
             font_size: crate::Pt(24.0),
             color: [1.0, 1.0, 1.0, 1.0],
             font_id,
@@ -103,8 +108,8 @@ impl Text {
     /// Sets the text content safely without re-allocating the entire struct.
     pub fn set_content(&mut self, content: impl Into<String>) {
         let new_content = content.into();
-        if self.content != new_content {
-            self.content = new_content;
+        if *self.content != *new_content {
+            self.content = new_content.into();
             self.invalidate_layout();
         }
     }
@@ -124,6 +129,14 @@ impl Text {
         }
     }
 
+    /// Sets the font to draw the text with, e.g. when the language changes.
+    pub fn set_font(&mut self, font_id: u32) {
+        if self.font_id != font_id {
+            self.font_id = font_id;
+            self.invalidate_layout();
+        }
+    }
+
     /// Sets the maximum width for text wrapping. Use `None` to disable wrapping.
     pub fn set_max_width(&mut self, max_width: Option<crate::Pt>) {
         if self.max_width != max_width {
@@ -236,6 +249,10 @@ impl Text {
     pub fn measure_with_y_offset(&self, ctx: &Context) -> (f32, f32, f32) {
         use ab_glyph::{Font as _, FontArc, Glyph, PxScale, ScaleFont as _};
 
+        if let Some(font) = ctx.registry.bitmap_fonts.get(&self.font_id) {
+            return font.measure(self);
+        }
+
         let font_data = match ctx.registry.fonts.get(&self.font_id) {
             Some(data) => data,
             None => return (0.0, 0.0, 0.0),
@@ -326,6 +343,10 @@ impl Text {
         &self,
         scaled: &ab_glyph::PxScaleFont<&ab_glyph::FontArc>,
     ) -> Vec<String> {
+        self.wrapped_lines(scaled)
+    }
+
+    pub(crate) fn wrapped_lines(&self, metrics: &impl LineMetrics) -> Vec<String> {
         if let Some(max_width) = self.max_width {
             let max_w = max_width.as_f32();
             if max_w <= 0.0 {
@@ -334,7 +355,7 @@ impl Text {
 
             let mut lines = Vec::new();
             for paragraph in self.content.split('\n') {
-                self.wrap_paragraph(paragraph, scaled, max_w, &mut lines);
+                self.wrap_paragraph(paragraph, metrics, max_w, &mut lines);
             }
             lines
         } else {
@@ -346,7 +367,7 @@ impl Text {
     fn wrap_paragraph(
         &self,
         paragraph: &str,
-        scaled: &ab_glyph::PxScaleFont<&ab_glyph::FontArc>,
+        metrics: &impl LineMetrics,
         max_w: f32,
         lines: &mut Vec<String>,
     ) {
@@ -357,25 +378,25 @@ impl Text {
 
         let mut current_line = String::new();
         let mut current_width = 0.0f32;
-        let mut prev: Option<ab_glyph::GlyphId> = None;
+        let mut prev: Option<char> = None;
         let mut saw_word = false;
 
         for word in paragraph.split_whitespace() {
             saw_word = true;
-            let word_width = self.measure_word_width(word, scaled);
-            let space_width = scaled.h_advance(scaled.glyph_id(' '));
+            let word_width = line_width(word, metrics);
+            let space_width = metrics.advance(' ');
 
             if current_line.is_empty() {
                 if word_width <= max_w {
                     current_line.push_str(word);
                     current_width = word_width;
-                    prev = word.chars().next_back().map(|ch| scaled.glyph_id(ch));
+                    prev = word.chars().next_back();
                 } else {
-                    self.wrap_long_word(word, scaled, max_w, lines);
+                    self.wrap_long_word(word, metrics, max_w, lines);
                 }
             } else {
                 let space_and_word_width = if let Some(p) = prev {
-                    scaled.kern(p, scaled.glyph_id(' ')) + space_width + word_width
+                    metrics.kern(p, ' ') + space_width + word_width
                 } else {
                     space_width + word_width
                 };
@@ -384,7 +405,7 @@ impl Text {
                     current_line.push(' ');
                     current_line.push_str(word);
                     current_width += space_and_word_width;
-                    prev = word.chars().next_back().map(|ch| scaled.glyph_id(ch));
+                    prev = word.chars().next_back();
                 } else {
                     lines.push(current_line.clone());
                     current_line.clear();
@@ -394,9 +415,9 @@ impl Text {
                     if word_width <= max_w {
                         current_line.push_str(word);
                         current_width = word_width;
-                        prev = word.chars().next_back().map(|ch| scaled.glyph_id(ch));
+                        prev = word.chars().next_back();
                     } else {
-                        self.wrap_long_word(word, scaled, max_w, lines);
+                        self.wrap_long_word(word, metrics, max_w, lines);
                     }
                 }
             }
@@ -415,35 +436,34 @@ impl Text {
     fn wrap_long_word(
         &self,
         word: &str,
-        scaled: &ab_glyph::PxScaleFont<&ab_glyph::FontArc>,
+        metrics: &impl LineMetrics,
         max_w: f32,
         lines: &mut Vec<String>,
     ) {
         let mut char_line = String::new();
         let mut char_width = 0.0f32;
-        let mut char_prev: Option<ab_glyph::GlyphId> = None;
+        let mut char_prev: Option<char> = None;
 
         for ch in word.chars() {
-            let id = scaled.glyph_id(ch);
             let char_w = if let Some(p) = char_prev {
-                scaled.kern(p, id) + scaled.h_advance(id)
+                metrics.kern(p, ch) + metrics.advance(ch)
             } else {
-                scaled.h_advance(id)
+                metrics.advance(ch)
             };
 
             if char_width + char_w <= max_w && !char_line.is_empty() {
                 char_line.push(ch);
                 char_width += char_w;
-                char_prev = Some(id);
+                char_prev = Some(ch);
             } else if char_line.is_empty() {
                 char_line.push(ch);
                 char_width = char_w;
-                char_prev = Some(id);
+                char_prev = Some(ch);
             } else {
                 lines.push(char_line);
                 char_line = ch.to_string();
                 char_width = char_w;
-                char_prev = Some(id);
+                char_prev = Some(ch);
             }
         }
 
@@ -458,19 +478,7 @@ impl Text {
         line: &str,
         scaled: &ab_glyph::PxScaleFont<&ab_glyph::FontArc>,
     ) -> f32 {
-        let mut width = 0.0f32;
-        let mut prev: Option<ab_glyph::GlyphId> = None;
-
-        for ch in line.chars() {
-            let id = scaled.glyph_id(ch);
-            if let Some(p) = prev {
-                width += scaled.kern(p, id);
-            }
-            width += scaled.h_advance(id);
-            prev = Some(id);
-        }
-
-        width
+        line_width(line, scaled)
     }
 
     /// Measure width of a single word (for wrapping logic)
@@ -479,22 +487,10 @@ impl Text {
         word: &str,
         scaled: &ab_glyph::PxScaleFont<&ab_glyph::FontArc>,
     ) -> f32 {
-        let mut width = 0.0f32;
-        let mut prev: Option<ab_glyph::GlyphId> = None;
-
-        for ch in word.chars() {
-            let id = scaled.glyph_id(ch);
-            if let Some(p) = prev {
-                width += scaled.kern(p, id);
-            }
-            width += scaled.h_advance(id);
-            prev = Some(id);
-        }
-
-        width
+        line_width(word, scaled)
     }
 
-    fn clone_for_draw(&self) -> Self {
+    pub(crate) fn clone_for_draw(&self) -> Self {
         Self {
             content: self.content.clone(),
             font_size: self.font_size,
@@ -509,17 +505,51 @@ impl Text {
     }
 }
 
+/// Horizontal metrics of a font at one size, all that line wrapping needs.
+pub(crate) trait LineMetrics {
+    /// Returns how far `ch` moves the caret.
+    fn advance(&self, ch: char) -> f32;
+    /// Returns the spacing adjustment between `left` and `right`.
+    fn kern(&self, left: char, right: char) -> f32;
+}
+
+impl LineMetrics for ab_glyph::PxScaleFont<&ab_glyph::FontArc> {
+    fn advance(&self, ch: char) -> f32 {
+        self.h_advance(self.glyph_id(ch))
+    }
+
+    fn kern(&self, left: char, right: char) -> f32 {
+        ab_glyph::ScaleFont::kern(self, self.glyph_id(left), self.glyph_id(right))
+    }
+}
+
+/// Returns the width of `line` set on a single line.
+pub(crate) fn line_width(line: &str, metrics: &impl LineMetrics) -> f32 {
+    let mut width = 0.0f32;
+    let mut prev: Option<char> = None;
+
+    for ch in line.chars() {
+        if let Some(p) = prev {
+            width += metrics.kern(p, ch);
+        }
+        width += metrics.advance(ch);
+        prev = Some(ch);
+    }
+
+    width
+}
+
 impl crate::Drawable for &Text {
     type Options = DrawOption;
 
     fn draw_to(self, ctx: &mut Context, target: crate::Image, options: Self::Options) {
-        ctx.push(crate::drawable::DrawCommand::Text(Box::new(
+        ctx.push(crate::drawable::DrawCommand::Text(
             crate::drawable::TextCommand {
                 target_texture_id: ctx.resolve_target_texture_id(target),
-                text: Box::new(self.clone_for_draw()),
+                text: self.clone_for_draw(),
                 opts: options,
             },
-        )));
+        ));
     }
 }
 
@@ -554,7 +584,7 @@ mod tests {
         text.set_content("changed");
         let changed_draw = text.clone_for_draw();
         assert_ne!(changed_draw.layout_revision, first_draw.layout_revision);
-        assert_eq!(changed_draw.content, "changed");
+        assert_eq!(&*changed_draw.content, "changed");
     }
 
     #[test]
@@ -0,0 +1,503 @@
+use crate::{Bounds, Context, DrawOption, Image, Pt};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const GID_FLAGS: u32 = 0xf000_0000;
+
+/// Tiles sharing one numbering range of a [`TileMap`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TileSet {
+    /// Global tile ID of the first tile; tile `i` of this set has the ID `first_gid + i`.
+    pub first_gid: u32,
+    /// Tile images by local ID; `None` for IDs without an image.
+    pub tiles: Vec<Option<Image>>,
+    /// Animated tiles by local ID, as local tile IDs with how long each is shown.
+    pub animations: HashMap<u32, Vec<(u32, Duration)>>,
+}
+
+impl TileSet {
+    pub fn new(first_gid: u32, tiles: Vec<Image>) -> Self {
+        Self {
+            first_gid,
+            tiles: tiles.into_iter().map(Some).collect(),
+            animations: HashMap::new(),
+        }
+    }
+
+    /// Cuts `image` into a grid of `tile_width` by `tile_height` tiles, read left to right
+    /// and top to bottom, skipping `margin` around the image and `spacing` between tiles.
+    pub fn from_image(
+        ctx: &mut Context,
+        first_gid: u32,
+        image: Image,
+        tile_size: [Pt; 2],
+        margin: Pt,
+        spacing: Pt,
+    ) -> anyhow::Result<Self> {
+        let [tile_width, tile_height] = tile_size;
+        if tile_width.as_f32() <= 0.0 || tile_height.as_f32() <= 0.0 {
+            anyhow::bail!("tiles must have a positive size");
+        }
+        let count = |extent: Pt, tile: Pt| {
+            let usable = extent.as_f32() - 2.0 * margin.as_f32() + spacing.as_f32();
+            (usable / (tile + spacing).as_f32()).max(0.0) as u32
+        };
+        let columns = count(image.width(), tile_width);
+        let rows = count(image.height(), tile_height);
+        let mut tiles = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for column in 0..columns {
+                let bounds = Bounds::new(
+                    margin + (tile_width + spacing) * column as f32,
+                    margin + (tile_height + spacing) * row as f32,
+                    tile_width,
+                    tile_height,
+                );
+                tiles.push(Image::sub_image(ctx, image, bounds)?);
+            }
+        }
+        Ok(Self::new(first_gid, tiles))
+    }
+
+    /// Whether the tile ID `gid`, without flip flags, belongs to this set.
+    pub fn contains(&self, gid: u32) -> bool {
+        gid >= self.first_gid && gid - self.first_gid < self.tiles.len() as u32
+    }
+}
+
+/// A grid of tile IDs. `0` is an empty cell.
+///
+/// IDs may carry Tiled's flip flags in their top bits, which flip the tile when drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileLayer {
+    pub name: String,
+    /// Size in tiles.
+    pub width: u32,
+    pub height: u32,
+    /// Tile IDs row by row from the top-left cell.
+    pub tiles: Vec<u32>,
+    pub visible: bool,
+    pub opacity: f32,
+    /// Drawing offset of the whole layer.
+    pub offset: [Pt; 2],
+}
+
+impl TileLayer {
+    pub fn new(name: impl Into<String>, width: u32, height: u32) -> Self {
+        Self {
+            name: name.into(),
+            width,
+            height,
+            tiles: vec![0; (width * height) as usize],
+            visible: true,
+            opacity: 1.0,
+            offset: [Pt(0.0), Pt(0.0)],
+        }
+    }
+
+    /// The tile ID in column `x` of row `y`, flip flags included.
+    pub fn tile(&self, x: u32, y: u32) -> Option<u32> {
+        (x < self.width && y < self.height)
+            .then(|| self.tiles.get((y * self.width + x) as usize).copied())
+            .flatten()
+    }
+
+    pub fn set_tile(&mut self, x: u32, y: u32, gid: u32) {
+        if x < self.width
+            && y < self.height
+            && let Some(tile) = self.tiles.get_mut((y * self.width + x) as usize)
+        {
+            *tile = gid;
+        }
+    }
+}
+
+/// A shape or marker placed in a map, such as a spawn point or trigger area.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    /// The object's class, called type in older Tiled versions.
+    pub class: String,
+    pub position: [Pt; 2],
+    pub size: [Pt; 2],
+    /// Rotation in radians.
+    pub rotation: f32,
+    /// Tile shown by tile objects.
+    pub gid: Option<u32>,
+    pub visible: bool,
+    /// Custom properties, with values as written in the map.
+    pub properties: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub objects: Vec<MapObject>,
+    pub visible: bool,
+}
+
+impl ObjectLayer {
+    /// The first object called `name`.
+    pub fn object(&self, name: &str) -> Option<&MapObject> {
+        self.objects.iter().find(|object| object.name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapLayer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+impl MapLayer {
+    pub fn name(&self) -> &str {
+        match self {
+            MapLayer::Tiles(layer) => &layer.name,
+            MapLayer::Objects(layer) => &layer.name,
+        }
+    }
+}
+
+/// An orthogonal tile map: tile sets, and tile and object layers drawn bottom to top.
+///
+/// Build one by hand or import it from Tiled with the `tiled` feature's
+/// [`utils::tiled`](crate::utils) loaders. Call [`update`](Self::update) to play
+/// animated tiles and [`draw`](Self::draw) to draw the cells the camera sees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileMap {
+    /// Size in tiles.
+    pub width: u32,
+    pub height: u32,
+    pub tile_width: Pt,
+    pub tile_height: Pt,
+    pub tilesets: Vec<TileSet>,
+    pub layers: Vec<MapLayer>,
+    elapsed: Duration,
+}
+
+impl TileMap {
+    pub fn new(width: u32, height: u32, tile_width: Pt, tile_height: Pt) -> Self {
+        Self {
+            width,
+            height,
+            tile_width,
+            tile_height,
+            tilesets: Vec::new(),
+            layers: Vec::new(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// The first layer called `name`.
+    pub fn layer(&self, name: &str) -> Option<&MapLayer> {
+        self.layers.iter().find(|layer| layer.name() == name)
+    }
+
+    pub fn tile_layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find_map(|layer| match layer {
+            MapLayer::Tiles(layer) if layer.name == name => Some(layer),
+            _ => None,
+        })
+    }
+
+    pub fn tile_layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.layers.iter_mut().find_map(|layer| match layer {
+            MapLayer::Tiles(layer) if layer.name == name => Some(layer),
+            _ => None,
+        })
+    }
+
+    pub fn object_layer(&self, name: &str) -> Option<&ObjectLayer> {
+        self.layers.iter().find_map(|layer| match layer {
+            MapLayer::Objects(layer) if layer.name == name => Some(layer),
+            _ => None,
+        })
+    }
+
+    /// Advances animated tiles; call once per `update`.
+    pub fn update(&mut self, dt: Duration) {
+        self.elapsed += dt;
+    }
+
+    /// The image currently shown for the tile ID `gid`, following its animation.
+    /// Flip flags are ignored.
+    pub fn tile_image(&self, gid: u32) -> Option<Image> {
+        let gid = gid & !GID_FLAGS;
+        let tileset = self.tilesets.iter().find(|tileset| tileset.contains(gid))?;
+        let mut local = gid - tileset.first_gid;
+        if let Some(frames) = tileset.animations.get(&local) {
+            let total: Duration = frames.iter().map(|&(_, duration)| duration).sum();
+            if !total.is_zero() {
+                let mut at =
+                    Duration::from_nanos((self.elapsed.as_nanos() % total.as_nanos()) as u64);
+                for &(frame, duration) in frames {
+                    if at < duration {
+                        local = frame;
+                        break;
+                    }
+                    at -= duration;
+                }
+            }
+        }
+        tileset.tiles.get(local as usize).copied().flatten()
+    }
+
+    /// Draws every visible tile layer into `target`, with `camera` the map position shown
+    /// at the target's top-left corner.
+    pub fn draw(&self, ctx: &mut Context, target: Image, camera: [Pt; 2]) {
+        for index in 0..self.layers.len() {
+            self.draw_layer(ctx, target, index, camera);
+        }
+    }
+
+    /// Draws the layer at `index` if it is a visible tile layer; see [`draw`](Self::draw).
+    ///
+    /// Only cells overlapping the target are drawn, so the cost follows the screen size
+    /// rather than the map size. Tiles taller or wider than a cell stick out to the top
+    /// and right, like in Tiled.
+    pub fn draw_layer(&self, ctx: &mut Context, target: Image, index: usize, camera: [Pt; 2]) {
+        let Some(MapLayer::Tiles(layer)) = self.layers.get(index) else {
+            return;
+        };
+        if !layer.visible || layer.opacity <= 0.0 {
+            return;
+        }
+        let tile_width = self.tile_width.as_f32();
+        let tile_height = self.tile_height.as_f32();
+        if tile_width <= 0.0 || tile_height <= 0.0 {
+            return;
+        }
+        let origin = [
+            layer.offset[0].as_f32() - camera[0].as_f32(),
+            layer.offset[1].as_f32() - camera[1].as_f32(),
+        ];
+        let (overhang_x, overhang_y) = self.largest_overhang();
+        let columns = visible_range(
+            -origin[0] - overhang_x,
+            -origin[0] + target.width().as_f32(),
+            tile_width,
+            layer.width,
+        );
+        let rows = visible_range(
+            -origin[1],
+            -origin[1] + target.height().as_f32() + overhang_y,
+            tile_height,
+            layer.height,
+        );
+
+        for y in rows {
+            for x in columns.clone() {
+                let gid = layer.tiles[(y * layer.width + x) as usize];
+                if gid & !GID_FLAGS == 0 {
+                    continue;
+                }
+                let Some(image) = self.tile_image(gid) else {
+                    continue;
+                };
+                let cell_left = origin[0] + x as f32 * tile_width;
+                let cell_bottom = origin[1] + (y + 1) as f32 * tile_height;
+                let options = tile_draw_option(gid, image, [cell_left, cell_bottom])
+                    .with_opacity(layer.opacity);
+                target.draw(ctx, &image, options);
+            }
+        }
+    }
+
+    /// How far the largest tile extends past a cell to the right and to the top.
+    fn largest_overhang(&self) -> (f32, f32) {
+        let mut overhang = (0.0f32, 0.0f32);
+        for image in self
+            .tilesets
+            .iter()
+            .flat_map(|set| set.tiles.iter().flatten())
+        {
+            let extent = image.width().as_f32().max(image.height().as_f32());
+            overhang.0 = overhang.0.max(extent - self.tile_width.as_f32());
+            overhang.1 = overhang.1.max(extent - self.tile_height.as_f32());
+        }
+        overhang
+    }
+}
+
+/// Cells of a row or column with `size` cells of `cell` length overlapping `start..end`.
+fn visible_range(start: f32, end: f32, cell: f32, size: u32) -> std::ops::Range<u32> {
+    let first = (start / cell).floor().clamp(0.0, size as f32) as u32;
+    let last = (end / cell).ceil().clamp(0.0, size as f32) as u32;
+    first..last.max(first)
+}
+
+/// Places `image` with its bottom-left corner on `cell_bottom_left`, applying the flip
+/// flags of `gid` the way Tiled does: diagonal first, then horizontal and vertical.
+fn tile_draw_option(gid: u32, image: Image, cell_bottom_left: [f32; 2]) -> DrawOption {
+    let (width, height) = (image.width().as_f32(), image.height().as_f32());
+    let flip_x = if gid & FLIPPED_HORIZONTALLY != 0 {
+        -1.0
+    } else {
+        1.0
+    };
+    let flip_y = if gid & FLIPPED_VERTICALLY != 0 {
+        -1.0
+    } else {
+        1.0
+    };
+    // A DrawOption maps a local point (u, v) to `position + [[c*sx, s*sy], [-s*sx, c*sy]] * (u, v)`.
+    let (rotation, scale, drawn_size) = if gid & FLIPPED_DIAGONALLY != 0 {
+        // Swapping the axes takes a quarter turn with the local y axis mirrored.
+        (
+            -std::f32::consts::FRAC_PI_2,
+            [flip_y, -flip_x],
+            [height, width],
+        )
+    } else {
+        (0.0, [flip_x, flip_y], [width, height])
+    };
+    let (s, c) = rotation.sin_cos();
+    let column = |u: f32, v: f32| {
+        [
+            c * scale[0] * u + s * scale[1] * v,
+            -s * scale[0] * u + c * scale[1] * v,
+        ]
+    };
+    let corners = [
+        column(0.0, 0.0),
+        column(width, 0.0),
+        column(0.0, height),
+        column(width, height),
+    ];
+    let min_x = corners.iter().map(|p| p[0]).fold(f32::INFINITY, f32::min);
+    let min_y = corners.iter().map(|p| p[1]).fold(f32::INFINITY, f32::min);
+    let top_left = [cell_bottom_left[0], cell_bottom_left[1] - drawn_size[1]];
+    DrawOption::new(
+        [Pt(top_left[0] - min_x), Pt(top_left[1] - min_y)],
+        rotation,
+        scale,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawable::DrawCommand;
+
+    fn tile(ctx: &mut Context, width: f32, height: f32) -> Image {
+        Image::new(
+            ctx,
+            Pt(width),
+            Pt(height),
+            &vec![255; (width * height * 4.0) as usize],
+        )
+        .unwrap()
+    }
+
+    fn drawn(ctx: &Context) -> Vec<(u32, [Pt; 2])> {
+        ctx.runtime
+            .draw_list
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Image(command) => Some((command.id, command.opts.position())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_cells_inside_the_target_are_drawn() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(32, 16, 1.0);
+        let screen = crate::window::make_screen_target(&ctx);
+        let grass = tile(&mut ctx, 16.0, 16.0);
+        let mut map = TileMap::new(100, 100, Pt(16.0), Pt(16.0));
+        map.tilesets.push(TileSet::new(1, vec![grass]));
+        let mut ground = TileLayer::new("ground", 100, 100);
+        ground.tiles.fill(1);
+        map.layers.push(MapLayer::Tiles(ground));
+
+        ctx.begin_frame();
+        map.draw(&mut ctx, screen, [Pt(40.0), Pt(16.0)]);
+        let positions: Vec<[Pt; 2]> = drawn(&ctx).into_iter().map(|(_, at)| at).collect();
+        assert_eq!(
+            positions,
+            [[Pt(-8.0), Pt(0.0)], [Pt(8.0), Pt(0.0)], [Pt(24.0), Pt(0.0)]]
+        );
+    }
+
+    #[test]
+    fn animated_tiles_follow_their_frames() {
+        let mut ctx = Context::new();
+        let frames: Vec<Image> = (0..3).map(|_| tile(&mut ctx, 8.0, 8.0)).collect();
+        let mut tileset = TileSet::new(10, frames.clone());
+        let ms = Duration::from_millis;
+        tileset
+            .animations
+            .insert(0, vec![(0, ms(100)), (2, ms(50))]);
+        let mut map = TileMap::new(1, 1, Pt(8.0), Pt(8.0));
+        map.tilesets.push(tileset);
+
+        assert_eq!(map.tile_image(10), Some(frames[0]));
+        assert_eq!(map.tile_image(11 | FLIPPED_HORIZONTALLY), Some(frames[1]));
+        map.update(ms(120));
+        assert_eq!(map.tile_image(10), Some(frames[2]));
+        map.update(ms(40));
+        assert_eq!(map.tile_image(10), Some(frames[0]));
+        assert_eq!(map.tile_image(13), None);
+    }
+
+    #[test]
+    fn flipped_tiles_stay_inside_their_cell() {
+        let mut ctx = Context::new();
+        let image = tile(&mut ctx, 16.0, 32.0);
+        let flags = [
+            0,
+            FLIPPED_HORIZONTALLY,
+            FLIPPED_VERTICALLY,
+            FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY,
+            FLIPPED_DIAGONALLY,
+            FLIPPED_DIAGONALLY | FLIPPED_HORIZONTALLY,
+            FLIPPED_DIAGONALLY | FLIPPED_VERTICALLY,
+            FLIPPED_DIAGONALLY | FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY,
+        ];
+        for flag in flags {
+            let options = tile_draw_option(1 | flag, image, [100.0, 200.0]);
+            let transform =
+                crate::Transform::new(options.position(), options.rotation(), options.scale());
+            let corners: Vec<[f32; 2]> = [[0.0, 0.0], [16.0, 0.0], [0.0, 32.0], [16.0, 32.0]]
+                .iter()
+                .map(|&[u, v]| {
+                    let p = transform.transform_point([Pt(u), Pt(v)]);
+                    [p[0].as_f32().round(), p[1].as_f32().round()]
+                })
+                .collect();
+            let min_x = corners.iter().map(|p| p[0]).fold(f32::INFINITY, f32::min);
+            let max_y = corners
+                .iter()
+                .map(|p| p[1])
+                .fold(f32::NEG_INFINITY, f32::max);
+            assert_eq!([min_x, max_y], [100.0, 200.0], "flags {flag:#x}");
+            // The image's top-left texel ends up in the corner Tiled puts it in.
+            let diagonal = flag & FLIPPED_DIAGONALLY != 0;
+            let (w, h) = if diagonal { (32.0, 16.0) } else { (16.0, 32.0) };
+            let expected_x = if flag & FLIPPED_HORIZONTALLY != 0 {
+                100.0 + w
+            } else {
+                100.0
+            };
+            let expected_y = if flag & FLIPPED_VERTICALLY != 0 {
+                200.0
+            } else {
+                200.0 - h
+            };
+            assert_eq!(corners[0], [expected_x, expected_y], "flags {flag:#x}");
+            // Diagonal flips send the image's top-right texel down the left edge.
+            let right = corners[1];
+            if diagonal {
+                assert_eq!(right[0], expected_x, "flags {flag:#x}");
+            } else {
+                assert_eq!(right[1], expected_y, "flags {flag:#x}");
+            }
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use crate::{DrawOption, Drawable, Image, Pt};
+
+/// Position, rotation and scale of a drawn item, relative to its parent in a
+/// [`TransformTree`] or to the draw target.
+///
+/// Follows [`DrawOption`]: items rotate about their position, which is their top-left
+/// corner. Children are positioned, rotated and scaled in their parent's frame; with
+/// non-uniform parent scale, rotated children are scaled along the world axes rather
+/// than sheared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub position: [Pt; 2],
+    /// Rotation in radians.
+    pub rotation: f32,
+    pub scale: [f32; 2],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        position: [Pt(0.0), Pt(0.0)],
+        rotation: 0.0,
+        scale: [1.0, 1.0],
+    };
+
+    pub fn new(position: [Pt; 2], rotation: f32, scale: [f32; 2]) -> Self {
+        Self {
+            position,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn with_position(mut self, position: [Pt; 2]) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: [f32; 2]) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Maps a point from this transform's local frame into its parent's frame.
+    pub fn transform_point(&self, point: [Pt; 2]) -> [Pt; 2] {
+        let x = point[0].as_f32() * self.scale[0];
+        let y = point[1].as_f32() * self.scale[1];
+        let (s, c) = self.rotation.sin_cos();
+        [
+            self.position[0] + Pt(c * x + s * y),
+            self.position[1] + Pt(c * y - s * x),
+        ]
+    }
+
+    /// Places `child`, given in this transform's frame, into this transform's parent frame.
+    pub fn then(&self, child: &Transform) -> Transform {
+        Transform {
+            position: self.transform_point(child.position),
+            rotation: self.rotation + child.rotation,
+            scale: [
+                self.scale[0] * child.scale[0],
+                self.scale[1] * child.scale[1],
+            ],
+        }
+    }
+}
+
+impl From<Transform> for DrawOption {
+    fn from(transform: Transform) -> Self {
+        DrawOption::new(transform.position, transform.rotation, transform.scale)
+    }
+}
+
+/// Handle to a node in a [`TransformTree`]. Handles of removed nodes stay invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    local: Transform,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Slot {
+    generation: u32,
+    node: Option<Node>,
+}
+
+/// Transforms arranged in a hierarchy, where each node is placed relative to its parent.
+///
+/// Moving, rotating or scaling a node carries its whole subtree along, e.g. a turret on
+/// a tank or the limbs of a character:
+///
+/// ```rust,no_run
+/// use spottedcat::{Context, Image, Pt, Transform, TransformTree};
+///
+/// fn draw_tank(ctx: &mut Context, screen: Image, hull: Image, turret: Image, angle: f32) {
+///     let mut tree = TransformTree::new();
+///     let hull_at = [Pt::from(200.0), Pt::from(120.0)];
+///     let body = tree.insert(None, Transform::default().with_position(hull_at));
+///     let gun = tree.insert(
+///         Some(body),
+///         Transform::default()
+///             .with_position([Pt::from(24.0), Pt::from(8.0)])
+///             .with_rotation(angle),
+///     );
+///
+///     screen.draw_with_transform(ctx, &hull, tree.world(body).unwrap());
+///     screen.draw_with_transform(ctx, &turret, tree.world(gun).unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TransformTree {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl TransformTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node with the `local` transform under `parent`, or as a root with `None`.
+    ///
+    /// A `parent` that is no longer in the tree makes the node a root.
+    pub fn insert(&mut self, parent: Option<NodeId>, local: Transform) -> NodeId {
+        let parent = parent.filter(|&parent| self.contains(parent));
+        let node = Node {
+            local,
+            parent,
+            children: Vec::new(),
+        };
+        let id = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.node = Some(node);
+                NodeId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: Some(node),
+                });
+                NodeId {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        };
+        if let Some(parent) = parent.and_then(|parent| self.node_mut(parent)) {
+            parent.children.push(id);
+        }
+        id
+    }
+
+    /// Removes `id` together with all of its descendants.
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        let Some(node) = self.take(id) else {
+            return false;
+        };
+        if let Some(parent) = node.parent.and_then(|parent| self.node_mut(parent)) {
+            parent.children.retain(|&child| child != id);
+        }
+        let mut pending = node.children;
+        while let Some(child) = pending.pop() {
+            if let Some(node) = self.take(child) {
+                pending.extend(node.children);
+            }
+        }
+        true
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.len() == self.free.len()
+    }
+
+    /// Removes every node; handles to them stay invalid.
+    pub fn clear(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.node.take().is_some() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+            }
+        }
+    }
+
+    /// The transform of `id` relative to its parent.
+    pub fn local(&self, id: NodeId) -> Option<Transform> {
+        self.node(id).map(|node| node.local)
+    }
+
+    pub fn local_mut(&mut self, id: NodeId) -> Option<&mut Transform> {
+        self.node_mut(id).map(|node| &mut node.local)
+    }
+
+    pub fn set_local(&mut self, id: NodeId, local: Transform) {
+        if let Some(node) = self.node_mut(id) {
+            node.local = local;
+        }
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).and_then(|node| node.parent)
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map_or(&[], |node| &node.children)
+    }
+
+    /// Moves `id` under `parent`, or makes it a root with `None`, keeping its local
+    /// transform. Returns false if either node is missing or `parent` lies inside the
+    /// subtree of `id`.
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> bool {
+        if !self.contains(id) {
+            return false;
+        }
+        if let Some(parent) = parent
+            && (!self.contains(parent) || self.is_ancestor_or_self(id, parent))
+        {
+            return false;
+        }
+        if let Some(old) = self.parent(id).and_then(|old| self.node_mut(old)) {
+            old.children.retain(|&child| child != id);
+        }
+        if let Some(new) = parent.and_then(|parent| self.node_mut(parent)) {
+            new.children.push(id);
+        }
+        if let Some(node) = self.node_mut(id) {
+            node.parent = parent;
+        }
+        true
+    }
+
+    /// Resolves the transform of `id` relative to the draw target by combining it with
+    /// the transforms of all its ancestors.
+    pub fn world(&self, id: NodeId) -> Option<Transform> {
+        let mut node = self.node(id)?;
+        let mut world = node.local;
+        while let Some(parent) = node.parent.and_then(|parent| self.node(parent)) {
+            world = parent.local.then(&world);
+            node = parent;
+        }
+        Some(world)
+    }
+
+    fn is_ancestor_or_self(&self, ancestor: NodeId, mut id: NodeId) -> bool {
+        loop {
+            if id == ancestor {
+                return true;
+            }
+            match self.parent(id) {
+                Some(parent) => id = parent,
+                None => return false,
+            }
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_ref())
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_mut())
+    }
+
+    fn take(&mut self, id: NodeId) -> Option<Node> {
+        let slot = self
+            .slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)?;
+        let node = slot.node.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        Some(node)
+    }
+}
+
+impl Image {
+    /// Draws `drawable` into this target at a resolved transform, typically
+    /// [`TransformTree::world`].
+    ///
+    /// Same as `target.draw(ctx, drawable, DrawOption::from(transform))`.
+    pub fn draw_with_transform<D: Drawable<Options = DrawOption>>(
+        self,
+        ctx: &mut crate::Context,
+        drawable: D,
+        transform: Transform,
+    ) {
+        self.draw(ctx, drawable, DrawOption::from(transform));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_close(actual: [Pt; 2], expected: [f32; 2]) {
+        assert!(
+            (actual[0].as_f32() - expected[0]).abs() < 1e-4
+                && (actual[1].as_f32() - expected[1]).abs() < 1e-4,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn children_inherit_parent_position_rotation_and_scale() {
+        let mut tree = TransformTree::new();
+        let root = tree.insert(
+            None,
+            Transform::new([Pt(100.0), Pt(50.0)], FRAC_PI_2, [2.0, 2.0]),
+        );
+        let child = tree.insert(
+            Some(root),
+            Transform::default().with_position([Pt(10.0), Pt(0.0)]),
+        );
+        let grandchild = tree.insert(
+            Some(child),
+            Transform::default()
+                .with_position([Pt(5.0), Pt(0.0)])
+                .with_scale([0.5, 0.5]),
+        );
+
+        let world = tree.world(child).unwrap();
+        // Rotation turns the local x axis towards negative y on screen, like DrawOption.
+        assert_close(world.position, [100.0, 30.0]);
+        assert_eq!(world.rotation, FRAC_PI_2);
+        assert_eq!(world.scale, [2.0, 2.0]);
+
+        let world = tree.world(grandchild).unwrap();
+        assert_close(world.position, [100.0, 20.0]);
+        assert_eq!(world.scale, [1.0, 1.0]);
+
+        tree.local_mut(root).unwrap().position = [Pt(0.0), Pt(0.0)];
+        assert_close(tree.world(grandchild).unwrap().position, [0.0, -30.0]);
+    }
+
+    #[test]
+    fn removing_a_node_removes_its_subtree_and_invalidates_handles() {
+        let mut tree = TransformTree::new();
+        let root = tree.insert(None, Transform::IDENTITY);
+        let child = tree.insert(Some(root), Transform::IDENTITY);
+        let grandchild = tree.insert(Some(child), Transform::IDENTITY);
+        let other = tree.insert(None, Transform::IDENTITY);
+
+        assert!(!tree.set_parent(root, Some(grandchild)));
+        assert!(tree.remove(child));
+        assert!(!tree.contains(grandchild));
+        assert!(tree.children(root).is_empty());
+
+        let reused = tree.insert(Some(other), Transform::IDENTITY);
+        assert!(!tree.contains(child));
+        assert!(tree.contains(reused));
+        assert_eq!(tree.parent(reused), Some(other));
+        assert!(tree.set_parent(reused, None));
+        assert!(tree.children(other).is_empty());
+    }
+}
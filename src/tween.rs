@@ -0,0 +1,526 @@
+//! Tweens and timers driven by the fixed update loop.
+
+use crate::{Context, Easing, Pt};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Values that can be interpolated by a tween.
+pub trait Lerp: Copy + 'static {
+    /// The value a fraction `t` of the way from `self` to `to`.
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Pt {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].lerp(to[i], t))
+    }
+}
+
+/// A value animated by [`tween`], shared between the scene that reads it and the
+/// tween that writes it.
+///
+/// Keep it as a field of the scene and read it with [`get`](Self::get) when drawing.
+/// Tweens stop once every clone of their target has been dropped.
+#[derive(Clone)]
+pub struct Tweened<T>(Rc<Cell<T>>);
+
+impl<T: Copy + Default> Default for Tweened<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy + std::fmt::Debug> std::fmt::Debug for Tweened<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Tweened").field(&self.get()).finish()
+    }
+}
+
+impl<T: Copy> Tweened<T> {
+    pub fn new(value: T) -> Self {
+        Self(Rc::new(Cell::new(value)))
+    }
+
+    pub fn get(&self) -> T {
+        self.0.get()
+    }
+
+    /// Overwrites the value; a running tween keeps writing to it on the next update.
+    pub fn set(&self, value: T) {
+        self.0.set(value);
+    }
+
+    fn key(&self) -> *const () {
+        Rc::as_ptr(&self.0) as *const ()
+    }
+}
+
+/// Handle to a callback scheduled with [`after`] or [`every`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+trait RunningTween {
+    /// Advances by `dt`; returns false once the tween has finished or lost its target.
+    fn advance(&mut self, dt: Duration) -> bool;
+    fn target(&self) -> *const ();
+}
+
+struct ActiveTween<T> {
+    target: Tweened<T>,
+    from: T,
+    to: T,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+}
+
+impl<T: Lerp> RunningTween for ActiveTween<T> {
+    fn advance(&mut self, dt: Duration) -> bool {
+        if Rc::strong_count(&self.target.0) == 1 {
+            return false;
+        }
+        self.elapsed += dt;
+        if self.elapsed >= self.duration {
+            self.target.set(self.to);
+            return false;
+        }
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        self.target
+            .set(self.from.lerp(self.to, self.easing.apply(t)));
+        true
+    }
+
+    fn target(&self) -> *const () {
+        self.target.key()
+    }
+}
+
+struct Timer {
+    id: TimerId,
+    remaining: Duration,
+    callback: TimerCallback,
+}
+
+enum TimerCallback {
+    Once(Box<dyn FnOnce(&mut Context)>),
+    Every {
+        interval: Duration,
+        callback: Box<dyn FnMut(&mut Context)>,
+    },
+}
+
+/// Running tweens and pending timers, advanced right before every `update`.
+#[derive(Default)]
+pub(crate) struct Tweens {
+    tweens: Vec<Box<dyn RunningTween>>,
+    /// Pending timers, ordered by id and so by when they were scheduled.
+    timers: Vec<Timer>,
+    next_timer: u64,
+    /// Timers that came due this tick and have not finished running yet.
+    running: Vec<TimerId>,
+    /// Running timers cancelled from inside a callback.
+    cancelled: Vec<TimerId>,
+}
+
+impl std::fmt::Debug for Tweens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tweens")
+            .field("tweens", &self.tweens.len())
+            .field("timers", &self.timers.len())
+            .finish()
+    }
+}
+
+/// Animates `target` from `from` to `to` over `duration`, following `easing`.
+///
+/// `target` is set to `from` right away and reaches `to` exactly after `duration` worth
+/// of updates. Starting a tween replaces any tween already running on `target`.
+///
+/// ```rust,no_run
+/// use spottedcat::{Context, Easing, Pt, Tweened, tween};
+/// use std::time::Duration;
+///
+/// fn open_menu(ctx: &mut Context, menu_x: &Tweened<Pt>) {
+///     let from = Pt::from(-200.0);
+///     let to = Pt::from(0.0);
+///     tween(ctx, menu_x, from, to, Duration::from_millis(300), Easing::OutCubic);
+/// }
+/// ```
+pub fn tween<T: Lerp>(
+    ctx: &mut Context,
+    target: &Tweened<T>,
+    from: T,
+    to: T,
+    duration: Duration,
+    easing: Easing,
+) {
+    stop_tween(ctx, target);
+    target.set(from);
+    if duration.is_zero() {
+        target.set(to);
+        return;
+    }
+    ctx.runtime.tweens.tweens.push(Box::new(ActiveTween {
+        target: target.clone(),
+        from,
+        to,
+        duration,
+        elapsed: Duration::ZERO,
+        easing,
+    }));
+    ctx.request_redraw();
+}
+
+/// Stops the tween running on `target`, leaving its current value in place. Returns
+/// false if there was none.
+pub fn stop_tween<T: Lerp>(ctx: &mut Context, target: &Tweened<T>) -> bool {
+    let tweens = &mut ctx.runtime.tweens.tweens;
+    let before = tweens.len();
+    tweens.retain(|tween| tween.target() != target.key());
+    tweens.len() != before
+}
+
+/// Whether a tween is still running on `target`.
+pub fn is_tweening<T: Lerp>(ctx: &Context, target: &Tweened<T>) -> bool {
+    ctx.runtime
+        .tweens
+        .tweens
+        .iter()
+        .any(|tween| tween.target() == target.key())
+}
+
+/// Calls `callback` right before the first `update` after `delay` has passed.
+///
+/// Timers outlive scene switches; cancel them with [`cancel_timer`] when the scene that
+/// scheduled them goes away.
+pub fn after(
+    ctx: &mut Context,
+    delay: Duration,
+    callback: impl FnOnce(&mut Context) + 'static,
+) -> TimerId {
+    schedule(ctx, delay, TimerCallback::Once(Box::new(callback)))
+}
+
+/// Calls `callback` every `interval` until the timer is cancelled with [`cancel_timer`].
+///
+/// The first call comes after one `interval`. When an update step spans several
+/// intervals the callback runs once for each, so it keeps pace with game time. A zero
+/// interval runs it before every `update`.
+pub fn every(
+    ctx: &mut Context,
+    interval: Duration,
+    callback: impl FnMut(&mut Context) + 'static,
+) -> TimerId {
+    let callback = TimerCallback::Every {
+        interval,
+        callback: Box::new(callback),
+    };
+    schedule(ctx, interval, callback)
+}
+
+fn schedule(ctx: &mut Context, delay: Duration, callback: TimerCallback) -> TimerId {
+    let tweens = &mut ctx.runtime.tweens;
+    let id = TimerId(tweens.next_timer);
+    tweens.next_timer += 1;
+    tweens.timers.push(Timer {
+        id,
+        remaining: delay,
+        callback,
+    });
+    ctx.request_redraw();
+    id
+}
+
+/// Cancels a timer scheduled with [`after`] or [`every`], also from inside its own
+/// callback. Returns false if it already ran or was cancelled.
+pub fn cancel_timer(ctx: &mut Context, id: TimerId) -> bool {
+    let tweens = &mut ctx.runtime.tweens;
+    if tweens.running.contains(&id) {
+        if tweens.cancelled.contains(&id) {
+            return false;
+        }
+        tweens.cancelled.push(id);
+        return true;
+    }
+    let before = tweens.timers.len();
+    tweens.timers.retain(|timer| timer.id != id);
+    tweens.timers.len() != before
+}
+
+impl Context {
+    /// Same as [`after`].
+    pub fn schedule_after(
+        &mut self,
+        delay: Duration,
+        callback: impl FnOnce(&mut Context) + 'static,
+    ) -> TimerId {
+        after(self, delay, callback)
+    }
+
+    /// Same as [`every`].
+    pub fn schedule_every(
+        &mut self,
+        interval: Duration,
+        callback: impl FnMut(&mut Context) + 'static,
+    ) -> TimerId {
+        every(self, interval, callback)
+    }
+}
+
+/// Advances every tween and timer by `dt`, running timers that came due in the order
+/// they were scheduled. Requests another frame while any are left, so they keep running
+/// in [`RedrawMode::Reactive`](crate::RedrawMode::Reactive).
+pub(crate) fn tick(ctx: &mut Context, dt: Duration) {
+    run_due(ctx, dt);
+    let tweens = &ctx.runtime.tweens;
+    if !tweens.tweens.is_empty() || !tweens.timers.is_empty() {
+        ctx.request_redraw();
+    }
+}
+
+fn run_due(ctx: &mut Context, dt: Duration) {
+    let tweens = &mut ctx.runtime.tweens;
+    tweens.tweens.retain_mut(|tween| tween.advance(dt));
+    if tweens.timers.is_empty() {
+        return;
+    }
+
+    // Take due timers out before running them so callbacks can schedule or cancel others.
+    let mut due = Vec::new();
+    for mut timer in std::mem::take(&mut tweens.timers) {
+        if dt < timer.remaining {
+            timer.remaining -= dt;
+            tweens.timers.push(timer);
+            continue;
+        }
+        let overshoot = dt - timer.remaining;
+        let mut runs = 1;
+        if let TimerCallback::Every { interval, .. } = timer.callback
+            && !interval.is_zero()
+        {
+            runs += overshoot.as_nanos() / interval.as_nanos();
+            let into_interval = overshoot.as_nanos() % interval.as_nanos();
+            timer.remaining = interval - Duration::from_nanos(into_interval as u64);
+        }
+        due.push((timer, runs));
+    }
+
+    // Due timers count as running until they ran, so an earlier callback can still
+    // cancel them.
+    tweens.running.extend(due.iter().map(|(timer, _)| timer.id));
+    for (timer, runs) in due {
+        let id = timer.id;
+        match timer.callback {
+            TimerCallback::Once(callback) => {
+                if !finish_running(&mut ctx.runtime.tweens, id) {
+                    callback(ctx);
+                }
+            }
+            TimerCallback::Every {
+                interval,
+                mut callback,
+            } => {
+                for _ in 0..runs {
+                    if ctx.runtime.tweens.cancelled.contains(&id) {
+                        break;
+                    }
+                    callback(ctx);
+                }
+                let tweens = &mut ctx.runtime.tweens;
+                if !finish_running(tweens, id) {
+                    let index = tweens.timers.partition_point(|other| other.id.0 < id.0);
+                    tweens.timers.insert(
+                        index,
+                        Timer {
+                            id,
+                            remaining: timer.remaining,
+                            callback: TimerCallback::Every { interval, callback },
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Marks a due timer as no longer running, returning true if it was cancelled.
+fn finish_running(tweens: &mut Tweens, id: TimerId) -> bool {
+    tweens.running.retain(|running| *running != id);
+    let before = tweens.cancelled.len();
+    tweens.cancelled.retain(|cancelled| *cancelled != id);
+    tweens.cancelled.len() != before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tween_follows_its_easing_and_ends_on_the_target_value() {
+        let mut ctx = Context::new();
+        let x = Tweened::new(0.0_f32);
+        tween(
+            &mut ctx,
+            &x,
+            10.0,
+            20.0,
+            Duration::from_millis(100),
+            Easing::Linear,
+        );
+        assert_eq!(x.get(), 10.0);
+
+        tick(&mut ctx, Duration::from_millis(25));
+        assert!((x.get() - 12.5).abs() < 1e-4);
+        assert!(is_tweening(&ctx, &x));
+
+        tick(&mut ctx, Duration::from_millis(100));
+        assert_eq!(x.get(), 20.0);
+        assert!(!is_tweening(&ctx, &x));
+    }
+
+    #[test]
+    fn tweens_stop_when_replaced_or_when_their_target_is_dropped() {
+        let mut ctx = Context::new();
+        let position = Tweened::new([Pt(0.0), Pt(0.0)]);
+        let step = Duration::from_millis(50);
+        tween(
+            &mut ctx,
+            &position,
+            [Pt(0.0), Pt(0.0)],
+            [Pt(100.0), Pt(0.0)],
+            step * 2,
+            Easing::OutCubic,
+        );
+        tween(
+            &mut ctx,
+            &position,
+            [Pt(0.0), Pt(0.0)],
+            [Pt(0.0), Pt(40.0)],
+            step * 2,
+            Easing::Linear,
+        );
+        tick(&mut ctx, step);
+        assert_eq!(position.get(), [Pt(0.0), Pt(20.0)]);
+
+        drop(position);
+        tick(&mut ctx, step);
+        assert!(ctx.runtime.tweens.tweens.is_empty());
+    }
+
+    #[test]
+    fn timers_fire_once_after_their_delay_unless_cancelled() {
+        let mut ctx = Context::new();
+        let fired = Rc::new(Cell::new(0));
+        let counter = fired.clone();
+        after(&mut ctx, Duration::from_millis(30), move |ctx| {
+            counter.set(counter.get() + 1);
+            let counter = counter.clone();
+            after(ctx, Duration::ZERO, move |_| {
+                counter.set(counter.get() + 10)
+            });
+        });
+        let cancelled = after(&mut ctx, Duration::from_millis(30), |_| unreachable!());
+        assert!(cancel_timer(&mut ctx, cancelled));
+
+        tick(&mut ctx, Duration::from_millis(20));
+        assert_eq!(fired.get(), 0);
+        tick(&mut ctx, Duration::from_millis(20));
+        assert_eq!(fired.get(), 1);
+        tick(&mut ctx, Duration::from_millis(20));
+        assert_eq!(fired.get(), 11);
+        assert!(ctx.runtime.tweens.timers.is_empty());
+    }
+
+    #[test]
+    fn pending_timers_keep_requesting_frames_until_they_ran() {
+        let mut ctx = Context::new();
+        after(&mut ctx, Duration::from_millis(30), |_| {});
+        assert!(ctx.take_redraw_request());
+        tick(&mut ctx, Duration::from_millis(20));
+        assert!(ctx.take_redraw_request());
+        tick(&mut ctx, Duration::from_millis(20));
+        assert!(!ctx.take_redraw_request());
+    }
+
+    #[test]
+    fn timers_cancelled_by_an_earlier_callback_in_the_same_tick_do_not_fire() {
+        let mut ctx = Context::new();
+        let later = Rc::new(Cell::new(None));
+        let to_cancel = later.clone();
+        after(&mut ctx, Duration::from_millis(10), move |ctx| {
+            assert!(cancel_timer(ctx, to_cancel.get().unwrap()));
+        });
+        later.set(Some(after(
+            &mut ctx,
+            Duration::from_millis(20),
+            |_| unreachable!(),
+        )));
+
+        tick(&mut ctx, Duration::from_millis(30));
+        assert!(!cancel_timer(&mut ctx, later.get().unwrap()));
+        assert!(ctx.runtime.tweens.running.is_empty());
+        assert!(ctx.runtime.tweens.cancelled.is_empty());
+    }
+
+    #[test]
+    fn repeating_timers_keep_pace_until_cancelled_from_their_callback() {
+        let mut ctx = Context::new();
+        let fired = Rc::new(Cell::new(0));
+        let counter = fired.clone();
+        let id = Rc::new(Cell::new(None));
+        let own_id = id.clone();
+        let timer = ctx.schedule_every(Duration::from_millis(10), move |ctx| {
+            counter.set(counter.get() + 1);
+            if counter.get() == 4 {
+                assert!(cancel_timer(ctx, own_id.get().unwrap()));
+            }
+        });
+        id.set(Some(timer));
+
+        tick(&mut ctx, Duration::from_millis(25));
+        assert_eq!(fired.get(), 2);
+        tick(&mut ctx, Duration::from_millis(4));
+        assert_eq!(fired.get(), 2);
+        tick(&mut ctx, Duration::from_millis(1));
+        assert_eq!(fired.get(), 3);
+        tick(&mut ctx, Duration::from_millis(50));
+        assert_eq!(fired.get(), 4);
+        assert!(ctx.runtime.tweens.timers.is_empty());
+        assert!(!cancel_timer(&mut ctx, timer));
+    }
+
+    #[test]
+    fn repeating_timers_cancelled_by_another_callback_stop_at_once() {
+        let mut ctx = Context::new();
+        let fired = Rc::new(Cell::new(0));
+        let counter = fired.clone();
+        let victim = Rc::new(Cell::new(None));
+        let to_cancel = victim.clone();
+        after(&mut ctx, Duration::from_millis(5), move |ctx| {
+            assert!(cancel_timer(ctx, to_cancel.get().unwrap()));
+        });
+        victim.set(Some(every(
+            &mut ctx,
+            Duration::from_millis(10),
+            move |_| counter.set(counter.get() + 1),
+        )));
+
+        tick(&mut ctx, Duration::from_millis(30));
+        assert_eq!(fired.get(), 0);
+        assert!(ctx.runtime.tweens.timers.is_empty());
+        assert!(!cancel_timer(&mut ctx, victim.get().unwrap()));
+    }
+}
@@ -0,0 +1,887 @@
+//! A small immediate-mode UI for menus and debug panels.
+//!
+//! Widgets are declared every `update` between [`Ui::begin`] and [`Ui::end`], each
+//! returning what the user did with it this step, and the last declared UI is rendered by
+//! [`Ui::draw`]. Widgets are placed with explicit [`Rect`]s and told apart by the order
+//! they are declared in, so keep that order stable between updates.
+//!
+//! Mouse and touch hover, press and click widgets. Tab and Shift+Tab move keyboard focus,
+//! Enter or Space activate the focused widget, and Left/Right move a focused slider.
+//! A focused [text box](Ui::text_box) takes typed and IME text instead.
+//!
+//! ```no_run
+//! # use spottedcat::{Context, Image, Rect, Spot, pt};
+//! # use spottedcat::ui::Ui;
+//! struct Menu {
+//!     ui: Ui,
+//!     volume: f32,
+//! }
+//!
+//! impl Spot for Menu {
+//!     fn initialize(ctx: &mut Context) -> Self {
+//!         let font = spottedcat::register_font(ctx, std::fs::read("font.ttf").unwrap());
+//!         Self { ui: Ui::new(font), volume: 0.5 }
+//!     }
+//!
+//!     fn update(&mut self, ctx: &mut Context, _dt: std::time::Duration) {
+//!         self.ui.begin(ctx);
+//!         if self.ui.button(ctx, "Play", Rect::new(pt(20.0), pt(20.0), pt(120.0), pt(32.0))) {
+//!             // start the game
+//!         }
+//!         let slider = Rect::new(pt(20.0), pt(60.0), pt(120.0), pt(24.0));
+//!         self.ui.slider(&mut self.volume, 0.0..=1.0, slider);
+//!         self.ui.end();
+//!     }
+//!
+//!     fn draw(&mut self, ctx: &mut Context, screen: Image) {
+//!         self.ui.draw(ctx, screen);
+//!     }
+//! }
+//! ```
+
+use crate::{Context, DrawOption, Image, Key, MouseButton, Pt, Rect, Text};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// How long the caret of a focused text box stays shown, then hidden.
+const CARET_BLINK: Duration = Duration::from_millis(530);
+
+/// Colors and sizes used to draw widgets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UiStyle {
+    pub font_size: Pt,
+    pub text: [f32; 4],
+    pub widget: [f32; 4],
+    pub hovered: [f32; 4],
+    pub pressed: [f32; 4],
+    /// Checkbox marks and slider fills.
+    pub accent: [f32; 4],
+    /// Outline of the focused widget.
+    pub focus: [f32; 4],
+    /// Space between a widget's edge and its contents.
+    pub padding: Pt,
+}
+
+impl Default for UiStyle {
+    fn default() -> Self {
+        Self {
+            font_size: Pt(16.0),
+            text: [1.0, 1.0, 1.0, 1.0],
+            widget: [0.22, 0.24, 0.28, 1.0],
+            hovered: [0.3, 0.33, 0.38, 1.0],
+            pressed: [0.16, 0.17, 0.2, 1.0],
+            accent: [0.35, 0.6, 0.95, 1.0],
+            focus: [0.95, 0.8, 0.3, 1.0],
+            padding: Pt(6.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Rect {
+        rect: Rect,
+        color: [f32; 4],
+    },
+    Text {
+        content: String,
+        position: [Pt; 2],
+        color: [f32; 4],
+    },
+}
+
+/// The text and caret of a [`Ui::text_box`], kept between steps.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextBox {
+    /// Shown dimmed while the text is empty.
+    pub placeholder: String,
+    /// Most characters the text may hold.
+    pub max_chars: Option<usize>,
+    text: String,
+    /// Caret position, in characters.
+    caret: usize,
+    /// First character shown, so the caret stays in view.
+    scroll: usize,
+    /// When the caret last moved; it blinks from then on.
+    blink_start: Duration,
+    /// Whether this box turned text input on.
+    editing: bool,
+    submitted: bool,
+}
+
+impl TextBox {
+    pub fn new(text: impl Into<String>) -> Self {
+        let mut field = Self::default();
+        field.set_text(text);
+        field
+    }
+
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text and moves the caret to its end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.caret = self.text.chars().count();
+    }
+
+    /// Caret position, in characters.
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Whether Enter was pressed in the box this step.
+    pub fn submitted(&self) -> bool {
+        self.submitted
+    }
+
+    /// Whether the box has keyboard focus and receives text input.
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(index, _)| index)
+    }
+
+    /// Inserts `typed` at the caret, up to `max_chars`; returns true if anything fit.
+    fn insert(&mut self, typed: &str) -> bool {
+        let room = self.max_chars.map_or(usize::MAX, |max| {
+            max.saturating_sub(self.text.chars().count())
+        });
+        let typed: String = typed.chars().take(room).collect();
+        if typed.is_empty() {
+            return false;
+        }
+        let index = self.byte_index(self.caret);
+        self.text.insert_str(index, &typed);
+        self.caret += typed.chars().count();
+        true
+    }
+
+    /// Removes the character after character position `at`, if any.
+    fn remove(&mut self, at: usize) -> bool {
+        if at >= self.text.chars().count() {
+            return false;
+        }
+        let index = self.byte_index(at);
+        self.text.remove(index);
+        true
+    }
+}
+
+/// What happened to a widget this step.
+#[derive(Debug, Clone, Copy, Default)]
+struct Interaction {
+    hovered: bool,
+    held: bool,
+    clicked: bool,
+    focused: bool,
+}
+
+/// Immediate-mode widget state; see the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Ui {
+    pub style: UiStyle,
+    font_id: u32,
+    commands: Vec<Command>,
+    next_id: u32,
+    /// Widget count of the last finished step, for wrapping keyboard focus.
+    widget_count: u32,
+    active: Option<u32>,
+    focus: Option<u32>,
+    pointer: Option<[Pt; 2]>,
+    pointer_pressed: bool,
+    pointer_released: bool,
+    touching: bool,
+    press_taken: bool,
+    activate: bool,
+    step: i32,
+    /// Whether a focused text box turned text input on this step.
+    text_input_claimed: bool,
+}
+
+impl Ui {
+    pub fn new(font_id: u32) -> Self {
+        Self {
+            style: UiStyle::default(),
+            font_id,
+            commands: Vec::new(),
+            next_id: 0,
+            widget_count: 0,
+            active: None,
+            focus: None,
+            pointer: None,
+            pointer_pressed: false,
+            pointer_released: false,
+            touching: false,
+            press_taken: false,
+            activate: false,
+            step: 0,
+            text_input_claimed: false,
+        }
+    }
+
+    pub fn with_style(mut self, style: UiStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Starts declaring this step's widgets and reads the input they react to.
+    pub fn begin(&mut self, ctx: &Context) {
+        self.commands.clear();
+        self.next_id = 0;
+        self.press_taken = false;
+        self.text_input_claimed = false;
+
+        let input = ctx.input();
+        // The first touch acts as the pointer; it disappears from the input when lifted.
+        let touch = input.touches().first().map(|touch| touch.position);
+        let was_touching = std::mem::replace(&mut self.touching, touch.is_some());
+        if let Some((x, y)) = touch {
+            self.pointer = Some([x, y]);
+        } else if !was_touching {
+            self.pointer = input.cursor_position().map(|(x, y)| [x, y]);
+        }
+        self.pointer_pressed =
+            input.mouse_pressed(MouseButton::Left) || (self.touching && !was_touching);
+        self.pointer_released =
+            input.mouse_released(MouseButton::Left) || (was_touching && !self.touching);
+
+        if input.key_pressed(Key::Tab) && self.widget_count > 0 {
+            let backwards = input.key_down(Key::LShift) || input.key_down(Key::RShift);
+            let count = self.widget_count;
+            self.focus = Some(match (self.focus, backwards) {
+                (Some(focus), false) => (focus + 1) % count,
+                (Some(focus), true) => (focus + count - 1) % count,
+                (None, false) => 0,
+                (None, true) => count - 1,
+            });
+        }
+        self.activate = input.key_pressed(Key::Enter) || input.key_pressed(Key::Space);
+        self.step = input.key_pressed(Key::Right) as i32 - input.key_pressed(Key::Left) as i32;
+    }
+
+    /// Finishes the step started by [`begin`](Self::begin).
+    pub fn end(&mut self) {
+        self.widget_count = self.next_id;
+        if self.pointer_released {
+            self.active = None;
+        }
+        // Clicking outside every widget drops keyboard focus.
+        if self.pointer_pressed && !self.press_taken {
+            self.focus = None;
+        }
+        if self.focus.is_some_and(|focus| focus >= self.widget_count) {
+            self.focus = None;
+        }
+    }
+
+    /// Whether a widget took this step's press or is being held, so a game can ignore
+    /// clicks the UI handled.
+    pub fn is_pointer_captured(&self) -> bool {
+        self.press_taken || self.active.is_some()
+    }
+
+    /// Renders the widgets declared in the last step into `target`.
+    pub fn draw(&self, ctx: &mut Context, target: Image) {
+        for command in &self.commands {
+            match command {
+                Command::Rect { rect, color } => {
+                    let options = DrawOption::new(
+                        [rect.x, rect.y],
+                        0.0,
+                        [rect.width.as_f32(), rect.height.as_f32()],
+                    )
+                    .with_tint(*color);
+                    let white = ctx.white_image();
+                    target.draw(ctx, &white, options);
+                }
+                Command::Text {
+                    content,
+                    position,
+                    color,
+                } => {
+                    let text = self.text(content.as_str()).with_color(*color);
+                    target.draw(ctx, &text, DrawOption::default().with_position(*position));
+                }
+            }
+        }
+    }
+
+    /// Text at `position`, its top-left corner.
+    pub fn label(&mut self, ctx: &Context, text: &str, position: [Pt; 2]) {
+        let (_, _, y_offset) = self.text(text).measure_with_y_offset(ctx);
+        self.commands.push(Command::Text {
+            content: text.to_owned(),
+            position: [position[0], position[1] + Pt(y_offset)],
+            color: self.style.text,
+        });
+    }
+
+    /// A button showing `label`; returns true when it is clicked or activated with the
+    /// keyboard.
+    pub fn button(&mut self, ctx: &Context, label: &str, rect: Rect) -> bool {
+        let interaction = self.interact(rect);
+        let color = if interaction.held {
+            self.style.pressed
+        } else if interaction.hovered || interaction.focused {
+            self.style.hovered
+        } else {
+            self.style.widget
+        };
+        self.frame(rect, color, interaction.focused);
+        self.centered_text(ctx, label, rect);
+        interaction.clicked
+    }
+
+    /// A box ticked when `checked`, followed by `label`; returns true when toggled.
+    pub fn checkbox(&mut self, ctx: &Context, label: &str, checked: &mut bool, rect: Rect) -> bool {
+        let interaction = self.interact(rect);
+        if interaction.clicked {
+            *checked = !*checked;
+        }
+        let side = rect.height;
+        let box_rect = Rect::new(rect.x, rect.y, side, side);
+        let color = if interaction.hovered || interaction.focused {
+            self.style.hovered
+        } else {
+            self.style.widget
+        };
+        self.frame(box_rect, color, interaction.focused);
+        if *checked {
+            let inset = self.style.padding.min(side / 4.0);
+            let mark = Rect::new(
+                rect.x + inset,
+                rect.y + inset,
+                side - inset * 2.0,
+                side - inset * 2.0,
+            );
+            self.fill(mark, self.style.accent);
+        }
+        let text_x = rect.x + side + self.style.padding;
+        let text = self.text(label);
+        let (_, height, y_offset) = text.measure_with_y_offset(ctx);
+        let text_y = rect.y + (rect.height - Pt(height)) / 2.0 + Pt(y_offset);
+        self.commands.push(Command::Text {
+            content: label.to_owned(),
+            position: [text_x, text_y],
+            color: self.style.text,
+        });
+        interaction.clicked
+    }
+
+    /// A horizontal slider for `value` within `range`; returns true when it changed.
+    ///
+    /// Dragging sets the value from the pointer, and Left/Right move a focused slider by a
+    /// twentieth of the range.
+    pub fn slider(&mut self, value: &mut f32, range: RangeInclusive<f32>, rect: Rect) -> bool {
+        let interaction = self.interact(rect);
+        let (min, max) = range.into_inner();
+        let before = *value;
+        if interaction.held
+            && let Some(pointer) = self.pointer
+            && rect.width > Pt(0.0)
+        {
+            let t = ((pointer[0] - rect.x) / rect.width.as_f32()).as_f32();
+            *value = min + (max - min) * t.clamp(0.0, 1.0);
+        }
+        if interaction.focused && self.step != 0 {
+            *value += (max - min) / 20.0 * self.step as f32;
+        }
+        *value = value.clamp(min.min(max), max.max(min));
+
+        let color = if interaction.held || interaction.hovered || interaction.focused {
+            self.style.hovered
+        } else {
+            self.style.widget
+        };
+        self.frame(rect, color, interaction.focused);
+        let t = if max == min {
+            0.0
+        } else {
+            (*value - min) / (max - min)
+        };
+        self.fill(
+            Rect::new(rect.x, rect.y, rect.width * t, rect.height),
+            self.style.accent,
+        );
+        *value != before
+    }
+
+    /// A single-line text field editing `field`; returns true when its text changed.
+    ///
+    /// Clicking or tabbing to the box focuses it and turns text input (and the IME) on
+    /// until focus moves away or Escape is pressed. Typed and committed IME text goes in
+    /// at the caret and uncommitted IME text is shown underlined there. Backspace and
+    /// Delete remove characters, Left, Right, Home and End move the caret, and Enter sets
+    /// [`TextBox::submitted`]. Text wider than the box scrolls to keep the caret in view.
+    pub fn text_box(&mut self, ctx: &mut Context, field: &mut TextBox, rect: Rect) -> bool {
+        let interaction = self.interact(rect);
+        let mut focused = interaction.focused;
+        if focused && ctx.input().key_pressed(Key::Escape) {
+            self.focus = None;
+            focused = false;
+        }
+        if focused {
+            // The caret blinks, so keep frames coming in reactive redraw mode.
+            ctx.request_redraw();
+            self.text_input_claimed = true;
+            if !field.editing {
+                field.editing = true;
+                ctx.input_mut().set_text_input_enabled(true);
+            }
+        } else if field.editing {
+            field.editing = false;
+            // Focus may have moved to a box declared earlier this step.
+            if !self.text_input_claimed {
+                ctx.input_mut().set_text_input_enabled(false);
+            }
+        }
+
+        let now = ctx.total_elapsed();
+        let inner_x = rect.x + self.style.padding;
+        let inner_width = rect.width - self.style.padding * 2.0;
+        field.submitted = false;
+        let mut changed = false;
+        let caret = field.caret;
+        if interaction.held
+            && self.pointer_pressed
+            && let Some(pointer) = self.pointer
+        {
+            let chars: Vec<char> = field.text.chars().collect();
+            field.caret = self.char_at(ctx, &chars, field.scroll, pointer[0] - inner_x);
+        }
+        if focused {
+            let input = ctx.input();
+            changed |= field.insert(input.text_input());
+            if input.key_pressed(Key::Backspace) && field.caret > 0 {
+                field.caret -= 1;
+                changed |= field.remove(field.caret);
+            }
+            if input.key_pressed(Key::Delete) {
+                changed |= field.remove(field.caret);
+            }
+            let len = field.text.chars().count();
+            if input.key_pressed(Key::Left) {
+                field.caret = field.caret.saturating_sub(1);
+            }
+            if input.key_pressed(Key::Right) {
+                field.caret = (field.caret + 1).min(len);
+            }
+            if input.key_pressed(Key::Home) {
+                field.caret = 0;
+            }
+            if input.key_pressed(Key::End) {
+                field.caret = len;
+            }
+            field.submitted = input.key_pressed(Key::Enter);
+        }
+        if changed || field.caret != caret {
+            field.blink_start = now;
+        }
+
+        let color = if interaction.hovered || focused {
+            self.style.hovered
+        } else {
+            self.style.widget
+        };
+        self.frame(rect, color, focused);
+
+        let preedit = if focused {
+            ctx.input().ime_preedit().unwrap_or_default()
+        } else {
+            ""
+        };
+        let (content, text_color) = if field.text.is_empty() && preedit.is_empty() && !focused {
+            let [r, g, b, a] = self.style.text;
+            (field.placeholder.as_str(), [r, g, b, a * 0.5])
+        } else {
+            (field.text.as_str(), self.style.text)
+        };
+        // The preedit is shown inside the text, with the caret after it.
+        let mut chars: Vec<char> = content.chars().collect();
+        let preedit_start = field.caret.min(chars.len());
+        let preedit_end = preedit_start + preedit.chars().count();
+        chars.splice(preedit_start..preedit_start, preedit.chars());
+        let caret = if focused { preedit_end } else { 0 };
+
+        // Scroll just far enough to keep the caret in view, and back when text shrinks.
+        field.scroll = field.scroll.min(caret);
+        while field.scroll < caret
+            && self.text_width(ctx, &chars[field.scroll..caret]) > inner_width
+        {
+            field.scroll += 1;
+        }
+        while field.scroll > 0 && self.text_width(ctx, &chars[field.scroll - 1..]) <= inner_width {
+            field.scroll -= 1;
+        }
+        let mut end = field.scroll;
+        while end < chars.len() && self.text_width(ctx, &chars[field.scroll..=end]) <= inner_width {
+            end += 1;
+        }
+
+        let visible: String = chars[field.scroll..end].iter().collect();
+        let line_height = self.style.font_size;
+        let line_y = rect.y + (rect.height - line_height) / 2.0;
+        let (_, height, y_offset) = self.text(&visible).measure_with_y_offset(ctx);
+        self.commands.push(Command::Text {
+            content: visible,
+            position: [
+                inner_x,
+                rect.y + (rect.height - Pt(height)) / 2.0 + Pt(y_offset),
+            ],
+            color: text_color,
+        });
+        if preedit_end > preedit_start {
+            let start = preedit_start.clamp(field.scroll, end);
+            let x = inner_x + self.text_width(ctx, &chars[field.scroll..start]);
+            let width = self.text_width(ctx, &chars[start..preedit_end.clamp(start, end)]);
+            self.fill(
+                Rect::new(x, line_y + line_height, width, Pt(1.0)),
+                self.style.text,
+            );
+        }
+        let blink = now.saturating_sub(field.blink_start).as_millis() / CARET_BLINK.as_millis();
+        if focused && blink.is_multiple_of(2) {
+            let x = inner_x + self.text_width(ctx, &chars[field.scroll..caret]);
+            self.fill(Rect::new(x, line_y, Pt(2.0), line_height), self.style.text);
+        }
+        changed
+    }
+
+    fn interact(&mut self, rect: Rect) -> Interaction {
+        let id = self.next_id;
+        self.next_id += 1;
+        let hovered = self
+            .pointer
+            .is_some_and(|pointer| rect.contains_point(pointer));
+        if self.pointer_pressed && hovered && !self.press_taken {
+            self.press_taken = true;
+            self.active = Some(id);
+            self.focus = Some(id);
+        }
+        let held = self.active == Some(id);
+        let focused = self.focus == Some(id);
+        let clicked = (held && hovered && self.pointer_released) || (focused && self.activate);
+        Interaction {
+            hovered,
+            held: held && !self.pointer_released,
+            clicked,
+            focused,
+        }
+    }
+
+    fn text(&self, content: &str) -> Text {
+        Text::new(content, self.font_id)
+            .with_font_size(self.style.font_size)
+            .with_color(self.style.text)
+    }
+
+    fn text_width(&self, ctx: &Context, chars: &[char]) -> Pt {
+        if chars.is_empty() {
+            return Pt(0.0);
+        }
+        let content: String = chars.iter().collect();
+        Pt(self.text(&content).measure(ctx).0)
+    }
+
+    /// The caret position closest to `x`, measured from where character `scroll` is
+    /// drawn.
+    fn char_at(&self, ctx: &Context, chars: &[char], scroll: usize, x: Pt) -> usize {
+        let scroll = scroll.min(chars.len());
+        let mut previous = Pt(0.0);
+        for end in scroll + 1..=chars.len() {
+            let width = self.text_width(ctx, &chars[scroll..end]);
+            if x < (previous + width) / 2.0 {
+                return end - 1;
+            }
+            previous = width;
+        }
+        chars.len()
+    }
+
+    fn centered_text(&mut self, ctx: &Context, content: &str, rect: Rect) {
+        let (width, height, y_offset) = self.text(content).measure_with_y_offset(ctx);
+        let [x, y] = rect.center();
+        self.commands.push(Command::Text {
+            content: content.to_owned(),
+            position: [x - Pt(width / 2.0), y - Pt(height / 2.0) + Pt(y_offset)],
+            color: self.style.text,
+        });
+    }
+
+    fn fill(&mut self, rect: Rect, color: [f32; 4]) {
+        self.commands.push(Command::Rect { rect, color });
+    }
+
+    /// A widget background, outlined when focused.
+    fn frame(&mut self, rect: Rect, color: [f32; 4], focused: bool) {
+        if focused {
+            let border = Pt(2.0);
+            self.fill(
+                Rect::new(
+                    rect.x - border,
+                    rect.y - border,
+                    rect.width + border * 2.0,
+                    rect.height + border * 2.0,
+                ),
+                self.style.focus,
+            );
+        }
+        self.fill(rect, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::event::{ElementState, MouseButton as WinitButton};
+    use winit::keyboard::{KeyCode, PhysicalKey};
+
+    fn rect(x: f32, y: f32) -> Rect {
+        Rect::new(Pt(x), Pt(y), Pt(100.0), Pt(20.0))
+    }
+
+    fn click(ctx: &mut Context, x: f32, y: f32, state: ElementState) {
+        ctx.input_mut().handle_cursor_moved(Pt(x), Pt(y));
+        ctx.input_mut().handle_mouse_input(state, WinitButton::Left);
+    }
+
+    fn press(ctx: &mut Context, key: KeyCode) {
+        let key = PhysicalKey::Code(key);
+        ctx.input_mut()
+            .handle_keyboard_input(ElementState::Pressed, key);
+        ctx.input_mut()
+            .handle_keyboard_input(ElementState::Released, key);
+    }
+
+    /// Declares a button, a checkbox and a slider, then ends the input frame.
+    fn step(ctx: &mut Context, ui: &mut Ui, checked: &mut bool, value: &mut f32) -> bool {
+        ui.begin(ctx);
+        let clicked = ui.button(ctx, "Play", rect(0.0, 0.0));
+        ui.checkbox(ctx, "Sound", checked, rect(0.0, 30.0));
+        ui.slider(value, 0.0..=10.0, rect(0.0, 60.0));
+        ui.end();
+        ctx.input_mut().end_frame();
+        clicked
+    }
+
+    #[test]
+    fn buttons_click_on_release_over_them() {
+        let mut ctx = Context::new();
+        let mut ui = Ui::new(0);
+        let (mut checked, mut value) = (false, 0.0);
+
+        click(&mut ctx, 10.0, 10.0, ElementState::Pressed);
+        assert!(!step(&mut ctx, &mut ui, &mut checked, &mut value));
+        assert!(ui.is_pointer_captured());
+        click(&mut ctx, 10.0, 10.0, ElementState::Released);
+        assert!(step(&mut ctx, &mut ui, &mut checked, &mut value));
+
+        // Pressing elsewhere and releasing over the button is not a click.
+        click(&mut ctx, 10.0, 150.0, ElementState::Pressed);
+        assert!(!step(&mut ctx, &mut ui, &mut checked, &mut value));
+        click(&mut ctx, 10.0, 10.0, ElementState::Released);
+        assert!(!step(&mut ctx, &mut ui, &mut checked, &mut value));
+
+        click(&mut ctx, 10.0, 35.0, ElementState::Pressed);
+        click(&mut ctx, 10.0, 35.0, ElementState::Released);
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        assert!(checked);
+    }
+
+    #[test]
+    fn sliders_follow_the_pointer_while_held() {
+        let mut ctx = Context::new();
+        let mut ui = Ui::new(0);
+        let (mut checked, mut value) = (false, 0.0);
+
+        click(&mut ctx, 25.0, 70.0, ElementState::Pressed);
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        assert_eq!(value, 2.5);
+
+        ctx.input_mut().handle_cursor_moved(Pt(500.0), Pt(300.0));
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        assert_eq!(value, 10.0);
+
+        click(&mut ctx, 500.0, 300.0, ElementState::Released);
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        ctx.input_mut().handle_cursor_moved(Pt(0.0), Pt(70.0));
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        assert_eq!(value, 10.0);
+    }
+
+    #[test]
+    fn keyboard_focus_cycles_and_activates_widgets() {
+        let mut ctx = Context::new();
+        let mut ui = Ui::new(0);
+        let (mut checked, mut value) = (false, 5.0);
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+
+        press(&mut ctx, KeyCode::Tab);
+        press(&mut ctx, KeyCode::Enter);
+        assert!(step(&mut ctx, &mut ui, &mut checked, &mut value));
+
+        press(&mut ctx, KeyCode::Tab);
+        press(&mut ctx, KeyCode::Space);
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        assert!(checked);
+
+        press(&mut ctx, KeyCode::Tab);
+        press(&mut ctx, KeyCode::ArrowLeft);
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        assert_eq!(value, 4.5);
+
+        // Focus wraps around to the first widget, and the UI is drawn with an outline.
+        press(&mut ctx, KeyCode::Tab);
+        step(&mut ctx, &mut ui, &mut checked, &mut value);
+        assert_eq!(ui.focus, Some(0));
+        assert!(ui.commands.contains(&Command::Rect {
+            rect: Rect::new(Pt(-2.0), Pt(-2.0), Pt(104.0), Pt(24.0)),
+            color: ui.style.focus,
+        }));
+    }
+
+    fn type_text(ctx: &mut Context, text: &str) {
+        for ch in text.chars() {
+            ctx.input_mut().handle_received_character(ch);
+        }
+    }
+
+    /// Declares two text boxes, then ends the input frame.
+    fn edit(ctx: &mut Context, ui: &mut Ui, fields: &mut [TextBox; 2]) -> [bool; 2] {
+        ui.begin(ctx);
+        let changed = [
+            ui.text_box(ctx, &mut fields[0], rect(0.0, 0.0)),
+            ui.text_box(ctx, &mut fields[1], rect(0.0, 30.0)),
+        ];
+        ui.end();
+        ctx.input_mut().end_frame();
+        changed
+    }
+
+    #[test]
+    fn text_boxes_edit_at_the_caret() {
+        let mut ctx = Context::new();
+        let mut ui = Ui::new(0);
+        let mut fields = [TextBox::new("").with_max_chars(4), TextBox::new("b")];
+
+        click(&mut ctx, 10.0, 10.0, ElementState::Pressed);
+        click(&mut ctx, 10.0, 10.0, ElementState::Released);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(fields[0].is_editing());
+        assert!(ctx.input().text_input_enabled());
+
+        type_text(&mut ctx, "héyo!");
+        assert_eq!(edit(&mut ctx, &mut ui, &mut fields), [true, false]);
+        assert_eq!(fields[0].text(), "héyo");
+
+        for key in [KeyCode::ArrowLeft, KeyCode::ArrowLeft, KeyCode::Backspace] {
+            press(&mut ctx, key);
+            edit(&mut ctx, &mut ui, &mut fields);
+        }
+        assert_eq!((fields[0].text(), fields[0].caret()), ("hyo", 1));
+
+        press(&mut ctx, KeyCode::Delete);
+        edit(&mut ctx, &mut ui, &mut fields);
+        press(&mut ctx, KeyCode::Home);
+        edit(&mut ctx, &mut ui, &mut fields);
+        type_text(&mut ctx, "o");
+        press(&mut ctx, KeyCode::Enter);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert_eq!(fields[0].text(), "oho");
+        assert!(fields[0].submitted());
+
+        press(&mut ctx, KeyCode::Escape);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(!fields[0].is_editing());
+        assert!(!ctx.input().text_input_enabled());
+        assert!(!fields[0].submitted());
+    }
+
+    #[test]
+    fn focus_moves_text_input_and_shows_the_preedit() {
+        let mut ctx = Context::new();
+        let mut ui = Ui::new(0);
+        let mut fields = [
+            TextBox::new("a"),
+            TextBox::new("ab").with_placeholder("Name"),
+        ];
+
+        click(&mut ctx, 10.0, 35.0, ElementState::Pressed);
+        click(&mut ctx, 10.0, 35.0, ElementState::Released);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(fields[1].is_editing());
+
+        // Tabbing backwards hands text input to the earlier box.
+        press(&mut ctx, KeyCode::ShiftLeft);
+        press(&mut ctx, KeyCode::Tab);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(fields[0].is_editing() && !fields[1].is_editing());
+        assert!(ctx.input().text_input_enabled());
+
+        ctx.input_mut()
+            .handle_ime(winit::event::Ime::Preedit("か".to_owned(), None));
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(ui.commands.iter().any(|command| matches!(
+            command,
+            Command::Text { content, .. } if content == "aか"
+        )));
+
+        ctx.input_mut()
+            .handle_ime(winit::event::Ime::Commit("か".to_owned()));
+        assert!(edit(&mut ctx, &mut ui, &mut fields)[0]);
+        assert_eq!(fields[0].text(), "aか");
+
+        // Clicking elsewhere turns text input off; empty boxes show their placeholder.
+        fields[1].set_text("");
+        click(&mut ctx, 10.0, 150.0, ElementState::Pressed);
+        edit(&mut ctx, &mut ui, &mut fields);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(!ctx.input().text_input_enabled());
+        assert!(ui.commands.iter().any(|command| matches!(
+            command,
+            Command::Text { content, .. } if content == "Name"
+        )));
+    }
+
+    #[test]
+    fn the_caret_blinks_and_resets_when_it_moves() {
+        let mut ctx = Context::new();
+        let mut ui = Ui::new(0);
+        let mut fields = [TextBox::new("hi"), TextBox::new("")];
+        let caret_shown = |ui: &Ui| {
+            ui.commands.iter().any(
+                |command| matches!(command, Command::Rect { rect, .. } if rect.width == Pt(2.0)),
+            )
+        };
+
+        edit(&mut ctx, &mut ui, &mut fields);
+        press(&mut ctx, KeyCode::Tab);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(caret_shown(&ui));
+
+        ctx.set_delta_time(CARET_BLINK);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(!caret_shown(&ui));
+
+        press(&mut ctx, KeyCode::ArrowLeft);
+        edit(&mut ctx, &mut ui, &mut fields);
+        assert!(caret_shown(&ui));
+    }
+}
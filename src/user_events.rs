@@ -0,0 +1,132 @@
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+/// A value sent to the running scene through an [`EventSender`].
+///
+/// Delivered on the main thread via [`Spot::user_event`](crate::Spot::user_event).
+pub struct UserEvent {
+    value: Box<dyn Any + Send>,
+}
+
+impl UserEvent {
+    /// Returns `true` if the event carries a value of type `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        self.value.is::<T>()
+    }
+
+    /// Returns a reference to the value if it is of type `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+
+    /// Takes the value out if it is of type `T`, or returns the event unchanged.
+    pub fn downcast<T: Any>(self) -> Result<T, Self> {
+        self.value
+            .downcast::<T>()
+            .map(|value| *value)
+            .map_err(|value| Self { value })
+    }
+}
+
+impl std::fmt::Debug for UserEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserEvent").finish_non_exhaustive()
+    }
+}
+
+/// Sends values of type `T` to the running scene from any thread.
+///
+/// Sending wakes the event loop, so background work such as network or file IO can
+/// hand its results to the scene without the scene polling shared state every frame.
+pub struct EventSender<T> {
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for EventSender<T> {}
+
+impl<T> std::fmt::Debug for EventSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSender")
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T: Send + 'static> EventSender<T> {
+    /// Queues `value` for delivery and wakes the event loop.
+    ///
+    /// Values sent before the application starts are delivered once the first scene exists.
+    pub fn send(&self, value: T) {
+        let mutex = PENDING_EVENTS.get_or_init(|| Mutex::new(Vec::new()));
+        if let Ok(mut events) = mutex.lock() {
+            events.push(UserEvent {
+                value: Box::new(value),
+            });
+        }
+        wake();
+    }
+}
+
+/// Returns a sender that delivers values of type `T` to [`Spot::user_event`](crate::Spot::user_event).
+pub fn event_sender<T: Send + 'static>() -> EventSender<T> {
+    EventSender {
+        _marker: PhantomData,
+    }
+}
+
+type Waker = Box<dyn Fn() + Send>;
+
+static PENDING_EVENTS: OnceLock<Mutex<Vec<UserEvent>>> = OnceLock::new();
+static WAKER: OnceLock<Mutex<Option<Waker>>> = OnceLock::new();
+
+/// Installs the function used to wake the platform event loop after a send.
+pub(crate) fn set_waker(waker: Option<Waker>) {
+    let mutex = WAKER.get_or_init(|| Mutex::new(None));
+    if let Ok(mut slot) = mutex.lock() {
+        *slot = waker;
+    }
+}
+
+pub(crate) fn wake() {
+    if let Some(mutex) = WAKER.get()
+        && let Ok(waker) = mutex.lock()
+        && let Some(waker) = waker.as_ref()
+    {
+        waker();
+    }
+}
+
+/// Drains all pending user events.
+pub(crate) fn take_events() -> Vec<UserEvent> {
+    if let Some(mutex) = PENDING_EVENTS.get()
+        && let Ok(mut events) = mutex.lock()
+    {
+        return std::mem::take(&mut *events);
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_event_downcasts_to_sent_type() {
+        let event = UserEvent {
+            value: Box::new(42u32),
+        };
+        assert!(event.is::<u32>());
+        assert_eq!(event.downcast_ref::<u32>(), Some(&42));
+
+        let event = event.downcast::<String>().expect_err("not a String");
+        assert_eq!(event.downcast::<u32>().ok(), Some(42));
+    }
+}
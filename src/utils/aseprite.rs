@@ -0,0 +1,826 @@
+//! Imports sprites saved by [Aseprite](https://www.aseprite.org) (`.aseprite`, `.ase`).
+//!
+//! Every frame is flattened into one [`Image`] from its visible layers, in RGBA,
+//! grayscale or indexed color. Layer blend modes other than normal are drawn as normal,
+//! and tilemap layers are skipped. Tags become [`Animation`]s and slices keep their
+//! bounds, nine-slice centers and pivots.
+
+use crate::{Animation, Bounds, Context, Image, LoopMode, Pt};
+use anyhow::{Context as _, anyhow, bail, ensure};
+use std::io::Read;
+use std::time::Duration;
+
+const HEADER_MAGIC: u16 = 0xa5e0;
+const FRAME_MAGIC: u16 = 0xf1fa;
+
+const CHUNK_OLD_PALETTE: u16 = 0x0004;
+const CHUNK_LAYER: u16 = 0x2004;
+const CHUNK_CEL: u16 = 0x2005;
+const CHUNK_TAGS: u16 = 0x2018;
+const CHUNK_PALETTE: u16 = 0x2019;
+const CHUNK_SLICE: u16 = 0x2022;
+
+/// A sprite loaded from an Aseprite file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aseprite {
+    /// Every frame with how long it is shown.
+    pub frames: Vec<(Image, Duration)>,
+    pub tags: Vec<AsepriteTag>,
+    pub slices: Vec<AsepriteSlice>,
+}
+
+impl Aseprite {
+    /// Plays every frame in order, looping.
+    pub fn animation(&self) -> Animation {
+        Animation::from_frames(self.frames.clone())
+    }
+
+    /// The first tag called `name`.
+    pub fn tag(&self, name: &str) -> Option<&AsepriteTag> {
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+
+    /// Plays the frames of the tag called `name` in its direction.
+    ///
+    /// Tags set to play once become [`LoopMode::Once`]; any other repeat count loops
+    /// forever.
+    pub fn tag_animation(&self, name: &str) -> Option<Animation> {
+        let tag = self.tag(name)?;
+        let mut frames = self.frames.get(tag.from..=tag.to)?.to_vec();
+        if matches!(
+            tag.direction,
+            TagDirection::Reverse | TagDirection::PingPongReverse
+        ) {
+            frames.reverse();
+        }
+        let mode = match tag.direction {
+            TagDirection::PingPong | TagDirection::PingPongReverse => LoopMode::PingPong,
+            _ if tag.repeat == 1 => LoopMode::Once,
+            _ => LoopMode::Loop,
+        };
+        Some(Animation::from_frames(frames).with_mode(mode))
+    }
+
+    /// The first slice called `name`.
+    pub fn slice(&self, name: &str) -> Option<&AsepriteSlice> {
+        self.slices.iter().find(|slice| slice.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagDirection {
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+/// A named range of frames.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsepriteTag {
+    pub name: String,
+    /// First and last frame, both included.
+    pub from: usize,
+    pub to: usize,
+    pub direction: TagDirection,
+    /// How often the tag plays; `0` means forever.
+    pub repeat: u16,
+}
+
+/// A named region of the sprite, such as a hitbox or a nine-slice panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsepriteSlice {
+    pub name: String,
+    /// The slice from each frame on where it changes, in frame order.
+    pub keys: Vec<SliceKey>,
+}
+
+impl AsepriteSlice {
+    /// The key in effect at `frame`.
+    pub fn key_at(&self, frame: usize) -> Option<&SliceKey> {
+        self.keys.iter().rev().find(|key| key.frame <= frame)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SliceKey {
+    /// First frame this key applies to.
+    pub frame: usize,
+    pub bounds: Bounds,
+    /// Center of a nine-slice, relative to `bounds`.
+    pub center: Option<Bounds>,
+    /// Pivot point, relative to `bounds`.
+    pub pivot: Option<[Pt; 2]>,
+}
+
+/// Loads an Aseprite file from a byte slice.
+pub fn load_aseprite_from_bytes(ctx: &mut Context, data: &[u8]) -> anyhow::Result<Aseprite> {
+    let document = parse(data).context("invalid Aseprite file")?;
+    let mut frames = Vec::with_capacity(document.frames.len());
+    for (index, frame) in document.frames.iter().enumerate() {
+        let pixels = document.flatten(index);
+        let image = Image::new(
+            ctx,
+            Pt(document.width as f32),
+            Pt(document.height as f32),
+            &pixels,
+        )?;
+        frames.push((image, frame.duration));
+    }
+    Ok(Aseprite {
+        frames,
+        tags: document.tags,
+        slices: document.slices,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorDepth {
+    Rgba,
+    Grayscale,
+    Indexed,
+}
+
+impl ColorDepth {
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            ColorDepth::Rgba => 4,
+            ColorDepth::Grayscale => 2,
+            ColorDepth::Indexed => 1,
+        }
+    }
+}
+
+struct Layer {
+    visible: bool,
+    opacity: u8,
+    kind: LayerKind,
+    /// Nesting depth inside groups; a layer belongs to the closest group above it with a
+    /// lower level.
+    level: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LayerKind {
+    Image,
+    Group,
+    Tilemap,
+}
+
+struct Cel {
+    layer: usize,
+    position: [i32; 2],
+    opacity: u8,
+    z_index: i16,
+    content: CelContent,
+}
+
+enum CelContent {
+    Pixels {
+        width: usize,
+        height: usize,
+        /// Pixels in the file's color depth.
+        data: Vec<u8>,
+    },
+    /// Reuses the cel of the same layer in another frame.
+    Linked(usize),
+}
+
+struct Frame {
+    duration: Duration,
+    cels: Vec<Cel>,
+}
+
+struct Document {
+    width: usize,
+    height: usize,
+    depth: ColorDepth,
+    transparent_index: u8,
+    palette: Vec<[u8; 4]>,
+    layers: Vec<Layer>,
+    frames: Vec<Frame>,
+    tags: Vec<AsepriteTag>,
+    slices: Vec<AsepriteSlice>,
+}
+
+impl Document {
+    /// Composites the visible layers of frame `index` into RGBA pixels.
+    fn flatten(&self, index: usize) -> Vec<u8> {
+        let mut canvas = vec![0u8; self.width * self.height * 4];
+        let mut cels: Vec<&Cel> = self.frames[index].cels.iter().collect();
+        // Aseprite orders cels by layer index plus z-index, the z-index breaking ties.
+        cels.sort_by_key(|cel| (cel.layer as i32 + cel.z_index as i32, cel.z_index));
+        for cel in cels {
+            let Some(layer) = self.layers.get(cel.layer) else {
+                continue;
+            };
+            if !layer.visible || layer.kind != LayerKind::Image {
+                continue;
+            }
+            let Some((width, height, data)) = self.cel_pixels(index, cel) else {
+                continue;
+            };
+            let opacity = cel.opacity as u32 * layer.opacity as u32;
+            self.blend_cel(&mut canvas, cel.position, width, height, data, opacity);
+        }
+        canvas
+    }
+
+    fn cel_pixels<'a>(&'a self, frame: usize, cel: &'a Cel) -> Option<(usize, usize, &'a [u8])> {
+        match &cel.content {
+            CelContent::Pixels {
+                width,
+                height,
+                data,
+            } => Some((*width, *height, data)),
+            // Follow the link to the frame holding the pixels, refusing to loop.
+            CelContent::Linked(target) if *target != frame => {
+                let linked = self.frames.get(*target)?;
+                let cel = linked.cels.iter().find(|other| other.layer == cel.layer)?;
+                match &cel.content {
+                    CelContent::Pixels {
+                        width,
+                        height,
+                        data,
+                    } => Some((*width, *height, data)),
+                    CelContent::Linked(_) => None,
+                }
+            }
+            CelContent::Linked(_) => None,
+        }
+    }
+
+    /// Draws a cel over `canvas` with normal blending; `opacity` is out of `255 * 255`.
+    fn blend_cel(
+        &self,
+        canvas: &mut [u8],
+        position: [i32; 2],
+        width: usize,
+        height: usize,
+        data: &[u8],
+        opacity: u32,
+    ) {
+        let bytes = self.depth.bytes_per_pixel();
+        for y in 0..height {
+            let canvas_y = position[1] + y as i32;
+            if canvas_y < 0 || canvas_y >= self.height as i32 {
+                continue;
+            }
+            for x in 0..width {
+                let canvas_x = position[0] + x as i32;
+                if canvas_x < 0 || canvas_x >= self.width as i32 {
+                    continue;
+                }
+                let offset = (y * width + x) * bytes;
+                let Some(pixel) = data.get(offset..offset + bytes) else {
+                    return;
+                };
+                let [r, g, b, a] = self.rgba(pixel);
+                let alpha = a as u32 * opacity / (255 * 255);
+                if alpha == 0 {
+                    continue;
+                }
+                let index = (canvas_y as usize * self.width + canvas_x as usize) * 4;
+                let dst = &mut canvas[index..index + 4];
+                let dst_alpha = dst[3] as u32 * (255 - alpha) / 255;
+                let out_alpha = alpha + dst_alpha;
+                for (channel, src) in dst[..3].iter_mut().zip([r, g, b]) {
+                    *channel =
+                        ((src as u32 * alpha + *channel as u32 * dst_alpha) / out_alpha) as u8;
+                }
+                dst[3] = out_alpha as u8;
+            }
+        }
+    }
+
+    fn rgba(&self, pixel: &[u8]) -> [u8; 4] {
+        match self.depth {
+            ColorDepth::Rgba => [pixel[0], pixel[1], pixel[2], pixel[3]],
+            ColorDepth::Grayscale => [pixel[0], pixel[0], pixel[0], pixel[1]],
+            ColorDepth::Indexed => {
+                if pixel[0] == self.transparent_index {
+                    [0; 4]
+                } else {
+                    self.palette
+                        .get(pixel[0] as usize)
+                        .copied()
+                        .unwrap_or([0; 4])
+                }
+            }
+        }
+    }
+}
+
+/// Little-endian reader over a byte slice.
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| anyhow!("unexpected end of data"))?;
+        let bytes = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> anyhow::Result<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> anyhow::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> anyhow::Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into()?))
+    }
+
+    fn i16(&mut self) -> anyhow::Result<i16> {
+        Ok(i16::from_le_bytes(self.bytes(2)?.try_into()?))
+    }
+
+    fn u32(&mut self) -> anyhow::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into()?))
+    }
+
+    fn i32(&mut self) -> anyhow::Result<i32> {
+        Ok(i32::from_le_bytes(self.bytes(4)?.try_into()?))
+    }
+
+    fn string(&mut self) -> anyhow::Result<String> {
+        let len = self.u16()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(len)?).into_owned())
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.offset..];
+        self.offset = self.data.len();
+        rest
+    }
+}
+
+fn parse(data: &[u8]) -> anyhow::Result<Document> {
+    let mut reader = Reader::new(data);
+    reader.skip(4)?;
+    ensure!(reader.u16()? == HEADER_MAGIC, "not an Aseprite file");
+    let frame_count = reader.u16()? as usize;
+    let width = reader.u16()? as usize;
+    let height = reader.u16()? as usize;
+    let depth = match reader.u16()? {
+        32 => ColorDepth::Rgba,
+        16 => ColorDepth::Grayscale,
+        8 => ColorDepth::Indexed,
+        other => bail!("unsupported color depth {other}"),
+    };
+    let header_flags = reader.u32()?;
+    reader.skip(2 + 4 + 4)?;
+    let transparent_index = reader.u8()?;
+    reader.skip(3)?;
+    let color_count = reader.u16()? as usize;
+    reader.skip(128 - 34)?;
+
+    let mut document = Document {
+        width,
+        height,
+        depth,
+        transparent_index,
+        palette: vec![[0; 4]; color_count.max(256)],
+        layers: Vec::new(),
+        frames: Vec::with_capacity(frame_count),
+        tags: Vec::new(),
+        slices: Vec::new(),
+    };
+    // Older files may leave the opacity fields unset; the header flag says they hold data.
+    let layer_opacity_valid = header_flags & 1 != 0;
+
+    for frame_index in 0..frame_count {
+        let frame_start = reader.offset;
+        let frame_size = reader.u32()? as usize;
+        ensure!(
+            reader.u16()? == FRAME_MAGIC,
+            "frame {frame_index} is corrupt"
+        );
+        let old_chunk_count = reader.u16()? as usize;
+        let duration = Duration::from_millis(reader.u16()?.into());
+        reader.skip(2)?;
+        let chunk_count = match reader.u32()? as usize {
+            0 => old_chunk_count,
+            count => count,
+        };
+        let mut frame = Frame {
+            duration,
+            cels: Vec::new(),
+        };
+        for _ in 0..chunk_count {
+            let chunk_size = reader.u32()? as usize;
+            ensure!(chunk_size >= 6, "chunk in frame {frame_index} is too small");
+            let chunk_type = reader.u16()?;
+            let mut chunk = Reader::new(reader.bytes(chunk_size - 6)?);
+            match chunk_type {
+                CHUNK_LAYER => document
+                    .layers
+                    .push(parse_layer(&mut chunk, layer_opacity_valid)?),
+                CHUNK_CEL => {
+                    if let Some(cel) = parse_cel(&mut chunk, depth)? {
+                        frame.cels.push(cel);
+                    }
+                }
+                CHUNK_PALETTE => parse_palette(&mut chunk, &mut document.palette)?,
+                CHUNK_OLD_PALETTE => parse_old_palette(&mut chunk, &mut document.palette)?,
+                CHUNK_TAGS => document.tags = parse_tags(&mut chunk)?,
+                CHUNK_SLICE => document.slices.push(parse_slice(&mut chunk)?),
+                _ => {}
+            }
+        }
+        reader.offset = frame_start + frame_size;
+        document.frames.push(frame);
+    }
+    apply_group_visibility(&mut document.layers);
+    Ok(document)
+}
+
+fn parse_layer(chunk: &mut Reader, opacity_valid: bool) -> anyhow::Result<Layer> {
+    let flags = chunk.u16()?;
+    let kind = match chunk.u16()? {
+        0 => LayerKind::Image,
+        1 => LayerKind::Group,
+        _ => LayerKind::Tilemap,
+    };
+    let level = chunk.u16()?;
+    chunk.skip(2 + 2 + 2)?;
+    let opacity = chunk.u8()?;
+    Ok(Layer {
+        visible: flags & 1 != 0,
+        opacity: if opacity_valid { opacity } else { 255 },
+        kind,
+        level,
+    })
+}
+
+fn parse_cel(chunk: &mut Reader, depth: ColorDepth) -> anyhow::Result<Option<Cel>> {
+    let layer = chunk.u16()? as usize;
+    let position = [chunk.i16()? as i32, chunk.i16()? as i32];
+    let opacity = chunk.u8()?;
+    let cel_type = chunk.u16()?;
+    let z_index = chunk.i16()?;
+    chunk.skip(5)?;
+    let content = match cel_type {
+        0 | 2 => {
+            let width = chunk.u16()? as usize;
+            let height = chunk.u16()? as usize;
+            let len = width * height * depth.bytes_per_pixel();
+            let data = if cel_type == 0 {
+                chunk.bytes(len)?.to_vec()
+            } else {
+                let mut data = Vec::with_capacity(len);
+                flate2::read::ZlibDecoder::new(chunk.rest())
+                    .take(len as u64)
+                    .read_to_end(&mut data)
+                    .context("invalid compressed cel")?;
+                data
+            };
+            ensure!(data.len() == len, "cel is missing pixels");
+            CelContent::Pixels {
+                width,
+                height,
+                data,
+            }
+        }
+        1 => CelContent::Linked(chunk.u16()? as usize),
+        _ => return Ok(None),
+    };
+    Ok(Some(Cel {
+        layer,
+        position,
+        opacity,
+        z_index,
+        content,
+    }))
+}
+
+fn parse_palette(chunk: &mut Reader, palette: &mut Vec<[u8; 4]>) -> anyhow::Result<()> {
+    let size = chunk.u32()? as usize;
+    let first = chunk.u32()? as usize;
+    let last = chunk.u32()? as usize;
+    chunk.skip(8)?;
+    if palette.len() < size {
+        palette.resize(size, [0; 4]);
+    }
+    for index in first..=last {
+        let flags = chunk.u16()?;
+        let color = [chunk.u8()?, chunk.u8()?, chunk.u8()?, chunk.u8()?];
+        if flags & 1 != 0 {
+            chunk.string()?;
+        }
+        if let Some(entry) = palette.get_mut(index) {
+            *entry = color;
+        }
+    }
+    Ok(())
+}
+
+fn parse_old_palette(chunk: &mut Reader, palette: &mut [[u8; 4]]) -> anyhow::Result<()> {
+    let packets = chunk.u16()?;
+    let mut index = 0usize;
+    for _ in 0..packets {
+        index += chunk.u8()? as usize;
+        let count = match chunk.u8()? {
+            0 => 256,
+            count => count as usize,
+        };
+        for _ in 0..count {
+            let color = [chunk.u8()?, chunk.u8()?, chunk.u8()?, 255];
+            if let Some(entry) = palette.get_mut(index) {
+                *entry = color;
+            }
+            index += 1;
+        }
+    }
+    Ok(())
+}
+
+fn parse_tags(chunk: &mut Reader) -> anyhow::Result<Vec<AsepriteTag>> {
+    let count = chunk.u16()?;
+    chunk.skip(8)?;
+    (0..count)
+        .map(|_| {
+            let from = chunk.u16()? as usize;
+            let to = chunk.u16()? as usize;
+            let direction = match chunk.u8()? {
+                1 => TagDirection::Reverse,
+                2 => TagDirection::PingPong,
+                3 => TagDirection::PingPongReverse,
+                _ => TagDirection::Forward,
+            };
+            let repeat = chunk.u16()?;
+            chunk.skip(6 + 3 + 1)?;
+            Ok(AsepriteTag {
+                name: chunk.string()?,
+                from,
+                to,
+                direction,
+                repeat,
+            })
+        })
+        .collect()
+}
+
+fn parse_slice(chunk: &mut Reader) -> anyhow::Result<AsepriteSlice> {
+    let key_count = chunk.u32()?;
+    let flags = chunk.u32()?;
+    chunk.skip(4)?;
+    let name = chunk.string()?;
+    let bounds = |chunk: &mut Reader| -> anyhow::Result<Bounds> {
+        Ok(Bounds::new(
+            Pt(chunk.i32()? as f32),
+            Pt(chunk.i32()? as f32),
+            Pt(chunk.u32()? as f32),
+            Pt(chunk.u32()? as f32),
+        ))
+    };
+    let keys = (0..key_count)
+        .map(|_| {
+            let frame = chunk.u32()? as usize;
+            let slice_bounds = bounds(chunk)?;
+            let center = if flags & 1 != 0 {
+                Some(bounds(chunk)?)
+            } else {
+                None
+            };
+            let pivot = if flags & 2 != 0 {
+                Some([Pt(chunk.i32()? as f32), Pt(chunk.i32()? as f32)])
+            } else {
+                None
+            };
+            Ok(SliceKey {
+                frame,
+                bounds: slice_bounds,
+                center,
+                pivot,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(AsepriteSlice { name, keys })
+}
+
+/// Hides the layers inside hidden groups.
+fn apply_group_visibility(layers: &mut [Layer]) {
+    // Visibility of the enclosing groups, outermost first.
+    let mut groups: Vec<bool> = Vec::new();
+    for layer in layers {
+        groups.truncate(layer.level as usize);
+        layer.visible &= groups.iter().all(|&visible| visible);
+        if layer.kind == LayerKind::Group {
+            groups.push(layer.visible);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// A chunk type and its data.
+    type Chunk = (u16, Vec<u8>);
+
+    /// Builds Aseprite files chunk by chunk.
+    #[derive(Default)]
+    struct Builder {
+        frames: Vec<(u16, Vec<Chunk>)>,
+    }
+
+    impl Builder {
+        fn frame(mut self, duration: u16) -> Self {
+            self.frames.push((duration, Vec::new()));
+            self
+        }
+
+        fn chunk(mut self, chunk_type: u16, data: Vec<u8>) -> Self {
+            self.frames.last_mut().unwrap().1.push((chunk_type, data));
+            self
+        }
+
+        fn layer(self, name: &str, visible: bool, layer_type: u16, level: u16) -> Self {
+            let mut data = Vec::new();
+            data.extend((visible as u16).to_le_bytes());
+            data.extend(layer_type.to_le_bytes());
+            data.extend(level.to_le_bytes());
+            data.extend([0; 6]);
+            data.push(255);
+            data.extend([0; 3]);
+            data.extend(string(name));
+            self.chunk(CHUNK_LAYER, data)
+        }
+
+        fn cel(self, layer: u16, position: [i16; 2], size: [u16; 2], pixels: &[u8]) -> Self {
+            let mut data = cel_header(layer, position, 2);
+            data.extend(size[0].to_le_bytes());
+            data.extend(size[1].to_le_bytes());
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(pixels).unwrap();
+            data.extend(encoder.finish().unwrap());
+            self.chunk(CHUNK_CEL, data)
+        }
+
+        fn linked_cel(self, layer: u16, frame: u16) -> Self {
+            let mut data = cel_header(layer, [0, 0], 1);
+            data.extend(frame.to_le_bytes());
+            self.chunk(CHUNK_CEL, data)
+        }
+
+        fn build(self, size: [u16; 2]) -> Vec<u8> {
+            let mut file = Vec::new();
+            file.extend(0u32.to_le_bytes());
+            file.extend(HEADER_MAGIC.to_le_bytes());
+            file.extend((self.frames.len() as u16).to_le_bytes());
+            file.extend(size[0].to_le_bytes());
+            file.extend(size[1].to_le_bytes());
+            file.extend(32u16.to_le_bytes());
+            file.extend(1u32.to_le_bytes());
+            file.resize(128, 0);
+            for (duration, chunks) in self.frames {
+                let mut body = Vec::new();
+                for (chunk_type, data) in &chunks {
+                    body.extend((data.len() as u32 + 6).to_le_bytes());
+                    body.extend(chunk_type.to_le_bytes());
+                    body.extend(data);
+                }
+                file.extend((body.len() as u32 + 16).to_le_bytes());
+                file.extend(FRAME_MAGIC.to_le_bytes());
+                file.extend((chunks.len() as u16).to_le_bytes());
+                file.extend(duration.to_le_bytes());
+                file.extend([0; 2]);
+                file.extend((chunks.len() as u32).to_le_bytes());
+                file.extend(body);
+            }
+            file
+        }
+    }
+
+    fn string(text: &str) -> Vec<u8> {
+        let mut data = (text.len() as u16).to_le_bytes().to_vec();
+        data.extend(text.as_bytes());
+        data
+    }
+
+    fn cel_header(layer: u16, position: [i16; 2], cel_type: u16) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(layer.to_le_bytes());
+        data.extend(position[0].to_le_bytes());
+        data.extend(position[1].to_le_bytes());
+        data.push(255);
+        data.extend(cel_type.to_le_bytes());
+        data.extend(0i16.to_le_bytes());
+        data.extend([0; 5]);
+        data
+    }
+
+    fn tags(tags: &[(&str, u16, u16, u8, u16)]) -> Vec<u8> {
+        let mut data = (tags.len() as u16).to_le_bytes().to_vec();
+        data.extend([0; 8]);
+        for &(name, from, to, direction, repeat) in tags {
+            data.extend(from.to_le_bytes());
+            data.extend(to.to_le_bytes());
+            data.push(direction);
+            data.extend(repeat.to_le_bytes());
+            data.extend([0; 10]);
+            data.extend(string(name));
+        }
+        data
+    }
+
+    fn slice_with_pivot(name: &str, bounds: [i32; 4], pivot: [i32; 2]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(1u32.to_le_bytes());
+        data.extend(2u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend(string(name));
+        data.extend(0u32.to_le_bytes());
+        for value in bounds.into_iter().chain(pivot) {
+            data.extend(value.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn frames_are_flattened_from_visible_layers() {
+        const RED: [u8; 4] = [255, 0, 0, 255];
+        const HALF_BLUE: [u8; 4] = [0, 0, 255, 128];
+        let file = Builder::default()
+            .frame(100)
+            .layer("back", true, 0, 0)
+            .layer("hidden group", false, 1, 0)
+            .layer("hidden child", true, 0, 1)
+            .layer("front", true, 0, 0)
+            .cel(0, [0, 0], [2, 1], &[RED, RED].concat())
+            .cel(2, [0, 0], [2, 1], &[RED, RED].concat())
+            .cel(3, [1, 0], [1, 1], &HALF_BLUE)
+            .frame(50)
+            .linked_cel(0, 0)
+            .build([2, 1]);
+
+        let document = parse(&file).unwrap();
+        assert!(!document.layers[2].visible);
+        assert_eq!(document.flatten(0), [255, 0, 0, 255, 127, 0, 128, 255]);
+        assert_eq!(document.flatten(1), [RED, RED].concat());
+        assert_eq!(document.frames[1].duration, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn tags_and_slices_are_loaded() {
+        let file = Builder::default()
+            .frame(100)
+            .layer("sprite", true, 0, 0)
+            .chunk(
+                CHUNK_TAGS,
+                tags(&[
+                    ("walk", 0, 2, 0, 0),
+                    ("hit", 1, 2, 1, 1),
+                    ("idle", 0, 1, 2, 0),
+                ]),
+            )
+            .chunk(CHUNK_SLICE, slice_with_pivot("feet", [1, 2, 3, 4], [2, 4]))
+            .frame(100)
+            .frame(100)
+            .build([2, 2]);
+        let mut ctx = Context::new();
+        let sprite = load_aseprite_from_bytes(&mut ctx, &file).unwrap();
+
+        assert_eq!(sprite.frames.len(), 3);
+        let hit = sprite.tag_animation("hit").unwrap();
+        assert_eq!(hit.mode(), LoopMode::Once);
+        assert_eq!(hit.frames()[0].0, sprite.frames[2].0);
+        assert_eq!(
+            sprite.tag_animation("idle").unwrap().mode(),
+            LoopMode::PingPong
+        );
+        assert_eq!(sprite.tag_animation("walk").unwrap().frames().len(), 3);
+
+        let key = sprite.slice("feet").unwrap().key_at(2).unwrap();
+        assert_eq!(key.bounds, Bounds::new(Pt(1.0), Pt(2.0), Pt(3.0), Pt(4.0)));
+        assert_eq!(key.pivot, Some([Pt(2.0), Pt(4.0)]));
+        assert_eq!(key.center, None);
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        let file = Builder::default()
+            .frame(100)
+            .layer("sprite", true, 0, 0)
+            .build([2, 2]);
+        assert!(parse(&file[..file.len() - 3]).is_err());
+        assert!(parse(b"not a sprite").is_err());
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! These helpers keep the source pixel dimensions while deriving a default
 //! logical size from the current [`crate::scale_factor`].
+//!
+//! The loaders decode every format the `image` crate enables by default, including BMP,
+//! TGA and QOI; QOI in particular decodes much faster than PNG. The `bmp`, `tga` and
+//! `qoi` features enable `utils` together with the named format, so a game that relies
+//! on one keeps decoding it however the defaults change.
 
 use crate::{Context, Image, Pt};
 
@@ -27,6 +32,16 @@ pub fn from_rgba_image(ctx: &mut Context, image: &image::RgbaImage) -> anyhow::R
     Image::new_from_rgba8_with_pixels(ctx, width_px, height_px, width, height, image.as_raw())
 }
 
+/// Decodes image file bytes, falling back to the extension of `path` for formats
+/// without a signature such as TGA.
+pub(crate) fn decode(bytes: &[u8], path: &str) -> anyhow::Result<image::DynamicImage> {
+    let format = match image::guess_format(bytes) {
+        Ok(format) => format,
+        Err(_) => image::ImageFormat::from_path(path)?,
+    };
+    Ok(image::load_from_memory_with_format(bytes, format)?)
+}
+
 use std::sync::mpsc::{self, Receiver};
 
 type ImageLoadResult = Result<(u32, u32, Vec<u8>), String>;
@@ -112,7 +127,8 @@ impl LoadingImage {
 
 /// Starts loading an image asynchronously from the specified path.
 ///
-/// This reads and decodes the image on a background thread.
+/// This reads and decodes the image on a background thread; on the web it is fetched
+/// over HTTP relative to the page.
 /// Call `poll(ctx)` on the returned `LoadingImage` during your `update` or `draw`
 /// loop to obtain the registered `Image` once it is ready.
 pub fn load_image_async(path: impl Into<String>) -> LoadingImage {
@@ -125,7 +141,7 @@ pub fn load_image_async(path: impl Into<String>) -> LoadingImage {
         std::thread::spawn(move || {
             let res = (|| -> Result<(u32, u32, Vec<u8>), anyhow::Error> {
                 let bytes = crate::assets::load_asset(&path_clone)?;
-                let img = image::load_from_memory(&bytes)?;
+                let img = decode(&bytes, &path_clone)?;
                 let rgba = img.to_rgba8();
                 Ok((rgba.width(), rgba.height(), rgba.into_raw()))
             })();
@@ -136,12 +152,13 @@ pub fn load_image_async(path: impl Into<String>) -> LoadingImage {
     #[cfg(target_arch = "wasm32")]
     {
         wasm_bindgen_futures::spawn_local(async move {
-            let res = (|| -> Result<(u32, u32, Vec<u8>), anyhow::Error> {
-                let bytes = crate::assets::load_asset(&path_clone)?;
-                let img = image::load_from_memory(&bytes)?;
+            let res = async {
+                let bytes = crate::assets::fetch_asset(&path_clone).await?;
+                let img = decode(&bytes, &path_clone)?;
                 let rgba = img.to_rgba8();
-                Ok((rgba.width(), rgba.height(), rgba.into_raw()))
-            })();
+                Ok::<_, anyhow::Error>((rgba.width(), rgba.height(), rgba.into_raw()))
+            }
+            .await;
             let _ = tx.send(res.map_err(|e| e.to_string()));
         });
     }
@@ -282,6 +299,40 @@ impl AsyncImageLoader {
 mod tests {
     use super::*;
 
+    /// Encodes a 2x2 image in `format` and loads it back through the engine.
+    #[cfg(any(feature = "bmp", feature = "tga", feature = "qoi"))]
+    fn decodes(format: image::ImageFormat, path: &str) {
+        let source = image::RgbaImage::from_fn(2, 2, |x, y| {
+            image::Rgba([x as u8 * 255, y as u8 * 255, 128, 255])
+        });
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        source.write_to(&mut bytes, format).unwrap();
+
+        let decoded = decode(bytes.get_ref(), path).unwrap();
+        assert_eq!(decoded.to_rgba8(), source);
+        let mut ctx = Context::new();
+        let image = from_image(&mut ctx, &decoded).unwrap();
+        assert_eq!(image.pixel_bounds().width, 2);
+    }
+
+    #[cfg(feature = "bmp")]
+    #[test]
+    fn bmp_images_decode() {
+        decodes(image::ImageFormat::Bmp, "sprite.bmp");
+    }
+
+    #[cfg(feature = "tga")]
+    #[test]
+    fn tga_images_decode() {
+        decodes(image::ImageFormat::Tga, "sprite.tga");
+    }
+
+    #[cfg(feature = "qoi")]
+    #[test]
+    fn qoi_images_decode() {
+        decodes(image::ImageFormat::Qoi, "sprite.qoi");
+    }
+
     #[test]
     fn test_async_loading() {
         let mut ctx = Context::new();
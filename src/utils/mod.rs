@@ -1,3 +1,12 @@
+/// Importer for sprites saved by the Aseprite editor.
+#[cfg(feature = "aseprite")]
+pub mod aseprite;
+#[cfg(any(
+    test,
+    feature = "tiled",
+    all(target_arch = "wasm32", target_os = "unknown")
+))]
+pub(crate) mod base64;
 #[cfg(feature = "gltf")]
 pub mod gltf;
 /// Helpers for bridging assets decoded by the `image` crate into `spottedcat::Image`.
@@ -5,3 +14,6 @@ pub mod gltf;
 pub mod image;
 #[cfg(feature = "model-3d")]
 pub mod obj;
+/// Importers for maps made with the Tiled editor.
+#[cfg(feature = "tiled")]
+pub mod tiled;
@@ -0,0 +1,767 @@
+//! Imports orthogonal maps made with the [Tiled](https://www.mapeditor.org) editor.
+//!
+//! Both the XML (`.tmx`) and the JSON (`.tmj`) formats are read, with tile layers in any
+//! encoding Tiled writes except zstd, object layers and animated tiles. Group layers are
+//! flattened into the layers they contain. Tilesets must be embedded in the map, and
+//! infinite maps are not supported.
+//!
+//! Maps refer to images by the path written in the file, usually relative to the map.
+//! The loaders hand each path to a callback that turns it into an [`Image`], so maps work
+//! with any way of loading images, e.g. [`from_image`](crate::utils::image::from_image)
+//! with the `utils` feature.
+
+use crate::tilemap::{MapLayer, MapObject, ObjectLayer, TileLayer, TileMap, TileSet};
+use crate::{Context, Image, Pt};
+use anyhow::{Context as _, anyhow, bail};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+
+/// Loads a map in Tiled's JSON format (`.tmj`).
+pub fn load_tmj_from_bytes(
+    ctx: &mut Context,
+    data: &[u8],
+    mut load_image: impl FnMut(&mut Context, &str) -> anyhow::Result<Image>,
+) -> anyhow::Result<TileMap> {
+    let root: Value = serde_json::from_slice(data).context("invalid Tiled JSON map")?;
+    let json = Json(&root);
+    check_map(
+        json.str("orientation").unwrap_or("orthogonal"),
+        json.bool("infinite").unwrap_or(false),
+    )?;
+    let mut map = TileMap::new(
+        json.u32("width")?,
+        json.u32("height")?,
+        Pt(json.f32("tilewidth")?),
+        Pt(json.f32("tileheight")?),
+    );
+    for tileset in json.array("tilesets") {
+        map.tilesets
+            .push(tmj_tileset(ctx, tileset, &mut load_image)?);
+    }
+    tmj_layers(&mut map, json.array("layers"), &Inherited::default())?;
+    Ok(map)
+}
+
+/// Loads a map in Tiled's XML format (`.tmx`).
+pub fn load_tmx_from_bytes(
+    ctx: &mut Context,
+    data: &[u8],
+    mut load_image: impl FnMut(&mut Context, &str) -> anyhow::Result<Image>,
+) -> anyhow::Result<TileMap> {
+    let text = std::str::from_utf8(data).context("Tiled XML map is not UTF-8")?;
+    let root = Element::from_xml(text)?;
+    if root.name != "map" {
+        bail!("expected a Tiled <map>, found <{}>", root.name);
+    }
+    check_map(
+        root.attr("orientation").unwrap_or("orthogonal"),
+        root.attr("infinite") == Some("1"),
+    )?;
+    let mut map = TileMap::new(
+        root.parse("width")?,
+        root.parse("height")?,
+        Pt(root.parse("tilewidth")?),
+        Pt(root.parse("tileheight")?),
+    );
+    for tileset in root.children("tileset") {
+        map.tilesets
+            .push(tmx_tileset(ctx, tileset, &mut load_image)?);
+    }
+    tmx_layers(&mut map, &root, &Inherited::default())?;
+    Ok(map)
+}
+
+fn check_map(orientation: &str, infinite: bool) -> anyhow::Result<()> {
+    if orientation != "orthogonal" {
+        bail!("{orientation} Tiled maps are not supported, only orthogonal ones");
+    }
+    if infinite {
+        bail!("infinite Tiled maps are not supported");
+    }
+    Ok(())
+}
+
+/// State group layers pass down to the layers they contain.
+#[derive(Debug, Clone, Copy)]
+struct Inherited {
+    offset: [f32; 2],
+    opacity: f32,
+    visible: bool,
+}
+
+impl Default for Inherited {
+    fn default() -> Self {
+        Self {
+            offset: [0.0, 0.0],
+            opacity: 1.0,
+            visible: true,
+        }
+    }
+}
+
+impl Inherited {
+    fn nest(&self, offset: [f32; 2], opacity: f32, visible: bool) -> Self {
+        Self {
+            offset: [self.offset[0] + offset[0], self.offset[1] + offset[1]],
+            opacity: self.opacity * opacity,
+            visible: self.visible && visible,
+        }
+    }
+}
+
+/// Tile set fields shared by both formats.
+struct TileSetSource<'a> {
+    first_gid: u32,
+    tile_size: [f32; 2],
+    margin: f32,
+    spacing: f32,
+    image: Option<&'a str>,
+    /// Per-tile images, animations and properties of the set.
+    tiles: Vec<TileSource<'a>>,
+}
+
+struct TileSource<'a> {
+    id: u32,
+    image: Option<&'a str>,
+    animation: Vec<(u32, Duration)>,
+}
+
+fn build_tileset(
+    ctx: &mut Context,
+    source: TileSetSource,
+    load_image: &mut impl FnMut(&mut Context, &str) -> anyhow::Result<Image>,
+) -> anyhow::Result<TileSet> {
+    let mut tileset = match source.image {
+        Some(path) => {
+            let image = load_image(ctx, path)
+                .with_context(|| format!("cannot load tileset image {path:?}"))?;
+            TileSet::from_image(
+                ctx,
+                source.first_gid,
+                image,
+                [Pt(source.tile_size[0]), Pt(source.tile_size[1])],
+                Pt(source.margin),
+                Pt(source.spacing),
+            )?
+        }
+        None => TileSet {
+            first_gid: source.first_gid,
+            ..TileSet::default()
+        },
+    };
+    for tile in source.tiles {
+        if let Some(path) = tile.image {
+            let image = load_image(ctx, path)
+                .with_context(|| format!("cannot load tile image {path:?}"))?;
+            let index = tile.id as usize;
+            if tileset.tiles.len() <= index {
+                tileset.tiles.resize(index + 1, None);
+            }
+            tileset.tiles[index] = Some(image);
+        }
+        if !tile.animation.is_empty() {
+            tileset.animations.insert(tile.id, tile.animation);
+        }
+    }
+    Ok(tileset)
+}
+
+/// Decodes the cells of a tile layer stored as CSV or base64, optionally compressed.
+fn decode_tiles(
+    data: &str,
+    encoding: Option<&str>,
+    compression: Option<&str>,
+    count: usize,
+) -> anyhow::Result<Vec<u32>> {
+    let tiles: Vec<u32> = match encoding {
+        Some("csv") => data
+            .split(',')
+            .map(str::trim)
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| {
+                cell.parse()
+                    .with_context(|| format!("invalid tile {cell:?}"))
+            })
+            .collect::<anyhow::Result<_>>()?,
+        Some("base64") => {
+            let bytes = crate::utils::base64::decode(data.trim())
+                .ok_or_else(|| anyhow!("invalid base64 tile data"))?;
+            let bytes = match compression {
+                None | Some("") => bytes,
+                Some("zlib") => inflate(flate2::read::ZlibDecoder::new(&bytes[..]))?,
+                Some("gzip") => inflate(flate2::read::GzDecoder::new(&bytes[..]))?,
+                Some(other) => bail!("{other} compressed tile layers are not supported"),
+            };
+            bytes
+                .chunks_exact(4)
+                .map(|cell| u32::from_le_bytes([cell[0], cell[1], cell[2], cell[3]]))
+                .collect()
+        }
+        Some(other) => bail!("{other} encoded tile layers are not supported"),
+        None => bail!("tile layers must be stored as CSV or base64"),
+    };
+    if tiles.len() != count {
+        bail!("tile layer has {} cells, expected {count}", tiles.len());
+    }
+    Ok(tiles)
+}
+
+fn inflate(mut decoder: impl Read) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    decoder
+        .read_to_end(&mut bytes)
+        .context("invalid compressed tile data")?;
+    Ok(bytes)
+}
+
+fn tile_layer(
+    name: &str,
+    size: [u32; 2],
+    tiles: Vec<u32>,
+    offset: [f32; 2],
+    opacity: f32,
+    visible: bool,
+) -> MapLayer {
+    MapLayer::Tiles(TileLayer {
+        name: name.to_string(),
+        width: size[0],
+        height: size[1],
+        tiles,
+        visible,
+        opacity,
+        offset: [Pt(offset[0]), Pt(offset[1])],
+    })
+}
+
+#[derive(Clone, Copy)]
+struct Json<'a>(&'a Value);
+
+impl<'a> Json<'a> {
+    fn get(self, key: &str) -> Option<&'a Value> {
+        self.0.get(key)
+    }
+
+    fn str(self, key: &str) -> Option<&'a str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    fn bool(self, key: &str) -> Option<bool> {
+        self.get(key).and_then(Value::as_bool)
+    }
+
+    fn f32_or(self, key: &str, default: f32) -> f32 {
+        self.get(key)
+            .and_then(Value::as_f64)
+            .map_or(default, |value| value as f32)
+    }
+
+    fn f32(self, key: &str) -> anyhow::Result<f32> {
+        self.get(key)
+            .and_then(Value::as_f64)
+            .map(|value| value as f32)
+            .ok_or_else(|| anyhow!("missing number {key:?}"))
+    }
+
+    fn u32(self, key: &str) -> anyhow::Result<u32> {
+        self.get(key)
+            .and_then(Value::as_u64)
+            .map(|value| value as u32)
+            .ok_or_else(|| anyhow!("missing number {key:?}"))
+    }
+
+    fn array(self, key: &str) -> impl Iterator<Item = Json<'a>> {
+        self.get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(Json)
+    }
+}
+
+fn tmj_tileset(
+    ctx: &mut Context,
+    json: Json,
+    load_image: &mut impl FnMut(&mut Context, &str) -> anyhow::Result<Image>,
+) -> anyhow::Result<TileSet> {
+    if let Some(source) = json.str("source") {
+        bail!("external tileset {source:?} is not supported; embed it in the map");
+    }
+    let tiles = json
+        .array("tiles")
+        .map(|tile| {
+            let animation = tile
+                .array("animation")
+                .map(|frame| {
+                    Ok((
+                        frame.u32("tileid")?,
+                        Duration::from_millis(frame.u32("duration")?.into()),
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            Ok(TileSource {
+                id: tile.u32("id")?,
+                image: tile.str("image"),
+                animation,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let source = TileSetSource {
+        first_gid: json.u32("firstgid")?,
+        tile_size: [json.f32("tilewidth")?, json.f32("tileheight")?],
+        margin: json.f32_or("margin", 0.0),
+        spacing: json.f32_or("spacing", 0.0),
+        image: json.str("image"),
+        tiles,
+    };
+    build_tileset(ctx, source, load_image)
+}
+
+fn tmj_layers<'a>(
+    map: &mut TileMap,
+    layers: impl Iterator<Item = Json<'a>>,
+    parent: &Inherited,
+) -> anyhow::Result<()> {
+    for layer in layers {
+        let name = layer.str("name").unwrap_or_default();
+        let inherited = parent.nest(
+            [layer.f32_or("offsetx", 0.0), layer.f32_or("offsety", 0.0)],
+            layer.f32_or("opacity", 1.0),
+            layer.bool("visible").unwrap_or(true),
+        );
+        match layer.str("type") {
+            Some("tilelayer") => {
+                let size = [layer.u32("width")?, layer.u32("height")?];
+                let count = (size[0] * size[1]) as usize;
+                let tiles = match layer.get("data") {
+                    Some(Value::Array(cells)) => {
+                        let tiles: Vec<u32> = cells
+                            .iter()
+                            .map(|cell| cell.as_u64().map(|gid| gid as u32))
+                            .collect::<Option<_>>()
+                            .ok_or_else(|| anyhow!("invalid tile in layer {name:?}"))?;
+                        if tiles.len() != count {
+                            bail!(
+                                "tile layer {name:?} has {} cells, expected {count}",
+                                tiles.len()
+                            );
+                        }
+                        tiles
+                    }
+                    Some(Value::String(data)) => decode_tiles(
+                        data,
+                        Some(layer.str("encoding").unwrap_or("base64")),
+                        layer.str("compression"),
+                        count,
+                    )
+                    .with_context(|| format!("in tile layer {name:?}"))?,
+                    _ => bail!("tile layer {name:?} has no data"),
+                };
+                map.layers.push(tile_layer(
+                    name,
+                    size,
+                    tiles,
+                    inherited.offset,
+                    inherited.opacity,
+                    inherited.visible,
+                ));
+            }
+            Some("objectgroup") => {
+                let objects = layer.array("objects").map(tmj_object).collect();
+                map.layers.push(MapLayer::Objects(ObjectLayer {
+                    name: name.to_string(),
+                    objects,
+                    visible: inherited.visible,
+                }));
+            }
+            Some("group") => tmj_layers(map, layer.array("layers"), &inherited)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tmj_object(json: Json) -> MapObject {
+    let properties = json
+        .array("properties")
+        .filter_map(|property| {
+            let value = match property.get("value")? {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            Some((property.str("name")?.to_string(), value))
+        })
+        .collect();
+    MapObject {
+        id: json.u32("id").unwrap_or_default(),
+        name: json.str("name").unwrap_or_default().to_string(),
+        class: json
+            .str("class")
+            .or_else(|| json.str("type"))
+            .unwrap_or_default()
+            .to_string(),
+        position: [Pt(json.f32_or("x", 0.0)), Pt(json.f32_or("y", 0.0))],
+        size: [
+            Pt(json.f32_or("width", 0.0)),
+            Pt(json.f32_or("height", 0.0)),
+        ],
+        rotation: json.f32_or("rotation", 0.0).to_radians(),
+        gid: json.u32("gid").ok(),
+        visible: json.bool("visible").unwrap_or(true),
+        properties,
+    }
+}
+
+/// An XML element with its attributes, child elements and text.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn from_xml(text: &str) -> anyhow::Result<Element> {
+        use quick_xml::events::{BytesStart, Event};
+
+        fn open(start: &BytesStart) -> anyhow::Result<Element> {
+            let mut element = Element {
+                name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+                ..Element::default()
+            };
+            for attribute in start.attributes() {
+                let attribute = attribute.context("invalid XML attribute")?;
+                element.attributes.insert(
+                    String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                    attribute
+                        .normalized_value(quick_xml::XmlVersion::default())?
+                        .into_owned(),
+                );
+            }
+            Ok(element)
+        }
+
+        let mut reader = quick_xml::Reader::from_str(text);
+        let mut stack = vec![Element::default()];
+        loop {
+            let event = reader.read_event().context("invalid Tiled XML map")?;
+            match event {
+                Event::Start(start) => stack.push(open(&start)?),
+                Event::Empty(start) => {
+                    let element = open(&start)?;
+                    stack.last_mut().unwrap().children.push(element);
+                }
+                Event::End(_) => {
+                    let element = stack.pop().unwrap();
+                    let Some(parent) = stack.last_mut() else {
+                        bail!("unbalanced XML in Tiled map");
+                    };
+                    parent.children.push(element);
+                }
+                Event::Text(text) => stack.last_mut().unwrap().text.push_str(&text.decode()?),
+                Event::CData(data) => stack.last_mut().unwrap().text.push_str(&data.decode()?),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        let document = stack.pop().filter(|_| stack.is_empty());
+        document
+            .and_then(|document| document.children.into_iter().next())
+            .ok_or_else(|| anyhow!("empty or unbalanced Tiled XML map"))
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    fn parse<T: std::str::FromStr>(&self, name: &str) -> anyhow::Result<T> {
+        let value = self
+            .attr(name)
+            .ok_or_else(|| anyhow!("<{}> is missing {name:?}", self.name))?;
+        value
+            .parse()
+            .map_err(|_| anyhow!("invalid {name:?} {value:?} on <{}>", self.name))
+    }
+
+    fn parse_or<T: std::str::FromStr>(&self, name: &str, default: T) -> anyhow::Result<T> {
+        match self.attr(name) {
+            Some(_) => self.parse(name),
+            None => Ok(default),
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+}
+
+fn tmx_tileset(
+    ctx: &mut Context,
+    element: &Element,
+    load_image: &mut impl FnMut(&mut Context, &str) -> anyhow::Result<Image>,
+) -> anyhow::Result<TileSet> {
+    if let Some(source) = element.attr("source") {
+        bail!("external tileset {source:?} is not supported; embed it in the map");
+    }
+    let tiles = element
+        .children("tile")
+        .map(|tile| {
+            let animation = tile
+                .child("animation")
+                .into_iter()
+                .flat_map(|animation| animation.children("frame"))
+                .map(|frame| {
+                    Ok((
+                        frame.parse("tileid")?,
+                        Duration::from_millis(frame.parse("duration")?),
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            Ok(TileSource {
+                id: tile.parse("id")?,
+                image: tile.child("image").and_then(|image| image.attr("source")),
+                animation,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let source = TileSetSource {
+        first_gid: element.parse("firstgid")?,
+        tile_size: [element.parse("tilewidth")?, element.parse("tileheight")?],
+        margin: element.parse_or("margin", 0.0)?,
+        spacing: element.parse_or("spacing", 0.0)?,
+        image: element
+            .child("image")
+            .and_then(|image| image.attr("source")),
+        tiles,
+    };
+    build_tileset(ctx, source, load_image)
+}
+
+fn tmx_layers(map: &mut TileMap, parent: &Element, inherited: &Inherited) -> anyhow::Result<()> {
+    for layer in &parent.children {
+        let name = layer.attr("name").unwrap_or_default();
+        let nested = inherited.nest(
+            [
+                layer.parse_or("offsetx", 0.0)?,
+                layer.parse_or("offsety", 0.0)?,
+            ],
+            layer.parse_or("opacity", 1.0)?,
+            layer.attr("visible") != Some("0"),
+        );
+        match layer.name.as_str() {
+            "layer" => {
+                let size = [layer.parse("width")?, layer.parse("height")?];
+                let data = layer
+                    .child("data")
+                    .ok_or_else(|| anyhow!("tile layer {name:?} has no data"))?;
+                let tiles = decode_tiles(
+                    &data.text,
+                    data.attr("encoding"),
+                    data.attr("compression"),
+                    (size[0] * size[1]) as usize,
+                )
+                .with_context(|| format!("in tile layer {name:?}"))?;
+                map.layers.push(tile_layer(
+                    name,
+                    size,
+                    tiles,
+                    nested.offset,
+                    nested.opacity,
+                    nested.visible,
+                ));
+            }
+            "objectgroup" => {
+                let objects = layer
+                    .children("object")
+                    .map(tmx_object)
+                    .collect::<anyhow::Result<_>>()?;
+                map.layers.push(MapLayer::Objects(ObjectLayer {
+                    name: name.to_string(),
+                    objects,
+                    visible: nested.visible,
+                }));
+            }
+            "group" => tmx_layers(map, layer, &nested)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tmx_object(element: &Element) -> anyhow::Result<MapObject> {
+    let properties = element
+        .child("properties")
+        .into_iter()
+        .flat_map(|properties| properties.children("property"))
+        .filter_map(|property| {
+            let value = property
+                .attr("value")
+                .map_or_else(|| property.text.clone(), str::to_string);
+            Some((property.attr("name")?.to_string(), value))
+        })
+        .collect();
+    Ok(MapObject {
+        id: element.parse_or("id", 0)?,
+        name: element.attr("name").unwrap_or_default().to_string(),
+        class: element
+            .attr("class")
+            .or_else(|| element.attr("type"))
+            .unwrap_or_default()
+            .to_string(),
+        position: [
+            Pt(element.parse_or("x", 0.0)?),
+            Pt(element.parse_or("y", 0.0)?),
+        ],
+        size: [
+            Pt(element.parse_or("width", 0.0)?),
+            Pt(element.parse_or("height", 0.0)?),
+        ],
+        rotation: element.parse_or("rotation", 0.0f32)?.to_radians(),
+        gid: element
+            .attr("gid")
+            .map(|_| element.parse("gid"))
+            .transpose()?,
+        visible: element.attr("visible") != Some("0"),
+        properties,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type LoadedPaths = std::rc::Rc<std::cell::RefCell<Vec<String>>>;
+
+    fn image_loader() -> (
+        LoadedPaths,
+        impl FnMut(&mut Context, &str) -> anyhow::Result<Image>,
+    ) {
+        let loaded = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let paths = loaded.clone();
+        let loader = move |ctx: &mut Context, path: &str| {
+            paths.borrow_mut().push(path.to_string());
+            Image::new(ctx, Pt(32.0), Pt(16.0), &[255; 32 * 16 * 4])
+        };
+        (loaded, loader)
+    }
+
+    fn assert_example_map(map: &TileMap, loaded: &[String]) {
+        assert_eq!(loaded, ["tiles.png"]);
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(map.tilesets[0].tiles.len(), 8);
+        assert_eq!(
+            map.tilesets[0].animations[&1],
+            [
+                (1, Duration::from_millis(100)),
+                (2, Duration::from_millis(200))
+            ]
+        );
+        let ground = map.tile_layer("ground").unwrap();
+        assert_eq!(ground.tiles, [1, 2, 0, 0, 3, 0x8000_0004]);
+        assert_eq!(ground.offset, [Pt(5.0), Pt(0.0)]);
+        assert!(!ground.visible);
+        let spawn = map
+            .object_layer("objects")
+            .unwrap()
+            .object("spawn")
+            .unwrap();
+        assert_eq!(spawn.class, "player");
+        assert_eq!(spawn.position, [Pt(24.0), Pt(8.0)]);
+        assert_eq!(spawn.properties["lives"], "3");
+    }
+
+    #[test]
+    fn loads_tmx_maps() {
+        let mut ctx = Context::new();
+        let (loaded, loader) = image_loader();
+        let tmx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="3" height="2" tilewidth="8" tileheight="8" infinite="0">
+ <tileset firstgid="1" name="tiles" tilewidth="8" tileheight="8" tilecount="8" columns="4">
+  <image source="tiles.png" width="32" height="16"/>
+  <tile id="1">
+   <animation>
+    <frame tileid="1" duration="100"/>
+    <frame tileid="2" duration="200"/>
+   </animation>
+  </tile>
+ </tileset>
+ <group name="world" offsetx="5" visible="0">
+  <layer id="1" name="ground" width="3" height="2">
+   <data encoding="csv">
+1,2,0,
+0,3,2147483652
+</data>
+  </layer>
+ </group>
+ <objectgroup id="2" name="objects">
+  <object id="1" name="spawn" type="player" x="24" y="8">
+   <properties>
+    <property name="lives" type="int" value="3"/>
+   </properties>
+  </object>
+ </objectgroup>
+</map>"#;
+        let map = load_tmx_from_bytes(&mut ctx, tmx.as_bytes(), loader).unwrap();
+        assert_example_map(&map, &loaded.borrow());
+    }
+
+    #[test]
+    fn loads_tmj_maps_with_base64_layers() {
+        let mut ctx = Context::new();
+        let (loaded, loader) = image_loader();
+        let cells: Vec<u8> = [1u32, 2, 0, 0, 3, 0x8000_0004]
+            .iter()
+            .flat_map(|cell| cell.to_le_bytes())
+            .collect();
+        let data = crate::utils::base64::encode(&cells);
+        let tmj = format!(
+            r#"{{
+  "orientation": "orthogonal", "infinite": false,
+  "width": 3, "height": 2, "tilewidth": 8, "tileheight": 8,
+  "tilesets": [{{
+    "firstgid": 1, "tilewidth": 8, "tileheight": 8, "image": "tiles.png",
+    "tiles": [{{ "id": 1, "animation": [
+      {{ "tileid": 1, "duration": 100 }}, {{ "tileid": 2, "duration": 200 }}
+    ] }}]
+  }}],
+  "layers": [
+    {{ "type": "group", "name": "world", "offsetx": 5, "visible": false, "layers": [
+      {{ "type": "tilelayer", "name": "ground", "width": 3, "height": 2,
+         "encoding": "base64", "data": "{data}" }}
+    ] }},
+    {{ "type": "objectgroup", "name": "objects", "objects": [
+      {{ "id": 1, "name": "spawn", "class": "player", "x": 24, "y": 8,
+         "properties": [{{ "name": "lives", "type": "int", "value": 3 }}] }}
+    ] }}
+  ]
+}}"#
+        );
+        let map = load_tmj_from_bytes(&mut ctx, tmj.as_bytes(), loader).unwrap();
+        assert_example_map(&map, &loaded.borrow());
+    }
+
+    #[test]
+    fn unsupported_maps_are_rejected() {
+        let mut ctx = Context::new();
+        let (_, mut loader) = image_loader();
+        let error = load_tmx_from_bytes(
+            &mut ctx,
+            br#"<map orientation="isometric" width="1" height="1" tilewidth="8" tileheight="8"/>"#,
+            &mut loader,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("isometric"));
+        assert!(
+            decode_tiles("AAAA", Some("base64"), Some("zstd"), 1)
+                .unwrap_err()
+                .to_string()
+                .contains("zstd")
+        );
+    }
+}
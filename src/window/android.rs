@@ -43,6 +43,9 @@ pub(crate) struct PlatformData {
     #[cfg(feature = "sensors")]
     pub(crate) sensor_state: Option<AndroidSensorState>,
     pub(crate) internal_data_path: Option<std::path::PathBuf>,
+    /// Set while the on-screen keyboard was requested, so its insets are polled.
+    pub(crate) virtual_keyboard_requested: bool,
+    pub(crate) power_polled_at: Option<std::time::Instant>,
 }
 
 impl PlatformData {
@@ -54,22 +57,171 @@ impl PlatformData {
             #[cfg(feature = "sensors")]
             sensor_state: None,
             internal_data_path: None,
+            virtual_keyboard_requested: false,
+            power_polled_at: None,
         }
     }
 }
 
+fn system_theme(app: &AndroidApp) -> Option<crate::Theme> {
+    match app.config().ui_mode_night() {
+        ndk::configuration::UiModeNight::Yes => Some(crate::Theme::Dark),
+        ndk::configuration::UiModeNight::No => Some(crate::Theme::Light),
+        _ => None,
+    }
+}
+
 impl App {
     fn apply_pending_window_requests(&mut self) {
-        // Android path currently ignores dynamic title/cursor/fullscreen requests.
+        // Android path currently ignores dynamic title/cursor/level/geometry requests.
         let _ = self.ctx.take_window_title_request();
         let _ = self.ctx.take_cursor_visible_request();
-        let _ = self.ctx.take_fullscreen_request();
+        let _ = self.ctx.take_window_level_request();
+        let _ = self.ctx.take_window_size_request();
+        let _ = self.ctx.take_window_position_request();
+
+        if let Some(visible) = self.ctx.take_virtual_keyboard_request()
+            && let Some(app) = crate::android::get_app()
+        {
+            if visible {
+                app.show_soft_input(true);
+            } else {
+                app.hide_soft_input(false);
+            }
+            self.platform.virtual_keyboard_requested = visible;
+        }
+        self.sync_virtual_keyboard();
+
+        if let Some(enabled) = self.ctx.take_fullscreen_request() {
+            self.apply_fullscreen(enabled);
+        }
+
+        if let Some(enabled) = self.ctx.take_keep_screen_on_request()
+            && let Some(app) = crate::android::get_app()
+        {
+            let flag = android_activity::WindowManagerFlags::KEEP_SCREEN_ON;
+            if enabled {
+                app.set_window_flags(flag, android_activity::WindowManagerFlags::empty());
+            } else {
+                app.set_window_flags(android_activity::WindowManagerFlags::empty(), flag);
+            }
+        }
+    }
+
+    /// Draws edge to edge into the display cutout with the system bars hidden until
+    /// swiped in, or restores the bars.
+    ///
+    /// Needs the activity's `setEdgeToEdge(boolean)`, as hiding bars has to run on the UI
+    /// thread. Without it window flags hide the status bar only. Either way the surface
+    /// is resized and the safe area updated through the usual window events.
+    fn apply_fullscreen(&mut self, enabled: bool) {
+        let handled = crate::android::set_edge_to_edge(enabled).unwrap_or_else(|error| {
+            crate::diagnostics::warn!("android", "switching fullscreen failed: {}", error);
+            false
+        });
+        if handled {
+            return;
+        }
+        crate::diagnostics::warn!(
+            "android",
+            "activity has no setEdgeToEdge(boolean); navigation bar stays visible"
+        );
+        if let Some(app) = crate::android::get_app() {
+            let flags = android_activity::WindowManagerFlags::FULLSCREEN
+                | android_activity::WindowManagerFlags::LAYOUT_NO_LIMITS;
+            if enabled {
+                app.set_window_flags(flags, android_activity::WindowManagerFlags::empty());
+            } else {
+                app.set_window_flags(android_activity::WindowManagerFlags::empty(), flags);
+            }
+        }
+    }
+
+    fn sync_power_status(&mut self) {
+        if self
+            .platform
+            .power_polled_at
+            .is_some_and(|at| at.elapsed() < crate::power::POWER_POLL_INTERVAL)
+        {
+            return;
+        }
+        self.platform.power_polled_at = Some(std::time::Instant::now());
+        match crate::android::power_status() {
+            Ok(status) => self.ctx.runtime.power = status,
+            Err(error) => {
+                crate::diagnostics::warn!("android", "reading power status failed: {}", error);
+            }
+        }
+    }
+
+    /// Polls the keyboard's insets while it was requested or is still on screen, since
+    /// showing it does not resize the window.
+    fn sync_virtual_keyboard(&mut self) {
+        if !self.platform.virtual_keyboard_requested && self.ctx.runtime.virtual_keyboard.is_none()
+        {
+            return;
+        }
+        let bottom = match crate::android::ime_inset_bottom() {
+            Ok(bottom) => bottom,
+            Err(error) => {
+                crate::diagnostics::warn!("android", "reading keyboard insets failed: {}", error);
+                self.platform.virtual_keyboard_requested = false;
+                return;
+            }
+        };
+        let height = (bottom > 0).then(|| Pt::from_physical_px(bottom as f64, self.scale_factor));
+        // Dismissed by the user after it was shown.
+        if height.is_none() && self.ctx.runtime.virtual_keyboard.is_some() {
+            self.platform.virtual_keyboard_requested = false;
+        }
+        self.ctx.set_virtual_keyboard(height);
     }
 
     fn request_redraw(&mut self) {
         self.platform.redraw_requested = true;
     }
 
+    /// Offers a back press to the active scene and finishes the activity like the system
+    /// would when the scene does not consume it.
+    fn handle_back_request(&mut self) {
+        let consumed = self
+            .scene
+            .spot_mut()
+            .is_some_and(|spot| spot.back_requested(&mut self.ctx));
+        if consumed {
+            self.request_redraw();
+        } else if let Err(error) = crate::android::finish_activity() {
+            crate::diagnostics::error!("android", "finishing activity failed: {}", error);
+        }
+    }
+
+    /// Derives the safe area from the content rect, which leaves out the system bars and
+    /// display cutouts, and from the window insets, which also cover bars drawn over the
+    /// window when it is edge to edge.
+    fn sync_safe_area(&mut self, app: &AndroidApp) {
+        let Some(window) = self.platform.native_window.as_ref() else {
+            return;
+        };
+        let rect = app.content_rect();
+        let (width, height) = (window.width(), window.height());
+        if width <= 0 || height <= 0 || rect.right <= rect.left || rect.bottom <= rect.top {
+            return;
+        }
+        let insets = crate::android::system_insets()
+            .unwrap_or_else(|error| {
+                crate::diagnostics::warn!("android", "reading window insets failed: {}", error);
+                None
+            })
+            .unwrap_or_default();
+        let pt = |px: i32| Pt::from_physical_px(px.max(0) as f64, self.scale_factor);
+        self.ctx.set_safe_area(
+            pt(rect.top.max(insets.top)),
+            pt((height - rect.bottom).max(insets.bottom)),
+            pt(rect.left.max(insets.left)),
+            pt((width - rect.right).max(insets.right)),
+        );
+    }
+
     #[cfg(feature = "sensors")]
     fn current_local_epoch_day() -> u64 {
         crate::android::current_local_epoch_day().unwrap_or_else(|| {
@@ -118,19 +270,18 @@ impl App {
     fn setup_native_window_surface(&mut self, window: &ndk::native_window::NativeWindow) {
         let size = (window.width() as u32, window.height() as u32);
         if size.0 == 0 || size.1 == 0 {
-            crate::android::logcat_warn(
-                "setup_native_window_surface skipped because window size is zero",
+            crate::diagnostics::warn!(
+                "android",
+                "setup_native_window_surface skipped because window size is zero"
             );
             return;
         }
 
-        crate::android::logcat_info(&format!(
-            "setup_native_window_surface size={}x{}",
-            size.0, size.1
-        ));
-        eprintln!(
-            "[spot][android] Setting up surface for window: {}x{}",
-            size.0, size.1
+        crate::diagnostics::info!(
+            "android",
+            "Setting up surface for window: {}x{}",
+            size.0,
+            size.1
         );
 
         // Force RGBA_8888 for better transparency support
@@ -168,9 +319,9 @@ impl App {
                 if let Some(g) = self.ctx.graphics_mut() {
                     g.resize(surface, size.0, size.1);
                 }
-                eprintln!("[spot][android] Reusing existing graphics device for new surface.");
-                crate::android::logcat_info(
-                    "reused existing graphics device for resumed/new surface",
+                crate::diagnostics::info!(
+                    "android",
+                    "Reusing existing graphics device for new surface."
                 );
                 // Graphics already initialized and reconfigured, no need for heavy init
                 self.init_state = platform::GraphicsInitState::Ready(Box::new(None));
@@ -186,7 +337,8 @@ impl App {
 
         // If we get here, we don't have a global device yet, so start fresh init
         self.init_state = platform::GraphicsInitState::NotStarted;
-        platform::begin_graphics_init(
+        // Failures are logged; the next surface retries initialization.
+        let _ = platform::begin_graphics_init(
             &mut self.init_state,
             &self.instance,
             self.surface.as_ref().unwrap(),
@@ -198,28 +350,35 @@ impl App {
         self.ctx
             .update_window_metrics_physical(size.0, size.1, self.scale_factor);
 
-        eprintln!("[spot][android] Graphics initialization started for new surface.");
-        crate::android::logcat_info("graphics initialization started for native surface");
+        crate::diagnostics::info!(
+            "android",
+            "Graphics initialization started for new surface."
+        );
         self.request_redraw();
     }
 
     pub(crate) fn run(&mut self, app: AndroidApp) {
         // Initialize Android-specific features (JVM, Activity, floating window service registration)
         crate::android::init(app.clone());
+        let waker = app.create_waker();
+        crate::user_events::set_waker(Some(Box::new(move || waker.wake())));
 
         // Initialize scale factor based on screen density (160 dpi is baseline 1.0)
-        self.scale_factor = app.config().density().unwrap_or(160) as f64 / 160.0;
+        self.scale_factor = self
+            .window_config
+            .resolve_scale_factor(app.config().density().unwrap_or(160) as f64 / 160.0);
         self.ctx.set_scale_factor(self.scale_factor);
+        self.ctx.set_system_theme(system_theme(&app));
         self.platform.internal_data_path = app.internal_data_path();
+        if self.window_config.fullscreen {
+            self.ctx.set_fullscreen(true);
+        }
 
-        eprintln!(
-            "[spot][android] entering run loop. scale_factor: {}",
+        crate::diagnostics::info!(
+            "android",
+            "entering run loop. scale_factor: {}",
             self.scale_factor
         );
-        crate::android::logcat_info(&format!(
-            "entering run loop scale_factor={}",
-            self.scale_factor
-        ));
 
         self.timing.reset();
         self.request_redraw();
@@ -231,6 +390,7 @@ impl App {
 
         loop {
             self.apply_pending_window_requests();
+            self.sync_power_status();
 
             let has_drawable_scene = self.scene.has_active_scene()
                 && self.ctx.has_graphics()
@@ -280,8 +440,10 @@ impl App {
                             })
                     } {
                         Ok(s) => {
-                            eprintln!("[spot][android][floating] surface created successfully");
-                            crate::android::logcat_info("floating surface created successfully");
+                            crate::diagnostics::info!(
+                                "android",
+                                "[floating] surface created successfully"
+                            );
                             let surface = unsafe {
                                 std::mem::transmute::<wgpu::Surface<'_>, wgpu::Surface<'static>>(s)
                             };
@@ -300,11 +462,11 @@ impl App {
                             self.request_redraw();
                         }
                         Err(e) => {
-                            crate::android::logcat_warn(&format!(
-                                "floating surface creation failed: {:?}",
+                            crate::diagnostics::error!(
+                                "android",
+                                "[floating] surface creation failed: {:?}",
                                 e
-                            ));
-                            eprintln!("[spot][android][floating] surface creation failed: {:?}", e)
+                            )
                         }
                     }
                 }
@@ -314,14 +476,12 @@ impl App {
                 if let Some(window) = app.native_window() {
                     let size = (window.width() as u32, window.height() as u32);
                     if size.0 > 0 && size.1 > 0 {
-                        eprintln!(
-                            "[spot][android] Recovering missing surface from current native window: {}x{}",
-                            size.0, size.1
+                        crate::diagnostics::info!(
+                            "android",
+                            "Recovering missing surface from current native window: {}x{}",
+                            size.0,
+                            size.1
                         );
-                        crate::android::logcat_info(&format!(
-                            "recovering missing surface from current native window {}x{}",
-                            size.0, size.1
-                        ));
                         self.platform.native_window = Some(window.clone());
                         self.setup_native_window_surface(&window);
                     }
@@ -334,34 +494,45 @@ impl App {
                         self.platform.native_window = app.native_window();
                         if let Some(window) = self.platform.native_window.clone() {
                             let size = (window.width(), window.height());
-                            eprintln!("[spot][android] InitWindow: {}x{}", size.0, size.1);
-                            crate::android::logcat_info(&format!(
-                                "MainEvent::InitWindow size={}x{}",
-                                size.0, size.1
-                            ));
+                            crate::diagnostics::info!(
+                                "android",
+                                "InitWindow: {}x{}",
+                                size.0,
+                                size.1
+                            );
                             self.setup_native_window_surface(&window);
+                            self.sync_safe_area(&app);
                         } else {
-                            crate::android::logcat_warn(
-                                "MainEvent::InitWindow received but native_window() returned None",
+                            crate::diagnostics::warn!(
+                                "android",
+                                "MainEvent::InitWindow received but native_window() returned None"
                             );
                         }
                     }
+                    PollEvent::Main(
+                        MainEvent::ContentRectChanged { .. } | MainEvent::InsetsChanged { .. },
+                    ) => {
+                        self.sync_safe_area(&app);
+                        self.request_redraw();
+                    }
                     PollEvent::Main(MainEvent::TerminateWindow { .. }) => {
-                        eprintln!("[spot][android] TerminateWindow");
-                        crate::android::logcat_warn("MainEvent::TerminateWindow");
+                        crate::diagnostics::info!("android", "TerminateWindow");
                         self.ctx.clear_transient_input();
                         self.ctx.clear_transient_state();
                         self.surface.take();
                         self.platform.native_window.take();
                     }
                     PollEvent::Main(MainEvent::WindowResized { .. }) => {
-                        if let (Some(surface), Some(window)) = (self.surface.as_ref(), self.platform.native_window.as_ref()) {
+                        if let (Some(surface), Some(window)) =
+                            (self.surface.as_ref(), self.platform.native_window.as_ref())
+                        {
                             let size = (window.width() as u32, window.height() as u32);
-                            eprintln!("[spot][android] WindowResized: {}x{}", size.0, size.1);
-                            crate::android::logcat_info(&format!(
-                                "MainEvent::WindowResized size={}x{}",
-                                size.0, size.1
-                            ));
+                            crate::diagnostics::info!(
+                                "android",
+                                "WindowResized: {}x{}",
+                                size.0,
+                                size.1
+                            );
                             if size.0 > 0 && size.1 > 0 {
                                 if let Some(g) = self.ctx.graphics_mut() {
                                     g.resize(surface, size.0, size.1);
@@ -371,32 +542,37 @@ impl App {
                                     size.1,
                                     self.scale_factor,
                                 );
+                                self.sync_safe_area(&app);
                                 self.request_redraw();
                             }
                         }
                     }
                     PollEvent::Main(MainEvent::Resume { .. }) => {
-                        eprintln!("[spot][android] Resume");
-                        crate::android::logcat_info("MainEvent::Resume");
+                        crate::diagnostics::info!("android", "Resume");
                         self.platform.floating_surface = None;
 
                         // Resume can invalidate buffers without changing the native window pointer.
                         if let Some(window) = self.platform.native_window.clone() {
                             unsafe {
-                                ndk_sys::ANativeWindow_setBuffersGeometry(window.ptr().as_ptr() as *mut _, 0, 0, 1);
+                                ndk_sys::ANativeWindow_setBuffersGeometry(
+                                    window.ptr().as_ptr() as *mut _,
+                                    0,
+                                    0,
+                                    1,
+                                );
                             }
 
-                            eprintln!("[spot][android] Re-creating surface on resume to ensure stability");
-                            crate::android::logcat_info(
-                                "recreating native surface on resume to ensure stability",
+                            crate::diagnostics::info!(
+                                "android",
+                                "Re-creating surface on resume to ensure stability"
                             );
                             self.setup_native_window_surface(&window);
 
                             self.timing.reset();
                         } else {
-                            eprintln!("[spot][android] Resume: No native window available. Waiting for InitWindow.");
-                            crate::android::logcat_warn(
-                                "MainEvent::Resume without native window; waiting for InitWindow",
+                            crate::diagnostics::warn!(
+                                "android",
+                                "Resume: No native window available. Waiting for InitWindow."
                             );
                         }
 
@@ -414,9 +590,14 @@ impl App {
                         if self.ctx.runtime.audio.is_none() {
                             match crate::audio::AudioSystem::new() {
                                 Ok(audio) => self.ctx.runtime.audio = Some(audio),
-                                Err(e) => eprintln!("[spot][android][audio] initialization failed: {:?}", e),
+                                Err(e) => crate::diagnostics::error!(
+                                    "android",
+                                    "[audio] initialization failed: {:?}",
+                                    e
+                                ),
                             }
                         }
+                        self.ctx.with_audio(|a| a.resume());
 
                         if let Some(spot) = self.scene.spot_mut() {
                             spot.resumed(&mut self.ctx);
@@ -426,8 +607,7 @@ impl App {
                         self.init_sensors();
                     }
                     PollEvent::Main(MainEvent::Pause) => {
-                        eprintln!("[spot][android] Pause");
-                        crate::android::logcat_warn("MainEvent::Pause");
+                        crate::diagnostics::info!("android", "Pause");
 
                         if crate::android::floating_window_enabled() {
                             // Only opt into floating mode when the host app explicitly enables it.
@@ -435,8 +615,17 @@ impl App {
                                 if let Some(factory) = crate::android::get_floating_scene_factory()
                                 {
                                     self.scene.remove_current(&mut self.ctx);
-                                    self.scene.set_active_scene(factory(&mut self.ctx));
-                                    self.scene.mark_floating();
+                                    self.ctx.push_scene_scope();
+                                    match factory(&mut self.ctx) {
+                                        Ok(spot) => {
+                                            self.scene.set_active_scene(&mut self.ctx, spot);
+                                            self.scene.mark_floating();
+                                        }
+                                        Err(error) => {
+                                            self.ctx.release_scene_scope();
+                                            crate::scenes::fail_scene(error);
+                                        }
+                                    }
                                 }
                             }
 
@@ -451,53 +640,131 @@ impl App {
                         }
                         self.ctx.clear_transient_input();
                         self.ctx.clear_transient_state();
+                        // The floating window keeps the app visible, so its audio keeps playing.
+                        if !crate::android::floating_window_enabled() {
+                            self.ctx.with_audio(|a| a.suspend());
+                        }
                         #[cfg(feature = "sensors")]
                         self.disable_high_frequency_sensors();
                     }
                     PollEvent::Main(MainEvent::ConfigChanged { .. }) => {
-                        self.scale_factor = app.config().density().unwrap_or(160) as f64 / 160.0;
+                        self.scale_factor = self.window_config.resolve_scale_factor(
+                            app.config().density().unwrap_or(160) as f64 / 160.0,
+                        );
                         self.ctx.set_scale_factor(self.scale_factor);
-                        eprintln!("[spot][android] ConfigChanged scale_factor: {}", self.scale_factor);
-                        crate::android::logcat_info(&format!(
-                            "MainEvent::ConfigChanged scale_factor={}",
+                        self.ctx.set_system_theme(system_theme(&app));
+                        crate::diagnostics::info!(
+                            "android",
+                            "ConfigChanged scale_factor: {}",
                             self.scale_factor
-                        ));
+                        );
                         self.request_redraw();
                     }
                     PollEvent::Main(MainEvent::Destroy) => {
-                        eprintln!("[spot][android] Destroy");
-                        crate::android::logcat_warn("MainEvent::Destroy");
+                        crate::diagnostics::info!("android", "Destroy");
                         should_exit_run_loop = true;
                     }
                     PollEvent::Main(MainEvent::InputAvailable) => {
                         if let Ok(mut iter) = app.input_events_iter() {
                             loop {
-                                let read = iter.next(|event| {
-                                    match event {
-                                        android_activity::input::InputEvent::MotionEvent(motion_event) => {
-                                            let action = motion_event.action();
-                                            let (pointer_index, phase) = match action {
-                                                android_activity::input::MotionAction::Down => (0, crate::TouchPhase::Started),
-                                                android_activity::input::MotionAction::PointerDown => (motion_event.pointer_index(), crate::TouchPhase::Started),
-                                                android_activity::input::MotionAction::Up => (0, crate::TouchPhase::Ended),
-                                                android_activity::input::MotionAction::PointerUp => (motion_event.pointer_index(), crate::TouchPhase::Ended),
-                                                android_activity::input::MotionAction::Move => (motion_event.pointer_index(), crate::TouchPhase::Moved),
-                                                android_activity::input::MotionAction::Cancel => (0, crate::TouchPhase::Cancelled),
-                                                _ => return android_activity::InputStatus::Unhandled,
-                                            };
-
-                                            let pointer = motion_event.pointer_at_index(pointer_index);
-                                            let id = pointer.pointer_id() as u64;
-                                            let x = Pt::from_physical_px(pointer.x() as f64, self.scale_factor);
-                                            let y = Pt::from_physical_px(pointer.y() as f64, self.scale_factor);
-
-                                            self.ctx.input_mut().handle_touch_raw(id, (x, y), phase);
-                                            android_activity::InputStatus::Handled
+                                let read = iter.next(|event| match event {
+                                    android_activity::input::InputEvent::MotionEvent(
+                                        motion_event,
+                                    ) => {
+                                        let action = motion_event.action();
+                                        let (pointer_index, phase) = match action {
+                                            android_activity::input::MotionAction::Down => {
+                                                (0, crate::TouchPhase::Started)
+                                            }
+                                            android_activity::input::MotionAction::PointerDown => (
+                                                motion_event.pointer_index(),
+                                                crate::TouchPhase::Started,
+                                            ),
+                                            android_activity::input::MotionAction::Up => {
+                                                (0, crate::TouchPhase::Ended)
+                                            }
+                                            android_activity::input::MotionAction::PointerUp => (
+                                                motion_event.pointer_index(),
+                                                crate::TouchPhase::Ended,
+                                            ),
+                                            android_activity::input::MotionAction::Move => (
+                                                motion_event.pointer_index(),
+                                                crate::TouchPhase::Moved,
+                                            ),
+                                            android_activity::input::MotionAction::Cancel => {
+                                                (0, crate::TouchPhase::Cancelled)
+                                            }
+                                            _ => return android_activity::InputStatus::Unhandled,
+                                        };
+
+                                        let pointer = motion_event.pointer_at_index(pointer_index);
+                                        let id = pointer.pointer_id() as u64;
+                                        let x = Pt::from_physical_px(
+                                            pointer.x() as f64,
+                                            self.scale_factor,
+                                        );
+                                        let y = Pt::from_physical_px(
+                                            pointer.y() as f64,
+                                            self.scale_factor,
+                                        );
+
+                                        self.ctx.input_mut().handle_touch_raw(id, (x, y), phase);
+                                        android_activity::InputStatus::Handled
+                                    }
+                                    android_activity::input::InputEvent::KeyEvent(key_event)
+                                        if key_event.key_code()
+                                            == android_activity::input::Keycode::Back =>
+                                    {
+                                        if key_event.action()
+                                            == android_activity::input::KeyAction::Up
+                                        {
+                                            self.handle_back_request();
                                         }
-                                        _ => android_activity::InputStatus::Unhandled,
+                                        android_activity::InputStatus::Handled
+                                    }
+                                    // The soft keyboard sends these as keys once the text
+                                    // field below is empty.
+                                    android_activity::input::InputEvent::KeyEvent(key_event)
+                                        if key_event.action()
+                                            == android_activity::input::KeyAction::Down =>
+                                    {
+                                        let key = match key_event.key_code() {
+                                            android_activity::input::Keycode::Del => {
+                                                crate::Key::Backspace
+                                            }
+                                            android_activity::input::Keycode::Enter => {
+                                                crate::Key::Enter
+                                            }
+                                            _ => return android_activity::InputStatus::Unhandled,
+                                        };
+                                        self.ctx.input_mut().handle_key_tap(key);
+                                        android_activity::InputStatus::Handled
                                     }
+                                    // Typed text is taken out of GameActivity's text field,
+                                    // which is then emptied for the next input.
+                                    android_activity::input::InputEvent::TextEvent(state) => {
+                                        for ch in state.text.chars() {
+                                            self.ctx.input_mut().handle_received_character(ch);
+                                        }
+                                        if !state.text.is_empty() {
+                                            app.set_text_input_state(
+                                                android_activity::input::TextInputState {
+                                                    text: String::new(),
+                                                    selection: android_activity::input::TextSpan {
+                                                        start: 0,
+                                                        end: 0,
+                                                    },
+                                                    compose_region: None,
+                                                },
+                                            );
+                                        }
+                                        android_activity::InputStatus::Handled
+                                    }
+                                    _ => android_activity::InputStatus::Unhandled,
                                 });
-                                if !read { break; }
+                                if !read {
+                                    break;
+                                }
                             }
                         }
                     }
@@ -506,7 +773,10 @@ impl App {
             });
 
             if should_exit_run_loop {
-                crate::android::logcat_warn("breaking Android run loop after MainEvent::Destroy");
+                crate::diagnostics::warn!(
+                    "android",
+                    "breaking Android run loop after MainEvent::Destroy"
+                );
                 break;
             }
 
@@ -523,7 +793,10 @@ impl App {
                     self.ctx.attach_graphics(g);
                 }
                 if graphics_finalized && !graphics_finalized_logged {
-                    crate::android::logcat_info("graphics initialization finalized successfully");
+                    crate::diagnostics::info!(
+                        "android",
+                        "graphics initialization finalized successfully"
+                    );
                     graphics_finalized_logged = true;
                 }
                 if graphics_finalized || was_floating_scene || self.scene.needs_initial_scene() {
@@ -531,11 +804,17 @@ impl App {
                         self.scene.restore_root_scene(&mut self.ctx);
 
                         if graphics_finalized {
-                            eprintln!("[spot][android] Scene recreated for new graphics device.");
+                            crate::diagnostics::info!(
+                                "android",
+                                "Scene recreated for new graphics device."
+                            );
                         } else if was_floating_scene {
-                            eprintln!("[spot][android] Main scene restored from floating state.");
+                            crate::diagnostics::info!(
+                                "android",
+                                "Main scene restored from floating state."
+                            );
                         } else {
-                            eprintln!("[spot][android] Scene initialized.");
+                            crate::diagnostics::info!("android", "Scene initialized.");
                         }
 
                         self.scene.clear_floating();
@@ -677,7 +956,11 @@ impl App {
                             }
                             x if x == ndk_sys::ASENSOR_TYPE_STEP_COUNTER as i32 => {
                                 let count = event.__bindgen_anon_1.u64_.step_counter;
-                                eprintln!("[spot][android] Step counter event: count={}", count);
+                                crate::diagnostics::debug!(
+                                    "android",
+                                    "Step counter event: count={}",
+                                    count
+                                );
 
                                 let state = self.platform.sensor_state.as_mut().unwrap();
                                 let current_day = Self::current_local_epoch_day();
@@ -686,15 +969,18 @@ impl App {
                                 let delta = match state.last_hardware_count {
                                     Some(previous) if count >= previous => count - previous,
                                     Some(previous) => {
-                                        eprintln!(
-                                            "[spot][android] Step counter reset detected: previous={} new={}",
-                                            previous, count
+                                        crate::diagnostics::info!(
+                                            "android",
+                                            "Step counter reset detected: previous={} new={}",
+                                            previous,
+                                            count
                                         );
                                         0
                                     }
                                     None => {
-                                        eprintln!(
-                                            "[spot][android] Establishing initial step counter at {}",
+                                        crate::diagnostics::debug!(
+                                            "android",
+                                            "Establishing initial step counter at {}",
                                             count
                                         );
                                         0
@@ -708,8 +994,9 @@ impl App {
                                 state.last_hardware_count = Some(count);
 
                                 if delta > 0 || rolled_day {
-                                    eprintln!(
-                                        "[spot][android] Updated daily steps: current_day={} today={} yesterday={} delta={} last_count={}",
+                                    crate::diagnostics::debug!(
+                                        "android",
+                                        "Updated daily steps: current_day={} today={} yesterday={} delta={} last_count={}",
                                         current_day,
                                         state.today_step_total,
                                         state.yesterday_step_total,
@@ -718,9 +1005,12 @@ impl App {
                                     );
                                 }
                                 Self::persist_step_state(state);
-                                eprintln!(
-                                    "[spot][android] Computed steps_today={} yesterday_steps={} using count={}",
-                                    state.today_step_total, state.yesterday_step_total, count,
+                                crate::diagnostics::debug!(
+                                    "android",
+                                    "Computed steps_today={} yesterday_steps={} using count={}",
+                                    state.today_step_total,
+                                    state.yesterday_step_total,
+                                    count,
                                 );
                                 self.ctx
                                     .input_mut()
@@ -736,14 +1026,24 @@ impl App {
             }
 
             self.poll_gamepads();
+            self.ctx.with_audio(|a| a.maintain());
+
+            self.scene.dispatch_user_events(&mut self.ctx);
+            crate::tasks::poll(&mut self.ctx);
 
             // Fixed update loop
             let updates = self.timing.run_updates(4, |dt| {
+                crate::lockstep::begin_tick(&mut self.ctx);
                 self.ctx.set_delta_time(dt);
+                crate::debug_overlay::handle_toggle_key(&mut self.ctx);
+                crate::tween::tick(&mut self.ctx, dt);
+                #[cfg(feature = "rapier2d")]
+                crate::physics::tick(&mut self.ctx, dt);
                 if let Some(spot) = self.scene.spot_mut() {
-                    spot.update(&mut self.ctx, dt);
+                    crate::lockstep::update(&mut self.ctx, spot.as_mut(), dt);
                 }
                 self.ctx.input_mut().end_frame();
+                crate::lockstep::end_tick(&mut self.ctx);
             });
             if updates > 0 {
                 self.request_redraw();
@@ -752,7 +1052,7 @@ impl App {
             // Draw
             if self.scene.has_active_scene() && self.platform.redraw_requested {
                 if !first_draw_logged {
-                    crate::android::logcat_info("starting first draw attempt");
+                    crate::diagnostics::info!("android", "starting first draw attempt");
                     first_draw_logged = true;
                 }
                 self.platform.redraw_requested = false;
@@ -760,19 +1060,23 @@ impl App {
                 let alpha = self.timing.alpha();
                 self.ctx.set_draw_alpha(alpha);
 
+                self.scene.dispatch_window_events(&mut self.ctx);
+
                 // Initialize frame context
                 self.ctx.begin_frame();
-                if let Some(spot) = self.scene.spot_mut() {
-                    let screen = super::make_screen_target(&self.ctx);
-                    spot.draw(&mut self.ctx, screen);
-                }
+                let screen = super::make_screen_target(&self.ctx);
+                self.scene.draw(&mut self.ctx, screen);
 
                 // Handle scene switch
                 if self.scene.apply_pending_switch(&mut self.ctx) {
-                    if let Some(spot) = self.scene.spot_mut() {
+                    if self.scene.is_transitioning() {
+                        // The outgoing frame was captured into this frame's draw list; the
+                        // new scene is drawn from the next frame on.
+                        self.request_redraw();
+                    } else if self.scene.has_active_scene() {
                         self.ctx.begin_frame();
                         let screen = super::make_screen_target(&self.ctx);
-                        spot.draw(&mut self.ctx, screen);
+                        self.scene.draw(&mut self.ctx, screen);
                     }
                 }
 
@@ -794,11 +1098,12 @@ impl App {
                 }
 
                 if let Some(Err(e)) = draw_result {
-                    crate::android::logcat_warn(&format!("draw_context failed with {:?}", e));
+                    crate::diagnostics::warn!("android", "draw_context failed with {:?}", e);
                     match e {
                         wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => {
-                            eprintln!(
-                                "[spot][android] Surface error: {:?}. Attempting recovery by re-creating surface.",
+                            crate::diagnostics::warn!(
+                                "android",
+                                "Surface error: {:?}. Attempting recovery by re-creating surface.",
                                 e
                             );
                             if let Some(window) = self.platform.native_window.clone() {
@@ -807,23 +1112,27 @@ impl App {
                             self.request_redraw();
                         }
                         wgpu::SurfaceError::Timeout => {
-                            eprintln!(
-                                "[spot][android] Surface acquisition timeout. Frame skipped."
+                            crate::diagnostics::warn!(
+                                "android",
+                                "Surface acquisition timeout. Frame skipped."
                             );
                             self.request_redraw();
                         }
                         wgpu::SurfaceError::OutOfMemory => {
-                            eprintln!("[spot][android] Out of memory error. Surface dropped.");
+                            crate::diagnostics::error!(
+                                "android",
+                                "Out of memory error. Surface dropped."
+                            );
                             self.surface.take();
                         }
                         _ => {
-                            eprintln!("[spot][android] Surface draw error: {:?}", e);
+                            crate::diagnostics::error!("android", "Surface draw error: {:?}", e);
                             self.request_redraw();
                         }
                     }
                 } else {
                     if !first_frame_presented_logged {
-                        crate::android::logcat_info("first frame submitted successfully");
+                        crate::diagnostics::info!("android", "first frame submitted successfully");
                         first_frame_presented_logged = true;
                     }
                     self.request_redraw();
@@ -836,17 +1145,20 @@ impl App {
 
                 frame_count += 1;
                 if frame_count % 300 == 0 {
-                    eprintln!("[spot][android] Loop alive. Frame: {}", frame_count);
+                    crate::diagnostics::debug!("android", "Loop alive. Frame: {}", frame_count);
                 }
             }
 
             if take_quit_request() {
-                crate::android::logcat_warn(
-                    "take_quit_request returned true; exiting Android run loop",
+                crate::diagnostics::warn!(
+                    "android",
+                    "take_quit_request returned true; exiting Android run loop"
                 );
                 break;
             }
         }
+
+        self.scene.shutdown(&mut self.ctx);
     }
 
     #[cfg(feature = "sensors")]
@@ -871,10 +1183,16 @@ impl App {
                     ndk_sys::ASENSOR_TYPE_STEP_COUNTER as i32,
                 );
                 if step_detector.is_null() {
-                    eprintln!("[spot][android] Step detector sensor unavailable on this device.");
+                    crate::diagnostics::warn!(
+                        "android",
+                        "Step detector sensor unavailable on this device."
+                    );
                 }
                 if step_counter.is_null() {
-                    eprintln!("[spot][android] Step counter sensor unavailable on this device.");
+                    crate::diagnostics::warn!(
+                        "android",
+                        "Step counter sensor unavailable on this device."
+                    );
                 }
 
                 let data_path = self
@@ -901,8 +1219,9 @@ impl App {
                         last_hardware_count = None;
                     }
                 }
-                eprintln!(
-                    "[spot][android] Restored step state: today={} yesterday={} last_day={} last_count={} file={}",
+                crate::diagnostics::debug!(
+                    "android",
+                    "Restored step state: today={} yesterday={} last_day={} last_count={} file={}",
                     today_step_total,
                     yesterday_step_total,
                     last_local_day,
@@ -953,52 +1272,52 @@ impl App {
                 );
 
                 if queue.is_null() {
-                    eprintln!("[spot][android] Failed to create sensor event queue");
+                    crate::diagnostics::error!("android", "Failed to create sensor event queue");
                     self.platform.sensor_state = None;
                     return;
                 }
                 state_ref.queue = queue;
-                eprintln!("[spot][android] Sensor event queue created successfully");
+                crate::diagnostics::debug!("android", "Sensor event queue created successfully");
             }
 
             if let Some(state) = self.platform.sensor_state.as_ref() {
                 if !state.accel.is_null() {
                     let rc = ndk_sys::ASensorEventQueue_enableSensor(state.queue, state.accel);
-                    eprintln!("[spot][android] Enable accelerometer rc={}", rc);
+                    crate::diagnostics::debug!("android", "Enable accelerometer rc={}", rc);
                     let rc =
                         ndk_sys::ASensorEventQueue_setEventRate(state.queue, state.accel, 20_000);
-                    eprintln!("[spot][android] Accelerometer event rate rc={}", rc);
+                    crate::diagnostics::debug!("android", "Accelerometer event rate rc={}", rc);
                 }
                 if !state.mag.is_null() {
                     let rc = ndk_sys::ASensorEventQueue_enableSensor(state.queue, state.mag);
-                    eprintln!("[spot][android] Enable magnetometer rc={}", rc);
+                    crate::diagnostics::debug!("android", "Enable magnetometer rc={}", rc);
                     let rc =
                         ndk_sys::ASensorEventQueue_setEventRate(state.queue, state.mag, 20_000);
-                    eprintln!("[spot][android] Magnetometer event rate rc={}", rc);
+                    crate::diagnostics::debug!("android", "Magnetometer event rate rc={}", rc);
                 }
                 if !state.gyro.is_null() {
                     let rc = ndk_sys::ASensorEventQueue_enableSensor(state.queue, state.gyro);
-                    eprintln!("[spot][android] Enable gyroscope rc={}", rc);
+                    crate::diagnostics::debug!("android", "Enable gyroscope rc={}", rc);
                     let rc =
                         ndk_sys::ASensorEventQueue_setEventRate(state.queue, state.gyro, 20_000);
-                    eprintln!("[spot][android] Gyroscope event rate rc={}", rc);
+                    crate::diagnostics::debug!("android", "Gyroscope event rate rc={}", rc);
                 }
                 if !state.rot.is_null() {
                     let rc = ndk_sys::ASensorEventQueue_enableSensor(state.queue, state.rot);
-                    eprintln!("[spot][android] Enable rotation vector rc={}", rc);
+                    crate::diagnostics::debug!("android", "Enable rotation vector rc={}", rc);
                     let rc =
                         ndk_sys::ASensorEventQueue_setEventRate(state.queue, state.rot, 20_000);
-                    eprintln!("[spot][android] Rotation vector event rate rc={}", rc);
+                    crate::diagnostics::debug!("android", "Rotation vector event rate rc={}", rc);
                 }
                 if !state.step_counter.is_null() {
                     let rc =
                         ndk_sys::ASensorEventQueue_enableSensor(state.queue, state.step_counter);
-                    eprintln!("[spot][android] Enable step counter rc={}", rc);
+                    crate::diagnostics::debug!("android", "Enable step counter rc={}", rc);
                 }
                 if !state.step_detector.is_null() {
                     let rc =
                         ndk_sys::ASensorEventQueue_enableSensor(state.queue, state.step_detector);
-                    eprintln!("[spot][android] Enable step detector rc={}", rc);
+                    crate::diagnostics::debug!("android", "Enable step detector rc={}", rc);
                 }
             }
         }
@@ -1,12 +1,12 @@
-use crate::{Spot, WindowConfig};
+use crate::{Spot, SpotError, WindowConfig};
 
 #[cfg(target_os = "android")]
 use android_activity::AndroidApp;
 
 pub(crate) trait WindowBackend {
     #[cfg(not(target_os = "android"))]
-    fn run<T: Spot + 'static>(window: WindowConfig);
+    fn run<T: Spot + 'static>(window: WindowConfig) -> Result<(), SpotError>;
 
     #[cfg(target_os = "android")]
-    fn run<T: Spot + 'static>(window: WindowConfig, app: AndroidApp);
+    fn run<T: Spot + 'static>(window: WindowConfig, app: AndroidApp) -> Result<(), SpotError>;
 }
@@ -1,7 +1,7 @@
 use super::App;
-use crate::Pt;
 use crate::platform;
 use crate::scenes::take_quit_request;
+use crate::{Pt, RedrawMode};
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 use wasm_bindgen::JsCast;
 use winit::application::ApplicationHandler;
@@ -12,12 +12,35 @@ use winit::window::{Fullscreen, Window, WindowId};
 pub(crate) struct PlatformData {
     pub(crate) window: Option<Window>,
     pub(crate) window_id: Option<WindowId>,
+    /// Set while a reactive app is waiting for something to redraw.
+    pub(crate) idle: bool,
     #[cfg(all(target_os = "ios", feature = "sensors"))]
     pub(crate) sensor_state: Option<super::ios::IosSensorState>,
     #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
     pub(crate) canvas_id: Option<String>,
     #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
     pub(crate) last_physical_size: Option<(u32, u32)>,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) canvas_observer: Option<super::wasm::CanvasObserver>,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "sensors"))]
+    pub(crate) device_sensors: Option<super::wasm::DeviceSensors>,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) virtual_keyboard: Option<super::wasm::VirtualKeyboard>,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) wake_lock: Option<super::wasm::ScreenWakeLock>,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) audio_unlock: super::wasm::AudioUnlock,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) paste_listener: Option<super::wasm::PasteListener>,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) battery: Option<super::wasm::BatteryMonitor>,
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) page_visibility: Option<super::wasm::PageVisibility>,
+    /// Set while the browser tab is hidden and the scene is suspended.
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) page_hidden: bool,
+    #[cfg(target_os = "ios")]
+    pub(crate) power_polled_at: Option<std::time::Instant>,
 }
 
 impl PlatformData {
@@ -26,12 +49,33 @@ impl PlatformData {
         Self {
             window: None,
             window_id: None,
+            idle: false,
             #[cfg(all(target_os = "ios", feature = "sensors"))]
             sensor_state: None,
             #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
             canvas_id: None,
             #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
             last_physical_size: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            canvas_observer: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "sensors"))]
+            device_sensors: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            virtual_keyboard: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            wake_lock: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            audio_unlock: super::wasm::AudioUnlock::default(),
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            paste_listener: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            battery: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            page_visibility: None,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            page_hidden: false,
+            #[cfg(target_os = "ios")]
+            power_polled_at: None,
         }
     }
 
@@ -40,18 +84,76 @@ impl PlatformData {
         Self {
             window: None,
             window_id: None,
+            idle: false,
             #[cfg(all(target_os = "ios", feature = "sensors"))]
             sensor_state: None,
             canvas_id,
             last_physical_size: None,
+            canvas_observer: None,
+            #[cfg(feature = "sensors")]
+            device_sensors: None,
+            virtual_keyboard: None,
+            wake_lock: None,
+            audio_unlock: super::wasm::AudioUnlock::default(),
+            paste_listener: None,
+            battery: None,
+            page_visibility: None,
+            page_hidden: false,
         }
     }
 }
 
+fn from_winit_theme(theme: winit::window::Theme) -> crate::Theme {
+    match theme {
+        winit::window::Theme::Light => crate::Theme::Light,
+        winit::window::Theme::Dark => crate::Theme::Dark,
+    }
+}
+
+fn to_winit_window_level(level: crate::WindowLevel) -> winit::window::WindowLevel {
+    match level {
+        crate::WindowLevel::AlwaysOnBottom => winit::window::WindowLevel::AlwaysOnBottom,
+        crate::WindowLevel::Normal => winit::window::WindowLevel::Normal,
+        crate::WindowLevel::AlwaysOnTop => winit::window::WindowLevel::AlwaysOnTop,
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+fn position_on_any_monitor(event_loop: &ActiveEventLoop, (x, y): (i32, i32)) -> bool {
+    event_loop.available_monitors().any(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        (origin.x..origin.x + size.width as i32).contains(&x)
+            && (origin.y..origin.y + size.height as i32).contains(&y)
+    })
+}
+
 impl App {
     fn sync_window_metrics(&mut self, width: u32, height: u32) {
         self.ctx
             .update_window_metrics_physical(width, height, self.scale_factor);
+        #[cfg(target_os = "ios")]
+        self.sync_safe_area();
+    }
+
+    /// winit reports the safe area as the inner rect and the whole screen as the outer
+    /// rect on iOS, so the insets are the difference between the two.
+    #[cfg(target_os = "ios")]
+    fn sync_safe_area(&mut self) {
+        let Some(window) = self.platform.window.as_ref() else {
+            return;
+        };
+        let (Ok(inner), Ok(outer)) = (window.inner_position(), window.outer_position()) else {
+            return;
+        };
+        let (inner_size, outer_size) = (window.inner_size(), window.outer_size());
+        let top = (inner.y - outer.y) as f64;
+        let left = (inner.x - outer.x) as f64;
+        let bottom = outer_size.height as f64 - inner_size.height as f64 - top;
+        let right = outer_size.width as f64 - inner_size.width as f64 - left;
+        let pt = |px: f64| Pt::from_physical_px(px, self.scale_factor);
+        self.ctx
+            .set_safe_area(pt(top), pt(bottom), pt(left), pt(right));
     }
 
     fn request_redraw(&self) {
@@ -60,11 +162,40 @@ impl App {
         }
     }
 
+    /// Converts a logical size into a winit size, honoring a forced scale factor.
+    fn to_winit_size(&self, width: Pt, height: Pt) -> winit::dpi::Size {
+        let width = width.0.max(1.0) as f64;
+        let height = height.0.max(1.0) as f64;
+        match self.window_config.forced_scale_factor() {
+            // The logical size is relative to the forced scale factor, not the display's.
+            Some(scale_factor) => {
+                winit::dpi::PhysicalSize::new(width * scale_factor, height * scale_factor).into()
+            }
+            None => winit::dpi::LogicalSize::new(width, height).into(),
+        }
+    }
+
+    fn to_winit_position(&self, x: Pt, y: Pt) -> winit::dpi::Position {
+        let x = x.0 as f64;
+        let y = y.0 as f64;
+        match self.window_config.forced_scale_factor() {
+            Some(scale_factor) => {
+                winit::dpi::PhysicalPosition::new(x * scale_factor, y * scale_factor).into()
+            }
+            None => winit::dpi::LogicalPosition::new(x, y).into(),
+        }
+    }
+
     fn apply_pending_window_requests(&mut self) {
         let Some(window) = self.platform.window.as_ref() else {
             let _ = self.ctx.take_window_title_request();
             let _ = self.ctx.take_cursor_visible_request();
             let _ = self.ctx.take_fullscreen_request();
+            let _ = self.ctx.take_window_level_request();
+            let _ = self.ctx.take_window_size_request();
+            let _ = self.ctx.take_window_position_request();
+            let _ = self.ctx.take_virtual_keyboard_request();
+            let _ = self.ctx.take_keep_screen_on_request();
             return;
         };
 
@@ -81,6 +212,65 @@ impl App {
                 window.set_fullscreen(None);
             }
         }
+        if let Some(level) = self.ctx.take_window_level_request() {
+            window.set_window_level(to_winit_window_level(level));
+        }
+        if let Some((x, y)) = self.ctx.take_window_position_request() {
+            window.set_outer_position(self.to_winit_position(x, y));
+        }
+        if let Some(visible) = self.ctx.take_virtual_keyboard_request() {
+            // winit does not report the keyboard's frame on iOS.
+            #[cfg(target_os = "ios")]
+            {
+                window.set_ime_allowed(visible);
+                self.ctx.set_virtual_keyboard(visible.then_some(Pt(0.0)));
+            }
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            {
+                self.platform.virtual_keyboard =
+                    visible.then(super::wasm::VirtualKeyboard::show).flatten();
+                if !visible {
+                    self.ctx.set_virtual_keyboard(None);
+                }
+            }
+            #[cfg(not(any(
+                target_os = "ios",
+                all(target_arch = "wasm32", target_os = "unknown")
+            )))]
+            let _ = visible;
+        }
+        if let Some(enabled) = self.ctx.take_keep_screen_on_request() {
+            #[cfg(target_os = "ios")]
+            crate::ios::set_idle_timer_disabled(enabled);
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            {
+                self.platform.wake_lock = enabled.then(super::wasm::ScreenWakeLock::default);
+            }
+            #[cfg(not(any(
+                target_os = "ios",
+                all(target_arch = "wasm32", target_os = "unknown")
+            )))]
+            let _ = enabled;
+        }
+        if let Some((width, height)) = self.ctx.take_window_size_request() {
+            // Platforms that resize synchronously return the new size instead of
+            // sending a `Resized` event.
+            if let Some(size) = window.request_inner_size(self.to_winit_size(width, height)) {
+                if let Some(surface) = self.surface.as_ref()
+                    && let Some(g) = self.ctx.graphics_mut()
+                {
+                    g.resize(surface, size.width, size.height);
+                }
+                self.sync_window_metrics(size.width, size.height);
+            }
+        }
+    }
+
+    /// Stops the application, which makes `run` return `error`.
+    fn fail(&mut self, error: crate::SpotError) {
+        crate::diagnostics::error!("init", "{error}");
+        self.error.get_or_insert(error);
+        crate::quit();
     }
 
     fn create_window_if_needed(&mut self, event_loop: &ActiveEventLoop) {
@@ -91,7 +281,8 @@ impl App {
         let attributes = Window::default_attributes()
             .with_title(self.window_config.title.clone())
             .with_resizable(self.window_config.resizable)
-            .with_transparent(self.window_config.transparent);
+            .with_transparent(self.window_config.transparent)
+            .with_window_level(to_winit_window_level(self.window_config.window_level));
 
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
         let attributes = {
@@ -114,30 +305,111 @@ impl App {
         };
 
         #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
-        let attributes = {
-            let width = self.window_config.width.0.max(1.0) as f64;
-            let height = self.window_config.height.0.max(1.0) as f64;
-            attributes.with_inner_size(winit::dpi::LogicalSize::new(width, height))
-        };
+        let restored = self
+            .window_config
+            .remember_geometry
+            .as_deref()
+            .and_then(super::geometry::WindowGeometry::load);
 
-        let window = event_loop
-            .create_window(attributes)
-            .expect("failed to create window");
+        #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+        let attributes = match restored {
+            Some(geometry) => {
+                let (width, height) = geometry.size;
+                let attributes =
+                    attributes.with_inner_size(winit::dpi::PhysicalSize::new(width, height));
+                // Skip positions on monitors that have since been disconnected.
+                match geometry
+                    .position
+                    .filter(|&position| position_on_any_monitor(event_loop, position))
+                {
+                    Some((x, y)) => {
+                        attributes.with_position(winit::dpi::PhysicalPosition::new(x, y))
+                    }
+                    None => attributes,
+                }
+            }
+            None => attributes.with_inner_size(
+                self.to_winit_size(self.window_config.width, self.window_config.height),
+            ),
+        };
+        #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+        let fullscreen = restored.map_or(self.window_config.fullscreen, |g| g.fullscreen);
+        #[cfg(any(target_os = "ios", target_os = "android", target_arch = "wasm32"))]
+        let fullscreen = self.window_config.fullscreen;
+
+        let window = match event_loop.create_window(attributes) {
+            Ok(window) => window,
+            Err(error) => {
+                self.fail(crate::SpotError::Window(error.into()));
+                return;
+            }
+        };
+        // On iOS allowing IME shows the keyboard, which waits for `show_virtual_keyboard`.
+        #[cfg(not(target_os = "ios"))]
         window.set_ime_allowed(true);
-        if self.window_config.fullscreen {
+        if fullscreen {
             window.set_fullscreen(Some(Fullscreen::Borderless(None)));
         }
 
-        self.scale_factor = window.scale_factor();
+        self.scale_factor = self
+            .window_config
+            .resolve_scale_factor(window.scale_factor());
         let size = window.inner_size();
         self.sync_window_metrics(size.width, size.height);
-        eprintln!(
-            "[spot][init] Window created: {}x{} (dpr: {})",
-            size.width, size.height, self.scale_factor
+        self.ctx
+            .set_system_theme(window.theme().map(from_winit_theme));
+        crate::diagnostics::info!(
+            "init",
+            "Window created: {}x{} (dpr: {})",
+            size.width,
+            size.height,
+            self.scale_factor
         );
 
         self.platform.window_id = Some(window.id());
         self.platform.window = Some(window);
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        self.observe_canvas();
+    }
+
+    /// Saves the window's placement if [`WindowConfig::remember_geometry`] is set.
+    #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+    fn save_window_geometry(&self) {
+        use super::geometry::WindowGeometry;
+
+        let (Some(app_name), Some(window)) = (
+            self.window_config.remember_geometry.as_deref(),
+            self.platform.window.as_ref(),
+        ) else {
+            return;
+        };
+        let geometry = if window.fullscreen().is_some() {
+            // Keep the windowed placement so leaving fullscreen next run restores it.
+            match WindowGeometry::load(app_name) {
+                Some(previous) => WindowGeometry {
+                    fullscreen: true,
+                    ..previous
+                },
+                None => {
+                    let size = self
+                        .to_winit_size(self.window_config.width, self.window_config.height)
+                        .to_physical::<u32>(window.scale_factor());
+                    WindowGeometry {
+                        position: None,
+                        size: (size.width, size.height),
+                        fullscreen: true,
+                    }
+                }
+            }
+        } else {
+            let size = window.inner_size();
+            WindowGeometry {
+                position: window.outer_position().ok().map(|p| (p.x, p.y)),
+                size: (size.width, size.height),
+                fullscreen: false,
+            }
+        };
+        geometry.save(app_name);
     }
 
     #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
@@ -145,7 +417,7 @@ impl App {
         if self.ctx.runtime.audio.is_none() {
             match crate::audio::AudioSystem::new() {
                 Ok(audio) => self.ctx.runtime.audio = Some(audio),
-                Err(e) => eprintln!("[spot][audio] initialization failed: {:?}", e),
+                Err(e) => crate::diagnostics::error!("audio", "initialization failed: {:?}", e),
             }
         }
     }
@@ -174,7 +446,7 @@ impl App {
                     g.resize(surface, size.width, size.height);
                 }
             }
-            Err(e) => eprintln!("[spot][surface] create failed: {:?}", e),
+            Err(e) => crate::diagnostics::error!("surface", "create failed: {:?}", e),
         }
     }
 
@@ -196,10 +468,10 @@ impl App {
                 {
                     g.resize(surface, size.width, size.height);
                 }
-                eprintln!("[spot][surface] Surface recreated successfully.");
+                crate::diagnostics::info!("surface", "Surface recreated successfully.");
             }
             Err(e) => {
-                eprintln!("[spot][surface] recreate after error failed: {:?}", e);
+                crate::diagnostics::error!("surface", "recreate after error failed: {:?}", e);
                 self.surface.take();
             }
         }
@@ -219,15 +491,15 @@ impl App {
         let size = window.inner_size();
 
         #[cfg(not(target_arch = "wasm32"))]
-        {
-            platform::begin_graphics_init(
-                &mut self.init_state,
-                &self.instance,
-                surface,
-                size.width,
-                size.height,
-                self.window_config.transparent,
-            );
+        if let Err(error) = platform::begin_graphics_init(
+            &mut self.init_state,
+            &self.instance,
+            surface,
+            size.width,
+            size.height,
+            self.window_config.transparent,
+        ) {
+            self.fail(crate::SpotError::Graphics(error));
         }
 
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
@@ -263,8 +535,9 @@ impl App {
     fn handle_surface_error(&mut self, event_loop: &ActiveEventLoop, error: wgpu::SurfaceError) {
         match error {
             wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => {
-                eprintln!(
-                    "[spot][surface] Surface lost or outdated: {:?}. Recreating...",
+                crate::diagnostics::warn!(
+                    "surface",
+                    "Surface lost or outdated: {:?}. Recreating...",
                     error
                 );
                 self.recreate_surface();
@@ -272,7 +545,7 @@ impl App {
             }
             wgpu::SurfaceError::OutOfMemory => event_loop.exit(),
             wgpu::SurfaceError::Timeout | wgpu::SurfaceError::Other => {
-                eprintln!("[spot][surface] draw error: {:?}", error);
+                crate::diagnostics::error!("surface", "draw error: {:?}", error);
                 self.request_redraw();
             }
         }
@@ -292,13 +565,12 @@ impl App {
         let alpha = self.timing.alpha();
         self.ctx.set_draw_alpha(alpha);
 
+        self.scene.dispatch_window_events(&mut self.ctx);
         self.ctx.begin_frame();
         let screen = super::make_screen_target(&self.ctx);
         let scene_draw_started_at =
             crate::graphics::profile::render_profiling_enabled().then(std::time::Instant::now);
-        if let Some(spot) = self.scene.spot_mut() {
-            spot.draw(&mut self.ctx, screen);
-        }
+        self.scene.draw(&mut self.ctx, screen);
         if let Some(scene_draw_started_at) = scene_draw_started_at {
             crate::graphics::profile::record_scene_draw(
                 scene_draw_started_at.elapsed().as_secs_f64() * 1000.0,
@@ -317,7 +589,7 @@ impl App {
 
         if let Some(Err(error)) = draw_result {
             self.handle_surface_error(event_loop, error);
-        } else {
+        } else if self.window_config.redraw == RedrawMode::Continuous {
             self.request_redraw();
         }
     }
@@ -326,11 +598,13 @@ impl App {
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         self.timing.reset();
+        self.ctx.request_redraw();
         event_loop.set_control_flow(ControlFlow::WaitUntil(self.timing.next_deadline()));
 
         self.create_window_if_needed(event_loop);
         #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
         self.ensure_audio_initialized();
+        self.ctx.with_audio(|a| a.resume());
         self.ensure_surface();
         self.begin_graphics_init_if_needed();
 
@@ -348,6 +622,24 @@ impl ApplicationHandler for App {
             }
         }
 
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "sensors"))]
+        if self.platform.device_sensors.is_none() {
+            self.platform.device_sensors = super::wasm::DeviceSensors::listen();
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        if self.platform.paste_listener.is_none() {
+            self.platform.paste_listener = super::wasm::PasteListener::listen();
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        if self.platform.battery.is_none() {
+            self.platform.battery = super::wasm::BatteryMonitor::request();
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        if self.platform.page_visibility.is_none() {
+            self.platform.page_visibility = super::wasm::PageVisibility::listen();
+        }
+
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
         {
             self.sync_canvas_resize();
@@ -375,6 +667,7 @@ impl ApplicationHandler for App {
         }
         self.ctx.clear_transient_input();
         self.ctx.clear_transient_state();
+        self.ctx.with_audio(|a| a.suspend());
         #[cfg(all(target_os = "ios", feature = "sensors"))]
         if let Some(state) = self.platform.sensor_state.as_ref() {
             state.disable();
@@ -383,17 +676,31 @@ impl ApplicationHandler for App {
     }
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        if !matches!(event, WindowEvent::RedrawRequested) {
+            self.ctx.request_redraw();
+        }
+        self.scene.dispatch_raw_event(&mut self.ctx, &event);
         match event {
             WindowEvent::CloseRequested => event_loop.exit(),
             WindowEvent::Focused(focused) => self.ctx.input_mut().handle_focus(focused),
             WindowEvent::Ime(ime) => self.ctx.input_mut().handle_ime(ime),
-            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                self.scale_factor = scale_factor;
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                mut inner_size_writer,
+            } => {
+                self.scale_factor = self.window_config.resolve_scale_factor(scale_factor);
                 if let Some(window) = self.platform.window.as_ref() {
+                    // A forced scale factor keeps the window's pixel size across displays.
+                    if self.window_config.forced_scale_factor().is_some() {
+                        let _ = inner_size_writer.request_inner_size(window.inner_size());
+                    }
                     let size = window.inner_size();
                     self.sync_window_metrics(size.width, size.height);
                 }
             }
+            WindowEvent::ThemeChanged(theme) => {
+                self.ctx.set_system_theme(Some(from_winit_theme(theme)));
+            }
             WindowEvent::Resized(new_size) => {
                 if let Some(surface) = self.surface.as_ref()
                     && let Some(g) = self.ctx.graphics_mut()
@@ -415,14 +722,15 @@ impl ApplicationHandler for App {
                 self.ctx.input_mut().handle_cursor_moved(x, y);
             }
             WindowEvent::Touch(touch) => {
+                #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+                if touch.phase == winit::event::TouchPhase::Ended {
+                    self.resume_audio_on_gesture();
+                }
                 self.ctx.input_mut().handle_touch(touch, self.scale_factor);
             }
             WindowEvent::MouseInput { state, button, .. } => {
                 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-                {
-                    self.init_audio_on_gesture();
-                    platform::try_resume_audio(&mut self.ctx);
-                }
+                self.resume_audio_on_gesture();
                 self.ctx.input_mut().handle_mouse_input(state, button);
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -430,10 +738,7 @@ impl ApplicationHandler for App {
             }
             WindowEvent::KeyboardInput { event, .. } => {
                 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-                {
-                    self.init_audio_on_gesture();
-                    platform::try_resume_audio(&mut self.ctx);
-                }
+                self.resume_audio_on_gesture();
 
                 self.ctx
                     .input_mut()
@@ -452,6 +757,11 @@ impl ApplicationHandler for App {
         }
     }
 
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, _event: ()) {
+        // Only used to wake the loop; queued events are delivered in `about_to_wait`.
+        self.ctx.request_redraw();
+    }
+
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
         self.apply_pending_window_requests();
         if take_quit_request() {
@@ -459,7 +769,63 @@ impl ApplicationHandler for App {
             return;
         }
 
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            self.sync_page_visibility();
+            if self.platform.page_hidden {
+                event_loop.set_control_flow(ControlFlow::Wait);
+                return;
+            }
+        }
+
+        let reactive = self.window_config.redraw == RedrawMode::Reactive;
+        if reactive {
+            if !self.ctx.take_redraw_request() {
+                self.platform.idle = true;
+                event_loop.set_control_flow(ControlFlow::Wait);
+                return;
+            }
+            if std::mem::take(&mut self.platform.idle) {
+                self.timing.resume_after_idle();
+            }
+        }
+
         self.poll_gamepads();
+        self.ctx.with_audio(|a| a.maintain());
+        self.scene.dispatch_user_events(&mut self.ctx);
+        crate::tasks::poll(&mut self.ctx);
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        if let Some(keyboard) = self.platform.virtual_keyboard.as_ref() {
+            keyboard.poll(&mut self.ctx);
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        if let Some(wake_lock) = self.platform.wake_lock.as_ref() {
+            wake_lock.acquire_if_released();
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        self.track_audio_unlock();
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        if let Some(text) = self.platform.paste_listener.as_ref().and_then(|p| p.take()) {
+            self.ctx.runtime.clipboard = Some(text);
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        if let Some(battery) = self.platform.battery.as_ref() {
+            battery.poll(&mut self.ctx);
+        }
+        #[cfg(target_os = "ios")]
+        if !self
+            .platform
+            .power_polled_at
+            .is_some_and(|at| at.elapsed() < crate::power::POWER_POLL_INTERVAL)
+        {
+            self.platform.power_polled_at = Some(std::time::Instant::now());
+            self.ctx.runtime.power = crate::ios::power_status();
+        }
+        #[cfg(all(
+            feature = "hot-reload",
+            any(target_os = "windows", target_os = "macos", target_os = "linux")
+        ))]
+        crate::hot_reload::poll(&mut self.ctx);
 
         // Run logic updates with fixed timestep (capped at 8 updates to prevent spiral of death)
         self.timing.run_updates(8, |dt| {
@@ -467,12 +833,21 @@ impl ApplicationHandler for App {
             if let Some(state) = self.platform.sensor_state.as_ref() {
                 state.poll(&mut self.ctx.input_mut());
             }
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "sensors"))]
+            if let Some(sensors) = self.platform.device_sensors.as_ref() {
+                sensors.poll(self.ctx.input_mut());
+            }
 
+            crate::lockstep::begin_tick(&mut self.ctx);
             self.ctx.set_delta_time(dt);
+            crate::debug_overlay::handle_toggle_key(&mut self.ctx);
+            crate::tween::tick(&mut self.ctx, dt);
+            #[cfg(feature = "rapier2d")]
+            crate::physics::tick(&mut self.ctx, dt);
             let scene_update_started_at =
                 crate::graphics::profile::render_profiling_enabled().then(std::time::Instant::now);
             if let Some(spot) = self.scene.spot_mut() {
-                spot.update(&mut self.ctx, dt);
+                crate::lockstep::update(&mut self.ctx, spot.as_mut(), dt);
             }
             if let Some(scene_update_started_at) = scene_update_started_at {
                 crate::graphics::profile::record_scene_update(
@@ -480,20 +855,29 @@ impl ApplicationHandler for App {
                 );
             }
             self.ctx.input_mut().end_frame();
+            crate::lockstep::end_tick(&mut self.ctx);
         });
 
         // Always request a redraw to allow dynamic rendering (following screen refresh rate)
         self.request_redraw();
 
-        // Use Poll to ensure we can redraw as fast as V-Sync/OS allows
-        event_loop.set_control_flow(ControlFlow::Poll);
+        if reactive {
+            // The redraw above wakes the loop; stay asleep afterwards unless asked again.
+            event_loop.set_control_flow(ControlFlow::Wait);
+        } else {
+            // Use Poll to ensure we can redraw as fast as V-Sync/OS allows
+            event_loop.set_control_flow(ControlFlow::Poll);
+        }
     }
 
     fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        self.scene.shutdown(&mut self.ctx);
         if let Some(graphics) = self.ctx.runtime.graphics.as_mut() {
             graphics.finish_profiling();
         }
         crate::graphics::profile::finalize_render_profiling();
+        #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+        self.save_window_geometry();
         self.surface.take();
         self.platform.window.take();
     }
@@ -0,0 +1,100 @@
+//! Window placement remembered across runs through [`crate::storage`].
+
+/// Storage key the geometry is saved under.
+const GEOMETRY_KEY: &str = "window-geometry";
+
+/// Physical window placement as saved on exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WindowGeometry {
+    /// Outer position in desktop coordinates, if the platform reports one.
+    pub(crate) position: Option<(i32, i32)>,
+    /// Inner size in physical pixels while windowed.
+    pub(crate) size: (u32, u32),
+    pub(crate) fullscreen: bool,
+}
+
+impl WindowGeometry {
+    pub(crate) fn load(app_name: &str) -> Option<Self> {
+        match crate::storage::load(app_name, GEOMETRY_KEY) {
+            Ok(bytes) => Self::decode(&String::from_utf8_lossy(&bytes?)),
+            Err(e) => {
+                crate::diagnostics::error!("window", "failed to load window geometry: {e}");
+                None
+            }
+        }
+    }
+
+    pub(crate) fn save(&self, app_name: &str) {
+        if let Err(e) = crate::storage::save(app_name, GEOMETRY_KEY, self.encode().as_bytes()) {
+            crate::diagnostics::error!("window", "failed to save window geometry: {e}");
+        }
+    }
+
+    fn encode(&self) -> String {
+        let mut text = format!(
+            "width={}\nheight={}\nfullscreen={}\n",
+            self.size.0, self.size.1, self.fullscreen
+        );
+        if let Some((x, y)) = self.position {
+            text.push_str(&format!("x={x}\ny={y}\n"));
+        }
+        text
+    }
+
+    /// Parses `key=value` lines, ignoring unknown keys. Returns `None` without a valid size.
+    fn decode(text: &str) -> Option<Self> {
+        let (mut width, mut height, mut x, mut y) = (None, None, None, None);
+        let mut fullscreen = false;
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "width" => width = value.parse::<u32>().ok(),
+                "height" => height = value.parse::<u32>().ok(),
+                "x" => x = value.parse::<i32>().ok(),
+                "y" => y = value.parse::<i32>().ok(),
+                "fullscreen" => fullscreen = value == "true",
+                _ => {}
+            }
+        }
+        let size = (width.filter(|w| *w > 0)?, height.filter(|h| *h > 0)?);
+        Some(Self {
+            position: x.zip(y),
+            size,
+            fullscreen,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_round_trips() {
+        let geometry = WindowGeometry {
+            position: Some((-1280, 40)),
+            size: (1600, 900),
+            fullscreen: true,
+        };
+        assert_eq!(WindowGeometry::decode(&geometry.encode()), Some(geometry));
+
+        let geometry = WindowGeometry {
+            position: None,
+            ..geometry
+        };
+        assert_eq!(WindowGeometry::decode(&geometry.encode()), Some(geometry));
+    }
+
+    #[test]
+    fn geometry_without_size_is_rejected() {
+        assert_eq!(WindowGeometry::decode("x=0\ny=0\n"), None);
+        assert_eq!(WindowGeometry::decode("width=0\nheight=600\n"), None);
+        assert_eq!(
+            WindowGeometry::decode("width=800\nheight=600\nunknown=1\n").map(|g| g.size),
+            Some((800, 600))
+        );
+    }
+}
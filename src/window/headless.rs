@@ -0,0 +1,109 @@
+//! Windowless runner that renders every frame into an offscreen texture, or on the CPU
+//! with the `software-render` feature.
+
+use super::SceneHost;
+use crate::graphics::core::Graphics;
+use crate::scenes::{take_quit_request, take_scene_error};
+use crate::{Context, HeadlessConfig, Spot, SpotError, platform};
+
+/// Runs `T` headless; `software` rasterizes frames on the CPU instead of a GPU.
+pub(crate) fn run<T: Spot + 'static>(
+    config: HeadlessConfig,
+    software: bool,
+) -> Result<(), SpotError> {
+    let step = config.fixed_update_step();
+    let (width, height) = config.physical_size();
+
+    let mut ctx = Box::pin(Context::new());
+    ctx.update_window_metrics_physical(width, height, config.scale_factor);
+    ctx.runtime.lockstep.enabled = config.deterministic;
+    if !software {
+        let instance = platform::create_wgpu_instance(crate::GraphicsBackend::Auto);
+        let graphics = platform::block_on(Graphics::new_headless(
+            &instance,
+            width,
+            height,
+            config.transparent,
+        ))
+        .map_err(SpotError::Graphics)?;
+        ctx.attach_graphics(graphics);
+    }
+
+    let _ = take_scene_error();
+    let mut scene = SceneHost::new::<T>();
+    scene.initialize_if_missing(&mut ctx);
+
+    let mut frame = 0;
+    let result = loop {
+        if take_quit_request() || config.frames.is_some_and(|frames| frame >= frames) {
+            break Ok(());
+        }
+
+        // There is no window to apply these to.
+        let _ = ctx.take_window_title_request();
+        let _ = ctx.take_cursor_visible_request();
+        let _ = ctx.take_fullscreen_request();
+        let _ = ctx.take_window_level_request();
+        let _ = ctx.take_window_position_request();
+        if let Some((w, h)) = ctx.take_window_size_request() {
+            let scale_factor = ctx.scale_factor();
+            let width = (w * scale_factor as f32).to_u32_clamped().max(1);
+            let height = (h * scale_factor as f32).to_u32_clamped().max(1);
+            if let Some(graphics) = ctx.graphics_mut() {
+                graphics.resize_headless(width, height);
+            }
+            ctx.update_window_metrics_physical(width, height, scale_factor);
+        }
+
+        scene.dispatch_user_events(&mut ctx);
+        crate::tasks::poll(&mut ctx);
+        crate::lockstep::begin_tick(&mut ctx);
+        ctx.set_delta_time(step);
+        crate::debug_overlay::handle_toggle_key(&mut ctx);
+        crate::tween::tick(&mut ctx, step);
+        #[cfg(feature = "rapier2d")]
+        crate::physics::tick(&mut ctx, step);
+        if let Some(spot) = scene.spot_mut() {
+            crate::lockstep::update(&mut ctx, spot.as_mut(), step);
+        }
+        ctx.input_mut().end_frame();
+        crate::lockstep::end_tick(&mut ctx);
+
+        ctx.set_draw_alpha(0.0);
+        scene.dispatch_window_events(&mut ctx);
+        ctx.begin_frame();
+        let screen = super::make_screen_target(&ctx);
+        scene.draw(&mut ctx, screen);
+        scene.apply_pending_switch(&mut ctx);
+
+        #[cfg(feature = "software-render")]
+        if software {
+            let capture = crate::software_render::render(&mut ctx, config.transparent);
+            ctx.runtime.last_frame = Some(capture);
+            frame += 1;
+            continue;
+        }
+        let Some(mut graphics) = ctx.detach_graphics() else {
+            break Err(SpotError::Graphics(anyhow::anyhow!(
+                "headless graphics were detached"
+            )));
+        };
+        let capture = graphics.draw_headless(&mut ctx);
+        ctx.attach_graphics(graphics);
+        match capture {
+            Ok(capture) => ctx.runtime.last_frame = Some(capture),
+            Err(e) => break Err(SpotError::Frame(e)),
+        }
+        frame += 1;
+    };
+
+    scene.shutdown(&mut ctx);
+    if let Some(graphics) = ctx.runtime.graphics.as_mut() {
+        graphics.finish_profiling();
+    }
+    crate::graphics::profile::finalize_render_profiling();
+    match take_scene_error() {
+        Some(error) => Err(SpotError::Scene(error)),
+        None => result,
+    }
+}
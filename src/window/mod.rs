@@ -1,6 +1,9 @@
 use crate::platform;
-use crate::scenes::{SceneFactory, ScenePayloadTypeId, Spot, take_scene_switch_request};
-use crate::{Context, WindowConfig, gamepad};
+use crate::scenes::{
+    SceneFactory, ScenePayload, ScenePayloadTypeId, SceneRequest, Spot, Transition,
+    take_scene_switch_request,
+};
+use crate::{Context, DrawOption, WindowConfig, gamepad};
 use std::pin::Pin;
 use std::rc::Rc;
 use std::time::Duration;
@@ -24,6 +27,10 @@ pub mod backend;
     )
 ))]
 pub mod desktop;
+#[cfg(not(any(target_os = "android", target_os = "ios", target_arch = "wasm32")))]
+mod geometry;
+#[cfg(not(any(target_os = "android", target_os = "ios", target_arch = "wasm32")))]
+pub mod headless;
 #[cfg(target_os = "ios")]
 pub mod ios;
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
@@ -96,6 +103,14 @@ impl FixedTimestep {
         }
     }
 
+    /// Restarts timing after an idle period so that the next `run_updates` runs a single step.
+    #[cfg(not(target_os = "android"))]
+    pub(crate) fn resume_after_idle(&mut self) {
+        let now = Instant::now();
+        self.previous = Some(now.checked_sub(self.step).unwrap_or(now));
+        self.lag = Duration::ZERO;
+    }
+
     pub(crate) fn next_deadline(&self) -> Instant {
         let now = Instant::now();
         match self.previous {
@@ -109,32 +124,48 @@ impl FixedTimestep {
     }
 }
 
+/// A running [`Transition`], drawn over the incoming scene until it completes.
+struct ActiveTransition {
+    transition: Transition,
+    started: Instant,
+}
+
 pub(crate) struct SceneHost {
-    spot: Option<Box<dyn Spot>>,
+    /// Active scene last; the scenes below it were paused by `push_scene`.
+    stack: Vec<Box<dyn Spot>>,
     factory: SceneFactory,
     is_floating_scene: bool,
+    transition: Option<ActiveTransition>,
+    /// Last frame of the outgoing scene, reused across transitions of the same size.
+    capture: Option<crate::Texture>,
+    /// Backdrop for captures and fades.
+    black: Option<crate::Image>,
 }
 
 impl SceneHost {
     pub(crate) fn new<T: Spot + 'static>() -> Self {
         Self {
-            spot: None,
-            factory: Box::new(|ctx| Box::new(T::initialize(ctx))),
+            stack: Vec::new(),
+            factory: crate::scenes::scene_factory::<T>(),
             is_floating_scene: false,
+            transition: None,
+            capture: None,
+            black: None,
         }
     }
 
+    /// The active scene, on top of the stack.
     pub(crate) fn spot_mut(&mut self) -> Option<&mut Box<dyn Spot>> {
-        self.spot.as_mut()
+        self.stack.last_mut()
     }
 
     pub(crate) fn has_active_scene(&self) -> bool {
-        self.spot.is_some()
+        !self.stack.is_empty()
     }
 
     #[cfg(target_os = "android")]
     pub(crate) fn needs_initial_scene(&self) -> bool {
-        self.spot.is_none()
+        self.stack.is_empty()
     }
 
     #[cfg(target_os = "android")]
@@ -153,27 +184,210 @@ impl SceneHost {
     }
 
     #[cfg(target_os = "android")]
-    pub(crate) fn set_active_scene(&mut self, spot: Box<dyn Spot>) {
-        self.spot = Some(spot);
+    pub(crate) fn set_active_scene(&mut self, ctx: &mut Context, spot: Box<dyn Spot>) {
+        ctx.clear_window_events();
+        self.stack = vec![spot];
+    }
+
+    /// Makes a newly initialized scene the active one, or stops the application if it
+    /// failed to initialize.
+    fn push_or_fail(&mut self, ctx: &mut Context, spot: anyhow::Result<Box<dyn Spot>>) {
+        match spot {
+            Ok(spot) => self.stack.push(spot),
+            Err(error) => {
+                ctx.release_scene_scope();
+                crate::scenes::fail_scene(error);
+            }
+        }
     }
 
+    /// Lets every scene save its state, then removes them, as the application exits.
+    pub(crate) fn shutdown(&mut self, ctx: &mut Context) {
+        for spot in self.stack.iter_mut().rev() {
+            spot.on_exit(ctx);
+        }
+        self.remove_current(ctx);
+        ctx.release_retired_scene_scopes();
+        self.transition = None;
+    }
+
+    /// Removes every scene on the stack, top first. Their assets are released at the
+    /// start of the next frame, after this one drew the outgoing scenes.
     pub(crate) fn remove_current(&mut self, ctx: &mut Context) {
-        if let Some(mut spot) = self.spot.take() {
+        while let Some(mut spot) = self.stack.pop() {
             spot.remove(ctx);
+            ctx.retire_scene_scope();
         }
     }
 
     #[cfg(target_os = "android")]
     pub(crate) fn restore_root_scene(&mut self, ctx: &mut Context) {
         self.remove_current(ctx);
-        self.spot = Some((self.factory)(ctx));
+        ctx.push_scene_scope();
+        let spot = (self.factory)(ctx);
+        self.push_or_fail(ctx, spot);
+        ctx.clear_window_events();
         self.is_floating_scene = false;
     }
 
     #[cfg(not(target_os = "android"))]
     pub(crate) fn initialize_if_missing(&mut self, ctx: &mut Context) {
-        if self.spot.is_none() {
-            self.spot = Some((self.factory)(ctx));
+        if self.stack.is_empty() {
+            ctx.push_scene_scope();
+            let spot = (self.factory)(ctx);
+            self.push_or_fail(ctx, spot);
+            ctx.clear_window_events();
+        }
+    }
+
+    /// Draws the active scene, preceded by the paused scenes it lets show through, and
+    /// any running transition and the debug overlay over it.
+    pub(crate) fn draw(&mut self, ctx: &mut Context, screen: crate::Image) {
+        self.draw_stack(ctx, screen);
+        self.draw_transition(ctx, screen);
+        #[cfg(feature = "frame-dump")]
+        crate::frame_dump::write_pending(ctx);
+        crate::debug_overlay::draw(ctx, screen);
+    }
+
+    fn draw_stack(&mut self, ctx: &mut Context, target: crate::Image) {
+        let first_visible = self
+            .stack
+            .iter()
+            .rposition(|spot| !spot.draws_scenes_below())
+            .unwrap_or(0);
+        for spot in &mut self.stack[first_visible..] {
+            spot.draw(ctx, target);
+        }
+    }
+
+    /// Whether a transition is animating; the next frames must keep being drawn.
+    #[cfg(target_os = "android")]
+    pub(crate) fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    fn draw_transition(&mut self, ctx: &mut Context, screen: crate::Image) {
+        let (Some(active), Some(capture)) = (&self.transition, self.capture) else {
+            return;
+        };
+        let duration = active.transition.duration().as_secs_f32();
+        let t = active.started.elapsed().as_secs_f32() / duration;
+        if t >= 1.0 {
+            self.transition = None;
+            return;
+        }
+        let capture = capture.view();
+        let (w, h) = (screen.width(), screen.height());
+        match active.transition {
+            Transition::CrossDissolve(_) => {
+                screen.draw(ctx, &capture, DrawOption::default().with_opacity(1.0 - t));
+            }
+            Transition::Fade(_) => {
+                let black = self.black_image(ctx);
+                let cover = DrawOption::default().with_scale([w.as_f32(), h.as_f32()]);
+                if t < 0.5 {
+                    screen.draw(ctx, &black, cover);
+                    let opacity = 1.0 - t * 2.0;
+                    screen.draw(ctx, &capture, DrawOption::default().with_opacity(opacity));
+                } else {
+                    screen.draw(ctx, &black, cover.with_opacity(2.0 - t * 2.0));
+                }
+            }
+            Transition::SlideLeft(_) => {
+                let eased = t * t * (3.0 - 2.0 * t);
+                let x = crate::Pt(-w.as_f32() * eased);
+                screen.draw(
+                    ctx,
+                    &capture,
+                    DrawOption::default().with_position([x, crate::Pt(0.0)]),
+                );
+            }
+        }
+        ctx.request_redraw();
+    }
+
+    /// Draws the visible scenes into the capture texture and starts `transition` from it.
+    fn begin_transition(&mut self, ctx: &mut Context, transition: Transition) {
+        if transition.duration().is_zero() || self.stack.is_empty() {
+            self.transition = None;
+            return;
+        }
+        let (w, h) = ctx.window_logical_size();
+        let capture = ctx.outside_scene_scope(|ctx| {
+            crate::Texture::sized_render_target(ctx, &mut self.capture, w, h)
+        });
+        let target = capture.view();
+        // Offscreen targets clear to transparent; match the opaque screen instead.
+        let black = self.black_image(ctx);
+        target.draw(
+            ctx,
+            &black,
+            DrawOption::default().with_scale([w.as_f32(), h.as_f32()]),
+        );
+        self.draw_stack(ctx, target);
+        self.transition = Some(ActiveTransition {
+            transition,
+            started: Instant::now(),
+        });
+    }
+
+    fn black_image(&mut self, ctx: &mut Context) -> crate::Image {
+        *self.black.get_or_insert_with(|| {
+            ctx.outside_scene_scope(|ctx| {
+                ctx.register_image(1, 1, crate::Pt(1.0), crate::Pt(1.0), &[0, 0, 0, 255])
+            })
+        })
+    }
+
+    /// Delivers values sent through `EventSender`s to the active scene.
+    ///
+    /// Events stay queued until a scene exists.
+    pub(crate) fn dispatch_user_events(&mut self, ctx: &mut Context) {
+        let Some(spot) = self.stack.last_mut() else {
+            return;
+        };
+        for event in crate::user_events::take_events() {
+            spot.user_event(ctx, event);
+        }
+    }
+
+    /// Hands a raw window event to the active scene.
+    #[cfg(not(target_os = "android"))]
+    pub(crate) fn dispatch_raw_event(
+        &mut self,
+        ctx: &mut Context,
+        event: &winit::event::WindowEvent,
+    ) {
+        if let Some(spot) = self.stack.last_mut() {
+            spot.on_event(ctx, event);
+        }
+    }
+
+    /// Forwards pending scale factor, window size, orientation, theme and on-screen
+    /// keyboard changes to every scene on the stack, since paused scenes may still be drawn.
+    pub(crate) fn dispatch_window_events(&mut self, ctx: &mut Context) {
+        let scale_factor = ctx.take_scale_factor_event();
+        let size = ctx.take_resize_event();
+        let theme = ctx.take_theme_event();
+        let orientation = ctx.take_orientation_event();
+        let keyboard = ctx.take_virtual_keyboard_event();
+        for spot in &mut self.stack {
+            if let Some(theme) = theme {
+                spot.theme_changed(ctx, theme);
+            }
+            if let Some(scale_factor) = scale_factor {
+                spot.scale_factor_changed(ctx, scale_factor);
+            }
+            if let Some(orientation) = orientation {
+                spot.orientation_changed(ctx, orientation);
+            }
+            if let Some((w, h)) = size {
+                spot.resized(ctx, w, h);
+            }
+            if let Some(height) = keyboard {
+                spot.virtual_keyboard_changed(ctx, height);
+            }
         }
     }
 
@@ -182,18 +396,55 @@ impl SceneHost {
             return false;
         };
 
+        match request {
+            SceneRequest::Switch {
+                request,
+                transition,
+            } => {
+                Self::replace_payload(ctx, request.payload);
+                match transition {
+                    Some(transition) => self.begin_transition(ctx, transition),
+                    None => self.transition = None,
+                }
+                self.remove_current(ctx);
+                ctx.push_scene_scope();
+                let spot = (request.factory)(ctx);
+                self.push_or_fail(ctx, spot);
+            }
+            SceneRequest::Push(request) => {
+                Self::replace_payload(ctx, request.payload);
+                ctx.push_scene_scope();
+                let spot = (request.factory)(ctx);
+                self.push_or_fail(ctx, spot);
+            }
+            SceneRequest::Pop => {
+                if self.stack.len() < 2 {
+                    crate::diagnostics::warn!(
+                        "scene",
+                        "pop_scene ignored: no scene below the active one"
+                    );
+                    return false;
+                }
+                if let Some(mut spot) = self.stack.pop() {
+                    spot.remove(ctx);
+                    ctx.retire_scene_scope();
+                }
+            }
+        }
+        ctx.clear_window_events();
+        self.is_floating_scene = false;
+        true
+    }
+
+    /// Drops the payload of the previous switch and stores the new one, if any.
+    fn replace_payload(ctx: &mut Context, payload: Option<ScenePayload>) {
         if let Some(last) = ctx.take_resource::<ScenePayloadTypeId>() {
             ctx.take_resource_dyn(last.0);
         }
-        if let Some(payload) = request.payload {
+        if let Some(payload) = payload {
             ctx.insert_resource_dyn(payload.type_id, payload.value);
             ctx.insert_resource(Rc::new(ScenePayloadTypeId(payload.type_id)));
         }
-
-        self.remove_current(ctx);
-        self.spot = Some((request.factory)(ctx));
-        self.is_floating_scene = false;
-        true
     }
 }
 
@@ -208,6 +459,8 @@ pub(crate) struct App {
     pub(crate) scale_factor: f64,
     pub(crate) timing: FixedTimestep,
     pub(crate) gamepads: gamepad::GamepadRuntime,
+    /// Why the event loop was stopped early, reported by `run` once it returns.
+    pub(crate) error: Option<crate::SpotError>,
 }
 
 pub(crate) fn make_screen_target(ctx: &Context) -> crate::Image {
@@ -231,20 +484,23 @@ pub(crate) fn make_screen_target(ctx: &Context) -> crate::Image {
 impl App {
     #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     pub(crate) fn new<T: Spot + 'static>(window_config: WindowConfig) -> Self {
-        let instance = platform::create_wgpu_instance();
+        let instance = platform::create_wgpu_instance(window_config.backend);
         let update_step = window_config.fixed_update_step();
+        let mut ctx = Context::new();
+        ctx.runtime.lockstep.enabled = window_config.deterministic;
 
         Self {
             platform: PlatformData::new(),
             instance,
             surface: None,
-            ctx: Box::pin(Context::new()),
+            ctx: Box::pin(ctx),
             scene: SceneHost::new::<T>(),
             window_config,
             init_state: GraphicsInitState::NotStarted,
             scale_factor: 1.0,
             timing: FixedTimestep::new(update_step),
             gamepads: gamepad::GamepadRuntime::new(),
+            error: None,
         }
     }
 
@@ -252,23 +508,37 @@ impl App {
     pub(crate) fn new_wasm<T: Spot + 'static>(
         window_config: WindowConfig,
         canvas_id: Option<String>,
+        instance: wgpu::Instance,
     ) -> Self {
-        let instance = platform::create_wgpu_instance();
         let update_step = window_config.fixed_update_step();
+        let mut ctx = Context::new();
+        ctx.runtime.lockstep.enabled = window_config.deterministic;
         Self {
             platform: PlatformData::new_wasm(canvas_id),
             instance,
             surface: None,
-            ctx: Box::pin(Context::new()),
+            ctx: Box::pin(ctx),
             scene: SceneHost::new::<T>(),
             window_config,
             init_state: GraphicsInitState::NotStarted,
             scale_factor: 1.0,
             timing: FixedTimestep::new(update_step),
             gamepads: gamepad::GamepadRuntime::new(),
+            error: None,
         }
     }
 
+    /// The result of the run: the error that stopped it, or one left by a scene that
+    /// failed to initialize.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub(crate) fn finish(&mut self) -> Result<(), crate::SpotError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        crate::scenes::take_scene_error()
+            .map_or(Ok(()), |error| Err(crate::SpotError::Scene(error)))
+    }
+
     pub(crate) fn poll_gamepads(&mut self) {
         self.gamepads.poll(self.ctx.input_mut());
     }
@@ -277,7 +547,7 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{switch_scene, switch_scene_with};
+    use crate::{pop_scene, push_scene, switch_scene, switch_scene_with};
 
     struct RootScene;
     struct PayloadScene;
@@ -357,6 +627,408 @@ mod tests {
         assert!(ctx.get_resource::<ScenePayloadTypeId>().is_none());
     }
 
+    /// Records draws and removals into a shared log.
+    #[derive(Default)]
+    struct SceneLog(std::cell::RefCell<Vec<&'static str>>);
+
+    fn log(ctx: &mut Context, entry: &'static str) {
+        let log = ctx.get_resource::<SceneLog>().expect("scene log");
+        log.0.borrow_mut().push(entry);
+    }
+
+    struct GameScene;
+    struct MenuScene;
+    struct FullScreenScene;
+
+    impl Spot for GameScene {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, ctx: &mut Context, _screen: crate::Image) {
+            log(ctx, "draw game");
+        }
+
+        fn remove(&mut self, ctx: &mut Context) {
+            log(ctx, "remove game");
+        }
+    }
+
+    impl Spot for MenuScene {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, ctx: &mut Context, _screen: crate::Image) {
+            log(ctx, "draw menu");
+        }
+
+        fn remove(&mut self, ctx: &mut Context) {
+            log(ctx, "remove menu");
+        }
+    }
+
+    impl Spot for FullScreenScene {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, ctx: &mut Context, _screen: crate::Image) {
+            log(ctx, "draw full screen");
+        }
+
+        fn draws_scenes_below(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn pushed_scenes_pause_the_scene_below_until_popped() {
+        let _ = take_scene_switch_request();
+
+        let mut ctx = Context::new();
+        ctx.insert_resource(Rc::new(SceneLog::default()));
+        let take_log = |ctx: &mut Context| {
+            let log = ctx.get_resource::<SceneLog>().expect("scene log");
+            log.0.take()
+        };
+        let screen = make_screen_target(&ctx);
+        let mut host = SceneHost::new::<GameScene>();
+        host.initialize_if_missing(&mut ctx);
+
+        push_scene::<MenuScene>();
+        assert!(host.apply_pending_switch(&mut ctx));
+        host.draw(&mut ctx, screen);
+        assert_eq!(take_log(&mut ctx), ["draw game", "draw menu"]);
+
+        push_scene::<FullScreenScene>();
+        assert!(host.apply_pending_switch(&mut ctx));
+        host.draw(&mut ctx, screen);
+        assert_eq!(take_log(&mut ctx), ["draw full screen"]);
+
+        pop_scene();
+        assert!(host.apply_pending_switch(&mut ctx));
+        pop_scene();
+        assert!(host.apply_pending_switch(&mut ctx));
+        assert_eq!(take_log(&mut ctx), ["remove menu"]);
+        host.draw(&mut ctx, screen);
+        assert_eq!(take_log(&mut ctx), ["draw game"]);
+
+        // The last scene cannot be popped.
+        pop_scene();
+        assert!(!host.apply_pending_switch(&mut ctx));
+        assert!(host.has_active_scene());
+
+        push_scene::<MenuScene>();
+        assert!(host.apply_pending_switch(&mut ctx));
+        switch_scene::<RootScene>();
+        assert!(host.apply_pending_switch(&mut ctx));
+        assert_eq!(take_log(&mut ctx), ["remove menu", "remove game"]);
+        assert_eq!(host.stack.len(), 1);
+    }
+
+    #[test]
+    fn transition_captures_outgoing_scene_and_expires() {
+        let _ = take_scene_switch_request();
+
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(800, 600, 1.0);
+        ctx.insert_resource(Rc::new(SceneLog::default()));
+        let screen = make_screen_target(&ctx);
+        let mut host = SceneHost::new::<GameScene>();
+        host.initialize_if_missing(&mut ctx);
+
+        crate::switch_scene_with_transition::<MenuScene>(Transition::CrossDissolve(
+            Duration::from_millis(300),
+        ));
+        assert!(host.apply_pending_switch(&mut ctx));
+        let log = ctx.get_resource::<SceneLog>().expect("scene log").0.take();
+        assert_eq!(log, ["draw game", "remove game"]);
+        let capture = host.capture.expect("capture target");
+        assert_eq!(
+            (capture.width(), capture.height()),
+            ctx.window_logical_size()
+        );
+
+        ctx.begin_frame();
+        host.draw(&mut ctx, screen);
+        assert!(host.transition.is_some());
+        let with_transition = ctx.runtime.draw_list.len();
+
+        if let Some(active) = host.transition.as_mut() {
+            active.started = Instant::now() - Duration::from_secs(1);
+        }
+        ctx.begin_frame();
+        host.draw(&mut ctx, screen);
+        assert!(host.transition.is_none());
+        assert!(ctx.runtime.draw_list.len() < with_transition);
+    }
+
+    #[derive(Default)]
+    struct ExitLog(std::cell::RefCell<Vec<String>>);
+
+    struct ExitScene<const ID: u8>;
+
+    impl<const ID: u8> Spot for ExitScene<ID> {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _screen: crate::Image) {}
+
+        fn on_exit(&mut self, ctx: &mut Context) {
+            let log = ctx.get_resource::<ExitLog>().expect("exit log");
+            log.0.borrow_mut().push(format!("exit {ID}"));
+        }
+
+        fn remove(&mut self, ctx: &mut Context) {
+            let log = ctx.get_resource::<ExitLog>().expect("exit log");
+            log.0.borrow_mut().push(format!("remove {ID}"));
+        }
+    }
+
+    #[test]
+    fn shutdown_lets_every_scene_exit_before_removing_them() {
+        let _ = take_scene_switch_request();
+
+        let mut ctx = Context::new();
+        ctx.insert_resource(Rc::new(ExitLog::default()));
+        let mut host = SceneHost::new::<ExitScene<1>>();
+        host.initialize_if_missing(&mut ctx);
+        push_scene::<ExitScene<2>>();
+        assert!(host.apply_pending_switch(&mut ctx));
+
+        host.shutdown(&mut ctx);
+        assert!(!host.has_active_scene());
+        let log = ctx.get_resource::<ExitLog>().expect("exit log");
+        assert_eq!(
+            *log.0.borrow(),
+            ["exit 2", "exit 1", "remove 2", "remove 1"]
+        );
+    }
+
+    struct FontScene;
+
+    impl Spot for FontScene {
+        fn initialize(ctx: &mut Context) -> Self {
+            crate::register_font(ctx, vec![0; 4]);
+            Self
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _screen: crate::Image) {}
+    }
+
+    #[test]
+    fn scene_resources_are_released_with_their_scene() {
+        let _ = take_scene_switch_request();
+
+        let mut ctx = Context::new();
+        let mut host = SceneHost::new::<RootScene>();
+        host.initialize_if_missing(&mut ctx);
+        push_scene::<FontScene>();
+        assert!(host.apply_pending_switch(&mut ctx));
+        let font = ctx.registry.next_font_id - 1;
+        assert!(crate::get_registered_font(&ctx, font).is_some());
+
+        pop_scene();
+        assert!(host.apply_pending_switch(&mut ctx));
+        // The frame that popped the scene may still draw its assets.
+        assert!(crate::get_registered_font(&ctx, font).is_some());
+        ctx.begin_frame();
+        assert!(crate::get_registered_font(&ctx, font).is_none());
+        assert_eq!(ctx.runtime.scene_scopes.len(), 1);
+    }
+
+    struct MissingAssetScene;
+
+    impl Spot for MissingAssetScene {
+        fn initialize(ctx: &mut Context) -> Self {
+            crate::scenes::initialize_or_panic(ctx)
+        }
+
+        fn try_initialize(_ctx: &mut Context) -> anyhow::Result<Self> {
+            anyhow::bail!("missing.png not found")
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _screen: crate::Image) {}
+    }
+
+    #[test]
+    fn failed_scene_initialization_quits_with_the_error() {
+        let _ = take_scene_switch_request();
+        let _ = crate::scenes::take_scene_error();
+        let _ = crate::scenes::take_quit_request();
+
+        let mut ctx = Context::new();
+        let mut host = SceneHost::new::<RootScene>();
+        host.initialize_if_missing(&mut ctx);
+        assert!(!crate::scenes::take_quit_request());
+
+        switch_scene::<MissingAssetScene>();
+        assert!(host.apply_pending_switch(&mut ctx));
+        assert!(!host.has_active_scene());
+        assert!(crate::scenes::take_quit_request());
+        let error = crate::scenes::take_scene_error().expect("scene error");
+        assert_eq!(
+            crate::SpotError::Scene(error).to_string(),
+            "failed to initialize scene: missing.png not found"
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct SeenFocus(bool);
+
+    struct RawEventScene;
+
+    impl Spot for RawEventScene {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _screen: crate::Image) {}
+
+        fn on_event(&mut self, ctx: &mut Context, event: &winit::event::WindowEvent) {
+            if let winit::event::WindowEvent::Focused(focused) = event {
+                ctx.insert_resource(Rc::new(SeenFocus(*focused)));
+            }
+        }
+    }
+
+    #[test]
+    fn raw_events_reach_only_the_active_scene() {
+        let _ = take_scene_switch_request();
+
+        let mut ctx = Context::new();
+        let mut host = SceneHost::new::<RawEventScene>();
+        host.initialize_if_missing(&mut ctx);
+        host.dispatch_raw_event(&mut ctx, &winit::event::WindowEvent::Focused(false));
+        assert_eq!(
+            ctx.take_resource::<SeenFocus>().as_deref(),
+            Some(&SeenFocus(false))
+        );
+
+        push_scene::<RootScene>();
+        assert!(host.apply_pending_switch(&mut ctx));
+        host.dispatch_raw_event(&mut ctx, &winit::event::WindowEvent::Focused(true));
+        assert!(ctx.get_resource::<SeenFocus>().is_none());
+    }
+
+    struct ResizeScene;
+
+    #[derive(Debug, PartialEq)]
+    struct LastResize(crate::Pt, crate::Pt);
+
+    impl Spot for ResizeScene {
+        fn initialize(_ctx: &mut Context) -> Self {
+            Self
+        }
+
+        fn draw(&mut self, _ctx: &mut Context, _screen: crate::Image) {}
+
+        fn resized(&mut self, ctx: &mut Context, w: crate::Pt, h: crate::Pt) {
+            ctx.insert_resource(Rc::new(LastResize(w, h)));
+        }
+
+        fn scale_factor_changed(&mut self, ctx: &mut Context, scale_factor: f64) {
+            ctx.insert_resource(Rc::new(LastScaleFactor(scale_factor)));
+        }
+
+        fn theme_changed(&mut self, ctx: &mut Context, theme: crate::Theme) {
+            ctx.insert_resource(Rc::new(theme));
+        }
+
+        fn orientation_changed(&mut self, ctx: &mut Context, orientation: crate::Orientation) {
+            ctx.insert_resource(Rc::new(orientation));
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct LastScaleFactor(f64);
+
+    #[test]
+    fn window_events_are_dispatched_once_per_change() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(800, 600, 1.0);
+        let mut host = SceneHost::new::<ResizeScene>();
+        host.initialize_if_missing(&mut ctx);
+
+        host.dispatch_window_events(&mut ctx);
+        assert!(ctx.get_resource::<LastResize>().is_none());
+
+        ctx.update_window_metrics_physical(1600, 1200, 2.0);
+        host.dispatch_window_events(&mut ctx);
+        assert!(ctx.get_resource::<LastResize>().is_none());
+        assert_eq!(
+            ctx.take_resource::<LastScaleFactor>().as_deref(),
+            Some(&LastScaleFactor(2.0))
+        );
+
+        ctx.update_window_metrics_physical(1024, 768, 2.0);
+        host.dispatch_window_events(&mut ctx);
+        assert_eq!(
+            ctx.take_resource::<LastResize>().as_deref(),
+            Some(&LastResize(crate::Pt::from(512.0), crate::Pt::from(384.0)))
+        );
+
+        host.dispatch_window_events(&mut ctx);
+        assert!(ctx.get_resource::<LastResize>().is_none());
+    }
+
+    #[test]
+    fn theme_changes_are_dispatched_but_not_the_initial_theme() {
+        let mut ctx = Context::new();
+        ctx.set_system_theme(Some(crate::Theme::Light));
+        let mut host = SceneHost::new::<ResizeScene>();
+        host.initialize_if_missing(&mut ctx);
+
+        host.dispatch_window_events(&mut ctx);
+        assert!(ctx.get_resource::<crate::Theme>().is_none());
+
+        ctx.set_system_theme(Some(crate::Theme::Dark));
+        host.dispatch_window_events(&mut ctx);
+        assert_eq!(
+            ctx.take_resource::<crate::Theme>().as_deref(),
+            Some(&crate::Theme::Dark)
+        );
+        assert_eq!(crate::system_theme(&ctx), Some(crate::Theme::Dark));
+    }
+
+    #[test]
+    fn rotation_dispatches_orientation_change() {
+        let mut ctx = Context::new();
+        ctx.update_window_metrics_physical(390, 844, 1.0);
+        let mut host = SceneHost::new::<ResizeScene>();
+        host.initialize_if_missing(&mut ctx);
+        host.dispatch_window_events(&mut ctx);
+        assert!(ctx.get_resource::<crate::Orientation>().is_none());
+
+        ctx.update_window_metrics_physical(390, 800, 1.0);
+        host.dispatch_window_events(&mut ctx);
+        assert!(ctx.get_resource::<crate::Orientation>().is_none());
+
+        ctx.update_window_metrics_physical(844, 390, 1.0);
+        host.dispatch_window_events(&mut ctx);
+        assert_eq!(
+            ctx.take_resource::<crate::Orientation>().as_deref(),
+            Some(&crate::Orientation::Landscape)
+        );
+    }
+
+    #[test]
+    fn resume_after_idle_runs_a_single_step() {
+        let mut timing = FixedTimestep::new(Duration::from_millis(10));
+        timing.reset();
+        timing.previous = Some(Instant::now() - Duration::from_secs(5));
+        timing.resume_after_idle();
+
+        let mut updates = 0;
+        timing.run_updates(8, |dt| {
+            assert_eq!(dt, Duration::from_millis(10));
+            updates += 1;
+        });
+        assert_eq!(updates, 1);
+    }
+
     #[test]
     fn app_context_address_stays_stable_when_app_moves() {
         let app = App::new::<RootScene>(crate::WindowConfig::default());
@@ -1,9 +1,37 @@
 use super::App;
 use crate::platform;
 use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::Closure;
 
 // PlatformData is now defined in desktop.rs to share winit ApplicationHandler logic.
 
+/// Audio the mixer must render after a resume before the AudioContext counts as running;
+/// a suspended context still pulls the first buffers.
+const AUDIO_RUNNING_THRESHOLD: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// How long the mixer clock may stand still before gestures resume the context again.
+const AUDIO_STALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Whether the browser lets the AudioContext run.
+pub(crate) struct AudioUnlock {
+    running: bool,
+    /// Mixer clock at the last resume attempt.
+    resumed_at: Option<std::time::Duration>,
+    last_clock: std::time::Duration,
+    last_progress: web_time::Instant,
+}
+
+impl Default for AudioUnlock {
+    fn default() -> Self {
+        Self {
+            running: false,
+            resumed_at: None,
+            last_clock: std::time::Duration::ZERO,
+            last_progress: web_time::Instant::now(),
+        }
+    }
+}
+
 impl App {
     /// Lazily initialise the audio system on the first user gesture so that
     /// the browser's autoplay policy is satisfied.
@@ -21,21 +49,178 @@ impl App {
         }
     }
 
-    pub(crate) fn sync_canvas_resize(&mut self) {
-        let Some(window) = self.platform.window.as_ref() else {
+    /// Resumes the AudioContext from a pointer, touch or key event until the mixer clock
+    /// shows it running, since browsers ignore resumes outside of user gestures.
+    pub(crate) fn resume_audio_on_gesture(&mut self) {
+        if self.platform.audio_unlock.running {
+            return;
+        }
+        self.init_audio_on_gesture();
+        platform::try_resume_audio(&mut self.ctx);
+        if let Some(audio) = self.ctx.runtime.audio.as_ref() {
+            self.platform.audio_unlock.resumed_at = Some(audio.audio_time().as_duration());
+        }
+    }
+
+    /// Marks audio as running once the mixer clock advances after a resume, and as
+    /// blocked again when it stalls, e.g. after iOS interrupted the AudioContext.
+    pub(crate) fn track_audio_unlock(&mut self) {
+        let Some(clock) = self
+            .ctx
+            .runtime
+            .audio
+            .as_ref()
+            .map(|audio| audio.audio_time().as_duration())
+        else {
             return;
         };
+        let now = web_time::Instant::now();
+        let unlock = &mut self.platform.audio_unlock;
+        if clock != unlock.last_clock {
+            unlock.last_clock = clock;
+            unlock.last_progress = now;
+        }
+        if unlock.running {
+            unlock.running = now.duration_since(unlock.last_progress) < AUDIO_STALL_TIMEOUT;
+        } else if let Some(resumed_at) = unlock.resumed_at
+            && clock.saturating_sub(resumed_at) >= AUDIO_RUNNING_THRESHOLD
+        {
+            unlock.running = true;
+            unlock.resumed_at = None;
+        }
+    }
 
-        let canvas = self.platform.canvas_id.as_deref().and_then(|id| {
+    /// Suspends the scene and audio while the tab is hidden and resumes them when it is
+    /// shown again, like an app moving to the background on mobile.
+    ///
+    /// The loop waits instead of updating while hidden, and the frame clock is reset on
+    /// return so the time away is not caught up in fixed updates.
+    pub(crate) fn sync_page_visibility(&mut self) {
+        let Some(visible) = self
+            .platform
+            .page_visibility
+            .as_ref()
+            .and_then(PageVisibility::take_change)
+        else {
+            return;
+        };
+        if visible == !self.platform.page_hidden {
+            return;
+        }
+        self.platform.page_hidden = !visible;
+        if visible {
+            self.timing.reset();
+            // Resuming without a gesture is allowed once the page was interacted with.
+            self.ctx.with_audio(|a| a.resume());
+            if let Some(spot) = self.scene.spot_mut() {
+                spot.resumed(&mut self.ctx);
+            }
+            self.ctx.request_redraw();
+            if let Some(window) = self.platform.window.as_ref() {
+                window.request_redraw();
+            }
+        } else {
+            if let Some(spot) = self.scene.spot_mut() {
+                spot.suspended(&mut self.ctx);
+            }
+            self.ctx.clear_transient_input();
+            self.ctx.with_audio(|a| a.suspend());
+        }
+    }
+
+    fn canvas(&self) -> Option<web_sys::HtmlCanvasElement> {
+        self.platform.canvas_id.as_deref().and_then(|id| {
             web_sys::window()
                 .and_then(|w| w.document())
                 .and_then(|d| d.get_element_by_id(id))
                 .and_then(|e| e.dyn_into::<web_sys::HtmlCanvasElement>().ok())
-        });
+        })
+    }
 
-        let Some(canvas) = canvas else {
+    /// Wakes the loop whenever the page layout resizes the canvas, so the surface
+    /// follows even while a reactive app is idle.
+    pub(crate) fn observe_canvas(&mut self) {
+        if self.platform.canvas_observer.is_some() {
+            return;
+        }
+        let Some(canvas) = self.canvas() else {
             return;
         };
+        let on_resize = Closure::<dyn FnMut(js_sys::Array, web_sys::ResizeObserver)>::new(
+            |_entries: js_sys::Array, _observer: web_sys::ResizeObserver| {
+                crate::user_events::wake();
+            },
+        );
+        match web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref()) {
+            Ok(observer) => {
+                observer.observe(&canvas);
+                self.platform.canvas_observer = Some(CanvasObserver {
+                    observer,
+                    _on_resize: on_resize,
+                });
+            }
+            Err(e) => {
+                web_sys::console::warn_1(
+                    &format!("[spot][wasm] ResizeObserver unavailable: {e:?}").into(),
+                );
+            }
+        }
+    }
+
+    /// Reads the CSS `env(safe-area-inset-*)` values, which scripts can only see through
+    /// the computed style of an element using them. They are in CSS pixels, i.e. `Pt`.
+    fn sync_safe_area(&mut self) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let Some(document) = window.document() else {
+            return;
+        };
+        let (Some(body), Ok(probe)) = (document.body(), document.create_element("div")) else {
+            return;
+        };
+        let _ = probe.set_attribute(
+            "style",
+            "position:fixed;visibility:hidden;pointer-events:none;\
+             padding-top:env(safe-area-inset-top);padding-bottom:env(safe-area-inset-bottom);\
+             padding-left:env(safe-area-inset-left);padding-right:env(safe-area-inset-right)",
+        );
+        if body.append_child(&probe).is_err() {
+            return;
+        }
+        let inset = |style: &web_sys::CssStyleDeclaration, side: &str| {
+            let value = style
+                .get_property_value(&format!("padding-{side}"))
+                .unwrap_or_default();
+            crate::Pt(value.trim_end_matches("px").parse::<f32>().unwrap_or(0.0))
+        };
+        if let Ok(Some(style)) = window.get_computed_style(&probe) {
+            self.ctx.set_safe_area(
+                inset(&style, "top"),
+                inset(&style, "bottom"),
+                inset(&style, "left"),
+                inset(&style, "right"),
+            );
+        }
+        probe.remove();
+    }
+
+    pub(crate) fn sync_canvas_resize(&mut self) {
+        if self.platform.window.is_none() {
+            return;
+        }
+        let Some(canvas) = self.canvas() else {
+            return;
+        };
+
+        // Browser zoom and moving between screens change the ratio without always
+        // reaching winit, so read it directly.
+        if let Some(dpr) = web_sys::window().map(|w| w.device_pixel_ratio())
+            && dpr.is_finite()
+            && dpr > 0.0
+        {
+            self.scale_factor = self.window_config.resolve_scale_factor(dpr);
+        }
 
         let rect = canvas.get_bounding_client_rect();
         let css_w = rect.width() as f64;
@@ -57,7 +242,12 @@ impl App {
             .map(|g| g.config.width == w && g.config.height == h)
             .unwrap_or(false);
 
-        if self.platform.last_physical_size == Some((w, h)) && canvas_matches && graphics_matches {
+        let scale_matches = self.ctx.scale_factor() == self.scale_factor;
+        if self.platform.last_physical_size == Some((w, h))
+            && canvas_matches
+            && graphics_matches
+            && scale_matches
+        {
             return;
         }
         self.platform.last_physical_size = Some((w, h));
@@ -76,9 +266,24 @@ impl App {
 
         self.ctx
             .update_window_metrics_physical(w, h, self.scale_factor);
+        self.sync_safe_area();
 
         // Ensure winit is aware of the effective surface size too.
-        window.request_redraw();
+        if let Some(window) = self.platform.window.as_ref() {
+            window.request_redraw();
+        }
+    }
+}
+
+/// A `ResizeObserver` on the canvas, disconnected when dropped.
+pub(crate) struct CanvasObserver {
+    observer: web_sys::ResizeObserver,
+    _on_resize: Closure<dyn FnMut(js_sys::Array, web_sys::ResizeObserver)>,
+}
+
+impl Drop for CanvasObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
     }
 }
 
@@ -115,3 +320,451 @@ pub(crate) unsafe fn handle_wasm_graphics_init_result(
         closure.forget();
     }
 }
+
+/// A `navigator.wakeLock` screen lock, released when dropped.
+///
+/// Browsers drop the lock whenever the page is hidden, so it is requested again while
+/// the page is visible and no lock is held. Called through `Reflect` because the API is
+/// still unstable in `web-sys`.
+#[derive(Default)]
+pub(crate) struct ScreenWakeLock {
+    sentinel: std::rc::Rc<std::cell::RefCell<Option<wasm_bindgen::JsValue>>>,
+    requesting: std::rc::Rc<std::cell::Cell<bool>>,
+}
+
+impl ScreenWakeLock {
+    pub(crate) fn acquire_if_released(&self) {
+        if self.requesting.get() {
+            return;
+        }
+        let held = self.sentinel.borrow().as_ref().is_some_and(|sentinel| {
+            js_sys::Reflect::get(sentinel, &"released".into()).is_ok_and(|r| r.is_falsy())
+        });
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let visible = window
+            .document()
+            .is_some_and(|d| d.visibility_state() == web_sys::VisibilityState::Visible);
+        if held || !visible {
+            return;
+        }
+        let Ok(wake_lock) = js_sys::Reflect::get(&window.navigator(), &"wakeLock".into()) else {
+            return;
+        };
+        let Ok(request) = js_sys::Reflect::get(&wake_lock, &"request".into())
+            .and_then(|f| f.dyn_into::<js_sys::Function>())
+        else {
+            return;
+        };
+        let Ok(promise) = request
+            .call1(&wake_lock, &"screen".into())
+            .and_then(|p| p.dyn_into::<js_sys::Promise>())
+        else {
+            return;
+        };
+
+        self.requesting.set(true);
+        let sentinel = self.sentinel.clone();
+        let requesting = self.requesting.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            match wasm_bindgen_futures::JsFuture::from(promise).await {
+                Ok(lock) => *sentinel.borrow_mut() = Some(lock),
+                Err(e) => web_sys::console::warn_1(
+                    &format!("[spot][wasm] Screen wake lock refused: {e:?}").into(),
+                ),
+            }
+            requesting.set(false);
+        });
+    }
+}
+
+impl Drop for ScreenWakeLock {
+    fn drop(&mut self) {
+        let Some(sentinel) = self.sentinel.borrow_mut().take() else {
+            return;
+        };
+        if let Ok(release) = js_sys::Reflect::get(&sentinel, &"release".into())
+            .and_then(|f| f.dyn_into::<js_sys::Function>())
+        {
+            let _ = release.call0(&sentinel);
+        }
+    }
+}
+
+/// Remembers text pasted into the page, which browsers hand out without the clipboard
+/// permission that `navigator.clipboard.readText` needs.
+pub(crate) struct PasteListener {
+    pasted: std::rc::Rc<std::cell::RefCell<Option<String>>>,
+    on_paste: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+impl PasteListener {
+    pub(crate) fn listen() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let pasted = std::rc::Rc::new(std::cell::RefCell::new(None));
+        let slot = pasted.clone();
+        let on_paste = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+            let text = js_sys::Reflect::get(&event, &"clipboardData".into())
+                .ok()
+                .filter(|data| !data.is_undefined() && !data.is_null())
+                .and_then(|data| {
+                    let get_data = js_sys::Reflect::get(&data, &"getData".into()).ok()?;
+                    get_data
+                        .dyn_into::<js_sys::Function>()
+                        .ok()?
+                        .call1(&data, &"text/plain".into())
+                        .ok()?
+                        .as_string()
+                });
+            if let Some(text) = text.filter(|text| !text.is_empty()) {
+                *slot.borrow_mut() = Some(text);
+            }
+        });
+        document
+            .add_event_listener_with_callback("paste", on_paste.as_ref().unchecked_ref())
+            .ok()?;
+        Some(Self { pasted, on_paste })
+    }
+
+    pub(crate) fn take(&self) -> Option<String> {
+        self.pasted.borrow_mut().take()
+    }
+}
+
+impl Drop for PasteListener {
+    fn drop(&mut self) {
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            let _ = document.remove_event_listener_with_callback(
+                "paste",
+                self.on_paste.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+/// Tracks `document.visibilityState`, including the `pagehide` and `pageshow` events of
+/// pages entering and leaving the back/forward cache.
+pub(crate) struct PageVisibility {
+    change: std::rc::Rc<std::cell::Cell<Option<bool>>>,
+    on_change: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+impl PageVisibility {
+    const EVENTS: [&str; 3] = ["visibilitychange", "pagehide", "pageshow"];
+
+    pub(crate) fn listen() -> Option<Self> {
+        let window = web_sys::window()?;
+        let change = std::rc::Rc::new(std::cell::Cell::new(None));
+        let slot = change.clone();
+        let on_change = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+            let visible = event.type_() != "pagehide"
+                && web_sys::window()
+                    .and_then(|w| w.document())
+                    .is_some_and(|d| d.visibility_state() == web_sys::VisibilityState::Visible);
+            slot.set(Some(visible));
+            crate::user_events::wake();
+        });
+        for event in Self::EVENTS {
+            window
+                .add_event_listener_with_callback(event, on_change.as_ref().unchecked_ref())
+                .ok()?;
+        }
+        Some(Self { change, on_change })
+    }
+
+    /// Returns whether the page is visible if that changed since the last call.
+    pub(crate) fn take_change(&self) -> Option<bool> {
+        self.change.take()
+    }
+}
+
+impl Drop for PageVisibility {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            for event in Self::EVENTS {
+                let _ = window.remove_event_listener_with_callback(
+                    event,
+                    self.on_change.as_ref().unchecked_ref(),
+                );
+            }
+        }
+    }
+}
+
+/// The page's `BatteryManager`, which only Chromium-based browsers provide.
+pub(crate) struct BatteryMonitor {
+    manager: std::rc::Rc<std::cell::RefCell<Option<wasm_bindgen::JsValue>>>,
+}
+
+impl BatteryMonitor {
+    pub(crate) fn request() -> Option<Self> {
+        let navigator = web_sys::window()?.navigator();
+        let get_battery = js_sys::Reflect::get(&navigator, &"getBattery".into())
+            .ok()?
+            .dyn_into::<js_sys::Function>()
+            .ok()?;
+        let promise = get_battery
+            .call0(&navigator)
+            .ok()?
+            .dyn_into::<js_sys::Promise>()
+            .ok()?;
+        let manager = std::rc::Rc::new(std::cell::RefCell::new(None));
+        let slot = manager.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Ok(battery) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                *slot.borrow_mut() = Some(battery);
+            }
+        });
+        Some(Self { manager })
+    }
+
+    /// Copies the manager's live `level` and `charging` properties into the context.
+    pub(crate) fn poll(&self, ctx: &mut crate::Context) {
+        let manager = self.manager.borrow();
+        let Some(battery) = manager.as_ref() else {
+            return;
+        };
+        let read = |name: &str| js_sys::Reflect::get(battery, &name.into()).ok();
+        ctx.runtime.power.battery_level = read("level")
+            .and_then(|level| level.as_f64())
+            .map(|level| level as f32);
+        ctx.runtime.power.charging = read("charging").and_then(|charging| charging.as_bool());
+    }
+}
+
+/// Text and keys typed into a [`VirtualKeyboard`] since the last poll.
+#[derive(Default)]
+struct TypedInput {
+    text: String,
+    keys: Vec<crate::Key>,
+}
+
+/// A hidden text field focused to bring up the on-screen keyboard, which mobile browsers
+/// only show for editable elements. Removed when dropped.
+pub(crate) struct VirtualKeyboard {
+    input: web_sys::HtmlInputElement,
+    typed: std::rc::Rc<std::cell::RefCell<TypedInput>>,
+    _on_input: Closure<dyn FnMut(web_sys::InputEvent)>,
+    _on_keydown: Closure<dyn FnMut(web_sys::KeyboardEvent)>,
+}
+
+impl VirtualKeyboard {
+    pub(crate) fn show() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let input = document
+            .create_element("input")
+            .ok()?
+            .dyn_into::<web_sys::HtmlInputElement>()
+            .ok()?;
+        // Kept on screen with a readable font size so iOS neither scrolls to it nor zooms.
+        let _ = input.set_attribute(
+            "style",
+            "position:fixed;left:0;bottom:0;width:1px;height:1px;opacity:0;\
+             font-size:16px;border:0;padding:0",
+        );
+        let _ = input.set_attribute("autocomplete", "off");
+        let _ = input.set_attribute("autocapitalize", "off");
+        document.body()?.append_child(&input).ok()?;
+
+        let typed = std::rc::Rc::new(std::cell::RefCell::new(TypedInput::default()));
+        let input_typed = typed.clone();
+        let field = input.clone();
+        // The field is emptied after every edit, so deletions become Backspace presses.
+        let on_input =
+            Closure::<dyn FnMut(web_sys::InputEvent)>::new(move |event: web_sys::InputEvent| {
+                let mut typed = input_typed.borrow_mut();
+                if event.input_type().starts_with("delete") {
+                    typed.keys.push(crate::Key::Backspace);
+                } else if let Some(data) = event.data() {
+                    typed.text.push_str(&data);
+                }
+                field.set_value("");
+            });
+        let keydown_typed = typed.clone();
+        let on_keydown = Closure::<dyn FnMut(web_sys::KeyboardEvent)>::new(
+            move |event: web_sys::KeyboardEvent| {
+                if event.key() == "Enter" {
+                    keydown_typed.borrow_mut().keys.push(crate::Key::Enter);
+                }
+            },
+        );
+        input
+            .add_event_listener_with_callback("input", on_input.as_ref().unchecked_ref())
+            .ok()?;
+        input
+            .add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref())
+            .ok()?;
+        let _ = input.focus();
+
+        Some(Self {
+            input,
+            typed,
+            _on_input: on_input,
+            _on_keydown: on_keydown,
+        })
+    }
+
+    /// Forwards what was typed and reports how much of the page the keyboard covers, or
+    /// `None` once the user dismissed it.
+    pub(crate) fn poll(&self, ctx: &mut crate::Context) {
+        let mut typed = self.typed.borrow_mut();
+        for ch in typed.text.drain(..) {
+            ctx.input_mut().handle_received_character(ch);
+        }
+        for key in typed.keys.drain(..) {
+            ctx.input_mut().handle_key_tap(key);
+        }
+        drop(typed);
+
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let focused = window
+            .document()
+            .and_then(|d| d.active_element())
+            .is_some_and(|element| element.is_same_node(Some(&self.input)));
+        if !focused {
+            ctx.set_virtual_keyboard(None);
+            return;
+        }
+        // The visual viewport shrinks above the keyboard while the layout viewport may not.
+        let covered = match (window.inner_height(), window.visual_viewport()) {
+            (Ok(inner), Some(viewport)) => {
+                inner.as_f64().unwrap_or(0.0) - viewport.height() - viewport.offset_top()
+            }
+            _ => 0.0,
+        };
+        ctx.set_virtual_keyboard(Some(crate::Pt(covered.max(0.0) as f32)));
+    }
+}
+
+impl Drop for VirtualKeyboard {
+    fn drop(&mut self) {
+        let _ = self.input.blur();
+        self.input.remove();
+    }
+}
+
+/// Latest `devicemotion` and `deviceorientation` readings, in the units the Android and
+/// iOS sensors report.
+#[cfg(feature = "sensors")]
+#[derive(Default)]
+struct DeviceReadings {
+    accelerometer: Option<[f32; 3]>,
+    gyroscope: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+}
+
+/// Listeners for the browser's motion and orientation events, removed when dropped.
+///
+/// iOS Safari only delivers these after the page called
+/// `DeviceMotionEvent.requestPermission()` from a user gesture.
+#[cfg(feature = "sensors")]
+pub(crate) struct DeviceSensors {
+    readings: std::rc::Rc<std::cell::RefCell<DeviceReadings>>,
+    on_motion: Closure<dyn FnMut(web_sys::DeviceMotionEvent)>,
+    on_orientation: Closure<dyn FnMut(web_sys::DeviceOrientationEvent)>,
+}
+
+#[cfg(feature = "sensors")]
+impl DeviceSensors {
+    pub(crate) fn listen() -> Option<Self> {
+        let window = web_sys::window()?;
+        let readings = std::rc::Rc::new(std::cell::RefCell::new(DeviceReadings::default()));
+
+        let motion_readings = readings.clone();
+        let on_motion = Closure::<dyn FnMut(web_sys::DeviceMotionEvent)>::new(
+            move |event: web_sys::DeviceMotionEvent| {
+                let mut readings = motion_readings.borrow_mut();
+                if let Some(a) = event.acceleration_including_gravity()
+                    && let (Some(x), Some(y), Some(z)) = (a.x(), a.y(), a.z())
+                {
+                    readings.accelerometer = Some([x as f32, y as f32, z as f32]);
+                }
+                // Degrees per second around the z, x and y axes; Android reports radians.
+                if let Some(r) = event.rotation_rate()
+                    && let (Some(alpha), Some(beta), Some(gamma)) = (r.alpha(), r.beta(), r.gamma())
+                {
+                    readings.gyroscope = Some([
+                        beta.to_radians() as f32,
+                        gamma.to_radians() as f32,
+                        alpha.to_radians() as f32,
+                    ]);
+                }
+            },
+        );
+
+        let orientation_readings = readings.clone();
+        let on_orientation = Closure::<dyn FnMut(web_sys::DeviceOrientationEvent)>::new(
+            move |event: web_sys::DeviceOrientationEvent| {
+                if let (Some(alpha), Some(beta), Some(gamma)) =
+                    (event.alpha(), event.beta(), event.gamma())
+                {
+                    orientation_readings.borrow_mut().rotation =
+                        Some(orientation_quaternion(alpha, beta, gamma));
+                }
+            },
+        );
+
+        window
+            .add_event_listener_with_callback("devicemotion", on_motion.as_ref().unchecked_ref())
+            .ok()?;
+        window
+            .add_event_listener_with_callback(
+                "deviceorientation",
+                on_orientation.as_ref().unchecked_ref(),
+            )
+            .ok()?;
+
+        Some(Self {
+            readings,
+            on_motion,
+            on_orientation,
+        })
+    }
+
+    pub(crate) fn poll(&self, input: &mut crate::InputManager) {
+        let readings = self.readings.borrow();
+        if let Some([x, y, z]) = readings.accelerometer {
+            input.handle_accelerometer(x, y, z);
+        }
+        if let Some([x, y, z]) = readings.gyroscope {
+            input.handle_gyroscope(x, y, z);
+        }
+        if let Some([x, y, z, w]) = readings.rotation {
+            input.handle_rotation(x, y, z, w);
+        }
+    }
+}
+
+#[cfg(feature = "sensors")]
+impl Drop for DeviceSensors {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.remove_event_listener_with_callback(
+                "devicemotion",
+                self.on_motion.as_ref().unchecked_ref(),
+            );
+            let _ = window.remove_event_listener_with_callback(
+                "deviceorientation",
+                self.on_orientation.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+/// Converts the Z-X'-Y'' Euler angles of a `deviceorientation` event, in degrees, to an
+/// `[x, y, z, w]` quaternion like Android's rotation vector.
+#[cfg(feature = "sensors")]
+fn orientation_quaternion(alpha: f64, beta: f64, gamma: f64) -> [f32; 4] {
+    let (sx, cx) = (beta.to_radians() / 2.0).sin_cos();
+    let (sy, cy) = (gamma.to_radians() / 2.0).sin_cos();
+    let (sz, cz) = (alpha.to_radians() / 2.0).sin_cos();
+    [
+        (sx * cy * cz - cx * sy * sz) as f32,
+        (cx * sy * cz + sx * cy * sz) as f32,
+        (cx * cy * sz + sx * sy * cz) as f32,
+        (cx * cy * cz - sx * sy * sz) as f32,
+    ]
+}
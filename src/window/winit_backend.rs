@@ -1,37 +1,57 @@
 use super::WindowBackend;
-use crate::{Spot, WindowConfig};
+use crate::{Spot, SpotError, WindowConfig};
 
 pub(crate) struct WinitWgpuBackend;
 
 impl WindowBackend for WinitWgpuBackend {
     #[cfg(not(target_os = "android"))]
-    fn run<T: Spot + 'static>(window: WindowConfig) {
+    fn run<T: Spot + 'static>(window: WindowConfig) -> Result<(), SpotError> {
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
         {
             console_error_panic_hook::set_once();
         }
 
-        let event_loop =
-            winit::event_loop::EventLoop::new().expect("failed to create winit EventLoop");
+        let _ = crate::scenes::take_scene_error();
+        let event_loop = winit::event_loop::EventLoop::new()
+            .map_err(|error| SpotError::EventLoop(error.into()))?;
+        let proxy = event_loop.create_proxy();
+        crate::user_events::set_waker(Some(Box::new(move || {
+            let _ = proxy.send_event(());
+        })));
 
+        // The instance is created asynchronously to detect WebGPU support, so the event
+        // loop is spawned instead of run and this returns right away.
         #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
         {
-            let app = super::App::new_wasm::<T>(window.clone(), window.canvas_id.clone());
-            let app = Box::new(app);
-            let app = Box::leak(app);
-            event_loop.run_app(app).expect("event loop error");
+            use winit::platform::web::EventLoopExtWebSys;
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let instance = crate::platform::create_wgpu_instance(window.backend).await;
+                let canvas_id = window.canvas_id.clone();
+                let app = super::App::new_wasm::<T>(window, canvas_id, instance);
+                event_loop.spawn_app(Box::leak(Box::new(app)));
+            });
+            Ok(())
         }
 
         #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
         {
             let mut app = super::App::new::<T>(window);
-            event_loop.run_app(&mut app).expect("event loop error");
+            event_loop
+                .run_app(&mut app)
+                .map_err(|error| SpotError::EventLoop(error.into()))?;
+            app.finish()
         }
     }
 
     #[cfg(target_os = "android")]
-    fn run<T: Spot + 'static>(window: WindowConfig, app: android_activity::AndroidApp) {
+    fn run<T: Spot + 'static>(
+        window: WindowConfig,
+        app: android_activity::AndroidApp,
+    ) -> Result<(), SpotError> {
+        let _ = crate::scenes::take_scene_error();
         let mut app_impl = super::App::new::<T>(window);
         app_impl.run(app);
+        app_impl.finish()
     }
 }